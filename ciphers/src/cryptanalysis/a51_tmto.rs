@@ -0,0 +1,280 @@
+use crate::digital::stream_ciphers::lfsr::Lfsr32;
+use rand::Rng;
+use std::collections::{HashMap, HashSet};
+
+// Register lengths, feedback taps and clocking bits for a scaled down A5/1 with 21 bits of state instead of 64
+const LENGTHS: [u32; 3] = [6, 7, 8];
+const TAPS: [u32; 3] = [0x30, 0x60, 0xB8];
+const CLOCK_BITS: [u32; 3] = [2, 3, 3];
+
+/// A5/1 with the registers shrunk to 6, 7 and 8 bits so that a time-memory tradeoff runs in moments. Each step clocks
+/// the registers whose clocking bit agrees with the majority and outputs the XOR of their top bits, exactly as in the
+/// full cipher. The key setup is also the same apart from the key being 21 bits and the mixing being shorter.
+#[derive(Debug, Clone)]
+pub struct ToyA51 {
+    pub lfsrs: [Lfsr32; 3],
+}
+
+impl ToyA51 {
+    pub const STATE_BITS: u32 = LENGTHS[0] + LENGTHS[1] + LENGTHS[2];
+    pub const KEY_BITS: u32 = Self::STATE_BITS;
+    pub const MIX_STEPS: usize = 25;
+    pub const BURST_BITS: usize = 114;
+
+    /// Load the key and then the 22 bit frame number into the zeroed registers with regular clocking. Since nothing
+    /// depends on the majority yet the loaded state is linear in the key and frame number.
+    pub fn load(key: u32, frame_number: u32) -> Self {
+        let mut out = Self::from_state(0);
+        for (value, n_bits) in [(key, Self::KEY_BITS), (frame_number, 22)] {
+            for i in 0..n_bits {
+                let b = (value >> i) & 1;
+                for lfsr in out.lfsrs.iter_mut() {
+                    lfsr.next_bit();
+                    lfsr.register ^= b;
+                }
+            }
+        }
+        out
+    }
+
+    pub fn with_key_and_frame(key: u32, frame_number: u32) -> Self {
+        let mut out = Self::load(key, frame_number);
+        for _ in 0..Self::MIX_STEPS {
+            out.next_bit();
+        }
+        out
+    }
+
+    /// The keystream for one direction of a frame
+    pub fn burst(key: u32, frame_number: u32) -> Vec<u32> {
+        let mut cipher = Self::with_key_and_frame(key, frame_number);
+        (0..Self::BURST_BITS).map(|_| cipher.next_bit()).collect()
+    }
+
+    /// The state is packed with the first register in the lowest bits
+    pub fn from_state(state: u32) -> Self {
+        let mut lfsrs = TAPS.map(Lfsr32::from_taps);
+        let mut shift = 0;
+        for (lfsr, len) in lfsrs.iter_mut().zip(LENGTHS) {
+            lfsr.register = (state >> shift) & lfsr.mask;
+            shift += len;
+        }
+        Self { lfsrs }
+    }
+
+    pub fn state(&self) -> u32 {
+        let mut out = 0;
+        let mut shift = 0;
+        for (lfsr, len) in self.lfsrs.iter().zip(LENGTHS) {
+            out |= lfsr.register << shift;
+            shift += len;
+        }
+        out
+    }
+
+    // Which registers are clocked from the current state
+    fn clocked(&self) -> [bool; 3] {
+        let bits = [0, 1, 2].map(|i| self.lfsrs[i].get_bit(CLOCK_BITS[i]));
+        let majority = (bits[0] & bits[1]) | (bits[0] & bits[2]) | (bits[1] & bits[2]);
+        bits.map(|b| b == majority)
+    }
+
+    pub fn next_bit(&mut self) -> u32 {
+        let clocked = self.clocked();
+        let mut out = 0;
+        for i in 0..3 {
+            if clocked[i] {
+                self.lfsrs[i].next_bit();
+            }
+            out ^= self.lfsrs[i].get_bit(LENGTHS[i] - 1);
+        }
+        out
+    }
+
+    /// Produce n bits of keystream with the first bit in the most significant position
+    pub fn next_bits(&mut self, n: u32) -> u64 {
+        (0..n).fold(0, |acc, _| (acc << 1) | self.next_bit() as u64)
+    }
+
+    /// Every state that steps to this one. There may be none or several since the irregular clocking is not
+    /// invertible.
+    pub fn predecessors(&self) -> Vec<ToyA51> {
+        let mut out = Vec::new();
+        for clocked in [
+            [true, true, true],
+            [true, true, false],
+            [true, false, true],
+            [false, true, true],
+        ] {
+            let mut prev = self.clone();
+            for i in 0..3 {
+                if clocked[i] {
+                    let lfsr = &mut prev.lfsrs[i];
+                    // The top tap is always the bit that was shifted out, so it is fixed by the new bit
+                    let r = lfsr.register;
+                    let top = (r & 1) ^ (((r >> 1) & lfsr.taps).count_ones() & 1);
+                    lfsr.register = (r >> 1) | (top << (LENGTHS[i] - 1));
+                }
+            }
+            if prev.clocked() == clocked {
+                out.push(prev);
+            }
+        }
+        out
+    }
+}
+
+/// A time-memory tradeoff in the style of Biryukov, Shamir and Wagner. Only special states, those whose output
+/// begins with a fixed pattern, are stored and they are indexed by the bits that follow the pattern. Online the
+/// keystream is searched for the pattern and the following bits are looked up, which gives the state at that point.
+/// The irregular clocking is then run backward to the start of the keystream.
+///
+/// With M stored states and D occurrences of the pattern the attack succeeds with good probability once M * D is
+/// around the number of special states, while the table only needs to hold M of them.
+pub struct A51Tradeoff {
+    pub pattern: u64,
+    pub pattern_bits: u32,
+    pub lookup_bits: u32,
+    pub table: HashMap<u64, u32>,
+}
+
+impl A51Tradeoff {
+    /// Precompute a table of special states by sampling random states and keeping those whose output begins with the
+    /// pattern
+    pub fn precompute<R: Rng>(
+        rng: &mut R,
+        pattern: u64,
+        pattern_bits: u32,
+        table_size: usize,
+    ) -> Self {
+        let lookup_bits = ToyA51::STATE_BITS + 8;
+        let mut table = HashMap::with_capacity(table_size);
+        while table.len() < table_size {
+            let state = rng.gen_range(0..(1 << ToyA51::STATE_BITS));
+            let mut cipher = ToyA51::from_state(state);
+            if cipher.next_bits(pattern_bits) == pattern {
+                table.insert(cipher.next_bits(lookup_bits), state);
+            }
+        }
+        Self {
+            pattern,
+            pattern_bits,
+            lookup_bits,
+            table,
+        }
+    }
+
+    /// Search the bursts of several frames for a state from the table, run it back to the state loaded at the start
+    /// of the frame, and solve for the key. Every key that reproduces all of the bursts is returned.
+    pub fn attack(&self, bursts: &[(u32, Vec<u32>)]) -> Vec<u32> {
+        let window = (self.pattern_bits + self.lookup_bits) as usize;
+        let pack = |bits: &[u32]| bits.iter().fold(0, |acc, b| (acc << 1) | *b as u64);
+
+        let mut found = HashSet::new();
+        for (frame_number, keystream) in bursts {
+            for t in 0..keystream.len().saturating_sub(window) {
+                let (head, tail) = keystream[t..t + window].split_at(self.pattern_bits as usize);
+                if pack(head) != self.pattern {
+                    continue;
+                }
+                let Some(state) = self.table.get(&pack(tail)) else {
+                    continue;
+                };
+
+                // Run the state back through the mixing, following every branch
+                let mut frontier = HashSet::from([*state]);
+                for _ in 0..(t + ToyA51::MIX_STEPS) {
+                    frontier = frontier
+                        .iter()
+                        .flat_map(|s| ToyA51::from_state(*s).predecessors())
+                        .map(|c| c.state())
+                        .collect();
+                }
+
+                for loaded in frontier {
+                    let Some(key) = key_from_loaded_state(loaded, *frame_number) else {
+                        continue;
+                    };
+                    if bursts.iter().all(|(f, k)| ToyA51::burst(key, *f) == *k) {
+                        found.insert(key);
+                    }
+                }
+            }
+            if !found.is_empty() {
+                break;
+            }
+        }
+        found.into_iter().collect()
+    }
+}
+
+/// The loaded state is the sum of a linear function of the key and one of the frame number, so with the frame number
+/// known the key is found by Gaussian elimination
+pub fn key_from_loaded_state(loaded: u32, frame_number: u32) -> Option<u32> {
+    let target = loaded ^ ToyA51::load(0, frame_number).state();
+    let columns: Vec<u32> = (0..ToyA51::KEY_BITS)
+        .map(|i| ToyA51::load(1 << i, 0).state())
+        .collect();
+
+    // Each row holds the key bits that contribute to one state bit with the target bit above them
+    let n = ToyA51::KEY_BITS as usize;
+    let mut rows: Vec<u32> = (0..ToyA51::STATE_BITS)
+        .map(|j| {
+            let coefs = columns
+                .iter()
+                .enumerate()
+                .fold(0, |acc, (i, c)| acc | (((c >> j) & 1) << i));
+            coefs | (((target >> j) & 1) << n)
+        })
+        .collect();
+
+    for col in 0..n {
+        let pivot = (col..rows.len()).find(|r| (rows[*r] >> col) & 1 == 1)?;
+        rows.swap(col, pivot);
+        for r in 0..rows.len() {
+            if r != col && (rows[r] >> col) & 1 == 1 {
+                rows[r] ^= rows[col];
+            }
+        }
+    }
+    Some((0..n).fold(0, |acc, i| acc | (((rows[i] >> n) & 1) << i)))
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn predecessors() {
+        for state in [0x1abcde, 0x00f00f, 0x123456] {
+            let cipher = ToyA51::from_state(state);
+            let mut next = cipher.clone();
+            next.next_bit();
+            assert!(next
+                .predecessors()
+                .iter()
+                .any(|p| p.state() == cipher.state()));
+        }
+    }
+
+    #[test]
+    fn key_from_state() {
+        let key = 0x0abcde;
+        let loaded = ToyA51::load(key, 0x134).state();
+        assert_eq!(Some(key), key_from_loaded_state(loaded, 0x134));
+    }
+
+    #[test]
+    fn tradeoff() {
+        let mut rng = StdRng::seed_from_u64(0x5eed);
+        let tradeoff = A51Tradeoff::precompute(&mut rng, 0b1011, 4, 1 << 13);
+
+        let key = 0x15b3c7;
+        let bursts: Vec<(u32, Vec<u32>)> =
+            (0x200..0x220).map(|f| (f, ToyA51::burst(key, f))).collect();
+
+        assert_eq!(vec![key], tradeoff.attack(&bursts));
+    }
+}
@@ -0,0 +1,150 @@
+use crate::digital::stream_ciphers::lfsr::{Lfsr32, Lfsr64};
+use utils::{bit_polynomial::BitPolynomial, bits::Bit};
+
+/// Find the shortest LFSR that generates the sequence of bits. Returns the linear complexity L and the connection
+/// polynomial C(x) = 1 + c_1 x + ... + c_L x^L so that s_t = c_1 s_(t-1) + ... + c_L s_(t-L) for every t >= L.
+/// When at least 2L bits are given the polynomial is unique.
+pub fn berlekamp_massey(bits: &[Bit]) -> (usize, BitPolynomial) {
+    let n = bits.len();
+    // Current connection polynomial and the one from before the last change in length
+    let mut c = vec![Bit::Zero; n + 1];
+    let mut b = vec![Bit::Zero; n + 1];
+    c[0] = Bit::One;
+    b[0] = Bit::One;
+    let mut l = 0;
+    // Position at which the length last changed
+    let mut m = 0;
+
+    for i in 0..n {
+        // Discrepancy between the next bit and the bit predicted by the current LFSR
+        let mut d = bits[i];
+        for j in 1..=l {
+            d ^= c[j] & bits[i - j];
+        }
+        if d == Bit::One {
+            let t = c.clone();
+            let shift = i + 1 - m;
+            for j in 0..(n + 1 - shift) {
+                c[j + shift] ^= b[j];
+            }
+            if 2 * l <= i {
+                l = i + 1 - l;
+                m = i + 1;
+                b = t;
+            }
+        }
+    }
+
+    c.truncate(l + 1);
+    (l, BitPolynomial::from(c))
+}
+
+/// The linear complexity of a sequence of bits
+pub fn linear_complexity(bits: &[Bit]) -> usize {
+    berlekamp_massey(bits).0
+}
+
+// Convert the connection polynomial into the tap positions used by Lfsr32 and Lfsr64, where bit j of the register
+// holds the output from j + 1 steps ago
+fn taps_from_connection(connection: &BitPolynomial) -> u64 {
+    let mut taps = 0;
+    for i in 1..connection.len() {
+        if connection[i] == Bit::One {
+            taps |= 1 << (i - 1);
+        }
+    }
+    taps
+}
+
+// Fill the register so that it continues the sequence
+fn register_from_bits(bits: &[Bit], length: usize) -> u64 {
+    let mut register = 0;
+    for (j, bit) in bits.iter().rev().take(length).enumerate() {
+        if *bit == Bit::One {
+            register |= 1 << j;
+        }
+    }
+    register
+}
+
+/// Recover an Lfsr32 that continues the sequence of bits, if one exists with at most 31 bits
+pub fn lfsr32_from_bits(bits: &[Bit]) -> Option<Lfsr32> {
+    let (l, connection) = berlekamp_massey(bits);
+    let taps = taps_from_connection(&connection);
+    if l == 0 || l > 31 || taps == 0 {
+        return None;
+    }
+    let mut lfsr = Lfsr32::from_taps(taps as u32);
+    lfsr.register = register_from_bits(bits, connection.degree()) as u32;
+    Some(lfsr)
+}
+
+/// Recover an Lfsr64 that continues the sequence of bits, if one exists with at most 63 bits
+pub fn lfsr64_from_bits(bits: &[Bit]) -> Option<Lfsr64> {
+    let (l, connection) = berlekamp_massey(bits);
+    let taps = taps_from_connection(&connection);
+    if l == 0 || l > 63 || taps == 0 {
+        return None;
+    }
+    let mut lfsr = Lfsr64::from_taps(taps);
+    lfsr.register = register_from_bits(bits, connection.degree());
+    Some(lfsr)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::digital::stream_ciphers::a51::A51Rng;
+
+    fn to_bit(b: u64) -> Bit {
+        Bit::from(b == 1)
+    }
+
+    #[test]
+    fn known_complexity() {
+        let bits: Vec<Bit> = [0, 0, 1, 1, 0, 1, 1, 1, 0]
+            .into_iter()
+            .map(to_bit)
+            .collect();
+        let (l, connection) = berlekamp_massey(&bits);
+        assert_eq!(5, l);
+        // s_t = s_(t-3) + s_(t-5)
+        assert_eq!("100101", connection.bit_string());
+    }
+
+    #[test]
+    fn recover_lfsr32() {
+        let mut lfsr = Lfsr32::from_taps(0x072000);
+        lfsr.register = 0x5a5a5;
+        let bits: Vec<Bit> = (0..38).map(|_| to_bit(lfsr.next_bit() as u64)).collect();
+
+        let mut recovered = lfsr32_from_bits(&bits).unwrap();
+        assert_eq!(lfsr.taps, recovered.taps);
+        for _ in 0..200 {
+            assert_eq!(lfsr.next_bit(), recovered.next_bit());
+        }
+    }
+
+    #[test]
+    fn recover_lfsr64() {
+        let mut lfsr = Lfsr64::from_taps(0x6000000000000000);
+        lfsr.register = 0x0123456789abcdef;
+        let bits: Vec<Bit> = (0..126).map(|_| to_bit(lfsr.next_bit())).collect();
+
+        let mut recovered = lfsr64_from_bits(&bits).unwrap();
+        assert_eq!(lfsr.taps, recovered.taps);
+        for _ in 0..200 {
+            assert_eq!(lfsr.next_bit(), recovered.next_bit());
+        }
+    }
+
+    #[test]
+    fn a51_complexity() {
+        // The irregular clocking makes the linear complexity of A5/1 far larger than the 64 bits of its registers
+        let mut rng = A51Rng::default();
+        rng.ksa([0x12, 0x23, 0x45, 0x67, 0x89, 0xAB, 0xCD, 0xEF], 0x134);
+        let bits: Vec<Bit> = (0..1000).map(|_| to_bit(rng.next_bit() as u64)).collect();
+        assert!(linear_complexity(&bits) > 400);
+    }
+}
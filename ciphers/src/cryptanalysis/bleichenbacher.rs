@@ -0,0 +1,168 @@
+use crate::digital::public_key_ciphers::rsa::Rsa;
+use num::{BigUint, Integer, One, Zero};
+use utils::errors::GeneralError;
+
+/// A server that decrypts RSA ciphertexts and reveals only whether the result begins with 0x00 0x02, as PKCS#1 v1.5
+/// encryption padding must. Bleichenbacher's original attack targeted exactly this kind of error message from SSL
+/// servers.
+pub fn pkcs1_oracle(rsa: &Rsa) -> impl Fn(&BigUint) -> bool + '_ {
+    let k = rsa.modulus_len();
+    move |c| match rsa.private_op(c) {
+        // The decryption has k bytes so it begins with 0x00 0x02 exactly when it has k-1 bytes, the first being 0x02
+        Ok(m) => {
+            let bytes = m.to_bytes_be();
+            bytes.len() == k - 1 && bytes[0] == 0x02
+        }
+        Err(_) => false,
+    }
+}
+
+fn ceil_div(a: &BigUint, b: &BigUint) -> BigUint {
+    Integer::div_ceil(a, b)
+}
+
+// Merge overlapping intervals
+fn union(mut intervals: Vec<(BigUint, BigUint)>) -> Vec<(BigUint, BigUint)> {
+    intervals.sort();
+    let mut out: Vec<(BigUint, BigUint)> = Vec::new();
+    for (a, b) in intervals {
+        match out.last_mut() {
+            Some(last) if a <= last.1 => {
+                if b > last.1 {
+                    last.1 = b
+                }
+            }
+            _ => out.push((a, b)),
+        }
+    }
+    out
+}
+
+/// Bleichenbacher's adaptive chosen ciphertext attack on PKCS#1 v1.5 encryption. Returns the complete padded message
+/// as k bytes.
+///
+/// Multiplying the ciphertext by s^e multiplies the hidden message by s. Whenever the oracle accepts such a ciphertext
+/// it is known that 2B <= m*s - r*n < 3B for some r, where B = 2^(8(k-2)), which narrows down the set of intervals
+/// that can contain m. Each step looks for a new s and applies the same reasoning until only a single value is left.
+/// The ciphertext must itself be PKCS#1 conforming. Only the public part of the key is used.
+pub fn bleichenbacher<F: Fn(&BigUint) -> bool>(
+    public: &Rsa,
+    ciphertext: &[u8],
+    oracle: &F,
+) -> Result<Vec<u8>, GeneralError> {
+    let (n, e) = public.public_key();
+    let k = public.modulus_len();
+    let c = BigUint::from_bytes_be(ciphertext);
+    if &c >= n || !oracle(&c) {
+        return Err(GeneralError::input(
+            "the ciphertext must be PKCS#1 conforming",
+        ));
+    }
+
+    let b = BigUint::one() << (8 * (k - 2));
+    let b2 = &b * 2_u32;
+    let b3 = &b * 3_u32;
+    let accepts = |s: &BigUint| oracle(&((&c * s.modpow(e, n)) % n));
+
+    // Step 2a, the smallest s that can possibly give another conforming message
+    let mut s = ceil_div(n, &b3);
+    while !accepts(&s) {
+        s += 1_u32;
+    }
+    let mut intervals = vec![(b2.clone(), &b3 - 1_u32)];
+
+    loop {
+        // Step 3, narrow the intervals using the most recent s
+        let mut narrowed = Vec::new();
+        for (lo, hi) in &intervals {
+            let lower = lo * &s + 1_u32;
+            let mut r = if lower > b3 {
+                ceil_div(&(lower - &b3), n)
+            } else {
+                BigUint::zero()
+            };
+            let r_max = (hi * &s - &b2) / n;
+            while r <= r_max {
+                let a = ceil_div(&(&b2 + &r * n), &s).max(lo.clone());
+                let b = ((&b3 - 1_u32 + &r * n) / &s).min(hi.clone());
+                if a <= b {
+                    narrowed.push((a, b));
+                }
+                r += 1_u32;
+            }
+        }
+        intervals = union(narrowed);
+
+        // Step 4, finished when only a single value remains
+        match intervals.as_slice() {
+            [] => return Err(GeneralError::general("no interval can contain the message")),
+            [(lo, hi)] if lo == hi => {
+                let mut out = vec![0; k - lo.to_bytes_be().len()];
+                out.extend(lo.to_bytes_be());
+                return Ok(out);
+            }
+            // Step 2b, with several intervals search upward for the next s
+            [_, _, ..] => {
+                s += 1_u32;
+                while !accepts(&s) {
+                    s += 1_u32;
+                }
+            }
+            // Step 2c, with one interval s can be chosen so the interval is roughly halved each time
+            [(lo, hi)] => {
+                let mut r = ceil_div(&((hi * &s - &b2) * 2_u32), n);
+                's: loop {
+                    let mut candidate = ceil_div(&(&b2 + &r * n), hi);
+                    let end = ceil_div(&(&b3 + &r * n), lo);
+                    while candidate < end {
+                        if accepts(&candidate) {
+                            s = candidate;
+                            break 's;
+                        }
+                        candidate += 1_u32;
+                    }
+                    r += 1_u32;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::digital::public_key_ciphers::rsa_padding::pkcs1_encryption_decode;
+    use rand::{rngs::StdRng, SeedableRng};
+    use std::cell::Cell;
+
+    #[test]
+    fn recover_message() {
+        let mut rng = StdRng::seed_from_u64(42);
+        let mut rsa = Rsa::default();
+        // A small modulus keeps the number of queries manageable for a test
+        rsa.generate_key(256, &mut rng).unwrap();
+        let message = b"key 0123456789ab";
+        let ciphertext = rsa.encrypt_pkcs1(message, &mut rng).unwrap();
+
+        let queries = Cell::new(0);
+        let inner = pkcs1_oracle(&rsa);
+        let oracle = |c: &BigUint| {
+            queries.set(queries.get() + 1);
+            inner(c)
+        };
+        let padded = bleichenbacher(&rsa, &ciphertext, &oracle).unwrap();
+        assert_eq!(message.to_vec(), pkcs1_encryption_decode(&padded).unwrap());
+        assert!(queries.get() < 100_000);
+    }
+
+    #[test]
+    fn nonconforming_rejected() {
+        let mut rng = StdRng::seed_from_u64(42);
+        let mut rsa = Rsa::default();
+        rsa.generate_key(512, &mut rng).unwrap();
+        let ciphertext = rsa.encrypt_bytes(&[0x01, 0x02, 0x03]).unwrap();
+        let oracle = pkcs1_oracle(&rsa);
+        assert!(bleichenbacher(&rsa, &ciphertext, &oracle).is_err());
+    }
+}
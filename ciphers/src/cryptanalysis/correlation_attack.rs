@@ -0,0 +1,116 @@
+use rngs::{
+    geffe::Geffe,
+    lfsr::{Lfsr, LfsrMode},
+};
+use utils::bits::Bit;
+
+// Every nonzero fill of a register with the given length
+fn all_fills(length: usize) -> impl Iterator<Item = Vec<Bit>> {
+    (1..(1_u64 << length)).map(move |n| {
+        (0..length)
+            .map(|i| Bit::from((n >> (length - 1 - i)) & 1 == 1))
+            .collect()
+    })
+}
+
+fn with_fill(taps: &[bool], mode: LfsrMode, fill: &[Bit]) -> Lfsr {
+    Lfsr {
+        bits: fill.to_vec(),
+        taps: taps.to_vec(),
+        ltr: true,
+        mode,
+    }
+}
+
+/// Fraction of the keystream bits that agree with the output of an LFSR
+pub fn agreement(lfsr: &mut Lfsr, keystream: &[Bit]) -> f64 {
+    let matches = keystream
+        .iter()
+        .filter(|bit| lfsr.next_bit() == **bit)
+        .count();
+    matches as f64 / keystream.len() as f64
+}
+
+/// Siegenthaler's correlation attack on a single register. Every nonzero fill of an LFSR with the given taps and mode
+/// is tried and the one whose output agrees most often with the keystream is returned, together with the rate of
+/// agreement. For a combining function that leaks the register the correct fill stands out once a few hundred bits
+/// of keystream are known, and the cost is 2^L rather than the product over all registers.
+pub fn correlate_register(taps: &[bool], mode: LfsrMode, keystream: &[Bit]) -> (Vec<Bit>, f64) {
+    let mut best = (Vec::new(), 0.0);
+    for fill in all_fills(taps.len()) {
+        let rate = agreement(&mut with_fill(taps, mode, &fill), keystream);
+        if rate > best.1 {
+            best = (fill, rate);
+        }
+    }
+    best
+}
+
+/// Recover the initial fills of the three registers of a Geffe generator from its output. Only the taps and modes of
+/// the template are used.
+///
+/// The output of the Geffe generator agrees with the second and third registers three quarters of the time, so each
+/// of them can be found alone by correlation. With those known the first register, which only selects between them,
+/// is found by exhaustive search.
+pub fn geffe_attack(template: &Geffe, keystream: &[Bit]) -> Option<Geffe> {
+    let [a, b, c] = &template.rngs;
+    let (b_fill, _) = correlate_register(&b.taps, b.mode, keystream);
+    let (c_fill, _) = correlate_register(&c.taps, c.mode, keystream);
+
+    let build = |a_fill: &[Bit]| Geffe {
+        rngs: [
+            with_fill(&a.taps, a.mode, a_fill),
+            with_fill(&b.taps, b.mode, &b_fill),
+            with_fill(&c.taps, c.mode, &c_fill),
+        ],
+        ltr: template.ltr,
+    };
+
+    for a_fill in all_fills(a.taps.len()) {
+        let mut candidate = build(&a_fill);
+        if keystream.iter().all(|bit| candidate.next_bit() == *bit) {
+            return Some(build(&a_fill));
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    fn geffe() -> Geffe {
+        let mut rngs = [
+            Lfsr::from_tap_positions(&[5, 9]),
+            Lfsr::from_tap_positions(&[7, 10]),
+            Lfsr::from_tap_positions(&[9, 11]),
+        ];
+        rngs[0].set_bits_from_str("101100111");
+        rngs[1].set_bits_from_str("0110001011");
+        rngs[2].set_bits_from_str("11100100101");
+        Geffe { rngs, ltr: true }
+    }
+
+    #[test]
+    fn correlation() {
+        let mut rng = geffe();
+        let keystream: Vec<Bit> = (0..300).map(|_| rng.next_bit()).collect();
+
+        let (fill, rate) =
+            correlate_register(&geffe().rngs[1].taps, LfsrMode::Fibonncci, &keystream);
+        assert_eq!(geffe().rngs[1].bits, fill);
+        assert!(rate > 0.65);
+    }
+
+    #[test]
+    fn full_attack() {
+        let mut rng = geffe();
+        let keystream: Vec<Bit> = (0..300).map(|_| rng.next_bit()).collect();
+
+        let recovered = geffe_attack(&geffe(), &keystream).unwrap();
+        for i in 0..3 {
+            assert_eq!(geffe().rngs[i].bits, recovered.rngs[i].bits);
+        }
+    }
+}
@@ -0,0 +1,365 @@
+use crypto_bigint::U256;
+use num::{BigUint, One, ToPrimitive};
+use std::{collections::HashMap, hash::Hash};
+use utils::{
+    elliptic_curves::{EcPoint, FiniteEllipticCurve},
+    math_functions::crt,
+    primality::prime_factorization_map,
+};
+
+/// A finite group written multiplicatively, so that the discrete logarithm of h to the base g is the x with g^x = h.
+/// For an elliptic curve this is the x with xG = H.
+pub trait Group {
+    type Element: Clone + Eq + Hash;
+
+    fn identity(&self) -> Self::Element;
+    fn op(&self, a: &Self::Element, b: &Self::Element) -> Self::Element;
+    fn inverse(&self, a: &Self::Element) -> Self::Element;
+
+    /// Any number derived from the element, used to choose the steps of the pseudorandom walks
+    fn label(&self, a: &Self::Element) -> u64;
+
+    fn pow(&self, a: &Self::Element, mut n: u64) -> Self::Element {
+        let mut out = self.identity();
+        let mut base = a.clone();
+        while n != 0 {
+            if n & 1 == 1 {
+                out = self.op(&out, &base);
+            }
+            base = self.op(&base, &base);
+            n >>= 1;
+        }
+        out
+    }
+}
+
+/// The multiplicative group of integers modulo p
+pub struct MultiplicativeGroup {
+    pub modulus: BigUint,
+}
+
+impl Group for MultiplicativeGroup {
+    type Element = BigUint;
+
+    fn identity(&self) -> BigUint {
+        BigUint::one()
+    }
+
+    fn op(&self, a: &BigUint, b: &BigUint) -> BigUint {
+        (a * b) % &self.modulus
+    }
+
+    // Only valid for a prime modulus
+    fn inverse(&self, a: &BigUint) -> BigUint {
+        a.modpow(&(&self.modulus - 2_u32), &self.modulus)
+    }
+
+    fn label(&self, a: &BigUint) -> u64 {
+        a.iter_u64_digits().next().unwrap_or(0)
+    }
+
+    fn pow(&self, a: &BigUint, n: u64) -> BigUint {
+        a.modpow(&BigUint::from(n), &self.modulus)
+    }
+}
+
+/// The group of points of an elliptic curve
+pub struct EllipticCurveGroup {
+    pub curve: FiniteEllipticCurve,
+}
+
+impl Group for EllipticCurveGroup {
+    type Element = EcPoint;
+
+    fn identity(&self) -> EcPoint {
+        EcPoint::point_at_inf()
+    }
+
+    fn op(&self, a: &EcPoint, b: &EcPoint) -> EcPoint {
+        self.curve.add(a, b)
+    }
+
+    fn inverse(&self, a: &EcPoint) -> EcPoint {
+        self.curve.inverse(a)
+    }
+
+    fn label(&self, a: &EcPoint) -> u64 {
+        a.x.map(|x| x.as_words()[0]).unwrap_or(0)
+    }
+
+    fn pow(&self, a: &EcPoint, n: u64) -> EcPoint {
+        self.curve.scalar_mul(a, &U256::from_u64(n))
+    }
+}
+
+fn mul_mod(a: u64, b: u64, m: u64) -> u64 {
+    (a as u128 * b as u128 % m as u128) as u64
+}
+
+fn inv_mod(a: u64, m: u64) -> Option<u64> {
+    let (mut r0, mut r1) = (m as i128, (a % m) as i128);
+    let (mut t0, mut t1) = (0_i128, 1_i128);
+    while r1 != 0 {
+        let q = r0 / r1;
+        (r0, r1) = (r1, r0 - q * r1);
+        (t0, t1) = (t1, t0 - q * t1);
+    }
+    (r0 == 1).then(|| t0.rem_euclid(m as i128) as u64)
+}
+
+/// Shanks' baby-step giant-step algorithm. Finds x < order with g^x = h using about sqrt(order) group operations and
+/// as many stored elements. The order only needs to be an upper bound on the order of g.
+pub fn baby_step_giant_step<G: Group>(
+    group: &G,
+    g: &G::Element,
+    h: &G::Element,
+    order: u64,
+) -> Option<u64> {
+    let m = (order as f64).sqrt().ceil() as u64;
+
+    // Baby steps g^j for 0 <= j < m
+    let mut table = HashMap::with_capacity(m as usize);
+    let mut e = group.identity();
+    for j in 0..m {
+        table.entry(e.clone()).or_insert(j);
+        e = group.op(&e, g);
+    }
+
+    // Giant steps h*g^(-im) until one lands among the baby steps
+    let giant = group.inverse(&group.pow(g, m));
+    let mut gamma = h.clone();
+    for i in 0..=m {
+        if let Some(j) = table.get(&gamma) {
+            let x = i * m + j;
+            if x < order {
+                return Some(x);
+            }
+        }
+        gamma = group.op(&gamma, &giant);
+    }
+    None
+}
+
+/// Pollard's rho algorithm for logarithms. A walk through elements of the form g^a * h^b moves in a way that depends
+/// only on the current element so it eventually cycles, Floyd's method finds a repeated element
+/// g^a1 * h^b1 = g^a2 * h^b2, and then x solves a1 + b1*x = a2 + b2*x modulo the order. Needs about sqrt(order)
+/// steps but almost no memory. The order of g must be known exactly and works best when it is prime.
+pub fn pollard_rho<G: Group>(group: &G, g: &G::Element, h: &G::Element, order: u64) -> Option<u64> {
+    let n = order;
+    // The walk multiplies by g or h or squares, depending on which third of the labels the element falls in
+    let step = |(x, a, b): &(G::Element, u64, u64)| match group.label(x) % 3 {
+        0 => (group.op(x, g), (a + 1) % n, *b),
+        1 => (group.op(x, h), *a, (b + 1) % n),
+        _ => (group.op(x, x), mul_mod(*a, 2, n), mul_mod(*b, 2, n)),
+    };
+
+    // Each attempt starts the walk from a different point
+    for start in 1..20_u64 {
+        let a0 = start % n;
+        let b0 = (start * 7) % n;
+        let x0 = group.op(&group.pow(g, a0), &group.pow(h, b0));
+        let mut tortoise = (x0.clone(), a0, b0);
+        let mut hare = (x0, a0, b0);
+        loop {
+            tortoise = step(&tortoise);
+            hare = step(&step(&hare));
+            if tortoise.0 == hare.0 {
+                break;
+            }
+        }
+        let (_, a1, b1) = tortoise;
+        let (_, a2, b2) = hare;
+
+        // (b1 - b2)x = (a2 - a1) mod n
+        let db = (b1 + n - b2) % n;
+        let da = (a2 + n - a1) % n;
+        if db == 0 {
+            continue;
+        }
+        // When d = gcd(db, n) > 1 there are d solutions to check
+        let d = num::integer::gcd(db, n);
+        if !da.is_multiple_of(d) {
+            continue;
+        }
+        let reduced = n / d;
+        let x0 = mul_mod(da / d, inv_mod(db / d, reduced)?, reduced);
+        for k in 0..d {
+            let x = x0 + k * reduced;
+            if group.pow(g, x) == *h {
+                return Some(x);
+            }
+        }
+    }
+    None
+}
+
+/// Pollard's kangaroo (lambda) algorithm for an x known to lie in the interval [lower, upper]. A tame kangaroo starts at
+/// g^upper and makes jumps of sizes determined by where it lands, then a wild kangaroo starting at h does the same. If
+/// the wild kangaroo ever lands where the tame one did they follow the same path from then on and meet at the tame
+/// kangaroo's final spot, revealing x. Takes about 2*sqrt(upper - lower) steps and may fail, in which case it can be
+/// run again with a different set of jumps.
+pub fn pollard_kangaroo<G: Group>(
+    group: &G,
+    g: &G::Element,
+    h: &G::Element,
+    lower: u64,
+    upper: u64,
+) -> Option<u64> {
+    let width = upper - lower;
+    let k = (64 - width.max(4).leading_zeros()) / 2 + 1;
+    // Jumps are powers of two with a mean close to sqrt(width)/2
+    let jumps: Vec<u64> = (0..k).map(|i| 1 << i).collect();
+    let jump_elements: Vec<G::Element> = jumps.iter().map(|j| group.pow(g, *j)).collect();
+    let pick = |e: &G::Element| (group.label(e) % k as u64) as usize;
+
+    // The tame kangaroo sets a trap
+    let steps = 4 * ((width as f64).sqrt() as u64 + 1);
+    let mut tame = group.pow(g, upper);
+    let mut tame_distance = 0_u64;
+    for _ in 0..steps {
+        let i = pick(&tame);
+        tame_distance += jumps[i];
+        tame = group.op(&tame, &jump_elements[i]);
+    }
+
+    // The wild kangaroo runs until it either falls into the trap or passes it
+    let mut wild = h.clone();
+    let mut wild_distance = 0_u64;
+    while wild_distance <= width + tame_distance {
+        if wild == tame {
+            return Some(upper + tame_distance - wild_distance);
+        }
+        let i = pick(&wild);
+        wild_distance += jumps[i];
+        wild = group.op(&wild, &jump_elements[i]);
+    }
+    None
+}
+
+/// The Pohlig-Hellman algorithm. When the order of g factors into small primes the logarithm can be found modulo each
+/// prime power in the subgroup of that size, one base p digit at a time, then combined with the Chinese remainder
+/// theorem. The cost depends on the largest prime factor of the order rather than on the order itself.
+pub fn pohlig_hellman<G: Group>(
+    group: &G,
+    g: &G::Element,
+    h: &G::Element,
+    order: u64,
+) -> Option<u64> {
+    let mut residues = Vec::new();
+    let mut moduli = Vec::new();
+    for (p, e) in prime_factorization_map(order) {
+        let pe = p.pow(e as u32);
+        let cofactor = order / pe;
+        // g_i has order p^e and h_i is in the subgroup it generates
+        let g_i = group.pow(g, cofactor);
+        let h_i = group.pow(h, cofactor);
+        // gamma has order p
+        let gamma = group.pow(&g_i, pe / p);
+
+        let mut x = 0_u64;
+        let mut p_k = 1_u64;
+        for _ in 0..e {
+            // Remove the digits found so far and project into the subgroup of order p to expose the next digit
+            let reduced = group.op(&group.inverse(&group.pow(&g_i, x)), &h_i);
+            let h_k = group.pow(&reduced, pe / (p_k * p));
+            let d = baby_step_giant_step(group, &gamma, &h_k, p)?;
+            x += d * p_k;
+            p_k *= p;
+        }
+        residues.push(BigUint::from(x));
+        moduli.push(BigUint::from(pe));
+    }
+    crt(&residues, &moduli)?.to_u64()
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    // A prime p where p-1 = 2 * 3 * 5^2 * 7 * 11 * 13 * 17 * 19 * 23 * 31 and 11 is a primitive root
+    const SMOOTH_P: u64 = 2 * 3 * 25 * 7 * 11 * 13 * 17 * 19 * 23 * 31 + 1;
+
+    fn mult_group(p: u64) -> MultiplicativeGroup {
+        MultiplicativeGroup {
+            modulus: BigUint::from(p),
+        }
+    }
+
+    // y^2 = x^3 - 3x + 16 over a 20-bit prime field with a prime number of points
+    fn curve_group() -> (EllipticCurveGroup, EcPoint, u64) {
+        (
+            EllipticCurveGroup {
+                curve: FiniteEllipticCurve::from_u64(1048580, 16, 1048583),
+            },
+            EcPoint::from_u64(2, 328603),
+            1048387,
+        )
+    }
+
+    #[test]
+    fn bsgs() {
+        let group = mult_group(1000003);
+        let g = BigUint::from(2_u32);
+        for x in [0, 1, 2, 12345, 500000] {
+            let h = group.pow(&g, x);
+            let found = baby_step_giant_step(&group, &g, &h, 1000002).unwrap();
+            assert_eq!(h, group.pow(&g, found));
+        }
+
+        let (group, g, n) = curve_group();
+        let h = group.pow(&g, 777777);
+        assert_eq!(Some(777777), baby_step_giant_step(&group, &g, &h, n));
+    }
+
+    #[test]
+    fn rho() {
+        // A subgroup of prime order q modulo p = 36q + 1
+        let q = 1000003_u64;
+        let p = 36 * q + 1;
+        let group = mult_group(p);
+        let g = group.pow(&BigUint::from(2_u32), (p - 1) / q);
+        let h = group.pow(&g, 654321);
+        assert_eq!(Some(654321), pollard_rho(&group, &g, &h, q));
+
+        let (group, g, n) = curve_group();
+        let h = group.pow(&g, 123456);
+        assert_eq!(Some(123456), pollard_rho(&group, &g, &h, n));
+    }
+
+    #[test]
+    fn kangaroo() {
+        let group = mult_group(1000003);
+        let g = BigUint::from(2_u32);
+        let h = group.pow(&g, 400100);
+        let found = pollard_kangaroo(&group, &g, &h, 400000, 410000).unwrap();
+        assert_eq!(400100, found);
+
+        let (group, g, _) = curve_group();
+        let h = group.pow(&g, 901234);
+        assert_eq!(
+            Some(901234),
+            pollard_kangaroo(&group, &g, &h, 900000, 910000)
+        );
+    }
+
+    #[test]
+    fn pohlig_hellman_smooth() {
+        let group = mult_group(SMOOTH_P);
+        let g = BigUint::from(11_u32);
+        for x in [1, 99, 31415926, SMOOTH_P - 2] {
+            let h = group.pow(&g, x);
+            assert_eq!(Some(x), pohlig_hellman(&group, &g, &h, SMOOTH_P - 1));
+        }
+
+        // A point of order 50 on the textbook curve y^2 = x^3 + 2x + 3 (mod 97)
+        let group = EllipticCurveGroup {
+            curve: FiniteEllipticCurve::from_u64(2, 3, 97),
+        };
+        let g = EcPoint::from_u64(0, 10);
+        for x in 0..50 {
+            let h = group.pow(&g, x);
+            assert_eq!(Some(x), pohlig_hellman(&group, &g, &h, 50));
+        }
+    }
+}
@@ -0,0 +1,250 @@
+use crate::digital::block_ciphers::block_cipher::{BCPadding, BlockCipher};
+use utils::errors::GeneralError;
+
+// The oracles accept input of any length so they need a padding that can always be applied
+fn check_padding(padding: BCPadding) -> Result<(), GeneralError> {
+    if padding == BCPadding::None {
+        return Err(GeneralError::input(
+            "the oracle accepts input of any length so it must use padding",
+        ));
+    }
+    Ok(())
+}
+
+/// A service that appends a secret to whatever it is given and encrypts the result in ECB mode
+pub fn ecb_suffix_oracle<'a, C: BlockCipher<N>, const N: usize>(
+    cipher: &'a C,
+    padding: BCPadding,
+    secret: &'a [u8],
+) -> Result<impl Fn(&[u8]) -> Vec<u8> + 'a, GeneralError> {
+    check_padding(padding)?;
+    Ok(move |input: &[u8]| {
+        let mut bytes = input.to_vec();
+        bytes.extend_from_slice(secret);
+        padding
+            .add_padding(&mut bytes, N as u32)
+            .expect("padding other than None cannot fail");
+        cipher.encrypt_ecb(&mut bytes);
+        bytes
+    })
+}
+
+/// Find the block size and the length of the secret suffix by feeding the oracle longer and longer inputs until the
+/// output grows by a whole block. This assumes a padding that always adds at least one byte.
+pub fn detect_block_size<F: Fn(&[u8]) -> Vec<u8>>(oracle: &F) -> Option<(usize, usize)> {
+    let base = oracle(&[]).len();
+    for k in 1..=256 {
+        let len = oracle(&vec![b'A'; k]).len();
+        if len > base {
+            return Some((len - base, base - k));
+        }
+    }
+    None
+}
+
+/// ECB encrypts identical blocks identically, so three blocks of the same byte give at least two equal blocks of
+/// ciphertext whatever precedes them
+pub fn is_ecb<F: Fn(&[u8]) -> Vec<u8>>(oracle: &F, block_size: usize) -> bool {
+    let ciphertext = oracle(&vec![b'A'; 3 * block_size]);
+    let blocks: Vec<&[u8]> = ciphertext.chunks_exact(block_size).collect();
+    blocks.windows(2).any(|w| w[0] == w[1])
+}
+
+/// Recover the secret suffix one byte at a time. Enough filler is sent that the next unknown byte is the last byte of
+/// a block whose other bytes are all known. Trying each of the 256 possible final bytes with the same known bytes
+/// finds the block that matches.
+pub fn ecb_byte_at_a_time<F: Fn(&[u8]) -> Vec<u8>>(oracle: &F) -> Result<Vec<u8>, GeneralError> {
+    let (n, suffix_len) = detect_block_size(oracle)
+        .ok_or(GeneralError::general("unable to detect the block size"))?;
+    if !is_ecb(oracle, n) {
+        return Err(GeneralError::general(
+            "the oracle does not appear to use ECB mode",
+        ));
+    }
+
+    let mut recovered = Vec::with_capacity(suffix_len);
+    for i in 0..suffix_len {
+        let filler = vec![b'A'; n - 1 - (i % n)];
+        let start = (i / n) * n;
+        let target = oracle(&filler)[start..start + n].to_vec();
+
+        let mut probe = filler;
+        probe.extend_from_slice(&recovered);
+        probe.push(0);
+        let last = probe.len() - 1;
+
+        let mut found = None;
+        for guess in 0..=255 {
+            probe[last] = guess;
+            if oracle(&probe)[start..start + n] == target[..] {
+                found = Some(guess);
+                break;
+            }
+        }
+        recovered.push(found.ok_or(GeneralError::general("no byte matched the block"))?);
+    }
+    Ok(recovered)
+}
+
+/// Encode a user profile as key=value pairs, removing the metacharacters from the email so that no fields can be
+/// injected through it
+pub fn profile_for(email: &[u8]) -> Vec<u8> {
+    let mut out = b"email=".to_vec();
+    out.extend(email.iter().filter(|b| **b != b'&' && **b != b'='));
+    out.extend_from_slice(b"&uid=10&role=user");
+    out
+}
+
+/// Split an encoded profile into its fields
+pub fn parse_profile(bytes: &[u8]) -> Vec<(Vec<u8>, Vec<u8>)> {
+    bytes
+        .split(|b| *b == b'&')
+        .filter_map(|field| {
+            let eq = field.iter().position(|b| *b == b'=')?;
+            Some((field[..eq].to_vec(), field[eq + 1..].to_vec()))
+        })
+        .collect()
+}
+
+/// A service that hands out ECB encrypted profiles for any email address
+pub fn ecb_profile_oracle<'a, C: BlockCipher<N>, const N: usize>(
+    cipher: &'a C,
+    padding: BCPadding,
+) -> Result<impl Fn(&[u8]) -> Vec<u8> + 'a, GeneralError> {
+    check_padding(padding)?;
+    Ok(move |email: &[u8]| {
+        let mut bytes = profile_for(email);
+        padding
+            .add_padding(&mut bytes, N as u32)
+            .expect("padding other than None cannot fail");
+        cipher.encrypt_ecb(&mut bytes);
+        bytes
+    })
+}
+
+/// Create an encrypted profile with the admin role using only the profile service. One email is chosen so that
+/// "admin" followed by valid padding fills a block by itself, another so that "user" begins the final block, and
+/// the first block is pasted over the second.
+pub fn ecb_cut_and_paste<F: Fn(&[u8]) -> Vec<u8>>(
+    oracle: &F,
+    block_size: usize,
+    padding: BCPadding,
+) -> Result<Vec<u8>, GeneralError> {
+    let n = block_size;
+    let prefix = b"email=".len();
+
+    let mut admin = b"admin".to_vec();
+    padding.add_padding(&mut admin, n as u32)?;
+    if admin.len() != n || admin.contains(&b'&') || admin.contains(&b'=') {
+        return Err(GeneralError::general(
+            "the padded role cannot be placed in a block",
+        ));
+    }
+    let fill = (n - prefix % n) % n;
+    let mut email = vec![b'x'; fill];
+    email.extend_from_slice(&admin);
+    let index = (prefix + fill) / n;
+    let admin_block = oracle(&email)[index * n..(index + 1) * n].to_vec();
+
+    let fixed = b"email=&uid=10&role=".len();
+    let email_len = (n - fixed % n) % n;
+    let mut out = oracle(&vec![b'x'; email_len]);
+    out.truncate(fixed + email_len);
+    out.extend(admin_block);
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::digital::block_ciphers::{aes::aes::Aes256, des::des::Des};
+    use hex_literal::hex;
+
+    const SECRET: &[u8] = b"Rollin' in my 5.0\nWith my rag-top down so my hair can blow";
+
+    fn des() -> Des {
+        let mut cipher = Des::default();
+        cipher.ksa(0x133457799bbcdff1).unwrap();
+        cipher
+    }
+
+    fn aes() -> Aes256 {
+        Aes256::default().with_key(hex!(
+            "603deb1015ca71be2b73aef0857d77811f352c073b6108d72d9810a30914dff4"
+        ))
+    }
+
+    fn role<C: BlockCipher<N>, const N: usize>(
+        cipher: &C,
+        padding: BCPadding,
+        ciphertext: &[u8],
+    ) -> Vec<u8> {
+        let mut bytes = ciphertext.to_vec();
+        cipher.decrypt_ecb(&mut bytes);
+        padding.strip_padding(&mut bytes, N as u32).unwrap();
+        parse_profile(&bytes)
+            .into_iter()
+            .find(|(k, _)| k == b"role")
+            .unwrap()
+            .1
+    }
+
+    #[test]
+    fn block_size() {
+        let cipher = des();
+        let oracle = ecb_suffix_oracle(&cipher, BCPadding::Pkcs, SECRET).unwrap();
+        assert_eq!(Some((8, SECRET.len())), detect_block_size(&oracle));
+        assert!(is_ecb(&oracle, 8));
+
+        let cipher = aes();
+        let oracle = ecb_suffix_oracle(&cipher, BCPadding::Bit, SECRET).unwrap();
+        assert_eq!(Some((16, SECRET.len())), detect_block_size(&oracle));
+        assert!(is_ecb(&oracle, 16));
+    }
+
+    #[test]
+    fn byte_at_a_time() {
+        let cipher = des();
+        let oracle = ecb_suffix_oracle(&cipher, BCPadding::Ansi923, SECRET).unwrap();
+        assert_eq!(SECRET, ecb_byte_at_a_time(&oracle).unwrap());
+
+        let cipher = aes();
+        let oracle = ecb_suffix_oracle(&cipher, BCPadding::Pkcs, SECRET).unwrap();
+        assert_eq!(SECRET, ecb_byte_at_a_time(&oracle).unwrap());
+    }
+
+    #[test]
+    fn cut_and_paste() {
+        for padding in [BCPadding::Pkcs, BCPadding::Ansi923, BCPadding::Bit] {
+            let cipher = des();
+            let oracle = ecb_profile_oracle(&cipher, padding).unwrap();
+            assert_eq!(
+                b"user".to_vec(),
+                role(&cipher, padding, &oracle(b"a@b.com"))
+            );
+            let forged = ecb_cut_and_paste(&oracle, 8, padding).unwrap();
+            assert_eq!(b"admin".to_vec(), role(&cipher, padding, &forged));
+
+            let cipher = aes();
+            let oracle = ecb_profile_oracle(&cipher, padding).unwrap();
+            let forged = ecb_cut_and_paste(&oracle, 16, padding).unwrap();
+            assert_eq!(b"admin".to_vec(), role(&cipher, padding, &forged));
+        }
+    }
+
+    #[test]
+    fn no_padding_rejected() {
+        let cipher = aes();
+        assert!(ecb_suffix_oracle(&cipher, BCPadding::None, SECRET).is_err());
+        assert!(ecb_profile_oracle(&cipher, BCPadding::None).is_err());
+    }
+
+    #[test]
+    fn metacharacters_removed() {
+        assert_eq!(
+            b"email=me@x.comroleadmin&uid=10&role=user".to_vec(),
+            profile_for(b"me@x.com&role=admin")
+        );
+    }
+}
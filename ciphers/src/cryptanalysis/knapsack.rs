@@ -0,0 +1,102 @@
+use crate::digital::public_key_ciphers::merkle_hellman::MerkleHellman;
+use num::{BigInt, BigUint, One, Signed, Zero};
+use utils::{lattice::lll, padding::strip_bit_padding};
+
+/// Solve the subset sum problem for a knapsack of low density with the lattice attack of Lagarias and Odlyzko, as
+/// improved by Coster, Joux, LaMacchia, Odlyzko, Schnorr and Stern. The solution is a very short vector, with every
+/// entry +1 or -1, in a lattice built from the weights and the target. LLL usually finds it when the density
+/// n/log2(max a_i) is below about 0.94 and n is small enough for LLL to act like an exact shortest vector search.
+/// https://doi.org/10.1007/BF01201999
+pub fn subset_sum(weights: &[BigUint], target: &BigUint) -> Option<Vec<bool>> {
+    let n = weights.len();
+    // Scaling the last column makes any vector without a zero there far too long to be short
+    let scale = BigInt::from(n.max(2));
+    let mut basis = Vec::with_capacity(n + 1);
+    for (i, a) in weights.iter().enumerate() {
+        let mut row = vec![BigInt::zero(); n + 1];
+        row[i] = BigInt::from(2);
+        row[n] = &scale * BigInt::from(a.clone());
+        basis.push(row);
+    }
+    let mut last = vec![BigInt::one(); n + 1];
+    last[n] = &scale * BigInt::from(target.clone());
+    basis.push(last);
+
+    // A solution x gives the vector (2x_i - 1, ..., 0) from sum(x_i b_i) - b_n
+    let check = |bits: &[bool]| {
+        weights
+            .iter()
+            .zip(bits)
+            .filter(|(_, b)| **b)
+            .map(|(a, _)| a)
+            .sum::<BigUint>()
+            == *target
+    };
+    for v in lll(&basis) {
+        if !v[n].is_zero() || !v[..n].iter().all(|x| x.abs().is_one()) {
+            continue;
+        }
+        for sign in [BigInt::one(), -BigInt::one()] {
+            let bits: Vec<bool> = v[..n].iter().map(|x| *x == sign).collect();
+            if check(&bits) {
+                return Some(bits);
+            }
+        }
+    }
+    None
+}
+
+/// Decrypt a Merkle-Hellman ciphertext using only the public key. The permutation does not matter here since the
+/// lattice attack works with the public key as it is.
+///
+/// Shamir's original 1982 break is not implemented. It recovers a trapdoor instead of a message: the smallest
+/// elements of the superincreasing sequence are tiny compared to the modulus, so if the multiplier is undone then the
+/// public elements that came from them all land very close to zero. Shamir writes each of these as a sawtooth
+/// function of the unknown inverse multiplier and looks for a value where a handful of them are small together.
+/// That search is an integer program in a fixed number of variables, which he solves with Lenstra's algorithm. The
+/// result is some multiplier and modulus that turn the public key into a superincreasing sequence, not necessarily
+/// the original ones. The permutation only means guessing which few public elements to start from. Lenstra's
+/// algorithm is polynomial in theory but heavy in practice. The lattice attack below is far simpler, and it also
+/// breaks the iterated variants and other low density knapsacks that Shamir's method does not reach.
+pub fn break_merkle_hellman(cipher: &MerkleHellman, ciphertext: &[u8]) -> Option<Vec<u8>> {
+    let len = cipher.ciphertext_len();
+    if len == 0 || !ciphertext.len().is_multiple_of(len) {
+        return None;
+    }
+    let mut out = Vec::new();
+    for block in ciphertext.chunks_exact(len) {
+        let bits = subset_sum(&cipher.public_key, &BigUint::from_bytes_be(block))?;
+        for byte in bits.chunks(8) {
+            out.push(byte.iter().fold(0, |acc, b| (acc << 1) | *b as u8));
+        }
+    }
+    strip_bit_padding(&mut out).ok()?;
+    Some(out)
+}
+
+#[cfg(test)]
+mod knapsack_tests {
+
+    use super::*;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn small_subset_sum() {
+        let weights = [366_u32, 385, 392, 401, 422, 437].map(BigUint::from);
+        let bits = subset_sum(&weights, &BigUint::from(1195_u32)).unwrap();
+        assert_eq!(vec![true, false, true, false, false, true], bits);
+    }
+
+    #[test]
+    fn break_cipher() {
+        let mut cipher = MerkleHellman::default();
+        cipher
+            .generate_key(16, &mut StdRng::seed_from_u64(9))
+            .unwrap();
+        let ciphertext = cipher.encrypt_bytes(b"attack at dawn").unwrap();
+        assert_eq!(
+            b"attack at dawn".to_vec(),
+            break_merkle_hellman(&cipher, &ciphertext).unwrap()
+        );
+    }
+}
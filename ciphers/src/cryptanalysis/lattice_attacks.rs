@@ -0,0 +1,280 @@
+use crate::digital::public_key_ciphers::{lwe::Lwe, ntru::Ntru};
+use num::{BigInt, One, Signed, ToPrimitive, Zero};
+use utils::lattice::lll;
+
+fn inverse_mod(a: i64, m: i64) -> Option<i64> {
+    let (mut r0, mut r1) = (m, a.rem_euclid(m));
+    let (mut t0, mut t1) = (0, 1);
+    while r1 != 0 {
+        let q = r0 / r1;
+        (r0, r1) = (r1, r0 - q * r1);
+        (t0, t1) = (t1, t0 - q * t1);
+    }
+    (r0 == 1).then_some(t0.rem_euclid(m))
+}
+
+fn center(x: i64, m: i64) -> i64 {
+    let x = x.rem_euclid(m);
+    if x > m / 2 {
+        x - m
+    } else {
+        x
+    }
+}
+
+// Inverse of a square matrix modulo q by Gauss-Jordan elimination
+fn invert_matrix(matrix: &[Vec<i64>], q: i64) -> Option<Vec<Vec<i64>>> {
+    let n = matrix.len();
+    let mut aug: Vec<Vec<i64>> = matrix
+        .iter()
+        .enumerate()
+        .map(|(i, row)| {
+            let mut row = row.clone();
+            row.extend((0..n).map(|j| (i == j) as i64));
+            row
+        })
+        .collect();
+    for col in 0..n {
+        let pivot = (col..n).find(|r| inverse_mod(aug[*r][col], q).is_some())?;
+        aug.swap(col, pivot);
+        let inv = inverse_mod(aug[col][col], q)?;
+        for x in aug[col].iter_mut() {
+            *x = *x * inv % q;
+        }
+        let pivot_row = aug[col].clone();
+        for (r, row) in aug.iter_mut().enumerate() {
+            let c = row[col];
+            if r != col && c != 0 {
+                for (x, y) in row.iter_mut().zip(&pivot_row) {
+                    *x = (*x - c * y).rem_euclid(q);
+                }
+            }
+        }
+    }
+    Some(aug.into_iter().map(|row| row[n..].to_vec()).collect())
+}
+
+/// Recover the secret of a small LWE instance from the public key alone with Kannan's embedding technique. Choosing n
+/// samples with an invertible matrix A1 lets the secret be eliminated, leaving the lattice of vectors (y1, y2) with
+/// y2 = X y1 mod q where X = A2 A1^-1. The vector b is then very close to a lattice point, the difference being the
+/// error vector, and appending (b, 1) to the basis turns that into finding the short vector (e, 1) which LLL can do
+/// when the dimension is small. Real parameters put this far out of reach.
+pub fn lwe_secret_recovery(cipher: &Lwe) -> Option<Vec<u64>> {
+    let n = cipher.n;
+    let m = cipher.a.len();
+    let q = i64::try_from(cipher.q).ok()?;
+    if cipher.b.len() != m || m <= n {
+        return None;
+    }
+    let a: Vec<Vec<i64>> = cipher
+        .a
+        .iter()
+        .map(|row| row.iter().map(|x| *x as i64).collect())
+        .collect();
+    let b: Vec<i64> = cipher.b.iter().map(|x| *x as i64).collect();
+
+    // Greedily choose n linearly independent rows, keeping them in echelon form as they are found
+    let mut echelon: Vec<(usize, Vec<i64>)> = Vec::with_capacity(n);
+    let mut order = Vec::with_capacity(m);
+    for (idx, row) in a.iter().enumerate() {
+        if echelon.len() == n {
+            break;
+        }
+        let mut r = row.clone();
+        for (col, e) in &echelon {
+            let c = r[*col];
+            if c != 0 {
+                for (x, y) in r.iter_mut().zip(e) {
+                    *x = (*x - c * y).rem_euclid(q);
+                }
+            }
+        }
+        let pivot = r
+            .iter()
+            .enumerate()
+            .find_map(|(i, x)| inverse_mod(*x, q).map(|inv| (i, inv)));
+        if let Some((col, inv)) = pivot {
+            for x in r.iter_mut() {
+                *x = *x * inv % q;
+            }
+            echelon.push((col, r));
+            order.push(idx);
+        }
+    }
+    if order.len() < n {
+        return None;
+    }
+    let rest: Vec<usize> = (0..m).filter(|i| !order.contains(i)).collect();
+    order.extend(rest);
+
+    let a1: Vec<Vec<i64>> = order[..n].iter().map(|i| a[*i].clone()).collect();
+    let a1_inv = invert_matrix(&a1, q)?;
+    let x: Vec<Vec<i64>> = order[n..]
+        .iter()
+        .map(|i| {
+            (0..n)
+                .map(|j| {
+                    a[*i]
+                        .iter()
+                        .zip(&a1_inv)
+                        .fold(0, |acc, (v, row)| (acc + v * row[j]) % q)
+                })
+                .collect()
+        })
+        .collect();
+
+    let mut basis = Vec::with_capacity(m + 1);
+    for i in 0..n {
+        let mut row = vec![BigInt::zero(); m + 1];
+        row[i] = BigInt::one();
+        for (k, x_row) in x.iter().enumerate() {
+            row[n + k] = BigInt::from(x_row[i]);
+        }
+        basis.push(row);
+    }
+    for j in n..m {
+        let mut row = vec![BigInt::zero(); m + 1];
+        row[j] = BigInt::from(q);
+        basis.push(row);
+    }
+    let mut last: Vec<BigInt> = order.iter().map(|i| BigInt::from(b[*i])).collect();
+    last.push(BigInt::one());
+    basis.push(last);
+
+    for v in lll(&basis) {
+        if !v[m].abs().is_one() {
+            continue;
+        }
+        let sign = if v[m].is_positive() { 1 } else { -1 };
+        let e: Vec<i64> = v[..m]
+            .iter()
+            .map(|x| sign * x.to_i64().unwrap_or(0))
+            .collect();
+        let secret: Vec<i64> = a1_inv
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .zip(&order[..n])
+                    .zip(&e)
+                    .fold(0, |acc, ((c, i), e)| (acc + c * (b[*i] - e)).rem_euclid(q))
+            })
+            .collect();
+        let bound = cipher.error_bound as i64;
+        let valid = a.iter().zip(&b).all(|(row, b)| {
+            let dot = row
+                .iter()
+                .zip(&secret)
+                .fold(0, |acc, (x, s)| (acc + x * s) % q);
+            center(b - dot, q).abs() <= bound
+        });
+        if valid {
+            return Some(secret.into_iter().map(|x| x as u64).collect());
+        }
+    }
+    None
+}
+
+/// Find an equivalent NTRU private key from the public key. With h' = h/p the private polynomials satisfy
+/// g = f h' mod q, so (f, g) is an unusually short vector in the lattice generated by the rows of [I H'; 0 qI], where
+/// H' is the circulant matrix of h'. Any short (f', g') found there with f' invertible works as a private key,
+/// usually it is (f, g) itself rotated or negated. For the small N used here LLL finds one.
+/// https://doi.org/10.1007/3-540-69053-0_5
+pub fn ntru_key_recovery(cipher: &Ntru) -> Option<Ntru> {
+    let (n, p, q) = (cipher.n, cipher.p, cipher.q);
+    if cipher.h.len() != n {
+        return None;
+    }
+    let p_inv = inverse_mod(p, q)?;
+    let h: Vec<i64> = cipher.h.iter().map(|x| x * p_inv % q).collect();
+
+    let mut basis = Vec::with_capacity(2 * n);
+    for i in 0..n {
+        let mut row = vec![BigInt::zero(); 2 * n];
+        row[i] = BigInt::one();
+        // Coefficients of x^i h'
+        for k in 0..n {
+            row[n + k] = BigInt::from(center(h[(k + n - i) % n], q));
+        }
+        basis.push(row);
+    }
+    for i in 0..n {
+        let mut row = vec![BigInt::zero(); 2 * n];
+        row[n + i] = BigInt::from(q);
+        basis.push(row);
+    }
+
+    for v in lll(&basis) {
+        let coefficients: Option<Vec<i64>> = v.iter().map(|x| x.to_i64()).collect();
+        let Some(coefficients) = coefficients else {
+            continue;
+        };
+        let (f, g) = coefficients.split_at(n);
+        let mut candidate = Ntru {
+            input_format: cipher.input_format,
+            output_format: cipher.output_format,
+            n,
+            p,
+            q,
+            d: cipher.d,
+            ..Default::default()
+        };
+        if candidate.set_key(f.to_vec(), g.to_vec()).is_ok() && candidate.h == cipher.h {
+            return Some(candidate);
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod lattice_attacks_tests {
+
+    use super::*;
+    use crate::Cipher;
+    use rand::{rngs::StdRng, SeedableRng};
+    use utils::byte_formatting::ByteFormat;
+
+    #[test]
+    fn lwe_attack() {
+        let mut rng = StdRng::seed_from_u64(16);
+        let mut cipher = Lwe::default();
+        cipher.set_parameters(8, 24, 97, 1).unwrap();
+        cipher.generate_key(&mut rng);
+        assert_eq!(cipher.secret, lwe_secret_recovery(&cipher).unwrap());
+    }
+
+    #[test]
+    fn ntru_attack_small_example() {
+        let mut cipher = Ntru {
+            n: 11,
+            q: 32,
+            ..Default::default()
+        };
+        let mut f = vec![-1, 1, 1, 0, -1, 0, 1, 0, 0, 1, -1];
+        let g = vec![-1, 0, 1, 1, 0, 1, 0, 0, -1, 0, -1];
+        cipher.set_key(f.clone(), g).unwrap();
+
+        let recovered = ntru_key_recovery(&cipher).unwrap();
+        let e = vec![14, 11, 26, 24, 14, 16, 30, 7, 25, 6, 19];
+        let m = vec![-1, 0, 0, 1, -1, 0, 0, 0, -1, 1, 1];
+        assert_eq!(m, recovered.decrypt_poly(&e).unwrap());
+        // The key found should be a rotation of f or its negative
+        let found = (0..11).any(|_| {
+            f.rotate_right(1);
+            recovered.f == f || recovered.f == f.iter().map(|x| -x).collect::<Vec<i64>>()
+        });
+        assert!(found);
+    }
+
+    #[test]
+    fn ntru_attack_random_key() {
+        let mut cipher = Ntru::default();
+        cipher.set_parameters(23, 3, 64, 3).unwrap();
+        cipher.generate_key(&mut StdRng::seed_from_u64(17));
+        let ctext = cipher.encrypt("hi").unwrap();
+
+        let mut recovered = ntru_key_recovery(&cipher).unwrap();
+        recovered.input_format = ByteFormat::Hex;
+        recovered.output_format = ByteFormat::Utf8;
+        assert_eq!("hi", recovered.decrypt(&ctext).unwrap());
+    }
+}
@@ -0,0 +1,16 @@
+// Attacks on the weaker ciphers and generators of the crate, intended to demonstrate why they are weak
+pub mod a51_tmto;
+pub mod berlekamp_massey;
+pub mod bleichenbacher;
+pub mod correlation_attack;
+pub mod discrete_log;
+pub mod ecb_oracle;
+pub mod knapsack;
+pub mod lattice_attacks;
+pub mod nonce_reuse;
+pub mod padding_oracle;
+pub mod rc4_biases;
+pub mod rsa_attacks;
+pub mod small_subgroup;
+pub mod two_time_pad;
+pub mod wep;
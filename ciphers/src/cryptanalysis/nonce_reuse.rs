@@ -0,0 +1,113 @@
+use num::{BigUint, Zero};
+use utils::math_functions::mul_inv;
+
+// DSA and ECDSA signatures satisfy s = (h + xr)/k mod q. Two signatures made with the same nonce k share r, which
+// makes the reuse visible, and leave only two unknowns in two linear equations. Schnorr signatures s = k + xe fail
+// the same way. This is how the PlayStation 3 signing key was recovered.
+
+fn sub_mod(a: &BigUint, b: &BigUint, q: &BigUint) -> BigUint {
+    (a % q + q - b % q) % q
+}
+
+fn div_mod(a: &BigUint, b: &BigUint, q: &BigUint) -> Option<BigUint> {
+    if (b % q).is_zero() {
+        return None;
+    }
+    let inv = mul_inv(b, q).and_then(|x| x.to_biguint())?;
+    Some((a * inv) % q)
+}
+
+/// Given the hashes h1 and h2 of two messages signed with DSA or ECDSA using the same nonce, so that both signatures
+/// share the same r, recover the nonce k = (h1 - h2)/(s1 - s2) and the private key x = (s1k - h1)/r. Fails if the
+/// two signatures are the same.
+pub fn dsa_nonce_reuse(
+    q: &BigUint,
+    r: &BigUint,
+    (h1, s1): (&BigUint, &BigUint),
+    (h2, s2): (&BigUint, &BigUint),
+) -> Option<(BigUint, BigUint)> {
+    let k = div_mod(&sub_mod(h1, h2, q), &sub_mod(s1, s2, q), q)?;
+    let x = div_mod(&sub_mod(&(s1 * &k), h1, q), r, q)?;
+    Some((k, x))
+}
+
+/// Given two Schnorr signatures (e, s) made with the same nonce, recover the private key x = (s1 - s2)/(e1 - e2)
+pub fn schnorr_nonce_reuse(
+    q: &BigUint,
+    (e1, s1): (&BigUint, &BigUint),
+    (e2, s2): (&BigUint, &BigUint),
+) -> Option<BigUint> {
+    div_mod(&sub_mod(s1, s2, q), &sub_mod(e1, e2, q), q)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::digital::public_key_ciphers::{dsa::Dsa, ecdsa::Ecdsa, schnorr::Schnorr};
+    use rand::{rngs::StdRng, SeedableRng};
+    use utils::{elliptic_curves::NamedCurve, primality::random_biguint_below};
+
+    #[test]
+    fn dsa() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let mut victim = Dsa::default();
+        victim.generate_key(&mut rng);
+        let q = victim.parameters.q.clone();
+        let k = random_biguint_below(&q, &mut rng);
+
+        let (r1, s1) = victim.sign_with_nonce(b"first message", &k).unwrap();
+        let (r2, s2) = victim.sign_with_nonce(b"second message", &k).unwrap();
+        assert_eq!(r1, r2);
+
+        let h1 = victim.hash_to_int(b"first message");
+        let h2 = victim.hash_to_int(b"second message");
+        let (recovered_k, x) = dsa_nonce_reuse(&q, &r1, (&h1, &s1), (&h2, &s2)).unwrap();
+        assert_eq!(k, recovered_k);
+        assert_eq!(victim.private_key, x);
+
+        // Anyone can now sign as the victim
+        let mut forger = Dsa::default();
+        forger.set_private_key(&x).unwrap();
+        let (r, s) = forger.sign(b"forged message").unwrap();
+        assert!(victim.verify(b"forged message", &r, &s));
+    }
+
+    #[test]
+    fn ecdsa() {
+        let mut rng = StdRng::seed_from_u64(2);
+        let mut victim = Ecdsa::default().with_curve(NamedCurve::Secp256k1);
+        victim.generate_key(&mut rng);
+        let n = victim.order();
+        let k = random_biguint_below(&n, &mut rng);
+
+        let (r1, s1) = victim.sign_with_nonce(b"first message", &k).unwrap();
+        let (r2, s2) = victim.sign_with_nonce(b"second message", &k).unwrap();
+        assert_eq!(r1, r2);
+
+        let h1 = victim.hash_to_int(b"first message");
+        let h2 = victim.hash_to_int(b"second message");
+        let (recovered_k, d) = dsa_nonce_reuse(&n, &r1, (&h1, &s1), (&h2, &s2)).unwrap();
+        assert_eq!(k, recovered_k);
+        assert_eq!(victim.private_key, d);
+
+        // The same signature twice gives no information
+        assert!(dsa_nonce_reuse(&n, &r1, (&h1, &s1), (&h1, &s1)).is_none());
+    }
+
+    #[test]
+    fn schnorr() {
+        let mut rng = StdRng::seed_from_u64(3);
+        let mut victim = Schnorr::default();
+        victim.generate_key(&mut rng);
+        let q = victim.parameters.q.clone();
+        let k = random_biguint_below(&q, &mut rng);
+
+        let (e1, s1) = victim.sign_with_nonce(b"first message", &k).unwrap();
+        let (e2, s2) = victim.sign_with_nonce(b"second message", &k).unwrap();
+        assert_eq!(
+            victim.private_key,
+            schnorr_nonce_reuse(&q, (&e1, &s1), (&e2, &s2)).unwrap()
+        );
+    }
+}
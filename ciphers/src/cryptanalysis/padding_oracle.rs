@@ -0,0 +1,241 @@
+use crate::digital::block_ciphers::block_cipher::{BCPadding, BlockCipher};
+use utils::errors::GeneralError;
+
+/// A receiver that decrypts CBC ciphertext with the given IV and reveals nothing except whether the padding was valid,
+/// perhaps through an error message or a difference in timing
+pub fn cbc_padding_oracle<'a, C: BlockCipher<N>, const N: usize>(
+    cipher: &'a C,
+    padding: BCPadding,
+) -> impl Fn(&[u8; N], &[u8]) -> bool + 'a {
+    move |iv, ciphertext| {
+        if ciphertext.is_empty() || !ciphertext.len().is_multiple_of(N) {
+            return false;
+        }
+        let mut bytes = ciphertext.to_vec();
+        cipher.decrypt_cbc(&mut bytes, *iv);
+        padding.strip_padding(&mut bytes, N as u32).is_ok()
+    }
+}
+
+// The final n bytes of a block that has exactly n bytes of padding
+fn padding_pattern(padding: BCPadding, n: usize) -> Option<Vec<u8>> {
+    match padding {
+        BCPadding::None => None,
+        BCPadding::Pkcs => Some(vec![n as u8; n]),
+        BCPadding::Ansi923 => {
+            let mut out = vec![0; n];
+            out[n - 1] = n as u8;
+            Some(out)
+        }
+        BCPadding::Bit => {
+            let mut out = vec![0; n];
+            out[0] = 0x80;
+            Some(out)
+        }
+    }
+}
+
+/// Find the raw block decryption of one ciphertext block, before it is XORed with the previous block, using only the
+/// padding oracle. This is the heart of Vaudenay's attack.
+///
+/// The block is sent after a forged block whose bytes are chosen so that the end of the plaintext is a valid padding
+/// of one byte, then two bytes, and so on. Each step needs at most 256 guesses. A guess that only works because of
+/// an unlucky byte before the padding is ruled out by changing the byte of the forged block just before it and asking
+/// again, which for the first step is byte N-2 as in Vaudenay's paper.
+pub fn decrypt_block_with_oracle<F, const N: usize>(
+    oracle: &F,
+    padding: BCPadding,
+    block: &[u8; N],
+) -> Result<[u8; N], GeneralError>
+where
+    F: Fn(&[u8; N], &[u8]) -> bool,
+{
+    let mut intermediate = [0; N];
+    let iv = [0; N];
+    let mut message = vec![0; 2 * N];
+    message[N..].copy_from_slice(block);
+
+    for i in (0..N).rev() {
+        let target = padding_pattern(padding, N - i).ok_or(GeneralError::input(
+            "without padding there is no padding oracle",
+        ))?;
+        for j in (i + 1)..N {
+            message[j] = intermediate[j] ^ target[j - i];
+        }
+
+        let mut found = None;
+        for guess in 0..=255 {
+            message[i] = guess;
+            if !oracle(&iv, &message) {
+                continue;
+            }
+            // When i is zero the padding fills the whole block so there is nothing before it to change
+            let confirmed = i == 0 || {
+                let mut check = message.clone();
+                check[i - 1] ^= 0xff;
+                oracle(&iv, &check)
+            };
+            if confirmed {
+                found = Some(guess);
+                break;
+            }
+        }
+
+        let guess = found.ok_or(GeneralError::general("oracle never accepted the padding"))?;
+        intermediate[i] = guess ^ target[0];
+    }
+    Ok(intermediate)
+}
+
+/// Decrypt a CBC message with a padding oracle and remove its padding
+pub fn padding_oracle_decrypt<F, const N: usize>(
+    oracle: &F,
+    padding: BCPadding,
+    iv: &[u8; N],
+    ciphertext: &[u8],
+) -> Result<Vec<u8>, GeneralError>
+where
+    F: Fn(&[u8; N], &[u8]) -> bool,
+{
+    if !ciphertext.len().is_multiple_of(N) {
+        return Err(GeneralError::input(format!(
+            "ciphertext must be in chunks of {} bytes",
+            N
+        )));
+    }
+    let mut out = Vec::with_capacity(ciphertext.len());
+    let mut previous = *iv;
+    for chunk in ciphertext.chunks_exact(N) {
+        let block: [u8; N] = chunk.try_into().unwrap();
+        let intermediate = decrypt_block_with_oracle(oracle, padding, &block)?;
+        out.extend(intermediate.iter().zip(previous).map(|(a, b)| a ^ b));
+        previous = block;
+    }
+    padding.strip_padding(&mut out, N as u32)?;
+    Ok(out)
+}
+
+/// Forge a CBC encryption of any message without the key. The last block is arbitrary, the oracle reveals its raw
+/// decryption and the block before it is chosen to turn that into the desired plaintext, and so on back to the IV.
+pub fn padding_oracle_encrypt<F, const N: usize>(
+    oracle: &F,
+    padding: BCPadding,
+    plaintext: &[u8],
+) -> Result<([u8; N], Vec<u8>), GeneralError>
+where
+    F: Fn(&[u8; N], &[u8]) -> bool,
+{
+    let mut padded = plaintext.to_vec();
+    padding.add_padding(&mut padded, N as u32)?;
+
+    let mut blocks: Vec<[u8; N]> = vec![[0; N]];
+    for chunk in padded.chunks_exact(N).rev() {
+        let intermediate = decrypt_block_with_oracle(oracle, padding, blocks.last().unwrap())?;
+        let mut previous = [0; N];
+        for i in 0..N {
+            previous[i] = intermediate[i] ^ chunk[i];
+        }
+        blocks.push(previous);
+    }
+
+    let iv = blocks.pop().unwrap();
+    let ciphertext = blocks.iter().rev().flatten().copied().collect();
+    Ok((iv, ciphertext))
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::digital::block_ciphers::{aes::aes::Aes256, des::des::Des};
+    use hex_literal::hex;
+    use std::cell::Cell;
+
+    const MESSAGE: &[u8] = b"The password for the vault is 'correct horse battery staple'.";
+
+    fn encrypt_cbc<C: BlockCipher<N>, const N: usize>(
+        cipher: &C,
+        padding: BCPadding,
+        iv: [u8; N],
+    ) -> Vec<u8> {
+        let mut bytes = MESSAGE.to_vec();
+        padding.add_padding(&mut bytes, N as u32).unwrap();
+        cipher.encrypt_cbc(&mut bytes, iv);
+        bytes
+    }
+
+    fn des() -> Des {
+        let mut cipher = Des::default();
+        cipher.ksa(0x133457799bbcdff1).unwrap();
+        cipher
+    }
+
+    fn aes() -> Aes256 {
+        Aes256::default().with_key(hex!(
+            "603deb1015ca71be2b73aef0857d77811f352c073b6108d72d9810a30914dff4"
+        ))
+    }
+
+    #[test]
+    fn decrypt_des() {
+        let cipher = des();
+        let iv = hex!("0102030405060708");
+        for padding in [BCPadding::Pkcs, BCPadding::Ansi923, BCPadding::Bit] {
+            let ciphertext = encrypt_cbc(&cipher, padding, iv);
+            let oracle = cbc_padding_oracle(&cipher, padding);
+            assert_eq!(
+                MESSAGE,
+                padding_oracle_decrypt(&oracle, padding, &iv, &ciphertext).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn decrypt_aes() {
+        let cipher = aes();
+        let iv = hex!("f0f1f2f3f4f5f6f7f8f9fafbfcfdfeff");
+        let ciphertext = encrypt_cbc(&cipher, BCPadding::Pkcs, iv);
+
+        let queries = Cell::new(0);
+        let inner = cbc_padding_oracle(&cipher, BCPadding::Pkcs);
+        let oracle = |iv: &[u8; 16], c: &[u8]| {
+            queries.set(queries.get() + 1);
+            inner(iv, c)
+        };
+        assert_eq!(
+            MESSAGE,
+            padding_oracle_decrypt(&oracle, BCPadding::Pkcs, &iv, &ciphertext).unwrap()
+        );
+        // On average about 128 guesses are needed per byte
+        assert!(queries.get() < 256 * ciphertext.len());
+    }
+
+    #[test]
+    fn forge() {
+        let forged = b"Transfer 1000000 to account 42";
+        for padding in [BCPadding::Pkcs, BCPadding::Bit] {
+            let cipher = aes();
+            let oracle = cbc_padding_oracle(&cipher, padding);
+            let (iv, ciphertext) = padding_oracle_encrypt(&oracle, padding, forged).unwrap();
+            let mut bytes = ciphertext.clone();
+            cipher.decrypt_cbc(&mut bytes, iv);
+            padding.strip_padding(&mut bytes, 16).unwrap();
+            assert_eq!(forged.to_vec(), bytes);
+
+            let cipher = des();
+            let oracle = cbc_padding_oracle(&cipher, padding);
+            let (iv, ciphertext) = padding_oracle_encrypt(&oracle, padding, forged).unwrap();
+            let mut bytes = ciphertext.clone();
+            cipher.decrypt_cbc(&mut bytes, iv);
+            padding.strip_padding(&mut bytes, 8).unwrap();
+            assert_eq!(forged.to_vec(), bytes);
+        }
+    }
+
+    #[test]
+    fn no_padding_no_oracle() {
+        let cipher = des();
+        let oracle = cbc_padding_oracle(&cipher, BCPadding::None);
+        assert!(decrypt_block_with_oracle(&oracle, BCPadding::None, &[0; 8]).is_err());
+    }
+}
@@ -0,0 +1,172 @@
+use crate::digital::stream_ciphers::rc4::Rc4;
+use rand::Rng;
+
+/// Count how often each value appears at each of the first n positions of the keystream over many random keys of the
+/// given length
+pub fn position_histogram<R: Rng>(
+    rng: &mut R,
+    key_len: usize,
+    n_keys: usize,
+    n_positions: usize,
+) -> Vec<[u64; 256]> {
+    let mut out = vec![[0_u64; 256]; n_positions];
+    let mut key = vec![0; key_len];
+    let mut rc4 = Rc4::default();
+    for _ in 0..n_keys {
+        rng.fill(&mut key[..]);
+        rc4.ksa(&key);
+        for counts in out.iter_mut() {
+            counts[rc4.next_byte() as usize] += 1;
+        }
+    }
+    out
+}
+
+/// The Mantin-Shamir bias. The second output byte is zero with probability close to 2/256 rather than 1/256, which
+/// distinguishes RC4 from random with a few hundred keys and lets the second byte of a broadcast message be read
+/// directly. Returns the observed rate.
+pub fn second_byte_zero_rate<R: Rng>(rng: &mut R, key_len: usize, n_keys: usize) -> f64 {
+    let histogram = position_histogram(rng, key_len, n_keys, 2);
+    histogram[1][0] as f64 / n_keys as f64
+}
+
+/// One of the long term digraph biases found by Fluhrer and McGrew. For each value of the index i when the first
+/// byte is output, the pattern gives the pair of consecutive bytes that is biased, if any.
+pub struct DigraphBias {
+    pub name: &'static str,
+    pub pattern: fn(u8) -> Option<(u8, u8)>,
+    /// Probability of the pair relative to the 2^-16 of a random stream
+    pub relative: f64,
+}
+
+const POS: f64 = 1.0 + 1.0 / 256.0;
+const NEG: f64 = 1.0 - 1.0 / 256.0;
+
+pub const FLUHRER_MCGREW: [DigraphBias; 12] = [
+    DigraphBias {
+        name: "(0, 0) i = 1",
+        pattern: |i| (i == 1).then_some((0, 0)),
+        relative: 1.0 + 1.0 / 512.0,
+    },
+    DigraphBias {
+        name: "(0, 0) i != 1, 255",
+        pattern: |i| (i != 1 && i != 255).then_some((0, 0)),
+        relative: POS,
+    },
+    DigraphBias {
+        name: "(0, 1) i != 0, 1",
+        pattern: |i| (i != 0 && i != 1).then_some((0, 1)),
+        relative: POS,
+    },
+    DigraphBias {
+        name: "(0, i + 1) i != 0, 255",
+        pattern: |i| (i != 0 && i != 255).then_some((0, i.wrapping_add(1))),
+        relative: POS,
+    },
+    DigraphBias {
+        name: "(i + 1, 255) i != 254",
+        pattern: |i| (i != 254).then_some((i.wrapping_add(1), 255)),
+        relative: POS,
+    },
+    DigraphBias {
+        name: "(255, i + 1) i != 1, 254",
+        pattern: |i| (i != 1 && i != 254).then_some((255, i.wrapping_add(1))),
+        relative: POS,
+    },
+    DigraphBias {
+        name: "(255, i + 2) i != 0, 253, 254, 255",
+        pattern: |i| (![0, 253, 254, 255].contains(&i)).then_some((255, i.wrapping_add(2))),
+        relative: POS,
+    },
+    DigraphBias {
+        name: "(255, 0) i = 254",
+        pattern: |i| (i == 254).then_some((255, 0)),
+        relative: POS,
+    },
+    DigraphBias {
+        name: "(255, 1) i = 255",
+        pattern: |i| (i == 255).then_some((255, 1)),
+        relative: POS,
+    },
+    DigraphBias {
+        name: "(255, 2) i = 0, 1",
+        pattern: |i| (i == 0 || i == 1).then_some((255, 2)),
+        relative: POS,
+    },
+    DigraphBias {
+        name: "(129, 129) i = 2",
+        pattern: |i| (i == 2).then_some((129, 129)),
+        relative: POS,
+    },
+    DigraphBias {
+        name: "(255, 255) i != 254",
+        pattern: |i| (i != 254).then_some((255, 255)),
+        relative: NEG,
+    },
+];
+
+/// Observed and expected counts for one digraph bias
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DigraphCount {
+    pub observed: u64,
+    /// Count expected from a random stream
+    pub uniform: f64,
+    /// Count expected from RC4 according to Fluhrer and McGrew
+    pub biased: f64,
+}
+
+/// Count the occurrences of each Fluhrer-McGrew digraph over n consecutive pairs of the keystream. The biases are
+/// about 2^-8 relative to a probability of 2^-16 so around 2^30 pairs are needed before they clearly show.
+pub fn fluhrer_mcgrew_counts(rc4: &mut Rc4, n_pairs: usize) -> Vec<DigraphCount> {
+    let mut out: Vec<DigraphCount> = FLUHRER_MCGREW
+        .iter()
+        .map(|_| DigraphCount {
+            observed: 0,
+            uniform: 0.0,
+            biased: 0.0,
+        })
+        .collect();
+
+    let mut first = rc4.next_byte();
+    for _ in 0..n_pairs {
+        // The index used to output the first byte of the pair
+        let i = rc4.i;
+        let second = rc4.next_byte();
+        for (bias, count) in FLUHRER_MCGREW.iter().zip(out.iter_mut()) {
+            if let Some(pair) = (bias.pattern)(i) {
+                count.uniform += 1.0 / 65536.0;
+                count.biased += bias.relative / 65536.0;
+                if pair == (first, second) {
+                    count.observed += 1;
+                }
+            }
+        }
+        first = second;
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn mantin_shamir() {
+        let mut rng = StdRng::seed_from_u64(0x2b);
+        let rate = second_byte_zero_rate(&mut rng, 16, 50000);
+        assert!(rate > 1.5 / 256.0);
+    }
+
+    #[test]
+    fn digraph_counts() {
+        let mut rc4 = Rc4::default();
+        rc4.ksa(b"Key");
+        let counts = fluhrer_mcgrew_counts(&mut rc4, 1 << 16);
+        // Over 2^16 pairs a digraph that applies for 254 of the 256 values of i is expected about once
+        assert!((counts[2].uniform - 254.0 / 256.0).abs() < 0.01);
+        assert!(counts[2].biased > counts[2].uniform);
+        assert!(counts[11].biased < counts[11].uniform);
+    }
+}
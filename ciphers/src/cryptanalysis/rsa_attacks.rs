@@ -0,0 +1,423 @@
+use crate::digital::public_key_ciphers::rsa::Rsa;
+use num::{BigInt, BigUint, Integer, One, Signed, ToPrimitive, Zero};
+use utils::{
+    errors::GeneralError,
+    math_functions::{crt, mul_inv},
+};
+
+/// Once any factor of the modulus is known the private key follows immediately
+pub fn private_key_from_factor(n: &BigUint, e: &BigUint, p: &BigUint) -> Result<Rsa, GeneralError> {
+    if p.is_zero() || p.is_one() || p == n || !(n % p).is_zero() {
+        return Err(GeneralError::key("p is not a proper factor of n"));
+    }
+    let q = n / p;
+    let mut rsa = Rsa {
+        e: e.clone(),
+        ..Default::default()
+    };
+    rsa.set_key(p, &q)?;
+    Ok(rsa)
+}
+
+// Terms of the continued fraction of a/b
+fn continued_fraction(a: &BigUint, b: &BigUint) -> Vec<BigUint> {
+    let (mut a, mut b) = (a.clone(), b.clone());
+    let mut out = Vec::new();
+    while !b.is_zero() {
+        let (q, r) = a.div_rem(&b);
+        out.push(q);
+        a = b;
+        b = r;
+    }
+    out
+}
+
+fn exact_sqrt(n: &BigUint) -> Option<BigUint> {
+    let r = n.sqrt();
+    (&r * &r == *n).then_some(r)
+}
+
+/// Wiener's attack. When d < n^(1/4)/3 the fraction k/d, where ed = 1 + k*phi(n), is one of the convergents of the
+/// continued fraction of e/n. Each convergent gives a guess at phi(n), and a correct guess makes p and q the roots of
+/// x^2 - (n - phi(n) + 1)x + n.
+pub fn wiener(n: &BigUint, e: &BigUint) -> Option<Rsa> {
+    let (mut h0, mut h1) = (BigUint::zero(), BigUint::one());
+    let (mut k0, mut k1) = (BigUint::one(), BigUint::zero());
+    for a in continued_fraction(e, n) {
+        let h = &a * &h1 + &h0;
+        let k = &a * &k1 + &k0;
+        (h0, h1) = (h1, h.clone());
+        (k0, k1) = (k1, k.clone());
+
+        // The convergent h/k is a guess for k/d in the equation above
+        if h.is_zero() {
+            continue;
+        }
+        let ed = e * &k;
+        if ed.is_zero() || !((&ed - 1_u32) % &h).is_zero() {
+            continue;
+        }
+        let phi = (ed - 1_u32) / &h;
+        if phi >= *n {
+            continue;
+        }
+        let s = n - &phi + 1_u32;
+        let s2 = &s * &s;
+        let four_n = n * 4_u32;
+        if s2 < four_n {
+            continue;
+        }
+        if let Some(root) = exact_sqrt(&(s2 - four_n)) {
+            if let Ok(rsa) = private_key_from_factor(n, e, &((s + root) / 2_u32)) {
+                return Some(rsa);
+            }
+        }
+    }
+    None
+}
+
+/// Håstad's broadcast attack. If the same message is encrypted with exponent e under e different moduli then the
+/// Chinese remainder theorem gives m^e modulo the product of the moduli. Since m is smaller than every modulus m^e is
+/// smaller than their product so an ordinary integer root gives m. Each ciphertext is paired with the public key that
+/// produced it.
+pub fn hastad_broadcast(keys: &[Rsa], ciphertexts: &[Vec<u8>]) -> Result<Vec<u8>, GeneralError> {
+    let e = keys
+        .first()
+        .ok_or(GeneralError::input("no public keys were given"))?
+        .e
+        .clone();
+    if keys.iter().any(|rsa| rsa.e != e) {
+        return Err(GeneralError::key("every key must use the same exponent"));
+    }
+    let e = e
+        .to_u32()
+        .ok_or(GeneralError::key("the exponent is far too large"))?;
+    if ciphertexts.len() != keys.len() || keys.len() < e as usize {
+        return Err(GeneralError::input(
+            "at least e ciphertexts and one key for each are needed",
+        ));
+    }
+    let moduli: Vec<BigUint> = keys.iter().map(|rsa| rsa.n.clone()).collect();
+    let ciphertexts: Vec<BigUint> = ciphertexts
+        .iter()
+        .map(|c| BigUint::from_bytes_be(c))
+        .collect();
+    let x = crt(&ciphertexts, &moduli).ok_or(GeneralError::input(
+        "moduli are not pairwise coprime, try a batch GCD",
+    ))?;
+    let m = x.nth_root(e);
+    if m.pow(e) != x {
+        return Err(GeneralError::general(
+            "the combined ciphertext is not a perfect power",
+        ));
+    }
+    Ok(m.to_bytes_be())
+}
+
+// x^k mod n for an exponent that may be negative
+fn signed_pow(x: &BigUint, k: &BigInt, n: &BigUint) -> Option<BigUint> {
+    let exp = k.abs().to_biguint().unwrap();
+    if k.is_negative() {
+        let inv = mul_inv(x, n)?;
+        Some(inv.to_biguint()?.modpow(&exp, n))
+    } else {
+        Some(x.modpow(&exp, n))
+    }
+}
+
+/// The common modulus attack. If the same message is encrypted under one modulus with coprime exponents e1 and e2
+/// then a*e1 + b*e2 = 1 for some a and b, and c1^a * c2^b = m.
+pub fn common_modulus(
+    first: &Rsa,
+    c1: &[u8],
+    second: &Rsa,
+    c2: &[u8],
+) -> Result<Vec<u8>, GeneralError> {
+    if first.n != second.n {
+        return Err(GeneralError::key("the keys must share a modulus"));
+    }
+    let n = &first.n;
+    let egcd = BigInt::from(first.e.clone()).extended_gcd(&BigInt::from(second.e.clone()));
+    if !egcd.gcd.is_one() {
+        return Err(GeneralError::input("exponents must be coprime"));
+    }
+    let a = signed_pow(&BigUint::from_bytes_be(c1), &egcd.x, n);
+    let b = signed_pow(&BigUint::from_bytes_be(c2), &egcd.y, n);
+    match (a, b) {
+        (Some(a), Some(b)) => Ok(((a * b) % n).to_bytes_be()),
+        _ => Err(GeneralError::input(
+            "a ciphertext is not invertible, so it shares a factor with n",
+        )),
+    }
+}
+
+/// Bernstein's batch GCD. A product tree of all the moduli is built, then reduced back down so that each modulus n
+/// ends up with the product of all the others modulo n, from which the GCD with the other moduli is found in a single
+/// step. Far faster than comparing every pair when there are many keys.
+pub fn batch_gcd(moduli: &[BigUint]) -> Vec<BigUint> {
+    let mut tree = vec![moduli.to_vec()];
+    while tree.last().unwrap().len() > 1 {
+        let level = tree
+            .last()
+            .unwrap()
+            .chunks(2)
+            .map(|c| c.iter().product())
+            .collect();
+        tree.push(level);
+    }
+
+    let mut remainders = tree.pop().unwrap();
+    while let Some(level) = tree.pop() {
+        remainders = level
+            .iter()
+            .enumerate()
+            .map(|(i, x)| &remainders[i / 2] % (x * x))
+            .collect();
+    }
+    remainders
+        .iter()
+        .zip(moduli)
+        .map(|(r, n)| (r / n).gcd(n))
+        .collect()
+}
+
+/// Recover the private key for every modulus that shares exactly one prime with another modulus. A modulus that
+/// shares both of its primes cannot be split this way and gives None.
+pub fn shared_prime_keys(moduli: &[BigUint], e: &BigUint) -> Vec<Option<Rsa>> {
+    batch_gcd(moduli)
+        .iter()
+        .zip(moduli)
+        .map(|(g, n)| private_key_from_factor(n, e, g).ok())
+        .collect()
+}
+
+/// Fermat's method writes n = a^2 - b^2 = (a - b)(a + b), searching upward from a = ceil(sqrt(n)). When p and q are
+/// close together b is small and the search ends almost immediately.
+pub fn fermat(n: &BigUint, max_steps: usize) -> Option<BigUint> {
+    if n.is_even() {
+        return Some(BigUint::from(2_u32));
+    }
+    let mut a = n.sqrt();
+    if &a * &a < *n {
+        a += 1_u32;
+    }
+    for _ in 0..max_steps {
+        if let Some(b) = exact_sqrt(&(&a * &a - n)) {
+            let p = &a - b;
+            return (!p.is_one()).then_some(p);
+        }
+        a += 1_u32;
+    }
+    None
+}
+
+/// Pollard's p-1 method. The value a = 2^(bound!) mod n is 1 modulo any prime p where p-1 has no prime power factor
+/// greater than the bound, so the GCD of a-1 and n reveals p.
+pub fn pollard_p_minus_1(n: &BigUint, bound: u32) -> Option<BigUint> {
+    let mut a = BigUint::from(2_u32);
+    for j in 2..=bound {
+        a = a.modpow(&BigUint::from(j), n);
+        if j % 64 == 0 || j == bound {
+            let g = (&a + n - 1_u32).gcd(n);
+            if g == *n {
+                return None;
+            }
+            if !g.is_one() {
+                return Some(g);
+            }
+        }
+    }
+    None
+}
+
+/// Pollard's rho method. The sequence x -> x^2 + c taken modulo a hidden prime p enters a cycle after about sqrt(p)
+/// steps, which Floyd's cycle finding detects as a nontrivial GCD with n. If the cycle closes modulo n as well a
+/// different c is tried.
+pub fn pollard_rho(n: &BigUint, max_steps: usize) -> Option<BigUint> {
+    if n.is_even() {
+        return Some(BigUint::from(2_u32));
+    }
+    let mut steps = 0;
+    for c in 1_u32..=16 {
+        let f = |x: &BigUint| (x * x + c) % n;
+        let mut x = BigUint::from(2_u32);
+        let mut y = x.clone();
+        loop {
+            x = f(&x);
+            y = f(&f(&y));
+            steps += 1;
+            if steps > max_steps {
+                return None;
+            }
+            let diff = if x > y { &x - &y } else { &y - &x };
+            let g = diff.gcd(n);
+            if g == *n {
+                break;
+            }
+            if !g.is_one() {
+                return Some(g);
+            }
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+    use utils::primality::{is_probable_prime, random_biguint_bits, random_prime};
+
+    const MESSAGE: &[u8] = b"Attack at dawn";
+
+    #[test]
+    fn wiener_small_d() {
+        let mut rng = StdRng::seed_from_u64(42);
+        let p = random_prime(256, &mut rng);
+        let q = random_prime(256, &mut rng);
+        let n = &p * &q;
+        let phi = (&p - 1_u32) * (&q - 1_u32);
+        let (d, e) = loop {
+            let d = random_biguint_bits(100, &mut rng) | BigUint::one();
+            if let Some(e) = mul_inv(&d, &phi) {
+                break (d, e.to_biguint().unwrap());
+            }
+        };
+
+        let rsa = wiener(&n, &e).unwrap();
+        assert_eq!(n, rsa.n);
+        let m = BigUint::from_bytes_be(MESSAGE);
+        let c = m.modpow(&e, &n);
+        assert_eq!(c.modpow(&d, &n), rsa.private_op(&c).unwrap());
+
+        // An ordinary key has a large d and is not vulnerable
+        let mut rsa = Rsa::default();
+        rsa.generate_key(512, &mut rng).unwrap();
+        assert!(wiener(&rsa.n, &rsa.e).is_none());
+    }
+
+    #[test]
+    fn hastad() {
+        let mut rng = StdRng::seed_from_u64(42);
+        let mut keys = Vec::new();
+        let mut ciphertexts = Vec::new();
+        for _ in 0..3 {
+            let mut rsa = Rsa {
+                e: BigUint::from(3_u32),
+                ..Default::default()
+            };
+            rsa.generate_key(512, &mut rng).unwrap();
+            ciphertexts.push(rsa.encrypt_bytes(MESSAGE).unwrap());
+            keys.push(rsa);
+        }
+        assert_eq!(
+            MESSAGE.to_vec(),
+            hastad_broadcast(&keys, &ciphertexts).unwrap()
+        );
+        assert!(hastad_broadcast(&keys[..2], &ciphertexts[..2]).is_err());
+    }
+
+    #[test]
+    fn common_modulus_attack() {
+        let mut rng = StdRng::seed_from_u64(42);
+        let mut first = Rsa::default();
+        first.generate_key(512, &mut rng).unwrap();
+        // A second key pair sharing the modulus, as when one organization hands out keys from a single modulus
+        let mut second = Rsa {
+            e: BigUint::from(257_u32),
+            ..Default::default()
+        };
+        second.set_key(&first.p, &first.q).unwrap();
+        let c1 = first.encrypt_bytes(MESSAGE).unwrap();
+        let c2 = second.encrypt_bytes(MESSAGE).unwrap();
+        assert_eq!(
+            MESSAGE.to_vec(),
+            common_modulus(&first, &c1, &second, &c2).unwrap()
+        );
+    }
+
+    #[test]
+    fn batch() {
+        let mut rng = StdRng::seed_from_u64(42);
+        let primes: Vec<BigUint> = (0..7).map(|_| random_prime(128, &mut rng)).collect();
+        // The second and fourth moduli share a prime
+        let moduli = vec![
+            &primes[0] * &primes[1],
+            &primes[2] * &primes[3],
+            &primes[4] * &primes[5],
+            &primes[2] * &primes[6],
+        ];
+        let e = BigUint::from(65537_u32);
+        let gcds = batch_gcd(&moduli);
+        assert_eq!(
+            vec![
+                BigUint::one(),
+                primes[2].clone(),
+                BigUint::one(),
+                primes[2].clone()
+            ],
+            gcds
+        );
+        let keys = shared_prime_keys(&moduli, &e);
+        assert!(keys[0].is_none() && keys[2].is_none());
+        for i in [1, 3] {
+            let rsa = keys[i].as_ref().unwrap();
+            assert_eq!(moduli[i], rsa.n);
+            let m = BigUint::from_bytes_be(MESSAGE);
+            assert_eq!(m, rsa.private_op(&rsa.public_op(&m).unwrap()).unwrap());
+        }
+    }
+
+    #[test]
+    fn close_primes() {
+        let mut rng = StdRng::seed_from_u64(42);
+        let p = random_prime(256, &mut rng);
+        let mut q = &p + 2_u32 + rng.gen_range(0..1_000_000_u32) * 2_u32;
+        while !is_probable_prime(&q, 40, &mut rng) {
+            q += 2_u32;
+        }
+        let n = &p * &q;
+        let factor = fermat(&n, 10).unwrap();
+        assert!(factor == p || factor == q);
+        let rsa = private_key_from_factor(&n, &BigUint::from(65537_u32), &factor).unwrap();
+        assert_eq!(n, rsa.n);
+
+        let distant = &p * random_prime(256, &mut rng);
+        assert!(fermat(&distant, 1000).is_none());
+    }
+
+    #[test]
+    fn smooth_prime() {
+        let mut rng = StdRng::seed_from_u64(42);
+        // p - 1 is a product of distinct primes below 500
+        let small: Vec<u32> = (3..500_u32)
+            .filter(|x| is_probable_prime(&BigUint::from(*x), 10, &mut rng))
+            .collect();
+        let p = loop {
+            let mut p = BigUint::from(2_u32);
+            for x in small.choose_multiple(&mut rng, small.len()) {
+                if p.bits() >= 256 {
+                    break;
+                }
+                p *= *x;
+            }
+            p += 1_u32;
+            if is_probable_prime(&p, 40, &mut rng) {
+                break p;
+            }
+        };
+        let q = random_prime(256, &mut rng);
+        let n = &p * &q;
+        assert_eq!(Some(p), pollard_p_minus_1(&n, 500));
+    }
+
+    #[test]
+    fn rho() {
+        let mut rng = StdRng::seed_from_u64(42);
+        let p = random_prime(32, &mut rng);
+        let q = random_prime(256, &mut rng);
+        let n = &p * &q;
+        assert_eq!(Some(p), pollard_rho(&n, 1_000_000));
+    }
+}
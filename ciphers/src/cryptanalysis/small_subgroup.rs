@@ -0,0 +1,285 @@
+use crate::digital::public_key_ciphers::diffie_hellman::DiffieHellman;
+use crypto_bigint::U256;
+use hashers::{
+    hmac::{Hmac, HmacVariant},
+    traits::StatefulHasher,
+};
+use num::{BigUint, One, ToPrimitive};
+use rand::Rng;
+use std::collections::BTreeSet;
+use utils::{
+    elliptic_curves::{EcPoint, FiniteEllipticCurve},
+    errors::GeneralError,
+    math_functions::crt,
+    primality::{is_probable_prime, prime_factorization_map, random_biguint_below, random_prime},
+};
+
+// After completing a key exchange the servers below prove they know the shared secret by sending a MAC of a fixed
+// message under it, as many protocols do. That is all an attacker needs to test guesses of the secret.
+fn confirmation_tag(shared_secret: &[u8]) -> Vec<u8> {
+    let mut h = Hmac::init(HmacVariant::Sha256, shared_secret);
+    h.update(b"key confirmation");
+    h.finalize()
+}
+
+/// Parameters of the form p = 2*q*r1*r2*...*rk + 1 where q is a large prime and the ri are small distinct odd primes
+/// whose product exceeds q. The generator has order q. The multiplicative group has many small subgroups, which is
+/// harmless only if every public key received is checked to lie in the subgroup of order q.
+pub fn weak_dh_parameters<R: Rng>(q_bits: u64, rng: &mut R) -> (DiffieHellman, BigUint, Vec<u64>) {
+    let mut small_factors = Vec::new();
+    let mut product = BigUint::one();
+    let mut r = 3_u64;
+    while product.bits() <= q_bits {
+        if is_probable_prime(&BigUint::from(r), 10, rng) {
+            small_factors.push(r);
+            product *= r;
+        }
+        r += 2;
+    }
+
+    loop {
+        let q = random_prime(q_bits, rng);
+        let p = &q * &product * 2_u32 + 1_u32;
+        if !is_probable_prime(&p, 20, rng) {
+            continue;
+        }
+        let g = random_biguint_below(&p, rng).modpow(&((&p - 1_u32) / &q), &p);
+        if g.is_one() {
+            continue;
+        }
+        let dh = DiffieHellman {
+            generator: g,
+            modulus: p,
+            order: None,
+            ..Default::default()
+        };
+        return (dh, q, small_factors);
+    }
+}
+
+/// A server with a static Diffie-Hellman key that accepts any public key the Diffie-Hellman implementation allows and
+/// answers with a key confirmation tag.
+pub fn dh_server<'a>(
+    dh: &'a DiffieHellman,
+    private_key: &'a BigUint,
+) -> impl Fn(&BigUint) -> Result<Vec<u8>, GeneralError> + 'a {
+    move |peer| Ok(confirmation_tag(&dh.shared_secret(private_key, peer)?))
+}
+
+/// The small subgroup confinement attack of Lim and Lee. Sending an element h of small prime order r confines the
+/// shared secret h^x to only r possible values, which the attacker tries one at a time against the confirmation tag
+/// to learn x mod r. Once the product of the small primes used exceeds q the Chinese remainder theorem gives x.
+pub fn small_subgroup_attack<F, R>(
+    dh: &DiffieHellman,
+    q: &BigUint,
+    small_factors: &[u64],
+    server: &F,
+    rng: &mut R,
+) -> Result<BigUint, GeneralError>
+where
+    F: Fn(&BigUint) -> Result<Vec<u8>, GeneralError>,
+    R: Rng,
+{
+    let p = &dh.modulus;
+    let len = dh.element_len();
+    let mut residues = Vec::new();
+    let mut moduli = Vec::new();
+    let mut product = BigUint::one();
+
+    for r in small_factors {
+        if &product > q {
+            break;
+        }
+        let r_big = BigUint::from(*r);
+        // An element of order r
+        let h = loop {
+            let a = random_biguint_below(&(p - 3_u32), rng) + 2_u32;
+            let h = a.modpow(&((p - 1_u32) / &r_big), p);
+            if !h.is_one() {
+                break h;
+            }
+        };
+        let tag = server(&h)?;
+
+        let mut candidate = BigUint::one();
+        let mut found = None;
+        for i in 0..*r {
+            let bytes = candidate.to_bytes_be();
+            let mut secret = vec![0; len - bytes.len()];
+            secret.extend(bytes);
+            if confirmation_tag(&secret) == tag {
+                found = Some(i);
+                break;
+            }
+            candidate = candidate * &h % p;
+        }
+        let i = found.ok_or(GeneralError::general("no element of the subgroup matched"))?;
+        residues.push(BigUint::from(i));
+        moduli.push(r_big);
+        product *= *r;
+    }
+
+    if &product <= q {
+        return Err(GeneralError::general(
+            "the small subgroups do not cover the size of the private key",
+        ));
+    }
+    crt(&residues, &moduli).ok_or(GeneralError::general("moduli are not coprime"))
+}
+
+/// A server with a static ECDH key that multiplies whatever point it receives by its private key, without checking
+/// that the point is on the curve, and answers with a key confirmation tag over the resulting point.
+pub fn ecdh_server<'a>(
+    curve: &'a FiniteEllipticCurve,
+    private_key: &'a U256,
+) -> impl Fn(&EcPoint) -> Vec<u8> + 'a {
+    move |peer| confirmation_tag(&curve.encode_point(&curve.scalar_mul(peer, private_key), false))
+}
+
+/// The invalid curve attack. The formulas for adding points never use the coefficient b, so a server that does not
+/// check points will happily compute on y^2 = x^3 + ax + b' for any b'. The attacker counts the points on such curves
+/// until they find ones with small prime factors r in their order, sends points of order r, and learns the private
+/// key modulo each r from the confirmation tags. The curve's prime must be small enough for the points to be
+/// counted one x at a time, a real attack would use Schoof's algorithm.
+pub fn invalid_curve_attack<F, R>(
+    curve: &FiniteEllipticCurve,
+    order: u64,
+    server: &F,
+    rng: &mut R,
+) -> Option<u64>
+where
+    F: Fn(&EcPoint) -> Vec<u8>,
+    R: Rng,
+{
+    // Only subgroups this small are searched exhaustively
+    const MAX_FACTOR: u64 = 4096;
+
+    let p = curve.m.as_words()[0];
+    let a = curve.a.as_words()[0];
+    let b = curve.b.as_words()[0];
+    let mul = |x: u64, y: u64| (x as u128 * y as u128 % p as u128) as u64;
+    let mut is_square = vec![false; p as usize];
+    for y in 0..p {
+        is_square[mul(y, y) as usize] = true;
+    }
+
+    let mut used = BTreeSet::new();
+    let mut residues = Vec::new();
+    let mut moduli = Vec::new();
+    let mut product = 1_u128;
+
+    for b_invalid in 1..p {
+        if product > order as u128 {
+            break;
+        }
+        // Skip the real curve and singular curves
+        let discriminant = (4 * mul(mul(a, a), a) + 27 * mul(b_invalid, b_invalid)) % p;
+        if b_invalid == b || discriminant == 0 {
+            continue;
+        }
+
+        // One point at infinity plus two points for each x where x^3 + ax + b' is a nonzero square
+        let mut points = 1_u64;
+        for x in 0..p {
+            let rhs = (mul(mul(x, x), x) + mul(a, x) + b_invalid) % p;
+            if rhs == 0 {
+                points += 1;
+            } else if is_square[rhs as usize] {
+                points += 2;
+            }
+        }
+
+        let invalid = FiniteEllipticCurve::from_u64(a, b_invalid, p);
+        for r in prime_factorization_map(points).into_keys() {
+            if r > MAX_FACTOR || used.contains(&r) {
+                continue;
+            }
+            // Multiplying a random point by the cofactor gives a point of order r unless it gives infinity
+            let point = loop {
+                let x = U256::from_u64(rng.gen_range(0..p));
+                if let Some(pt) = invalid.lift_x(&x, rng.gen()) {
+                    let q = invalid.scalar_mul(&pt, &U256::from_u64(points / r));
+                    if !q.is_inf() {
+                        break q;
+                    }
+                }
+            };
+            let tag = server(&point);
+
+            let mut multiple = EcPoint::point_at_inf();
+            for i in 0..r {
+                if confirmation_tag(&invalid.encode_point(&multiple, false)) == tag {
+                    residues.push(BigUint::from(i));
+                    moduli.push(BigUint::from(r));
+                    used.insert(r);
+                    product *= r as u128;
+                    break;
+                }
+                multiple = invalid.add(&multiple, &point);
+            }
+        }
+    }
+
+    crt(&residues, &moduli)?.to_u64()
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use rand::{rngs::StdRng, SeedableRng};
+    use std::cell::RefCell;
+    use utils::elliptic_curves::EcDomain;
+
+    #[test]
+    fn lim_lee() {
+        let mut rng = StdRng::seed_from_u64(11);
+        let (mut dh, q, small_factors) = weak_dh_parameters(48, &mut rng);
+        let x = random_biguint_below(&q, &mut rng);
+
+        {
+            let server = dh_server(&dh, &x);
+            let recovered =
+                small_subgroup_attack(&dh, &q, &small_factors, &server, &mut rng).unwrap();
+            assert_eq!(x, recovered);
+        }
+
+        // Checking that public keys lie in the subgroup of order q stops the attack at the first query
+        dh.order = Some(q.clone());
+        let server = dh_server(&dh, &x);
+        assert!(small_subgroup_attack(&dh, &q, &small_factors, &server, &mut rng).is_err());
+    }
+
+    #[test]
+    fn invalid_curve() {
+        let mut rng = StdRng::seed_from_u64(5);
+        // y^2 = x^3 - 3x + 16 over a 20-bit prime field with a prime number of points
+        let curve = FiniteEllipticCurve::from_u64(1048580, 16, 1048583);
+        let order = 1048387;
+        let d = rng.gen_range(1..order);
+
+        let private_key = U256::from_u64(d);
+        let server = ecdh_server(&curve, &private_key);
+        assert_eq!(
+            Some(d),
+            invalid_curve_attack(&curve, order, &server, &mut rng)
+        );
+
+        // None of the points used by the attack are valid public keys
+        let domain = EcDomain {
+            curve: curve.clone(),
+            generator: EcPoint::from_u64(2, 328603),
+            order: U256::from_u64(order),
+            cofactor: 1,
+        };
+        let sent = RefCell::new(Vec::new());
+        let recording_server = |pt: &EcPoint| {
+            sent.borrow_mut().push(pt.clone());
+            server(pt)
+        };
+        invalid_curve_attack(&curve, order, &recording_server, &mut rng);
+        let sent = sent.into_inner();
+        assert!(!sent.is_empty());
+        assert!(sent.iter().all(|pt| !domain.is_valid_public_key(pt)));
+    }
+}
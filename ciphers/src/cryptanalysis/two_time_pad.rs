@@ -0,0 +1,391 @@
+use std::{collections::HashMap, sync::LazyLock};
+use utils::errors::GeneralError;
+
+// Log probabilities, base 2, of English letter n-grams. The files store them scaled by 100.
+fn load_ngrams<const N: usize>(csv: &str) -> HashMap<[u8; N], f64> {
+    csv.lines()
+        .skip(1)
+        .filter_map(|line| {
+            let mut fields = line.split(',');
+            let gram: [u8; N] = fields.next()?.as_bytes().try_into().ok()?;
+            let logprob: f64 = fields.nth(1)?.trim().parse().ok()?;
+            Some((gram, logprob / 100.0))
+        })
+        .collect()
+}
+
+static BIGRAMS: LazyLock<HashMap<[u8; 2], f64>> =
+    LazyLock::new(|| load_ngrams(include_str!("../../../attacks/2_gram_scores.csv")));
+static TRIGRAMS: LazyLock<HashMap<[u8; 3], f64>> =
+    LazyLock::new(|| load_ngrams(include_str!("../../../attacks/3_gram_scores.csv")));
+
+// Used for n-grams that do not appear in the tables
+const NGRAM_FLOOR: f64 = -24.0;
+
+// Approximate frequencies of the letters in English text, with spaces counted as characters
+const LETTER_FREQUENCIES: [f64; 26] = [
+    0.0653, 0.0126, 0.0223, 0.0328, 0.1027, 0.0198, 0.0162, 0.0498, 0.0567, 0.0010, 0.0056, 0.0332,
+    0.0203, 0.0571, 0.0616, 0.0150, 0.0008, 0.0499, 0.0532, 0.0752, 0.0228, 0.0080, 0.0170, 0.0014,
+    0.0143, 0.0005,
+];
+
+// Fraction of the characters that are letters
+const LETTER_TOTAL: f64 = 0.8152;
+
+fn letter_frequency(b: u8) -> f64 {
+    LETTER_FREQUENCIES[(b.to_ascii_lowercase() - b'a') as usize]
+}
+
+/// Log probability, base 2, of a single byte of English text with no context
+pub fn byte_log_probability(b: u8) -> f64 {
+    let p = match b {
+        b' ' => 0.16,
+        b'a'..=b'z' => letter_frequency(b),
+        b'A'..=b'Z' => letter_frequency(b) * 0.1,
+        b'.' | b',' | b'\'' | b'\n' => 0.005,
+        _ if b.is_ascii_graphic() => 0.0005,
+        _ => 0.000001,
+    };
+    p.log2()
+}
+
+/// Log probability, base 2, of the byte c following a and b in English text. Runs of letters are scored with bigram
+/// and trigram statistics and everything else falls back to single byte frequencies.
+pub fn next_byte_score(a: Option<u8>, b: Option<u8>, c: u8) -> f64 {
+    let is_letter = |x: Option<u8>| x.is_some_and(|x| x.is_ascii_alphabetic());
+    if !c.is_ascii_alphabetic() || !is_letter(b) {
+        return byte_log_probability(c);
+    }
+
+    let b = b.unwrap();
+    let upper = |x: u8| x.to_ascii_uppercase();
+    // A letter following a letter, with a small allowance for the chance that the word ends instead
+    let mut score = LETTER_TOTAL.log2();
+    if is_letter(a) {
+        let a = a.unwrap();
+        let tri = TRIGRAMS
+            .get(&[upper(a), upper(b), upper(c)])
+            .unwrap_or(&NGRAM_FLOOR);
+        let bi = BIGRAMS.get(&[upper(a), upper(b)]).unwrap_or(&NGRAM_FLOOR);
+        score += tri - bi;
+    } else {
+        let bi = BIGRAMS.get(&[upper(b), upper(c)]).unwrap_or(&NGRAM_FLOOR);
+        score += bi - (letter_frequency(b) / LETTER_TOTAL).log2();
+    }
+    // Capitals rarely appear inside words
+    if c.is_ascii_uppercase() && b.is_ascii_lowercase() {
+        score -= 5.0;
+    }
+    score
+}
+
+/// Average score per byte of a fragment of English text
+pub fn score_text(text: &[u8]) -> f64 {
+    if text.is_empty() {
+        return 0.0;
+    }
+    let total: f64 = (0..text.len())
+        .map(|i| {
+            let a = i.checked_sub(2).map(|j| text[j]);
+            let b = i.checked_sub(1).map(|j| text[j]);
+            next_byte_score(a, b, text[i])
+        })
+        .sum();
+    total / text.len() as f64
+}
+
+pub fn xor_bytes(a: &[u8], b: &[u8]) -> Vec<u8> {
+    a.iter().zip(b).map(|(x, y)| x ^ y).collect()
+}
+
+/// The text revealed by placing a crib at an offset, along with its score
+#[derive(Debug, Clone, PartialEq)]
+pub struct CribPlacement {
+    pub offset: usize,
+    pub score: f64,
+    pub revealed: Vec<Vec<u8>>,
+}
+
+fn sort_placements(placements: &mut [CribPlacement]) {
+    placements.sort_by(|a, b| b.score.total_cmp(&a.score));
+}
+
+/// Drag a crib across the XOR of two ciphertexts that share a keystream. At each offset the XOR with the crib is the
+/// other plaintext if the guess is correct, so the placements are returned from the most to the least English.
+pub fn crib_drag(xored: &[u8], crib: &[u8]) -> Vec<CribPlacement> {
+    let mut out: Vec<CribPlacement> = (0..=xored.len().saturating_sub(crib.len()))
+        .map(|offset| {
+            let revealed = xor_bytes(&xored[offset..], crib);
+            CribPlacement {
+                offset,
+                score: score_text(&revealed),
+                revealed: vec![revealed],
+            }
+        })
+        .collect();
+    sort_placements(&mut out);
+    out
+}
+
+/// Several ciphertexts encrypted with the same keystream, such as messages sent under a reused stream cipher key or
+/// CTR nonce, together with whatever has been learned of the keystream. Knowing a byte of any plaintext gives the
+/// keystream byte and so the bytes of all the others at that position.
+#[derive(Debug, Clone)]
+pub struct ReusedKeystream {
+    pub ciphertexts: Vec<Vec<u8>>,
+    pub keystream: Vec<Option<u8>>,
+}
+
+impl ReusedKeystream {
+    pub fn new(ciphertexts: Vec<Vec<u8>>) -> Self {
+        let len = ciphertexts.iter().map(|c| c.len()).max().unwrap_or(0);
+        Self {
+            ciphertexts,
+            keystream: vec![None; len],
+        }
+    }
+
+    pub fn plaintext(&self, index: usize) -> Vec<Option<u8>> {
+        self.ciphertexts[index]
+            .iter()
+            .zip(&self.keystream)
+            .map(|(c, k)| k.map(|k| c ^ k))
+            .collect()
+    }
+
+    /// Display a plaintext with unknown bytes shown as underscores and unprintable bytes as periods
+    pub fn plaintext_string(&self, index: usize) -> String {
+        self.plaintext(index)
+            .iter()
+            .map(|b| match b {
+                None => '_',
+                Some(b) if (0x20..0x7f).contains(b) => *b as char,
+                Some(_) => '.',
+            })
+            .collect()
+    }
+
+    /// Fix part of one plaintext, which fixes the keystream under it
+    pub fn set_plaintext(
+        &mut self,
+        index: usize,
+        offset: usize,
+        text: &[u8],
+    ) -> Result<(), GeneralError> {
+        let ciphertext = self
+            .ciphertexts
+            .get(index)
+            .ok_or(GeneralError::input("no ciphertext with that index"))?;
+        if offset + text.len() > ciphertext.len() {
+            return Err(GeneralError::input(
+                "plaintext extends past the end of the ciphertext",
+            ));
+        }
+        for (i, b) in text.iter().enumerate() {
+            self.keystream[offset + i] = Some(ciphertext[offset + i] ^ b);
+        }
+        Ok(())
+    }
+
+    /// Drag a crib across one of the ciphertexts and score the text it reveals in all of the others
+    pub fn drag_crib(&self, index: usize, crib: &[u8]) -> Vec<CribPlacement> {
+        let target = &self.ciphertexts[index];
+        let mut out = Vec::new();
+        for offset in 0..=target.len().saturating_sub(crib.len()) {
+            let keystream = xor_bytes(&target[offset..], crib);
+            let revealed: Vec<Vec<u8>> = self
+                .ciphertexts
+                .iter()
+                .enumerate()
+                .filter(|(i, c)| *i != index && c.len() > offset)
+                .map(|(_, c)| xor_bytes(&c[offset..], &keystream))
+                .collect();
+            if revealed.is_empty() {
+                continue;
+            }
+            let score = revealed.iter().map(|r| score_text(r)).sum::<f64>() / revealed.len() as f64;
+            out.push(CribPlacement {
+                offset,
+                score,
+                revealed,
+            });
+        }
+        sort_placements(&mut out);
+        out
+    }
+
+    // Total score of the bytes around position j that depend on the keystream byte there
+    fn local_score(&self, j: usize) -> f64 {
+        let mut total = 0.0;
+        for i in 0..self.ciphertexts.len() {
+            let plain = self.plaintext(i);
+            for p in j..(j + 3).min(plain.len()) {
+                let Some(c) = plain[p] else { break };
+                let a = p.checked_sub(2).and_then(|q| plain[q]);
+                let b = p.checked_sub(1).and_then(|q| plain[q]);
+                total += next_byte_score(a, b, c);
+            }
+        }
+        total
+    }
+
+    fn coverage(&self, j: usize) -> usize {
+        self.ciphertexts.iter().filter(|c| c.len() > j).count()
+    }
+
+    // The best keystream byte at an unknown position and its average score per plaintext
+    fn best_byte(&mut self, j: usize) -> (u8, f64) {
+        let mut best = (0, f64::NEG_INFINITY);
+        for k in 0..=255 {
+            self.keystream[j] = Some(k);
+            let score = self.local_score(j);
+            if score > best.1 {
+                best = (k, score);
+            }
+        }
+        self.keystream[j] = None;
+        (best.0, best.1 / self.coverage(j) as f64)
+    }
+
+    /// Extend the known stretches of keystream one byte at a time in both directions, choosing whichever byte makes
+    /// all of the plaintexts most English. Extension stops in a direction once the best choice averages below the
+    /// minimum score, which should be somewhere around -10 to -15 bits. Returns the number of bytes added.
+    pub fn extend(&mut self, min_score: f64) -> usize {
+        let mut added = 0;
+        loop {
+            let frontier: Vec<usize> = (0..self.keystream.len())
+                .filter(|j| {
+                    self.keystream[*j].is_none()
+                        && ((*j > 0 && self.keystream[j - 1].is_some())
+                            || self.keystream.get(j + 1).is_some_and(|k| k.is_some()))
+                })
+                .collect();
+
+            let mut progress = false;
+            for j in frontier {
+                let (k, score) = self.best_byte(j);
+                if score >= min_score {
+                    self.keystream[j] = Some(k);
+                    added += 1;
+                    progress = true;
+                }
+            }
+            if !progress {
+                return added;
+            }
+        }
+    }
+
+    /// Fill in every unknown byte of the keystream by frequency analysis. Each position is an independent single byte
+    /// XOR across the ciphertexts, so the byte that makes that column most like English text is chosen. This needs a
+    /// good number of ciphertexts to be reliable and tends to make mistakes where only a few of them remain.
+    pub fn frequency_analysis(&mut self) {
+        for j in 0..self.keystream.len() {
+            if self.keystream[j].is_some() {
+                continue;
+            }
+            let column: Vec<u8> = self
+                .ciphertexts
+                .iter()
+                .filter_map(|c| c.get(j).copied())
+                .collect();
+            let best = (0..=255)
+                .map(|k: u8| {
+                    let score: f64 = column.iter().map(|c| byte_log_probability(c ^ k)).sum();
+                    (k, score)
+                })
+                .max_by(|a, b| a.1.total_cmp(&b.1))
+                .unwrap();
+            self.keystream[j] = Some(best.0);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::digital::stream_ciphers::chacha::chacha::ChaCha;
+
+    const MESSAGES: [&str; 20] = [
+        "The meeting has been moved to the north gate at seven tonight.",
+        "Please bring the documents we discussed and do not be late again.",
+        "Our contact in the city says the shipment will arrive on Tuesday.",
+        "Nobody else should know about the plan until the money is moved.",
+        "I think that someone has been reading our letters for a while now.",
+        "Tell the others that the old code words are no longer safe to use.",
+        "We will need a second car and a driver who knows the back roads.",
+        "The weather was terrible so the boat stayed in the harbor all week.",
+        "If anything goes wrong you should leave the country as fast as you can.",
+        "He said that the key was hidden under the stairs in the old house.",
+        "They have started asking questions about where we were last month.",
+        "Send word as soon as you hear anything from the people in the south.",
+        "The bank opens at nine and the guards change shifts around noon.",
+        "It would be better for everyone if we waited until after the holiday.",
+        "She never trusted him and now it seems that she was right all along.",
+        "My brother will meet you at the station with the rest of the papers.",
+        "Remember to burn this letter after you have read it at least twice.",
+        "There is a small hotel near the river where we can stay for the night.",
+        "All of the equipment has to be returned before the end of the month.",
+        "Do not call the office again because the lines are being watched.",
+    ];
+
+    fn ciphertexts(n: usize) -> Vec<Vec<u8>> {
+        let cipher = ChaCha::default();
+        MESSAGES[..n]
+            .iter()
+            .map(|m| {
+                let mut bytes = m.as_bytes().to_vec();
+                cipher.encrypt_bytes(&mut bytes);
+                bytes
+            })
+            .collect()
+    }
+
+    #[test]
+    fn english_scores_higher() {
+        assert!(score_text(b"attack at dawn") > score_text(b"xqzvkj wpl rtq"));
+        assert!(score_text(b"the house") > score_text(b"tHe\x07hou;e"));
+    }
+
+    #[test]
+    fn drag_pair() {
+        let c = ciphertexts(2);
+        let xored = xor_bytes(&c[0], &c[1]);
+        let best = &crib_drag(&xored, b" has been ")[0];
+        assert_eq!(11, best.offset);
+        assert_eq!(b"g the docu".to_vec(), best.revealed[0]);
+    }
+
+    #[test]
+    fn drag_set_and_extend() {
+        let mut reused = ReusedKeystream::new(ciphertexts(6));
+        let best = reused.drag_crib(0, b" the north ")[0].offset;
+        assert_eq!(29, best);
+        reused.set_plaintext(0, best, b" the north ").unwrap();
+        reused.extend(-12.0);
+        let recovered = reused.plaintext_string(3);
+        assert!(recovered.contains("until the money"), "{}", recovered);
+    }
+
+    #[test]
+    fn set_plaintext_out_of_range() {
+        let mut reused = ReusedKeystream::new(ciphertexts(2));
+        assert!(reused.set_plaintext(2, 0, b"The").is_err());
+        assert!(reused.set_plaintext(0, 60, b"tonight.").is_err());
+        assert!(reused.keystream.iter().all(|k| k.is_none()));
+    }
+
+    #[test]
+    fn frequency() {
+        let mut reused = ReusedKeystream::new(ciphertexts(20));
+        reused.frequency_analysis();
+        let recovered = reused.plaintext_string(0);
+        let correct = recovered
+            .bytes()
+            .zip(MESSAGES[0].bytes())
+            .filter(|(a, b)| a == b)
+            .count();
+        assert!(correct > 50, "{}", recovered);
+    }
+}
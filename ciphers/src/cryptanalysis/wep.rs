@@ -0,0 +1,353 @@
+use crate::digital::stream_ciphers::rc4::Rc4;
+use rand::Rng;
+use utils::errors::GeneralError;
+
+/// The LLC/SNAP header and start of an ARP request, which begins nearly every packet an attacker can provoke and so
+/// gives the first sixteen bytes of keystream
+pub const ARP_PREFIX: [u8; 16] = [
+    0xAA, 0xAA, 0x03, 0x00, 0x00, 0x00, 0x08, 0x06, 0x00, 0x01, 0x08, 0x00, 0x06, 0x04, 0x00, 0x01,
+];
+
+// Bitwise CRC-32 used for the integrity check value
+fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = 0xffffffff_u32;
+    for byte in bytes {
+        crc ^= *byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ 0xedb88320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    !crc
+}
+
+fn rc4_with_key(key: &[u8]) -> Rc4 {
+    let mut rc4 = Rc4::default();
+    rc4.ksa(key);
+    rc4
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WepPacket {
+    pub iv: [u8; 3],
+    pub ciphertext: Vec<u8>,
+}
+
+/// Wired Equivalent Privacy. Each packet is encrypted with RC4 keyed by a 24-bit IV, which is sent in the clear,
+/// followed by the 40-bit or 104-bit secret key. A CRC-32 of the plaintext is appended before encryption.
+#[derive(Debug, Clone)]
+pub struct Wep {
+    pub key: Vec<u8>,
+}
+
+impl Wep {
+    pub fn new(key: &[u8]) -> Result<Self, GeneralError> {
+        if key.len() != 5 && key.len() != 13 {
+            return Err(GeneralError::key("WEP keys must be 5 or 13 bytes"));
+        }
+        Ok(Self { key: key.to_vec() })
+    }
+
+    pub fn per_packet_key(&self, iv: [u8; 3]) -> Vec<u8> {
+        let mut out = iv.to_vec();
+        out.extend_from_slice(&self.key);
+        out
+    }
+
+    pub fn encrypt(&self, iv: [u8; 3], plaintext: &[u8]) -> WepPacket {
+        let mut ciphertext = plaintext.to_vec();
+        ciphertext.extend_from_slice(&crc32(plaintext).to_le_bytes());
+        rc4_with_key(&self.per_packet_key(iv)).encrypt_bytes(&mut ciphertext);
+        WepPacket { iv, ciphertext }
+    }
+
+    pub fn decrypt(&self, packet: &WepPacket) -> Result<Vec<u8>, GeneralError> {
+        if packet.ciphertext.len() < 4 {
+            return Err(GeneralError::input("packet is too short to contain an ICV"));
+        }
+        let mut bytes = packet.ciphertext.clone();
+        rc4_with_key(&self.per_packet_key(packet.iv)).encrypt_bytes(&mut bytes);
+        let icv = bytes.split_off(bytes.len() - 4);
+        if crc32(&bytes).to_le_bytes() != icv[..] {
+            return Err(GeneralError::input("ICV does not match"));
+        }
+        Ok(bytes)
+    }
+
+    /// Simulate capturing ARP requests sent with random IVs
+    pub fn capture_random<R: Rng>(&self, rng: &mut R, n: usize) -> Vec<WepPacket> {
+        (0..n)
+            .map(|_| {
+                let mut plaintext = ARP_PREFIX.to_vec();
+                plaintext.extend((0..20).map(|_| rng.gen::<u8>()));
+                self.encrypt(rng.gen(), &plaintext)
+            })
+            .collect()
+    }
+
+    /// Simulate capturing ARP requests sent with every IV of the form (A + 3, 255, X) that Fluhrer, Mantin and Shamir
+    /// found to be weak for the key byte A
+    pub fn capture_weak(&self) -> Vec<WepPacket> {
+        let mut out = Vec::new();
+        for a in 0..self.key.len() {
+            for x in 0..=255 {
+                out.push(self.encrypt([a as u8 + 3, 255, x], &ARP_PREFIX));
+            }
+        }
+        out
+    }
+}
+
+// The keystream bytes that can be recovered from a packet known to start with the ARP prefix
+fn known_keystream(packet: &WepPacket) -> Vec<u8> {
+    packet
+        .ciphertext
+        .iter()
+        .zip(ARP_PREFIX)
+        .map(|(c, p)| c ^ p)
+        .collect()
+}
+
+// Run the first n steps of the key scheduling, returning the permutation and j
+fn partial_ksa(key: &[u8], n: usize) -> ([u8; 256], u8) {
+    let mut s = [0; 256];
+    for (i, x) in s.iter_mut().enumerate() {
+        *x = i as u8;
+    }
+    let mut j: u8 = 0;
+    for i in 0..n {
+        j = j.wrapping_add(s[i]).wrapping_add(key[i % key.len()]);
+        s.swap(i, j as usize);
+    }
+    (s, j)
+}
+
+fn inverse(s: &[u8; 256]) -> [u8; 256] {
+    let mut out = [0; 256];
+    for (i, x) in s.iter().enumerate() {
+        out[*x as usize] = i as u8;
+    }
+    out
+}
+
+// Candidate values in decreasing order of votes
+fn ranked(votes: &[u32; 256]) -> Vec<u8> {
+    let mut order: Vec<u8> = (0..=255).collect();
+    order.sort_by_key(|x| std::cmp::Reverse(votes[*x as usize]));
+    order
+}
+
+// Check a guessed key by decrypting a packet
+fn verify(key: &[u8], packet: &WepPacket) -> bool {
+    Wep::new(key)
+        .map(|wep| wep.decrypt(packet).is_ok())
+        .unwrap_or(false)
+}
+
+/// Votes for the key byte following the known ones. For each packet the key scheduling is run as far as the known
+/// bytes allow and, when the state is in the resolved condition, the first keystream byte reveals the next key byte
+/// with probability about 5%.
+pub fn fms_votes(packets: &[WepPacket], known: &[u8]) -> [u32; 256] {
+    let mut votes = [0_u32; 256];
+    let n = known.len() + 3;
+    for packet in packets {
+        let Some(first) = known_keystream(packet).first().copied() else {
+            continue;
+        };
+        let mut partial = packet.iv.to_vec();
+        partial.extend_from_slice(known);
+        let (s, j) = partial_ksa(&partial, n);
+        let x = s[1] as usize;
+        if x < n && (x + s[x] as usize) % 256 == n {
+            let candidate = inverse(&s)[first as usize]
+                .wrapping_sub(j)
+                .wrapping_sub(s[n]);
+            votes[candidate as usize] += 1;
+        }
+    }
+    votes
+}
+
+// Number of alternatives tried for each key byte by the FMS search
+const FMS_BREADTH: usize = 3;
+
+fn fms_search(packets: &[WepPacket], key_len: usize, key: &mut Vec<u8>) -> bool {
+    if key.len() == key_len {
+        return verify(key, &packets[0]);
+    }
+    let votes = fms_votes(packets, key);
+    let order = ranked(&votes);
+    let most = votes[order[0] as usize];
+    // Candidates with less than half the votes of the leader are not worth following
+    for candidate in order
+        .into_iter()
+        .take(FMS_BREADTH)
+        .filter(|c| votes[*c as usize] * 2 >= most)
+    {
+        key.push(candidate);
+        if fms_search(packets, key_len, key) {
+            return true;
+        }
+        key.pop();
+    }
+    false
+}
+
+/// The Fluhrer-Mantin-Shamir attack. Key bytes are recovered in order from the votes of packets with weak IVs, so an
+/// error in one byte spoils those after it. When the vote for a byte is close the runners up are also followed and
+/// every complete key is checked by decrypting a packet.
+pub fn fms_attack(packets: &[WepPacket], key_len: usize) -> Option<Vec<u8>> {
+    if packets.is_empty() {
+        return None;
+    }
+    let mut key = Vec::with_capacity(key_len);
+    fms_search(packets, key_len, &mut key).then_some(key)
+}
+
+/// Votes for the sums of key bytes used by the Pyshkin-Tews-Weinmann attack. Entry i counts the votes for
+/// K[3] + ... + K[3 + i], which each packet casts using Klein's correlation between the keystream and the
+/// permutation.
+pub fn ptw_votes(packets: &[WepPacket], key_len: usize) -> Vec<[u32; 256]> {
+    let mut votes = vec![[0_u32; 256]; key_len];
+    for packet in packets {
+        let x = known_keystream(packet);
+        if x.len() < key_len + 2 {
+            continue;
+        }
+        let (s, j) = partial_ksa(&packet.iv, 3);
+        let s_inv = inverse(&s);
+        let mut sum = j;
+        for i in 0..key_len {
+            sum = sum.wrapping_add(s[3 + i]);
+            let target = ((3 + i) as u8).wrapping_sub(x[2 + i]);
+            let sigma = s_inv[target as usize].wrapping_sub(sum);
+            votes[i][sigma as usize] += 1;
+        }
+    }
+    votes
+}
+
+// Convert the sums K[3] + ... + K[3 + i] back into key bytes
+fn key_from_sums(sums: &[u8]) -> Vec<u8> {
+    let mut prev = 0_u8;
+    sums.iter()
+        .map(|s| {
+            let k = s.wrapping_sub(prev);
+            prev = *s;
+            k
+        })
+        .collect()
+}
+
+// Largest number of candidates considered for any one sum by the PTW search, and the largest total of their ranks
+const PTW_BREADTH: usize = 4;
+const PTW_MAX_BUDGET: usize = 4;
+
+// Try every choice of candidates whose ranks add up to exactly the budget
+fn ptw_search(
+    candidates: &[Vec<u8>],
+    budget: usize,
+    sums: &mut Vec<u8>,
+    check: &dyn Fn(&[u8]) -> Option<Vec<u8>>,
+) -> Option<Vec<u8>> {
+    let i = sums.len();
+    if i == candidates.len() {
+        if budget != 0 {
+            return None;
+        }
+        // The last sum is the least reliable so every value is tried
+        return (0..=255).find_map(|last| {
+            sums.push(last);
+            let key = check(sums);
+            sums.pop();
+            key
+        });
+    }
+    for (rank, candidate) in candidates[i].iter().enumerate().take(budget + 1) {
+        sums.push(*candidate);
+        if let Some(key) = ptw_search(candidates, budget - rank, sums, check) {
+            return Some(key);
+        }
+        sums.pop();
+    }
+    None
+}
+
+/// The Pyshkin-Tews-Weinmann attack. Every packet votes for every key byte at once so bytes do not have to be
+/// recovered in order and far fewer packets are needed than for FMS. For each sum the candidates within two standard
+/// deviations of the leader are kept and combinations are tried in order of their total rank, each checked by
+/// decrypting a packet. The votes for the last sum are the weakest so it is found by exhaustive search instead.
+pub fn ptw_attack(packets: &[WepPacket], key_len: usize) -> Option<Vec<u8>> {
+    let packet = packets.first()?;
+    let deviation = (packets.len() as f64 / 256.0).sqrt();
+    let candidates: Vec<Vec<u8>> = ptw_votes(packets, key_len - 1)
+        .iter()
+        .map(|votes| {
+            let order = ranked(votes);
+            let top = votes[order[0] as usize] as f64;
+            order
+                .into_iter()
+                .take(PTW_BREADTH)
+                .take_while(|c| votes[*c as usize] as f64 >= top - 2.0 * deviation)
+                .collect()
+        })
+        .collect();
+
+    let check = |sums: &[u8]| {
+        let key = key_from_sums(sums);
+        verify(&key, packet).then_some(key)
+    };
+    let max_budget = candidates.iter().map(|c| c.len() - 1).sum::<usize>();
+    (0..=max_budget.min(PTW_MAX_BUDGET))
+        .find_map(|budget| ptw_search(&candidates, budget, &mut Vec::new(), &check))
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use hex_literal::hex;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn crc() {
+        assert_eq!(0xcbf43926, crc32(b"123456789"));
+    }
+
+    #[test]
+    fn round_trip() {
+        let wep = Wep::new(&hex!("0123456789")).unwrap();
+        let packet = wep.encrypt([1, 2, 3], b"hello world");
+        assert_eq!(b"hello world".to_vec(), wep.decrypt(&packet).unwrap());
+
+        let mut forged = packet.clone();
+        forged.ciphertext[0] ^= 1;
+        assert!(wep.decrypt(&forged).is_err());
+    }
+
+    #[test]
+    fn fms_40_bit() {
+        let wep = Wep::new(&hex!("1f2e3d4c5b")).unwrap();
+        let packets = wep.capture_weak();
+        assert_eq!(Some(wep.key), fms_attack(&packets, 5));
+    }
+
+    #[test]
+    fn ptw_40_bit() {
+        let mut rng = StdRng::seed_from_u64(0x3e9);
+        let wep = Wep::new(&hex!("a1b2c3d4e5")).unwrap();
+        let packets = wep.capture_random(&mut rng, 40000);
+        assert_eq!(Some(wep.key), ptw_attack(&packets, 5));
+    }
+
+    #[test]
+    fn ptw_104_bit() {
+        let mut rng = StdRng::seed_from_u64(0x3e9);
+        let wep = Wep::new(&hex!("0a1b2c3d4e5f60718293a4b5c6")).unwrap();
+        let packets = wep.capture_random(&mut rng, 60000);
+        assert_eq!(Some(wep.key), ptw_attack(&packets, 13));
+    }
+}
@@ -0,0 +1,244 @@
+use super::block_cipher::{BCMode, BCPadding, BlockCipher};
+use utils::{
+    byte_formatting::{xor_into_bytes, ByteFormat},
+    errors::GeneralError,
+};
+
+// The tweaked S-box from the final submission to NESSIE, which is an involution
+const SBOX: [u8; 256] = [
+    0xba, 0x54, 0x2f, 0x74, 0x53, 0xd3, 0xd2, 0x4d, 0x50, 0xac, 0x8d, 0xbf, 0x70, 0x52, 0x9a, 0x4c,
+    0xea, 0xd5, 0x97, 0xd1, 0x33, 0x51, 0x5b, 0xa6, 0xde, 0x48, 0xa8, 0x99, 0xdb, 0x32, 0xb7, 0xfc,
+    0xe3, 0x9e, 0x91, 0x9b, 0xe2, 0xbb, 0x41, 0x6e, 0xa5, 0xcb, 0x6b, 0x95, 0xa1, 0xf3, 0xb1, 0x02,
+    0xcc, 0xc4, 0x1d, 0x14, 0xc3, 0x63, 0xda, 0x5d, 0x5f, 0xdc, 0x7d, 0xcd, 0x7f, 0x5a, 0x6c, 0x5c,
+    0xf7, 0x26, 0xff, 0xed, 0xe8, 0x9d, 0x6f, 0x8e, 0x19, 0xa0, 0xf0, 0x89, 0x0f, 0x07, 0xaf, 0xfb,
+    0x08, 0x15, 0x0d, 0x04, 0x01, 0x64, 0xdf, 0x76, 0x79, 0xdd, 0x3d, 0x16, 0x3f, 0x37, 0x6d, 0x38,
+    0xb9, 0x73, 0xe9, 0x35, 0x55, 0x71, 0x7b, 0x8c, 0x72, 0x88, 0xf6, 0x2a, 0x3e, 0x5e, 0x27, 0x46,
+    0x0c, 0x65, 0x68, 0x61, 0x03, 0xc1, 0x57, 0xd6, 0xd9, 0x58, 0xd8, 0x66, 0xd7, 0x3a, 0xc8, 0x3c,
+    0xfa, 0x96, 0xa7, 0x98, 0xec, 0xb8, 0xc7, 0xae, 0x69, 0x4b, 0xab, 0xa9, 0x67, 0x0a, 0x47, 0xf2,
+    0xb5, 0x22, 0xe5, 0xee, 0xbe, 0x2b, 0x81, 0x12, 0x83, 0x1b, 0x0e, 0x23, 0xf5, 0x45, 0x21, 0xce,
+    0x49, 0x2c, 0xf9, 0xe6, 0xb6, 0x28, 0x17, 0x82, 0x1a, 0x8b, 0xfe, 0x8a, 0x09, 0xc9, 0x87, 0x4e,
+    0xe1, 0x2e, 0xe4, 0xe0, 0xeb, 0x90, 0xa4, 0x1e, 0x85, 0x60, 0x00, 0x25, 0xf4, 0xf1, 0x94, 0x0b,
+    0xe7, 0x75, 0xef, 0x34, 0x31, 0xd4, 0xd0, 0x86, 0x7e, 0xad, 0xfd, 0x29, 0x30, 0x3b, 0x9f, 0xf8,
+    0xc6, 0x13, 0x06, 0x05, 0xc5, 0x11, 0x77, 0x7c, 0x7a, 0x78, 0x36, 0x1c, 0x39, 0x59, 0x18, 0x56,
+    0xb3, 0xb0, 0x24, 0x20, 0xb2, 0x92, 0xa3, 0xc0, 0x44, 0x62, 0x10, 0xb4, 0x84, 0x43, 0x93, 0xc2,
+    0x4a, 0xbd, 0x8f, 0x2d, 0xbc, 0x9c, 0x6a, 0x40, 0xcf, 0xa2, 0x80, 0x4f, 0x1f, 0xca, 0xaa, 0x42,
+];
+
+// First row of the involutional matrix H = had(1, 2, 4, 6), entry (i, j) is H_ROW[i ^ j]
+const H_ROW: [u8; 4] = [0x01, 0x02, 0x04, 0x06];
+
+// Elements of the Vandermonde matrix used to extract round keys
+const V_ROW: [u8; 4] = [0x01, 0x02, 0x06, 0x08];
+
+const MAX_ROUNDS: usize = 18;
+
+// Multiplication in GF(2^8) modulo x^8 + x^4 + x^3 + x^2 + 1
+fn mul_gf(mut a: u8, mut b: u8) -> u8 {
+    let mut out = 0;
+    while b != 0 {
+        if b & 1 == 1 {
+            out ^= a;
+        }
+        let carry = a & 0x80;
+        a <<= 1;
+        if carry != 0 {
+            a ^= 0x1d;
+        }
+        b >>= 1;
+    }
+    out
+}
+
+// The state is a 4x4 matrix of bytes in row major order
+
+fn gamma(state: &mut [u8; 16]) {
+    for byte in state.iter_mut() {
+        *byte = SBOX[*byte as usize];
+    }
+}
+
+// Transpose the state
+fn tau(state: &mut [u8; 16]) {
+    let old = *state;
+    for i in 0..4 {
+        for j in 0..4 {
+            state[4 * i + j] = old[4 * j + i];
+        }
+    }
+}
+
+// Multiply a row vector by H
+fn theta_row(row: &mut [u8]) {
+    let old: [u8; 4] = (&*row).try_into().unwrap();
+    for (j, byte) in row.iter_mut().enumerate() {
+        *byte = (0..4).fold(0, |acc, k| acc ^ mul_gf(old[k], H_ROW[k ^ j]));
+    }
+}
+
+fn theta(state: &mut [u8; 16]) {
+    for row in state.chunks_exact_mut(4) {
+        theta_row(row);
+    }
+}
+
+// Anubis is involutional so decryption is the same function as encryption using a modified key schedule
+fn crypt(block: &mut [u8], round_keys: &[[u8; 16]]) {
+    let mut state: [u8; 16] = (&*block).try_into().unwrap();
+    let (last, keys) = round_keys.split_last().unwrap();
+    xor_into_bytes(&mut state, keys[0]);
+    for key in &keys[1..] {
+        gamma(&mut state);
+        tau(&mut state);
+        theta(&mut state);
+        xor_into_bytes(&mut state, key);
+    }
+    gamma(&mut state);
+    tau(&mut state);
+    xor_into_bytes(&mut state, last);
+    block.copy_from_slice(&state);
+}
+
+/// Anubis, the involutional 128-bit block cipher by Barreto and Rijmen, in the tweaked version submitted to NESSIE.
+/// Keys can be from 128 to 320 bits in steps of 32 bits and the number of rounds depends on the key size.
+pub struct Anubis {
+    pub input_format: ByteFormat,
+    pub output_format: ByteFormat,
+    pub rounds: usize,
+    pub enc_round_keys: [[u8; 16]; MAX_ROUNDS + 1],
+    pub dec_round_keys: [[u8; 16]; MAX_ROUNDS + 1],
+    pub iv: u128,
+    pub mode: BCMode,
+    pub padding: BCPadding,
+}
+
+impl Default for Anubis {
+    fn default() -> Self {
+        Self {
+            input_format: ByteFormat::Hex,
+            output_format: ByteFormat::Hex,
+            rounds: 12,
+            enc_round_keys: [[0; 16]; MAX_ROUNDS + 1],
+            dec_round_keys: [[0; 16]; MAX_ROUNDS + 1],
+            iv: 0,
+            mode: BCMode::default(),
+            padding: BCPadding::default(),
+        }
+    }
+}
+
+crate::block_cipher_builders! {Anubis, u128}
+
+impl Anubis {
+    pub fn ksa(&mut self, bytes: &[u8]) -> Result<(), GeneralError> {
+        if bytes.len() < 16 || bytes.len() > 40 || !bytes.len().is_multiple_of(4) {
+            return Err(GeneralError::key(
+                "Anubis key must be between 16 and 40 bytes and a multiple of 4 bytes",
+            ));
+        }
+        // The key state is an Nx4 matrix of bytes in row major order
+        let n = bytes.len() / 4;
+        let r = 8 + n;
+        let mut kappa = bytes.to_vec();
+
+        for round in 0..=r {
+            // The round key is the Vandermonde matrix times the S-box applied to the key state, transposed
+            let key = &mut self.enc_round_keys[round];
+            *key = [0; 16];
+            for i in (0..n).rev() {
+                for j in 0..4 {
+                    for (b, v) in V_ROW.iter().enumerate() {
+                        key[4 * j + b] =
+                            mul_gf(key[4 * j + b], *v) ^ SBOX[kappa[4 * i + j] as usize];
+                    }
+                }
+            }
+
+            if round == r {
+                break;
+            }
+
+            // Evolve the key state. The S-box is applied, column j is rotated downward by j positions, each row is
+            // multiplied by H, and finally the round constant is added to the first row.
+            let mut next = vec![0; 4 * n];
+            for (i, row) in next.chunks_exact_mut(4).enumerate() {
+                for (j, byte) in row.iter_mut().enumerate() {
+                    *byte = SBOX[kappa[4 * ((i + n - j) % n) + j] as usize];
+                }
+                theta_row(row);
+            }
+            for j in 0..4 {
+                next[j] ^= SBOX[4 * round + j];
+            }
+            kappa = next;
+        }
+
+        // Decryption uses the round keys in reverse order with theta applied to all but the first and last
+        self.dec_round_keys[0] = self.enc_round_keys[r];
+        self.dec_round_keys[r] = self.enc_round_keys[0];
+        for round in 1..r {
+            let mut key = self.enc_round_keys[r - round];
+            theta(&mut key);
+            self.dec_round_keys[round] = key;
+        }
+        self.rounds = r;
+        Ok(())
+    }
+
+    pub fn with_key(mut self, bytes: &[u8]) -> Result<Self, GeneralError> {
+        self.ksa(bytes)?;
+        Ok(self)
+    }
+}
+
+impl BlockCipher<16> for Anubis {
+    fn encrypt_block(&self, bytes: &mut [u8]) {
+        crypt(bytes, &self.enc_round_keys[..=self.rounds])
+    }
+
+    fn decrypt_block(&self, bytes: &mut [u8]) {
+        crypt(bytes, &self.dec_round_keys[..=self.rounds])
+    }
+
+    crate::block_cipher_getters!();
+}
+
+crate::impl_cipher_for_block_cipher!(Anubis, 16);
+
+#[cfg(test)]
+mod anubis_tests {
+
+    use super::*;
+
+    #[test]
+    fn sbox_involution() {
+        for i in 0..=255 {
+            assert_eq!(i, SBOX[SBOX[i as usize] as usize]);
+        }
+    }
+
+    #[test]
+    fn invalid_key() {
+        assert!(Anubis::default().ksa(&[0; 15]).is_err());
+        assert!(Anubis::default().ksa(&[0; 18]).is_err());
+        assert!(Anubis::default().ksa(&[0; 44]).is_err());
+    }
+}
+
+// Test vectors for the tweaked cipher from the NESSIE submission
+crate::test_block_cipher!(
+    test_128, Anubis::default().with_key(&hex_literal::hex!("80000000000000000000000000000000")).unwrap(),
+    [0; 16],
+    hex_literal::hex!("b835bdc334829d8371bfa371e4b3c4fd");
+    test_160, Anubis::default().with_key(&hex_literal::hex!("0000000000000000000000000000000000000001")).unwrap(),
+    [0; 16],
+    hex_literal::hex!("b80dfb9be4a15887b376d5021895c12e");
+    test_192, Anubis::default().with_key(&hex_literal::hex!("800000000000000000000000000000000000000000000000")).unwrap(),
+    [0; 16],
+    hex_literal::hex!("7d623b52c74c64d8ebc72d579785438f");
+    test_256, Anubis::default().with_key(&hex_literal::hex!("0000000000000000000000000000000000000000000000000000000000000001")).unwrap(),
+    [0; 16],
+    hex_literal::hex!("699cafdd94c7bc6044fe02058a6eefbd");
+    test_320, Anubis::default().with_key(&hex_literal::hex!("80000000000000000000000000000000000000000000000000000000000000000000000000000000")).unwrap(),
+    [0; 16],
+    hex_literal::hex!("fee20e2a9dc583baa3a6d6a6f2e806a5");
+);
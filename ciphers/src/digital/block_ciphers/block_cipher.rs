@@ -72,24 +72,46 @@ pub trait BlockCipher<const N: usize> {
     fn get_iv_le(&self) -> Vec<u8>;
 
     fn encrypt_bytes(&self, bytes: &mut [u8]) {
-        match self.get_mode() {
-            BCMode::Cbc => self.encrypt_cbc(bytes, self.get_iv_be().try_into().unwrap()),
-            BCMode::Ctr => self.encrypt_ctr(bytes, self.get_iv_be().try_into().unwrap()),
+        let mode = self.get_mode();
+        let iv = if mode.iv_needed() {
+            self.get_iv_be().try_into().unwrap()
+        } else {
+            [0; N]
+        };
+        self.encrypt_bytes_with_mode(bytes, mode, iv)
+    }
+
+    fn decrypt_bytes(&self, bytes: &mut [u8]) {
+        let mode = self.get_mode();
+        let iv = if mode.iv_needed() {
+            self.get_iv_be().try_into().unwrap()
+        } else {
+            [0; N]
+        };
+        self.decrypt_bytes_with_mode(bytes, mode, iv)
+    }
+
+    /// Encrypt using the given mode and IV rather than the ones stored in the cipher. The IV is ignored in ECB mode.
+    fn encrypt_bytes_with_mode(&self, bytes: &mut [u8], mode: BCMode, iv: [u8; N]) {
+        match mode {
+            BCMode::Cbc => self.encrypt_cbc(bytes, iv),
+            BCMode::Ctr => self.encrypt_ctr(bytes, iv),
             BCMode::Ecb => self.encrypt_ecb(bytes),
-            BCMode::Pcbc => self.encrypt_pcbc(bytes, self.get_iv_be().try_into().unwrap()),
-            BCMode::Ofb => self.encrypt_ofb(bytes, self.get_iv_be().try_into().unwrap()),
-            BCMode::Cfb => self.encrypt_cfb(bytes, self.get_iv_be().try_into().unwrap()),
+            BCMode::Pcbc => self.encrypt_pcbc(bytes, iv),
+            BCMode::Ofb => self.encrypt_ofb(bytes, iv),
+            BCMode::Cfb => self.encrypt_cfb(bytes, iv),
         }
     }
 
-    fn decrypt_bytes(&self, bytes: &mut [u8]) {
-        match self.get_mode() {
-            BCMode::Cbc => self.decrypt_cbc(bytes, self.get_iv_be().try_into().unwrap()),
-            BCMode::Ctr => self.decrypt_ctr(bytes, self.get_iv_be().try_into().unwrap()),
+    /// Decrypt using the given mode and IV rather than the ones stored in the cipher. The IV is ignored in ECB mode.
+    fn decrypt_bytes_with_mode(&self, bytes: &mut [u8], mode: BCMode, iv: [u8; N]) {
+        match mode {
+            BCMode::Cbc => self.decrypt_cbc(bytes, iv),
+            BCMode::Ctr => self.decrypt_ctr(bytes, iv),
             BCMode::Ecb => self.decrypt_ecb(bytes),
-            BCMode::Pcbc => self.decrypt_pcbc(bytes, self.get_iv_be().try_into().unwrap()),
-            BCMode::Ofb => self.decrypt_ofb(bytes, self.get_iv_be().try_into().unwrap()),
-            BCMode::Cfb => self.decrypt_cfb(bytes, self.get_iv_be().try_into().unwrap()),
+            BCMode::Pcbc => self.decrypt_pcbc(bytes, iv),
+            BCMode::Ofb => self.decrypt_ofb(bytes, iv),
+            BCMode::Cfb => self.decrypt_cfb(bytes, iv),
         }
     }
 
@@ -217,8 +239,8 @@ pub trait BlockCipher<const N: usize> {
             // XOR the current chain value into the mixed text, making it plaintext
             xor_into_bytes(&mut mixed, &chain);
 
-            // XOR the mixed text (now plaintext) with the chain
-            xor_into_bytes(&mut chain, &mixed);
+            // Replace the chain with the plaintext
+            overwrite_bytes(&mut chain, &mixed);
 
             // The overwrite ciphertext at source with the plaintext
             overwrite_bytes(ctext, &mixed);
@@ -262,9 +284,21 @@ pub trait BlockCipher<const N: usize> {
         }
     }
 
-    /// Decrypt in Cipher Feedback Mode (equivalent to encrypt)
+    /// Decrypt in Cipher Feedback Mode
     fn decrypt_cfb(&self, bytes: &mut [u8], iv: [u8; N]) {
-        self.encrypt_cfb(bytes, iv)
+        let mut chain = iv;
+
+        for ctext in bytes.chunks_mut(N) {
+            // Encrypt the chain to create a mask
+            let mut mask = chain;
+            self.encrypt_block(&mut mask);
+
+            // The ciphertext is the next chain value
+            overwrite_bytes(&mut chain, &ctext);
+
+            // XOR the mask into the ciphertext at the source, creating plaintext
+            xor_into_bytes(ctext, mask);
+        }
     }
 }
 
@@ -392,7 +426,7 @@ impl Display for BCPadding {
 #[cfg(test)]
 #[test]
 fn aes_test_modes() {
-    use crate::digital::block_ciphers::aes::aes::Aes256;
+    use crate::digital::block_ciphers::aes::aes::{Aes128, Aes256};
     use hex_literal::hex;
 
     // Multiblock ECB test
@@ -437,5 +471,24 @@ fn aes_test_modes() {
     cipher.encrypt_ctr(&mut ptext, cipher.iv.to_be_bytes());
     assert_eq!(ctext, ptext);
 
-    // todo!("need to find test vectors for PCBC and CFB but all others are passing")
+    // CFB128-AES128 from NIST SP 800-38A F.3.13
+    let key = hex!("2b7e151628aed2a6abf7158809cf4f3c");
+    let iv = hex!("000102030405060708090a0b0c0d0e0f");
+    let ptext = hex!("6bc1bee22e409f96e93d7e117393172aae2d8a571e03ac9c9eb76fac45af8e5130c81c46a35ce411e5fbc1191a0a52eff69f2445df4f9b17ad2b417be66c3710");
+    let cipher = Aes128::default().with_key(key);
+    let ctext = hex!("3b3fd92eb72dad20333449f8e83cfb4ac8a64537a0b3a93fcde3cdad9f1ce58b26751f67a3cbb140b1808cf187a4f4dfc04b05357c5d1c0eeac4c66f9ff7f2e6");
+    let mut buffer = ptext;
+    cipher.encrypt_cfb(&mut buffer, iv);
+    assert_eq!(ctext, buffer);
+    cipher.decrypt_cfb(&mut buffer, iv);
+    assert_eq!(ptext, buffer);
+
+    // There is no standard for PCBC so this was computed with the AES of OpenSSL using the same key, IV and plaintext
+    // as above. The first block matches CBC, the later ones only match if the plaintext is fed into the chain.
+    let ctext = hex!("7649abac8119b246cee98e9b12e9197d9e8baff12ad5270a0d1eef93d70379945700b39803779fa35a3c600a49a163c033ae199f27379f21be6dd57d295cc87d");
+    let mut buffer = ptext;
+    cipher.encrypt_pcbc(&mut buffer, iv);
+    assert_eq!(ctext, buffer);
+    cipher.decrypt_pcbc(&mut buffer, iv);
+    assert_eq!(ptext, buffer);
 }
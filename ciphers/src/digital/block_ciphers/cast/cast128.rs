@@ -0,0 +1,193 @@
+use super::{
+    cast_arrays::{S5, S6, S7, S8},
+    f1, f2, f3,
+};
+use crate::digital::block_ciphers::block_cipher::{BCMode, BCPadding, BlockCipher};
+use utils::{
+    byte_formatting::{make_u32s_be, u32s_to_bytes_be, ByteFormat},
+    errors::GeneralError,
+};
+
+// Bytes used to extract each group of four subkeys. The first four bytes of each entry select from S5, S6, S7, and S8
+// and the last selects from S5, S6, S7, or S8 in turn. Groups alternate between being taken from z and from x.
+const EXTRACT: [[[usize; 5]; 4]; 4] = [
+    [
+        [0x8, 0x9, 0x7, 0x6, 0x2],
+        [0xa, 0xb, 0x5, 0x4, 0x6],
+        [0xc, 0xd, 0x3, 0x2, 0x9],
+        [0xe, 0xf, 0x1, 0x0, 0xc],
+    ],
+    [
+        [0x3, 0x2, 0xc, 0xd, 0x8],
+        [0x1, 0x0, 0xe, 0xf, 0xd],
+        [0x7, 0x6, 0x8, 0x9, 0x3],
+        [0x5, 0x4, 0xa, 0xb, 0x7],
+    ],
+    [
+        [0x3, 0x2, 0xc, 0xd, 0x9],
+        [0x1, 0x0, 0xe, 0xf, 0xc],
+        [0x7, 0x6, 0x8, 0x9, 0x2],
+        [0x5, 0x4, 0xa, 0xb, 0x6],
+    ],
+    [
+        [0x8, 0x9, 0x7, 0x6, 0x3],
+        [0xa, 0xb, 0x5, 0x4, 0x7],
+        [0xc, 0xd, 0x3, 0x2, 0x8],
+        [0xe, 0xf, 0x1, 0x0, 0xd],
+    ],
+];
+
+// Byte i of the 16 byte value held in four words, numbered as in RFC 2144
+fn byte(words: &[u32; 4], i: usize) -> usize {
+    words[i / 4].to_be_bytes()[i % 4] as usize
+}
+
+// S5[a] ^ S6[b] ^ S7[c] ^ S8[d]
+fn sboxes(words: &[u32; 4], a: usize, b: usize, c: usize, d: usize) -> u32 {
+    S5[byte(words, a)] ^ S6[byte(words, b)] ^ S7[byte(words, c)] ^ S8[byte(words, d)]
+}
+
+fn z_from_x(x: &[u32; 4]) -> [u32; 4] {
+    let mut z = [0; 4];
+    z[0] = x[0] ^ sboxes(x, 0xd, 0xf, 0xc, 0xe) ^ S7[byte(x, 0x8)];
+    z[1] = x[2] ^ sboxes(&z, 0x0, 0x2, 0x1, 0x3) ^ S8[byte(x, 0xa)];
+    z[2] = x[3] ^ sboxes(&z, 0x7, 0x6, 0x5, 0x4) ^ S5[byte(x, 0x9)];
+    z[3] = x[1] ^ sboxes(&z, 0xa, 0x9, 0xb, 0x8) ^ S6[byte(x, 0xb)];
+    z
+}
+
+fn x_from_z(z: &[u32; 4]) -> [u32; 4] {
+    let mut x = [0; 4];
+    x[0] = z[2] ^ sboxes(z, 0x5, 0x7, 0x4, 0x6) ^ S7[byte(z, 0x0)];
+    x[1] = z[0] ^ sboxes(&x, 0x0, 0x2, 0x1, 0x3) ^ S8[byte(z, 0x2)];
+    x[2] = z[1] ^ sboxes(&x, 0x7, 0x6, 0x5, 0x4) ^ S5[byte(z, 0x1)];
+    x[3] = z[3] ^ sboxes(&x, 0xa, 0x9, 0xb, 0x8) ^ S6[byte(z, 0x3)];
+    x
+}
+
+fn extract(words: &[u32; 4], table: &[[usize; 5]; 4]) -> [u32; 4] {
+    let mut out = [0; 4];
+    for (k, (idx, extra)) in out.iter_mut().zip(table.iter().zip([S5, S6, S7, S8])) {
+        *k = sboxes(words, idx[0], idx[1], idx[2], idx[3]) ^ extra[byte(words, idx[4])];
+    }
+    out
+}
+
+// Generate the 32 subkeys K1 through K32, alternately computing z from x and x from z
+fn key_schedule(key: [u8; 16]) -> [u32; 32] {
+    let mut x = make_u32s_be::<4>(&key);
+    let mut z = [0; 4];
+    let mut out = [0; 32];
+    for (i, subkeys) in out.chunks_exact_mut(4).enumerate() {
+        if i % 2 == 0 {
+            z = z_from_x(&x);
+            subkeys.copy_from_slice(&extract(&z, &EXTRACT[i % 4]));
+        } else {
+            x = x_from_z(&z);
+            subkeys.copy_from_slice(&extract(&x, &EXTRACT[i % 4]));
+        }
+    }
+    out
+}
+
+/// CAST-128 (also called CAST5) as defined in RFC 2144. Keys from 40 to 128 bits are accepted. Keys of 80 bits or
+/// less use only 12 rounds.
+pub struct Cast128 {
+    pub input_format: ByteFormat,
+    pub output_format: ByteFormat,
+    pub masking_keys: [u32; 16],
+    pub rotation_keys: [u8; 16],
+    pub rounds: usize,
+    pub iv: u64,
+    pub mode: BCMode,
+    pub padding: BCPadding,
+}
+
+impl Default for Cast128 {
+    fn default() -> Self {
+        Self {
+            input_format: ByteFormat::Hex,
+            output_format: ByteFormat::Hex,
+            masking_keys: [0; 16],
+            rotation_keys: [0; 16],
+            rounds: 16,
+            iv: 0,
+            mode: BCMode::default(),
+            padding: BCPadding::default(),
+        }
+    }
+}
+
+crate::block_cipher_builders! {Cast128, u64}
+
+impl Cast128 {
+    pub fn ksa(&mut self, bytes: &[u8]) -> Result<(), GeneralError> {
+        if bytes.len() < 5 || bytes.len() > 16 {
+            return Err(GeneralError::key(
+                "CAST-128 key must be between 5 and 16 bytes",
+            ));
+        }
+        // Short keys are padded with zeroes on the right
+        let mut key = [0; 16];
+        key[..bytes.len()].copy_from_slice(bytes);
+        let subkeys = key_schedule(key);
+
+        self.masking_keys.copy_from_slice(&subkeys[..16]);
+        for (r, k) in self.rotation_keys.iter_mut().zip(&subkeys[16..]) {
+            *r = (k & 0x1f) as u8;
+        }
+        self.rounds = if bytes.len() <= 10 { 12 } else { 16 };
+        Ok(())
+    }
+
+    pub fn with_key(mut self, bytes: &[u8]) -> Result<Self, GeneralError> {
+        self.ksa(bytes)?;
+        Ok(self)
+    }
+
+    // Rounds cycle through the three function types
+    fn f(&self, round: usize, d: u32) -> u32 {
+        let km = self.masking_keys[round];
+        let kr = self.rotation_keys[round];
+        match round % 3 {
+            0 => f1(d, km, kr),
+            1 => f2(d, km, kr),
+            _ => f3(d, km, kr),
+        }
+    }
+}
+
+impl BlockCipher<8> for Cast128 {
+    fn encrypt_block(&self, bytes: &mut [u8]) {
+        let [mut l, mut r] = make_u32s_be::<2>(bytes);
+        for round in 0..self.rounds {
+            (l, r) = (r, l ^ self.f(round, r));
+        }
+        u32s_to_bytes_be(bytes, [r, l]);
+    }
+
+    fn decrypt_block(&self, bytes: &mut [u8]) {
+        let [mut l, mut r] = make_u32s_be::<2>(bytes);
+        for round in (0..self.rounds).rev() {
+            (l, r) = (r, l ^ self.f(round, r));
+        }
+        u32s_to_bytes_be(bytes, [r, l]);
+    }
+
+    crate::block_cipher_getters!();
+}
+
+crate::impl_cipher_for_block_cipher!(Cast128, 8);
+
+// Test vectors from RFC 2144 Appendix B.1
+crate::test_block_cipher!(
+    test_128, Cast128::default().with_key(&hex_literal::hex!("0123456712345678234567893456789a")).unwrap(),
+    hex_literal::hex!("0123456789abcdef"),
+    hex_literal::hex!("238b4fe5847e44b2");
+    test_80, Cast128::default().with_key(&hex_literal::hex!("01234567123456782345")).unwrap(),
+    hex_literal::hex!("0123456789abcdef"),
+    hex_literal::hex!("eb6a711a2c02271b");
+    test_40, Cast128::default().with_key(&hex_literal::hex!("0123456712")).unwrap(),
+    hex_literal::hex!("0123456789abcdef"),
+    hex_literal::hex!("7ac816d16e9b302e");
+);
@@ -0,0 +1,27 @@
+pub mod cast128;
+pub mod cast256;
+pub mod cast_arrays;
+
+use cast_arrays::{S1, S2, S3, S4};
+
+// The three round function types shared by CAST-128 and CAST-256. They differ only in the operations used to combine
+// the data with the masking key and the outputs of the S-boxes.
+fn f1(d: u32, km: u32, kr: u8) -> u32 {
+    let [a, b, c, d] = km.wrapping_add(d).rotate_left(kr as u32).to_be_bytes();
+    (S1[a as usize] ^ S2[b as usize])
+        .wrapping_sub(S3[c as usize])
+        .wrapping_add(S4[d as usize])
+}
+
+fn f2(d: u32, km: u32, kr: u8) -> u32 {
+    let [a, b, c, d] = (km ^ d).rotate_left(kr as u32).to_be_bytes();
+    (S1[a as usize]
+        .wrapping_sub(S2[b as usize])
+        .wrapping_add(S3[c as usize]))
+        ^ S4[d as usize]
+}
+
+fn f3(d: u32, km: u32, kr: u8) -> u32 {
+    let [a, b, c, d] = km.wrapping_sub(d).rotate_left(kr as u32).to_be_bytes();
+    ((S1[a as usize].wrapping_add(S2[b as usize])) ^ S3[c as usize]).wrapping_sub(S4[d as usize])
+}
@@ -0,0 +1,424 @@
+use super::{
+    aes::aes::{Aes128, Aes192, Aes256},
+    anubis::Anubis,
+    aria::{Aria128, Aria192, Aria256},
+    block_cipher::{BCMode, BCPadding, BlockCipher},
+    blowfish::blowfish::Blowfish,
+    camellia::{Camellia128, Camellia192, Camellia256},
+    cast::{cast128::Cast128, cast256::Cast256},
+    deoxys::{DeoxysBc256, DeoxysBc384},
+    des::{des::Des, triple_des::TripleDes},
+    fealnx::FealNx,
+    gift::Gift64,
+    gost::Gost,
+    idea::Idea,
+    kasumi::Kasumi,
+    kuznyechik::Kuznyechik,
+    lea::{Lea128, Lea192, Lea256},
+    magma::Magma,
+    misty1::Misty1,
+    rc2::Rc2,
+    rc5::{rc5_16::Rc5_16, rc5_32::Rc5_32, rc5_64::Rc5_64},
+    rc6::Rc6,
+    seed::Seed,
+    serpent::Serpent,
+    simon::{
+        simon128::{Simon128_128, Simon128_192, Simon128_256},
+        simon32::Simon32_64,
+        simon48::{Simon48_72, Simon48_96},
+        simon64::{Simon64_128, Simon64_96},
+        simon96::{Simon96_144, Simon96_96},
+    },
+    skinny::Skinny64_128,
+    skipjack::Skipjack,
+    sm4::Sm4,
+    speck::{
+        speck128::{Speck128_128, Speck128_192, Speck128_256},
+        speck32::Speck32_64,
+        speck48::{Speck48_72, Speck48_96},
+        speck64::{Speck64_128, Speck64_96},
+        speck96::{Speck96_144, Speck96_96},
+    },
+    tea::{tea::Tea, xtea::Xtea},
+    twofish::twofish::{Twofish128, Twofish192, Twofish256},
+};
+use std::collections::BTreeMap;
+use utils::errors::GeneralError;
+
+/// An object safe version of BlockCipher where the block size is known only at runtime. Any BlockCipher<N> can be
+/// turned into one by wrapping it in a BlockCipherAdapter, which also supplies the key setter.
+pub trait DynBlockCipher {
+    /// Number of bytes in a block.
+    fn block_size(&self) -> usize;
+
+    /// Use the block function to encrypt a single block of bytes.
+    fn encrypt_block(&self, bytes: &mut [u8]);
+
+    /// Use the block function to decrypt a single block of bytes.
+    fn decrypt_block(&self, bytes: &mut [u8]);
+
+    /// Run the key schedule using a key given as bytes.
+    fn set_key(&mut self, key: &[u8]) -> Result<(), GeneralError>;
+
+    /// Encrypt in the given mode. The IV must be exactly one block but is ignored in ECB mode.
+    fn encrypt_bytes_with_mode(
+        &self,
+        bytes: &mut [u8],
+        mode: BCMode,
+        iv: &[u8],
+    ) -> Result<(), GeneralError>;
+
+    /// Decrypt in the given mode. The IV must be exactly one block but is ignored in ECB mode.
+    fn decrypt_bytes_with_mode(
+        &self,
+        bytes: &mut [u8],
+        mode: BCMode,
+        iv: &[u8],
+    ) -> Result<(), GeneralError>;
+
+    /// Pad the bytes if needed by the mode and then encrypt.
+    fn encrypt_padded(
+        &self,
+        bytes: &mut Vec<u8>,
+        mode: BCMode,
+        padding: BCPadding,
+        iv: &[u8],
+    ) -> Result<(), GeneralError> {
+        if mode.padded() {
+            padding.add_padding(bytes, self.block_size() as u32)?;
+        }
+        self.encrypt_bytes_with_mode(bytes, mode, iv)
+    }
+
+    /// Decrypt and then remove padding if needed by the mode.
+    fn decrypt_padded(
+        &self,
+        bytes: &mut Vec<u8>,
+        mode: BCMode,
+        padding: BCPadding,
+        iv: &[u8],
+    ) -> Result<(), GeneralError> {
+        if mode.padded() && !bytes.len().is_multiple_of(self.block_size()) {
+            return Err(GeneralError::input(format!(
+                "decryption requires blocks of exactly {} bytes",
+                self.block_size()
+            )));
+        }
+        self.decrypt_bytes_with_mode(bytes, mode, iv)?;
+        if mode.padded() {
+            padding.strip_padding(bytes, self.block_size() as u32)?;
+        }
+        Ok(())
+    }
+}
+
+/// Wraps any BlockCipher<N> so that it can be used as a DynBlockCipher.
+pub struct BlockCipherAdapter<C, const N: usize> {
+    pub cipher: C,
+    key_setter: fn(&mut C, &[u8]) -> Result<(), GeneralError>,
+}
+
+impl<C: BlockCipher<N>, const N: usize> BlockCipherAdapter<C, N> {
+    pub fn new(cipher: C, key_setter: fn(&mut C, &[u8]) -> Result<(), GeneralError>) -> Self {
+        Self { cipher, key_setter }
+    }
+
+    fn check_block_size(&self, bytes: &[u8], mode: BCMode) -> Result<(), GeneralError> {
+        if mode.padded() && !bytes.len().is_multiple_of(N) {
+            return Err(GeneralError::input(format!(
+                "{mode} mode requires a multiple of {N} bytes"
+            )));
+        }
+        Ok(())
+    }
+
+    fn iv_array(iv: &[u8], mode: BCMode) -> Result<[u8; N], GeneralError> {
+        if mode.iv_needed() {
+            iv.try_into()
+                .map_err(|_| GeneralError::input(format!("IV must be exactly {N} bytes")))
+        } else {
+            Ok([0; N])
+        }
+    }
+}
+
+impl<C: BlockCipher<N>, const N: usize> DynBlockCipher for BlockCipherAdapter<C, N> {
+    fn block_size(&self) -> usize {
+        N
+    }
+
+    fn encrypt_block(&self, bytes: &mut [u8]) {
+        self.cipher.encrypt_block(bytes)
+    }
+
+    fn decrypt_block(&self, bytes: &mut [u8]) {
+        self.cipher.decrypt_block(bytes)
+    }
+
+    fn set_key(&mut self, key: &[u8]) -> Result<(), GeneralError> {
+        (self.key_setter)(&mut self.cipher, key)
+    }
+
+    fn encrypt_bytes_with_mode(
+        &self,
+        bytes: &mut [u8],
+        mode: BCMode,
+        iv: &[u8],
+    ) -> Result<(), GeneralError> {
+        self.check_block_size(bytes, mode)?;
+        let iv = Self::iv_array(iv, mode)?;
+        self.cipher.encrypt_bytes_with_mode(bytes, mode, iv);
+        Ok(())
+    }
+
+    fn decrypt_bytes_with_mode(
+        &self,
+        bytes: &mut [u8],
+        mode: BCMode,
+        iv: &[u8],
+    ) -> Result<(), GeneralError> {
+        self.check_block_size(bytes, mode)?;
+        let iv = Self::iv_array(iv, mode)?;
+        self.cipher.decrypt_bytes_with_mode(bytes, mode, iv);
+        Ok(())
+    }
+}
+
+// Interpret a key of exactly K bytes
+fn fixed_key<const K: usize>(key: &[u8]) -> Result<[u8; K], GeneralError> {
+    key.try_into()
+        .map_err(|_| GeneralError::key(format!("key must be exactly {K} bytes")))
+}
+
+// Key setter for ciphers that have a ksa method taking a fixed size array of bytes
+macro_rules! ksa {
+    () => {
+        |c, k| {
+            c.ksa(fixed_key(k)?);
+            Ok(())
+        }
+    };
+}
+
+// Each entry gives the name, the default cipher, the block size, and the key setter
+macro_rules! registry {
+    ($($name: literal, $cipher: expr, $n: literal, $setter: expr);+ $(;)?) => {
+        /// Names of every block cipher that can be created at runtime.
+        pub const BLOCK_CIPHER_NAMES: &[&str] = &[$($name),+];
+
+        /// Create a block cipher in its default state from its name. Use set_key to run the key schedule.
+        pub fn block_cipher_by_name(name: &str) -> Option<Box<dyn DynBlockCipher>> {
+            match name {
+                $(
+                    $name => Some(Box::new(BlockCipherAdapter::<_, $n>::new($cipher, $setter))),
+                )+
+                _ => None,
+            }
+        }
+    };
+}
+
+registry!(
+    "AES-128", Aes128::default(), 16, ksa!();
+    "AES-192", Aes192::default(), 16, ksa!();
+    "AES-256", Aes256::default(), 16, ksa!();
+    "Anubis", Anubis::default(), 16, |c, k| c.ksa(k);
+    "ARIA-128", Aria128::default(), 16, ksa!();
+    "ARIA-192", Aria192::default(), 16, ksa!();
+    "ARIA-256", Aria256::default(), 16, ksa!();
+    "Blowfish", Blowfish::default(), 8, |c, k| {
+        c.key = k.to_vec();
+        c.valid_key()?;
+        c.key_schedule();
+        Ok(())
+    };
+    "Camellia-128", Camellia128::default(), 16, ksa!();
+    "Camellia-192", Camellia192::default(), 16, ksa!();
+    "Camellia-256", Camellia256::default(), 16, ksa!();
+    "CAST-128", Cast128::default(), 8, |c, k| c.ksa(k);
+    "CAST-256", Cast256::default(), 16, |c, k| c.ksa(k);
+    "Deoxys-BC-256", DeoxysBc256::default(), 16, ksa!();
+    "Deoxys-BC-384", DeoxysBc384::default(), 16, ksa!();
+    "DES", Des::default(), 8, |c, k| c.ksa(u64::from_be_bytes(fixed_key(k)?));
+    "FEAL-NX", FealNx::default(), 8, ksa!();
+    "GIFT-64", Gift64::default(), 8, ksa!();
+    "GOST 28147-89", Gost::default(), 8, ksa!();
+    "IDEA", Idea::default(), 8, ksa!();
+    "KASUMI", Kasumi::default(), 8, ksa!();
+    "Kuznyechik", Kuznyechik::default(), 16, ksa!();
+    "LEA-128", Lea128::default(), 16, ksa!();
+    "LEA-192", Lea192::default(), 16, ksa!();
+    "LEA-256", Lea256::default(), 16, ksa!();
+    "Magma", Magma::default(), 8, ksa!();
+    "MISTY1", Misty1::default(), 8, ksa!();
+    "RC2", Rc2::default(), 8, |c, k| {
+        if k.is_empty() || k.len() > 128 {
+            return Err(GeneralError::key("RC2 key must be between 1 and 128 bytes"));
+        }
+        c.ksa(k);
+        Ok(())
+    };
+    "RC5-16", Rc5_16::default(), 4, |c, k| rc5_key(k).map(|k| c.ksa(k));
+    "RC5-32", Rc5_32::default(), 8, |c, k| rc5_key(k).map(|k| c.ksa(k));
+    "RC5-64", Rc5_64::default(), 16, |c, k| rc5_key(k).map(|k| c.ksa(k));
+    "RC6", Rc6::default(), 16, |c, k| {
+        match k.len() {
+            16 => c.ksa_128(k),
+            24 => c.ksa_192(k),
+            32 => c.ksa_256(k),
+            _ => return Err(GeneralError::key("RC6 key must be 16, 24, or 32 bytes")),
+        }
+        Ok(())
+    };
+    "SEED", Seed::default(), 16, ksa!();
+    "Serpent", Serpent::default(), 16, |c, k| {
+        match k.len() {
+            16 => c.ksa_128(fixed_key(k)?),
+            24 => c.ksa_192(fixed_key(k)?),
+            32 => c.ksa_256(fixed_key(k)?),
+            _ => return Err(GeneralError::key("Serpent key must be 16, 24, or 32 bytes")),
+        }
+        Ok(())
+    };
+    "Simon32/64", Simon32_64::default(), 4, ksa!();
+    "Simon48/72", Simon48_72::default(), 6, ksa!();
+    "Simon48/96", Simon48_96::default(), 6, ksa!();
+    "Simon64/96", Simon64_96::default(), 8, ksa!();
+    "Simon64/128", Simon64_128::default(), 8, ksa!();
+    "Simon96/96", Simon96_96::default(), 12, ksa!();
+    "Simon96/144", Simon96_144::default(), 12, ksa!();
+    "Simon128/128", Simon128_128::default(), 16, ksa!();
+    "Simon128/192", Simon128_192::default(), 16, ksa!();
+    "Simon128/256", Simon128_256::default(), 16, ksa!();
+    "SKINNY-64/128", Skinny64_128::default(), 8, ksa!();
+    "Skipjack", Skipjack::default(), 8, ksa!();
+    "SM4", Sm4::default(), 16, ksa!();
+    "Speck32/64", Speck32_64::default(), 4, ksa!();
+    "Speck48/72", Speck48_72::default(), 6, ksa!();
+    "Speck48/96", Speck48_96::default(), 6, ksa!();
+    "Speck64/96", Speck64_96::default(), 8, ksa!();
+    "Speck64/128", Speck64_128::default(), 8, ksa!();
+    "Speck96/96", Speck96_96::default(), 12, ksa!();
+    "Speck96/144", Speck96_144::default(), 12, ksa!();
+    "Speck128/128", Speck128_128::default(), 16, ksa!();
+    "Speck128/192", Speck128_192::default(), 16, ksa!();
+    "Speck128/256", Speck128_256::default(), 16, ksa!();
+    "TEA", Tea::default(), 8, ksa!();
+    "Triple DES", TripleDes::default(), 8, |c, k| {
+        let k: [u8; 24] = fixed_key(k)?;
+        c.ksa([
+            u64::from_be_bytes(k[0..8].try_into().unwrap()),
+            u64::from_be_bytes(k[8..16].try_into().unwrap()),
+            u64::from_be_bytes(k[16..24].try_into().unwrap()),
+        ])
+    };
+    "Twofish-128", Twofish128::default(), 16, ksa!();
+    "Twofish-192", Twofish192::default(), 16, ksa!();
+    "Twofish-256", Twofish256::default(), 16, ksa!();
+    "XTEA", Xtea::default(), 8, ksa!();
+);
+
+fn rc5_key(key: &[u8]) -> Result<&[u8], GeneralError> {
+    if key.len() > 255 {
+        Err(GeneralError::key("RC5 key is limited to 255 bytes"))
+    } else {
+        Ok(key)
+    }
+}
+
+/// A collection of block ciphers, each with its own key, that can be looked up by name.
+pub struct BlockCipherRegistry {
+    ciphers: BTreeMap<&'static str, Box<dyn DynBlockCipher>>,
+}
+
+impl Default for BlockCipherRegistry {
+    fn default() -> Self {
+        Self {
+            ciphers: BLOCK_CIPHER_NAMES
+                .iter()
+                .map(|name| (*name, block_cipher_by_name(name).unwrap()))
+                .collect(),
+        }
+    }
+}
+
+impl BlockCipherRegistry {
+    pub fn names(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.ciphers.keys().copied()
+    }
+
+    pub fn get(&self, name: &str) -> Option<&dyn DynBlockCipher> {
+        self.ciphers.get(name).map(|c| c.as_ref())
+    }
+
+    pub fn get_mut(&mut self, name: &str) -> Option<&mut Box<dyn DynBlockCipher>> {
+        self.ciphers.get_mut(name)
+    }
+
+    pub fn set_key(&mut self, name: &str, key: &[u8]) -> Result<(), GeneralError> {
+        self.ciphers
+            .get_mut(name)
+            .ok_or_else(|| GeneralError::general(format!("unknown block cipher {name}")))?
+            .set_key(key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use hex_literal::hex;
+    use strum::IntoEnumIterator;
+
+    #[test]
+    fn dyn_aes_matches_static() {
+        let mut registry = BlockCipherRegistry::default();
+        let key = hex!("000102030405060708090a0b0c0d0e0f");
+        registry.set_key("AES-128", &key).unwrap();
+        let cipher = registry.get("AES-128").unwrap();
+        assert_eq!(16, cipher.block_size());
+
+        // https://nvlpubs.nist.gov/nistpubs/FIPS/NIST.FIPS.197.pdf
+        let mut block = hex!("00112233445566778899aabbccddeeff");
+        cipher.encrypt_block(&mut block);
+        assert_eq!(hex!("69c4e0d86a7b0430d8cdb78070b4c55a"), block);
+    }
+
+    #[test]
+    fn dyn_modes_round_trip() {
+        for name in BLOCK_CIPHER_NAMES {
+            let mut cipher = block_cipher_by_name(name).unwrap();
+            // Use the shortest key the cipher accepts
+            assert!(
+                (1..=32).any(|len| cipher.set_key(&vec![0x2a; len]).is_ok()),
+                "{name} rejected every key"
+            );
+            let n = cipher.block_size();
+            let iv = vec![0x55; n];
+            for mode in BCMode::iter() {
+                let ptext = (0..3 * n as u8).collect::<Vec<u8>>();
+                let mut bytes = ptext.clone();
+                cipher
+                    .encrypt_bytes_with_mode(&mut bytes, mode, &iv)
+                    .unwrap();
+                assert_ne!(ptext, bytes, "{name} {mode}");
+                cipher
+                    .decrypt_bytes_with_mode(&mut bytes, mode, &iv)
+                    .unwrap();
+                assert_eq!(ptext, bytes, "{name} {mode}");
+            }
+        }
+    }
+
+    #[test]
+    fn dyn_key_errors() {
+        let mut registry = BlockCipherRegistry::default();
+        assert!(registry.set_key("AES-128", &[0; 15]).is_err());
+        assert!(registry.set_key("Serpent", &[0; 20]).is_err());
+        assert!(registry.set_key("Not A Cipher", &[0; 16]).is_err());
+        let cipher = registry.get("DES").unwrap();
+        assert!(cipher
+            .encrypt_bytes_with_mode(&mut [0; 8], BCMode::Cbc, &[0; 16])
+            .is_err());
+    }
+}
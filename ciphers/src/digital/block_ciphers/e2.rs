@@ -1,6 +1,10 @@
 use super::block_cipher::{BCMode, BCPadding, BlockCipher};
 use utils::byte_formatting::ByteFormat;
 
+// Unfinished. The S-box, the BP, IT and FT functions and the key schedule still have to be written from the NTT
+// specification and checked against its known answer tests for 128, 192 and 256 bit keys. Until then E2 has no GUI
+// frame and the test below is only a placeholder.
+
 fn it() {}
 
 fn ft() {}
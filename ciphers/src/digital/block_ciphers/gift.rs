@@ -4,20 +4,13 @@ use utils::byte_formatting::ByteFormat;
 // https://github.com/RustCrypto/block-ciphers/tree/master/gift/src
 // https://eprint.iacr.org/2020/412.pdf
 
-// const SBOX: [u8; 16] = [
-//     0x1, 0xa, 0x4, 0xc, 0x6, 0xf, 0x3, 0x9, 0x2, 0xd, 0xb, 0x7, 0x5, 0x0, 0x8, 0xe,
-// ];
-
-// const SBOX_INV: [u8; 16] = [
-//     0xd, 0x0, 0x8, 0x6, 0x2, 0xc, 0x4, 0xb, 0xe, 0x7, 0x1, 0xa, 0x3, 0x9, 0xf, 0x5,
-// ];
+const SBOX: [u64; 16] = [
+    0x1, 0xa, 0x4, 0xc, 0x6, 0xf, 0x3, 0x9, 0x2, 0xd, 0xb, 0x7, 0x5, 0x0, 0x8, 0xe,
+];
 
-// const PERM64: [[usize; 16]; 4] = [
-//     [0, 12, 8, 4, 1, 13, 9, 5, 2, 14, 10, 6, 3, 15, 11, 7],
-//     [4, 0, 12, 8, 5, 1, 13, 9, 6, 2, 14, 10, 7, 3, 15, 11],
-//     [8, 4, 0, 12, 9, 5, 1, 13, 10, 6, 2, 14, 11, 7, 3, 15],
-//     [12, 8, 4, 0, 13, 9, 5, 1, 14, 10, 6, 2, 15, 11, 7, 3],
-// ];
+const SBOX_INV: [u64; 16] = [
+    0xd, 0x0, 0x8, 0x6, 0x2, 0xc, 0x4, 0xb, 0xe, 0x7, 0x1, 0xa, 0x3, 0x9, 0xf, 0x5,
+];
 
 // const PERM128: [[usize; 32]; 4] = [
 //     [
@@ -280,50 +273,138 @@ pub fn quintuple_round_inv(x: &mut [u32; 4], round_keys: &[u32], index: usize) {
     sbox_inv(s3, s1, s2, s0);
 }
 
-// pub struct Gift64 {
-//     pub input_format: ByteFormat,
-//     pub output_format: ByteFormat,
-//     pub iv: u64,
-//     pub round_keys: [u32; 28],
-//     pub mode: BCMode,
-//     pub padding: BCPadding,
-// }
-
-// crate::block_cipher_builders! {Gift64, u64}
-
-// impl Default for Gift64 {
-//     fn default() -> Self {
-//         Self {
-//             input_format: ByteFormat::Hex,
-//             output_format: ByteFormat::Hex,
-//             iv: 0,
-//             round_keys: [0; 28],
-//             mode: Default::default(),
-//             padding: Default::default(),
-//         }
-//     }
-// }
-
-// impl Gift64 {
-//     pub fn ksa(&mut self, bytes: [u8; 16]) {}
-
-//     pub fn with_key(mut self, bytes: [u8; 16]) -> Self {
-//         self.ksa(bytes);
-//         self
-//     }
-// }
-
-// impl BlockCipher<8> for Gift64 {
-//     fn encrypt_block(&self, bytes: &mut [u8]) {
-//         for i in 0..28 {}
-//     }
-
-//     fn decrypt_block(&self, bytes: &mut [u8]) {
-//         for i in 0..28 {}
-//     }
-// }
-
-// crate::impl_cipher_for_block_cipher!(Gift64, 8);
+// Bit i of the input is moved to bit P64(i) of the output
+const fn perm64(i: usize) -> usize {
+    4 * (i / 16) + 16 * ((3 * ((i % 16) / 4) + (i % 4)) % 4) + (i % 4)
+}
+
+fn perm_bits_64(state: u64) -> u64 {
+    let mut out = 0;
+    for i in 0..64 {
+        out |= ((state >> i) & 1) << perm64(i);
+    }
+    out
+}
+
+fn perm_bits_64_inv(state: u64) -> u64 {
+    let mut out = 0;
+    for i in 0..64 {
+        out |= ((state >> perm64(i)) & 1) << i;
+    }
+    out
+}
+
+fn sub_cells_64(state: u64, sbox: &[u64; 16]) -> u64 {
+    let mut out = 0;
+    for i in 0..16 {
+        out |= sbox[((state >> (4 * i)) & 0xf) as usize] << (4 * i);
+    }
+    out
+}
+
+// The six bit affine LFSR that produces the round constants
+fn round_constants_64() -> [u64; 28] {
+    let mut c = 0_u64;
+    let mut out = [0; 28];
+    for rc in out.iter_mut() {
+        c = ((c << 1) & 0x3f) | (((c >> 5) ^ (c >> 4) ^ 1) & 1);
+        *rc = c;
+    }
+    out
+}
+
+// The round key is made from the two lowest words of the key state and XORed into bits 1 and 0 of each nibble along
+// with the round constant, which goes into bit 3 of the lowest six nibbles and the top bit of the state
+fn round_key_64(key: &[u16; 8], rc: u64) -> u64 {
+    let mut rk = 1 << 63;
+    for i in 0..16 {
+        rk |= ((key[1] as u64 >> i) & 1) << (4 * i + 1);
+        rk |= ((key[0] as u64 >> i) & 1) << (4 * i);
+    }
+    for i in 0..6 {
+        rk |= ((rc >> i) & 1) << (4 * i + 3);
+    }
+    rk
+}
+
+// The key state is k7 down to k0 with k0 being the lowest 16 bits of the key
+fn update_key_64(key: &mut [u16; 8]) {
+    let k0 = key[0].rotate_right(12);
+    let k1 = key[1].rotate_right(2);
+    key.rotate_left(2);
+    key[6] = k0;
+    key[7] = k1;
+}
+
+pub struct Gift64 {
+    pub input_format: ByteFormat,
+    pub output_format: ByteFormat,
+    pub iv: u64,
+    pub round_keys: [u64; 28],
+    pub mode: BCMode,
+    pub padding: BCPadding,
+}
+
+crate::block_cipher_builders! {Gift64, u64}
+
+// The round constants are part of the round keys so the all zero key still needs the key schedule
+impl Default for Gift64 {
+    fn default() -> Self {
+        Self {
+            input_format: ByteFormat::Hex,
+            output_format: ByteFormat::Hex,
+            iv: 0,
+            round_keys: [0; 28],
+            mode: Default::default(),
+            padding: Default::default(),
+        }
+        .with_key([0; 16])
+    }
+}
+
+impl Gift64 {
+    pub fn ksa(&mut self, bytes: [u8; 16]) {
+        let mut key = [0_u16; 8];
+        for (i, k) in key.iter_mut().enumerate() {
+            *k = u16::from_be_bytes([bytes[14 - 2 * i], bytes[15 - 2 * i]]);
+        }
+        for (rk, rc) in self.round_keys.iter_mut().zip(round_constants_64()) {
+            *rk = round_key_64(&key, rc);
+            update_key_64(&mut key);
+        }
+    }
+
+    pub fn with_key(mut self, bytes: [u8; 16]) -> Self {
+        self.ksa(bytes);
+        self
+    }
+}
+
+impl BlockCipher<8> for Gift64 {
+    fn encrypt_block(&self, bytes: &mut [u8]) {
+        let mut state = u64::from_be_bytes(bytes[0..8].try_into().unwrap());
+        for rk in self.round_keys {
+            state = sub_cells_64(state, &SBOX);
+            state = perm_bits_64(state);
+            state ^= rk;
+        }
+        bytes[0..8].copy_from_slice(&state.to_be_bytes());
+    }
+
+    fn decrypt_block(&self, bytes: &mut [u8]) {
+        let mut state = u64::from_be_bytes(bytes[0..8].try_into().unwrap());
+        for rk in self.round_keys.into_iter().rev() {
+            state ^= rk;
+            state = perm_bits_64_inv(state);
+            state = sub_cells_64(state, &SBOX_INV);
+        }
+        bytes[0..8].copy_from_slice(&state.to_be_bytes());
+    }
+
+    crate::block_cipher_getters!();
+}
+
+crate::impl_cipher_for_block_cipher!(Gift64, 8);
 
 pub struct Gift128 {
     pub input_format: ByteFormat,
@@ -385,13 +466,6 @@ impl BlockCipher<16> for Gift128 {
 
 crate::impl_cipher_for_block_cipher!(Gift128, 16);
 
-// #[cfg(test)]
-// mod gift_tests {
-
-//     use super::*;
-
-// }
-
 crate::test_block_cipher!(
     test_1, Gift128::default().with_key([0;16]),
     [0; 16],
@@ -405,3 +479,39 @@ crate::test_block_cipher!(
     [0xe3, 0x9c, 0x14, 0x1f, 0xa5, 0x7d, 0xba, 0x43, 0xf0, 0x8a, 0x85, 0xb6, 0xa9, 0x1f, 0x86, 0xc1],
     [0x13, 0xed, 0xe6, 0x7c, 0xbd, 0xcc, 0x3d, 0xbf, 0x40, 0x0a, 0x62, 0xd6, 0x97, 0x72, 0x65, 0xea];
 );
+
+#[cfg(test)]
+mod gift64_tests {
+
+    use super::*;
+    use hex_literal::hex;
+
+    // Test vectors from the designers' reference implementation
+    #[test]
+    fn gift64_vectors() {
+        for (key, ptext, ctext) in [
+            (
+                hex!("00000000000000000000000000000000"),
+                hex!("0000000000000000"),
+                hex!("f62bc3ef34f775ac"),
+            ),
+            (
+                hex!("fedcba9876543210fedcba9876543210"),
+                hex!("fedcba9876543210"),
+                hex!("c1b71f66160ff587"),
+            ),
+            (
+                hex!("bd91731eb6bc2713a1f9f6ffc75044e7"),
+                hex!("c450c7727a9b8a7d"),
+                hex!("e3272885fa94ba8b"),
+            ),
+        ] {
+            let cipher = Gift64::default().with_key(key);
+            let mut block = ptext;
+            cipher.encrypt_block(&mut block);
+            assert_eq!(ctext, block);
+            cipher.decrypt_block(&mut block);
+            assert_eq!(ptext, block);
+        }
+    }
+}
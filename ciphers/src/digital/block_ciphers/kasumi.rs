@@ -1,12 +1,75 @@
-use utils::byte_formatting::ByteFormat;
-use super::block_cipher::{BCMode, BCPadding};
+use super::block_cipher::{BCMode, BCPadding, BlockCipher};
+use utils::byte_formatting::{make_u32s_be, u32s_to_bytes_be, ByteFormat};
 
+// https://www.etsi.org/deliver/etsi_ts/135200_135299/135202/
+
+const S7: [u16; 128] = [
+    54, 50, 62, 56, 22, 34, 94, 96, 38, 6, 63, 93, 2, 18, 123, 33, 55, 113, 39, 114, 21, 67, 65,
+    12, 47, 73, 46, 27, 25, 111, 124, 81, 53, 9, 121, 79, 52, 60, 58, 48, 101, 127, 40, 120, 104,
+    70, 71, 43, 20, 122, 72, 61, 23, 109, 13, 100, 77, 1, 16, 7, 82, 10, 105, 98, 117, 116, 76, 11,
+    89, 106, 0, 125, 118, 99, 86, 69, 30, 57, 126, 87, 112, 51, 17, 5, 95, 14, 90, 84, 91, 8, 35,
+    103, 32, 97, 28, 66, 102, 31, 26, 45, 75, 4, 85, 92, 37, 74, 80, 49, 68, 29, 115, 44, 64, 107,
+    108, 24, 110, 83, 36, 78, 42, 19, 15, 41, 88, 119, 59, 3,
+];
+
+const S9: [u16; 512] = [
+    167, 239, 161, 379, 391, 334, 9, 338, 38, 226, 48, 358, 452, 385, 90, 397, 183, 253, 147, 331,
+    415, 340, 51, 362, 306, 500, 262, 82, 216, 159, 356, 177, 175, 241, 489, 37, 206, 17, 0, 333,
+    44, 254, 378, 58, 143, 220, 81, 400, 95, 3, 315, 245, 54, 235, 218, 405, 472, 264, 172, 494,
+    371, 290, 399, 76, 165, 197, 395, 121, 257, 480, 423, 212, 240, 28, 462, 176, 406, 507, 288,
+    223, 501, 407, 249, 265, 89, 186, 221, 428, 164, 74, 440, 196, 458, 421, 350, 163, 232, 158,
+    134, 354, 13, 250, 491, 142, 191, 69, 193, 425, 152, 227, 366, 135, 344, 300, 276, 242, 437,
+    320, 113, 278, 11, 243, 87, 317, 36, 93, 496, 27, 487, 446, 482, 41, 68, 156, 457, 131, 326,
+    403, 339, 20, 39, 115, 442, 124, 475, 384, 508, 53, 112, 170, 479, 151, 126, 169, 73, 268, 279,
+    321, 168, 364, 363, 292, 46, 499, 393, 327, 324, 24, 456, 267, 157, 460, 488, 426, 309, 229,
+    439, 506, 208, 271, 349, 401, 434, 236, 16, 209, 359, 52, 56, 120, 199, 277, 465, 416, 252,
+    287, 246, 6, 83, 305, 420, 345, 153, 502, 65, 61, 244, 282, 173, 222, 418, 67, 386, 368, 261,
+    101, 476, 291, 195, 430, 49, 79, 166, 330, 280, 383, 373, 128, 382, 408, 155, 495, 367, 388,
+    274, 107, 459, 417, 62, 454, 132, 225, 203, 316, 234, 14, 301, 91, 503, 286, 424, 211, 347,
+    307, 140, 374, 35, 103, 125, 427, 19, 214, 453, 146, 498, 314, 444, 230, 256, 329, 198, 285,
+    50, 116, 78, 410, 10, 205, 510, 171, 231, 45, 139, 467, 29, 86, 505, 32, 72, 26, 342, 150, 313,
+    490, 431, 238, 411, 325, 149, 473, 40, 119, 174, 355, 185, 233, 389, 71, 448, 273, 372, 55,
+    110, 178, 322, 12, 469, 392, 369, 190, 1, 109, 375, 137, 181, 88, 75, 308, 260, 484, 98, 272,
+    370, 275, 412, 111, 336, 318, 4, 504, 492, 259, 304, 77, 337, 435, 21, 357, 303, 332, 483, 18,
+    47, 85, 25, 497, 474, 289, 100, 269, 296, 478, 270, 106, 31, 104, 433, 84, 414, 486, 394, 96,
+    99, 154, 511, 148, 413, 361, 409, 255, 162, 215, 302, 201, 266, 351, 343, 144, 441, 365, 108,
+    298, 251, 34, 182, 509, 138, 210, 335, 133, 311, 352, 328, 141, 396, 346, 123, 319, 450, 281,
+    429, 228, 443, 481, 92, 404, 485, 422, 248, 297, 23, 213, 130, 466, 22, 217, 283, 70, 294, 360,
+    419, 127, 312, 377, 7, 468, 194, 2, 117, 295, 463, 258, 224, 447, 247, 187, 80, 398, 284, 353,
+    105, 390, 299, 471, 470, 184, 57, 200, 348, 63, 204, 188, 33, 451, 97, 30, 310, 219, 94, 160,
+    129, 493, 64, 179, 263, 102, 189, 207, 114, 402, 438, 477, 387, 122, 192, 42, 381, 5, 145, 118,
+    180, 449, 293, 323, 136, 380, 43, 66, 60, 455, 341, 445, 202, 432, 8, 237, 15, 376, 436, 464,
+    59, 461,
+];
+
+const C: [u16; 8] = [
+    0x0123, 0x4567, 0x89ab, 0xcdef, 0xfedc, 0xba98, 0x7654, 0x3210,
+];
+
+// The nonlinear function FI on 16 bits, split unequally into nine and seven bit halves
+fn fi(x: u16, key: u16) -> u16 {
+    let mut d9 = x >> 7;
+    let mut d7 = x & 0x7f;
+    d9 = S9[d9 as usize] ^ d7;
+    d7 = S7[d7 as usize] ^ (d9 & 0x7f);
+    d7 ^= key >> 9;
+    d9 ^= key & 0x1ff;
+    d9 = S9[d9 as usize] ^ d7;
+    d7 = S7[d7 as usize] ^ (d9 & 0x7f);
+    (d7 << 9) | d9
+}
+
+/// Kasumi, the 64-bit block cipher used by the 3GPP confidentiality and integrity algorithms. It is a simplified
+/// variant of MISTY1 with a 128-bit key.
 pub struct Kasumi {
     pub input_format: ByteFormat,
     pub output_format: ByteFormat,
     pub iv: u64,
     pub mode: BCMode,
     pub padding: BCPadding,
+    pub kl: [[u16; 2]; 8],
+    pub ko: [[u16; 3]; 8],
+    pub ki: [[u16; 3]; 8],
 }
 
 impl Default for Kasumi {
@@ -17,8 +80,121 @@ impl Default for Kasumi {
             iv: 0,
             mode: Default::default(),
             padding: Default::default(),
+            kl: [[0; 2]; 8],
+            ko: [[0; 3]; 8],
+            ki: [[0; 3]; 8],
+        }
+    }
+}
+
+crate::block_cipher_builders! {Kasumi, u64}
+
+impl Kasumi {
+    pub fn ksa(&mut self, bytes: [u8; 16]) {
+        let mut k = [0_u16; 8];
+        for i in 0..8 {
+            k[i] = u16::from_be_bytes([bytes[2 * i], bytes[2 * i + 1]]);
+        }
+        let mut k_prime = [0_u16; 8];
+        for i in 0..8 {
+            k_prime[i] = k[i] ^ C[i];
+        }
+        for i in 0..8 {
+            self.kl[i] = [k[i].rotate_left(1), k_prime[(i + 2) % 8]];
+            self.ko[i] = [
+                k[(i + 1) % 8].rotate_left(5),
+                k[(i + 5) % 8].rotate_left(8),
+                k[(i + 6) % 8].rotate_left(13),
+            ];
+            self.ki[i] = [
+                k_prime[(i + 4) % 8],
+                k_prime[(i + 3) % 8],
+                k_prime[(i + 7) % 8],
+            ];
+        }
+    }
+
+    pub fn with_key(mut self, bytes: [u8; 16]) -> Self {
+        self.ksa(bytes);
+        self
+    }
+
+    pub fn ksa_u32(&mut self, key: [u32; 4]) {
+        let mut bytes = [0u8; 16];
+        u32s_to_bytes_be(&mut bytes, key);
+        self.ksa(bytes)
+    }
+
+    pub fn with_key_u32(mut self, key: [u32; 4]) -> Self {
+        self.ksa_u32(key);
+        self
+    }
+
+    fn fo(&self, x: u32, round: usize) -> u32 {
+        let (mut l, mut r) = ((x >> 16) as u16, x as u16);
+        let ko = self.ko[round];
+        let ki = self.ki[round];
+        for i in 0..3 {
+            l = fi(l ^ ko[i], ki[i]) ^ r;
+            (l, r) = (r, l);
+        }
+        ((l as u32) << 16) | r as u32
+    }
+
+    fn fl(&self, x: u32, round: usize) -> u32 {
+        let (mut l, mut r) = ((x >> 16) as u16, x as u16);
+        r ^= (l & self.kl[round][0]).rotate_left(1);
+        l ^= (r | self.kl[round][1]).rotate_left(1);
+        ((l as u32) << 16) | r as u32
+    }
+
+    // Odd numbered rounds (counting from one) apply FL then FO while even rounds apply FO then FL
+    fn f(&self, x: u32, round: usize) -> u32 {
+        if round.is_multiple_of(2) {
+            self.fo(self.fl(x, round), round)
+        } else {
+            self.fl(self.fo(x, round), round)
         }
     }
 }
 
-crate::block_cipher_builders! {Kasumi, u64}
\ No newline at end of file
+impl BlockCipher<8> for Kasumi {
+    fn encrypt_block(&self, bytes: &mut [u8]) {
+        let [mut l, mut r] = make_u32s_be::<2>(bytes);
+        for round in 0..8 {
+            (l, r) = (r ^ self.f(l, round), l);
+        }
+        u32s_to_bytes_be(bytes, [l, r]);
+    }
+
+    fn decrypt_block(&self, bytes: &mut [u8]) {
+        let [mut l, mut r] = make_u32s_be::<2>(bytes);
+        for round in (0..8).rev() {
+            (l, r) = (r, l ^ self.f(r, round));
+        }
+        u32s_to_bytes_be(bytes, [l, r]);
+    }
+
+    crate::block_cipher_getters!();
+}
+
+crate::impl_cipher_for_block_cipher!(Kasumi, 8);
+
+// Test vectors from LibTomCrypt
+crate::test_block_cipher!(
+    test_1, Kasumi::default().with_key(hex_literal::hex!("80000000000000000000000000000000")),
+    [0; 8],
+    hex_literal::hex!("4b58a771afc7e5e8");
+    test_2, Kasumi::default().with_key(hex_literal::hex!("00800000000000000000000000000000")),
+    [0; 8],
+    hex_literal::hex!("7eef113c95bb5a77");
+    test_3, Kasumi::default().with_key(hex_literal::hex!("00008000000000000000000000000000")),
+    [0; 8],
+    hex_literal::hex!("5f140686d7ad5a39");
+    test_4, Kasumi::default().with_key(hex_literal::hex!("00000000000000000000000000000001")),
+    [0; 8],
+    hex_literal::hex!("2e1491cf70aa465d");
+    test_5, Kasumi::default().with_key(hex_literal::hex!("00000000000000000000000000000100")),
+    [0; 8],
+    hex_literal::hex!("b54586f4ab9ae546");
+);
@@ -0,0 +1,393 @@
+use super::{
+    aes::aes::{Aes128, Aes192, Aes256},
+    block_cipher::BlockCipher,
+    des::triple_des::TripleDes,
+};
+use utils::{
+    byte_formatting::{xor_into_bytes, ByteFormat},
+    errors::GeneralError,
+};
+
+/// Default initial value for KW (RFC 3394)
+pub const ICV1: [u8; 8] = [0xa6; 8];
+/// Prefix of the alternative initial value for KWP (RFC 5649)
+pub const ICV2: [u8; 4] = [0xa6, 0x59, 0x59, 0xa6];
+/// Default initial value for TKW (SP 800-38F)
+pub const ICV3: [u8; 4] = [0xa6; 4];
+
+// XOR the step counter into the integrity register. The counter is big endian
+// and has the same width as the register, half the block size.
+fn xor_step<const N: usize>(a: &mut [u8], t: u64) {
+    let t = t.to_be_bytes();
+    xor_into_bytes(a, &t[8 - N / 2..]);
+}
+
+/// The wrapping function W from NIST SP 800-38F. The input is split into semiblocks of half the block size, the first
+/// of which acts as the integrity register. At least three semiblocks are required.
+pub fn w<C: BlockCipher<N>, const N: usize>(cipher: &C, bytes: &mut [u8]) {
+    let h = N / 2;
+    assert!(bytes.len().is_multiple_of(h) && bytes.len() / h >= 3);
+    let n = bytes.len() / h;
+    let mut block = [0u8; N];
+    let mut t = 1;
+    for _ in 0..6 {
+        for i in 1..n {
+            block[..h].copy_from_slice(&bytes[..h]);
+            block[h..].copy_from_slice(&bytes[i * h..(i + 1) * h]);
+            cipher.encrypt_block(&mut block);
+            xor_step::<N>(&mut block[..h], t);
+            bytes[..h].copy_from_slice(&block[..h]);
+            bytes[i * h..(i + 1) * h].copy_from_slice(&block[h..]);
+            t += 1;
+        }
+    }
+}
+
+/// The unwrapping function W^-1 from NIST SP 800-38F, the exact inverse of W.
+pub fn w_inv<C: BlockCipher<N>, const N: usize>(cipher: &C, bytes: &mut [u8]) {
+    let h = N / 2;
+    assert!(bytes.len().is_multiple_of(h) && bytes.len() / h >= 3);
+    let n = bytes.len() / h;
+    let mut block = [0u8; N];
+    let mut t = 6 * (n as u64 - 1);
+    for _ in 0..6 {
+        for i in (1..n).rev() {
+            block[..h].copy_from_slice(&bytes[..h]);
+            xor_step::<N>(&mut block[..h], t);
+            block[h..].copy_from_slice(&bytes[i * h..(i + 1) * h]);
+            cipher.decrypt_block(&mut block);
+            bytes[..h].copy_from_slice(&block[..h]);
+            bytes[i * h..(i + 1) * h].copy_from_slice(&block[h..]);
+            t -= 1;
+        }
+    }
+}
+
+/// Key Wrap (RFC 3394, SP 800-38F KW-AE). The key must be at least 16 bytes and a multiple of 8 bytes.
+pub fn kw_wrap<C: BlockCipher<16>>(cipher: &C, key: &[u8]) -> Result<Vec<u8>, GeneralError> {
+    if key.len() < 16 || !key.len().is_multiple_of(8) {
+        return Err(GeneralError::input(
+            "KW requires a key of at least 16 bytes that is a multiple of 8 bytes",
+        ));
+    }
+    let mut out = ICV1.to_vec();
+    out.extend_from_slice(key);
+    w(cipher, &mut out);
+    Ok(out)
+}
+
+/// Key Unwrap (RFC 3394, SP 800-38F KW-AD). Fails if the integrity check value does not match.
+pub fn kw_unwrap<C: BlockCipher<16>>(cipher: &C, wrapped: &[u8]) -> Result<Vec<u8>, GeneralError> {
+    if wrapped.len() < 24 || !wrapped.len().is_multiple_of(8) {
+        return Err(GeneralError::input(
+            "KW ciphertext must be at least 24 bytes and a multiple of 8 bytes",
+        ));
+    }
+    let mut out = wrapped.to_vec();
+    w_inv(cipher, &mut out);
+    if out[..8] != ICV1 {
+        return Err(GeneralError::input("integrity check failed"));
+    }
+    Ok(out.split_off(8))
+}
+
+/// Key Wrap with Padding (RFC 5649, SP 800-38F KWP-AE). The key may be any length from 1 byte to 2^32 - 1 bytes.
+pub fn kwp_wrap<C: BlockCipher<16>>(cipher: &C, key: &[u8]) -> Result<Vec<u8>, GeneralError> {
+    if key.is_empty() || key.len() > u32::MAX as usize {
+        return Err(GeneralError::input(
+            "KWP requires a key between 1 and 2^32 - 1 bytes",
+        ));
+    }
+    // The alternative initial value encodes the message length indicator
+    let mut out = ICV2.to_vec();
+    out.extend_from_slice(&(key.len() as u32).to_be_bytes());
+    out.extend_from_slice(key);
+    while !out.len().is_multiple_of(8) {
+        out.push(0)
+    }
+
+    // A single semiblock of key is encrypted directly as one block
+    if out.len() == 16 {
+        cipher.encrypt_block(&mut out);
+    } else {
+        w(cipher, &mut out);
+    }
+    Ok(out)
+}
+
+/// Key Unwrap with Padding (RFC 5649, SP 800-38F KWP-AD). Fails if the integrity check value, the length indicator,
+/// or the padding is invalid.
+pub fn kwp_unwrap<C: BlockCipher<16>>(cipher: &C, wrapped: &[u8]) -> Result<Vec<u8>, GeneralError> {
+    if wrapped.len() < 16 || !wrapped.len().is_multiple_of(8) {
+        return Err(GeneralError::input(
+            "KWP ciphertext must be at least 16 bytes and a multiple of 8 bytes",
+        ));
+    }
+    let mut out = wrapped.to_vec();
+    if out.len() == 16 {
+        cipher.decrypt_block(&mut out);
+    } else {
+        w_inv(cipher, &mut out);
+    }
+
+    if out[..4] != ICV2 {
+        return Err(GeneralError::input("integrity check failed"));
+    }
+    let mli = u32::from_be_bytes(out[4..8].try_into().unwrap()) as usize;
+    let padded_len = out.len() - 8;
+    if mli > padded_len || mli + 8 <= padded_len {
+        return Err(GeneralError::input("integrity check failed"));
+    }
+    if out[8 + mli..].iter().any(|b| *b != 0) {
+        return Err(GeneralError::input("integrity check failed"));
+    }
+    out.truncate(8 + mli);
+    Ok(out.split_off(8))
+}
+
+/// TDEA Key Wrap (SP 800-38F TKW-AE). The key must be at least 8 bytes and a multiple of 4 bytes.
+pub fn tkw_wrap<C: BlockCipher<8>>(cipher: &C, key: &[u8]) -> Result<Vec<u8>, GeneralError> {
+    if key.len() < 8 || !key.len().is_multiple_of(4) {
+        return Err(GeneralError::input(
+            "TKW requires a key of at least 8 bytes that is a multiple of 4 bytes",
+        ));
+    }
+    let mut out = ICV3.to_vec();
+    out.extend_from_slice(key);
+    w(cipher, &mut out);
+    Ok(out)
+}
+
+/// TDEA Key Unwrap (SP 800-38F TKW-AD). Fails if the integrity check value does not match.
+pub fn tkw_unwrap<C: BlockCipher<8>>(cipher: &C, wrapped: &[u8]) -> Result<Vec<u8>, GeneralError> {
+    if wrapped.len() < 12 || !wrapped.len().is_multiple_of(4) {
+        return Err(GeneralError::input(
+            "TKW ciphertext must be at least 12 bytes and a multiple of 4 bytes",
+        ));
+    }
+    let mut out = wrapped.to_vec();
+    w_inv(cipher, &mut out);
+    if out[..4] != ICV3 {
+        return Err(GeneralError::input("integrity check failed"));
+    }
+    Ok(out.split_off(4))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyWrapMode {
+    Kw,
+    Kwp,
+    Tkw,
+}
+
+impl std::fmt::Display for KeyWrapMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Kw => write!(f, "AES-KW"),
+            Self::Kwp => write!(f, "AES-KWP"),
+            Self::Tkw => write!(f, "TKW"),
+        }
+    }
+}
+
+/// Wraps and unwraps keys with a key encryption key (KEK). For the AES modes the KEK is 16, 24, or 32 bytes and
+/// selects AES-128, AES-192, or AES-256. For TKW the KEK is 24 bytes, interpreted as three DES keys.
+pub struct KeyWrap {
+    pub input_format: ByteFormat,
+    pub output_format: ByteFormat,
+    pub kek: Vec<u8>,
+    pub mode: KeyWrapMode,
+}
+
+impl Default for KeyWrap {
+    fn default() -> Self {
+        Self {
+            input_format: ByteFormat::Hex,
+            output_format: ByteFormat::Hex,
+            kek: vec![0; 16],
+            mode: KeyWrapMode::Kw,
+        }
+    }
+}
+
+impl KeyWrap {
+    pub fn with_kek(mut self, kek: &[u8]) -> Self {
+        self.kek = kek.to_vec();
+        self
+    }
+
+    pub fn with_mode(mut self, mode: KeyWrapMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    fn tdea(&self) -> Result<TripleDes, GeneralError> {
+        if self.kek.len() != 24 {
+            return Err(GeneralError::key("TKW requires a KEK of 24 bytes"));
+        }
+        // TripleDes encrypts with its last key first so the keys are reversed to get E(K3, D(K2, E(K1, P))) as in
+        // SP 800-67, with the KEK read as K1 || K2 || K3
+        let mut keys = [0u64; 3];
+        for (key, chunk) in keys.iter_mut().rev().zip(self.kek.chunks_exact(8)) {
+            *key = u64::from_be_bytes(chunk.try_into().unwrap());
+        }
+        let mut cipher = TripleDes::default();
+        cipher.ksa(keys)?;
+        Ok(cipher)
+    }
+
+    fn aes_wrap<C: BlockCipher<16>>(&self, cipher: C, key: &[u8]) -> Result<Vec<u8>, GeneralError> {
+        match self.mode {
+            KeyWrapMode::Kwp => kwp_wrap(&cipher, key),
+            _ => kw_wrap(&cipher, key),
+        }
+    }
+
+    fn aes_unwrap<C: BlockCipher<16>>(
+        &self,
+        cipher: C,
+        wrapped: &[u8],
+    ) -> Result<Vec<u8>, GeneralError> {
+        match self.mode {
+            KeyWrapMode::Kwp => kwp_unwrap(&cipher, wrapped),
+            _ => kw_unwrap(&cipher, wrapped),
+        }
+    }
+
+    pub fn wrap(&self, key: &[u8]) -> Result<Vec<u8>, GeneralError> {
+        let kek = self.kek.clone();
+        match (self.mode, kek.len()) {
+            (KeyWrapMode::Tkw, _) => tkw_wrap(&self.tdea()?, key),
+            (_, 16) => self.aes_wrap(Aes128::default().with_key(kek.try_into().unwrap()), key),
+            (_, 24) => self.aes_wrap(Aes192::default().with_key(kek.try_into().unwrap()), key),
+            (_, 32) => self.aes_wrap(Aes256::default().with_key(kek.try_into().unwrap()), key),
+            _ => Err(GeneralError::key("AES KEK must be 16, 24, or 32 bytes")),
+        }
+    }
+
+    pub fn unwrap(&self, wrapped: &[u8]) -> Result<Vec<u8>, GeneralError> {
+        let kek = self.kek.clone();
+        match (self.mode, kek.len()) {
+            (KeyWrapMode::Tkw, _) => tkw_unwrap(&self.tdea()?, wrapped),
+            (_, 16) => {
+                self.aes_unwrap(Aes128::default().with_key(kek.try_into().unwrap()), wrapped)
+            }
+            (_, 24) => {
+                self.aes_unwrap(Aes192::default().with_key(kek.try_into().unwrap()), wrapped)
+            }
+            (_, 32) => {
+                self.aes_unwrap(Aes256::default().with_key(kek.try_into().unwrap()), wrapped)
+            }
+            _ => Err(GeneralError::key("AES KEK must be 16, 24, or 32 bytes")),
+        }
+    }
+}
+
+impl crate::Cipher for KeyWrap {
+    fn encrypt(&self, text: &str) -> Result<String, GeneralError> {
+        let bytes = self
+            .input_format
+            .text_to_bytes(text)
+            .map_err(GeneralError::input)?;
+        Ok(self.output_format.byte_slice_to_text(&self.wrap(&bytes)?))
+    }
+
+    fn decrypt(&self, text: &str) -> Result<String, GeneralError> {
+        let bytes = self
+            .input_format
+            .text_to_bytes(text)
+            .map_err(GeneralError::input)?;
+        Ok(self.output_format.byte_slice_to_text(&self.unwrap(&bytes)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use hex_literal::hex;
+
+    // https://datatracker.ietf.org/doc/html/rfc3394#section-4
+    #[test]
+    fn rfc3394_kw() {
+        let kek128 = hex!("000102030405060708090A0B0C0D0E0F");
+        let kek192 = hex!("000102030405060708090A0B0C0D0E0F1011121314151617");
+        let kek256 = hex!("000102030405060708090A0B0C0D0E0F101112131415161718191A1B1C1D1E1F");
+        let key128 = hex!("00112233445566778899AABBCCDDEEFF");
+        let key192 = hex!("00112233445566778899AABBCCDDEEFF0001020304050607");
+        let key256 = hex!("00112233445566778899AABBCCDDEEFF000102030405060708090A0B0C0D0E0F");
+
+        for (kek, key, wrapped) in [
+            (&kek128[..], &key128[..], &hex!("1FA68B0A8112B447AEF34BD8FB5A7B829D3E862371D2CFE5")[..]),
+            (&kek192[..], &key128[..], &hex!("96778B25AE6CA435F92B5B97C050AED2468AB8A17AD84E5D")[..]),
+            (&kek256[..], &key128[..], &hex!("64E8C3F9CE0F5BA263E9777905818A2A93C8191E7D6E8AE7")[..]),
+            (&kek192[..], &key192[..], &hex!("031D33264E15D33268F24EC260743EDCE1C6C7DDEE725A936BA814915C6762D2")[..]),
+            (&kek256[..], &key192[..], &hex!("A8F9BC1612C68B3FF6E6F4FBE30E71E4769C8B80A32CB8958CD5D17D6B254DA1")[..]),
+            (&kek256[..], &key256[..], &hex!("28C9F404C4B810F4CBCCB35CFB87F8263F5786E2D80ED326CBC7F0E71A99F43BFB988B9B7A02DD21")[..]),
+        ] {
+            let cipher = KeyWrap::default().with_kek(kek);
+            assert_eq!(wrapped, cipher.wrap(key).unwrap());
+            assert_eq!(key, cipher.unwrap(wrapped).unwrap());
+        }
+    }
+
+    // https://datatracker.ietf.org/doc/html/rfc5649#section-6
+    #[test]
+    fn rfc5649_kwp() {
+        let cipher = KeyWrap::default()
+            .with_kek(&hex!("5840df6e29b02af1ab493b705bf16ea1ae8338f4dcc176a8"))
+            .with_mode(KeyWrapMode::Kwp);
+        for (key, wrapped) in [
+            (
+                &hex!("c37b7e6492584340bed12207808941155068f738")[..],
+                &hex!("138bdeaa9b8fa7fc61f97742e72248ee5ae6ae5360d1ae6a5f54f373fa543b6a")[..],
+            ),
+            (
+                &hex!("466f7250617369")[..],
+                &hex!("afbeb0f07dfbf5419200f2ccb50bb24f")[..],
+            ),
+        ] {
+            assert_eq!(wrapped, cipher.wrap(key).unwrap());
+            assert_eq!(key, cipher.unwrap(wrapped).unwrap());
+        }
+    }
+
+    // No CAVP TKW file was available, these were computed with the TDEA of OpenSSL and a separate implementation
+    // of the W function so they check this code against something other than itself
+    #[test]
+    fn tkw_known_answer() {
+        let cipher = KeyWrap::default()
+            .with_kek(&hex!("0123456789abcdef23456789abcdef01456789abcdef0123"))
+            .with_mode(KeyWrapMode::Tkw);
+        for (key, wrapped) in [
+            (
+                &hex!("00112233445566778899aabbccddeeff00112233")[..],
+                &hex!("31513f42a4da8da276b049ebaac0ee466ef3d0f79d34efe5")[..],
+            ),
+            (
+                &hex!("0011223344556677")[..],
+                &hex!("5f72f34727157ee1caa002b9")[..],
+            ),
+        ] {
+            assert_eq!(wrapped, cipher.wrap(key).unwrap());
+            assert_eq!(key, cipher.unwrap(wrapped).unwrap());
+        }
+    }
+
+    #[test]
+    fn integrity_check() {
+        for mode in [KeyWrapMode::Kw, KeyWrapMode::Kwp, KeyWrapMode::Tkw] {
+            let cipher = KeyWrap::default()
+                .with_kek(&hex!("0123456789abcdef23456789abcdef01456789abcdef0123"))
+                .with_mode(mode);
+            let mut wrapped = cipher
+                .wrap(&hex!("00112233445566778899aabbccddeeff"))
+                .unwrap();
+            wrapped[3] ^= 0x01;
+            assert!(
+                cipher.unwrap(&wrapped).is_err(),
+                "{mode} accepted a corrupted key"
+            );
+        }
+    }
+}
@@ -0,0 +1,240 @@
+use super::block_cipher::{BCMode, BCPadding, BlockCipher};
+use utils::byte_formatting::{xor_into_bytes, ByteFormat};
+
+// The nonlinear bijection pi from GOST R 34.12-2015 and its inverse
+const PI: [u8; 256] = [
+    0xfc, 0xee, 0xdd, 0x11, 0xcf, 0x6e, 0x31, 0x16, 0xfb, 0xc4, 0xfa, 0xda, 0x23, 0xc5, 0x04, 0x4d,
+    0xe9, 0x77, 0xf0, 0xdb, 0x93, 0x2e, 0x99, 0xba, 0x17, 0x36, 0xf1, 0xbb, 0x14, 0xcd, 0x5f, 0xc1,
+    0xf9, 0x18, 0x65, 0x5a, 0xe2, 0x5c, 0xef, 0x21, 0x81, 0x1c, 0x3c, 0x42, 0x8b, 0x01, 0x8e, 0x4f,
+    0x05, 0x84, 0x02, 0xae, 0xe3, 0x6a, 0x8f, 0xa0, 0x06, 0x0b, 0xed, 0x98, 0x7f, 0xd4, 0xd3, 0x1f,
+    0xeb, 0x34, 0x2c, 0x51, 0xea, 0xc8, 0x48, 0xab, 0xf2, 0x2a, 0x68, 0xa2, 0xfd, 0x3a, 0xce, 0xcc,
+    0xb5, 0x70, 0x0e, 0x56, 0x08, 0x0c, 0x76, 0x12, 0xbf, 0x72, 0x13, 0x47, 0x9c, 0xb7, 0x5d, 0x87,
+    0x15, 0xa1, 0x96, 0x29, 0x10, 0x7b, 0x9a, 0xc7, 0xf3, 0x91, 0x78, 0x6f, 0x9d, 0x9e, 0xb2, 0xb1,
+    0x32, 0x75, 0x19, 0x3d, 0xff, 0x35, 0x8a, 0x7e, 0x6d, 0x54, 0xc6, 0x80, 0xc3, 0xbd, 0x0d, 0x57,
+    0xdf, 0xf5, 0x24, 0xa9, 0x3e, 0xa8, 0x43, 0xc9, 0xd7, 0x79, 0xd6, 0xf6, 0x7c, 0x22, 0xb9, 0x03,
+    0xe0, 0x0f, 0xec, 0xde, 0x7a, 0x94, 0xb0, 0xbc, 0xdc, 0xe8, 0x28, 0x50, 0x4e, 0x33, 0x0a, 0x4a,
+    0xa7, 0x97, 0x60, 0x73, 0x1e, 0x00, 0x62, 0x44, 0x1a, 0xb8, 0x38, 0x82, 0x64, 0x9f, 0x26, 0x41,
+    0xad, 0x45, 0x46, 0x92, 0x27, 0x5e, 0x55, 0x2f, 0x8c, 0xa3, 0xa5, 0x7d, 0x69, 0xd5, 0x95, 0x3b,
+    0x07, 0x58, 0xb3, 0x40, 0x86, 0xac, 0x1d, 0xf7, 0x30, 0x37, 0x6b, 0xe4, 0x88, 0xd9, 0xe7, 0x89,
+    0xe1, 0x1b, 0x83, 0x49, 0x4c, 0x3f, 0xf8, 0xfe, 0x8d, 0x53, 0xaa, 0x90, 0xca, 0xd8, 0x85, 0x61,
+    0x20, 0x71, 0x67, 0xa4, 0x2d, 0x2b, 0x09, 0x5b, 0xcb, 0x9b, 0x25, 0xd0, 0xbe, 0xe5, 0x6c, 0x52,
+    0x59, 0xa6, 0x74, 0xd2, 0xe6, 0xf4, 0xb4, 0xc0, 0xd1, 0x66, 0xaf, 0xc2, 0x39, 0x4b, 0x63, 0xb6,
+];
+
+const PI_INV: [u8; 256] = [
+    0xa5, 0x2d, 0x32, 0x8f, 0x0e, 0x30, 0x38, 0xc0, 0x54, 0xe6, 0x9e, 0x39, 0x55, 0x7e, 0x52, 0x91,
+    0x64, 0x03, 0x57, 0x5a, 0x1c, 0x60, 0x07, 0x18, 0x21, 0x72, 0xa8, 0xd1, 0x29, 0xc6, 0xa4, 0x3f,
+    0xe0, 0x27, 0x8d, 0x0c, 0x82, 0xea, 0xae, 0xb4, 0x9a, 0x63, 0x49, 0xe5, 0x42, 0xe4, 0x15, 0xb7,
+    0xc8, 0x06, 0x70, 0x9d, 0x41, 0x75, 0x19, 0xc9, 0xaa, 0xfc, 0x4d, 0xbf, 0x2a, 0x73, 0x84, 0xd5,
+    0xc3, 0xaf, 0x2b, 0x86, 0xa7, 0xb1, 0xb2, 0x5b, 0x46, 0xd3, 0x9f, 0xfd, 0xd4, 0x0f, 0x9c, 0x2f,
+    0x9b, 0x43, 0xef, 0xd9, 0x79, 0xb6, 0x53, 0x7f, 0xc1, 0xf0, 0x23, 0xe7, 0x25, 0x5e, 0xb5, 0x1e,
+    0xa2, 0xdf, 0xa6, 0xfe, 0xac, 0x22, 0xf9, 0xe2, 0x4a, 0xbc, 0x35, 0xca, 0xee, 0x78, 0x05, 0x6b,
+    0x51, 0xe1, 0x59, 0xa3, 0xf2, 0x71, 0x56, 0x11, 0x6a, 0x89, 0x94, 0x65, 0x8c, 0xbb, 0x77, 0x3c,
+    0x7b, 0x28, 0xab, 0xd2, 0x31, 0xde, 0xc4, 0x5f, 0xcc, 0xcf, 0x76, 0x2c, 0xb8, 0xd8, 0x2e, 0x36,
+    0xdb, 0x69, 0xb3, 0x14, 0x95, 0xbe, 0x62, 0xa1, 0x3b, 0x16, 0x66, 0xe9, 0x5c, 0x6c, 0x6d, 0xad,
+    0x37, 0x61, 0x4b, 0xb9, 0xe3, 0xba, 0xf1, 0xa0, 0x85, 0x83, 0xda, 0x47, 0xc5, 0xb0, 0x33, 0xfa,
+    0x96, 0x6f, 0x6e, 0xc2, 0xf6, 0x50, 0xff, 0x5d, 0xa9, 0x8e, 0x17, 0x1b, 0x97, 0x7d, 0xec, 0x58,
+    0xf7, 0x1f, 0xfb, 0x7c, 0x09, 0x0d, 0x7a, 0x67, 0x45, 0x87, 0xdc, 0xe8, 0x4f, 0x1d, 0x4e, 0x04,
+    0xeb, 0xf8, 0xf3, 0x3e, 0x3d, 0xbd, 0x8a, 0x88, 0xdd, 0xcd, 0x0b, 0x13, 0x98, 0x02, 0x93, 0x80,
+    0x90, 0xd0, 0x24, 0x34, 0xcb, 0xed, 0xf4, 0xce, 0x99, 0x10, 0x44, 0x40, 0x92, 0x3a, 0x01, 0x26,
+    0x12, 0x1a, 0x48, 0x68, 0xf5, 0x81, 0x8b, 0xc7, 0xd6, 0x20, 0x0a, 0x08, 0x00, 0x4c, 0xd7, 0x74,
+];
+
+// Coefficients of the linear function l, which are applied to the bytes of the block in order
+const L_VEC: [u8; 16] = [
+    148, 32, 133, 16, 194, 192, 1, 251, 1, 192, 194, 16, 133, 32, 148, 1,
+];
+
+// Multiplication in GF(2^8) modulo x^8 + x^7 + x^6 + x + 1
+fn mul_gf(mut a: u8, mut b: u8) -> u8 {
+    let mut out = 0;
+    while b != 0 {
+        if b & 1 == 1 {
+            out ^= a;
+        }
+        let carry = a & 0x80;
+        a <<= 1;
+        if carry != 0 {
+            a ^= 0xc3;
+        }
+        b >>= 1;
+    }
+    out
+}
+
+fn l(bytes: &[u8]) -> u8 {
+    bytes
+        .iter()
+        .zip(L_VEC)
+        .fold(0, |acc, (byte, c)| acc ^ mul_gf(*byte, c))
+}
+
+// One step of the LFSR that makes up the linear transformation
+fn r(block: &mut [u8; 16]) {
+    let x = l(block);
+    block.rotate_right(1);
+    block[0] = x;
+}
+
+// Since the last coefficient of l is 1 the byte shifted out by R is recovered by applying l again
+fn r_inv(block: &mut [u8; 16]) {
+    block.rotate_left(1);
+    block[15] = l(block);
+}
+
+// The linear transformation L is sixteen steps of R
+fn l_transform(block: &mut [u8; 16]) {
+    for _ in 0..16 {
+        r(block);
+    }
+}
+
+fn l_transform_inv(block: &mut [u8; 16]) {
+    for _ in 0..16 {
+        r_inv(block);
+    }
+}
+
+fn s_transform(block: &mut [u8; 16]) {
+    for byte in block.iter_mut() {
+        *byte = PI[*byte as usize];
+    }
+}
+
+fn s_transform_inv(block: &mut [u8; 16]) {
+    for byte in block.iter_mut() {
+        *byte = PI_INV[*byte as usize];
+    }
+}
+
+// The round constants C_i are L applied to the number i
+fn round_constant(i: u8) -> [u8; 16] {
+    let mut c = [0; 16];
+    c[15] = i;
+    l_transform(&mut c);
+    c
+}
+
+/// Kuznyechik, the 128-bit block cipher of GOST R 34.12-2015, with a 256-bit key.
+pub struct Kuznyechik {
+    pub input_format: ByteFormat,
+    pub output_format: ByteFormat,
+    pub round_keys: [[u8; 16]; 10],
+    pub iv: u128,
+    pub mode: BCMode,
+    pub padding: BCPadding,
+}
+
+impl Default for Kuznyechik {
+    fn default() -> Self {
+        Self {
+            input_format: ByteFormat::Hex,
+            output_format: ByteFormat::Hex,
+            round_keys: [[0; 16]; 10],
+            iv: 0,
+            mode: BCMode::default(),
+            padding: BCPadding::default(),
+        }
+    }
+}
+
+crate::block_cipher_builders! {Kuznyechik, u128}
+
+impl Kuznyechik {
+    pub fn ksa(&mut self, bytes: [u8; 32]) {
+        let mut k1: [u8; 16] = bytes[..16].try_into().unwrap();
+        let mut k2: [u8; 16] = bytes[16..].try_into().unwrap();
+        self.round_keys[0] = k1;
+        self.round_keys[1] = k2;
+
+        // Each pair of round keys comes from eight Feistel rounds applied to the previous pair, with the round
+        // function being a single round of the cipher keyed by a round constant
+        for pair in 1..5 {
+            for i in 0..8 {
+                let mut t = k1;
+                xor_into_bytes(&mut t, round_constant(8 * (pair - 1) + i + 1));
+                s_transform(&mut t);
+                l_transform(&mut t);
+                xor_into_bytes(&mut t, k2);
+                k2 = k1;
+                k1 = t;
+            }
+            self.round_keys[2 * pair as usize] = k1;
+            self.round_keys[2 * pair as usize + 1] = k2;
+        }
+    }
+
+    pub fn with_key(mut self, bytes: [u8; 32]) -> Self {
+        self.ksa(bytes);
+        self
+    }
+}
+
+impl BlockCipher<16> for Kuznyechik {
+    fn encrypt_block(&self, bytes: &mut [u8]) {
+        let mut block: [u8; 16] = (&*bytes).try_into().unwrap();
+        for key in &self.round_keys[..9] {
+            xor_into_bytes(&mut block, key);
+            s_transform(&mut block);
+            l_transform(&mut block);
+        }
+        xor_into_bytes(&mut block, self.round_keys[9]);
+        bytes.copy_from_slice(&block);
+    }
+
+    fn decrypt_block(&self, bytes: &mut [u8]) {
+        let mut block: [u8; 16] = (&*bytes).try_into().unwrap();
+        xor_into_bytes(&mut block, self.round_keys[9]);
+        for key in self.round_keys[..9].iter().rev() {
+            l_transform_inv(&mut block);
+            s_transform_inv(&mut block);
+            xor_into_bytes(&mut block, key);
+        }
+        bytes.copy_from_slice(&block);
+    }
+
+    crate::block_cipher_getters!();
+}
+
+crate::impl_cipher_for_block_cipher!(Kuznyechik, 16);
+
+#[cfg(test)]
+mod kuznyechik_tests {
+
+    use super::*;
+    use hex_literal::hex;
+
+    // Examples of the S, R, and L transformations from GOST R 34.12-2015 Appendix A.1
+    #[test]
+    fn transformations() {
+        let mut block = hex!("ffeeddccbbaa99881122334455667700");
+        s_transform(&mut block);
+        assert_eq!(hex!("b66cd8887d38e8d77765aeea0c9a7efc"), block);
+
+        let mut block = hex!("00000000000000000000000000000100");
+        r(&mut block);
+        assert_eq!(hex!("94000000000000000000000000000001"), block);
+        r_inv(&mut block);
+        assert_eq!(hex!("00000000000000000000000000000100"), block);
+
+        let mut block = hex!("64a59400000000000000000000000000");
+        l_transform(&mut block);
+        assert_eq!(hex!("d456584dd0e3e84cc3166e4b7fa2890d"), block);
+        l_transform_inv(&mut block);
+        assert_eq!(hex!("64a59400000000000000000000000000"), block);
+    }
+
+    #[test]
+    fn sbox_inverse() {
+        for i in 0..=255 {
+            assert_eq!(i, PI_INV[PI[i as usize] as usize]);
+        }
+    }
+}
+
+// Test vector from GOST R 34.12-2015 Appendix A.1
+crate::test_block_cipher!(
+    test_1, Kuznyechik::default().with_key(hex_literal::hex!("8899aabbccddeeff0011223344556677fedcba98765432100123456789abcdef")),
+    hex_literal::hex!("1122334455667700ffeeddccbbaa9988"),
+    hex_literal::hex!("7f679d90bebc24305a468d42b9d4edcd");
+);
@@ -0,0 +1,116 @@
+use super::block_cipher::{BCMode, BCPadding, BlockCipher};
+use utils::byte_formatting::{fill_u32s_be, make_u32s_be, u32s_to_bytes_be, ByteFormat};
+
+// The substitutions pi_0 through pi_7 from GOST R 34.12-2015, pi_0 is applied to the lowest four bits
+const PI: [[u8; 16]; 8] = [
+    [12, 4, 6, 2, 10, 5, 11, 9, 14, 8, 13, 7, 0, 3, 15, 1],
+    [6, 8, 2, 3, 9, 10, 5, 12, 1, 14, 4, 7, 11, 13, 0, 15],
+    [11, 3, 5, 8, 2, 15, 10, 13, 14, 1, 7, 4, 12, 9, 6, 0],
+    [12, 8, 2, 1, 13, 4, 15, 6, 7, 0, 10, 5, 3, 14, 9, 11],
+    [7, 15, 5, 10, 8, 1, 6, 13, 0, 9, 3, 14, 11, 4, 2, 12],
+    [5, 13, 15, 6, 9, 2, 12, 10, 11, 7, 8, 1, 4, 3, 14, 0],
+    [8, 14, 2, 5, 6, 9, 1, 12, 15, 4, 11, 0, 13, 10, 3, 7],
+    [1, 7, 14, 13, 0, 5, 8, 3, 4, 15, 10, 6, 9, 12, 11, 2],
+];
+
+// The t transformation
+fn t(a: u32) -> u32 {
+    let mut out = 0;
+    for (i, pi) in PI.iter().enumerate() {
+        let nibble = (a >> (4 * i)) & 0xf;
+        out |= (pi[nibble as usize] as u32) << (4 * i);
+    }
+    out
+}
+
+// The g transformation, which is the round function
+fn g(k: u32, a: u32) -> u32 {
+    t(a.wrapping_add(k)).rotate_left(11)
+}
+
+// The eight key words are used in order three times and then in reverse order
+const ROUND_KEY_IDX: [usize; 32] = [
+    0, 1, 2, 3, 4, 5, 6, 7, 0, 1, 2, 3, 4, 5, 6, 7, 0, 1, 2, 3, 4, 5, 6, 7, 7, 6, 5, 4, 3, 2, 1, 0,
+];
+
+/// Magma, the 64-bit block cipher of GOST R 34.12-2015. This is GOST 28147-89 with the S-boxes fixed by the standard
+/// and with blocks and keys read in big endian order.
+pub struct Magma {
+    pub input_format: ByteFormat,
+    pub output_format: ByteFormat,
+    pub subkeys: [u32; 8],
+    pub iv: u64,
+    pub mode: BCMode,
+    pub padding: BCPadding,
+}
+
+impl Default for Magma {
+    fn default() -> Self {
+        Self {
+            input_format: ByteFormat::Hex,
+            output_format: ByteFormat::Hex,
+            subkeys: [0; 8],
+            iv: 0,
+            mode: BCMode::default(),
+            padding: BCPadding::default(),
+        }
+    }
+}
+
+crate::block_cipher_builders! {Magma, u64}
+
+impl Magma {
+    pub fn ksa(&mut self, bytes: [u8; 32]) {
+        fill_u32s_be(&mut self.subkeys, bytes);
+    }
+
+    pub fn with_key(mut self, bytes: [u8; 32]) -> Self {
+        self.ksa(bytes);
+        self
+    }
+}
+
+impl BlockCipher<8> for Magma {
+    fn encrypt_block(&self, bytes: &mut [u8]) {
+        let [mut a1, mut a0] = make_u32s_be::<2>(bytes);
+        for idx in ROUND_KEY_IDX {
+            (a1, a0) = (a0, a1 ^ g(self.subkeys[idx], a0));
+        }
+        // The final round does not swap the halves
+        u32s_to_bytes_be(bytes, [a0, a1]);
+    }
+
+    fn decrypt_block(&self, bytes: &mut [u8]) {
+        let [mut a1, mut a0] = make_u32s_be::<2>(bytes);
+        for idx in ROUND_KEY_IDX.into_iter().rev() {
+            (a1, a0) = (a0, a1 ^ g(self.subkeys[idx], a0));
+        }
+        u32s_to_bytes_be(bytes, [a0, a1]);
+    }
+
+    crate::block_cipher_getters!();
+}
+
+crate::impl_cipher_for_block_cipher!(Magma, 8);
+
+#[cfg(test)]
+mod magma_tests {
+
+    use super::*;
+
+    // Examples of the t and g transformations from GOST R 34.12-2015 Appendix A.2
+    #[test]
+    fn transformations() {
+        assert_eq!(0x2a196f34, t(0xfdb97531));
+        assert_eq!(0xebd9f03a, t(0x2a196f34));
+        assert_eq!(0xfdcbc20c, g(0x87654321, 0xfedcba98));
+        assert_eq!(0x7e791a4b, g(0xfdcbc20c, 0x87654321));
+    }
+}
+
+// Test vector from GOST R 34.12-2015 Appendix A.2
+crate::test_block_cipher!(
+    test_1, Magma::default().with_key(hex_literal::hex!("ffeeddccbbaa99887766554433221100f0f1f2f3f4f5f6f7f8f9fafbfcfdfeff")),
+    hex_literal::hex!("fedcba9876543210"),
+    hex_literal::hex!("4ee901e5c2d8ca3d");
+);
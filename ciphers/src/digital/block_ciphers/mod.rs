@@ -1,19 +1,26 @@
 pub mod aes;
+pub mod anubis;
 pub mod aria;
 pub mod ascon;
 pub mod block_cipher;
 pub mod blowfish;
 pub mod camellia;
+pub mod cast;
+pub mod deoxys;
 pub mod des;
+pub mod dyn_block_cipher;
 pub mod e2;
 pub mod fealnx;
 pub mod gift;
 pub mod gost;
 pub mod idea;
 pub mod kasumi;
+pub mod key_wrap;
 pub mod khufu_khafre;
+pub mod kuznyechik;
 pub mod lea;
 pub mod lucifer;
+pub mod magma;
 pub mod misty1;
 pub mod present;
 pub mod rc2;
@@ -22,11 +29,13 @@ pub mod rc6;
 pub mod seed;
 pub mod serpent;
 pub mod simon;
+pub mod skinny;
 pub mod skipjack;
 pub mod sm4;
 pub mod speck;
 pub mod tea;
 pub mod threefish;
+pub mod tweakable_block_cipher;
 pub mod twofish;
 
 // This Big Scary Macro is just avoiding a lot of boilerplate since all block ciphers have essentially the same
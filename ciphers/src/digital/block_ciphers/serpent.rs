@@ -1,23 +1,12 @@
 use super::block_cipher::{BCMode, BCPadding, BlockCipher};
 use hex_literal::hex;
+use rngs::sosemanuk::{lt, sbox_bitslice, serpent_subkeys, xor_words};
 use std::ops::Shl;
-use utils::byte_formatting::{fill_u32s_le, make_u32s_le, u32s_to_bytes_le, ByteFormat};
+use utils::byte_formatting::{make_u32s_le, u32s_to_bytes_le, ByteFormat};
 
 pub const ROUNDS: usize = 32;
-pub const FRAC: u32 = 0x9e3779b9;
-
-// Serpents eight 4-bit sboxes and their inverses
-pub const SBOX: [[u8; 16]; 8] = [
-    [3, 8, 15, 1, 10, 6, 5, 11, 14, 13, 4, 2, 7, 0, 9, 12],
-    [15, 12, 2, 7, 9, 0, 5, 10, 1, 11, 14, 8, 6, 13, 3, 4],
-    [8, 6, 7, 9, 3, 12, 10, 15, 13, 1, 14, 4, 0, 11, 5, 2],
-    [0, 15, 11, 8, 12, 9, 6, 3, 13, 1, 2, 4, 10, 7, 5, 14],
-    [1, 15, 8, 3, 12, 0, 11, 6, 2, 5, 4, 10, 9, 14, 7, 13],
-    [15, 5, 2, 11, 4, 10, 9, 12, 0, 3, 14, 8, 13, 6, 7, 1],
-    [7, 2, 12, 5, 8, 4, 6, 11, 14, 9, 1, 15, 13, 3, 10, 0],
-    [1, 13, 15, 0, 14, 8, 2, 11, 7, 4, 12, 10, 9, 3, 5, 6],
-];
 
+// The inverses of Serpents eight 4-bit sboxes, the sboxes themselves are shared with Sosemanuk in the rngs crate
 pub const SBOX_INV: [[u8; 16]; 8] = [
     [13, 3, 11, 0, 10, 6, 5, 12, 1, 14, 4, 7, 15, 9, 8, 2],
     [5, 8, 2, 14, 15, 6, 12, 3, 11, 4, 7, 9, 1, 13, 10, 0],
@@ -29,11 +18,6 @@ pub const SBOX_INV: [[u8; 16]; 8] = [
     [3, 0, 6, 13, 9, 14, 15, 8, 5, 12, 11, 7, 10, 1, 4, 2],
 ];
 
-// Apply a specific SBOX, u8 should only use the lower 4 bits
-fn sbox(i: usize, nibble: u8) -> u8 {
-    SBOX[i][nibble as usize]
-}
-
 // Apply a specific SBOX_INV, u8 should only use the lower 4 bits
 fn sbox_inv(i: usize, nibble: u8) -> u8 {
     SBOX_INV[i][nibble as usize]
@@ -44,27 +28,7 @@ fn get_bit(x: u32, i: usize) -> u8 {
     (x >> i) as u8 & 0x01
 }
 
-// Apply a sbox across the bits of four 32-bit words
-fn sbox_bitslice(idx: usize, words: [u32; 4]) -> [u32; 4] {
-    let mut out: [u32; 4] = [0; 4];
-    for i in 0..32 {
-        // Take bits across the words
-        let nibble = get_bit(words[0], i)
-            | get_bit(words[1], i) << 1
-            | get_bit(words[2], i) << 2
-            | get_bit(words[3], i) << 3;
-
-        // Apply the sbox to the bits
-        let s = sbox(idx % 8, nibble);
-
-        // Push the transformed bits into the output
-        for pos in 0..4 {
-            out[pos] |= u32::from(get_bit(s as u32, pos)) << i;
-        }
-    }
-    out
-}
-
+// Apply a sbox_inv across the bits of four 32-bit words
 fn sbox_bitslice_inv(idx: usize, words: [u32; 4]) -> [u32; 4] {
     let mut out: [u32; 4] = [0; 4];
     for i in 0..32 {
@@ -85,21 +49,7 @@ fn sbox_bitslice_inv(idx: usize, words: [u32; 4]) -> [u32; 4] {
     out
 }
 
-// Serpent's Linear Transformation and its inverse
-fn lt(mut x: [u32; 4]) -> [u32; 4] {
-    x[0] = x[0].rotate_left(13);
-    x[2] = x[2].rotate_left(3);
-    x[1] = x[1] ^ x[0] ^ x[2];
-    x[3] = x[3] ^ x[2] ^ x[0].shl(3);
-    x[1] = x[1].rotate_left(1);
-    x[3] = x[3].rotate_left(7);
-    x[0] = x[0] ^ x[1] ^ x[3];
-    x[2] = x[2] ^ x[3] ^ x[1].shl(7);
-    x[0] = x[0].rotate_left(5);
-    x[2] = x[2].rotate_left(22);
-    x
-}
-
+// The inverse of Serpent's Linear Transformation
 fn lt_inv(mut x: [u32; 4]) -> [u32; 4] {
     x[2] = x[2].rotate_right(22);
     x[0] = x[0].rotate_right(5);
@@ -114,74 +64,6 @@ fn lt_inv(mut x: [u32; 4]) -> [u32; 4] {
     x
 }
 
-// Expand a key to 256 bits.
-// Serpent accepts keys of any bit length from 128 to 256 bits.
-// I will not bother since keys not given in bytes are rare.
-fn expand_key(bytes: &[u8]) -> [u8; 32] {
-    let mut ex = [0; 32];
-    ex[..bytes.len()].copy_from_slice(bytes);
-    if bytes.len() < 32 {
-        ex[bytes.len()] = 0x01 // this is correct per test vectors, probably because of little endian view of the bytes
-    }
-    ex
-}
-
-// Generate the pre_keys that are used to generate the round keys
-fn pre_keys(bytes: &[u8]) -> [u32; 132] {
-    // Copy the expanded key into the start of the pre_key
-    let mut pre_key: [u32; 140] = [0; 140];
-    fill_u32s_le(&mut pre_key[0..8], &expand_key(bytes));
-    // Fill the entire pre_key
-    for i in 0..132 {
-        let pos = i + 8;
-        pre_key[pos] = (pre_key[pos - 8]
-            ^ pre_key[pos - 5]
-            ^ pre_key[pos - 3]
-            ^ pre_key[pos - 1]
-            ^ FRAC
-            ^ i as u32)
-            .rotate_left(11);
-    }
-    // Discard the first eight words
-    pre_key[8..].try_into().unwrap()
-}
-
-// Convert the pre_keys into the actual round keys using the sboxes
-fn round_keys(pre_keys: [u32; 132]) -> [[u32; 4]; ROUNDS + 1] {
-    let mut t = [0; 132];
-    for (idx, chunk) in pre_keys.chunks_exact(4).enumerate() {
-        let s_idx = (ROUNDS + 3 - idx) % ROUNDS;
-        for i in 0..32 {
-            // Take bits across the words
-            let nibble = get_bit(chunk[0], i)
-                | get_bit(chunk[1], i) << 1
-                | get_bit(chunk[2], i) << 2
-                | get_bit(chunk[3], i) << 3;
-
-            // Apply the sbox to the bits
-            let s = sbox(s_idx % 8, nibble);
-
-            // Modified schedule for where to push the bits, not the same as the bit sliced sbox
-            for pos in 0..4 {
-                t[4 * idx + pos] |= u32::from(get_bit(s as u32, pos)) << i;
-            }
-        }
-    }
-    let mut out = [[0; 4]; ROUNDS + 1];
-    for (i, chunk) in t.chunks_exact(4).enumerate() {
-        out[i].copy_from_slice(chunk);
-    }
-    out
-}
-
-fn xor_words(a: [u32; 4], b: [u32; 4]) -> [u32; 4] {
-    let mut out = [0; 4];
-    for i in 0..4 {
-        out[i] = a[i] ^ b[i]
-    }
-    out
-}
-
 pub struct Serpent {
     pub input_format: ByteFormat,
     pub output_format: ByteFormat,
@@ -208,7 +90,7 @@ crate::block_cipher_builders! {Serpent, u128}
 
 impl Serpent {
     pub fn ksa_128(&mut self, bytes: [u8; 16]) {
-        self.round_keys = round_keys(pre_keys(&bytes));
+        self.round_keys = serpent_subkeys(&bytes);
     }
 
     pub fn with_key_128(mut self, bytes: [u8; 16]) -> Self {
@@ -217,7 +99,7 @@ impl Serpent {
     }
 
     pub fn ksa_192(&mut self, bytes: [u8; 24]) {
-        self.round_keys = round_keys(pre_keys(&bytes));
+        self.round_keys = serpent_subkeys(&bytes);
     }
 
     pub fn with_key_192(mut self, bytes: [u8; 24]) -> Self {
@@ -226,7 +108,7 @@ impl Serpent {
     }
 
     pub fn ksa_256(&mut self, bytes: [u8; 32]) {
-        self.round_keys = round_keys(pre_keys(&bytes));
+        self.round_keys = serpent_subkeys(&bytes);
     }
 
     pub fn with_key_256(mut self, bytes: [u8; 32]) -> Self {
@@ -237,7 +119,7 @@ impl Serpent {
     pub fn ksa_u32(&mut self, words: &[u32]) {
         assert!(words.len() >= 4 && words.len() <= 8);
         let bytes: Vec<u8> = words.iter().flat_map(|w| w.to_le_bytes()).collect();
-        self.round_keys = round_keys(pre_keys(&bytes));
+        self.round_keys = serpent_subkeys(&bytes);
     }
 }
 
@@ -0,0 +1,230 @@
+use super::{
+    block_cipher::{BCMode, BCPadding, BlockCipher},
+    tweakable_block_cipher::TweakableBlockCipher,
+};
+use utils::byte_formatting::ByteFormat;
+
+const SBOX: [u8; 16] = [
+    0xc, 0x6, 0x9, 0x0, 0x1, 0xa, 0x2, 0xb, 0x3, 0x8, 0x5, 0xd, 0x4, 0xe, 0x7, 0xf,
+];
+
+const SBOX_INV: [u8; 16] = [
+    0x3, 0x4, 0x6, 0x8, 0xc, 0xa, 0x1, 0xe, 0x9, 0x2, 0x5, 0x7, 0x0, 0xb, 0xd, 0xf,
+];
+
+// Permutation applied to the tweakey cells each round
+const PT: [usize; 16] = [9, 15, 8, 13, 10, 14, 12, 11, 0, 1, 2, 3, 4, 5, 6, 7];
+
+const ROUNDS: usize = 36;
+
+// The state is a 4x4 grid of nibbles in row major order, with the first nibble as the high bits of the first byte
+fn bytes_to_cells(bytes: &[u8]) -> [u8; 16] {
+    let mut cells = [0; 16];
+    for (i, byte) in bytes.iter().take(8).enumerate() {
+        cells[2 * i] = byte >> 4;
+        cells[2 * i + 1] = byte & 0xf;
+    }
+    cells
+}
+
+fn cells_to_bytes(bytes: &mut [u8], cells: &[u8; 16]) {
+    for (i, byte) in bytes.iter_mut().take(8).enumerate() {
+        *byte = (cells[2 * i] << 4) | cells[2 * i + 1];
+    }
+}
+
+// Apply a cell permutation to the state
+fn permute(cells: &mut [u8; 16], p: &[usize; 16]) {
+    let old = *cells;
+    for i in 0..16 {
+        cells[i] = old[p[i]];
+    }
+}
+
+// The LFSR applied to the top two rows of TK2
+fn lfsr2(cell: u8) -> u8 {
+    ((cell << 1) & 0xe) | (((cell >> 3) ^ (cell >> 2)) & 1)
+}
+
+// The round tweakeys are the first two rows of TK1 XOR TK2
+fn round_tweakeys(tweak: &[u8; 8], key: &[u8; 8]) -> [[u8; 8]; ROUNDS] {
+    let mut tk1 = bytes_to_cells(tweak);
+    let mut tk2 = bytes_to_cells(key);
+    let mut out = [[0; 8]; ROUNDS];
+    for rtk in out.iter_mut() {
+        for i in 0..8 {
+            rtk[i] = tk1[i] ^ tk2[i];
+        }
+        permute(&mut tk1, &PT);
+        permute(&mut tk2, &PT);
+        for cell in tk2[0..8].iter_mut() {
+            *cell = lfsr2(*cell);
+        }
+    }
+    out
+}
+
+// The six bit affine LFSR that produces the round constants
+fn round_constants() -> [u8; ROUNDS] {
+    let mut rc = 0_u8;
+    let mut out = [0; ROUNDS];
+    for c in out.iter_mut() {
+        rc = ((rc << 1) & 0x3f) | (((rc >> 5) ^ (rc >> 4) ^ 1) & 1);
+        *c = rc;
+    }
+    out
+}
+
+fn add_constants(cells: &mut [u8; 16], rc: u8) {
+    cells[0] ^= rc & 0xf;
+    cells[4] ^= rc >> 4;
+    cells[8] ^= 0x2;
+}
+
+// Each row is rotated to the right by its index
+fn shift_rows(cells: &mut [u8; 16]) {
+    for row in 1..4 {
+        cells[4 * row..4 * row + 4].rotate_right(row);
+    }
+}
+
+fn inv_shift_rows(cells: &mut [u8; 16]) {
+    for row in 1..4 {
+        cells[4 * row..4 * row + 4].rotate_left(row);
+    }
+}
+
+// Multiply each column by the binary matrix
+// 1 0 1 1
+// 1 0 0 0
+// 0 1 1 0
+// 1 0 1 0
+fn mix_columns(cells: &mut [u8; 16]) {
+    for c in 0..4 {
+        let [a, b, x, d] = [cells[c], cells[4 + c], cells[8 + c], cells[12 + c]];
+        cells[c] = a ^ x ^ d;
+        cells[4 + c] = a;
+        cells[8 + c] = b ^ x;
+        cells[12 + c] = a ^ x;
+    }
+}
+
+fn inv_mix_columns(cells: &mut [u8; 16]) {
+    for c in 0..4 {
+        let [a, b, x, d] = [cells[c], cells[4 + c], cells[8 + c], cells[12 + c]];
+        cells[c] = b;
+        cells[4 + c] = b ^ x ^ d;
+        cells[8 + c] = b ^ d;
+        cells[12 + c] = a ^ d;
+    }
+}
+
+/// SKINNY-64-128 with the 128-bit tweakey split into a 64-bit tweak (TK1) and a 64-bit key (TK2).
+pub struct Skinny64_128 {
+    pub input_format: ByteFormat,
+    pub output_format: ByteFormat,
+    pub key: [u8; 8],
+    pub tweak: [u8; 8],
+    pub iv: u64,
+    pub mode: BCMode,
+    pub padding: BCPadding,
+}
+
+impl Default for Skinny64_128 {
+    fn default() -> Self {
+        Self {
+            input_format: ByteFormat::Hex,
+            output_format: ByteFormat::Hex,
+            key: [0; 8],
+            tweak: [0; 8],
+            iv: 0,
+            mode: BCMode::default(),
+            padding: BCPadding::default(),
+        }
+    }
+}
+
+crate::block_cipher_builders! {Skinny64_128, u64}
+
+impl Skinny64_128 {
+    pub fn ksa(&mut self, key: [u8; 8]) {
+        self.key = key;
+    }
+
+    pub fn with_key(mut self, key: [u8; 8]) -> Self {
+        self.ksa(key);
+        self
+    }
+
+    pub fn with_tweak(mut self, tweak: [u8; 8]) -> Self {
+        self.tweak = tweak;
+        self
+    }
+
+    /// Set the full tweakey as it is given in the specification, with TK1 first.
+    pub fn with_tweakey(mut self, tweakey: [u8; 16]) -> Self {
+        self.tweak.copy_from_slice(&tweakey[..8]);
+        self.key.copy_from_slice(&tweakey[8..]);
+        self
+    }
+}
+
+impl TweakableBlockCipher<8, 8> for Skinny64_128 {
+    fn encrypt_block_tweaked(&self, bytes: &mut [u8], tweak: &[u8; 8]) {
+        let rtks = round_tweakeys(tweak, &self.key);
+        let mut cells = bytes_to_cells(bytes);
+
+        for (rtk, rc) in rtks.iter().zip(round_constants()) {
+            for cell in cells.iter_mut() {
+                *cell = SBOX[*cell as usize];
+            }
+            add_constants(&mut cells, rc);
+            for i in 0..8 {
+                cells[i] ^= rtk[i];
+            }
+            shift_rows(&mut cells);
+            mix_columns(&mut cells);
+        }
+
+        cells_to_bytes(bytes, &cells);
+    }
+
+    fn decrypt_block_tweaked(&self, bytes: &mut [u8], tweak: &[u8; 8]) {
+        let rtks = round_tweakeys(tweak, &self.key);
+        let mut cells = bytes_to_cells(bytes);
+
+        for (rtk, rc) in rtks.iter().zip(round_constants()).rev() {
+            inv_mix_columns(&mut cells);
+            inv_shift_rows(&mut cells);
+            for i in 0..8 {
+                cells[i] ^= rtk[i];
+            }
+            add_constants(&mut cells, rc);
+            for cell in cells.iter_mut() {
+                *cell = SBOX_INV[*cell as usize];
+            }
+        }
+
+        cells_to_bytes(bytes, &cells);
+    }
+}
+
+impl BlockCipher<8> for Skinny64_128 {
+    fn encrypt_block(&self, bytes: &mut [u8]) {
+        self.encrypt_block_tweaked(bytes, &self.tweak)
+    }
+
+    fn decrypt_block(&self, bytes: &mut [u8]) {
+        self.decrypt_block_tweaked(bytes, &self.tweak)
+    }
+
+    crate::block_cipher_getters!();
+}
+
+crate::impl_cipher_for_block_cipher!(Skinny64_128, 8);
+
+crate::test_block_cipher!(
+    test_1, Skinny64_128::default().with_tweakey(hex_literal::hex!("9eb93640d088da6376a39d1c8bea71e1")),
+    hex_literal::hex!("cf16cfe8fd0f98aa"),
+    hex_literal::hex!("6ceda1f43de92b9e");
+);
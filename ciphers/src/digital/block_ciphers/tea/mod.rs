@@ -1,4 +1,5 @@
 pub mod tea;
 pub mod xtea;
+pub mod xxtea;
 
 const DELTA: u32 = 0x9e3779b9;
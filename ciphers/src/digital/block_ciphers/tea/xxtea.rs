@@ -0,0 +1,180 @@
+use crate::Cipher;
+use utils::{
+    byte_formatting::{fill_u32s_le, u32s_to_bytes_le, ByteFormat},
+    errors::GeneralError,
+};
+
+fn mx(sum: u32, y: u32, z: u32, p: usize, e: u32, key: &[u32; 4]) -> u32 {
+    (((z >> 5) ^ (y << 2)).wrapping_add((y >> 3) ^ (z << 4)))
+        ^ ((sum ^ y).wrapping_add(key[(p ^ e as usize) & 3] ^ z))
+}
+
+/// XXTEA (Corrected Block TEA) encrypts the entire message as a single block of 32-bit words. Words are read in
+/// little endian order, as in the reference implementation.
+pub struct Xxtea {
+    pub input_format: ByteFormat,
+    pub output_format: ByteFormat,
+    pub key: [u32; 4],
+}
+
+impl Default for Xxtea {
+    fn default() -> Self {
+        Self {
+            input_format: ByteFormat::Hex,
+            output_format: ByteFormat::Hex,
+            key: [0; 4],
+        }
+    }
+}
+
+impl Xxtea {
+    pub fn ksa(&mut self, bytes: [u8; 16]) {
+        fill_u32s_le(&mut self.key, bytes);
+    }
+
+    pub fn with_key(mut self, bytes: [u8; 16]) -> Self {
+        self.ksa(bytes);
+        self
+    }
+
+    // The number of full cycles is chosen so that every word is mixed at least 6 times, with short blocks getting
+    // more cycles to make up for having fewer words
+    fn cycles(n: usize) -> usize {
+        6 + 52 / n
+    }
+
+    pub fn encrypt_words(&self, v: &mut [u32]) {
+        let n = v.len();
+        let mut sum: u32 = 0;
+        let mut z = v[n - 1];
+        for _ in 0..Self::cycles(n) {
+            sum = sum.wrapping_add(super::DELTA);
+            let e = (sum >> 2) & 3;
+            for p in 0..n {
+                let y = v[(p + 1) % n];
+                v[p] = v[p].wrapping_add(mx(sum, y, z, p, e, &self.key));
+                z = v[p];
+            }
+        }
+    }
+
+    pub fn decrypt_words(&self, v: &mut [u32]) {
+        let n = v.len();
+        let cycles = Self::cycles(n);
+        let mut sum = (cycles as u32).wrapping_mul(super::DELTA);
+        let mut y = v[0];
+        for _ in 0..cycles {
+            let e = (sum >> 2) & 3;
+            for p in (0..n).rev() {
+                let z = v[(p + n - 1) % n];
+                v[p] = v[p].wrapping_sub(mx(sum, y, z, p, e, &self.key));
+                y = v[p];
+            }
+            sum = sum.wrapping_sub(super::DELTA);
+        }
+    }
+
+    fn bytes_to_words(bytes: &[u8]) -> Result<Vec<u32>, GeneralError> {
+        if !bytes.len().is_multiple_of(4) || bytes.len() < 8 {
+            return Err(GeneralError::input(
+                "XXTEA requires a multiple of 4 bytes and at least 8 bytes",
+            ));
+        }
+        let mut words = vec![0; bytes.len() / 4];
+        fill_u32s_le(&mut words, bytes);
+        Ok(words)
+    }
+
+    pub fn encrypt_bytes(&self, bytes: &mut [u8]) -> Result<(), GeneralError> {
+        let mut words = Self::bytes_to_words(bytes)?;
+        self.encrypt_words(&mut words);
+        u32s_to_bytes_le(bytes, &words);
+        Ok(())
+    }
+
+    pub fn decrypt_bytes(&self, bytes: &mut [u8]) -> Result<(), GeneralError> {
+        let mut words = Self::bytes_to_words(bytes)?;
+        self.decrypt_words(&mut words);
+        u32s_to_bytes_le(bytes, &words);
+        Ok(())
+    }
+}
+
+impl Cipher for Xxtea {
+    fn encrypt(&self, text: &str) -> Result<String, GeneralError> {
+        let mut bytes = self
+            .input_format
+            .text_to_bytes(text)
+            .map_err(|e| GeneralError::input(e.to_string()))?;
+        self.encrypt_bytes(&mut bytes)?;
+        Ok(self.output_format.byte_slice_to_text(&bytes))
+    }
+
+    fn decrypt(&self, text: &str) -> Result<String, GeneralError> {
+        let mut bytes = self
+            .input_format
+            .text_to_bytes(text)
+            .map_err(|e| GeneralError::input(e.to_string()))?;
+        self.decrypt_bytes(&mut bytes)?;
+        Ok(self.output_format.byte_slice_to_text(&bytes))
+    }
+}
+
+#[cfg(test)]
+mod xxtea_tests {
+
+    use super::*;
+    use hex_literal::hex;
+
+    // Test vectors from https://github.com/mycelium-com/entropy/blob/master/lib/xxtea.c
+    #[test]
+    fn test_vectors() {
+        let vectors: [(&[u8], &[u8], &[u8]); 6] = [
+            (
+                &hex!("00000000000000000000000000000000"),
+                &hex!("0000000000000000"),
+                &hex!("ab043705808c5d57"),
+            ),
+            (
+                &hex!("0102040810204080fffefcf8f0e0c080"),
+                &hex!("0000000000000000"),
+                &hex!("d1e78be2c746728a"),
+            ),
+            (
+                &hex!("9e3779b99b9773e9b979379e6b695156"),
+                &hex!("ffffffffffffffff"),
+                &hex!("67ed0ea8e8973fc5"),
+            ),
+            (
+                &hex!("ffffffffffffffffffffffffffffffff"),
+                &hex!("157c13a850ba5e57306d7791"),
+                &hex!("b2601cefb078b772abccba6a"),
+            ),
+            (
+                &hex!("aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa"),
+                &hex!("0102040810204080fffefcf8f0e0c080"),
+                &hex!("c0a19f06ebb0d63925aa27f74cc6b2d0"),
+            ),
+            (
+                &hex!("9e3779b99b9773e9b979379e6b695156"),
+                &hex!("690342f45054a708c475c91db77761bc01b815fd2e4894d1"),
+                &hex!("759e5b212ee58be734d610248e1daa1c9d0647d428b4f95a"),
+            ),
+        ];
+        for (key, ptext, ctext) in vectors {
+            let cipher = Xxtea::default().with_key(key.try_into().unwrap());
+            let mut bytes = ptext.to_vec();
+            cipher.encrypt_bytes(&mut bytes).unwrap();
+            assert_eq!(ctext, bytes);
+            cipher.decrypt_bytes(&mut bytes).unwrap();
+            assert_eq!(ptext, bytes);
+        }
+    }
+
+    #[test]
+    fn invalid_length() {
+        let cipher = Xxtea::default();
+        assert!(cipher.encrypt_bytes(&mut [0; 4]).is_err());
+        assert!(cipher.encrypt_bytes(&mut [0; 10]).is_err());
+    }
+}
@@ -9,6 +9,7 @@ macro_rules! threefish {
             pub input_format: utils::byte_formatting::ByteFormat,
             pub output_format: utils::byte_formatting::ByteFormat,
             pub iv: $iv,
+            pub key: [u64; $block_words],
             pub subkeys: [[u64; $block_words]; ($rounds / 4 + 1)],
             pub mode: crate::digital::block_ciphers::block_cipher::BCMode,
             pub padding: crate::digital::block_ciphers::block_cipher::BCPadding,
@@ -20,6 +21,7 @@ macro_rules! threefish {
                     input_format: utils::byte_formatting::ByteFormat::Hex,
                     output_format: utils::byte_formatting::ByteFormat::Hex,
                     iv: <$iv>::ZERO,
+                    key: [0; $block_words],
                     subkeys: [[0; $block_words]; ($rounds / 4 + 1)],
                     mode: Default::default(),
                     padding: Default::default(),
@@ -91,6 +93,7 @@ macro_rules! threefish {
                     input_format: utils::byte_formatting::ByteFormat::Hex,
                     output_format: utils::byte_formatting::ByteFormat::Hex,
                     iv: <$iv>::ZERO,
+                    key: utils::byte_formatting::make_u64s_le(key),
                     subkeys: Self::create_subkeys(key, tweak),
                     mode: Default::default(),
                     padding: Default::default(),
@@ -102,6 +105,7 @@ macro_rules! threefish {
                     input_format: utils::byte_formatting::ByteFormat::Hex,
                     output_format: utils::byte_formatting::ByteFormat::Hex,
                     iv: <$iv>::ZERO,
+                    key: *key,
                     subkeys: Self::create_subkeys_u64(key, tweak),
                     mode: Default::default(),
                     padding: Default::default(),
@@ -109,43 +113,83 @@ macro_rules! threefish {
             }
 
             pub fn ksa(&mut self, key: &[u8; $block_bytes], tweak: &[u8; 16]) {
+                self.key = utils::byte_formatting::make_u64s_le(key);
                 self.subkeys = Self::create_subkeys(key, tweak);
             }
 
             pub fn ksa_u64(&mut self, key: &[u64; $block_words], tweak: &[u64; 2]) {
+                self.key = *key;
                 self.subkeys = Self::create_subkeys_u64(key, tweak);
             }
-        }
-
-        crate::block_cipher_builders!($name, $iv);
 
-        impl crate::digital::block_ciphers::block_cipher::BlockCipher<$block_bytes> for $name {
-            fn encrypt_block(&self, bytes: &mut [u8]) {
+            fn encrypt_with_subkeys(
+                bytes: &mut [u8],
+                subkeys: &[[u64; $block_words]; ($rounds / 4 + 1)],
+            ) {
                 let mut block: [u64; $block_words] = utils::byte_formatting::make_u64s_le(bytes);
 
                 for r in 0..($rounds / 8) {
-                    $round_func(&mut block, &self.subkeys[(2 * r)..][..2]);
+                    $round_func(&mut block, &subkeys[(2 * r)..][..2]);
                 }
 
                 for i in 0..$block_words {
-                    block[i] = block[i].wrapping_add(self.subkeys[($rounds / 4 + 1) - 1][i])
+                    block[i] = block[i].wrapping_add(subkeys[($rounds / 4 + 1) - 1][i])
                 }
 
-                utils::byte_formatting::u64s_to_bytes_le(bytes, &block.map(|n| n));
+                utils::byte_formatting::u64s_to_bytes_le(bytes, &block);
             }
 
-            fn decrypt_block(&self, bytes: &mut [u8]) {
+            fn decrypt_with_subkeys(
+                bytes: &mut [u8],
+                subkeys: &[[u64; $block_words]; ($rounds / 4 + 1)],
+            ) {
                 let mut block: [u64; $block_words] = utils::byte_formatting::make_u64s_le(bytes);
 
                 for i in 0..$block_words {
-                    block[i] = block[i].wrapping_sub(self.subkeys[($rounds / 4 + 1) - 1][i])
+                    block[i] = block[i].wrapping_sub(subkeys[($rounds / 4 + 1) - 1][i])
                 }
 
                 for r in (0..($rounds / 8)).rev() {
-                    $round_func_inv(&mut block, &self.subkeys[(2 * r)..][..2]);
+                    $round_func_inv(&mut block, &subkeys[(2 * r)..][..2]);
                 }
 
-                utils::byte_formatting::u64s_to_bytes_le(bytes, &block.map(|n| n));
+                utils::byte_formatting::u64s_to_bytes_le(bytes, &block);
+            }
+        }
+
+        // The tweak given here replaces the one used in the key schedule
+        impl
+            crate::digital::block_ciphers::tweakable_block_cipher::TweakableBlockCipher<
+                $block_bytes,
+                16,
+            > for $name
+        {
+            fn encrypt_block_tweaked(&self, bytes: &mut [u8], tweak: &[u8; 16]) {
+                let subkeys = Self::create_subkeys_u64(
+                    &self.key,
+                    &utils::byte_formatting::make_u64s_le(tweak),
+                );
+                Self::encrypt_with_subkeys(bytes, &subkeys)
+            }
+
+            fn decrypt_block_tweaked(&self, bytes: &mut [u8], tweak: &[u8; 16]) {
+                let subkeys = Self::create_subkeys_u64(
+                    &self.key,
+                    &utils::byte_formatting::make_u64s_le(tweak),
+                );
+                Self::decrypt_with_subkeys(bytes, &subkeys)
+            }
+        }
+
+        crate::block_cipher_builders!($name, $iv);
+
+        impl crate::digital::block_ciphers::block_cipher::BlockCipher<$block_bytes> for $name {
+            fn encrypt_block(&self, bytes: &mut [u8]) {
+                Self::encrypt_with_subkeys(bytes, &self.subkeys)
+            }
+
+            fn decrypt_block(&self, bytes: &mut [u8]) {
+                Self::decrypt_with_subkeys(bytes, &self.subkeys)
             }
 
             crate::block_cipher_getters!();
@@ -210,3 +254,30 @@ crate::test_block_cipher!(
     hex_literal::hex!("FFFEFDFCFBFAF9F8 F7F6F5F4F3F2F1F0 EFEEEDECEBEAE9E8 E7E6E5E4E3E2E1E0 DFDEDDDCDBDAD9D8 D7D6D5D4D3D2D1D0 CFCECDCCCBCAC9C8 C7C6C5C4C3C2C1C0 BFBEBDBCBBBAB9B8 B7B6B5B4B3B2B1B0 AFAEADACABAAA9A8 A7A6A5A4A3A2A1A0 9F9E9D9C9B9A9998 9796959493929190 8F8E8D8C8B8A8988 8786858483828180"),
     hex_literal::hex!(" A6654DDBD73CC3B0 5DD777105AA849BC E49372EAAFFC5568 D254771BAB85531C 94F780E7FFAAE430 D5D8AF8C70EEBBE1 760F3B42B737A89C B363490D670314BD 8AA41EE63C2E1F45 FBD477922F8360B3 88D6125EA6C7AF0A D7056D01796E90C8 3313F4150A5716B3 0ED5F569288AE974 CE2B4347926FCE57 DE44512177DD7CDE");
 );
+
+#[cfg(test)]
+mod tweak_tests {
+
+    use super::*;
+    use crate::digital::block_ciphers::{
+        block_cipher::BlockCipher, tweakable_block_cipher::TweakableBlockCipher,
+    };
+    use hex_literal::hex;
+
+    #[test]
+    fn tweak_replaces_key_schedule_tweak() {
+        let key = hex!("1011121314151617 18191A1B1C1D1E1F 2021222324252627 28292A2B2C2D2E2F");
+        let tweak = hex!("0001020304050607 08090A0B0C0D0E0F");
+        let keyed = Threefish256::with_key_and_tweak(&key, &[0; 16]);
+        let tweaked = Threefish256::with_key_and_tweak(&key, &tweak);
+
+        let mut a = [0x55; 32];
+        let mut b = [0x55; 32];
+        keyed.encrypt_block_tweaked(&mut a, &tweak);
+        tweaked.encrypt_block(&mut b);
+        assert_eq!(a, b);
+
+        keyed.decrypt_block_tweaked(&mut a, &tweak);
+        assert_eq!([0x55; 32], a);
+    }
+}
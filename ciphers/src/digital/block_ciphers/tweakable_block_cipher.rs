@@ -0,0 +1,312 @@
+use super::block_cipher::BlockCipher;
+use utils::byte_formatting::xor_into_bytes;
+
+/// A block cipher with N byte blocks that also takes a public T byte tweak. Changing the tweak changes the permutation
+/// as completely as changing the key, but unlike the key the tweak does not need to be secret.
+pub trait TweakableBlockCipher<const N: usize, const T: usize> {
+    /// Use the block function to encrypt a single block of bytes under the given tweak.
+    fn encrypt_block_tweaked(&self, bytes: &mut [u8], tweak: &[u8; T]);
+
+    /// Use the block function to decrypt a single block of bytes under the given tweak.
+    fn decrypt_block_tweaked(&self, bytes: &mut [u8], tweak: &[u8; T]);
+}
+
+// Multiplication in GF(2^128) modulo x^128 + x^7 + x^2 + x + 1 with bit i of the integer as the coefficient of x^i
+fn gf128_mul(mut a: u128, mut b: u128) -> u128 {
+    let mut out = 0;
+    while b != 0 {
+        if b & 1 == 1 {
+            out ^= a;
+        }
+        a = (a << 1) ^ (0x87 * (a >> 127));
+        b >>= 1;
+    }
+    out
+}
+
+// x^e in GF(2^128) by square and multiply
+fn gf128_pow_x(mut e: u64) -> u128 {
+    let mut base = 2;
+    let mut out = 1;
+    while e != 0 {
+        if e & 1 == 1 {
+            out = gf128_mul(out, base);
+        }
+        base = gf128_mul(base, base);
+        e >>= 1;
+    }
+    out
+}
+
+/// The LRW construction of Liskov, Rivest, and Wagner. Any 128-bit block cipher becomes a tweakable block cipher by
+/// masking the input and output with the product of the tweak and a second secret key. As in IEEE P1619 the tweak
+/// and key are read as big endian polynomials in GF(2^128).
+pub struct Lrw<C: BlockCipher<16>> {
+    pub cipher: C,
+    pub mask_key: u128,
+}
+
+impl<C: BlockCipher<16>> Lrw<C> {
+    pub fn new(cipher: C, mask_key: u128) -> Self {
+        Self { cipher, mask_key }
+    }
+
+    fn mask(&self, tweak: &[u8; 16]) -> [u8; 16] {
+        gf128_mul(self.mask_key, u128::from_be_bytes(*tweak)).to_be_bytes()
+    }
+}
+
+impl<C: BlockCipher<16>> TweakableBlockCipher<16, 16> for Lrw<C> {
+    fn encrypt_block_tweaked(&self, bytes: &mut [u8], tweak: &[u8; 16]) {
+        let mask = self.mask(tweak);
+        xor_into_bytes(&mut *bytes, mask);
+        self.cipher.encrypt_block(bytes);
+        xor_into_bytes(bytes, mask);
+    }
+
+    fn decrypt_block_tweaked(&self, bytes: &mut [u8], tweak: &[u8; 16]) {
+        let mask = self.mask(tweak);
+        xor_into_bytes(&mut *bytes, mask);
+        self.cipher.decrypt_block(bytes);
+        xor_into_bytes(bytes, mask);
+    }
+}
+
+// Multiply by the primitive element in GF(2^128) with the little endian convention used by XTS
+fn double_le(block: &mut [u8; 16]) {
+    let carry = block[15] >> 7;
+    for i in (1..16).rev() {
+        block[i] = (block[i] << 1) | (block[i - 1] >> 7);
+    }
+    block[0] = (block[0] << 1) ^ (0x87 * carry);
+}
+
+/// Rogaway's XEX construction. The tweak is a 16 byte nonce followed by a 64-bit big endian block index i. The mask
+/// is the encrypted nonce multiplied by the i-th power of the primitive element, so consecutive blocks are cheap to
+/// process. XTS is XEX with a separate key for encrypting the nonce, see [`Xts`].
+pub struct Xex<C: BlockCipher<16>> {
+    pub cipher: C,
+}
+
+impl<C: BlockCipher<16>> Xex<C> {
+    pub fn new(cipher: C) -> Self {
+        Self { cipher }
+    }
+
+    fn mask(&self, tweak: &[u8; 24]) -> [u8; 16] {
+        let mut mask: [u8; 16] = tweak[..16].try_into().unwrap();
+        self.cipher.encrypt_block(&mut mask);
+        let index = u64::from_be_bytes(tweak[16..].try_into().unwrap());
+        gf128_mul(u128::from_le_bytes(mask), gf128_pow_x(index)).to_le_bytes()
+    }
+
+    /// Encrypt a sequence of whole blocks using the nonce and the position of each block as the tweak.
+    pub fn encrypt_blocks(&self, bytes: &mut [u8], nonce: [u8; 16]) {
+        assert!(bytes.len().is_multiple_of(16));
+        let mut mask = nonce;
+        self.cipher.encrypt_block(&mut mask);
+        for block in bytes.chunks_mut(16) {
+            xor_into_bytes(&mut *block, mask);
+            self.cipher.encrypt_block(block);
+            xor_into_bytes(block, mask);
+            double_le(&mut mask);
+        }
+    }
+
+    /// Decrypt a sequence of whole blocks using the nonce and the position of each block as the tweak.
+    pub fn decrypt_blocks(&self, bytes: &mut [u8], nonce: [u8; 16]) {
+        assert!(bytes.len().is_multiple_of(16));
+        let mut mask = nonce;
+        self.cipher.encrypt_block(&mut mask);
+        for block in bytes.chunks_mut(16) {
+            xor_into_bytes(&mut *block, mask);
+            self.cipher.decrypt_block(block);
+            xor_into_bytes(block, mask);
+            double_le(&mut mask);
+        }
+    }
+}
+
+impl<C: BlockCipher<16>> TweakableBlockCipher<16, 24> for Xex<C> {
+    fn encrypt_block_tweaked(&self, bytes: &mut [u8], tweak: &[u8; 24]) {
+        let mask = self.mask(tweak);
+        xor_into_bytes(&mut *bytes, mask);
+        self.cipher.encrypt_block(bytes);
+        xor_into_bytes(bytes, mask);
+    }
+
+    fn decrypt_block_tweaked(&self, bytes: &mut [u8], tweak: &[u8; 24]) {
+        let mask = self.mask(tweak);
+        xor_into_bytes(&mut *bytes, mask);
+        self.cipher.decrypt_block(bytes);
+        xor_into_bytes(bytes, mask);
+    }
+}
+
+/// XTS as in IEEE 1619 for whole blocks. The 128-bit data unit sequence number is encrypted under its own key as
+/// a little endian value and then used as in XEX. Ciphertext stealing for partial final blocks is not included.
+pub struct Xts<C: BlockCipher<16>> {
+    pub cipher: C,
+    pub tweak_cipher: C,
+}
+
+impl<C: BlockCipher<16>> Xts<C> {
+    pub fn new(cipher: C, tweak_cipher: C) -> Self {
+        Self {
+            cipher,
+            tweak_cipher,
+        }
+    }
+
+    fn initial_mask(&self, sequence_number: u128) -> [u8; 16] {
+        let mut mask = sequence_number.to_le_bytes();
+        self.tweak_cipher.encrypt_block(&mut mask);
+        mask
+    }
+
+    /// Encrypt a data unit made of whole blocks.
+    pub fn encrypt_data_unit(&self, bytes: &mut [u8], sequence_number: u128) {
+        assert!(bytes.len().is_multiple_of(16));
+        let mut mask = self.initial_mask(sequence_number);
+        for block in bytes.chunks_mut(16) {
+            xor_into_bytes(&mut *block, mask);
+            self.cipher.encrypt_block(block);
+            xor_into_bytes(block, mask);
+            double_le(&mut mask);
+        }
+    }
+
+    /// Decrypt a data unit made of whole blocks.
+    pub fn decrypt_data_unit(&self, bytes: &mut [u8], sequence_number: u128) {
+        assert!(bytes.len().is_multiple_of(16));
+        let mut mask = self.initial_mask(sequence_number);
+        for block in bytes.chunks_mut(16) {
+            xor_into_bytes(&mut *block, mask);
+            self.cipher.decrypt_block(block);
+            xor_into_bytes(block, mask);
+            double_le(&mut mask);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::digital::block_ciphers::aes::aes::Aes128;
+    use hex_literal::hex;
+
+    fn aes() -> Aes128 {
+        Aes128::default().with_key(hex!("000102030405060708090a0b0c0d0e0f"))
+    }
+
+    #[test]
+    fn lrw_zero_tweak_is_block_cipher() {
+        let lrw = Lrw::new(aes(), 0x0123456789abcdef0123456789abcdef);
+        let mut a = hex!("00112233445566778899aabbccddeeff");
+        let mut b = a;
+        lrw.encrypt_block_tweaked(&mut a, &[0; 16]);
+        aes().encrypt_block(&mut b);
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn lrw_round_trip() {
+        let lrw = Lrw::new(aes(), 0x0123456789abcdef0123456789abcdef);
+        let ptext = hex!("00112233445566778899aabbccddeeff");
+        let mut a = ptext;
+        let mut b = ptext;
+        lrw.encrypt_block_tweaked(&mut a, &[1; 16]);
+        lrw.encrypt_block_tweaked(&mut b, &[2; 16]);
+        assert_ne!(a, b);
+        lrw.decrypt_block_tweaked(&mut a, &[1; 16]);
+        assert_eq!(ptext, a);
+    }
+
+    // XTS-AES vector 1 from IEEE 1619 uses the same all zero key for both halves so it is also a test of XEX
+    #[test]
+    fn xex_xts_vector() {
+        let xex = Xex::new(Aes128::default().with_key([0; 16]));
+        let mut bytes = [0; 32];
+        xex.encrypt_blocks(&mut bytes, [0; 16]);
+        assert_eq!(
+            hex!("917cf69ebd68b2ec9b9fe9a3eadda692cd43d2f59598ed858c02c2652fbf922e"),
+            bytes
+        );
+
+        let mut block = [0; 16];
+        let mut tweak = [0; 24];
+        tweak[23] = 1;
+        xex.encrypt_block_tweaked(&mut block, &tweak);
+        assert_eq!(bytes[16..], block);
+
+        xex.decrypt_blocks(&mut bytes, [0; 16]);
+        assert_eq!([0; 32], bytes);
+    }
+
+    // LRW-AES vectors from the IEEE P1619 drafts, the first 16 bytes of the key are the AES key and the rest the mask key
+    #[test]
+    fn lrw_p1619_vectors() {
+        for (key, tweak, ctext) in [
+            (
+                hex!("4562ac25f828176d4c268414b5680185258e2a05e73e9d03ee5a830ccc094c87"),
+                hex!("00000000000000000000000000000001"),
+                hex!("f1b273cd65a3df5fe95d489254634eb8"),
+            ),
+            (
+                hex!("59704714f557478cd779e80f548879440d48f0b7b15a53ea1caa6b29c2cafbaf"),
+                hex!("00000000000000000000000000000002"),
+                hex!("00c82bae95bbcde5274f0769b260e136"),
+            ),
+        ] {
+            let lrw = Lrw::new(
+                Aes128::default().with_key(key[..16].try_into().unwrap()),
+                u128::from_be_bytes(key[16..].try_into().unwrap()),
+            );
+            let mut block = *b"0123456789ABCDEF";
+            lrw.encrypt_block_tweaked(&mut block, &tweak);
+            assert_eq!(ctext, block);
+            lrw.decrypt_block_tweaked(&mut block, &tweak);
+            assert_eq!(*b"0123456789ABCDEF", block);
+        }
+    }
+
+    // XTS-AES-128 vectors 2 and 3 from IEEE 1619
+    #[test]
+    fn xts_ieee_1619_vectors() {
+        for (key1, ctext) in [
+            (
+                hex!("11111111111111111111111111111111"),
+                hex!("c454185e6a16936e39334038acef838bfb186fff7480adc4289382ecd6d394f0"),
+            ),
+            (
+                hex!("fffefdfcfbfaf9f8f7f6f5f4f3f2f1f0"),
+                hex!("af85336b597afc1a900b2eb21ec949d292df4c047e0b21532186a5971a227a89"),
+            ),
+        ] {
+            let xts = Xts::new(
+                Aes128::default().with_key(key1),
+                Aes128::default().with_key(hex!("22222222222222222222222222222222")),
+            );
+            let mut bytes = [0x44; 32];
+            xts.encrypt_data_unit(&mut bytes, 0x3333333333);
+            assert_eq!(ctext, bytes);
+            xts.decrypt_data_unit(&mut bytes, 0x3333333333);
+            assert_eq!([0x44; 32], bytes);
+        }
+    }
+
+    #[test]
+    fn xex_mask_matches_repeated_doubling() {
+        let xex = Xex::new(aes());
+        let mut tweak = [0; 24];
+        tweak[..16].copy_from_slice(&hex!("000102030405060708090a0b0c0d0e0f"));
+        tweak[16..].copy_from_slice(&300_u64.to_be_bytes());
+        let mut expected: [u8; 16] = tweak[..16].try_into().unwrap();
+        aes().encrypt_block(&mut expected);
+        for _ in 0..300 {
+            double_le(&mut expected);
+        }
+        assert_eq!(expected, xex.mask(&tweak));
+    }
+}
@@ -0,0 +1,270 @@
+use crate::Cipher;
+use hashers::{sha::Sha256, traits::StatefulHasher};
+use num::{BigUint, Zero};
+use rand::Rng;
+use utils::{
+    byte_formatting::ByteFormat,
+    elliptic_curves::{biguint_to_u256, u256_to_biguint, EcDomain, EcPoint, NamedCurve},
+    errors::GeneralError,
+};
+
+// Schnorr signatures on secp256k1 as used by Bitcoin. Public keys are only the x coordinate of a point, the point
+// with even y is always meant, and the nonce point is also chosen to have even y so that a signature is just 64 bytes.
+// https://github.com/bitcoin/bips/blob/master/bip-0340.mediawiki
+
+/// SHA-256(SHA-256(tag) || SHA-256(tag) || data), which keeps hashes used for different purposes apart
+pub fn tagged_hash(tag: &str, parts: &[&[u8]]) -> [u8; 32] {
+    let tag_hash = Sha256::init().hash(tag.as_bytes());
+    let mut h = Sha256::init();
+    h.update(&tag_hash);
+    h.update(&tag_hash);
+    for p in parts {
+        h.update(p);
+    }
+    h.finalize().try_into().unwrap()
+}
+
+fn bytes32(x: &BigUint) -> [u8; 32] {
+    let mut out = [0; 32];
+    let bytes = x.to_bytes_be();
+    out[32 - bytes.len()..].copy_from_slice(&bytes);
+    out
+}
+
+fn has_even_y(p: &EcPoint) -> bool {
+    p.y.map(|y| !bool::from(y.bit(0))).unwrap_or(false)
+}
+
+pub struct Bip340 {
+    pub input_format: ByteFormat,
+    pub output_format: ByteFormat,
+    domain: EcDomain,
+    pub private_key: [u8; 32],
+    pub public_key: [u8; 32],
+    /// Fresh randomness mixed into the nonce to protect against side channels, all zero is still secure
+    pub aux_rand: [u8; 32],
+}
+
+impl Default for Bip340 {
+    fn default() -> Self {
+        Self {
+            input_format: ByteFormat::Utf8,
+            output_format: ByteFormat::Hex,
+            domain: NamedCurve::Secp256k1.domain(),
+            private_key: [0; 32],
+            public_key: [0; 32],
+            aux_rand: [0; 32],
+        }
+    }
+}
+
+impl Bip340 {
+    fn order(&self) -> BigUint {
+        u256_to_biguint(&self.domain.order)
+    }
+
+    fn mul_generator(&self, k: &BigUint) -> EcPoint {
+        self.domain.mul_generator(&biguint_to_u256(k))
+    }
+
+    fn x_bytes(p: &EcPoint) -> [u8; 32] {
+        p.x.expect("point is not infinity").to_be_bytes()
+    }
+
+    pub fn set_private_key(&mut self, key: &[u8; 32]) -> Result<(), GeneralError> {
+        let d = BigUint::from_bytes_be(key);
+        if d.is_zero() || d >= self.order() {
+            return Err(GeneralError::key("private key must be between 1 and n-1"));
+        }
+        self.private_key = *key;
+        self.public_key = Self::x_bytes(&self.mul_generator(&d));
+        Ok(())
+    }
+
+    pub fn generate_key<R: Rng>(&mut self, rng: &mut R) {
+        loop {
+            let key: [u8; 32] = rng.gen();
+            if self.set_private_key(&key).is_ok() {
+                return;
+            }
+        }
+    }
+
+    /// The point with even y and the given x coordinate, if there is one
+    pub fn lift_x(&self, x: &[u8; 32]) -> Option<EcPoint> {
+        let x = BigUint::from_bytes_be(x);
+        if x >= u256_to_biguint(&self.domain.curve.m) {
+            return None;
+        }
+        self.domain.curve.lift_x(&biguint_to_u256(&x), false)
+    }
+
+    pub fn sign_with_aux(&self, msg: &[u8], aux_rand: &[u8; 32]) -> Result<[u8; 64], GeneralError> {
+        let n = self.order();
+        let d0 = BigUint::from_bytes_be(&self.private_key);
+        if d0.is_zero() || d0 >= n {
+            return Err(GeneralError::state("private key has not been set"));
+        }
+        // Negate the key if needed so that it matches the public point with even y
+        let p = self.mul_generator(&d0);
+        let d = if has_even_y(&p) { d0 } else { &n - d0 };
+
+        let mut t = bytes32(&d);
+        for (a, b) in t.iter_mut().zip(tagged_hash("BIP0340/aux", &[aux_rand])) {
+            *a ^= b;
+        }
+        let k0 =
+            BigUint::from_bytes_be(&tagged_hash("BIP0340/nonce", &[&t, &self.public_key, msg]))
+                % &n;
+        if k0.is_zero() {
+            return Err(GeneralError::general("nonce is zero"));
+        }
+        let r = self.mul_generator(&k0);
+        let k = if has_even_y(&r) { k0 } else { &n - k0 };
+        let r_bytes = Self::x_bytes(&r);
+
+        let e = BigUint::from_bytes_be(&tagged_hash(
+            "BIP0340/challenge",
+            &[&r_bytes, &self.public_key, msg],
+        )) % &n;
+
+        let mut out = [0; 64];
+        out[..32].copy_from_slice(&r_bytes);
+        out[32..].copy_from_slice(&bytes32(&((k + e * d) % &n)));
+        if !self.verify(msg, &out) {
+            return Err(GeneralError::general("created an invalid signature"));
+        }
+        Ok(out)
+    }
+
+    pub fn sign(&self, msg: &[u8]) -> Result<[u8; 64], GeneralError> {
+        self.sign_with_aux(msg, &self.aux_rand)
+    }
+
+    pub fn verify(&self, msg: &[u8], signature: &[u8]) -> bool {
+        if signature.len() != 64 {
+            return false;
+        }
+        let Some(p) = self.lift_x(&self.public_key) else {
+            return false;
+        };
+        let n = self.order();
+        let r = BigUint::from_bytes_be(&signature[..32]);
+        let s = BigUint::from_bytes_be(&signature[32..]);
+        if r >= u256_to_biguint(&self.domain.curve.m) || s >= n {
+            return false;
+        }
+        let e = BigUint::from_bytes_be(&tagged_hash(
+            "BIP0340/challenge",
+            &[&signature[..32], &self.public_key, msg],
+        )) % &n;
+
+        // R = sG - eP
+        let curve = &self.domain.curve;
+        let point = curve.add(
+            &self.mul_generator(&s),
+            &curve.inverse(&curve.scalar_mul(&p, &biguint_to_u256(&e))),
+        );
+        !point.is_inf() && has_even_y(&point) && Self::x_bytes(&point)[..] == signature[..32]
+    }
+}
+
+impl Cipher for Bip340 {
+    fn encrypt(&self, text: &str) -> Result<String, GeneralError> {
+        let bytes = self
+            .input_format
+            .text_to_bytes(text)
+            .map_err(|_| GeneralError::input("byte format error"))?;
+        Ok(self.output_format.byte_slice_to_text(self.sign(&bytes)?))
+    }
+
+    fn decrypt(&self, _text: &str) -> Result<String, GeneralError> {
+        Err(GeneralError::general(
+            "Schnorr signatures cannot be decrypted",
+        ))
+    }
+}
+
+#[cfg(test)]
+mod bip340_tests {
+
+    use super::*;
+
+    fn hex32(s: &str) -> [u8; 32] {
+        ByteFormat::Hex
+            .text_to_bytes(s)
+            .unwrap()
+            .try_into()
+            .unwrap()
+    }
+
+    // Private key, public key, aux_rand, message, signature from the BIP's test vectors
+    const VECTORS: [(&str, &str, &str, &str, &str); 4] = [
+        (
+            "0000000000000000000000000000000000000000000000000000000000000003",
+            "F9308A019258C31049344F85F89D5229B531C845836F99B08601F113BCE036F9",
+            "0000000000000000000000000000000000000000000000000000000000000000",
+            "0000000000000000000000000000000000000000000000000000000000000000",
+            "E907831F80848D1069A5371B402410364BDF1C5F8307B0084C55F1CE2DCA821525F66A4A85EA8B71E482A74F382D2CE5EBEEE8FDB2172F477DF4900D310536C0",
+        ),
+        (
+            "B7E151628AED2A6ABF7158809CF4F3C762E7160F38B4DA56A784D9045190CFEF",
+            "DFF1D77F2A671C5F36183726DB2341BE58FEAE1DA2DECED843240F7B502BA659",
+            "0000000000000000000000000000000000000000000000000000000000000001",
+            "243F6A8885A308D313198A2E03707344A4093822299F31D0082EFA98EC4E6C89",
+            "6896BD60EEAE296DB48A229FF71DFE071BDE413E6D43F917DC8DCF8C78DE33418906D11AC976ABCCB20B091292BFF4EA897EFCB639EA871CFA95F6DE339E4B0A",
+        ),
+        (
+            "C90FDAA22168C234C4C6628B80DC1CD129024E088A67CC74020BBEA63B14E5C9",
+            "DD308AFEC5777E13121FA72B9CC1B7CC0139715309B086C960E18FD969774EB8",
+            "C87AA53824B4D7AE2EB035A2B5BBBCCC080E76CDC6D1692C4B0B62D798E6D906",
+            "7E2D58D8B3BCDF1ABADEC7829054F90DDA9805AAB56C77333024B9D0A508B75C",
+            "5831AAEED7B44BB74E5EAB94BA9D4294C49BCF2A60728D8B4C200F50DD313C1BAB745879A5AD954A72C45A91C3A51D3C7ADEA98D82F8481E0E1E03674A6F3FB7",
+        ),
+        (
+            "0340034003400340034003400340034003400340034003400340034003400340",
+            "778CAA53B4393AC467774D09497A87224BF9FAB6F6E68B23086497324D6FD117",
+            "0000000000000000000000000000000000000000000000000000000000000000",
+            "",
+            "71535DB165ECD9FBBC046E5FFAEA61186BB6AD436732FCCC25291A55895464CF6069CE26BF03466228F19A3A62DB8A649F2D560FAC652827D1AF0574E427AB63",
+        ),
+    ];
+
+    #[test]
+    fn test_vectors() {
+        for (sk, pk, aux, msg, sig) in VECTORS {
+            let mut cipher = Bip340::default();
+            cipher.set_private_key(&hex32(sk)).unwrap();
+            assert_eq!(hex32(pk), cipher.public_key);
+            let msg = ByteFormat::Hex.text_to_bytes(msg).unwrap();
+            let signature = cipher.sign_with_aux(&msg, &hex32(aux)).unwrap();
+            assert_eq!(ByteFormat::Hex.text_to_bytes(sig).unwrap(), signature);
+            assert!(cipher.verify(&msg, &signature));
+        }
+    }
+
+    #[test]
+    fn invalid_signatures() {
+        let (_, pk, _, msg, sig) = VECTORS[1];
+        let mut verifier = Bip340 {
+            public_key: hex32(pk),
+            ..Default::default()
+        };
+        let msg = ByteFormat::Hex.text_to_bytes(msg).unwrap();
+        let sig = ByteFormat::Hex.text_to_bytes(sig).unwrap();
+        assert!(verifier.verify(&msg, &sig));
+
+        for i in [0, 31, 32, 63] {
+            let mut bad = sig.clone();
+            bad[i] ^= 1;
+            assert!(!verifier.verify(&msg, &bad));
+        }
+        assert!(!verifier.verify(&msg[1..], &sig));
+        assert!(!verifier.verify(&msg, &sig[..63]));
+
+        // No point has this x coordinate
+        verifier.public_key =
+            hex32("EEFDEA4CDB677750A420FEE807EACF21EB9898AE79B9768766E4FAA04A2D4A34");
+        assert!(!verifier.verify(&msg, &sig));
+    }
+}
@@ -0,0 +1,255 @@
+use super::{dh_groups::DhGroup, rfc6979::int2octets};
+use crate::Cipher;
+use hashers::hmac::HmacVariant;
+use num::{BigUint, One, Zero};
+use rand::{thread_rng, Rng};
+use utils::{byte_formatting::ByteFormat, errors::GeneralError, primality::random_biguint_below};
+
+// Cramer-Shoup encryption. It extends ElGamal with a second generator and a hash based check value so that any
+// change to a ciphertext is detected, making it the first practical scheme secure against adaptive chosen ciphertext
+// attacks without random oracles. The group is the subgroup of prime order q = (p-1)/2 of a safe prime group.
+// https://doi.org/10.1007/BFb0055717
+pub struct CramerShoup {
+    pub input_format: ByteFormat,
+    pub output_format: ByteFormat,
+
+    pub modulus: BigUint,
+    pub order: BigUint,
+    pub g1: BigUint,
+    pub g2: BigUint,
+    pub hash: HmacVariant,
+
+    pub x1: BigUint,
+    pub x2: BigUint,
+    pub y1: BigUint,
+    pub y2: BigUint,
+    pub z: BigUint,
+
+    pub c: BigUint,
+    pub d: BigUint,
+    pub h: BigUint,
+}
+
+impl Default for CramerShoup {
+    fn default() -> Self {
+        Self {
+            input_format: ByteFormat::Utf8,
+            output_format: ByteFormat::Hex,
+
+            modulus: DhGroup::Modp2048.modulus(),
+            order: DhGroup::Modp2048.order(),
+            g1: DhGroup::Modp2048.generator(),
+            g2: BigUint::zero(),
+            hash: HmacVariant::Sha256,
+
+            x1: BigUint::zero(),
+            x2: BigUint::zero(),
+            y1: BigUint::zero(),
+            y2: BigUint::zero(),
+            z: BigUint::zero(),
+
+            c: BigUint::zero(),
+            d: BigUint::zero(),
+            h: BigUint::zero(),
+        }
+    }
+}
+
+impl CramerShoup {
+    fn len(&self) -> usize {
+        self.modulus.bits().div_ceil(8) as usize
+    }
+
+    // Both generators have order q so elements of the subgroup are exactly those with x^q = 1
+    fn in_subgroup(&self, x: &BigUint) -> bool {
+        !x.is_zero() && *x < self.modulus && x.modpow(&self.order, &self.modulus).is_one()
+    }
+
+    /// Choose a new second generator and private key
+    pub fn generate_key<R: Rng>(&mut self, rng: &mut R) {
+        let p = &self.modulus;
+        let q = &self.order;
+        let mut nonzero = || random_biguint_below(&(q - 1_u32), rng) + 1_u32;
+        self.g2 = self.g1.modpow(&nonzero(), p);
+        self.x1 = nonzero();
+        self.x2 = nonzero();
+        self.y1 = nonzero();
+        self.y2 = nonzero();
+        self.z = nonzero();
+        self.c = (self.g1.modpow(&self.x1, p) * self.g2.modpow(&self.x2, p)) % p;
+        self.d = (self.g1.modpow(&self.y1, p) * self.g2.modpow(&self.y2, p)) % p;
+        self.h = self.g1.modpow(&self.z, p);
+    }
+
+    /// The message is read as the integer 0x01 || msg, which must be less than q, and then mapped into the subgroup.
+    /// Since -1 is not a square modulo a safe prime exactly one of m and p-m is in the subgroup.
+    pub fn encode(&self, msg: &[u8]) -> Result<BigUint, GeneralError> {
+        let mut bytes = vec![0x01];
+        bytes.extend_from_slice(msg);
+        let m = BigUint::from_bytes_be(&bytes);
+        if m > self.order {
+            return Err(GeneralError::input("message is too long for the group"));
+        }
+        if self.in_subgroup(&m) {
+            Ok(m)
+        } else {
+            Ok(&self.modulus - m)
+        }
+    }
+
+    pub fn decode(&self, e: &BigUint) -> Result<Vec<u8>, GeneralError> {
+        let m = e.min(&(&self.modulus - e)).to_bytes_be();
+        match m.split_first() {
+            Some((0x01, msg)) => Ok(msg.to_vec()),
+            _ => Err(GeneralError::input(
+                "decrypted value is not a valid encoding",
+            )),
+        }
+    }
+
+    fn alpha(&self, u1: &BigUint, u2: &BigUint, e: &BigUint) -> BigUint {
+        let mut bytes = int2octets(u1, self.len());
+        bytes.extend(int2octets(u2, self.len()));
+        bytes.extend(int2octets(e, self.len()));
+        BigUint::from_bytes_be(&self.hash.hash(&bytes)) % &self.order
+    }
+
+    /// Encrypt an encoded message with the nonce k, giving (u1, u2, e, v)
+    pub fn encrypt_with_nonce(
+        &self,
+        m: &BigUint,
+        k: &BigUint,
+    ) -> Result<[BigUint; 4], GeneralError> {
+        if self.h.is_zero() {
+            return Err(GeneralError::state("key has not been set"));
+        }
+        if !self.in_subgroup(m) {
+            return Err(GeneralError::input("message is not in the subgroup"));
+        }
+        let p = &self.modulus;
+        let u1 = self.g1.modpow(k, p);
+        let u2 = self.g2.modpow(k, p);
+        let e = (self.h.modpow(k, p) * m) % p;
+        let alpha = self.alpha(&u1, &u2, &e);
+        let v = (self.c.modpow(k, p) * self.d.modpow(&((k * alpha) % &self.order), p)) % p;
+        Ok([u1, u2, e, v])
+    }
+
+    /// Reject the ciphertext unless u1^(x1 + y1 alpha) u2^(x2 + y2 alpha) = v and then recover m = e / u1^z
+    pub fn decrypt_elements(&self, ciphertext: &[BigUint; 4]) -> Result<BigUint, GeneralError> {
+        if self.z.is_zero() {
+            return Err(GeneralError::state("private key has not been set"));
+        }
+        let [u1, u2, e, v] = ciphertext;
+        if !ciphertext.iter().all(|x| self.in_subgroup(x)) {
+            return Err(GeneralError::input("ciphertext rejected"));
+        }
+        let p = &self.modulus;
+        let q = &self.order;
+        let alpha = self.alpha(u1, u2, e);
+        let check = (u1.modpow(&((&self.x1 + &self.y1 * &alpha) % q), p)
+            * u2.modpow(&((&self.x2 + &self.y2 * &alpha) % q), p))
+            % p;
+        if check != *v {
+            return Err(GeneralError::input("ciphertext rejected"));
+        }
+        // u1^-z is u1^(q-z) because u1 has order q
+        Ok((e * u1.modpow(&(q - &self.z), p)) % p)
+    }
+
+    pub fn encrypt_bytes(&self, bytes: &[u8]) -> Result<Vec<u8>, GeneralError> {
+        let m = self.encode(bytes)?;
+        let k = random_biguint_below(&(&self.order - 1_u32), &mut thread_rng()) + 1_u32;
+        let mut out = Vec::with_capacity(4 * self.len());
+        for x in self.encrypt_with_nonce(&m, &k)? {
+            out.extend(int2octets(&x, self.len()));
+        }
+        Ok(out)
+    }
+
+    pub fn decrypt_bytes(&self, bytes: &[u8]) -> Result<Vec<u8>, GeneralError> {
+        let len = self.len();
+        if bytes.len() != 4 * len {
+            return Err(GeneralError::input(format!(
+                "ciphertext must be exactly {} bytes",
+                4 * len
+            )));
+        }
+        let ciphertext: [BigUint; 4] =
+            std::array::from_fn(|i| BigUint::from_bytes_be(&bytes[i * len..(i + 1) * len]));
+        self.decode(&self.decrypt_elements(&ciphertext)?)
+    }
+}
+
+impl Cipher for CramerShoup {
+    fn encrypt(&self, text: &str) -> Result<String, GeneralError> {
+        let bytes = self
+            .input_format
+            .text_to_bytes(text)
+            .map_err(|_| GeneralError::input("byte format error"))?;
+        Ok(self
+            .output_format
+            .byte_slice_to_text(self.encrypt_bytes(&bytes)?))
+    }
+
+    fn decrypt(&self, text: &str) -> Result<String, GeneralError> {
+        let bytes = self
+            .input_format
+            .text_to_bytes(text)
+            .map_err(|_| GeneralError::input("byte format error"))?;
+        Ok(self
+            .output_format
+            .byte_slice_to_text(self.decrypt_bytes(&bytes)?))
+    }
+}
+
+#[cfg(test)]
+mod cramer_shoup_tests {
+
+    use super::*;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    fn cipher() -> CramerShoup {
+        let mut cipher = CramerShoup::default();
+        cipher.generate_key(&mut StdRng::seed_from_u64(8));
+        cipher
+    }
+
+    #[test]
+    fn encrypt_decrypt() {
+        let mut cipher = cipher();
+        let ctext = cipher.encrypt("attack at dawn").unwrap();
+        assert_ne!(ctext, cipher.encrypt("attack at dawn").unwrap());
+        cipher.output_format = ByteFormat::Utf8;
+        cipher.input_format = ByteFormat::Hex;
+        assert_eq!("attack at dawn", cipher.decrypt(&ctext).unwrap());
+    }
+
+    #[test]
+    fn tampering_rejected() {
+        let cipher = cipher();
+        let m = cipher.encode(b"attack at dawn").unwrap();
+        let [u1, u2, e, v] = cipher
+            .encrypt_with_nonce(&m, &BigUint::from(123456789_u32))
+            .unwrap();
+        assert_eq!(
+            m,
+            cipher
+                .decrypt_elements(&[u1.clone(), u2.clone(), e.clone(), v.clone()])
+                .unwrap()
+        );
+
+        // Multiplying e by a group element would change the message undetectably in plain ElGamal
+        let p = &cipher.modulus;
+        let mauled = (&e * &cipher.g1) % p;
+        assert!(cipher
+            .decrypt_elements(&[u1.clone(), u2.clone(), mauled, v.clone()])
+            .is_err());
+        let mauled = (&u2 * &cipher.g2) % p;
+        assert!(cipher
+            .decrypt_elements(&[u1.clone(), mauled, e.clone(), v.clone()])
+            .is_err());
+        // Elements outside the subgroup are rejected before anything else
+        assert!(cipher.decrypt_elements(&[u1, u2, p - e, v]).is_err());
+    }
+}
@@ -1,45 +1,167 @@
-use num::integer::gcd;
-use utils::math_functions::mod_pow_32;
-
+use super::dh_groups::DhGroup;
 use crate::Cipher;
+use hashers::{hkdf::Hkdf, hmac::HmacVariant};
+use num::{integer::gcd, BigUint, One};
+use rand::Rng;
+use utils::{
+    errors::GeneralError,
+    primality::{is_probable_prime, random_biguint_below},
+};
 
 pub struct DiffieHellman {
-    pub private_keys: Vec<u32>,
-    pub generator: u32,
-    pub modulus: u32,
+    /// None for a small hand chosen group, otherwise one of the standard groups
+    pub group: Option<DhGroup>,
+    pub private_keys: Vec<BigUint>,
+    pub generator: BigUint,
+    pub modulus: BigUint,
+    /// The order of the subgroup generated by the generator, when it is known
+    pub order: Option<BigUint>,
+    pub kdf: HmacVariant,
 }
 
 impl Default for DiffieHellman {
     fn default() -> Self {
         Self {
-            private_keys: vec![4, 3],
-            generator: 5,
-            modulus: 23,
+            group: None,
+            private_keys: vec![BigUint::from(4_u32), BigUint::from(3_u32)],
+            generator: BigUint::from(5_u32),
+            modulus: BigUint::from(23_u32),
+            order: None,
+            kdf: HmacVariant::Sha256,
         }
     }
 }
 
 impl DiffieHellman {
+    pub fn with_group(mut self, group: DhGroup) -> Self {
+        self.set_group(group);
+        self
+    }
+
+    /// A small group for working by hand, the order of the generator is not tracked
+    pub fn set_toy_group(&mut self, generator: u32, modulus: u32) {
+        self.group = None;
+        self.generator = BigUint::from(generator);
+        self.modulus = BigUint::from(modulus);
+        self.order = None;
+    }
+
+    pub fn set_group(&mut self, group: DhGroup) {
+        self.group = Some(group);
+        self.generator = group.generator();
+        self.modulus = group.modulus();
+        self.order = Some(group.order());
+    }
+
     // Check if g is a generator in the multiplicative group
     pub fn g_is_valid(&self) -> bool {
-        gcd(self.generator, self.modulus) == 1
+        gcd(self.generator.clone(), self.modulus.clone()).is_one()
+    }
+
+    /// Check that the modulus is prime and, if the order of the generator is given, that it is a prime dividing p-1
+    /// and really is the order of g. A prime order rules out small subgroups for private keys to fall into.
+    pub fn validate_parameters<R: Rng>(&self, rng: &mut R) -> Result<(), GeneralError> {
+        let p = &self.modulus;
+        if !is_probable_prime(p, 20, rng) {
+            return Err(GeneralError::key("modulus is not prime"));
+        }
+        if self.generator <= BigUint::one() || &self.generator >= p {
+            return Err(GeneralError::key("generator must be between 2 and p-1"));
+        }
+        if let Some(q) = &self.order {
+            if !is_probable_prime(q, 20, rng) {
+                return Err(GeneralError::key("subgroup order is not prime"));
+            }
+            if ((p - 1_u32) % q) != BigUint::ZERO {
+                return Err(GeneralError::key("subgroup order does not divide p-1"));
+            }
+            if !self.generator.modpow(q, p).is_one() {
+                return Err(GeneralError::key("generator does not have the given order"));
+            }
+        }
+        Ok(())
     }
 
-    pub fn public_keys(&self) -> Vec<u32> {
-        Vec::from_iter(
-            self.private_keys
-                .iter()
-                .map(|p| mod_pow_32(self.generator, *p, self.modulus)),
-        )
+    /// The number of bytes needed to hold any element of the group
+    pub fn element_len(&self) -> usize {
+        self.modulus.bits().div_ceil(8) as usize
     }
 
-    pub fn shared_key(&self) -> u32 {
-        let mut b = self.generator;
+    /// A random private key between 2 and q-1, or between 2 and p-2 if the order is unknown
+    pub fn generate_private_key<R: Rng>(&self, rng: &mut R) -> BigUint {
+        let bound = match &self.order {
+            Some(q) => q - 2_u32,
+            None => &self.modulus - 3_u32,
+        };
+        random_biguint_below(&bound, rng) + 2_u32
+    }
+
+    pub fn randomize_private_keys<R: Rng>(&mut self, rng: &mut R) {
+        for i in 0..self.private_keys.len() {
+            self.private_keys[i] = self.generate_private_key(rng);
+        }
+    }
+
+    pub fn public_key(&self, private_key: &BigUint) -> BigUint {
+        self.generator.modpow(private_key, &self.modulus)
+    }
+
+    pub fn public_keys(&self) -> Vec<BigUint> {
+        Vec::from_iter(self.private_keys.iter().map(|p| self.public_key(p)))
+    }
+
+    /// The key shared by every party, found by raising the generator to each private key in turn
+    pub fn shared_key(&self) -> BigUint {
+        let mut b = self.generator.clone();
         for k in self.private_keys.iter() {
-            b = mod_pow_32(b, *k, self.modulus);
+            b = b.modpow(k, &self.modulus);
         }
         b
     }
+
+    /// Reject 0, 1 and p-1 and, when the order q of the subgroup is known, any value y with y^q != 1. Otherwise an
+    /// attacker can send an element of small order and learn the private key modulo that order from the result.
+    pub fn validate_public_key(&self, y: &BigUint) -> Result<(), GeneralError> {
+        let p = &self.modulus;
+        if y <= &BigUint::one() || y >= &(p - 1_u32) {
+            return Err(GeneralError::key("public key must be between 2 and p-2"));
+        }
+        if let Some(q) = &self.order {
+            if !y.modpow(q, p).is_one() {
+                return Err(GeneralError::key(
+                    "public key is not in the subgroup of order q",
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// The shared secret for two parties as a big endian integer padded to the length of the modulus
+    pub fn shared_secret(
+        &self,
+        private_key: &BigUint,
+        peer: &BigUint,
+    ) -> Result<Vec<u8>, GeneralError> {
+        self.validate_public_key(peer)?;
+        let z = peer.modpow(private_key, &self.modulus).to_bytes_be();
+        let mut out = vec![0; self.element_len() - z.len()];
+        out.extend(z);
+        Ok(out)
+    }
+
+    /// The shared secret is not uniformly distributed and should not be used as a key directly, instead it is passed
+    /// through HKDF
+    pub fn derive_key(
+        &self,
+        private_key: &BigUint,
+        peer: &BigUint,
+        salt: &[u8],
+        info: &[u8],
+        length: usize,
+    ) -> Result<Vec<u8>, GeneralError> {
+        let z = self.shared_secret(private_key, peer)?;
+        Ok(Hkdf::derive_key(self.kdf, length, &z, salt, info))
+    }
 }
 
 impl Cipher for DiffieHellman {
@@ -58,10 +180,82 @@ impl Cipher for DiffieHellman {
 mod diffie_hellman_tests {
 
     use super::*;
+    use rand::{rngs::StdRng, SeedableRng};
+    use utils::byte_formatting::ByteFormat;
+
+    fn big(hex: &str) -> BigUint {
+        BigUint::parse_bytes(hex.as_bytes(), 16).unwrap()
+    }
+
     #[test]
     fn test_keys() {
         let cipher = DiffieHellman::default();
-        assert_eq!(vec![4, 10], cipher.public_keys());
-        assert_eq!(18, cipher.shared_key());
+        assert_eq!(
+            vec![BigUint::from(4_u32), BigUint::from(10_u32)],
+            cipher.public_keys()
+        );
+        assert_eq!(BigUint::from(18_u32), cipher.shared_key());
+    }
+
+    #[test]
+    fn standard_group() {
+        let cipher = DiffieHellman::default().with_group(DhGroup::Ffdhe2048);
+        let a = big("9c1a4d6b13f0e2c5b8a7d3e1f4c2b6a8d0e9f7c5a3b1d2e4f6a8c0b2d4e6f8a1");
+        let b = big("5e3c1a2b4d6f8e0c9a7b5d3f1e2c4a6b8d0f9e7c5a3b1d2f4e6c8a0b2d4f6e81");
+        let ya = cipher.public_key(&a);
+        let yb = cipher.public_key(&b);
+        let za = cipher.shared_secret(&a, &yb).unwrap();
+        assert_eq!(za, cipher.shared_secret(&b, &ya).unwrap());
+        assert_eq!(256, za.len());
+
+        // Secret and derived key computed with Python's cryptography library
+        assert_eq!(
+            "b938f782d8066febbc92a47764e441fa",
+            ByteFormat::Hex.byte_slice_to_text(&za[..16])
+        );
+        assert_eq!(
+            "4bc4f65910ec777d910c62cb2585acbc",
+            ByteFormat::Hex.byte_slice_to_text(
+                cipher
+                    .derive_key(&a, &yb, b"salt", b"handshake", 16)
+                    .unwrap()
+            )
+        );
+    }
+
+    #[test]
+    fn invalid_public_keys() {
+        let cipher = DiffieHellman::default().with_group(DhGroup::Modp2048);
+        let x = BigUint::from(123456789_u32);
+        let p = cipher.modulus.clone();
+        for y in [
+            BigUint::ZERO,
+            BigUint::one(),
+            &p - 1_u32,
+            p.clone(),
+            // Outside the subgroup of order q since 2 is a quadratic residue and -2 is not
+            &p - 2_u32,
+        ] {
+            assert!(cipher.shared_secret(&x, &y).is_err());
+        }
+        assert!(cipher.shared_secret(&x, &BigUint::from(4_u32)).is_ok());
+    }
+
+    #[test]
+    fn parameters() {
+        let mut rng = StdRng::seed_from_u64(3);
+        let mut cipher = DiffieHellman::default().with_group(DhGroup::Modp1536);
+        assert!(cipher.validate_parameters(&mut rng).is_ok());
+        let x = cipher.generate_private_key(&mut rng);
+        assert!(x >= BigUint::from(2_u32) && &x < cipher.order.as_ref().unwrap());
+
+        // Claiming that the generator has order p-1 is rejected because it is not prime
+        cipher.order = Some(&cipher.modulus - 1_u32);
+        assert!(cipher.validate_parameters(&mut rng).is_err());
+
+        cipher.set_toy_group(5, 24);
+        assert!(cipher.validate_parameters(&mut rng).is_err());
+        cipher.set_toy_group(5, 23);
+        assert!(cipher.validate_parameters(&mut rng).is_ok());
     }
 }
@@ -1,49 +1,96 @@
-use num::integer::gcd;
-use utils::math_functions::mod_pow_32;
-
+use super::{dh_groups::DhGroup, diffie_hellman::DiffieHellman};
 use crate::Cipher;
+use hashers::hkdf::Hkdf;
+use num::BigUint;
+use rand::Rng;
+use utils::errors::GeneralError;
 
+/// Triple Diffie-Hellman, as in the Signal handshake. Each of the two parties has a long term identity key and a fresh
+/// ephemeral key. Three exchanges are combined: identity of the first with ephemeral of the second, ephemeral of the
+/// first with identity of the second, and the two ephemeral keys. The first two authenticate the parties and the last
+/// gives forward secrecy.
 pub struct DiffieHellmanTriple {
-    pub private_keys: Vec<u32>,
-    pub ephemeral_keys: Vec<u32>,
-    pub generator: u32,
-    pub modulus: u32,
+    /// The group and KDF, the private keys inside are not used
+    pub dh: DiffieHellman,
+    pub private_keys: Vec<BigUint>,
+    pub ephemeral_keys: Vec<BigUint>,
 }
 
 impl Default for DiffieHellmanTriple {
     fn default() -> Self {
         Self {
-            private_keys: vec![4, 3],
-            ephemeral_keys: vec![7, 10],
-            generator: 5,
-            modulus: 23,
+            dh: DiffieHellman::default(),
+            private_keys: vec![BigUint::from(4_u32), BigUint::from(3_u32)],
+            ephemeral_keys: vec![BigUint::from(7_u32), BigUint::from(10_u32)],
         }
     }
 }
 
 impl DiffieHellmanTriple {
+    pub fn with_group(mut self, group: DhGroup) -> Self {
+        self.dh.set_group(group);
+        self
+    }
+
+    /// A small group for working by hand
+    pub fn set_toy_group(&mut self, generator: u32, modulus: u32) {
+        self.dh.set_toy_group(generator, modulus);
+    }
+
+    pub fn set_group(&mut self, group: DhGroup) {
+        self.dh.set_group(group);
+    }
+
     // Check if g is a generator in the multiplicative group
     pub fn g_is_valid(&self) -> bool {
-        gcd(self.generator, self.modulus) == 1
+        self.dh.g_is_valid()
     }
 
-    pub fn public_keys(&self) -> Vec<(u32, u32)> {
-        let mut out = Vec::with_capacity(self.private_keys.len());
+    pub fn randomize_keys<R: Rng>(&mut self, rng: &mut R) {
         for i in 0..self.private_keys.len() {
-            out.push((
-                mod_pow_32(self.generator, self.private_keys[i], self.modulus),
-                mod_pow_32(self.generator, self.ephemeral_keys[i], self.modulus),
-            ))
+            self.private_keys[i] = self.dh.generate_private_key(rng);
+            self.ephemeral_keys[i] = self.dh.generate_private_key(rng);
         }
-        out
     }
 
-    pub fn shared_key(&self) -> u32 {
-        let mut b = self.generator;
-        for k in self.private_keys.iter() {
-            b = mod_pow_32(b, *k, self.modulus);
+    /// The identity and ephemeral public keys of each party
+    pub fn public_keys(&self) -> Vec<(BigUint, BigUint)> {
+        self.private_keys
+            .iter()
+            .zip(self.ephemeral_keys.iter())
+            .map(|(k, e)| (self.dh.public_key(k), self.dh.public_key(e)))
+            .collect()
+    }
+
+    /// The three shared secrets concatenated in a fixed order, as computed by one party (0 or 1) from its own private
+    /// keys and the other party's public keys. Both parties arrive at the same value.
+    pub fn shared_secret(&self, party: usize) -> Result<Vec<u8>, GeneralError> {
+        if party > 1 {
+            return Err(GeneralError::input("party must be 0 or 1"));
         }
-        b
+        let public = self.public_keys();
+        let (own_id, own_eph) = (&self.private_keys[party], &self.ephemeral_keys[party]);
+        let (peer_id, peer_eph) = &public[1 - party];
+        let (a, b) = (
+            self.dh.shared_secret(own_id, peer_eph)?,
+            self.dh.shared_secret(own_eph, peer_id)?,
+        );
+        // The first exchange is always the identity key of party 0 with the ephemeral key of party 1
+        let (first, second) = if party == 0 { (a, b) } else { (b, a) };
+        let third = self.dh.shared_secret(own_eph, peer_eph)?;
+        Ok([first, second, third].concat())
+    }
+
+    /// Pass the combined secret through HKDF
+    pub fn derive_key(
+        &self,
+        party: usize,
+        salt: &[u8],
+        info: &[u8],
+        length: usize,
+    ) -> Result<Vec<u8>, GeneralError> {
+        let z = self.shared_secret(party)?;
+        Ok(Hkdf::derive_key(self.dh.kdf, length, &z, salt, info))
     }
 }
 
@@ -58,3 +105,59 @@ impl Cipher for DiffieHellmanTriple {
         self.encrypt(text)
     }
 }
+
+#[cfg(test)]
+mod diffie_hellman_triple_tests {
+
+    use super::*;
+    use rand::{rngs::StdRng, SeedableRng};
+    use utils::byte_formatting::ByteFormat;
+
+    #[test]
+    fn toy_group() {
+        let cipher = DiffieHellmanTriple::default();
+        assert_eq!(
+            vec![
+                (BigUint::from(4_u32), BigUint::from(17_u32)),
+                (BigUint::from(10_u32), BigUint::from(9_u32))
+            ],
+            cipher.public_keys()
+        );
+        // 9^4, 10^7 and 9^7 modulo 23
+        assert_eq!(vec![6, 14, 4], cipher.shared_secret(0).unwrap());
+        assert_eq!(vec![6, 14, 4], cipher.shared_secret(1).unwrap());
+    }
+
+    #[test]
+    fn standard_group() {
+        let mut cipher = DiffieHellmanTriple::default().with_group(DhGroup::Ffdhe2048);
+        cipher.randomize_keys(&mut StdRng::seed_from_u64(7));
+        let z = cipher.shared_secret(0).unwrap();
+        assert_eq!(768, z.len());
+        assert_eq!(z, cipher.shared_secret(1).unwrap());
+        assert_eq!(
+            cipher.derive_key(0, b"salt", b"handshake", 32).unwrap(),
+            cipher.derive_key(1, b"salt", b"handshake", 32).unwrap()
+        );
+    }
+
+    #[test]
+    fn fixed_keys() {
+        let mut cipher = DiffieHellmanTriple::default().with_group(DhGroup::Modp2048);
+        cipher.private_keys = vec![BigUint::from(1001_u32), BigUint::from(2002_u32)];
+        cipher.ephemeral_keys = vec![BigUint::from(3003_u32), BigUint::from(4004_u32)];
+        // Computed with Python's pow() and the HKDF of the cryptography library
+        assert_eq!(
+            "bbccdac803a935cd8d2ee6e34deb048b",
+            ByteFormat::Hex
+                .byte_slice_to_text(cipher.derive_key(0, b"salt", b"handshake", 16).unwrap())
+        );
+    }
+
+    #[test]
+    fn invalid_public_key() {
+        let mut cipher = DiffieHellmanTriple::default().with_group(DhGroup::Modp2048);
+        cipher.ephemeral_keys[1] = BigUint::ZERO;
+        assert!(cipher.shared_secret(0).is_err());
+    }
+}
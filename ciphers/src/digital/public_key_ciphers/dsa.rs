@@ -0,0 +1,469 @@
+use super::rfc6979::{bits2int, int2octets, Rfc6979};
+use crate::Cipher;
+use hashers::hmac::HmacVariant;
+use num::{BigUint, One, Zero};
+use rand::Rng;
+use utils::{
+    byte_formatting::ByteFormat,
+    errors::GeneralError,
+    math_functions::mul_inv,
+    primality::{is_probable_prime, random_biguint_below},
+};
+
+// https://nvlpubs.nist.gov/nistpubs/FIPS/NIST.FIPS.186-4.pdf
+
+/// The pairs of bit lengths (L, N) for p and q allowed by FIPS 186-4
+pub const DSA_SIZES: [(u64, u64); 4] = [(1024, 160), (2048, 224), (2048, 256), (3072, 256)];
+
+/// Primes p and q with q dividing p-1 and a generator g of the subgroup of order q
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DsaParameters {
+    pub p: BigUint,
+    pub q: BigUint,
+    pub g: BigUint,
+}
+
+/// The seed and counter that produced p and q, which let anyone check that the primes were not chosen to have some
+/// hidden weakness
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DsaSeed {
+    pub seed: Vec<u8>,
+    pub counter: usize,
+}
+
+// Parameters with L = 2048 and N = 256 generated with SHA-256, the seed is in the tests
+const DEFAULT_P: &str = concat!(
+    "f76bcdbaf2e5166b5aa91e54a23106dd3578de79b4db291c3033e33c9bd9a741",
+    "77c86d2361aacd62238de1f17cb4538b4598f868293b85a98d0601b556eb621b",
+    "f529edbf0e6c5fde0852dd8c7e81ec89ad58a4d2d1f4a922199b60c797cfc03e",
+    "38a63ac382c764dde1739220d04dd43ef6e5eb43766cb6598c524016797c12db",
+    "4b3ae751d3533073bd0c1e295f01d60940953b311a56de78a39bb889dae37cdb",
+    "1417649ce6a29e7b718e2b18cdb04adf078643c14c30f1a2bc5d5a49f2fb323b",
+    "012b2bb7fe0fa23ff6dd6df2991a6b06f92dc619838544dce39ed1fd8266207c",
+    "2c9d22e1d4a687ad2811586a2aefc631a58005f91c4df02f58eacbc285152553",
+);
+const DEFAULT_Q: &str = "9d8b685b89a071319dac025b234e935101278b37c72e4b97af9f1d8dfc823b0d";
+const DEFAULT_G: &str = concat!(
+    "44327bed7276d302c20dd1a465f37919094133be08eaf9cac051b903669f52ac",
+    "17690c25dd5e13f4498bbf7e134789318af81c27168ade045416c7f14d234939",
+    "210507ff10091399357b3b4f7bd2439b547f1c6937347723188ed243cc528b96",
+    "07e39df20ccf6da63c09532f0f2962ae03aeefe12ee5b1016cfe5e50f0e3393e",
+    "691143a3e9910c71dbaef5967b553bb41ca4ae2fd8d817a2ef3540e34b228406",
+    "10d3e2dee8a410b292580219d8bdb67408561b6fe32455bb8ae7def65a1501cc",
+    "ce09e554bcb7f9bb67f9eb7b25da594958211095fe2f8af46e97d5dd545fdebe",
+    "fc213e5a919479c6cc47dba088d237ee7dda93ce7be2a30dc7560b1ee54c88e4",
+);
+
+impl DsaParameters {
+    /// Generate p and q from a random seed using method A.1.1.2 then g using method A.2.3 with index 1
+    pub fn generate<R: Rng>(
+        l: u64,
+        n: u64,
+        hash: HmacVariant,
+        rng: &mut R,
+    ) -> Result<(Self, DsaSeed), GeneralError> {
+        if !DSA_SIZES.contains(&(l, n)) {
+            return Err(GeneralError::input("not an allowed pair of sizes"));
+        }
+        if (hash.output_size() as u64) * 8 < n {
+            return Err(GeneralError::input("hash output is shorter than q"));
+        }
+        loop {
+            let mut seed = vec![0; n as usize / 8];
+            rng.fill(&mut seed[..]);
+            if let Some((p, q, counter)) = Self::primes_from_seed(l, n, hash, &seed, rng) {
+                let g = Self::canonical_generator(&p, &q, hash, &seed, 1)
+                    .ok_or(GeneralError::general("no generator found"))?;
+                return Ok((Self { p, q, g }, DsaSeed { seed, counter }));
+            }
+        }
+    }
+
+    // Method A.1.1.2, gives None if the seed does not lead to primes
+    fn primes_from_seed<R: Rng>(
+        l: u64,
+        n: u64,
+        hash: HmacVariant,
+        seed: &[u8],
+        rng: &mut R,
+    ) -> Option<(BigUint, BigUint, usize)> {
+        let outlen = hash.output_size() as u64 * 8;
+        let blocks = l.div_ceil(outlen) - 1;
+        let b = l - 1 - blocks * outlen;
+        let seedlen = seed.len() as u64 * 8;
+        let seed_modulus = BigUint::one() << seedlen;
+
+        let u = BigUint::from_bytes_be(&hash.hash(seed)) % (BigUint::one() << (n - 1));
+        let q = (BigUint::one() << (n - 1)) + &u + 1_u32 - (&u % 2_u32);
+        if !is_probable_prime(&q, 64, rng) {
+            return None;
+        }
+
+        let seed_int = BigUint::from_bytes_be(seed);
+        let mut offset = 1_u64;
+        for counter in 0..(4 * l as usize) {
+            let mut w = BigUint::zero();
+            for j in 0..=blocks {
+                let input = (&seed_int + offset + j) % &seed_modulus;
+                let mut v = BigUint::from_bytes_be(&hash.hash(&int2octets(&input, seed.len())));
+                if j == blocks {
+                    v %= BigUint::one() << b;
+                }
+                w += v << (j * outlen);
+            }
+            let x = w + (BigUint::one() << (l - 1));
+            let c = &x % (&q * 2_u32);
+            let p = x - (c - 1_u32);
+            if p.bits() == l && is_probable_prime(&p, 64, rng) {
+                return Some((p, q, counter));
+            }
+            offset += blocks + 1;
+        }
+        None
+    }
+
+    /// Method A.2.3, a generator derived from the seed so that it is also verifiably random
+    pub fn canonical_generator(
+        p: &BigUint,
+        q: &BigUint,
+        hash: HmacVariant,
+        seed: &[u8],
+        index: u8,
+    ) -> Option<BigUint> {
+        let e = (p - 1_u32) / q;
+        for count in 1..=u16::MAX {
+            let mut u = seed.to_vec();
+            u.extend_from_slice(b"ggen");
+            u.push(index);
+            u.extend_from_slice(&count.to_be_bytes());
+            let w = BigUint::from_bytes_be(&hash.hash(&u));
+            let g = w.modpow(&e, p);
+            if g > BigUint::one() {
+                return Some(g);
+            }
+        }
+        None
+    }
+
+    /// Repeat the generation from the seed and check that it gives the same p, q and g. The counter must also match,
+    /// otherwise the primes could have been chosen by searching.
+    pub fn validate<R: Rng>(&self, hash: HmacVariant, seed: &DsaSeed, rng: &mut R) -> bool {
+        let (l, n) = (self.p.bits(), self.q.bits());
+        if !DSA_SIZES.contains(&(l, n)) || (seed.seed.len() as u64) * 8 < n {
+            return false;
+        }
+        match Self::primes_from_seed(l, n, hash, &seed.seed, rng) {
+            Some((p, q, counter)) => {
+                p == self.p
+                    && q == self.q
+                    && counter == seed.counter
+                    && Self::canonical_generator(&p, &q, hash, &seed.seed, 1).as_ref()
+                        == Some(&self.g)
+            }
+            None => false,
+        }
+    }
+}
+
+impl Default for DsaParameters {
+    fn default() -> Self {
+        let big = |s: &str| BigUint::parse_bytes(s.as_bytes(), 16).unwrap();
+        Self {
+            p: big(DEFAULT_P),
+            q: big(DEFAULT_Q),
+            g: big(DEFAULT_G),
+        }
+    }
+}
+
+pub struct Dsa {
+    pub input_format: ByteFormat,
+    pub output_format: ByteFormat,
+    pub parameters: DsaParameters,
+    pub private_key: BigUint,
+    pub public_key: BigUint,
+    pub hash: HmacVariant,
+}
+
+impl Default for Dsa {
+    fn default() -> Self {
+        Self {
+            input_format: ByteFormat::Utf8,
+            output_format: ByteFormat::Hex,
+            parameters: DsaParameters::default(),
+            private_key: BigUint::zero(),
+            public_key: BigUint::zero(),
+            hash: HmacVariant::Sha256,
+        }
+    }
+}
+
+impl Dsa {
+    pub fn with_parameters(mut self, parameters: DsaParameters) -> Self {
+        self.set_parameters(parameters);
+        self
+    }
+
+    /// Changing the parameters discards the keys
+    pub fn set_parameters(&mut self, parameters: DsaParameters) {
+        self.parameters = parameters;
+        self.private_key = BigUint::zero();
+        self.public_key = BigUint::zero();
+    }
+
+    /// Number of bytes used for each half of the signature
+    pub fn scalar_len(&self) -> usize {
+        self.parameters.q.bits().div_ceil(8) as usize
+    }
+
+    pub fn set_private_key(&mut self, x: &BigUint) -> Result<(), GeneralError> {
+        if x.is_zero() || *x >= self.parameters.q {
+            return Err(GeneralError::key("private key must be between 1 and q-1"));
+        }
+        self.private_key = x.clone();
+        self.public_key = self.parameters.g.modpow(x, &self.parameters.p);
+        Ok(())
+    }
+
+    pub fn generate_key<R: Rng>(&mut self, rng: &mut R) {
+        let x = random_biguint_below(&(&self.parameters.q - 1_u32), rng) + 1_u32;
+        self.set_private_key(&x)
+            .expect("generated key is always in range");
+    }
+
+    /// Set only the public key, for verification. The key must lie in the subgroup of order q.
+    pub fn set_public_key(&mut self, y: &BigUint) -> Result<(), GeneralError> {
+        let DsaParameters { p, q, .. } = &self.parameters;
+        if *y <= BigUint::one() || y >= p || !y.modpow(q, p).is_one() {
+            return Err(GeneralError::key("not a valid public key"));
+        }
+        self.private_key = BigUint::zero();
+        self.public_key = y.clone();
+        Ok(())
+    }
+
+    /// The hash of the message as an integer with no more bits than q
+    pub fn hash_to_int(&self, msg: &[u8]) -> BigUint {
+        bits2int(&self.hash.hash(msg), self.parameters.q.bits() as usize)
+    }
+
+    /// Sign with a chosen nonce k. Any nonce that is reused or predictable reveals the private key.
+    pub fn sign_with_nonce(
+        &self,
+        msg: &[u8],
+        k: &BigUint,
+    ) -> Result<(BigUint, BigUint), GeneralError> {
+        let DsaParameters { p, q, g } = &self.parameters;
+        if self.private_key.is_zero() {
+            return Err(GeneralError::state("private key has not been set"));
+        }
+        if k.is_zero() || k >= q {
+            return Err(GeneralError::input("nonce must be between 1 and q-1"));
+        }
+        let r = g.modpow(k, p) % q;
+        if r.is_zero() {
+            return Err(GeneralError::input("nonce gives r = 0"));
+        }
+        let k_inv = mul_inv(k, q)
+            .and_then(|x| x.to_biguint())
+            .expect("q is prime");
+        let s = (k_inv * (self.hash_to_int(msg) + &r * &self.private_key)) % q;
+        if s.is_zero() {
+            return Err(GeneralError::input("nonce gives s = 0"));
+        }
+        Ok((r, s))
+    }
+
+    /// Sign with the deterministic nonce of RFC 6979
+    pub fn sign(&self, msg: &[u8]) -> Result<(BigUint, BigUint), GeneralError> {
+        if self.private_key.is_zero() {
+            return Err(GeneralError::state("private key has not been set"));
+        }
+        let mut nonces = Rfc6979::new(
+            self.hash,
+            &self.parameters.q,
+            &self.private_key,
+            &self.hash.hash(msg),
+        );
+        loop {
+            if let Ok(signature) = self.sign_with_nonce(msg, &nonces.next_nonce()) {
+                return Ok(signature);
+            }
+        }
+    }
+
+    pub fn verify(&self, msg: &[u8], r: &BigUint, s: &BigUint) -> bool {
+        let DsaParameters { p, q, g } = &self.parameters;
+        if r.is_zero() || s.is_zero() || r >= q || s >= q || self.public_key.is_zero() {
+            return false;
+        }
+        let w = match mul_inv(s, q).and_then(|x| x.to_biguint()) {
+            Some(w) => w,
+            None => return false,
+        };
+        let u1 = (self.hash_to_int(msg) * &w) % q;
+        let u2 = (r * w) % q;
+        let v = (g.modpow(&u1, p) * self.public_key.modpow(&u2, p)) % p % q;
+        v == *r
+    }
+
+    /// Fixed width encoding of r followed by s
+    pub fn signature_to_bytes(&self, r: &BigUint, s: &BigUint) -> Vec<u8> {
+        let mut out = int2octets(r, self.scalar_len());
+        out.extend(int2octets(s, self.scalar_len()));
+        out
+    }
+
+    pub fn signature_from_bytes(&self, bytes: &[u8]) -> Result<(BigUint, BigUint), GeneralError> {
+        let len = self.scalar_len();
+        if bytes.len() != 2 * len {
+            return Err(GeneralError::input(format!(
+                "signature must be {} bytes",
+                2 * len
+            )));
+        }
+        Ok((
+            BigUint::from_bytes_be(&bytes[..len]),
+            BigUint::from_bytes_be(&bytes[len..]),
+        ))
+    }
+
+    pub fn verify_bytes(&self, msg: &[u8], signature: &[u8]) -> bool {
+        match self.signature_from_bytes(signature) {
+            Ok((r, s)) => self.verify(msg, &r, &s),
+            Err(_) => false,
+        }
+    }
+}
+
+impl Cipher for Dsa {
+    fn encrypt(&self, text: &str) -> Result<String, GeneralError> {
+        let bytes = self
+            .input_format
+            .text_to_bytes(text)
+            .map_err(|_| GeneralError::input("byte format error"))?;
+        let (r, s) = self.sign(&bytes)?;
+        Ok(self
+            .output_format
+            .byte_slice_to_text(self.signature_to_bytes(&r, &s)))
+    }
+
+    fn decrypt(&self, _text: &str) -> Result<String, GeneralError> {
+        Err(GeneralError::general(
+            "DSA creates signatures, it does not decrypt",
+        ))
+    }
+}
+
+#[cfg(test)]
+mod dsa_tests {
+
+    use super::*;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    fn big(hex: &str) -> BigUint {
+        BigUint::parse_bytes(hex.as_bytes(), 16).unwrap()
+    }
+
+    fn default_seed() -> DsaSeed {
+        DsaSeed {
+            seed: ByteFormat::Hex
+                .text_to_bytes("5e4b1c9a2f7d3e8061b5c4a39d2e7f108a6b3c5d4e9f0a1b2c3d4e5f6071830f")
+                .unwrap(),
+            counter: 245,
+        }
+    }
+
+    // Signatures with deterministic nonces checked with Python's cryptography library
+    const VECTORS: [(HmacVariant, &str, &str, &str); 4] = [
+        (
+            HmacVariant::Sha256,
+            "sample",
+            "5b7f04af41164c44daee9cd60c6e8039eba73dce72cee8a1addd6c9f22de088c",
+            "802a7e61b7babf017ecb24d442ee700a7ab224346396dc08d50d3aebe7a08ddc",
+        ),
+        (
+            HmacVariant::Sha256,
+            "test",
+            "59abeddeea38bbbbae1484f276a2f7ef46de85e5b48af2386325bb659bc63f79",
+            "8571ffd2fd22e4612e79be40901c9929e1d7dd6fe5fcf157b982920c48a267b5",
+        ),
+        (
+            HmacVariant::Sha384,
+            "sample",
+            "5f9f163745b8a8d7ea4c03440ab7529839d81f59f8cb899f1d507cfd926d783d",
+            "6b1f91f0b5100e7cb70c60042a6aa4720b2b32c8869ec6e61fbe84234931be32",
+        ),
+        (
+            HmacVariant::Sha384,
+            "test",
+            "3cb1f0e18641bae38d247b562fb1699f2321303012e6b5f6bcddc66b5b2b83b5",
+            "24b8e4e92d7e7b856aed789fd0295370b56a0822b4222a6fc5ea97ed2f506504",
+        ),
+    ];
+
+    #[test]
+    fn default_parameters() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let parameters = DsaParameters::default();
+        assert!(parameters.validate(HmacVariant::Sha256, &default_seed(), &mut rng));
+
+        let mut wrong_counter = default_seed();
+        wrong_counter.counter = 244;
+        assert!(!parameters.validate(HmacVariant::Sha256, &wrong_counter, &mut rng));
+        let mut wrong_seed = default_seed();
+        wrong_seed.seed[0] ^= 1;
+        assert!(!parameters.validate(HmacVariant::Sha256, &wrong_seed, &mut rng));
+    }
+
+    #[test]
+    fn generate_parameters() {
+        let mut rng = StdRng::seed_from_u64(2);
+        let (parameters, seed) =
+            DsaParameters::generate(1024, 160, HmacVariant::Sha256, &mut rng).unwrap();
+        assert_eq!(1024, parameters.p.bits());
+        assert_eq!(160, parameters.q.bits());
+        assert!(((&parameters.p - 1_u32) % &parameters.q).is_zero());
+        assert!(parameters.g.modpow(&parameters.q, &parameters.p).is_one());
+        assert!(parameters.validate(HmacVariant::Sha256, &seed, &mut rng));
+
+        assert!(DsaParameters::generate(1024, 256, HmacVariant::Sha256, &mut rng).is_err());
+        assert!(DsaParameters::generate(2048, 256, HmacVariant::Sha224, &mut rng).is_err());
+    }
+
+    #[test]
+    fn deterministic_signatures() {
+        let mut cipher = Dsa::default();
+        cipher
+            .set_private_key(&big(
+                "3a1f5c7e9b2d4f6081a3c5e7092b4d6f8a1c3e5f7092b4d6e8f0a2c4e6081a3c",
+            ))
+            .unwrap();
+        for (hash, msg, r, s) in VECTORS {
+            cipher.hash = hash;
+            let (sig_r, sig_s) = cipher.sign(msg.as_bytes()).unwrap();
+            assert_eq!(big(r), sig_r, "{:?} {}", hash, msg);
+            assert_eq!(big(s), sig_s, "{:?} {}", hash, msg);
+            assert!(cipher.verify(msg.as_bytes(), &sig_r, &sig_s));
+            assert!(!cipher.verify(b"other", &sig_r, &sig_s));
+        }
+    }
+
+    #[test]
+    fn verify_only() {
+        let mut signer = Dsa::default();
+        signer.generate_key(&mut rand::thread_rng());
+        let signature = signer.encrypt("attack at dawn").unwrap();
+        let signature = ByteFormat::Hex.text_to_bytes(&signature).unwrap();
+
+        let mut verifier = Dsa::default();
+        verifier.set_public_key(&signer.public_key).unwrap();
+        assert!(verifier.verify_bytes(b"attack at dawn", &signature));
+        assert!(!verifier.verify_bytes(b"attack at dusk", &signature));
+        assert!(verifier.sign(b"attack at dawn").is_err());
+        // p-1 has order 2 so it is not in the subgroup
+        assert!(verifier
+            .set_public_key(&(&verifier.parameters.p - 1_u32))
+            .is_err());
+    }
+}
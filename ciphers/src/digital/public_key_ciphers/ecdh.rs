@@ -0,0 +1,150 @@
+use crate::Cipher;
+use num::{BigUint, Zero};
+use rand::Rng;
+use utils::{
+    elliptic_curves::{biguint_to_u256, u256_to_biguint, EcDomain, EcPoint, NamedCurve},
+    errors::GeneralError,
+    primality::random_biguint_below,
+};
+
+// https://www.secg.org/sec1-v2.pdf section 3.3.1
+pub struct Ecdh {
+    pub domain: EcDomain,
+    pub private_key: BigUint,
+}
+
+impl Default for Ecdh {
+    fn default() -> Self {
+        Self {
+            domain: NamedCurve::P256.domain(),
+            private_key: BigUint::from(1_u32),
+        }
+    }
+}
+
+impl Ecdh {
+    pub fn with_curve(mut self, curve: NamedCurve) -> Self {
+        self.domain = curve.domain();
+        self
+    }
+
+    pub fn with_private_key(mut self, d: &BigUint) -> Result<Self, GeneralError> {
+        if d.is_zero() || *d >= u256_to_biguint(&self.domain.order) {
+            return Err(GeneralError::key("private key must be between 1 and n-1"));
+        }
+        self.private_key = d.clone();
+        Ok(self)
+    }
+
+    pub fn generate_key<R: Rng>(&mut self, rng: &mut R) {
+        let n = u256_to_biguint(&self.domain.order);
+        self.private_key = random_biguint_below(&(n - 1_u32), rng) + 1_u32;
+    }
+
+    pub fn public_key(&self) -> EcPoint {
+        self.domain
+            .mul_generator(&biguint_to_u256(&self.private_key))
+    }
+
+    /// The x coordinate of dQ for the other party's public key Q. Points that are not valid public keys are refused,
+    /// otherwise a malicious peer could learn the private key by sending points of small order.
+    pub fn shared_secret(&self, peer: &EcPoint) -> Result<Vec<u8>, GeneralError> {
+        if !self.domain.is_valid_public_key(peer) {
+            return Err(GeneralError::key("peer point is not a valid public key"));
+        }
+        let shared = self
+            .domain
+            .curve
+            .scalar_mul(peer, &biguint_to_u256(&self.private_key));
+        match shared.x {
+            Some(x) => Ok(x.to_be_bytes()[32 - self.domain.curve.byte_len()..].to_vec()),
+            None => Err(GeneralError::general("shared point is infinity")),
+        }
+    }
+}
+
+impl Cipher for Ecdh {
+    fn encrypt(&self, _text: &str) -> Result<String, GeneralError> {
+        Err(GeneralError::general(
+            "ECDH key agreement does not encrypt a message",
+        ))
+    }
+
+    fn decrypt(&self, text: &str) -> Result<String, GeneralError> {
+        self.encrypt(text)
+    }
+}
+
+#[cfg(test)]
+mod ecdh_tests {
+
+    use super::*;
+    use utils::byte_formatting::ByteFormat;
+
+    fn big(hex: &str) -> BigUint {
+        BigUint::parse_bytes(hex.as_bytes(), 16).unwrap()
+    }
+
+    #[test]
+    fn shared_secrets() {
+        // Shared secrets computed with OpenSSL
+        for (curve, a, b, secret) in [
+            (
+                NamedCurve::P224,
+                "3f0c488e987c80be0fee521f8d90be6034ec69ae11ca72aa777481e8",
+                "1234567",
+                "68d6019253397be6418c1f7a5b4f0f7265793857fb0894869a3ce4c4",
+            ),
+            (
+                NamedCurve::P256,
+                "c9afa9d845ba75166b5c215767b1d6934e50c3db36e89b127b8a622b120f6721",
+                "2468ace",
+                "f79a5fa6555e3f3bd9d6137f38e31b68e8d182de79f062388b82a1f776562d61",
+            ),
+            (
+                NamedCurve::Secp256k1,
+                "1234567890abcdef1234567890abcdef1234567890abcdef1234567890abcdef",
+                "13579bdf",
+                "621fc595c24878df123a01b5e8bc696aa144644395ea3eca7f4aaf9289fdb3a0",
+            ),
+            (
+                NamedCurve::BrainpoolP256r1,
+                "123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef",
+                "fedcba987",
+                "204bcfc498bf2f20fdffa76026503d653813bb7e92078db9db766cbba828c6bf",
+            ),
+        ] {
+            let alice = Ecdh::default()
+                .with_curve(curve)
+                .with_private_key(&big(a))
+                .unwrap();
+            let bob = Ecdh::default()
+                .with_curve(curve)
+                .with_private_key(&big(b))
+                .unwrap();
+            let s1 = alice.shared_secret(&bob.public_key()).unwrap();
+            let s2 = bob.shared_secret(&alice.public_key()).unwrap();
+            assert_eq!(secret, ByteFormat::Hex.byte_slice_to_text(&s1));
+            assert_eq!(s1, s2);
+        }
+    }
+
+    #[test]
+    fn invalid_points() {
+        let alice = Ecdh::default()
+            .with_curve(NamedCurve::P256)
+            .with_private_key(&BigUint::from(12345_u32))
+            .unwrap();
+        assert!(alice.shared_secret(&EcPoint::point_at_inf()).is_err());
+        assert!(alice.shared_secret(&EcPoint::from_u64(1, 2)).is_err());
+
+        // On this curve (30, 0) has order 2 and lies outside the subgroup generated by the base point
+        let alice = Ecdh::default()
+            .with_curve(NamedCurve::Textbook97)
+            .with_private_key(&BigUint::from(3_u32))
+            .unwrap();
+        let small = EcPoint::from_u64(30, 0);
+        assert!(alice.domain.curve.on_curve(&small));
+        assert!(alice.shared_secret(&small).is_err());
+    }
+}
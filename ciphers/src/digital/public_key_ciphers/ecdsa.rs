@@ -0,0 +1,363 @@
+use super::rfc6979::{bits2int, int2octets, Rfc6979};
+use crate::Cipher;
+use hashers::hmac::HmacVariant;
+use num::{BigUint, Zero};
+use rand::Rng;
+use utils::{
+    byte_formatting::ByteFormat,
+    elliptic_curves::{biguint_to_u256, u256_to_biguint, EcDomain, EcPoint, NamedCurve},
+    errors::GeneralError,
+    math_functions::mul_inv,
+    primality::random_biguint_below,
+};
+
+// https://nvlpubs.nist.gov/nistpubs/FIPS/NIST.FIPS.186-5.pdf
+pub struct Ecdsa {
+    pub input_format: ByteFormat,
+    pub output_format: ByteFormat,
+    pub domain: EcDomain,
+    pub private_key: BigUint,
+    pub public_key: EcPoint,
+    pub hash: HmacVariant,
+}
+
+impl Default for Ecdsa {
+    fn default() -> Self {
+        Self {
+            input_format: ByteFormat::Utf8,
+            output_format: ByteFormat::Hex,
+            domain: NamedCurve::P256.domain(),
+            private_key: BigUint::zero(),
+            public_key: EcPoint::point_at_inf(),
+            hash: HmacVariant::Sha256,
+        }
+    }
+}
+
+impl Ecdsa {
+    pub fn with_curve(mut self, curve: NamedCurve) -> Self {
+        self.set_curve(curve);
+        self
+    }
+
+    /// Changing the curve discards the keys
+    pub fn set_curve(&mut self, curve: NamedCurve) {
+        self.domain = curve.domain();
+        self.private_key = BigUint::zero();
+        self.public_key = EcPoint::point_at_inf();
+    }
+
+    pub fn order(&self) -> BigUint {
+        u256_to_biguint(&self.domain.order)
+    }
+
+    /// Number of bytes used for each half of the signature
+    pub fn scalar_len(&self) -> usize {
+        self.domain.order_bits().div_ceil(8)
+    }
+
+    pub fn set_private_key(&mut self, d: &BigUint) -> Result<(), GeneralError> {
+        if d.is_zero() || *d >= self.order() {
+            return Err(GeneralError::key("private key must be between 1 and n-1"));
+        }
+        self.private_key = d.clone();
+        self.public_key = self.domain.mul_generator(&biguint_to_u256(d));
+        Ok(())
+    }
+
+    pub fn generate_key<R: Rng>(&mut self, rng: &mut R) {
+        let d = random_biguint_below(&(self.order() - 1_u32), rng) + 1_u32;
+        self.set_private_key(&d)
+            .expect("generated key is always in range");
+    }
+
+    /// The hash of the message as an integer with no more bits than the order
+    pub fn hash_to_int(&self, msg: &[u8]) -> BigUint {
+        bits2int(&self.hash.hash(msg), self.domain.order_bits())
+    }
+
+    /// Sign with a chosen nonce k. Any nonce that is reused or predictable reveals the private key.
+    pub fn sign_with_nonce(
+        &self,
+        msg: &[u8],
+        k: &BigUint,
+    ) -> Result<(BigUint, BigUint), GeneralError> {
+        let n = self.order();
+        if self.private_key.is_zero() {
+            return Err(GeneralError::state("private key has not been set"));
+        }
+        if k.is_zero() || *k >= n {
+            return Err(GeneralError::input("nonce must be between 1 and n-1"));
+        }
+        let point = self.domain.mul_generator(&biguint_to_u256(k));
+        let r = u256_to_biguint(&point.x.expect("kG cannot be infinity for 0 < k < n")) % &n;
+        if r.is_zero() {
+            return Err(GeneralError::input("nonce gives r = 0"));
+        }
+        let k_inv = mul_inv(k, &n)
+            .and_then(|x| x.to_biguint())
+            .expect("n is prime");
+        let s = (k_inv * (self.hash_to_int(msg) + &r * &self.private_key)) % &n;
+        if s.is_zero() {
+            return Err(GeneralError::input("nonce gives s = 0"));
+        }
+        Ok((r, s))
+    }
+
+    /// Sign with the deterministic nonce of RFC 6979
+    pub fn sign(&self, msg: &[u8]) -> Result<(BigUint, BigUint), GeneralError> {
+        if self.private_key.is_zero() {
+            return Err(GeneralError::state("private key has not been set"));
+        }
+        let mut nonces = Rfc6979::new(
+            self.hash,
+            &self.order(),
+            &self.private_key,
+            &self.hash.hash(msg),
+        );
+        loop {
+            if let Ok(signature) = self.sign_with_nonce(msg, &nonces.next_nonce()) {
+                return Ok(signature);
+            }
+        }
+    }
+
+    pub fn verify(&self, msg: &[u8], r: &BigUint, s: &BigUint) -> bool {
+        let n = self.order();
+        if r.is_zero() || s.is_zero() || *r >= n || *s >= n {
+            return false;
+        }
+        if !self.domain.is_valid_public_key(&self.public_key) {
+            return false;
+        }
+        let w = match mul_inv(s, &n).and_then(|x| x.to_biguint()) {
+            Some(w) => w,
+            None => return false,
+        };
+        let u1 = (self.hash_to_int(msg) * &w) % &n;
+        let u2 = (r * w) % &n;
+        let curve = &self.domain.curve;
+        let point = curve.add(
+            &self.domain.mul_generator(&biguint_to_u256(&u1)),
+            &curve.scalar_mul(&self.public_key, &biguint_to_u256(&u2)),
+        );
+        match point.x {
+            Some(x) => u256_to_biguint(&x) % &n == *r,
+            None => false,
+        }
+    }
+
+    /// Fixed width encoding of r followed by s
+    pub fn signature_to_bytes(&self, r: &BigUint, s: &BigUint) -> Vec<u8> {
+        let mut out = int2octets(r, self.scalar_len());
+        out.extend(int2octets(s, self.scalar_len()));
+        out
+    }
+
+    pub fn signature_from_bytes(&self, bytes: &[u8]) -> Result<(BigUint, BigUint), GeneralError> {
+        let len = self.scalar_len();
+        if bytes.len() != 2 * len {
+            return Err(GeneralError::input(format!(
+                "signature must be {} bytes",
+                2 * len
+            )));
+        }
+        Ok((
+            BigUint::from_bytes_be(&bytes[..len]),
+            BigUint::from_bytes_be(&bytes[len..]),
+        ))
+    }
+
+    pub fn verify_bytes(&self, msg: &[u8], signature: &[u8]) -> bool {
+        match self.signature_from_bytes(signature) {
+            Ok((r, s)) => self.verify(msg, &r, &s),
+            Err(_) => false,
+        }
+    }
+
+    pub fn public_key_bytes(&self, compressed: bool) -> Vec<u8> {
+        self.domain.curve.encode_point(&self.public_key, compressed)
+    }
+
+    /// Set only the public key, for verification
+    pub fn set_public_key_bytes(&mut self, bytes: &[u8]) -> Result<(), GeneralError> {
+        let point = self.domain.curve.decode_point(bytes)?;
+        if !self.domain.is_valid_public_key(&point) {
+            return Err(GeneralError::key("point is not a valid public key"));
+        }
+        self.private_key = BigUint::zero();
+        self.public_key = point;
+        Ok(())
+    }
+}
+
+impl Cipher for Ecdsa {
+    fn encrypt(&self, text: &str) -> Result<String, GeneralError> {
+        let bytes = self
+            .input_format
+            .text_to_bytes(text)
+            .map_err(|_| GeneralError::input("byte format error"))?;
+        let (r, s) = self.sign(&bytes)?;
+        Ok(self
+            .output_format
+            .byte_slice_to_text(self.signature_to_bytes(&r, &s)))
+    }
+
+    fn decrypt(&self, _text: &str) -> Result<String, GeneralError> {
+        Err(GeneralError::general(
+            "ECDSA creates signatures, it does not decrypt",
+        ))
+    }
+}
+
+#[cfg(test)]
+mod ecdsa_tests {
+
+    use super::*;
+
+    fn big(hex: &str) -> BigUint {
+        BigUint::parse_bytes(hex.as_bytes(), 16).unwrap()
+    }
+
+    // Signatures produced by OpenSSL with deterministic nonces, the P-224 and P-256 SHA-256 "sample" cases are also
+    // given in RFC 6979 appendix A.2
+    const VECTORS: [(NamedCurve, &str, HmacVariant, &str, &str, &str); 8] = [
+        (
+            NamedCurve::P224,
+            "3f0c488e987c80be0fee521f8d90be6034ec69ae11ca72aa777481e8",
+            HmacVariant::Sha256,
+            "sample",
+            "56e3ff909429b7c6452c74be1f7baeeb096cf56f2ce6c29c4f54e8e4",
+            "b58b49cf943543fc959ec8bccee0031faf97f4939a34bdd1825c6cf1",
+        ),
+        (
+            NamedCurve::P224,
+            "3f0c488e987c80be0fee521f8d90be6034ec69ae11ca72aa777481e8",
+            HmacVariant::Sha384,
+            "test",
+            "24c8d427a7660301a445ff130751383a5956daca5db9e1ee7a349f14",
+            "2c389123c18738a000ee81c78e87c29c1b7034944ab137949007140b",
+        ),
+        (
+            NamedCurve::P256,
+            "c9afa9d845ba75166b5c215767b1d6934e50c3db36e89b127b8a622b120f6721",
+            HmacVariant::Sha256,
+            "sample",
+            "efd48b2aacb6a8fd1140dd9cd45e81d69d2c877b56aaf991c34d0ea84eaf3716",
+            "f7cb1c942d657c41d436c7a1b6e29f65f3e900dbb9aff4064dc4ab2f843acda8",
+        ),
+        (
+            NamedCurve::P256,
+            "c9afa9d845ba75166b5c215767b1d6934e50c3db36e89b127b8a622b120f6721",
+            HmacVariant::Sha384,
+            "test",
+            "83910e8b48bb0c74244ebdf7f07a1c5413d61472bd941ef3920e623fbccebeb6",
+            "8ddbec54cf8cd5874883841d712142a56a8d0f218f5003cb0296b6b509619f2c",
+        ),
+        (
+            NamedCurve::Secp256k1,
+            "1234567890abcdef1234567890abcdef1234567890abcdef1234567890abcdef",
+            HmacVariant::Sha256,
+            "sample",
+            "a1cab70784171b8d7746f71b10deb1b891051c09906d76a0e096cfbdc3fb82fa",
+            "3b970804815b83648b1aa9bca69fd8179291173fd7ac97b5bbf656b3e95a56c8",
+        ),
+        (
+            NamedCurve::Secp256k1,
+            "1234567890abcdef1234567890abcdef1234567890abcdef1234567890abcdef",
+            HmacVariant::Sha384,
+            "test",
+            "48fcef046b0e2e37a3bab6184aef2d8a1b2c8babc84a5a3ba7767c0ce2b3402c",
+            "14f696459d0f781ab1805de96c8d6a4e1526fa5fab0ba3bd7fc931dba9a6c57a",
+        ),
+        (
+            NamedCurve::BrainpoolP256r1,
+            "123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef",
+            HmacVariant::Sha256,
+            "sample",
+            "981444a1cc4e372deb153bfa7ab397f7cb17648a95ece3dc986eda5f873a0777",
+            "4ed6aa49a6a8627439a0dc3543cc55fbbea49d838e8252c2b6575538075231c7",
+        ),
+        (
+            NamedCurve::BrainpoolP256r1,
+            "123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef",
+            HmacVariant::Sha384,
+            "test",
+            "8c5ac589ca2a187a98f3c52a0c0b11f25f6c3cf024878146e45ec5376121e50",
+            "89fe03c4ec17b331e30a36deb2802c188fcff8ae12627044eed2c0bf9139c4c9",
+        ),
+    ];
+
+    #[test]
+    fn public_keys() {
+        let mut cipher = Ecdsa::default().with_curve(NamedCurve::P256);
+        cipher
+            .set_private_key(&big(
+                "c9afa9d845ba75166b5c215767b1d6934e50c3db36e89b127b8a622b120f6721",
+            ))
+            .unwrap();
+        assert_eq!(
+            EcPoint::from_be_hex(
+                "60fed4ba255a9d31c961eb74c6356d68c049b8923b61fa6ce669622e60f29fb6",
+                "7903fe1008b8bc99a41ae9e95628bc64f2f1b20c2d7e9f5177a3c294d4462299"
+            ),
+            cipher.public_key
+        );
+        assert_eq!(
+            "0360fed4ba255a9d31c961eb74c6356d68c049b8923b61fa6ce669622e60f29fb6",
+            ByteFormat::Hex.byte_slice_to_text(cipher.public_key_bytes(true))
+        );
+
+        let mut cipher = Ecdsa::default().with_curve(NamedCurve::P224);
+        cipher
+            .set_private_key(&big(
+                "3f0c488e987c80be0fee521f8d90be6034ec69ae11ca72aa777481e8",
+            ))
+            .unwrap();
+        assert_eq!(
+            "03e84fb0b8e7000cb657d7973cf6b42ed78b301674276df744af130b3e",
+            ByteFormat::Hex.byte_slice_to_text(cipher.public_key_bytes(true))
+        );
+    }
+
+    #[test]
+    fn deterministic_signatures() {
+        for (curve, d, hash, msg, r, s) in VECTORS {
+            let mut cipher = Ecdsa::default().with_curve(curve);
+            cipher.hash = hash;
+            cipher.set_private_key(&big(d)).unwrap();
+            let (sig_r, sig_s) = cipher.sign(msg.as_bytes()).unwrap();
+            assert_eq!(big(r), sig_r, "{:?} {}", curve, msg);
+            assert_eq!(big(s), sig_s, "{:?} {}", curve, msg);
+            assert!(cipher.verify(msg.as_bytes(), &sig_r, &sig_s));
+        }
+    }
+
+    #[test]
+    fn verify_only() {
+        let mut signer = Ecdsa::default().with_curve(NamedCurve::Secp256k1);
+        signer.generate_key(&mut rand::thread_rng());
+        let signature = signer.encrypt("attack at dawn").unwrap();
+        let signature = ByteFormat::Hex.text_to_bytes(&signature).unwrap();
+
+        let mut verifier = Ecdsa::default().with_curve(NamedCurve::Secp256k1);
+        verifier
+            .set_public_key_bytes(&signer.public_key_bytes(false))
+            .unwrap();
+        assert!(verifier.verify_bytes(b"attack at dawn", &signature));
+        assert!(!verifier.verify_bytes(b"attack at dusk", &signature));
+        assert!(verifier.sign(b"attack at dawn").is_err());
+    }
+
+    #[test]
+    fn textbook_curve() {
+        let mut cipher = Ecdsa::default().with_curve(NamedCurve::Textbook17);
+        cipher.set_private_key(&BigUint::from(7_u32)).unwrap();
+        assert_eq!(EcPoint::from_u64(0, 6), cipher.public_key);
+        for msg in ["a", "b", "c", "d"] {
+            let (r, s) = cipher.sign(msg.as_bytes()).unwrap();
+            assert!(cipher.verify(msg.as_bytes(), &r, &s));
+        }
+        assert!(cipher.set_private_key(&BigUint::from(19_u32)).is_err());
+    }
+}
@@ -0,0 +1,452 @@
+use super::rfc7748::Field25519;
+use crate::Cipher;
+use crypto_bigint::{impl_modulus, modular::ConstMontyForm, NonZero, U256, U512};
+use hashers::{sha::Sha512, traits::StatefulHasher};
+use rand::Rng;
+use utils::{byte_formatting::ByteFormat, errors::GeneralError};
+
+// Edwards-curve Digital Signature Algorithm over edwards25519
+// https://datatracker.ietf.org/doc/html/rfc8032#section-5.1
+
+impl_modulus!(
+    GroupOrder,
+    U256,
+    "1000000000000000000000000000000014def9dea2f79cd65812631a5cf5d3ed",
+    "The order L of the base point of edwards25519"
+);
+
+type Fe = ConstMontyForm<Field25519, 4>;
+type Scalar = ConstMontyForm<GroupOrder, 4>;
+
+const P: U256 =
+    U256::from_be_hex("7fffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffed");
+const L: U256 =
+    U256::from_be_hex("1000000000000000000000000000000014def9dea2f79cd65812631a5cf5d3ed");
+// d = -121665/121666
+const D: Fe = Fe::new(&U256::from_be_hex(
+    "52036cee2b6ffe738cc740797779e89800700a4d4141d8ab75eb4dca135978a3",
+));
+// A square root of -1
+const SQRT_M1: Fe = Fe::new(&U256::from_be_hex(
+    "2b8324804fc1df0b2b4d00993dfbd7a72f431806ad2fe478c4ee1b274a0ea0b0",
+));
+
+const BASE: EdPoint = EdPoint::from_affine(
+    Fe::new(&U256::from_be_hex(
+        "216936d3cd6e53fec0a4e231fdd6dc5c692cc7609525a7b2c9562d608f25d51a",
+    )),
+    Fe::new(&U256::from_be_hex(
+        "6666666666666666666666666666666666666666666666666666666666666658",
+    )),
+);
+
+fn inv(a: &Fe) -> Fe {
+    a.pow(&P.wrapping_sub(&U256::from_u8(2)))
+}
+
+/// A point of the twisted Edwards curve -x^2 + y^2 = 1 + d*x^2*y^2 in extended coordinates (X:Y:Z:T) with x = X/Z,
+/// y = Y/Z and x*y = T/Z. These allow points to be added without any inversions.
+#[derive(Debug, Clone, Copy)]
+struct EdPoint {
+    x: Fe,
+    y: Fe,
+    z: Fe,
+    t: Fe,
+}
+
+impl EdPoint {
+    const IDENTITY: Self = Self::from_affine(Fe::ZERO, Fe::ONE);
+
+    const fn from_affine(x: Fe, y: Fe) -> Self {
+        Self {
+            x,
+            y,
+            z: Fe::ONE,
+            t: x.mul(&y),
+        }
+    }
+
+    // The unified addition formula works for doubling as well
+    fn add(&self, other: &Self) -> Self {
+        let a = (self.y - self.x) * (other.y - other.x);
+        let b = (self.y + self.x) * (other.y + other.x);
+        let c = self.t * other.t * D.double();
+        let d = (self.z * other.z).double();
+        let e = b - a;
+        let f = d - c;
+        let g = d + c;
+        let h = b + a;
+        Self {
+            x: e * f,
+            y: g * h,
+            z: f * g,
+            t: e * h,
+        }
+    }
+
+    fn scalar_mul(&self, s: &U256) -> Self {
+        let mut out = Self::IDENTITY;
+        for i in (0..s.bits()).rev() {
+            out = out.add(&out);
+            if s.bit_vartime(i) {
+                out = out.add(self);
+            }
+        }
+        out
+    }
+
+    fn equals(&self, other: &Self) -> bool {
+        self.x * other.z == other.x * self.z && self.y * other.z == other.y * self.z
+    }
+
+    /// The y-coordinate in little endian with the lowest bit of x placed in the unused top bit
+    fn encode(&self) -> [u8; 32] {
+        let zi = inv(&self.z);
+        let x = (self.x * zi).retrieve();
+        let mut out = (self.y * zi).retrieve().to_le_bytes();
+        out[31] |= (x.bit_vartime(0) as u8) << 7;
+        out
+    }
+
+    /// Section 5.1.3, recover x from y and the sign bit. The encoding is rejected if it is not canonical or no such
+    /// point exists.
+    fn decode(bytes: &[u8; 32]) -> Option<Self> {
+        let mut bytes = *bytes;
+        let sign = bytes[31] >> 7 == 1;
+        bytes[31] &= 0x7f;
+        let y = U256::from_le_slice(&bytes);
+        if y >= P {
+            return None;
+        }
+        let y = Fe::new(&y);
+
+        // x^2 = (y^2 - 1) / (d*y^2 + 1)
+        let yy = y.square();
+        let x2 = (yy - Fe::ONE) * inv(&(D * yy + Fe::ONE));
+
+        // A candidate root is x2^((p+3)/8), which may be off by a factor of sqrt(-1)
+        let mut x = x2.pow(&P.wrapping_add(&U256::from_u8(3)).shr_vartime(3));
+        if x.square() != x2 {
+            x *= SQRT_M1;
+            if x.square() != x2 {
+                return None;
+            }
+        }
+        if x == Fe::ZERO && sign {
+            return None;
+        }
+        if x.retrieve().bit_vartime(0) != sign {
+            x = -x;
+        }
+        Some(Self::from_affine(x, y))
+    }
+}
+
+/// Interpret a SHA-512 output as a little endian integer modulo L
+fn reduce_digest(digest: &[u8]) -> Scalar {
+    let wide_l = NonZero::new(L.resize::<8>()).unwrap();
+    Scalar::new(&U512::from_le_slice(digest).rem(&wide_l).resize())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Ed25519Variant {
+    /// Ed25519, the message is signed directly
+    Pure,
+    /// Ed25519ctx, signatures are bound to a context string
+    Ctx,
+    /// Ed25519ph, the SHA-512 hash of the message is signed so a large message only has to be passed over once
+    Ph,
+}
+
+pub struct Ed25519 {
+    pub input_format: ByteFormat,
+    pub output_format: ByteFormat,
+    pub variant: Ed25519Variant,
+    pub context: Vec<u8>,
+    pub private_key: [u8; 32],
+    pub public_key: [u8; 32],
+}
+
+impl Default for Ed25519 {
+    fn default() -> Self {
+        let mut out = Self {
+            input_format: ByteFormat::Utf8,
+            output_format: ByteFormat::Hex,
+            variant: Ed25519Variant::Pure,
+            context: Vec::new(),
+            private_key: [0; 32],
+            public_key: [0; 32],
+        };
+        out.set_private_key(&[0; 32]);
+        out
+    }
+}
+
+impl Ed25519 {
+    pub fn with_private_key(mut self, private_key: &[u8; 32]) -> Self {
+        self.set_private_key(private_key);
+        self
+    }
+
+    pub fn with_variant(mut self, variant: Ed25519Variant) -> Self {
+        self.variant = variant;
+        self
+    }
+
+    pub fn with_context(mut self, context: &[u8]) -> Self {
+        self.context = context.to_vec();
+        self
+    }
+
+    /// Any 32 bytes are a valid private key
+    pub fn set_private_key(&mut self, private_key: &[u8; 32]) {
+        self.private_key = *private_key;
+        let (s, _) = self.expand_private_key();
+        self.public_key = BASE.scalar_mul(&s).encode();
+    }
+
+    pub fn generate_key<R: Rng>(&mut self, rng: &mut R) {
+        let mut key = [0; 32];
+        rng.fill(&mut key);
+        self.set_private_key(&key);
+    }
+
+    /// The first half of the hash of the private key becomes the secret scalar, clamped to a multiple of the cofactor
+    /// 8 with the top bit set. The second half is used to derive nonces.
+    fn expand_private_key(&self) -> (U256, Vec<u8>) {
+        let h = Sha512::init().hash(&self.private_key);
+        let mut s = [0; 32];
+        s.copy_from_slice(&h[..32]);
+        s[0] &= 248;
+        s[31] &= 127;
+        s[31] |= 64;
+        (U256::from_le_slice(&s), h[32..].to_vec())
+    }
+
+    /// The dom2 prefix that separates the variants, plain Ed25519 has none
+    fn dom2(&self) -> Result<Vec<u8>, GeneralError> {
+        if self.context.len() > 255 {
+            return Err(GeneralError::key("context cannot be longer than 255 bytes"));
+        }
+        let flag = match self.variant {
+            Ed25519Variant::Pure => {
+                if !self.context.is_empty() {
+                    return Err(GeneralError::key("plain Ed25519 does not take a context"));
+                }
+                return Ok(Vec::new());
+            }
+            Ed25519Variant::Ctx => 0,
+            Ed25519Variant::Ph => 1,
+        };
+        let mut out = b"SigEd25519 no Ed25519 collisions".to_vec();
+        out.push(flag);
+        out.push(self.context.len() as u8);
+        out.extend_from_slice(&self.context);
+        Ok(out)
+    }
+
+    fn prehash(&self, message: &[u8]) -> Vec<u8> {
+        match self.variant {
+            Ed25519Variant::Ph => Sha512::init().hash(message),
+            _ => message.to_vec(),
+        }
+    }
+
+    fn hash_to_scalar(parts: &[&[u8]]) -> Scalar {
+        let mut h = Sha512::init();
+        for p in parts {
+            h.update(p);
+        }
+        reduce_digest(&h.finalize())
+    }
+
+    /// Signatures are deterministic, the nonce is a hash of part of the private key and the message
+    pub fn sign(&self, message: &[u8]) -> Result<[u8; 64], GeneralError> {
+        let dom = self.dom2()?;
+        let m = self.prehash(message);
+        let (s, prefix) = self.expand_private_key();
+
+        let r = Self::hash_to_scalar(&[&dom, &prefix, &m]);
+        let big_r = BASE.scalar_mul(&r.retrieve()).encode();
+        let k = Self::hash_to_scalar(&[&dom, &big_r, &self.public_key, &m]);
+        let s = (r + k * Scalar::new(&s)).retrieve();
+
+        let mut out = [0; 64];
+        out[..32].copy_from_slice(&big_r);
+        out[32..].copy_from_slice(&s.to_le_bytes());
+        Ok(out)
+    }
+
+    /// Check that [8][S]B = [8]R + [8][k]A
+    pub fn verify(&self, message: &[u8], signature: &[u8]) -> bool {
+        if signature.len() != 64 {
+            return false;
+        }
+        let Ok(dom) = self.dom2() else {
+            return false;
+        };
+        let (Some(a), Some(r)) = (
+            EdPoint::decode(&self.public_key),
+            EdPoint::decode(signature[..32].try_into().unwrap()),
+        ) else {
+            return false;
+        };
+        let s = U256::from_le_slice(&signature[32..]);
+        if s >= L {
+            return false;
+        }
+        let m = self.prehash(message);
+        let k = Self::hash_to_scalar(&[&dom, &signature[..32], &self.public_key, &m]);
+
+        let eight = U256::from_u8(8);
+        let lhs = BASE.scalar_mul(&s).scalar_mul(&eight);
+        let rhs = r.add(&a.scalar_mul(&k.retrieve())).scalar_mul(&eight);
+        lhs.equals(&rhs)
+    }
+}
+
+impl Cipher for Ed25519 {
+    fn encrypt(&self, text: &str) -> Result<String, GeneralError> {
+        let bytes = self
+            .input_format
+            .text_to_bytes(text)
+            .map_err(|_| GeneralError::input("byte format error"))?;
+        Ok(self.output_format.byte_slice_to_text(self.sign(&bytes)?))
+    }
+
+    fn decrypt(&self, _text: &str) -> Result<String, GeneralError> {
+        Err(GeneralError::general(
+            "Ed25519 creates signatures, it does not decrypt",
+        ))
+    }
+}
+
+#[cfg(test)]
+mod ed25519_tests {
+
+    use super::*;
+    use hex_literal::hex;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    // RFC 8032 section 7.1
+    #[test]
+    fn pure_vectors() {
+        for (private, public, message, signature) in [
+            (
+                hex!("9d61b19deffd5a60ba844af492ec2cc44449c5697b326919703bac031cae7f60"),
+                hex!("d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a"),
+                &b""[..],
+                hex!("e5564300c360ac729086e2cc806e828a84877f1eb8e5d974d873e065224901555fb8821590a33bacc61e39701cf9b46bd25bf5f0595bbe24655141438e7a100b"),
+            ),
+            (
+                hex!("4ccd089b28ff96da9db6c346ec114e0f5b8a319f35aba624da8cf6ed4fb8a6fb"),
+                hex!("3d4017c3e843895a92b70aa74d1b7ebc9c982ccf2ec4968cc0cd55f12af4660c"),
+                &hex!("72")[..],
+                hex!("92a009a9f0d4cab8720e820b5f642540a2b27b5416503f8fb3762223ebdb69da085ac1e43e15996e458f3613d0f11d8c387b2eaeb4302aeeb00d291612bb0c00"),
+            ),
+            (
+                hex!("c5aa8df43f9f837bedb7442f31dcb7b166d38535076f094b85ce3a2e0b4458f7"),
+                hex!("fc51cd8e6218a1a38da47ed00230f0580816ed13ba3303ac5deb911548908025"),
+                &hex!("af82")[..],
+                hex!("6291d657deec24024827e69c3abe01a30ce548a284743a445e3680d7db5ac3ac18ff9b538d16f290ae67f760984dc6594a7c15e9716ed28dc027beceea1ec40a"),
+            ),
+        ] {
+            let cipher = Ed25519::default().with_private_key(&private);
+            assert_eq!(public, cipher.public_key);
+            assert_eq!(signature, cipher.sign(message).unwrap());
+            assert!(cipher.verify(message, &signature));
+        }
+    }
+
+    // RFC 8032 section 7.2
+    #[test]
+    fn ctx_vectors() {
+        let private = hex!("0305334e381af78f141cb666f6199f57bc3495335a256a95bd2a55bf546663f6");
+        let message = hex!("f726936d19c800494e3fdaff20b276a8");
+        for (context, signature) in [
+            (
+                &b"foo"[..],
+                hex!("55a4cc2f70a54e04288c5f4cd1e45a7bb520b36292911876cada7323198dd87a8b36950b95130022907a7fb7c4e9b2d5f6cca685a587b4b21f4b888e4e7edb0d"),
+            ),
+            (
+                &b"bar"[..],
+                hex!("fc60d5872fc46b3aa69f8b5b4351d5808f92bcc044606db097abab6dbcb1aee3216c48e8b3b66431b5b186d1d28f8ee15a5ca2df6668346291c2043d4eb3e90d"),
+            ),
+        ] {
+            let cipher = Ed25519::default()
+                .with_private_key(&private)
+                .with_variant(Ed25519Variant::Ctx)
+                .with_context(context);
+            assert_eq!(
+                hex!("dfc9425e4f968f7f0c29f0259cf5f9aed6851c2bb4ad8bfb860cfee0ab248292"),
+                cipher.public_key
+            );
+            assert_eq!(signature, cipher.sign(&message).unwrap());
+            assert!(cipher.verify(&message, &signature));
+        }
+    }
+
+    // RFC 8032 section 7.3
+    #[test]
+    fn ph_vector() {
+        let cipher = Ed25519::default()
+            .with_private_key(&hex!(
+                "833fe62409237b9d62ec77587520911e9a759cec1d19755b7da901b96dca3d42"
+            ))
+            .with_variant(Ed25519Variant::Ph);
+        assert_eq!(
+            hex!("ec172b93ad5e563bf4932c70e1245034c35467ef2efd4d64ebf819683467e2bf"),
+            cipher.public_key
+        );
+        let signature = hex!("98a70222f0b8121aa9d30f813d683f809e462b469c7ff87639499bb94e6dae4131f85042463c2a355a2003d062adf5aaa10b8c61e636062aaad11c2a26083406");
+        assert_eq!(signature, cipher.sign(b"abc").unwrap());
+        assert!(cipher.verify(b"abc", &signature));
+    }
+
+    #[test]
+    fn rejected_signatures() {
+        let mut cipher = Ed25519::default();
+        cipher.generate_key(&mut StdRng::seed_from_u64(7));
+        let mut signature = cipher.sign(b"message").unwrap();
+        assert!(cipher.verify(b"message", &signature));
+        assert!(!cipher.verify(b"massage", &signature));
+
+        // A signature for one variant or context is not valid for another
+        let ctx = Ed25519::default()
+            .with_private_key(&cipher.private_key)
+            .with_variant(Ed25519Variant::Ctx)
+            .with_context(b"context");
+        assert!(!ctx.verify(b"message", &signature));
+        let ctx_signature = ctx.sign(b"message").unwrap();
+        assert!(ctx.verify(b"message", &ctx_signature));
+        assert!(!ctx
+            .with_context(b"other")
+            .verify(b"message", &ctx_signature));
+
+        // S must be reduced, adding L gives the same point but is not accepted
+        let s = U256::from_le_slice(&signature[32..]).wrapping_add(&L);
+        signature[32..].copy_from_slice(&s.to_le_bytes());
+        assert!(!cipher.verify(b"message", &signature));
+
+        signature[0] ^= 1;
+        assert!(!cipher.verify(b"message", &signature));
+        assert!(!cipher.verify(b"message", &signature[..63]));
+    }
+
+    #[test]
+    fn point_encoding() {
+        assert_eq!(
+            hex!("5866666666666666666666666666666666666666666666666666666666666666"),
+            BASE.encode()
+        );
+        let p = BASE.scalar_mul(&U256::from_u64(123456789));
+        assert!(EdPoint::decode(&p.encode()).unwrap().equals(&p));
+        // y = p is not canonical
+        assert!(EdPoint::decode(&hex!(
+            "edffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff7f"
+        ))
+        .is_none());
+        // The base point has order L
+        assert!(BASE.scalar_mul(&L).equals(&EdPoint::IDENTITY));
+    }
+}
@@ -0,0 +1,214 @@
+use super::{dh_groups::DhGroup, rfc6979::int2octets};
+use crate::Cipher;
+use hashers::hmac::HmacVariant;
+use num::{integer::gcd, BigUint, One, Zero};
+use rand::Rng;
+use utils::{
+    byte_formatting::ByteFormat, errors::GeneralError, math_functions::mul_inv,
+    primality::random_biguint_below,
+};
+
+// ElGamal signatures, the ancestor of DSA. Exponents are taken modulo p-1 so the nonce must be coprime to p-1.
+// https://doi.org/10.1109/TIT.1985.1057074
+pub struct ElGamalSignature {
+    pub input_format: ByteFormat,
+    pub output_format: ByteFormat,
+    pub modulus: BigUint,
+    pub generator: BigUint,
+    pub private_key: BigUint,
+    pub public_key: BigUint,
+    pub hash: HmacVariant,
+}
+
+impl Default for ElGamalSignature {
+    fn default() -> Self {
+        Self {
+            input_format: ByteFormat::Utf8,
+            output_format: ByteFormat::Hex,
+            modulus: DhGroup::Modp2048.modulus(),
+            generator: DhGroup::Modp2048.generator(),
+            private_key: BigUint::zero(),
+            public_key: BigUint::zero(),
+            hash: HmacVariant::Sha256,
+        }
+    }
+}
+
+impl ElGamalSignature {
+    /// Changing the group discards the keys
+    pub fn set_group(&mut self, generator: BigUint, modulus: BigUint) {
+        self.generator = generator;
+        self.modulus = modulus;
+        self.private_key = BigUint::zero();
+        self.public_key = BigUint::zero();
+    }
+
+    fn len(&self) -> usize {
+        self.modulus.bits().div_ceil(8) as usize
+    }
+
+    pub fn set_private_key(&mut self, x: &BigUint) -> Result<(), GeneralError> {
+        if x.is_zero() || *x >= &self.modulus - 1_u32 {
+            return Err(GeneralError::key("private key must be between 1 and p-2"));
+        }
+        self.private_key = x.clone();
+        self.public_key = self.generator.modpow(x, &self.modulus);
+        Ok(())
+    }
+
+    pub fn generate_key<R: Rng>(&mut self, rng: &mut R) {
+        let x = random_biguint_below(&(&self.modulus - 2_u32), rng) + 1_u32;
+        self.set_private_key(&x)
+            .expect("generated key is always in range");
+    }
+
+    /// The hash of the message reduced modulo p-1
+    pub fn hash_to_int(&self, msg: &[u8]) -> BigUint {
+        BigUint::from_bytes_be(&self.hash.hash(msg)) % (&self.modulus - 1_u32)
+    }
+
+    /// Sign an integer h directly with the nonce k, giving r = g^k and s = (h - xr)/k mod p-1. Reusing k for two
+    /// messages reveals the private key, just as with DSA.
+    pub fn sign_int_with_nonce(
+        &self,
+        h: &BigUint,
+        k: &BigUint,
+    ) -> Result<(BigUint, BigUint), GeneralError> {
+        let p = &self.modulus;
+        let p1 = p - 1_u32;
+        if self.private_key.is_zero() {
+            return Err(GeneralError::state("private key has not been set"));
+        }
+        if k.is_zero() || *k >= p1 || !gcd(k.clone(), p1.clone()).is_one() {
+            return Err(GeneralError::input(
+                "nonce must be between 1 and p-2 and coprime to p-1",
+            ));
+        }
+        let r = self.generator.modpow(k, p);
+        let k_inv = mul_inv(k, &p1)
+            .and_then(|x| x.to_biguint())
+            .expect("k is coprime to p-1");
+        let xr = (&self.private_key * &r) % &p1;
+        let s = ((h % &p1 + &p1 - xr) * k_inv) % &p1;
+        if s.is_zero() {
+            return Err(GeneralError::input("nonce gives s = 0"));
+        }
+        Ok((r, s))
+    }
+
+    /// Sign with a random nonce
+    pub fn sign<R: Rng>(
+        &self,
+        msg: &[u8],
+        rng: &mut R,
+    ) -> Result<(BigUint, BigUint), GeneralError> {
+        if self.private_key.is_zero() {
+            return Err(GeneralError::state("private key has not been set"));
+        }
+        let h = self.hash_to_int(msg);
+        let bound = &self.modulus - 2_u32;
+        loop {
+            let k = random_biguint_below(&bound, rng) + 1_u32;
+            if let Ok(signature) = self.sign_int_with_nonce(&h, &k) {
+                return Ok(signature);
+            }
+        }
+    }
+
+    /// Check that g^h = y^r * r^s
+    pub fn verify_int(&self, h: &BigUint, r: &BigUint, s: &BigUint) -> bool {
+        let p = &self.modulus;
+        if r.is_zero() || r >= p || s.is_zero() || *s >= p - 1_u32 || self.public_key.is_zero() {
+            return false;
+        }
+        let lhs = self.generator.modpow(h, p);
+        let rhs = (self.public_key.modpow(r, p) * r.modpow(s, p)) % p;
+        lhs == rhs
+    }
+
+    pub fn verify(&self, msg: &[u8], r: &BigUint, s: &BigUint) -> bool {
+        self.verify_int(&self.hash_to_int(msg), r, s)
+    }
+
+    /// Fixed width encoding of r followed by s
+    pub fn signature_to_bytes(&self, r: &BigUint, s: &BigUint) -> Vec<u8> {
+        let mut out = int2octets(r, self.len());
+        out.extend(int2octets(s, self.len()));
+        out
+    }
+
+    pub fn verify_bytes(&self, msg: &[u8], signature: &[u8]) -> bool {
+        let len = self.len();
+        if signature.len() != 2 * len {
+            return false;
+        }
+        self.verify(
+            msg,
+            &BigUint::from_bytes_be(&signature[..len]),
+            &BigUint::from_bytes_be(&signature[len..]),
+        )
+    }
+}
+
+impl Cipher for ElGamalSignature {
+    fn encrypt(&self, text: &str) -> Result<String, GeneralError> {
+        let bytes = self
+            .input_format
+            .text_to_bytes(text)
+            .map_err(|_| GeneralError::input("byte format error"))?;
+        let (r, s) = self.sign(&bytes, &mut rand::thread_rng())?;
+        Ok(self
+            .output_format
+            .byte_slice_to_text(self.signature_to_bytes(&r, &s)))
+    }
+
+    fn decrypt(&self, _text: &str) -> Result<String, GeneralError> {
+        Err(GeneralError::general(
+            "ElGamal signatures cannot be decrypted",
+        ))
+    }
+}
+
+#[cfg(test)]
+mod elgamal_signature_tests {
+
+    use super::*;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    // Example 11.66 from the Handbook of Applied Cryptography
+    #[test]
+    fn textbook_example() {
+        let mut cipher = ElGamalSignature::default();
+        cipher.set_group(BigUint::from(2_u32), BigUint::from(2357_u32));
+        cipher.set_private_key(&BigUint::from(1751_u32)).unwrap();
+        assert_eq!(BigUint::from(1185_u32), cipher.public_key);
+
+        let h = BigUint::from(1463_u32);
+        let (r, s) = cipher
+            .sign_int_with_nonce(&h, &BigUint::from(1529_u32))
+            .unwrap();
+        assert_eq!(BigUint::from(1490_u32), r);
+        assert_eq!(BigUint::from(1777_u32), s);
+        assert!(cipher.verify_int(&h, &r, &s));
+        assert!(!cipher.verify_int(&(h + 1_u32), &r, &s));
+
+        // An even nonce shares a factor with p-1
+        assert!(cipher
+            .sign_int_with_nonce(&BigUint::from(1463_u32), &BigUint::from(1528_u32))
+            .is_err());
+    }
+
+    #[test]
+    fn sign_verify() {
+        let mut rng = StdRng::seed_from_u64(6);
+        let mut cipher = ElGamalSignature::default();
+        cipher.generate_key(&mut rng);
+        let (r, s) = cipher.sign(b"attack at dawn", &mut rng).unwrap();
+        assert!(cipher.verify(b"attack at dawn", &r, &s));
+        assert!(!cipher.verify(b"attack at dusk", &r, &s));
+
+        let signature = cipher.signature_to_bytes(&r, &s);
+        assert!(cipher.verify_bytes(b"attack at dawn", &signature));
+        assert!(!cipher.verify_bytes(b"attack at dawn", &signature[1..]));
+    }
+}
@@ -0,0 +1,211 @@
+use super::rfc6979::int2octets;
+use crate::Cipher;
+use num::{integer::gcd, BigUint, One, Zero};
+use rand::{thread_rng, Rng};
+use utils::{
+    byte_formatting::ByteFormat,
+    errors::GeneralError,
+    primality::{is_probable_prime, random_biguint_below, random_prime},
+};
+
+// Goldwasser-Micali encryption, the first provably secure probabilistic scheme. Each bit is encrypted separately as
+// a random square for 0 or a random non-square with Jacobi symbol 1 for 1. Telling them apart without the factors of n
+// is the quadratic residuosity problem. Ciphertexts are enormous, one full size integer per bit.
+// https://doi.org/10.1016/0022-0000(84)90070-9
+
+// Euler's criterion for an odd prime p
+fn is_residue(a: &BigUint, p: &BigUint) -> bool {
+    a.modpow(&((p - 1_u32) >> 1), p).is_one()
+}
+
+pub struct GoldwasserMicali {
+    pub input_format: ByteFormat,
+    pub output_format: ByteFormat,
+
+    pub n: BigUint,
+    /// A non-residue modulo both p and q, so its Jacobi symbol modulo n is 1
+    pub x: BigUint,
+    pub p: BigUint,
+    pub q: BigUint,
+}
+
+impl Default for GoldwasserMicali {
+    fn default() -> Self {
+        Self {
+            input_format: ByteFormat::Utf8,
+            output_format: ByteFormat::Hex,
+
+            n: BigUint::zero(),
+            x: BigUint::zero(),
+            p: BigUint::zero(),
+            q: BigUint::zero(),
+        }
+    }
+}
+
+impl GoldwasserMicali {
+    /// Sets the key using the smallest suitable x
+    pub fn set_key(&mut self, p: &BigUint, q: &BigUint) -> Result<(), GeneralError> {
+        if p == q {
+            return Err(GeneralError::key("p and q must be distinct"));
+        }
+        let mut rng = thread_rng();
+        for (name, y) in [("p", p), ("q", q)] {
+            if !y.bit(0) || !is_probable_prime(y, 20, &mut rng) {
+                return Err(GeneralError::key(format!("{name} is not an odd prime")));
+            }
+        }
+        let mut x = BigUint::from(2_u32);
+        while is_residue(&x, p) || is_residue(&x, q) || (p % &x).is_zero() || (q % &x).is_zero() {
+            x += 1_u32;
+        }
+        self.n = p * q;
+        self.x = x;
+        self.p = p.clone();
+        self.q = q.clone();
+        Ok(())
+    }
+
+    pub fn generate_key<R: Rng>(&mut self, bits: u64, rng: &mut R) -> Result<(), GeneralError> {
+        if bits < 16 {
+            return Err(GeneralError::key("modulus must be at least 16 bits"));
+        }
+        let p = random_prime(bits / 2, rng);
+        let q = loop {
+            let q = random_prime(bits - bits / 2, rng);
+            if q != p {
+                break q;
+            }
+        };
+        self.set_key(&p, &q)
+    }
+
+    /// Length in bytes of the ciphertext of a single bit
+    pub fn block_len(&self) -> usize {
+        self.n.bits().div_ceil(8) as usize
+    }
+
+    /// c = y^2 x^b mod n for a random unit y
+    pub fn encrypt_bit<R: Rng>(&self, bit: bool, rng: &mut R) -> Result<BigUint, GeneralError> {
+        if self.n.is_zero() {
+            return Err(GeneralError::state("key has not been set"));
+        }
+        let y = loop {
+            let y = random_biguint_below(&self.n, rng);
+            if !y.is_zero() && gcd(y.clone(), self.n.clone()).is_one() {
+                break y;
+            }
+        };
+        let c = (&y * &y) % &self.n;
+        Ok(if bit { (c * &self.x) % &self.n } else { c })
+    }
+
+    /// The bit is 1 exactly when c is not a square modulo p
+    pub fn decrypt_bit(&self, c: &BigUint) -> Result<bool, GeneralError> {
+        if self.p.is_zero() {
+            return Err(GeneralError::state("private key has not been set"));
+        }
+        Ok(!is_residue(c, &self.p))
+    }
+
+    /// Ciphertext of the exclusive or of the two plaintext bits
+    pub fn xor(&self, c1: &BigUint, c2: &BigUint) -> BigUint {
+        (c1 * c2) % &self.n
+    }
+
+    /// Bits are encrypted from most to least significant
+    pub fn encrypt_bytes(&self, bytes: &[u8]) -> Result<Vec<u8>, GeneralError> {
+        let mut rng = thread_rng();
+        let mut out = Vec::with_capacity(bytes.len() * 8 * self.block_len());
+        for byte in bytes {
+            for i in (0..8).rev() {
+                let c = self.encrypt_bit((byte >> i) & 1 == 1, &mut rng)?;
+                out.extend(int2octets(&c, self.block_len()));
+            }
+        }
+        Ok(out)
+    }
+
+    pub fn decrypt_bytes(&self, bytes: &[u8]) -> Result<Vec<u8>, GeneralError> {
+        let len = self.block_len();
+        if len == 0 || !bytes.len().is_multiple_of(8 * len) {
+            return Err(GeneralError::input(format!(
+                "ciphertext must be in chunks of {} bytes",
+                8 * len
+            )));
+        }
+        let mut out = Vec::with_capacity(bytes.len() / (8 * len));
+        for chunk in bytes.chunks_exact(8 * len) {
+            let mut byte = 0;
+            for block in chunk.chunks_exact(len) {
+                byte <<= 1;
+                if self.decrypt_bit(&BigUint::from_bytes_be(block))? {
+                    byte |= 1;
+                }
+            }
+            out.push(byte);
+        }
+        Ok(out)
+    }
+}
+
+impl Cipher for GoldwasserMicali {
+    fn encrypt(&self, text: &str) -> Result<String, GeneralError> {
+        let bytes = self
+            .input_format
+            .text_to_bytes(text)
+            .map_err(|_| GeneralError::input("byte format error"))?;
+        Ok(self
+            .output_format
+            .byte_slice_to_text(self.encrypt_bytes(&bytes)?))
+    }
+
+    fn decrypt(&self, text: &str) -> Result<String, GeneralError> {
+        let bytes = self
+            .input_format
+            .text_to_bytes(text)
+            .map_err(|_| GeneralError::input("byte format error"))?;
+        Ok(self
+            .output_format
+            .byte_slice_to_text(self.decrypt_bytes(&bytes)?))
+    }
+}
+
+#[cfg(test)]
+mod goldwasser_micali_tests {
+
+    use super::*;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn bits() {
+        let mut rng = StdRng::seed_from_u64(5);
+        let mut cipher = GoldwasserMicali::default();
+        cipher
+            .set_key(&BigUint::from(499_u32), &BigUint::from(547_u32))
+            .unwrap();
+        assert_eq!(BigUint::from(2_u32), cipher.x);
+        for bit in [false, true] {
+            let c1 = cipher.encrypt_bit(bit, &mut rng).unwrap();
+            let c2 = cipher.encrypt_bit(bit, &mut rng).unwrap();
+            assert_ne!(c1, c2);
+            assert_eq!(bit, cipher.decrypt_bit(&c1).unwrap());
+            assert!(!cipher.decrypt_bit(&cipher.xor(&c1, &c2)).unwrap());
+        }
+        let c0 = cipher.encrypt_bit(false, &mut rng).unwrap();
+        let c1 = cipher.encrypt_bit(true, &mut rng).unwrap();
+        assert!(cipher.decrypt_bit(&cipher.xor(&c0, &c1)).unwrap());
+    }
+
+    #[test]
+    fn encrypt_decrypt() {
+        let mut cipher = GoldwasserMicali::default();
+        cipher
+            .generate_key(128, &mut StdRng::seed_from_u64(6))
+            .unwrap();
+        let ctext = cipher.encrypt("attack at dawn").unwrap();
+        cipher.output_format = ByteFormat::Utf8;
+        cipher.input_format = ByteFormat::Hex;
+        assert_eq!("attack at dawn", cipher.decrypt(&ctext).unwrap());
+    }
+}
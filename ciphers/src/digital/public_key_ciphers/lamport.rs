@@ -0,0 +1,247 @@
+use crate::Cipher;
+use hashers::{sha::Sha256, traits::StatefulHasher};
+use rand::Rng;
+use std::cell::Cell;
+use utils::{byte_formatting::ByteFormat, errors::GeneralError};
+
+// Lamport one-time signatures. The private key is two random values for every bit of the message digest and the
+// public key is the hash of each of them. Signing reveals one value from each pair, selected by the bits of the
+// digest. Only the security of the hash function is needed but a key can sign just one message, signing a second
+// reveals enough of the private key to forge signatures on other messages.
+// https://www.microsoft.com/en-us/research/publication/constructing-digital-signatures-one-way-function/
+
+fn digest_bit(digest: &[u8], i: usize) -> usize {
+    ((digest[i / 8] >> (7 - i % 8)) & 1) as usize
+}
+
+pub struct Lamport<H: StatefulHasher> {
+    pub input_format: ByteFormat,
+    pub output_format: ByteFormat,
+
+    pub hasher: fn() -> H,
+    /// One pair of secret values for each bit of the digest
+    pub private_key: Vec<[Vec<u8>; 2]>,
+    pub public_key: Vec<[Vec<u8>; 2]>,
+    used: Cell<bool>,
+}
+
+impl Default for Lamport<Sha256> {
+    fn default() -> Self {
+        Self::new(Sha256::init)
+    }
+}
+
+impl<H: StatefulHasher> Lamport<H> {
+    pub fn new(hasher: fn() -> H) -> Self {
+        Self {
+            input_format: ByteFormat::Utf8,
+            output_format: ByteFormat::Hex,
+            hasher,
+            private_key: Vec::new(),
+            public_key: Vec::new(),
+            used: Cell::new(false),
+        }
+    }
+
+    fn hash(&self, bytes: &[u8]) -> Vec<u8> {
+        (self.hasher)().hash(bytes)
+    }
+
+    /// Output length of the hash function in bytes
+    pub fn hash_len(&self) -> usize {
+        (self.hasher)().finalize().len()
+    }
+
+    /// Number of bytes in a signature, one secret value for each bit of the digest
+    pub fn signature_len(&self) -> usize {
+        8 * self.hash_len() * self.hash_len()
+    }
+
+    pub fn set_private_key(&mut self, private_key: Vec<[Vec<u8>; 2]>) -> Result<(), GeneralError> {
+        let n = self.hash_len();
+        if private_key.len() != 8 * n || private_key.iter().flatten().any(|x| x.len() != n) {
+            return Err(GeneralError::key(format!(
+                "private key must be {} pairs of {n} byte values",
+                8 * n
+            )));
+        }
+        self.public_key = private_key
+            .iter()
+            .map(|[a, b]| [self.hash(a), self.hash(b)])
+            .collect();
+        self.private_key = private_key;
+        self.used.set(false);
+        Ok(())
+    }
+
+    pub fn generate_key<R: Rng>(&mut self, rng: &mut R) {
+        let n = self.hash_len();
+        let mut random = || (0..n).map(|_| rng.gen()).collect::<Vec<u8>>();
+        let private_key = (0..8 * n).map(|_| [random(), random()]).collect();
+        self.set_private_key(private_key)
+            .expect("generated key always has the right size");
+    }
+
+    /// Derive the private key from a secret seed, x[i][b] = H(seed || index || i || b), so that a single seed can
+    /// stand in for many one-time keys
+    pub fn derive_key(&mut self, seed: &[u8], index: u32) {
+        let private_key = (0..8 * self.hash_len() as u16)
+            .map(|i| {
+                [0_u8, 1].map(|b| {
+                    let mut h = (self.hasher)();
+                    h.update(seed);
+                    h.update(&index.to_be_bytes());
+                    h.update(&i.to_be_bytes());
+                    h.update(&[b]);
+                    h.finalize()
+                })
+            })
+            .collect();
+        self.set_private_key(private_key)
+            .expect("derived key always has the right size");
+    }
+
+    /// Hash of the whole public key, which is all that a Merkle tree needs to commit to
+    pub fn public_key_hash(&self) -> Vec<u8> {
+        let mut h = (self.hasher)();
+        for x in self.public_key.iter().flatten() {
+            h.update(x);
+        }
+        h.finalize()
+    }
+
+    pub fn is_used(&self) -> bool {
+        self.used.get()
+    }
+
+    /// Sign without checking whether the key has been used before. Signing two different messages with the same
+    /// key reveals both secrets for every bit where their digests differ.
+    pub fn sign_unchecked(&self, msg: &[u8]) -> Result<Vec<u8>, GeneralError> {
+        if self.private_key.is_empty() {
+            return Err(GeneralError::state("private key has not been set"));
+        }
+        let digest = self.hash(msg);
+        Ok((0..self.private_key.len())
+            .flat_map(|i| self.private_key[i][digest_bit(&digest, i)].clone())
+            .collect())
+    }
+
+    /// Sign a message, refusing if the key has already signed something
+    pub fn sign(&self, msg: &[u8]) -> Result<Vec<u8>, GeneralError> {
+        if self.used.get() {
+            return Err(GeneralError::state(
+                "this one-time key has already been used",
+            ));
+        }
+        let signature = self.sign_unchecked(msg)?;
+        self.used.set(true);
+        Ok(signature)
+    }
+
+    /// The public values that a signature of msg does not reveal, which lets a verifier who only knows the hash of
+    /// the public key rebuild all of it
+    pub fn complement(&self, msg: &[u8]) -> Vec<u8> {
+        let digest = self.hash(msg);
+        (0..self.public_key.len())
+            .flat_map(|i| self.public_key[i][1 - digest_bit(&digest, i)].clone())
+            .collect()
+    }
+
+    /// Rebuild the hash of the public key from a signature and its complement
+    pub fn public_key_hash_from_signature(
+        &self,
+        msg: &[u8],
+        signature: &[u8],
+        complement: &[u8],
+    ) -> Option<Vec<u8>> {
+        let n = self.hash_len();
+        if signature.len() != self.signature_len() || complement.len() != self.signature_len() {
+            return None;
+        }
+        let digest = self.hash(msg);
+        let mut h = (self.hasher)();
+        for (i, (s, c)) in signature
+            .chunks_exact(n)
+            .zip(complement.chunks_exact(n))
+            .enumerate()
+        {
+            let revealed = self.hash(s);
+            if digest_bit(&digest, i) == 0 {
+                h.update(&revealed);
+                h.update(c);
+            } else {
+                h.update(c);
+                h.update(&revealed);
+            }
+        }
+        Some(h.finalize())
+    }
+
+    pub fn verify(&self, msg: &[u8], signature: &[u8]) -> bool {
+        let n = self.hash_len();
+        if self.public_key.is_empty() || signature.len() != self.signature_len() {
+            return false;
+        }
+        let digest = self.hash(msg);
+        signature
+            .chunks_exact(n)
+            .enumerate()
+            .all(|(i, s)| self.hash(s) == self.public_key[i][digest_bit(&digest, i)])
+    }
+}
+
+impl<H: StatefulHasher> Cipher for Lamport<H> {
+    fn encrypt(&self, text: &str) -> Result<String, GeneralError> {
+        let bytes = self
+            .input_format
+            .text_to_bytes(text)
+            .map_err(|_| GeneralError::input("byte format error"))?;
+        Ok(self.output_format.byte_slice_to_text(self.sign(&bytes)?))
+    }
+
+    fn decrypt(&self, _text: &str) -> Result<String, GeneralError> {
+        Err(GeneralError::general(
+            "Lamport creates signatures, it does not decrypt",
+        ))
+    }
+}
+
+#[cfg(test)]
+mod lamport_tests {
+
+    use super::*;
+    use hashers::{blake::Blake2b, sha::Keccack};
+    use rand::{rngs::StdRng, SeedableRng};
+
+    fn sign_and_verify<H: StatefulHasher>(mut signer: Lamport<H>) {
+        signer.generate_key(&mut StdRng::seed_from_u64(18));
+        let signature = signer.sign(b"attack at dawn").unwrap();
+        assert_eq!(signer.signature_len(), signature.len());
+        assert!(signer.verify(b"attack at dawn", &signature));
+        assert!(!signer.verify(b"attack at dusk", &signature));
+        let complement = signer.complement(b"attack at dawn");
+        assert_eq!(
+            Some(signer.public_key_hash()),
+            signer.public_key_hash_from_signature(b"attack at dawn", &signature, &complement)
+        );
+    }
+
+    #[test]
+    fn hash_functions() {
+        sign_and_verify(Lamport::default());
+        sign_and_verify(Lamport::new(|| Keccack::shake_256(24)));
+        sign_and_verify(Lamport::new(|| Blake2b::init_hash(16)));
+    }
+
+    #[test]
+    fn one_time() {
+        let mut signer = Lamport::default();
+        signer.derive_key(b"seed", 0);
+        assert!(signer.sign(b"attack at dawn").is_ok());
+        assert!(signer.sign(b"attack at dusk").is_err());
+
+        let mut other = Lamport::default();
+        other.derive_key(b"seed", 1);
+        assert_ne!(signer.public_key_hash(), other.public_key_hash());
+    }
+}
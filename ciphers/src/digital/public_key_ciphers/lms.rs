@@ -0,0 +1,613 @@
+use crate::Cipher;
+use hashers::{
+    sha::{Keccack, Sha256},
+    traits::StatefulHasher,
+};
+use rand::{thread_rng, Rng};
+use std::cell::Cell;
+use utils::{byte_formatting::ByteFormat, errors::GeneralError};
+
+// Leighton-Micali signatures as specified in RFC 8554. Like the Merkle signature scheme a hash tree is built over
+// one-time public keys, here LM-OTS keys which are Winternitz chains. Every hash includes the 16-byte identifier of
+// the tree, the number of the leaf or node and a domain separator so no two hash calls anywhere share an input.
+// RFC 8554 only assigns type codes to SHA-256 with n = 32, NIST SP 800-208 adds SHA-256 truncated to 24 bytes and
+// SHAKE256 with 24 or 32 bytes of output. Only a single tree is supported, not the hierarchy of trees (HSS) that the
+// RFC builds on top of it. HSS only adds the signature of each tree's public key by the tree above it, as the tests
+// show with the two levels of an RFC test case, and keeping several trees and their states is out of scope here.
+// https://www.rfc-editor.org/rfc/rfc8554
+// https://csrc.nist.gov/pubs/sp/800/208/final
+
+const D_PBLC: [u8; 2] = [0x80, 0x80];
+const D_MESG: [u8; 2] = [0x81, 0x81];
+const D_LEAF: [u8; 2] = [0x82, 0x82];
+const D_INTR: [u8; 2] = [0x83, 0x83];
+
+fn digest<H: StatefulHasher>(mut h: H, parts: &[&[u8]]) -> Vec<u8> {
+    for part in parts {
+        h.update(part);
+    }
+    h.finalize()
+}
+
+/// The hash functions and output lengths that have type codes, in the order of their codes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LmsHash {
+    Sha256N32,
+    Sha256N24,
+    ShakeN32,
+    ShakeN24,
+}
+
+impl LmsHash {
+    pub const ALL: [Self; 4] = [
+        Self::Sha256N32,
+        Self::Sha256N24,
+        Self::ShakeN32,
+        Self::ShakeN24,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Sha256N32 | Self::Sha256N24 => "SHA256",
+            Self::ShakeN32 | Self::ShakeN24 => "SHAKE",
+        }
+    }
+
+    pub fn n(&self) -> usize {
+        match self {
+            Self::Sha256N32 | Self::ShakeN32 => 32,
+            Self::Sha256N24 | Self::ShakeN24 => 24,
+        }
+    }
+
+    fn position(&self) -> u32 {
+        Self::ALL.iter().position(|h| h == self).unwrap() as u32
+    }
+
+    pub fn hash(&self, parts: &[&[u8]]) -> Vec<u8> {
+        match self {
+            Self::Sha256N32 => digest(Sha256::init(), parts),
+            Self::Sha256N24 => {
+                let mut out = digest(Sha256::init(), parts);
+                out.truncate(24);
+                out
+            }
+            Self::ShakeN32 => digest(Keccack::shake_256(32), parts),
+            Self::ShakeN24 => digest(Keccack::shake_256(24), parts),
+        }
+    }
+
+    /// Each hash has five consecutive LMS type codes, one for each height, starting from 5
+    pub fn lms_type(&self, height: u32) -> u32 {
+        5 + 5 * self.position() + height / 5 - 1
+    }
+
+    /// Each hash has four consecutive LM-OTS type codes, one for each Winternitz parameter, starting from 1
+    pub fn lmots_type(&self, w: u32) -> u32 {
+        1 + 4 * self.position() + w.trailing_zeros()
+    }
+
+    /// The hash and height given by an LMS type code
+    pub fn from_lms_type(code: u32) -> Option<(Self, u32)> {
+        let i = code.checked_sub(5)?;
+        Some((*Self::ALL.get(i as usize / 5)?, 5 * (i % 5 + 1)))
+    }
+
+    /// The hash and Winternitz parameter given by an LM-OTS type code
+    pub fn from_lmots_type(code: u32) -> Option<(Self, u32)> {
+        let i = code.checked_sub(1)?;
+        Some((*Self::ALL.get(i as usize / 4)?, 1 << (i % 4)))
+    }
+}
+
+pub struct Lms {
+    pub input_format: ByteFormat,
+    pub output_format: ByteFormat,
+
+    pub hash: LmsHash,
+    /// Height of the tree, one of 5, 10, 15, 20 or 25
+    pub height: u32,
+    /// Winternitz parameter, the number of bits in each digit, one of 1, 2, 4 or 8
+    pub w: u32,
+    pub seed: Vec<u8>,
+    pub identifier: [u8; 16],
+    // Node r of the tree is nodes[r], with the root at 1 and the leaves at 2^h to 2^(h+1)-1
+    nodes: Vec<Vec<u8>>,
+    next_index: Cell<u32>,
+}
+
+impl Default for Lms {
+    fn default() -> Self {
+        Self::new(LmsHash::Sha256N32, 5, 4).expect("parameters are valid")
+    }
+}
+
+impl Lms {
+    pub fn new(hash: LmsHash, height: u32, w: u32) -> Result<Self, GeneralError> {
+        let mut out = Self {
+            input_format: ByteFormat::Utf8,
+            output_format: ByteFormat::Hex,
+            hash,
+            height: 5,
+            w: 4,
+            seed: Vec::new(),
+            identifier: [0; 16],
+            nodes: Vec::new(),
+            next_index: Cell::new(0),
+        };
+        out.set_parameters(hash, height, w)?;
+        Ok(out)
+    }
+
+    /// Changing the parameters discards the key
+    pub fn set_parameters(
+        &mut self,
+        hash: LmsHash,
+        height: u32,
+        w: u32,
+    ) -> Result<(), GeneralError> {
+        if ![5, 10, 15, 20, 25].contains(&height) {
+            return Err(GeneralError::key("height must be 5, 10, 15, 20 or 25"));
+        }
+        if ![1, 2, 4, 8].contains(&w) {
+            return Err(GeneralError::key("w must be 1, 2, 4 or 8"));
+        }
+        self.hash = hash;
+        self.height = height;
+        self.w = w;
+        self.seed.clear();
+        self.nodes.clear();
+        self.next_index.set(0);
+        Ok(())
+    }
+
+    pub fn hash_len(&self) -> usize {
+        self.hash.n()
+    }
+
+    pub fn lms_type(&self) -> u32 {
+        self.hash.lms_type(self.height)
+    }
+
+    pub fn lmots_type(&self) -> u32 {
+        self.hash.lmots_type(self.w)
+    }
+
+    /// Names of the parameter sets as in RFC 8554 and SP 800-208
+    pub fn parameter_names(&self) -> (String, String) {
+        let (name, n) = (self.hash.name(), self.hash_len());
+        (
+            format!("LMS_{name}_M{n}_H{}", self.height),
+            format!("LMOTS_{name}_N{n}_W{}", self.w),
+        )
+    }
+
+    fn hash(&self, parts: &[&[u8]]) -> Vec<u8> {
+        self.hash.hash(parts)
+    }
+
+    // Number of digits in the digest and the left shift of the checksum, from Appendix B
+    fn u_and_ls(&self) -> (usize, u32) {
+        let w = self.w as usize;
+        let u = (8 * self.hash_len()).div_ceil(w);
+        let v = ((((1 << w) - 1) * u).ilog2() as usize + 1).div_ceil(w);
+        (u, 16 - (v * w) as u32)
+    }
+
+    /// Number of Winternitz chains in a one-time key
+    pub fn chains(&self) -> usize {
+        let (u, ls) = self.u_and_ls();
+        u + (16 - ls as usize) / self.w as usize
+    }
+
+    fn coefficient(&self, bytes: &[u8], i: usize) -> usize {
+        let w = self.w as usize;
+        let byte = bytes[i * w / 8] as usize;
+        (byte >> (8 - (w * (i % (8 / w)) + w))) & ((1 << w) - 1)
+    }
+
+    // The digits of Q followed by the checksum
+    fn digits(&self, q_hash: &[u8]) -> Vec<usize> {
+        let (u, ls) = self.u_and_ls();
+        let max = (1 << self.w) - 1;
+        let checksum: usize = (0..u).map(|i| max - self.coefficient(q_hash, i)).sum();
+        let mut bytes = q_hash.to_vec();
+        bytes.extend_from_slice(&((checksum << ls) as u16).to_be_bytes());
+        (0..self.chains())
+            .map(|i| self.coefficient(&bytes, i))
+            .collect()
+    }
+
+    fn chain(&self, q: u32, i: usize, x: &[u8], start: usize, end: usize) -> Vec<u8> {
+        let mut tmp = x.to_vec();
+        for j in start..end {
+            tmp = self.hash(&[
+                &self.identifier,
+                &q.to_be_bytes(),
+                &(i as u16).to_be_bytes(),
+                &[j as u8],
+                &tmp,
+            ]);
+        }
+        tmp
+    }
+
+    // Secret values are derived from the seed as in Appendix A
+    fn ots_secret(&self, q: u32, i: usize) -> Vec<u8> {
+        self.hash(&[
+            &self.identifier,
+            &q.to_be_bytes(),
+            &(i as u16).to_be_bytes(),
+            &[0xff],
+            &self.seed,
+        ])
+    }
+
+    fn ots_public_key(&self, q: u32) -> Vec<u8> {
+        let end = (1 << self.w) - 1;
+        let mut parts = Vec::with_capacity(self.chains());
+        for i in 0..self.chains() {
+            parts.push(self.chain(q, i, &self.ots_secret(q, i), 0, end));
+        }
+        let q = q.to_be_bytes();
+        let mut input: Vec<&[u8]> = vec![&self.identifier, &q, &D_PBLC];
+        input.extend(parts.iter().map(|part| part.as_slice()));
+        self.hash(&input)
+    }
+
+    /// Derive every one-time key from the seed and build the tree
+    pub fn set_key(&mut self, seed: Vec<u8>, identifier: [u8; 16]) -> Result<(), GeneralError> {
+        if seed.len() != self.hash_len() {
+            return Err(GeneralError::key(format!(
+                "seed must be exactly {} bytes",
+                self.hash_len()
+            )));
+        }
+        self.seed = seed;
+        self.identifier = identifier;
+        let leaves = 1_u32 << self.height;
+        let mut nodes = vec![Vec::new(); 2 * leaves as usize];
+        for q in 0..leaves {
+            let r = leaves + q;
+            nodes[r as usize] = self.hash(&[
+                &self.identifier,
+                &r.to_be_bytes(),
+                &D_LEAF,
+                &self.ots_public_key(q),
+            ]);
+        }
+        for r in (1..leaves).rev() {
+            nodes[r as usize] = self.hash(&[
+                &self.identifier,
+                &r.to_be_bytes(),
+                &D_INTR,
+                &nodes[2 * r as usize],
+                &nodes[2 * r as usize + 1],
+            ]);
+        }
+        self.nodes = nodes;
+        self.next_index.set(0);
+        Ok(())
+    }
+
+    pub fn generate_key<R: Rng>(&mut self, rng: &mut R) {
+        let seed = (0..self.hash_len()).map(|_| rng.gen()).collect();
+        self.set_key(seed, rng.gen())
+            .expect("generated seed always has the right size");
+    }
+
+    /// The public key is u32str(type) || u32str(otstype) || I || T[1]
+    pub fn public_key(&self) -> Vec<u8> {
+        let mut out = self.lms_type().to_be_bytes().to_vec();
+        out.extend(self.lmots_type().to_be_bytes());
+        out.extend(self.identifier);
+        if let Some(root) = self.nodes.get(1) {
+            out.extend(root);
+        }
+        out
+    }
+
+    pub fn next_index(&self) -> u32 {
+        self.next_index.get()
+    }
+
+    /// Sign with leaf q using the randomizer c. The signature is u32str(q) || LM-OTS signature || u32str(type) ||
+    /// the authentication path.
+    pub fn sign_with_index(&self, q: u32, msg: &[u8], c: &[u8]) -> Result<Vec<u8>, GeneralError> {
+        if self.nodes.is_empty() {
+            return Err(GeneralError::state("key has not been set"));
+        }
+        if q >> self.height != 0 {
+            return Err(GeneralError::input("index is outside of the tree"));
+        }
+        if c.len() != self.hash_len() {
+            return Err(GeneralError::input("randomizer has the wrong length"));
+        }
+        let q_hash = self.hash(&[&self.identifier, &q.to_be_bytes(), &D_MESG, c, msg]);
+        let mut out = q.to_be_bytes().to_vec();
+        out.extend(self.lmots_type().to_be_bytes());
+        out.extend_from_slice(c);
+        for (i, a) in self.digits(&q_hash).into_iter().enumerate() {
+            out.extend(self.chain(q, i, &self.ots_secret(q, i), 0, a));
+        }
+        out.extend(self.lms_type().to_be_bytes());
+        let mut r = (1 << self.height) + q as usize;
+        while r > 1 {
+            out.extend_from_slice(&self.nodes[r ^ 1]);
+            r /= 2;
+        }
+        Ok(out)
+    }
+
+    /// Sign with the next unused leaf and a random randomizer
+    pub fn sign(&self, msg: &[u8]) -> Result<Vec<u8>, GeneralError> {
+        let q = self.next_index.get();
+        if q >> self.height != 0 {
+            return Err(GeneralError::state("every one-time key has been used"));
+        }
+        let c: Vec<u8> = (0..self.hash_len()).map(|_| thread_rng().gen()).collect();
+        let signature = self.sign_with_index(q, msg, &c)?;
+        self.next_index.set(q + 1);
+        Ok(signature)
+    }
+
+    /// Verify a signature against any public key. The hash function, tree height and Winternitz parameter are read
+    /// from the type codes in the key and the signature.
+    pub fn verify(&self, public_key: &[u8], msg: &[u8], signature: &[u8]) -> bool {
+        if public_key.len() < 24 || signature.len() < 8 {
+            return false;
+        }
+        let word = |bytes: &[u8], i: usize| u32::from_be_bytes(bytes[i..i + 4].try_into().unwrap());
+        let (lms_type, lmots_type) = (word(public_key, 0), word(public_key, 4));
+        let (Some((hash, height)), Some((ots_hash, w))) = (
+            LmsHash::from_lms_type(lms_type),
+            LmsHash::from_lmots_type(lmots_type),
+        ) else {
+            return false;
+        };
+        // SP 800-208 only allows a one-time signature with the same hash function and output length as the tree
+        if hash != ots_hash {
+            return false;
+        }
+        let Ok(mut params) = Self::new(hash, height, w) else {
+            return false;
+        };
+        let n = params.hash_len();
+        if public_key.len() != 24 + n {
+            return false;
+        }
+        params.identifier = public_key[8..24].try_into().unwrap();
+        let root = &public_key[24..];
+
+        let p = params.chains();
+        let height = params.height as usize;
+        if signature.len() != 12 + n * (p + 1) + height * n {
+            return false;
+        }
+        let q = word(signature, 0);
+        if word(signature, 4) != lmots_type || word(signature, 8 + n * (p + 1)) != lms_type {
+            return false;
+        }
+        if q >> height != 0 {
+            return false;
+        }
+        let c = &signature[8..8 + n];
+        let y = signature[8 + n..8 + n * (p + 1)].chunks_exact(n);
+        let path = signature[12 + n * (p + 1)..].chunks_exact(n);
+
+        let q_hash = params.hash(&[&params.identifier, &q.to_be_bytes(), &D_MESG, c, msg]);
+        let end = (1 << params.w) - 1;
+        let z: Vec<Vec<u8>> = params
+            .digits(&q_hash)
+            .into_iter()
+            .zip(y)
+            .enumerate()
+            .map(|(i, (a, y))| params.chain(q, i, y, a, end))
+            .collect();
+        let q_bytes = q.to_be_bytes();
+        let mut input: Vec<&[u8]> = vec![&params.identifier, &q_bytes, &D_PBLC];
+        input.extend(z.iter().map(|z| z.as_slice()));
+        let candidate = params.hash(&input);
+
+        let mut r = (1_u32 << height) + q;
+        let mut tmp = params.hash(&[&params.identifier, &r.to_be_bytes(), &D_LEAF, &candidate]);
+        for sibling in path {
+            tmp = if r % 2 == 1 {
+                params.hash(&[
+                    &params.identifier,
+                    &(r / 2).to_be_bytes(),
+                    &D_INTR,
+                    sibling,
+                    &tmp,
+                ])
+            } else {
+                params.hash(&[
+                    &params.identifier,
+                    &(r / 2).to_be_bytes(),
+                    &D_INTR,
+                    &tmp,
+                    sibling,
+                ])
+            };
+            r /= 2;
+        }
+        tmp == root
+    }
+}
+
+impl Cipher for Lms {
+    fn encrypt(&self, text: &str) -> Result<String, GeneralError> {
+        let bytes = self
+            .input_format
+            .text_to_bytes(text)
+            .map_err(|_| GeneralError::input("byte format error"))?;
+        Ok(self.output_format.byte_slice_to_text(self.sign(&bytes)?))
+    }
+
+    fn decrypt(&self, _text: &str) -> Result<String, GeneralError> {
+        Err(GeneralError::general(
+            "LMS creates signatures, it does not decrypt",
+        ))
+    }
+}
+
+#[cfg(test)]
+mod lms_tests {
+
+    use super::*;
+    use hex_literal::hex;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    // The private keys of RFC 8554 Appendix F Test Case 2, both levels of the HSS key
+    #[test]
+    fn public_keys() {
+        let mut top = Lms::new(LmsHash::Sha256N32, 10, 4).unwrap();
+        top.set_key(
+            hex!("558b8966c48ae9cb898b423c83443aae014a72f1b1ab5cc85cf1d892903b5439").to_vec(),
+            hex!("d08fabd4a2091ff0a8cb4ed834e74534"),
+        )
+        .unwrap();
+        assert_eq!(
+            hex!("00000006 00000003 d08fabd4a2091ff0a8cb4ed834e74534 32a58885cd9ba0431235466bff9651c6c92124404d45fa53cf161c28f1ad5a8e").to_vec(),
+            top.public_key()
+        );
+
+        let mut bottom = Lms::new(LmsHash::Sha256N32, 5, 8).unwrap();
+        bottom
+            .set_key(
+                hex!("a1c4696e2608035a886100d05cd99945eb3370731884a8235e2fb3d4d71f2547").to_vec(),
+                hex!("215f83b7ccb9acbcd08db97b0d04dc2b"),
+            )
+            .unwrap();
+        assert_eq!(
+            hex!("00000005 00000004 215f83b7ccb9acbcd08db97b0d04dc2b a1cd035833e0e90059603f26e07ad2aad152338e7a5e5984bcd5f7bb4eba40b7").to_vec(),
+            bottom.public_key()
+        );
+
+        // The top level signs the public key of the bottom level
+        let signature = top.sign(&bottom.public_key()).unwrap();
+        assert!(bottom.verify(&top.public_key(), &bottom.public_key(), &signature));
+    }
+
+    #[test]
+    fn chain_counts() {
+        // The values of p from Table 1 of RFC 8554
+        for (w, p) in [(1, 265), (2, 133), (4, 67), (8, 34)] {
+            assert_eq!(p, Lms::new(LmsHash::Sha256N32, 5, w).unwrap().chains());
+        }
+    }
+
+    #[test]
+    fn type_codes() {
+        // LMS_SHAKE_M24_H5 and LMOTS_SHAKE_N24_W2 from SP 800-208
+        let lms = Lms::new(LmsHash::ShakeN24, 5, 2).unwrap();
+        assert_eq!((0x14, 0x0e), (lms.lms_type(), lms.lmots_type()));
+        // LMS_SHA256_M24_H25 and LMOTS_SHA256_N24_W8
+        let lms = Lms::new(LmsHash::Sha256N24, 25, 8).unwrap();
+        assert_eq!((0x0e, 0x08), (lms.lms_type(), lms.lmots_type()));
+        for hash in LmsHash::ALL {
+            for height in [5, 10, 15, 20, 25] {
+                assert_eq!(
+                    Some((hash, height)),
+                    LmsHash::from_lms_type(hash.lms_type(height))
+                );
+            }
+            for w in [1, 2, 4, 8] {
+                assert_eq!(
+                    Some((hash, w)),
+                    LmsHash::from_lmots_type(hash.lmots_type(w))
+                );
+            }
+        }
+        assert_eq!(None, LmsHash::from_lms_type(4));
+        assert_eq!(None, LmsHash::from_lms_type(0x19));
+        assert_eq!(None, LmsHash::from_lmots_type(0));
+        assert_eq!(None, LmsHash::from_lmots_type(0x11));
+    }
+
+    #[test]
+    fn sign_verify() {
+        let mut signer = Lms::new(LmsHash::ShakeN24, 5, 2).unwrap();
+        signer.generate_key(&mut StdRng::seed_from_u64(21));
+        let public_key = signer.public_key();
+        for _ in 0..3 {
+            let signature = signer.sign(b"attack at dawn").unwrap();
+            assert!(signer.verify(&public_key, b"attack at dawn", &signature));
+            assert!(!signer.verify(&public_key, b"attack at dusk", &signature));
+        }
+        let mut signature = signer.sign(b"attack at dawn").unwrap();
+        signature[3] ^= 1;
+        assert!(!signer.verify(&public_key, b"attack at dawn", &signature));
+    }
+
+    #[test]
+    fn mismatched_hashes() {
+        let mut signer = Lms::new(LmsHash::Sha256N24, 5, 4).unwrap();
+        signer.generate_key(&mut StdRng::seed_from_u64(24));
+        let mut public_key = signer.public_key();
+        let mut signature = signer.sign(b"attack at dawn").unwrap();
+        assert!(signer.verify(&public_key, b"attack at dawn", &signature));
+        // Claim LMOTS_SHAKE_N24_W4 in both the key and the signature
+        public_key[7] = 0x0f;
+        signature[7] = 0x0f;
+        assert!(!signer.verify(&public_key, b"attack at dawn", &signature));
+    }
+
+    // The SHA-256/192 test case of draft-fluhrer-lms-more-parm-sets-15, which SP 800-208 is based on. The signature
+    // uses leaf 5 of an LMS_SHA256_M24_H5 tree with LMOTS_SHA256_N24_W8.
+    #[test]
+    fn sha256_192_test_case() {
+        let mut lms = Lms::new(LmsHash::Sha256N24, 5, 8).unwrap();
+        lms.set_key(
+            hex!("000102030405060708090a0b0c0d0e0f1011121314151617").to_vec(),
+            hex!("202122232425262728292a2b2c2d2e2f"),
+        )
+        .unwrap();
+        let public_key = hex!("0000000a 00000008 202122232425262728292a2b2c2d2e2f 2c571450aed99cfb4f4ac285da14882796618314508b12d2");
+        assert_eq!(public_key.to_vec(), lms.public_key());
+
+        let msg = b"Test message for SHA256-192\n";
+        let signature = hex!(
+            "0000000500000008"
+            "0b5040a18c1b5cabcbc85b047402ec6294a30dd8da8fc3da"
+            "e13b9f0875f09361dc77fcc4481ea463c073716249719193"
+            "614b835b4694c059f12d3aedd34f3db93f3580fb88743b8b"
+            "3d0648c0537b7a50e433d7ea9d6672fffc5f42770feab4f9"
+            "8eb3f3b23fd2061e4d0b38f832860ae76673ad1a1a52a900"
+            "5dcf1bfb56fe16ff723627612f9a48f790f3c47a67f870b8"
+            "1e919d99919c8db48168838cece0abfb683da48b9209868b"
+            "e8ec10c63d8bf80d36498dfc205dc45d0dd870572d6d8f1d"
+            "90177cf5137b8bbf7bcb67a46f86f26cfa5a44cbcaa4e18d"
+            "a099a98b0b3f96d5ac8ac375d8da2a7c248004ba11d7ac77"
+            "5b9218359cddab4cf8ccc6d54cb7e1b35a36ddc9265c0870"
+            "63d2fc6742a7177876476a324b03295bfed99f2eaf1f3897"
+            "0583c1b2b616aad0f31cd7a4b1bb0a51e477e94a01bbb4d6"
+            "f8866e2528a159df3d6ce244d2b6518d1f0212285a3c2d4a"
+            "927054a1e1620b5b02aab0c8c10ed48ae518ea73cba81fcf"
+            "ff88bff461dac51e7ab4ca75f47a6259d24820b9995792d1"
+            "39f61ae2a8186ae4e3c9bfe0af2cc717f424f41aa67f03fa"
+            "edb0665115f2067a46843a4cbbd297d5e83bc1aafc18d1d0"
+            "3b3d894e8595a6526073f02ab0f08b99fd9eb208b59ff631"
+            "7e5545e6f9ad5f9c183abd043d5acd6eb2dd4da3f02dbc31"
+            "67b468720a4b8b92ddfe7960998bb7a0ecf2a26a37598299"
+            "413f7b2aecd39a30cec527b4d9710c4473639022451f50d0"
+            "1c0457125da0fa4429c07dad859c846cbbd93ab5b91b01bc"
+            "770b089cfede6f651e86dd7c15989c8b5321dea9ca608c71"
+            "fd862323072b827cee7a7e28e4e2b999647233c3456944bb"
+            "7aef9187c96b3f5b79fb98bc76c3574dd06f0e95685e5b3a"
+            "ef3a54c4155fe3ad817749629c30adbe897c4f4454c86c49"
+            "0000000a"
+            "e9ca10eaa811b22ae07fb195e3590a334ea64209942fbae3"
+            "38d19f152182c807d3c40b189d3fcbea942f44682439b191"
+            "332d33ae0b761a2a8f984b56b2ac2fd4ab08223a69ed1f77"
+            "19c7aa7e9eee96504b0e60c6bb5c942d695f0493eb25f80a"
+            "5871cffd131d0e04ffe5065bc7875e82d34b40b69dd9f3c1"
+        );
+        assert_eq!(
+            signature.to_vec(),
+            lms.sign_with_index(5, msg, &signature[8..32]).unwrap()
+        );
+        assert!(lms.verify(&public_key, msg, &signature));
+        assert!(!lms.verify(&public_key, b"Test message for SHA256-192", &signature));
+    }
+}
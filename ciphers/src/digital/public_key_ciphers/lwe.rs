@@ -0,0 +1,257 @@
+use crate::Cipher;
+use rand::{thread_rng, Rng};
+use utils::{byte_formatting::ByteFormat, errors::GeneralError};
+
+// Regev's public key encryption from the learning with errors problem. The public key is a random matrix A together
+// with b = As + e where s is the secret and e is a vector of small errors. Without the errors s could be found by
+// Gaussian elimination, with them the problem is believed to be hard even for quantum computers. Each bit is
+// encrypted as the sum of a random subset of the rows, with q/2 added for a 1.
+// https://doi.org/10.1145/1568318.1568324
+pub struct Lwe {
+    pub input_format: ByteFormat,
+    pub output_format: ByteFormat,
+
+    /// Length of the secret
+    pub n: usize,
+    /// Number of samples in the public key
+    pub m: usize,
+    pub q: u64,
+    /// Errors are chosen uniformly from -error_bound to error_bound
+    pub error_bound: u64,
+
+    pub secret: Vec<u64>,
+    pub a: Vec<Vec<u64>>,
+    pub b: Vec<u64>,
+}
+
+impl Default for Lwe {
+    fn default() -> Self {
+        Self {
+            input_format: ByteFormat::Utf8,
+            output_format: ByteFormat::Hex,
+
+            n: 64,
+            m: 256,
+            q: 4093,
+            error_bound: 2,
+
+            secret: Vec::new(),
+            a: Vec::new(),
+            b: Vec::new(),
+        }
+    }
+}
+
+impl Lwe {
+    /// Changing the parameters discards the key. Requiring q > 4 m B, where B bounds the errors, guarantees that the
+    /// errors accumulated in a ciphertext can never cause decryption to fail.
+    pub fn set_parameters(
+        &mut self,
+        n: usize,
+        m: usize,
+        q: u64,
+        error_bound: u64,
+    ) -> Result<(), GeneralError> {
+        if n == 0 || m == 0 {
+            return Err(GeneralError::key("n and m must be positive"));
+        }
+        if q >= 1 << 31 {
+            return Err(GeneralError::key("q must be less than 2^31"));
+        }
+        if q <= 4 * m as u64 * error_bound || q < 4 {
+            return Err(GeneralError::key("q must be greater than 4 m B"));
+        }
+        self.n = n;
+        self.m = m;
+        self.q = q;
+        self.error_bound = error_bound;
+        self.secret.clear();
+        self.a.clear();
+        self.b.clear();
+        Ok(())
+    }
+
+    /// Set the secret and the public matrix, the errors are random
+    pub fn set_key<R: Rng>(
+        &mut self,
+        secret: Vec<u64>,
+        a: Vec<Vec<u64>>,
+        rng: &mut R,
+    ) -> Result<(), GeneralError> {
+        if secret.len() != self.n || a.len() != self.m || a.iter().any(|row| row.len() != self.n) {
+            return Err(GeneralError::key("secret or matrix has the wrong size"));
+        }
+        let q = self.q;
+        if secret.iter().chain(a.iter().flatten()).any(|x| *x >= q) {
+            return Err(GeneralError::key("entries must be less than q"));
+        }
+        self.b = a
+            .iter()
+            .map(|row| {
+                let e = rng.gen_range(0..=2 * self.error_bound);
+                (self.dot(row, &secret) + e + q - self.error_bound) % q
+            })
+            .collect();
+        self.secret = secret;
+        self.a = a;
+        Ok(())
+    }
+
+    pub fn generate_key<R: Rng>(&mut self, rng: &mut R) {
+        let q = self.q;
+        let secret = (0..self.n).map(|_| rng.gen_range(0..q)).collect();
+        let a = (0..self.m)
+            .map(|_| (0..self.n).map(|_| rng.gen_range(0..q)).collect())
+            .collect();
+        self.set_key(secret, a, rng)
+            .expect("generated key always has the right size");
+    }
+
+    fn dot(&self, x: &[u64], y: &[u64]) -> u64 {
+        x.iter()
+            .zip(y)
+            .fold(0, |acc, (a, b)| (acc + a * b) % self.q)
+    }
+
+    /// Number of bytes used for each integer modulo q
+    fn element_len(&self) -> usize {
+        (64 - (self.q - 1).leading_zeros() as usize).div_ceil(8)
+    }
+
+    /// Sum a random subset of the samples, giving u = sum(a_i) and v = sum(b_i) + bit * floor(q/2)
+    pub fn encrypt_bit<R: Rng>(
+        &self,
+        bit: bool,
+        rng: &mut R,
+    ) -> Result<(Vec<u64>, u64), GeneralError> {
+        if self.b.is_empty() {
+            return Err(GeneralError::state("key has not been set"));
+        }
+        let q = self.q;
+        let mut u = vec![0; self.n];
+        let mut v = if bit { q / 2 } else { 0 };
+        for (row, b) in self.a.iter().zip(&self.b) {
+            if rng.gen() {
+                for (x, a) in u.iter_mut().zip(row) {
+                    *x = (*x + a) % q;
+                }
+                v = (v + b) % q;
+            }
+        }
+        Ok((u, v))
+    }
+
+    /// v - <u, s> is the sum of the chosen errors, plus about q/2 for a 1
+    pub fn decrypt_bit(&self, u: &[u64], v: u64) -> Result<bool, GeneralError> {
+        if self.secret.is_empty() {
+            return Err(GeneralError::state("private key has not been set"));
+        }
+        let q = self.q;
+        let d = (v + q - self.dot(u, &self.secret)) % q;
+        Ok(d > q / 4 && d < q - q / 4)
+    }
+
+    /// Bits are encrypted from most to least significant, each one as n+1 integers
+    pub fn encrypt_bytes(&self, bytes: &[u8]) -> Result<Vec<u8>, GeneralError> {
+        let mut rng = thread_rng();
+        let len = self.element_len();
+        let mut out = Vec::with_capacity(bytes.len() * 8 * (self.n + 1) * len);
+        for byte in bytes {
+            for i in (0..8).rev() {
+                let (u, v) = self.encrypt_bit((byte >> i) & 1 == 1, &mut rng)?;
+                for x in u.iter().chain(std::iter::once(&v)) {
+                    out.extend_from_slice(&x.to_be_bytes()[8 - len..]);
+                }
+            }
+        }
+        Ok(out)
+    }
+
+    pub fn decrypt_bytes(&self, bytes: &[u8]) -> Result<Vec<u8>, GeneralError> {
+        let len = self.element_len();
+        let block = (self.n + 1) * len;
+        if !bytes.len().is_multiple_of(8 * block) {
+            return Err(GeneralError::input(format!(
+                "ciphertext must be in chunks of {} bytes",
+                8 * block
+            )));
+        }
+        let mut out = Vec::with_capacity(bytes.len() / (8 * block));
+        for chunk in bytes.chunks_exact(8 * block) {
+            let mut byte = 0;
+            for bit in chunk.chunks_exact(block) {
+                let mut elements = bit
+                    .chunks_exact(len)
+                    .map(|x| x.iter().fold(0, |acc, b| (acc << 8) | *b as u64) % self.q);
+                let u: Vec<u64> = elements.by_ref().take(self.n).collect();
+                let v = elements.next().expect("block holds n+1 elements");
+                byte <<= 1;
+                if self.decrypt_bit(&u, v)? {
+                    byte |= 1;
+                }
+            }
+            out.push(byte);
+        }
+        Ok(out)
+    }
+}
+
+impl Cipher for Lwe {
+    fn encrypt(&self, text: &str) -> Result<String, GeneralError> {
+        let bytes = self
+            .input_format
+            .text_to_bytes(text)
+            .map_err(|_| GeneralError::input("byte format error"))?;
+        Ok(self
+            .output_format
+            .byte_slice_to_text(self.encrypt_bytes(&bytes)?))
+    }
+
+    fn decrypt(&self, text: &str) -> Result<String, GeneralError> {
+        let bytes = self
+            .input_format
+            .text_to_bytes(text)
+            .map_err(|_| GeneralError::input("byte format error"))?;
+        Ok(self
+            .output_format
+            .byte_slice_to_text(self.decrypt_bytes(&bytes)?))
+    }
+}
+
+#[cfg(test)]
+mod lwe_tests {
+
+    use super::*;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn public_key_errors() {
+        let mut cipher = Lwe::default();
+        cipher.generate_key(&mut StdRng::seed_from_u64(12));
+        for (row, b) in cipher.a.iter().zip(&cipher.b) {
+            let e = (b + cipher.q - cipher.dot(row, &cipher.secret)) % cipher.q;
+            assert!(e <= cipher.error_bound || e >= cipher.q - cipher.error_bound);
+        }
+    }
+
+    #[test]
+    fn encrypt_decrypt() {
+        let mut cipher = Lwe::default();
+        cipher.generate_key(&mut StdRng::seed_from_u64(13));
+        let ctext = cipher.encrypt("attack at dawn").unwrap();
+        assert_ne!(ctext, cipher.encrypt("attack at dawn").unwrap());
+        cipher.output_format = ByteFormat::Utf8;
+        cipher.input_format = ByteFormat::Hex;
+        assert_eq!("attack at dawn", cipher.decrypt(&ctext).unwrap());
+    }
+
+    #[test]
+    fn parameters() {
+        let mut cipher = Lwe::default();
+        assert!(cipher.set_parameters(8, 24, 96, 1).is_err());
+        assert!(cipher.set_parameters(8, 24, 97, 1).is_ok());
+        assert!(cipher
+            .encrypt_bit(true, &mut StdRng::seed_from_u64(0))
+            .is_err());
+    }
+}
@@ -0,0 +1,300 @@
+use super::rfc6979::int2octets;
+use crate::Cipher;
+use num::{integer::gcd, BigUint, One, Zero};
+use rand::{seq::SliceRandom, Rng};
+use utils::{
+    byte_formatting::ByteFormat,
+    errors::GeneralError,
+    math_functions::mul_inv,
+    padding::{bit_padding, strip_bit_padding},
+    primality::random_biguint_below,
+};
+
+// The Merkle-Hellman knapsack cryptosystem. The private key is a superincreasing sequence, for which subset sums are
+// easy to solve greedily, disguised by modular multiplication and a secret permutation. Shamir broke the original system in 1982 and the
+// lattice attack in cryptanalysis::knapsack breaks it, and most later knapsack systems, in general.
+// https://doi.org/10.1109/TIT.1978.1055927
+pub struct MerkleHellman {
+    pub input_format: ByteFormat,
+    pub output_format: ByteFormat,
+
+    pub private_sequence: Vec<BigUint>,
+    pub modulus: BigUint,
+    pub multiplier: BigUint,
+    /// Element i of the public key comes from element permutation[i] of the private sequence
+    pub permutation: Vec<usize>,
+    pub public_key: Vec<BigUint>,
+}
+
+impl Default for MerkleHellman {
+    fn default() -> Self {
+        Self {
+            input_format: ByteFormat::Utf8,
+            output_format: ByteFormat::Hex,
+
+            private_sequence: Vec::new(),
+            modulus: BigUint::zero(),
+            multiplier: BigUint::zero(),
+            permutation: Vec::new(),
+            public_key: Vec::new(),
+        }
+    }
+}
+
+impl MerkleHellman {
+    /// The sequence must be superincreasing with a length that is a multiple of 8, the modulus must exceed its sum,
+    /// the multiplier must be coprime to the modulus and the permutation must contain each index of the sequence once.
+    pub fn set_key(
+        &mut self,
+        private_sequence: Vec<BigUint>,
+        modulus: BigUint,
+        multiplier: BigUint,
+        permutation: Vec<usize>,
+    ) -> Result<(), GeneralError> {
+        if private_sequence.is_empty() || !private_sequence.len().is_multiple_of(8) {
+            return Err(GeneralError::key(
+                "length of the sequence must be a positive multiple of 8",
+            ));
+        }
+        let mut sum = BigUint::zero();
+        for w in private_sequence.iter() {
+            if *w <= sum {
+                return Err(GeneralError::key("sequence is not superincreasing"));
+            }
+            sum += w;
+        }
+        if modulus <= sum {
+            return Err(GeneralError::key(
+                "modulus must be greater than the sum of the sequence",
+            ));
+        }
+        if multiplier.is_zero() || !gcd(multiplier.clone(), modulus.clone()).is_one() {
+            return Err(GeneralError::key(
+                "multiplier must be coprime to the modulus",
+            ));
+        }
+        let mut sorted = permutation.clone();
+        sorted.sort_unstable();
+        if !sorted.into_iter().eq(0..private_sequence.len()) {
+            return Err(GeneralError::key(
+                "permutation must contain each index of the sequence exactly once",
+            ));
+        }
+        self.public_key = permutation
+            .iter()
+            .map(|j| (&private_sequence[*j] * &multiplier) % &modulus)
+            .collect();
+        self.private_sequence = private_sequence;
+        self.modulus = modulus;
+        self.multiplier = multiplier;
+        self.permutation = permutation;
+        Ok(())
+    }
+
+    /// Random key of length n. Each element is the sum of the previous ones plus a random n bit number, so the public
+    /// key has a density of about 1/2, as Merkle and Hellman suggested. The permutation is chosen uniformly.
+    pub fn generate_key<R: Rng>(&mut self, n: usize, rng: &mut R) -> Result<(), GeneralError> {
+        let step = BigUint::one() << n;
+        let mut sum = BigUint::zero();
+        let mut sequence = Vec::with_capacity(n);
+        for _ in 0..n {
+            let w = &sum + random_biguint_below(&step, rng) + 1_u32;
+            sum += &w;
+            sequence.push(w);
+        }
+        let modulus = &sum + random_biguint_below(&sum, rng) + 1_u32;
+        let multiplier = loop {
+            let r = random_biguint_below(&modulus, rng);
+            if !r.is_zero() && gcd(r.clone(), modulus.clone()).is_one() {
+                break r;
+            }
+        };
+        let mut permutation: Vec<usize> = (0..n).collect();
+        permutation.shuffle(rng);
+        self.set_key(sequence, modulus, multiplier, permutation)
+    }
+
+    /// Number of bytes of plaintext encrypted together
+    pub fn block_bytes(&self) -> usize {
+        self.public_key.len() / 8
+    }
+
+    /// Number of bytes in the ciphertext of a block, enough to hold the sum of the entire public key
+    pub fn ciphertext_len(&self) -> usize {
+        self.public_key.iter().sum::<BigUint>().bits().div_ceil(8) as usize
+    }
+
+    /// Sum of the elements of the public key selected by the bits of the block, most significant first
+    pub fn encrypt_block(&self, block: &[u8]) -> Result<BigUint, GeneralError> {
+        if self.public_key.is_empty() {
+            return Err(GeneralError::state("key has not been set"));
+        }
+        if block.len() != self.block_bytes() {
+            return Err(GeneralError::input("block has the wrong length"));
+        }
+        let mut c = BigUint::zero();
+        for (i, b) in self.public_key.iter().enumerate() {
+            if (block[i / 8] >> (7 - i % 8)) & 1 == 1 {
+                c += b;
+            }
+        }
+        Ok(c)
+    }
+
+    /// Undo the multiplier, solve the easy knapsack greedily from the largest element down and then undo the
+    /// permutation
+    pub fn decrypt_block(&self, c: &BigUint) -> Result<Vec<u8>, GeneralError> {
+        if self.private_sequence.is_empty() {
+            return Err(GeneralError::state("private key has not been set"));
+        }
+        let r_inv = mul_inv(&self.multiplier, &self.modulus)
+            .and_then(|x| x.to_biguint())
+            .expect("multiplier is coprime to the modulus");
+        let mut position = vec![0; self.permutation.len()];
+        for (i, j) in self.permutation.iter().enumerate() {
+            position[*j] = i;
+        }
+        let mut s = (c * r_inv) % &self.modulus;
+        let mut out = vec![0; self.block_bytes()];
+        for (j, w) in self.private_sequence.iter().enumerate().rev() {
+            if s >= *w {
+                s -= w;
+                let i = position[j];
+                out[i / 8] |= 1 << (7 - i % 8);
+            }
+        }
+        if !s.is_zero() {
+            return Err(GeneralError::input("ciphertext is not a valid subset sum"));
+        }
+        Ok(out)
+    }
+
+    pub fn encrypt_bytes(&self, bytes: &[u8]) -> Result<Vec<u8>, GeneralError> {
+        if self.public_key.is_empty() {
+            return Err(GeneralError::state("key has not been set"));
+        }
+        let mut bytes = bytes.to_vec();
+        bit_padding(&mut bytes, self.block_bytes() as u32)
+            .map_err(|e| GeneralError::input(e.to_string()))?;
+        let mut out = Vec::new();
+        for block in bytes.chunks_exact(self.block_bytes()) {
+            out.extend(int2octets(
+                &self.encrypt_block(block)?,
+                self.ciphertext_len(),
+            ));
+        }
+        Ok(out)
+    }
+
+    pub fn decrypt_bytes(&self, bytes: &[u8]) -> Result<Vec<u8>, GeneralError> {
+        let len = self.ciphertext_len();
+        if len == 0 || !bytes.len().is_multiple_of(len) {
+            return Err(GeneralError::input(format!(
+                "ciphertext must be in chunks of {len} bytes"
+            )));
+        }
+        let mut out = Vec::new();
+        for block in bytes.chunks_exact(len) {
+            out.extend(self.decrypt_block(&BigUint::from_bytes_be(block))?);
+        }
+        strip_bit_padding(&mut out).map_err(|e| GeneralError::input(e.to_string()))?;
+        Ok(out)
+    }
+}
+
+impl Cipher for MerkleHellman {
+    fn encrypt(&self, text: &str) -> Result<String, GeneralError> {
+        let bytes = self
+            .input_format
+            .text_to_bytes(text)
+            .map_err(|_| GeneralError::input("byte format error"))?;
+        Ok(self
+            .output_format
+            .byte_slice_to_text(self.encrypt_bytes(&bytes)?))
+    }
+
+    fn decrypt(&self, text: &str) -> Result<String, GeneralError> {
+        let bytes = self
+            .input_format
+            .text_to_bytes(text)
+            .map_err(|_| GeneralError::input("byte format error"))?;
+        Ok(self
+            .output_format
+            .byte_slice_to_text(self.decrypt_bytes(&bytes)?))
+    }
+}
+
+#[cfg(test)]
+mod merkle_hellman_tests {
+
+    use super::*;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    fn sequence() -> Vec<BigUint> {
+        [12_u32, 17, 33, 74, 157, 316, 635, 1272]
+            .map(BigUint::from)
+            .to_vec()
+    }
+
+    #[test]
+    fn identity_permutation() {
+        let mut cipher = MerkleHellman::default();
+        cipher
+            .set_key(
+                sequence(),
+                BigUint::from(2731_u32),
+                BigUint::from(1394_u32),
+                (0..8).collect(),
+            )
+            .unwrap();
+        assert_eq!(
+            [342_u32, 1850, 2306, 2109, 378, 813, 346, 749].map(BigUint::from),
+            cipher.public_key[..]
+        );
+        let c = cipher.encrypt_block(&[0b10010101]).unwrap();
+        assert_eq!(BigUint::from(342_u32 + 2109 + 813 + 749), c);
+        assert_eq!(vec![0b10010101], cipher.decrypt_block(&c).unwrap());
+    }
+
+    #[test]
+    fn permuted() {
+        let mut cipher = MerkleHellman::default();
+        cipher
+            .set_key(
+                sequence(),
+                BigUint::from(2731_u32),
+                BigUint::from(1394_u32),
+                vec![2, 5, 0, 1, 4, 3, 7, 6],
+            )
+            .unwrap();
+        assert_eq!(
+            [2306_u32, 813, 342, 1850, 378, 2109, 749, 346].map(BigUint::from),
+            cipher.public_key[..]
+        );
+        let c = cipher.encrypt_block(&[0b10010101]).unwrap();
+        assert_eq!(BigUint::from(2306_u32 + 1850 + 2109 + 346), c);
+        assert_eq!(vec![0b10010101], cipher.decrypt_block(&c).unwrap());
+
+        // Repeated or missing indices are rejected
+        assert!(cipher
+            .set_key(
+                sequence(),
+                BigUint::from(2731_u32),
+                BigUint::from(1394_u32),
+                vec![2, 5, 0, 1, 4, 3, 7, 7],
+            )
+            .is_err());
+    }
+
+    #[test]
+    fn encrypt_decrypt() {
+        let mut cipher = MerkleHellman::default();
+        cipher
+            .generate_key(64, &mut StdRng::seed_from_u64(7))
+            .unwrap();
+        let ctext = cipher.encrypt("attack at dawn").unwrap();
+        cipher.output_format = ByteFormat::Utf8;
+        cipher.input_format = ByteFormat::Hex;
+        assert_eq!("attack at dawn", cipher.decrypt(&ctext).unwrap());
+    }
+}
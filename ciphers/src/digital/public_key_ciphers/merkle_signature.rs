@@ -0,0 +1,325 @@
+use super::lamport::Lamport;
+use crate::Cipher;
+use hashers::{sha::Sha256, traits::StatefulHasher};
+use rand::Rng;
+use std::cell::Cell;
+use utils::{byte_formatting::ByteFormat, errors::GeneralError};
+
+// The Merkle signature scheme. A binary hash tree is built over the hashes of many one-time public keys and its root
+// becomes a single public key. Each signature carries the one-time signature, the rest of that one-time public key
+// and the authentication path, the sibling of every node on the way from the leaf to the root, so the verifier can
+// recompute the root. The signer has to keep track of which leaves have been used.
+// https://doi.org/10.1007/0-387-34805-0_21
+
+/// A complete binary tree where each node is the hash of its two children
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct MerkleTree {
+    /// levels[0] holds the leaves and the last level holds only the root
+    pub levels: Vec<Vec<Vec<u8>>>,
+}
+
+impl MerkleTree {
+    pub fn new<H: StatefulHasher>(
+        hasher: fn() -> H,
+        leaves: Vec<Vec<u8>>,
+    ) -> Result<Self, GeneralError> {
+        if !leaves.len().is_power_of_two() {
+            return Err(GeneralError::input(
+                "number of leaves must be a power of two",
+            ));
+        }
+        let mut levels = vec![leaves];
+        while levels.last().unwrap().len() > 1 {
+            let next = levels
+                .last()
+                .unwrap()
+                .chunks_exact(2)
+                .map(|pair| {
+                    let mut h = hasher();
+                    h.update(&pair[0]);
+                    h.update(&pair[1]);
+                    h.finalize()
+                })
+                .collect();
+            levels.push(next);
+        }
+        Ok(Self { levels })
+    }
+
+    pub fn height(&self) -> usize {
+        self.levels.len().saturating_sub(1)
+    }
+
+    pub fn root(&self) -> &[u8] {
+        self.levels.last().map(|l| l[0].as_slice()).unwrap_or(&[])
+    }
+
+    /// The sibling of each node on the path from a leaf to the root, starting at the bottom
+    pub fn auth_path(&self, index: usize) -> Vec<Vec<u8>> {
+        (0..self.height())
+            .map(|level| self.levels[level][(index >> level) ^ 1].clone())
+            .collect()
+    }
+
+    /// Climb from a leaf to the root using an authentication path
+    pub fn root_from_path<H: StatefulHasher>(
+        hasher: fn() -> H,
+        leaf: &[u8],
+        index: usize,
+        path: &[Vec<u8>],
+    ) -> Vec<u8> {
+        let mut node = leaf.to_vec();
+        for (level, sibling) in path.iter().enumerate() {
+            let mut h = hasher();
+            if (index >> level) & 1 == 0 {
+                h.update(&node);
+                h.update(sibling);
+            } else {
+                h.update(sibling);
+                h.update(&node);
+            }
+            node = h.finalize();
+        }
+        node
+    }
+
+    /// Draw the tree as text with the root at the top, showing the first hex_len hex digits of each node. When a leaf
+    /// is given the nodes on its path to the root are shown in parentheses and its authentication path in brackets.
+    pub fn diagram(&self, index: Option<usize>, hex_len: usize) -> String {
+        let height = self.height();
+        let label_len = hex_len + 2;
+        let cell = label_len + 1;
+        let width = cell << height;
+        let center = |level: usize, k: usize| (k << level) * cell + ((cell << level) - 1) / 2;
+
+        let mut rows = Vec::new();
+        for level in (0..=height).rev() {
+            let mut row = vec![' '; width];
+            for (k, node) in self.levels[level].iter().enumerate() {
+                let (open, close) = match index.map(|i| i >> level) {
+                    Some(i) if i == k => ('(', ')'),
+                    Some(i) if i ^ 1 == k => ('[', ']'),
+                    _ => (' ', ' '),
+                };
+                let hex: String = node
+                    .iter()
+                    .map(|b| format!("{b:02x}"))
+                    .collect::<String>()
+                    .chars()
+                    .take(hex_len)
+                    .collect();
+                let label = format!("{open}{hex:<hex_len$}{close}");
+                let start = center(level, k) - (label_len - 1) / 2;
+                for (i, c) in label.chars().enumerate() {
+                    row[start + i] = c;
+                }
+            }
+            rows.push(row);
+
+            if level > 0 {
+                let mut row = vec![' '; width];
+                for k in 0..self.levels[level].len() {
+                    let (left, right) = (center(level - 1, 2 * k), center(level - 1, 2 * k + 1));
+                    row[left..=right].fill('─');
+                    row[left] = '┌';
+                    row[right] = '┐';
+                    row[center(level, k)] = '┴';
+                }
+                rows.push(row);
+            }
+        }
+        rows.into_iter()
+            .map(|row| row.into_iter().collect::<String>().trim_end().to_string())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+pub struct MerkleSignature<H: StatefulHasher> {
+    pub input_format: ByteFormat,
+    pub output_format: ByteFormat,
+
+    pub hasher: fn() -> H,
+    pub height: usize,
+    /// Secret seed that every one-time key is derived from
+    pub seed: Vec<u8>,
+    pub tree: MerkleTree,
+    next_index: Cell<usize>,
+}
+
+impl Default for MerkleSignature<Sha256> {
+    fn default() -> Self {
+        Self::new(Sha256::init, 4)
+    }
+}
+
+impl<H: StatefulHasher> MerkleSignature<H> {
+    pub fn new(hasher: fn() -> H, height: usize) -> Self {
+        Self {
+            input_format: ByteFormat::Utf8,
+            output_format: ByteFormat::Hex,
+            hasher,
+            height,
+            seed: Vec::new(),
+            tree: MerkleTree::default(),
+            next_index: Cell::new(0),
+        }
+    }
+
+    /// The Lamport key for one leaf of the tree
+    pub fn one_time_key(&self, index: usize) -> Lamport<H> {
+        let mut key = Lamport::new(self.hasher);
+        key.derive_key(&self.seed, index as u32);
+        key
+    }
+
+    /// Derive every one-time key from the seed and build the tree over them
+    pub fn set_seed(&mut self, seed: Vec<u8>) -> Result<(), GeneralError> {
+        if self.height > 16 {
+            return Err(GeneralError::key("height must be at most 16"));
+        }
+        self.seed = seed;
+        let leaves = (0..1 << self.height)
+            .map(|i| self.one_time_key(i).public_key_hash())
+            .collect();
+        self.tree = MerkleTree::new(self.hasher, leaves)?;
+        self.next_index.set(0);
+        Ok(())
+    }
+
+    pub fn generate_key<R: Rng>(&mut self, rng: &mut R) -> Result<(), GeneralError> {
+        let seed = (0..32).map(|_| rng.gen()).collect();
+        self.set_seed(seed)
+    }
+
+    pub fn public_key(&self) -> &[u8] {
+        self.tree.root()
+    }
+
+    pub fn next_index(&self) -> usize {
+        self.next_index.get()
+    }
+
+    /// The signature is the 4-byte leaf index, the Lamport signature, the unrevealed half of the Lamport public key
+    /// and the authentication path. Signing with the same index twice is as dangerous as reusing any one-time key.
+    pub fn sign_with_index(&self, index: usize, msg: &[u8]) -> Result<Vec<u8>, GeneralError> {
+        if self.tree.levels.is_empty() {
+            return Err(GeneralError::state("key has not been set"));
+        }
+        if index >> self.tree.height() != 0 {
+            return Err(GeneralError::input("index is outside of the tree"));
+        }
+        let key = self.one_time_key(index);
+        let mut out = (index as u32).to_be_bytes().to_vec();
+        out.extend(key.sign_unchecked(msg)?);
+        out.extend(key.complement(msg));
+        out.extend(self.tree.auth_path(index).concat());
+        Ok(out)
+    }
+
+    /// Sign with the next unused leaf
+    pub fn sign(&self, msg: &[u8]) -> Result<Vec<u8>, GeneralError> {
+        let index = self.next_index.get();
+        if index >> self.tree.height() != 0 {
+            return Err(GeneralError::state("every one-time key has been used"));
+        }
+        let signature = self.sign_with_index(index, msg)?;
+        self.next_index.set(index + 1);
+        Ok(signature)
+    }
+
+    pub fn verify(&self, msg: &[u8], signature: &[u8]) -> bool {
+        let lamport = Lamport::new(self.hasher);
+        let n = lamport.hash_len();
+        let sig_len = lamport.signature_len();
+        let height = self.tree.height();
+        if signature.len() != 4 + 2 * sig_len + height * n {
+            return false;
+        }
+        let index = u32::from_be_bytes(signature[..4].try_into().unwrap()) as usize;
+        if index >> height != 0 {
+            return false;
+        }
+        let (one_time, rest) = signature[4..].split_at(sig_len);
+        let (complement, path) = rest.split_at(sig_len);
+        let Some(leaf) = lamport.public_key_hash_from_signature(msg, one_time, complement) else {
+            return false;
+        };
+        let path: Vec<Vec<u8>> = path.chunks_exact(n).map(|x| x.to_vec()).collect();
+        MerkleTree::root_from_path(self.hasher, &leaf, index, &path) == self.public_key()
+    }
+}
+
+impl<H: StatefulHasher> Cipher for MerkleSignature<H> {
+    fn encrypt(&self, text: &str) -> Result<String, GeneralError> {
+        let bytes = self
+            .input_format
+            .text_to_bytes(text)
+            .map_err(|_| GeneralError::input("byte format error"))?;
+        Ok(self.output_format.byte_slice_to_text(self.sign(&bytes)?))
+    }
+
+    fn decrypt(&self, _text: &str) -> Result<String, GeneralError> {
+        Err(GeneralError::general(
+            "the Merkle signature scheme creates signatures, it does not decrypt",
+        ))
+    }
+}
+
+#[cfg(test)]
+mod merkle_signature_tests {
+
+    use super::*;
+    use hashers::blake::Blake2s;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn auth_paths() {
+        let leaves: Vec<Vec<u8>> = (0..8_u8).map(|i| Sha256::init().hash(&[i])).collect();
+        let tree = MerkleTree::new(Sha256::init, leaves.clone()).unwrap();
+        assert_eq!(3, tree.height());
+        for (i, leaf) in leaves.iter().enumerate() {
+            let path = tree.auth_path(i);
+            assert_eq!(
+                tree.root(),
+                MerkleTree::root_from_path(Sha256::init, leaf, i, &path)
+            );
+            assert_ne!(
+                tree.root(),
+                MerkleTree::root_from_path(Sha256::init, leaf, i ^ 1, &path)
+            );
+        }
+        assert!(MerkleTree::new(Sha256::init, vec![vec![0]; 6]).is_err());
+    }
+
+    #[test]
+    fn diagram() {
+        let leaves = (0..4_u8).map(|i| vec![i * 0x11]).collect();
+        let tree = MerkleTree::new(Sha256::init, leaves).unwrap();
+        let diagram = tree.diagram(Some(2), 2);
+        let lines: Vec<&str> = diagram.lines().collect();
+        assert_eq!(5, lines.len());
+        assert!(lines[0].trim_start().starts_with('('));
+        assert!(lines[2].contains('[') && lines[2].contains('('));
+        assert_eq!("  ┌─┴──┐    ┌─┴──┐", lines[3]);
+        assert_eq!("  00   11  (22) [33]", lines[4]);
+    }
+
+    #[test]
+    fn sign_verify() {
+        let mut signer = MerkleSignature::new(|| Blake2s::init_hash_var(16), 3);
+        signer.generate_key(&mut StdRng::seed_from_u64(20)).unwrap();
+        for i in 0..8 {
+            let signature = signer.sign(b"attack at dawn").unwrap();
+            assert_eq!(i, u32::from_be_bytes(signature[..4].try_into().unwrap()));
+            assert!(signer.verify(b"attack at dawn", &signature));
+            assert!(!signer.verify(b"attack at dusk", &signature));
+        }
+        assert!(signer.sign(b"attack at dawn").is_err());
+
+        // Claiming a different leaf breaks the path
+        let mut signature = signer.sign_with_index(5, b"attack at dawn").unwrap();
+        signature[3] = 4;
+        assert!(!signer.verify(b"attack at dawn", &signature));
+    }
+}
@@ -0,0 +1,684 @@
+use crate::Cipher;
+use hashers::{sha::Keccack, traits::StatefulHasher};
+use rand::Rng;
+use utils::{byte_formatting::ByteFormat, errors::GeneralError};
+
+// ML-KEM, the lattice based key encapsulation mechanism standardized from Kyber. Everything works with polynomials of
+// degree less than 256 over Z_3329 and multiplication is done with the number theoretic transform. This follows the
+// algorithms of FIPS 203 step by step and is not constant time.
+// https://doi.org/10.6028/NIST.FIPS.203
+
+const N: usize = 256;
+const Q: u32 = 3329;
+
+type Poly = [u32; N];
+
+const fn bit_rev7(x: usize) -> usize {
+    let mut out = 0;
+    let mut i = 0;
+    while i < 7 {
+        out |= ((x >> i) & 1) << (6 - i);
+        i += 1;
+    }
+    out
+}
+
+const fn pow17(mut e: usize) -> u32 {
+    let mut out = 1;
+    let mut base = 17;
+    while e > 0 {
+        if e & 1 == 1 {
+            out = out * base % Q;
+        }
+        base = base * base % Q;
+        e >>= 1;
+    }
+    out
+}
+
+// 17^BitRev7(i), 17 being a primitive 256th root of unity modulo 3329
+const ZETAS: [u32; 128] = {
+    let mut out = [0; 128];
+    let mut i = 0;
+    while i < 128 {
+        out[i] = pow17(bit_rev7(i));
+        i += 1;
+    }
+    out
+};
+
+// 17^(2 BitRev7(i) + 1), used to multiply in the transformed domain
+const GAMMAS: [u32; 128] = {
+    let mut out = [0; 128];
+    let mut i = 0;
+    while i < 128 {
+        out[i] = pow17(2 * bit_rev7(i) + 1);
+        i += 1;
+    }
+    out
+};
+
+fn add(a: &Poly, b: &Poly) -> Poly {
+    std::array::from_fn(|i| (a[i] + b[i]) % Q)
+}
+
+fn sub(a: &Poly, b: &Poly) -> Poly {
+    std::array::from_fn(|i| (a[i] + Q - b[i]) % Q)
+}
+
+/// Algorithm 9, the number theoretic transform
+pub fn ntt(f: &Poly) -> Poly {
+    let mut f = *f;
+    let mut i = 1;
+    let mut len = 128;
+    while len >= 2 {
+        for start in (0..N).step_by(2 * len) {
+            let zeta = ZETAS[i];
+            i += 1;
+            for j in start..start + len {
+                let t = zeta * f[j + len] % Q;
+                f[j + len] = (f[j] + Q - t) % Q;
+                f[j] = (f[j] + t) % Q;
+            }
+        }
+        len /= 2;
+    }
+    f
+}
+
+/// Algorithm 10, the inverse number theoretic transform
+pub fn ntt_inverse(f: &Poly) -> Poly {
+    let mut f = *f;
+    let mut i = 127;
+    let mut len = 2;
+    while len <= 128 {
+        for start in (0..N).step_by(2 * len) {
+            let zeta = ZETAS[i];
+            i -= 1;
+            for j in start..start + len {
+                let t = f[j];
+                f[j] = (t + f[j + len]) % Q;
+                f[j + len] = zeta * ((f[j + len] + Q - t) % Q) % Q;
+            }
+        }
+        len *= 2;
+    }
+    // 3303 is the inverse of 128
+    f.map(|x| x * 3303 % Q)
+}
+
+/// Algorithms 11 and 12, the product of two polynomials in the transformed domain, which is 128 products of linear
+/// polynomials modulo X^2 - gamma
+pub fn multiply_ntts(f: &Poly, g: &Poly) -> Poly {
+    let mut h = [0; N];
+    for i in 0..128 {
+        let (a0, a1, b0, b1) = (f[2 * i], f[2 * i + 1], g[2 * i], g[2 * i + 1]);
+        h[2 * i] = (a0 * b0 + a1 * b1 % Q * GAMMAS[i]) % Q;
+        h[2 * i + 1] = (a0 * b1 + a1 * b0) % Q;
+    }
+    h
+}
+
+/// Algorithm 5, pack d bit integers in little endian bit order
+fn byte_encode(f: &Poly, d: usize) -> Vec<u8> {
+    let mut out = vec![0; 32 * d];
+    for (i, x) in f.iter().enumerate() {
+        for j in 0..d {
+            let bit = i * d + j;
+            out[bit / 8] |= (((x >> j) & 1) as u8) << (bit % 8);
+        }
+    }
+    out
+}
+
+/// Algorithm 6, unpack d bit integers, which are reduced modulo q when d = 12
+fn byte_decode(bytes: &[u8], d: usize) -> Poly {
+    let m = if d == 12 { Q } else { 1 << d };
+    std::array::from_fn(|i| {
+        let mut x = 0;
+        for j in 0..d {
+            let bit = i * d + j;
+            x |= (((bytes[bit / 8] >> (bit % 8)) & 1) as u32) << j;
+        }
+        x % m
+    })
+}
+
+// Round 2^d x / q to the nearest integer, q is odd so there are no ties
+fn compress(f: &Poly, d: usize) -> Poly {
+    f.map(|x| (((x << d) + Q / 2) / Q) & ((1 << d) - 1))
+}
+
+fn decompress(f: &Poly, d: usize) -> Poly {
+    f.map(|y| (y * Q + (1 << (d - 1))) >> d)
+}
+
+/// Algorithm 7, rejection sampling of a polynomial in the transformed domain from SHAKE128
+fn sample_ntt(seed: &[u8; 34]) -> Poly {
+    // The output of SHAKE128 for a longer length extends the shorter one so it is safe to start over with more bytes
+    // in the very unlikely case that the first block is not enough
+    let mut len = 840;
+    loop {
+        let bytes = Keccack::shake_128(len as u64).hash(seed);
+        let mut out = [0; N];
+        let mut j = 0;
+        for c in bytes.chunks_exact(3) {
+            let d1 = c[0] as u32 + 256 * (c[1] as u32 % 16);
+            let d2 = c[1] as u32 / 16 + 16 * c[2] as u32;
+            for d in [d1, d2] {
+                if d < Q && j < N {
+                    out[j] = d;
+                    j += 1;
+                }
+            }
+            if j == N {
+                return out;
+            }
+        }
+        len *= 2;
+    }
+}
+
+/// Algorithm 8, a centered binomial distribution from 64 eta bytes
+fn sample_poly_cbd(bytes: &[u8], eta: usize) -> Poly {
+    let bit = |i: usize| ((bytes[i / 8] >> (i % 8)) & 1) as u32;
+    std::array::from_fn(|i| {
+        let x: u32 = (0..eta).map(|j| bit(2 * i * eta + j)).sum();
+        let y: u32 = (0..eta).map(|j| bit(2 * i * eta + eta + j)).sum();
+        (x + Q - y) % Q
+    })
+}
+
+fn prf(eta: usize, s: &[u8; 32], b: u8) -> Vec<u8> {
+    let mut h = Keccack::shake_256(64 * eta as u64);
+    h.update(s);
+    h.update(&[b]);
+    h.finalize()
+}
+
+fn hash_h(bytes: &[u8]) -> [u8; 32] {
+    Keccack::sha3_256().hash(bytes).try_into().unwrap()
+}
+
+fn hash_j(bytes: &[u8]) -> [u8; 32] {
+    Keccack::shake_256(32).hash(bytes).try_into().unwrap()
+}
+
+fn hash_g(bytes: &[u8]) -> ([u8; 32], [u8; 32]) {
+    let out = Keccack::sha3_512().hash(bytes);
+    (out[..32].try_into().unwrap(), out[32..].try_into().unwrap())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MlKemParameters {
+    MlKem512,
+    MlKem768,
+    MlKem1024,
+}
+
+impl MlKemParameters {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::MlKem512 => "ML-KEM-512",
+            Self::MlKem768 => "ML-KEM-768",
+            Self::MlKem1024 => "ML-KEM-1024",
+        }
+    }
+
+    /// Rank of the module, the number of polynomials in each vector
+    pub fn k(&self) -> usize {
+        match self {
+            Self::MlKem512 => 2,
+            Self::MlKem768 => 3,
+            Self::MlKem1024 => 4,
+        }
+    }
+
+    pub fn eta1(&self) -> usize {
+        match self {
+            Self::MlKem512 => 3,
+            _ => 2,
+        }
+    }
+
+    pub fn eta2(&self) -> usize {
+        2
+    }
+
+    pub fn du(&self) -> usize {
+        match self {
+            Self::MlKem1024 => 11,
+            _ => 10,
+        }
+    }
+
+    pub fn dv(&self) -> usize {
+        match self {
+            Self::MlKem1024 => 5,
+            _ => 4,
+        }
+    }
+
+    pub fn encapsulation_key_len(&self) -> usize {
+        384 * self.k() + 32
+    }
+
+    pub fn decapsulation_key_len(&self) -> usize {
+        768 * self.k() + 96
+    }
+
+    pub fn ciphertext_len(&self) -> usize {
+        32 * (self.du() * self.k() + self.dv())
+    }
+}
+
+pub struct MlKem {
+    pub input_format: ByteFormat,
+    pub output_format: ByteFormat,
+    pub parameters: MlKemParameters,
+    pub encapsulation_key: Vec<u8>,
+    pub decapsulation_key: Vec<u8>,
+}
+
+impl Default for MlKem {
+    fn default() -> Self {
+        Self {
+            input_format: ByteFormat::Hex,
+            output_format: ByteFormat::Hex,
+            parameters: MlKemParameters::MlKem768,
+            encapsulation_key: Vec::new(),
+            decapsulation_key: Vec::new(),
+        }
+    }
+}
+
+impl MlKem {
+    // The matrix A in the transformed domain, entry (i, j) is sampled from rho || j || i
+    fn matrix(&self, rho: &[u8]) -> Vec<Vec<Poly>> {
+        let k = self.parameters.k();
+        (0..k)
+            .map(|i| {
+                (0..k)
+                    .map(|j| {
+                        let mut seed = [0; 34];
+                        seed[..32].copy_from_slice(rho);
+                        seed[32] = j as u8;
+                        seed[33] = i as u8;
+                        sample_ntt(&seed)
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    fn dot(a: &[Poly], b: &[Poly]) -> Poly {
+        a.iter()
+            .zip(b)
+            .fold([0; N], |acc, (x, y)| add(&acc, &multiply_ntts(x, y)))
+    }
+
+    /// Algorithm 13, K-PKE.KeyGen
+    fn pke_key_gen(&self, d: &[u8; 32]) -> (Vec<u8>, Vec<u8>) {
+        let k = self.parameters.k();
+        let eta1 = self.parameters.eta1();
+        let mut seed = d.to_vec();
+        seed.push(k as u8);
+        let (rho, sigma) = hash_g(&seed);
+        let a = self.matrix(&rho);
+        let mut counter = 0;
+        let mut sample = || {
+            let out = ntt(&sample_poly_cbd(&prf(eta1, &sigma, counter), eta1));
+            counter += 1;
+            out
+        };
+        let s: Vec<Poly> = (0..k).map(|_| sample()).collect();
+        let e: Vec<Poly> = (0..k).map(|_| sample()).collect();
+        let mut ek = Vec::with_capacity(self.parameters.encapsulation_key_len());
+        let mut dk = Vec::with_capacity(384 * k);
+        for i in 0..k {
+            let t = add(&Self::dot(&a[i], &s), &e[i]);
+            ek.extend(byte_encode(&t, 12));
+            dk.extend(byte_encode(&s[i], 12));
+        }
+        ek.extend_from_slice(&rho);
+        (ek, dk)
+    }
+
+    /// Algorithm 14, K-PKE.Encrypt
+    fn pke_encrypt(&self, ek: &[u8], m: &[u8; 32], r: &[u8; 32]) -> Vec<u8> {
+        let k = self.parameters.k();
+        let (eta1, eta2) = (self.parameters.eta1(), self.parameters.eta2());
+        let (du, dv) = (self.parameters.du(), self.parameters.dv());
+        let t: Vec<Poly> = ek[..384 * k]
+            .chunks_exact(384)
+            .map(|c| byte_decode(c, 12))
+            .collect();
+        let a = self.matrix(&ek[384 * k..]);
+        let mut counter = 0;
+        let mut sample = |eta| {
+            let out = sample_poly_cbd(&prf(eta, r, counter), eta);
+            counter += 1;
+            out
+        };
+        let y: Vec<Poly> = (0..k).map(|_| ntt(&sample(eta1))).collect();
+        let e1: Vec<Poly> = (0..k).map(|_| sample(eta2)).collect();
+        let e2 = sample(eta2);
+
+        let mut c = Vec::with_capacity(self.parameters.ciphertext_len());
+        for i in 0..k {
+            // The transpose of A
+            let column: Vec<Poly> = (0..k).map(|j| a[j][i]).collect();
+            let u = add(&ntt_inverse(&Self::dot(&column, &y)), &e1[i]);
+            c.extend(byte_encode(&compress(&u, du), du));
+        }
+        let mu = decompress(&byte_decode(m, 1), 1);
+        let v = add(&add(&ntt_inverse(&Self::dot(&t, &y)), &e2), &mu);
+        c.extend(byte_encode(&compress(&v, dv), dv));
+        c
+    }
+
+    /// Algorithm 15, K-PKE.Decrypt
+    fn pke_decrypt(&self, dk: &[u8], c: &[u8]) -> [u8; 32] {
+        let k = self.parameters.k();
+        let (du, dv) = (self.parameters.du(), self.parameters.dv());
+        let u: Vec<Poly> = c[..32 * du * k]
+            .chunks_exact(32 * du)
+            .map(|x| ntt(&decompress(&byte_decode(x, du), du)))
+            .collect();
+        let v = decompress(&byte_decode(&c[32 * du * k..], dv), dv);
+        let s: Vec<Poly> = dk.chunks_exact(384).map(|x| byte_decode(x, 12)).collect();
+        let w = sub(&v, &ntt_inverse(&Self::dot(&s, &u)));
+        byte_encode(&compress(&w, 1), 1).try_into().unwrap()
+    }
+
+    /// Algorithm 16, deterministic key generation from the seeds d and z
+    pub fn generate_key_internal(&mut self, d: &[u8; 32], z: &[u8; 32]) {
+        let (ek, mut dk) = self.pke_key_gen(d);
+        dk.extend_from_slice(&ek);
+        dk.extend(hash_h(&ek));
+        dk.extend_from_slice(z);
+        self.encapsulation_key = ek;
+        self.decapsulation_key = dk;
+    }
+
+    pub fn generate_key<R: Rng>(&mut self, rng: &mut R) {
+        self.generate_key_internal(&rng.gen(), &rng.gen());
+    }
+
+    // Encapsulation key check from section 7.2, every coefficient must already be reduced modulo q
+    fn check_encapsulation_key(&self) -> Result<(), GeneralError> {
+        let ek = &self.encapsulation_key;
+        let k = self.parameters.k();
+        if ek.len() != self.parameters.encapsulation_key_len() {
+            return Err(GeneralError::key("encapsulation key has the wrong length"));
+        }
+        for chunk in ek[..384 * k].chunks_exact(384) {
+            if byte_encode(&byte_decode(chunk, 12), 12) != chunk {
+                return Err(GeneralError::key(
+                    "encapsulation key is not reduced modulo q",
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Algorithm 17, encapsulation with a chosen message m, gives the shared key and the ciphertext
+    pub fn encapsulate_internal(&self, m: &[u8; 32]) -> Result<([u8; 32], Vec<u8>), GeneralError> {
+        self.check_encapsulation_key()?;
+        let mut seed = m.to_vec();
+        seed.extend(hash_h(&self.encapsulation_key));
+        let (key, r) = hash_g(&seed);
+        Ok((key, self.pke_encrypt(&self.encapsulation_key, m, &r)))
+    }
+
+    pub fn encapsulate<R: Rng>(&self, rng: &mut R) -> Result<([u8; 32], Vec<u8>), GeneralError> {
+        self.encapsulate_internal(&rng.gen())
+    }
+
+    /// Algorithm 18, decapsulation. Decryption is checked by encrypting again and if the ciphertext does not match a
+    /// pseudorandom key derived from the secret z is returned instead, so an attacker learns nothing from invalid
+    /// ciphertexts.
+    pub fn decapsulate(&self, c: &[u8]) -> Result<[u8; 32], GeneralError> {
+        let k = self.parameters.k();
+        let dk = &self.decapsulation_key;
+        if dk.len() != self.parameters.decapsulation_key_len() {
+            return Err(GeneralError::key("decapsulation key has the wrong length"));
+        }
+        if c.len() != self.parameters.ciphertext_len() {
+            return Err(GeneralError::input(format!(
+                "ciphertext must be exactly {} bytes",
+                self.parameters.ciphertext_len()
+            )));
+        }
+        let dk_pke = &dk[..384 * k];
+        let ek_pke = &dk[384 * k..768 * k + 32];
+        let h = &dk[768 * k + 32..768 * k + 64];
+        let z = &dk[768 * k + 64..];
+        if hash_h(ek_pke) != h {
+            return Err(GeneralError::key("decapsulation key failed the hash check"));
+        }
+
+        let m = self.pke_decrypt(dk_pke, c);
+        let mut seed = m.to_vec();
+        seed.extend_from_slice(h);
+        let (key, r) = hash_g(&seed);
+        let mut rejection = z.to_vec();
+        rejection.extend_from_slice(c);
+        if self.pke_encrypt(ek_pke, &m, &r) != c {
+            return Ok(hash_j(&rejection));
+        }
+        Ok(key)
+    }
+}
+
+impl Cipher for MlKem {
+    /// The input is the 32 byte message to encapsulate, the output is the ciphertext and the shared key on separate
+    /// lines
+    fn encrypt(&self, text: &str) -> Result<String, GeneralError> {
+        let m: [u8; 32] = self
+            .input_format
+            .text_to_bytes(text)
+            .map_err(|_| GeneralError::input("byte format error"))?
+            .try_into()
+            .map_err(|_| GeneralError::input("message must be exactly 32 bytes"))?;
+        let (key, c) = self.encapsulate_internal(&m)?;
+        Ok(format!(
+            "{}\n{}",
+            self.output_format.byte_slice_to_text(&c),
+            self.output_format.byte_slice_to_text(key)
+        ))
+    }
+
+    /// The input is a ciphertext, the output is the shared key
+    fn decrypt(&self, text: &str) -> Result<String, GeneralError> {
+        let c = self
+            .input_format
+            .text_to_bytes(text)
+            .map_err(|_| GeneralError::input("byte format error"))?;
+        Ok(self.output_format.byte_slice_to_text(self.decapsulate(&c)?))
+    }
+}
+
+#[cfg(test)]
+mod ml_kem_tests {
+
+    use super::*;
+    use hashers::sha::Sha256;
+    use hex_literal::hex;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    fn seeds() -> ([u8; 32], [u8; 32]) {
+        let seed: Vec<u8> = (0..64).collect();
+        (
+            seed[..32].try_into().unwrap(),
+            seed[32..].try_into().unwrap(),
+        )
+    }
+
+    #[test]
+    fn ntt_roundtrip() {
+        let mut rng = StdRng::seed_from_u64(10);
+        let f: Poly = std::array::from_fn(|_| rng.gen_range(0..Q));
+        let g: Poly = std::array::from_fn(|_| rng.gen_range(0..Q));
+        assert_eq!(f, ntt_inverse(&ntt(&f)));
+
+        // Multiplication in the transformed domain is multiplication modulo X^256 + 1
+        let mut h = [0_u64; 2 * N];
+        for i in 0..N {
+            for j in 0..N {
+                h[i + j] += f[i] as u64 * g[j] as u64;
+            }
+        }
+        let schoolbook: Poly = std::array::from_fn(|i| {
+            ((h[i] % Q as u64 + Q as u64 - h[i + N] % Q as u64) % Q as u64) as u32
+        });
+        assert_eq!(schoolbook, ntt_inverse(&multiply_ntts(&ntt(&f), &ntt(&g))));
+    }
+
+    // The keys, ciphertexts and shared keys were produced by OpenSSL 3.5, using
+    // the seed 00 01 02 ... 3f for d || z
+    #[test]
+    fn encapsulation_keys() {
+        let (d, z) = seeds();
+        for (parameters, hash) in [
+            (
+                MlKemParameters::MlKem512,
+                "3ae268dccc5456ac0d0f9b39257dc48fe081383b97c400512d712b739762daee",
+            ),
+            (
+                MlKemParameters::MlKem768,
+                "0b7934c83125c788995e2ba6bd761e33046b3e40571be53e023309a29f398cc9",
+            ),
+            (
+                MlKemParameters::MlKem1024,
+                "c7b8fa0aa471d5ae18922d6ccad5b31e1d84f92ae723abfd13747018740a8530",
+            ),
+        ] {
+            let mut kem = MlKem {
+                parameters,
+                ..Default::default()
+            };
+            kem.generate_key_internal(&d, &z);
+            assert_eq!(
+                ByteFormat::Hex.text_to_bytes(hash).unwrap(),
+                Sha256::init().hash(&kem.encapsulation_key)
+            );
+        }
+    }
+
+    // Encapsulation with the message 40 41 42 ... 5f, given to OpenSSL as its "ikme" parameter. The hash of the
+    // ciphertext is compared along with the shared key.
+    #[test]
+    fn deterministic_encapsulation() {
+        let (d, z) = seeds();
+        let m: [u8; 32] = std::array::from_fn(|i| 0x40 + i as u8);
+        for (parameters, hash, shared_key) in [
+            (
+                MlKemParameters::MlKem512,
+                "81efe667826848514dcae46fc10cfd34f7b95ed6900e094f727c9e7cccc34df2",
+                hex!("14cace3e48771b316676afad2cfcfe8488daaa4fad954e57236caa3f24a42cf7"),
+            ),
+            (
+                MlKemParameters::MlKem768,
+                "dbf4e9aa48b078ad46ec1c9c47bda8c2d2fec9d0e7a21bd48d2238a2abedb856",
+                hex!("9cddd089ffe70e3996e76f7c8d06746df34d07e8657bc0fcf2bb0e1c3084aea1"),
+            ),
+            (
+                MlKemParameters::MlKem1024,
+                "7c89743960f7c3d17bb69572e49de14fe0990c9113a0706963a8f4c7b39afcdf",
+                hex!("0ad8d1ea1b8dd788979b4379581218df9321bdce5567eca42ae6be7d395f1a54"),
+            ),
+        ] {
+            let mut kem = MlKem {
+                parameters,
+                ..Default::default()
+            };
+            kem.generate_key_internal(&d, &z);
+            let (k, c) = kem.encapsulate_internal(&m).unwrap();
+            assert_eq!(shared_key, k);
+            assert_eq!(
+                ByteFormat::Hex.text_to_bytes(hash).unwrap(),
+                Sha256::init().hash(&c)
+            );
+            assert_eq!(shared_key, kem.decapsulate(&c).unwrap());
+        }
+    }
+
+    const CIPHERTEXT: [u8; 1088] = hex!(
+        "e4355eef862aeab24715a9fbc92d32a50e1d5c95d233c4718093684d61b15ed0"
+        "5a42d2e3d292922a374b9e37065d1d904047842191cd0bb792f9b6b587860bf2"
+        "841c35d61f9bf63b8190f13a60bdc36cb95171b9ace89465db7bbfba0d521160"
+        "5dadb07936a999c12d3e67df7d938114a7946f0299d5160971f747866add8542"
+        "b1042f72f69feed22c9fb27dd676e5e0c4ca7a08bf3ea686b953ab563ca0284f"
+        "20fda0010081bcdca2804eb6e9f883d554e28c1711047d6ea908e65e2219c962"
+        "c505845c014ba2297d4aa893c6de39bb2b885e91986ca10c3b1a90a6d0955318"
+        "ea9c2d5789e47795b3d909ecfb47261fc5ca96e50c984b045549cf5884abc328"
+        "ee8c33a1423107c0d63ef8ce24665bfec4ae1b151a1c5aa2c3599f4a3ea38a99"
+        "8c9c1896975ba54ffb45eb7313feac333126e9dcd78fd92b03fd115b0a36793c"
+        "ba7f1b238ee2e1df960502509da4e99fd536731891acdf59f38736422ee05663"
+        "598bc3868432366b1eac34c518820df5f474ed709a18b859cefa50d46aa7821d"
+        "4439a66c4b2c25c8cea516131df0946558c70891c8f11bd5a9057b94c567639e"
+        "edc70a9c0ef15c8f0fb6b0e06bc43d4389abfe83c7f37f37120ffdd7a4347173"
+        "a8179891666ec53f88ae52695f89d3cfcc6d0158fdfd535a1b5258f51fe6b257"
+        "ef8ef9b707a526b5573b5efc0f1061afca68fc3d163b91e5ce24120b2b306ec8"
+        "157b3b9f1ae77c1d89f4025c45d059cfd3190aac6a3d4741512d2647bfbbf89a"
+        "132bdfe03d614d529bb9241ba347ea5b3f1e09a533d8e24819b02a94fa775035"
+        "e39f3d7e2af458d048fd1f0d9c048607ec856c727a460c6bf29c7d24d1829593"
+        "2f8f95bd9540f9b5e8d27f5fbf7e590782b4b8a7ba7e8692be85a8da5989a26a"
+        "52c5c3a909b2b392856e9a0d3daa3502741acc29c22b4fcc7f152fef4027b312"
+        "637574007e1fab8588ae3eaf3b24e5ab163e08213c3c7322be3e3031cb4ad8c3"
+        "0ee735c49a52ffb9a65c024cf14fa288335c7e4ffeb3eb76f306442acc287f19"
+        "ddaac6326af3c750cbcf7af33b2d592cbc91da4c1707b551c3d55497c9f512b4"
+        "4bdd5c716bc61b787a86fd6308131d58378bb492b1ed7981f2f788d3e1d5937a"
+        "d90eee5e7bb6ebce9ae5fefbf7499875dd0e30ecc6f60169edd50881dc384d32"
+        "181b7d9529fc1ab045582b1176d234e94ce47c088aa666f131fd8348f86e26f1"
+        "0fd3fcbef3b6db6cd933fd6f581f303eebef3f9bb5c7ab256c566d7a91c856d9"
+        "4773d4c136a15a036f5c11cb92a27541a3930ea1e84f90179b09df192a826167"
+        "87fb4954bfdcdc6e6ba5bfca424cc003690f10697a608bf366b64bbb66bfdb5d"
+        "9bc76aeb5a3bfee601a3a6663c15d681a14e2858e7a678388274575aee43ffdc"
+        "d733319d23125f3c240063b0652e71964b99aafa03fd4456bea1de41ffb1b0d4"
+        "6952f37bda152644bb76f9ccece46d8cf72b9f5395e6537dfdce96a4db872f52"
+        "821ab74df8b227798c56a79e188229af27764c2d10aab002b288acf7f55b0be0"
+    );
+
+    #[test]
+    fn decapsulation() {
+        let (d, z) = seeds();
+        let mut kem = MlKem::default();
+        kem.generate_key_internal(&d, &z);
+        let mut c = CIPHERTEXT;
+        assert_eq!(
+            hex!("108af782a7331c4529f7c82b6c54086796ac3e120f27ed227780d9075cc9fbb6"),
+            kem.decapsulate(&c).unwrap()
+        );
+        // Implicit rejection
+        c[0] ^= 1;
+        assert_eq!(
+            hex!("3bad79d4720cf7491847f7ff60ce00862238811f726a2786db1678ec96c7f0c3"),
+            kem.decapsulate(&c).unwrap()
+        );
+    }
+
+    #[test]
+    fn encapsulate_decapsulate() {
+        let mut rng = StdRng::seed_from_u64(11);
+        for parameters in [
+            MlKemParameters::MlKem512,
+            MlKemParameters::MlKem768,
+            MlKemParameters::MlKem1024,
+        ] {
+            let mut kem = MlKem {
+                parameters,
+                ..Default::default()
+            };
+            kem.generate_key(&mut rng);
+            assert_eq!(
+                parameters.decapsulation_key_len(),
+                kem.decapsulation_key.len()
+            );
+            let (key, c) = kem.encapsulate(&mut rng).unwrap();
+            assert_eq!(parameters.ciphertext_len(), c.len());
+            assert_eq!(key, kem.decapsulate(&c).unwrap());
+        }
+    }
+}
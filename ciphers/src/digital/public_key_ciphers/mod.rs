@@ -1,4 +1,29 @@
+pub mod bip340;
+pub mod cramer_shoup;
+pub mod dh_groups;
 pub mod diffie_hellman;
 pub mod diffie_hellman_triple;
+pub mod dsa;
+pub mod ecdh;
+pub mod ecdsa;
+pub mod ed25519;
 pub mod elgamal;
+pub mod elgamal_signature;
+pub mod goldwasser_micali;
+pub mod lamport;
+pub mod lms;
+pub mod lwe;
+pub mod merkle_hellman;
+pub mod merkle_signature;
+pub mod ml_kem;
+pub mod ntru;
+pub mod paillier;
+pub mod rabin;
+pub mod rfc6979;
+pub mod rfc7748;
 pub mod rsa;
+pub mod rsa_blind;
+pub mod rsa_padding;
+pub mod schnorr;
+pub mod wots;
+pub mod xmss;
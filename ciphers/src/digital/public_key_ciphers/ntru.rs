@@ -0,0 +1,450 @@
+use crate::Cipher;
+use rand::{seq::SliceRandom, thread_rng, Rng};
+use utils::{
+    byte_formatting::ByteFormat,
+    errors::GeneralError,
+    padding::{bit_padding, strip_bit_padding},
+};
+
+// NTRUEncrypt as originally described, working with polynomials modulo x^n - 1. The private key is a pair of small
+// polynomials f and g and the public key is h = p g / f mod q. A message with coefficients modulo p is encrypted as
+// e = r h + m mod q for a small random r. Multiplying by f gives p r g + f m, which is small enough that it is not
+// changed by reduction mod q, and then reducing mod p and dividing by f recovers m.
+// https://doi.org/10.1007/BFb0054868
+
+// Reduce into the range -m/2 < x <= m/2
+fn center(x: i64, m: i64) -> i64 {
+    let x = x.rem_euclid(m);
+    if x > m / 2 {
+        x - m
+    } else {
+        x
+    }
+}
+
+/// Product of two polynomials modulo x^n - 1 with coefficients reduced into 0..m
+pub fn cyclic_mul(a: &[i64], b: &[i64], m: i64) -> Vec<i64> {
+    let n = a.len();
+    let mut out = vec![0; n];
+    for (i, x) in a.iter().enumerate() {
+        if *x == 0 {
+            continue;
+        }
+        for (j, y) in b.iter().enumerate() {
+            let k = (i + j) % n;
+            out[k] = (out[k] + x * y).rem_euclid(m);
+        }
+    }
+    out
+}
+
+fn trim(a: &mut Vec<i64>) {
+    while a.last() == Some(&0) {
+        a.pop();
+    }
+}
+
+fn inverse_mod(a: i64, m: i64) -> Option<i64> {
+    let (mut r0, mut r1) = (m, a.rem_euclid(m));
+    let (mut t0, mut t1) = (0, 1);
+    while r1 != 0 {
+        let q = r0 / r1;
+        (r0, r1) = (r1, r0 - q * r1);
+        (t0, t1) = (t1, t0 - q * t1);
+    }
+    (r0 == 1).then_some(t0.rem_euclid(m))
+}
+
+// Ordinary product of polynomials with coefficients modulo p
+fn mul_mod(a: &[i64], b: &[i64], p: i64) -> Vec<i64> {
+    if a.is_empty() || b.is_empty() {
+        return Vec::new();
+    }
+    let mut out = vec![0; a.len() + b.len() - 1];
+    for (i, x) in a.iter().enumerate() {
+        for (j, y) in b.iter().enumerate() {
+            out[i + j] = (out[i + j] + x * y).rem_euclid(p);
+        }
+    }
+    trim(&mut out);
+    out
+}
+
+// Quotient and remainder of polynomials with coefficients modulo a prime p, b must not be zero
+fn div_rem(a: &[i64], b: &[i64], p: i64) -> (Vec<i64>, Vec<i64>) {
+    let mut rem = a.to_vec();
+    trim(&mut rem);
+    let lead = inverse_mod(*b.last().unwrap(), p).expect("p is prime");
+    let mut quot = vec![0; rem.len().saturating_sub(b.len()) + 1];
+    while rem.len() >= b.len() {
+        let shift = rem.len() - b.len();
+        let c = rem.last().unwrap() * lead % p;
+        quot[shift] = c;
+        for (i, x) in b.iter().enumerate() {
+            rem[shift + i] = (rem[shift + i] - c * x).rem_euclid(p);
+        }
+        trim(&mut rem);
+    }
+    trim(&mut quot);
+    (quot, rem)
+}
+
+/// Inverse of a modulo x^n - 1 and a prime p, by the extended Euclidean algorithm
+pub fn inverse_mod_prime(a: &[i64], p: i64) -> Option<Vec<i64>> {
+    let n = a.len();
+    let mut r0 = vec![0; n + 1];
+    r0[0] = p - 1;
+    r0[n] = 1;
+    let mut r1: Vec<i64> = a.iter().map(|x| x.rem_euclid(p)).collect();
+    trim(&mut r1);
+    let (mut t0, mut t1) = (Vec::new(), vec![1]);
+    while r1.len() > 1 {
+        let (quot, rem) = div_rem(&r0, &r1, p);
+        let qt = mul_mod(&quot, &t1, p);
+        let mut t2 = vec![0; t0.len().max(qt.len())];
+        for (i, x) in t2.iter_mut().enumerate() {
+            *x = (t0.get(i).unwrap_or(&0) - qt.get(i).unwrap_or(&0)).rem_euclid(p);
+        }
+        trim(&mut t2);
+        (r0, r1) = (r1, rem);
+        (t0, t1) = (t1, t2);
+    }
+    // Either the gcd is a nonzero constant or a is not invertible
+    let c = inverse_mod(*r1.first()?, p)?;
+    let mut out: Vec<i64> = t1.iter().map(|x| x * c % p).collect();
+    out.resize(n, 0);
+    Some(out)
+}
+
+/// Inverse of a modulo x^n - 1 and a power of two q, found modulo 2 and then lifted by Newton iteration
+pub fn inverse_mod_power_of_two(a: &[i64], q: i64) -> Option<Vec<i64>> {
+    let mut b = inverse_mod_prime(a, 2)?;
+    let mut one = vec![0; a.len()];
+    one[0] = 1;
+    // Each step doubles the number of correct bits
+    loop {
+        let ab = cyclic_mul(a, &b, q);
+        if ab == one {
+            return Some(b);
+        }
+        let mut two_minus_ab: Vec<i64> = ab.iter().map(|x| (-x).rem_euclid(q)).collect();
+        two_minus_ab[0] = (two_minus_ab[0] + 2).rem_euclid(q);
+        b = cyclic_mul(&b, &two_minus_ab, q);
+    }
+}
+
+/// Random polynomial with exactly `ones` coefficients equal to 1 and `minus_ones` equal to -1
+pub fn random_ternary<R: Rng>(n: usize, ones: usize, minus_ones: usize, rng: &mut R) -> Vec<i64> {
+    let mut out = vec![0; n];
+    out[..ones].fill(1);
+    out[ones..ones + minus_ones].fill(-1);
+    out.shuffle(rng);
+    out
+}
+
+pub struct Ntru {
+    pub input_format: ByteFormat,
+    pub output_format: ByteFormat,
+
+    pub n: usize,
+    pub p: i64,
+    pub q: i64,
+    /// f has d+1 coefficients equal to 1 and d equal to -1, g and r have d of each
+    pub d: usize,
+
+    pub f: Vec<i64>,
+    pub g: Vec<i64>,
+    pub fp: Vec<i64>,
+    pub h: Vec<i64>,
+}
+
+impl Default for Ntru {
+    fn default() -> Self {
+        Self {
+            input_format: ByteFormat::Utf8,
+            output_format: ByteFormat::Hex,
+
+            n: 107,
+            p: 3,
+            q: 512,
+            d: 20,
+
+            f: Vec::new(),
+            g: Vec::new(),
+            fp: Vec::new(),
+            h: Vec::new(),
+        }
+    }
+}
+
+impl Ntru {
+    /// Changing the parameters discards the key. When q > (6d + 1)p decryption can never fail.
+    pub fn set_parameters(
+        &mut self,
+        n: usize,
+        p: i64,
+        q: i64,
+        d: usize,
+    ) -> Result<(), GeneralError> {
+        if n < 2 || 2 * d + 1 > n {
+            return Err(GeneralError::key("n must be at least 2d+1"));
+        }
+        if !(3..100).contains(&p) || (2..p).any(|x| p % x == 0) {
+            return Err(GeneralError::key("p must be a small odd prime"));
+        }
+        if !(2..=1 << 16).contains(&q) || (q & (q - 1)) != 0 {
+            return Err(GeneralError::key(
+                "q must be a power of two no larger than 2^16",
+            ));
+        }
+        if q <= (6 * d as i64 + 1) * p {
+            return Err(GeneralError::key("q must be greater than (6d + 1)p"));
+        }
+        self.n = n;
+        self.p = p;
+        self.q = q;
+        self.d = d;
+        self.f.clear();
+        self.g.clear();
+        self.fp.clear();
+        self.h.clear();
+        Ok(())
+    }
+
+    /// Set the private key, f must be invertible both mod p and mod q
+    pub fn set_key(&mut self, f: Vec<i64>, g: Vec<i64>) -> Result<(), GeneralError> {
+        if f.len() != self.n || g.len() != self.n {
+            return Err(GeneralError::key(format!(
+                "f and g must have exactly {} coefficients",
+                self.n
+            )));
+        }
+        let fp = inverse_mod_prime(&f, self.p)
+            .ok_or(GeneralError::key("f is not invertible modulo p"))?;
+        let fq = inverse_mod_power_of_two(&f, self.q)
+            .ok_or(GeneralError::key("f is not invertible modulo q"))?;
+        let pg: Vec<i64> = g.iter().map(|x| x * self.p).collect();
+        self.h = cyclic_mul(&fq, &pg, self.q);
+        self.fp = fp;
+        self.f = f;
+        self.g = g;
+        Ok(())
+    }
+
+    pub fn generate_key<R: Rng>(&mut self, rng: &mut R) {
+        loop {
+            let f = random_ternary(self.n, self.d + 1, self.d, rng);
+            let g = random_ternary(self.n, self.d, self.d, rng);
+            if self.set_key(f, g).is_ok() {
+                return;
+            }
+        }
+    }
+
+    /// e = r h + m mod q
+    pub fn encrypt_poly(&self, m: &[i64], r: &[i64]) -> Result<Vec<i64>, GeneralError> {
+        if self.h.is_empty() {
+            return Err(GeneralError::state("key has not been set"));
+        }
+        if m.len() != self.n || r.len() != self.n {
+            return Err(GeneralError::input(format!(
+                "polynomials must have exactly {} coefficients",
+                self.n
+            )));
+        }
+        let rh = cyclic_mul(r, &self.h, self.q);
+        Ok(rh
+            .iter()
+            .zip(m)
+            .map(|(x, y)| (x + y).rem_euclid(self.q))
+            .collect())
+    }
+
+    /// Compute a = f e mod q with centered coefficients and then m = fp a mod p, also centered
+    pub fn decrypt_poly(&self, e: &[i64]) -> Result<Vec<i64>, GeneralError> {
+        if self.f.is_empty() {
+            return Err(GeneralError::state("private key has not been set"));
+        }
+        if e.len() != self.n {
+            return Err(GeneralError::input(format!(
+                "ciphertext must have exactly {} coefficients",
+                self.n
+            )));
+        }
+        let a: Vec<i64> = cyclic_mul(&self.f, e, self.q)
+            .into_iter()
+            .map(|x| center(x, self.q))
+            .collect();
+        Ok(cyclic_mul(&self.fp, &a, self.p)
+            .into_iter()
+            .map(|x| center(x, self.p))
+            .collect())
+    }
+
+    /// Each block of n/8 bytes becomes a message polynomial with coefficients 0 and 1, most significant bit first
+    pub fn block_bytes(&self) -> usize {
+        self.n / 8
+    }
+
+    fn element_len(&self) -> usize {
+        (64 - (self.q - 1).leading_zeros() as usize).div_ceil(8)
+    }
+
+    pub fn encrypt_bytes(&self, bytes: &[u8]) -> Result<Vec<u8>, GeneralError> {
+        let block_bytes = self.block_bytes();
+        if block_bytes == 0 {
+            return Err(GeneralError::key("n must be at least 8"));
+        }
+        let mut bytes = bytes.to_vec();
+        bit_padding(&mut bytes, block_bytes as u32)
+            .map_err(|e| GeneralError::input(e.to_string()))?;
+        let mut rng = thread_rng();
+        let len = self.element_len();
+        let mut out = Vec::new();
+        for block in bytes.chunks_exact(block_bytes) {
+            let mut m = vec![0; self.n];
+            for (i, c) in m.iter_mut().take(8 * block_bytes).enumerate() {
+                *c = ((block[i / 8] >> (7 - i % 8)) & 1) as i64;
+            }
+            let r = random_ternary(self.n, self.d, self.d, &mut rng);
+            for x in self.encrypt_poly(&m, &r)? {
+                out.extend_from_slice(&x.to_be_bytes()[8 - len..]);
+            }
+        }
+        Ok(out)
+    }
+
+    pub fn decrypt_bytes(&self, bytes: &[u8]) -> Result<Vec<u8>, GeneralError> {
+        let len = self.element_len();
+        let block = self.n * len;
+        if !bytes.len().is_multiple_of(block) {
+            return Err(GeneralError::input(format!(
+                "ciphertext must be in chunks of {block} bytes"
+            )));
+        }
+        let mut out = Vec::new();
+        for chunk in bytes.chunks_exact(block) {
+            let e: Vec<i64> = chunk
+                .chunks_exact(len)
+                .map(|x| x.iter().fold(0, |acc, b| (acc << 8) | *b as i64) % self.q)
+                .collect();
+            let m = self.decrypt_poly(&e)?;
+            let mut bytes = vec![0; self.block_bytes()];
+            for (i, c) in m.iter().take(8 * self.block_bytes()).enumerate() {
+                if *c == 1 {
+                    bytes[i / 8] |= 1 << (7 - i % 8);
+                } else if *c != 0 {
+                    return Err(GeneralError::input("decryption failed"));
+                }
+            }
+            out.extend(bytes);
+        }
+        strip_bit_padding(&mut out).map_err(|e| GeneralError::input(e.to_string()))?;
+        Ok(out)
+    }
+}
+
+impl Cipher for Ntru {
+    fn encrypt(&self, text: &str) -> Result<String, GeneralError> {
+        let bytes = self
+            .input_format
+            .text_to_bytes(text)
+            .map_err(|_| GeneralError::input("byte format error"))?;
+        Ok(self
+            .output_format
+            .byte_slice_to_text(self.encrypt_bytes(&bytes)?))
+    }
+
+    fn decrypt(&self, text: &str) -> Result<String, GeneralError> {
+        let bytes = self
+            .input_format
+            .text_to_bytes(text)
+            .map_err(|_| GeneralError::input("byte format error"))?;
+        Ok(self
+            .output_format
+            .byte_slice_to_text(self.decrypt_bytes(&bytes)?))
+    }
+}
+
+#[cfg(test)]
+mod ntru_tests {
+
+    use super::*;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    fn poly(n: usize, terms: &[(usize, i64)]) -> Vec<i64> {
+        let mut out = vec![0; n];
+        for (i, c) in terms {
+            out[*i] = *c;
+        }
+        out
+    }
+
+    // The example from the Wikipedia article on NTRUEncrypt, N = 11, p = 3 and q = 32
+    #[test]
+    fn small_example() {
+        let mut cipher = Ntru {
+            n: 11,
+            p: 3,
+            q: 32,
+            ..Default::default()
+        };
+        cipher
+            .set_key(
+                poly(
+                    11,
+                    &[(0, -1), (1, 1), (2, 1), (4, -1), (6, 1), (9, 1), (10, -1)],
+                ),
+                poly(11, &[(0, -1), (2, 1), (3, 1), (5, 1), (8, -1), (10, -1)]),
+            )
+            .unwrap();
+        assert_eq!(vec![1, 2, 0, 2, 2, 1, 0, 2, 1, 2, 0], cipher.fp);
+        assert_eq!(vec![8, 25, 22, 20, 12, 24, 15, 19, 12, 19, 16], cipher.h);
+
+        let m = poly(11, &[(0, -1), (3, 1), (4, -1), (8, -1), (9, 1), (10, 1)]);
+        let r = poly(11, &[(0, -1), (2, 1), (3, 1), (4, 1), (5, -1), (7, -1)]);
+        let e = cipher.encrypt_poly(&m, &r).unwrap();
+        assert_eq!(vec![14, 11, 26, 24, 14, 16, 30, 7, 25, 6, 19], e);
+        assert_eq!(m, cipher.decrypt_poly(&e).unwrap());
+    }
+
+    #[test]
+    fn inverses() {
+        let mut rng = StdRng::seed_from_u64(14);
+        let mut one = vec![0; 107];
+        one[0] = 1;
+        let mut found = 0;
+        for _ in 0..10 {
+            let f = random_ternary(107, 21, 20, &mut rng);
+            if let Some(fp) = inverse_mod_prime(&f, 3) {
+                assert_eq!(one, cyclic_mul(&f, &fp, 3));
+                found += 1;
+            }
+            if let Some(fq) = inverse_mod_power_of_two(&f, 512) {
+                assert_eq!(one, cyclic_mul(&f, &fq, 512));
+            }
+        }
+        assert!(found > 0);
+        // f(1) = 0 means x - 1 divides f
+        assert!(inverse_mod_prime(&random_ternary(107, 20, 20, &mut rng), 3).is_none());
+    }
+
+    #[test]
+    fn encrypt_decrypt() {
+        let mut cipher = Ntru::default();
+        cipher.generate_key(&mut StdRng::seed_from_u64(15));
+        let ctext = cipher.encrypt("attack at dawn").unwrap();
+        assert_ne!(ctext, cipher.encrypt("attack at dawn").unwrap());
+        cipher.output_format = ByteFormat::Utf8;
+        cipher.input_format = ByteFormat::Hex;
+        assert_eq!("attack at dawn", cipher.decrypt(&ctext).unwrap());
+    }
+
+    #[test]
+    fn parameters() {
+        let mut cipher = Ntru::default();
+        assert!(cipher.set_parameters(107, 3, 256, 20).is_err());
+        assert!(cipher.set_parameters(107, 4, 512, 20).is_err());
+        assert!(cipher.set_parameters(167, 3, 1024, 40).is_ok());
+    }
+}
@@ -0,0 +1,272 @@
+use super::rfc6979::int2octets;
+use crate::Cipher;
+use num::{integer::gcd, BigUint, Integer, One, Zero};
+use rand::{thread_rng, Rng};
+use utils::{
+    byte_formatting::ByteFormat,
+    errors::GeneralError,
+    math_functions::mul_inv,
+    primality::{is_probable_prime, random_biguint_below, random_prime},
+};
+
+// Paillier encryption. The ciphertext of m is (1+n)^m * r^n mod n^2 for a random r, so multiplying ciphertexts adds
+// the plaintexts and raising a ciphertext to a power multiplies the plaintext, all without knowing the private key.
+// https://doi.org/10.1007/3-540-48910-X_16
+pub struct Paillier {
+    pub input_format: ByteFormat,
+    pub output_format: ByteFormat,
+
+    pub n: BigUint,
+    pub n_squared: BigUint,
+    pub lambda: BigUint,
+    pub mu: BigUint,
+
+    pub p: BigUint,
+    pub q: BigUint,
+}
+
+impl Default for Paillier {
+    fn default() -> Self {
+        Self {
+            input_format: ByteFormat::Utf8,
+            output_format: ByteFormat::Hex,
+
+            n: BigUint::zero(),
+            n_squared: BigUint::zero(),
+            lambda: BigUint::zero(),
+            mu: BigUint::zero(),
+
+            p: BigUint::zero(),
+            q: BigUint::zero(),
+        }
+    }
+}
+
+impl Paillier {
+    pub fn set_key(&mut self, p: &BigUint, q: &BigUint) -> Result<(), GeneralError> {
+        if p == q {
+            return Err(GeneralError::key("p and q must be distinct"));
+        }
+        let mut rng = thread_rng();
+        if !is_probable_prime(p, 20, &mut rng) {
+            return Err(GeneralError::key("p is not prime"));
+        }
+        if !is_probable_prime(q, 20, &mut rng) {
+            return Err(GeneralError::key("q is not prime"));
+        }
+        let n = p * q;
+        let one = BigUint::one();
+        let phi = (p - &one) * (q - &one);
+        if !gcd(n.clone(), phi.clone()).is_one() {
+            return Err(GeneralError::key("n must be coprime to (p-1)(q-1)"));
+        }
+        let n_squared = &n * &n;
+        let lambda = (p - &one).lcm(&(q - &one));
+        // With g = n+1 the value L(g^lambda mod n^2) is just lambda mod n
+        let mu = mul_inv(&(&lambda % &n), &n)
+            .and_then(|x| x.to_biguint())
+            .ok_or(GeneralError::key("lambda is not invertible modulo n"))?;
+
+        self.n = n;
+        self.n_squared = n_squared;
+        self.lambda = lambda;
+        self.mu = mu;
+        self.p = p.clone();
+        self.q = q.clone();
+        Ok(())
+    }
+
+    /// Generate a key with a modulus of exactly the given number of bits
+    pub fn generate_key<R: Rng>(&mut self, bits: u64, rng: &mut R) -> Result<(), GeneralError> {
+        if bits < 16 {
+            return Err(GeneralError::key("modulus must be at least 16 bits"));
+        }
+        loop {
+            let p = random_prime(bits / 2, rng);
+            let q = random_prime(bits - bits / 2, rng);
+            if self.set_key(&p, &q).is_ok() {
+                return Ok(());
+            }
+        }
+    }
+
+    fn check_public_key(&self) -> Result<(), GeneralError> {
+        if self.n.is_zero() {
+            return Err(GeneralError::state("key has not been set"));
+        }
+        Ok(())
+    }
+
+    /// Length in bytes of a ciphertext
+    pub fn ciphertext_len(&self) -> usize {
+        self.n_squared.bits().div_ceil(8) as usize
+    }
+
+    /// Encrypt with a chosen r, which must be coprime to n
+    pub fn encrypt_with_r(&self, m: &BigUint, r: &BigUint) -> Result<BigUint, GeneralError> {
+        self.check_public_key()?;
+        if *m >= self.n {
+            return Err(GeneralError::input("message must be less than n"));
+        }
+        if r.is_zero() || *r >= self.n || !gcd(r.clone(), self.n.clone()).is_one() {
+            return Err(GeneralError::input(
+                "r must be between 1 and n-1 and coprime to n",
+            ));
+        }
+        // (1+n)^m = 1 + mn mod n^2 so no exponentiation is needed for that part
+        let gm = (BigUint::one() + m * &self.n) % &self.n_squared;
+        Ok((gm * r.modpow(&self.n, &self.n_squared)) % &self.n_squared)
+    }
+
+    pub fn encrypt_int<R: Rng>(&self, m: &BigUint, rng: &mut R) -> Result<BigUint, GeneralError> {
+        self.check_public_key()?;
+        loop {
+            let r = random_biguint_below(&self.n, rng);
+            if !r.is_zero() && gcd(r.clone(), self.n.clone()).is_one() {
+                return self.encrypt_with_r(m, &r);
+            }
+        }
+    }
+
+    /// m = L(c^lambda mod n^2) * mu mod n where L(x) = (x-1)/n
+    pub fn decrypt_int(&self, c: &BigUint) -> Result<BigUint, GeneralError> {
+        if self.lambda.is_zero() {
+            return Err(GeneralError::state("private key has not been set"));
+        }
+        if *c >= self.n_squared {
+            return Err(GeneralError::input("ciphertext must be less than n^2"));
+        }
+        let x = c.modpow(&self.lambda, &self.n_squared);
+        if x.is_zero() {
+            return Err(GeneralError::input("ciphertext is not a unit modulo n^2"));
+        }
+        let l = (x - 1_u32) / &self.n;
+        Ok((l * &self.mu) % &self.n)
+    }
+
+    /// Ciphertext of the sum of the two plaintexts
+    pub fn add(&self, c1: &BigUint, c2: &BigUint) -> BigUint {
+        (c1 * c2) % &self.n_squared
+    }
+
+    /// Ciphertext of the plaintext plus k
+    pub fn add_plain(&self, c: &BigUint, k: &BigUint) -> BigUint {
+        let gk = (BigUint::one() + (k % &self.n) * &self.n) % &self.n_squared;
+        (c * gk) % &self.n_squared
+    }
+
+    /// Ciphertext of the plaintext times k
+    pub fn mul_plain(&self, c: &BigUint, k: &BigUint) -> BigUint {
+        c.modpow(k, &self.n_squared)
+    }
+
+    /// A fresh ciphertext of the same plaintext that cannot be linked to the original
+    pub fn rerandomize<R: Rng>(&self, c: &BigUint, rng: &mut R) -> BigUint {
+        let r = loop {
+            let r = random_biguint_below(&self.n, rng);
+            if !r.is_zero() && gcd(r.clone(), self.n.clone()).is_one() {
+                break r;
+            }
+        };
+        (c * r.modpow(&self.n, &self.n_squared)) % &self.n_squared
+    }
+
+    /// Encrypt the bytes as a single integer less than n
+    pub fn encrypt_bytes(&self, bytes: &[u8]) -> Result<Vec<u8>, GeneralError> {
+        let c = self.encrypt_int(&BigUint::from_bytes_be(bytes), &mut thread_rng())?;
+        Ok(int2octets(&c, self.ciphertext_len()))
+    }
+
+    pub fn decrypt_bytes(&self, bytes: &[u8]) -> Result<Vec<u8>, GeneralError> {
+        Ok(self
+            .decrypt_int(&BigUint::from_bytes_be(bytes))?
+            .to_bytes_be())
+    }
+}
+
+impl Cipher for Paillier {
+    fn encrypt(&self, text: &str) -> Result<String, GeneralError> {
+        let bytes = self
+            .input_format
+            .text_to_bytes(text)
+            .map_err(|_| GeneralError::input("byte format error"))?;
+        Ok(self
+            .output_format
+            .byte_slice_to_text(self.encrypt_bytes(&bytes)?))
+    }
+
+    fn decrypt(&self, text: &str) -> Result<String, GeneralError> {
+        let bytes = self
+            .input_format
+            .text_to_bytes(text)
+            .map_err(|_| GeneralError::input("byte format error"))?;
+        Ok(self
+            .output_format
+            .byte_slice_to_text(self.decrypt_bytes(&bytes)?))
+    }
+}
+
+#[cfg(test)]
+mod paillier_tests {
+
+    use super::*;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn toy_example() {
+        let mut cipher = Paillier::default();
+        cipher
+            .set_key(&BigUint::from(7_u32), &BigUint::from(11_u32))
+            .unwrap();
+        assert_eq!(BigUint::from(30_u32), cipher.lambda);
+        assert_eq!(BigUint::from(18_u32), cipher.mu);
+        let c = cipher
+            .encrypt_with_r(&BigUint::from(42_u32), &BigUint::from(23_u32))
+            .unwrap();
+        assert_eq!(BigUint::from(3840_u32), c);
+        assert_eq!(BigUint::from(42_u32), cipher.decrypt_int(&c).unwrap());
+    }
+
+    #[test]
+    fn homomorphism() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let mut cipher = Paillier::default();
+        cipher.generate_key(256, &mut rng).unwrap();
+        assert_eq!(256, cipher.n.bits());
+
+        let a = BigUint::from(1234_u32);
+        let b = BigUint::from(5678_u32);
+        let ca = cipher.encrypt_int(&a, &mut rng).unwrap();
+        let cb = cipher.encrypt_int(&b, &mut rng).unwrap();
+        assert_eq!(&a + &b, cipher.decrypt_int(&cipher.add(&ca, &cb)).unwrap());
+        assert_eq!(
+            &a + 99_u32,
+            cipher
+                .decrypt_int(&cipher.add_plain(&ca, &BigUint::from(99_u32)))
+                .unwrap()
+        );
+        assert_eq!(
+            &b * 3_u32,
+            cipher
+                .decrypt_int(&cipher.mul_plain(&cb, &BigUint::from(3_u32)))
+                .unwrap()
+        );
+
+        let fresh = cipher.rerandomize(&ca, &mut rng);
+        assert_ne!(ca, fresh);
+        assert_eq!(a, cipher.decrypt_int(&fresh).unwrap());
+    }
+
+    #[test]
+    fn encrypt_decrypt() {
+        let mut cipher = Paillier::default();
+        cipher
+            .generate_key(256, &mut StdRng::seed_from_u64(2))
+            .unwrap();
+        let ctext = cipher.encrypt("attack at dawn").unwrap();
+        assert_ne!(ctext, cipher.encrypt("attack at dawn").unwrap());
+        cipher.output_format = ByteFormat::Utf8;
+        cipher.input_format = ByteFormat::Hex;
+        assert_eq!("attack at dawn", cipher.decrypt(&ctext).unwrap());
+    }
+}
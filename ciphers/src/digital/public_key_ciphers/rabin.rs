@@ -0,0 +1,226 @@
+use super::rfc6979::int2octets;
+use crate::Cipher;
+use hashers::{sha::Sha256, traits::StatefulHasher};
+use num::{BigUint, Zero};
+use rand::{thread_rng, Rng};
+use utils::{
+    byte_formatting::ByteFormat,
+    elliptic_curves::sqrt_mod_prime,
+    errors::GeneralError,
+    math_functions::crt,
+    primality::{is_probable_prime, random_prime},
+};
+
+// Rabin encryption. The ciphertext is just m^2 mod n and decrypting it is exactly as hard as factoring n, but every
+// ciphertext has four square roots. Redundancy is added to the message so the right one can be recognized.
+// https://apps.dtic.mil/sti/citations/ADA078415
+
+const TAG_LEN: usize = 8;
+
+fn tag(msg: &[u8]) -> Vec<u8> {
+    Sha256::init().hash(msg)[..TAG_LEN].to_vec()
+}
+
+pub struct Rabin {
+    pub input_format: ByteFormat,
+    pub output_format: ByteFormat,
+
+    pub n: BigUint,
+    pub p: BigUint,
+    pub q: BigUint,
+}
+
+impl Default for Rabin {
+    fn default() -> Self {
+        Self {
+            input_format: ByteFormat::Utf8,
+            output_format: ByteFormat::Hex,
+
+            n: BigUint::zero(),
+            p: BigUint::zero(),
+            q: BigUint::zero(),
+        }
+    }
+}
+
+impl Rabin {
+    pub fn set_key(&mut self, p: &BigUint, q: &BigUint) -> Result<(), GeneralError> {
+        if p == q {
+            return Err(GeneralError::key("p and q must be distinct"));
+        }
+        let mut rng = thread_rng();
+        for (name, x) in [("p", p), ("q", q)] {
+            if !x.bit(0) || !is_probable_prime(x, 20, &mut rng) {
+                return Err(GeneralError::key(format!("{name} is not an odd prime")));
+            }
+        }
+        self.n = p * q;
+        self.p = p.clone();
+        self.q = q.clone();
+        Ok(())
+    }
+
+    /// Generate a key with a modulus of exactly the given number of bits. The primes are both 3 mod 4 so that square
+    /// roots only need a single exponentiation.
+    pub fn generate_key<R: Rng>(&mut self, bits: u64, rng: &mut R) -> Result<(), GeneralError> {
+        if bits < 16 {
+            return Err(GeneralError::key("modulus must be at least 16 bits"));
+        }
+        let mut blum_prime = |bits| loop {
+            let p = random_prime(bits, rng);
+            if p.bit(1) {
+                return p;
+            }
+        };
+        let p = blum_prime(bits / 2);
+        let q = loop {
+            let q = blum_prime(bits - bits / 2);
+            if q != p {
+                break q;
+            }
+        };
+        self.set_key(&p, &q)
+    }
+
+    pub fn modulus_len(&self) -> usize {
+        self.n.bits().div_ceil(8) as usize
+    }
+
+    pub fn encrypt_int(&self, m: &BigUint) -> Result<BigUint, GeneralError> {
+        if self.n.is_zero() {
+            return Err(GeneralError::state("key has not been set"));
+        }
+        if *m >= self.n {
+            return Err(GeneralError::input("message must be less than n"));
+        }
+        Ok(m.modpow(&BigUint::from(2_u32), &self.n))
+    }
+
+    /// The four square roots of c, found modulo p and q separately and combined by the Chinese remainder theorem
+    pub fn roots(&self, c: &BigUint) -> Result<[BigUint; 4], GeneralError> {
+        if self.p.is_zero() {
+            return Err(GeneralError::state("private key has not been set"));
+        }
+        let (p, q) = (&self.p, &self.q);
+        let rp = sqrt_mod_prime(c, p).ok_or(GeneralError::input("c is not a square mod p"))?;
+        let rq = sqrt_mod_prime(c, q).ok_or(GeneralError::input("c is not a square mod q"))?;
+        let moduli = [p.clone(), q.clone()];
+        let combine = |a: &BigUint, b: &BigUint| {
+            crt(&[a.clone(), b.clone()], &moduli).expect("p and q are coprime")
+        };
+        let mut out = [
+            combine(&rp, &rq),
+            combine(&rp, &((q - &rq) % q)),
+            combine(&((p - &rp) % p), &rq),
+            combine(&((p - &rp) % p), &((q - &rq) % q)),
+        ];
+        out.sort();
+        Ok(out)
+    }
+
+    /// The message is encoded as the integer 0x01 || msg || SHA-256(msg)[..8] which must be less than n
+    pub fn encrypt_bytes(&self, bytes: &[u8]) -> Result<Vec<u8>, GeneralError> {
+        let mut encoded = vec![0x01];
+        encoded.extend_from_slice(bytes);
+        encoded.extend(tag(bytes));
+        let c = self.encrypt_int(&BigUint::from_bytes_be(&encoded))?;
+        Ok(int2octets(&c, self.modulus_len()))
+    }
+
+    /// Only one of the four roots should have the correct redundancy
+    pub fn decrypt_bytes(&self, bytes: &[u8]) -> Result<Vec<u8>, GeneralError> {
+        let c = BigUint::from_bytes_be(bytes);
+        if c >= self.n {
+            return Err(GeneralError::input("ciphertext must be less than n"));
+        }
+        for root in self.roots(&c)? {
+            let encoded = root.to_bytes_be();
+            if encoded.len() < 1 + TAG_LEN || encoded[0] != 0x01 {
+                continue;
+            }
+            let (msg, t) = encoded[1..].split_at(encoded.len() - 1 - TAG_LEN);
+            if tag(msg) == t {
+                return Ok(msg.to_vec());
+            }
+        }
+        Err(GeneralError::input("no square root has valid redundancy"))
+    }
+}
+
+impl Cipher for Rabin {
+    fn encrypt(&self, text: &str) -> Result<String, GeneralError> {
+        let bytes = self
+            .input_format
+            .text_to_bytes(text)
+            .map_err(|_| GeneralError::input("byte format error"))?;
+        Ok(self
+            .output_format
+            .byte_slice_to_text(self.encrypt_bytes(&bytes)?))
+    }
+
+    fn decrypt(&self, text: &str) -> Result<String, GeneralError> {
+        let bytes = self
+            .input_format
+            .text_to_bytes(text)
+            .map_err(|_| GeneralError::input("byte format error"))?;
+        Ok(self
+            .output_format
+            .byte_slice_to_text(self.decrypt_bytes(&bytes)?))
+    }
+}
+
+#[cfg(test)]
+mod rabin_tests {
+
+    use super::*;
+    use num::{integer::gcd, One};
+    use rand::{rngs::StdRng, SeedableRng};
+
+    // Example 8.11 from the Handbook of Applied Cryptography
+    #[test]
+    fn textbook_example() {
+        let mut cipher = Rabin::default();
+        cipher
+            .set_key(&BigUint::from(277_u32), &BigUint::from(331_u32))
+            .unwrap();
+        assert_eq!(BigUint::from(91687_u32), cipher.n);
+        let c = cipher.encrypt_int(&BigUint::from(40569_u32)).unwrap();
+        assert_eq!(BigUint::from(62111_u32), c);
+        assert_eq!(
+            [22033_u32, 40569, 51118, 69654].map(BigUint::from),
+            cipher.roots(&c).unwrap()
+        );
+    }
+
+    #[test]
+    fn encrypt_decrypt() {
+        let mut cipher = Rabin::default();
+        cipher
+            .generate_key(512, &mut StdRng::seed_from_u64(3))
+            .unwrap();
+        let ctext = cipher.encrypt("attack at dawn").unwrap();
+        cipher.output_format = ByteFormat::Utf8;
+        cipher.input_format = ByteFormat::Hex;
+        assert_eq!("attack at dawn", cipher.decrypt(&ctext).unwrap());
+    }
+
+    // Anyone who can be made to return an arbitrary square root of a chosen square can be used to factor n, which is
+    // why the redundancy check matters
+    #[test]
+    fn chosen_ciphertext_factoring() {
+        let mut rng = StdRng::seed_from_u64(4);
+        let mut cipher = Rabin::default();
+        cipher.generate_key(256, &mut rng).unwrap();
+        let m = BigUint::from(123456789_u32);
+        let c = cipher.encrypt_int(&m).unwrap();
+        let factor = cipher
+            .roots(&c)
+            .unwrap()
+            .iter()
+            .filter(|r| **r != m && **r != &cipher.n - &m)
+            .map(|r| gcd(&cipher.n + r - &m, cipher.n.clone()))
+            .find(|g| !g.is_one())
+            .unwrap();
+        assert!(factor == cipher.p || factor == cipher.q);
+    }
+}
@@ -0,0 +1,96 @@
+use hashers::{
+    hmac::{Hmac, HmacVariant},
+    traits::StatefulHasher,
+};
+use num::BigUint;
+
+// Deterministic nonces for DSA and ECDSA
+// https://datatracker.ietf.org/doc/html/rfc6979#section-3.2
+
+/// Interpret bytes as an integer keeping only the leftmost qlen bits
+pub fn bits2int(bytes: &[u8], qlen: usize) -> BigUint {
+    let x = BigUint::from_bytes_be(bytes);
+    let blen = bytes.len() * 8;
+    if blen > qlen {
+        x >> (blen - qlen)
+    } else {
+        x
+    }
+}
+
+/// Big endian encoding of x in exactly len bytes, x must fit
+pub fn int2octets(x: &BigUint, len: usize) -> Vec<u8> {
+    let bytes = x.to_bytes_be();
+    let mut out = vec![0; len - bytes.len()];
+    out.extend(bytes);
+    out
+}
+
+/// Produces the sequence of candidate nonces for a private key and message hash. Usually the first one is suitable.
+pub struct Rfc6979 {
+    variant: HmacVariant,
+    q: BigUint,
+    qlen: usize,
+    k: Vec<u8>,
+    v: Vec<u8>,
+    started: bool,
+}
+
+impl Rfc6979 {
+    pub fn new(variant: HmacVariant, q: &BigUint, x: &BigUint, h1: &[u8]) -> Self {
+        let qlen = q.bits() as usize;
+        let rolen = qlen.div_ceil(8);
+        let hlen = variant.output_size() as usize;
+
+        // bits2octets(h1) reduces the hash modulo q
+        let mut z = bits2int(h1, qlen);
+        if &z >= q {
+            z -= q;
+        }
+        let x = int2octets(x, rolen);
+        let h = int2octets(&z, rolen);
+
+        let mut state = Self {
+            variant,
+            q: q.clone(),
+            qlen,
+            k: vec![0x00; hlen],
+            v: vec![0x01; hlen],
+            started: false,
+        };
+        for sep in [0x00, 0x01] {
+            state.k = state.hmac(&[&state.v, &[sep], &x, &h]);
+            state.v = state.hmac(&[&state.v]);
+        }
+        state
+    }
+
+    fn hmac(&self, parts: &[&[u8]]) -> Vec<u8> {
+        let mut h = Hmac::init(self.variant, &self.k);
+        for p in parts {
+            h.update(p);
+        }
+        h.finalize()
+    }
+
+    /// The next candidate k with 1 <= k < q
+    pub fn next_nonce(&mut self) -> BigUint {
+        loop {
+            if self.started {
+                self.k = self.hmac(&[&self.v, &[0x00]]);
+                self.v = self.hmac(&[&self.v]);
+            }
+            self.started = true;
+
+            let mut t = Vec::new();
+            while t.len() * 8 < self.qlen {
+                self.v = self.hmac(&[&self.v]);
+                t.extend_from_slice(&self.v);
+            }
+            let k = bits2int(&t, self.qlen);
+            if k.bits() > 0 && k < self.q {
+                return k;
+            }
+        }
+    }
+}
@@ -0,0 +1,272 @@
+use crypto_bigint::{
+    impl_modulus,
+    modular::{ConstMontyForm, ConstMontyParams},
+    subtle::{Choice, ConditionallySelectable},
+    Uint, U256, U448,
+};
+use utils::errors::GeneralError;
+
+// X25519 and X448 key exchange
+// https://datatracker.ietf.org/doc/html/rfc7748
+
+impl_modulus!(
+    Field25519,
+    U256,
+    "7fffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffed",
+    "The prime 2^255 - 19"
+);
+impl_modulus!(
+    Field448,
+    U448,
+    "fffffffffffffffffffffffffffffffffffffffffffffffffffffffeffffffffffffffffffffffffffffffffffffffffffffffffffffffff",
+    "The prime 2^448 - 2^224 - 1"
+);
+
+/// The u-coordinate of the base point of Curve25519
+pub const X25519_BASE: [u8; 32] = {
+    let mut b = [0; 32];
+    b[0] = 9;
+    b
+};
+
+/// The u-coordinate of the base point of Curve448
+pub const X448_BASE: [u8; 56] = {
+    let mut b = [0; 56];
+    b[0] = 5;
+    b
+};
+
+/// The Montgomery ladder of RFC 7748 section 5, computing the u-coordinate of k times the point with u-coordinate u
+/// on the curve v^2 = u^3 + A*u^2 + u, where a24 = (A - 2) / 4. Only the u-coordinate is ever needed. Every step does
+/// the same work whatever the bit of k is, the two working points are just swapped.
+fn ladder<M: ConstMontyParams<LIMBS> + Copy, const LIMBS: usize>(
+    k: &Uint<LIMBS>,
+    u: &Uint<LIMBS>,
+    a24: u64,
+    bits: u32,
+) -> Uint<LIMBS> {
+    let a24 = ConstMontyForm::<M, LIMBS>::new(&Uint::from_u64(a24));
+    let x1 = ConstMontyForm::<M, LIMBS>::new(u);
+    let mut x2 = ConstMontyForm::ONE;
+    let mut z2 = ConstMontyForm::ZERO;
+    let mut x3 = x1;
+    let mut z3 = ConstMontyForm::ONE;
+    let mut swap = Choice::from(0);
+
+    for t in (0..bits).rev() {
+        let kt = Choice::from(k.bit_vartime(t) as u8);
+        swap ^= kt;
+        ConstMontyForm::conditional_swap(&mut x2, &mut x3, swap);
+        ConstMontyForm::conditional_swap(&mut z2, &mut z3, swap);
+        swap = kt;
+
+        let a = x2 + z2;
+        let aa = a.square();
+        let b = x2 - z2;
+        let bb = b.square();
+        let e = aa - bb;
+        let c = x3 + z3;
+        let d = x3 - z3;
+        let da = d * a;
+        let cb = c * b;
+        x3 = (da + cb).square();
+        z3 = x1 * (da - cb).square();
+        x2 = aa * bb;
+        z2 = e * (aa + a24 * e);
+    }
+    ConstMontyForm::conditional_swap(&mut x2, &mut x3, swap);
+    ConstMontyForm::conditional_swap(&mut z2, &mut z3, swap);
+
+    // Return x2/z2, the inverse is found as z2^(p-2) which is zero when z2 is zero
+    let exp = M::MODULUS.wrapping_sub(&Uint::from_u8(2));
+    (x2 * z2.pow(&exp)).retrieve()
+}
+
+/// The X25519 function, scalar multiplication on Curve25519. The scalar is clamped so that it is a multiple of the
+/// cofactor 8 with its highest bit set and the top bit of the u-coordinate is ignored.
+pub fn x25519(k: &[u8; 32], u: &[u8; 32]) -> [u8; 32] {
+    let mut k = *k;
+    k[0] &= 248;
+    k[31] &= 127;
+    k[31] |= 64;
+    let mut u = *u;
+    u[31] &= 127;
+    // Non-canonical u-coordinates are accepted and reduced when converted to Montgomery form
+    ladder::<Field25519, 4>(
+        &U256::from_le_slice(&k),
+        &U256::from_le_slice(&u),
+        121665,
+        255,
+    )
+    .to_le_bytes()
+}
+
+/// The X448 function, scalar multiplication on Curve448. The scalar is clamped to be a multiple of the cofactor 4 with
+/// its highest bit set.
+pub fn x448(k: &[u8; 56], u: &[u8; 56]) -> [u8; 56] {
+    let mut k = *k;
+    k[0] &= 252;
+    k[55] |= 128;
+    ladder::<Field448, 7>(
+        &U448::from_le_slice(&k),
+        &U448::from_le_slice(u),
+        39081,
+        448,
+    )
+    .to_le_bytes()
+}
+
+pub fn x25519_public_key(private_key: &[u8; 32]) -> [u8; 32] {
+    x25519(private_key, &X25519_BASE)
+}
+
+pub fn x448_public_key(private_key: &[u8; 56]) -> [u8; 56] {
+    x448(private_key, &X448_BASE)
+}
+
+/// A peer key of small order forces the shared secret to zero no matter what the private key is, RFC 7748 allows this
+/// to be detected and rejected.
+pub fn x25519_shared_secret(
+    private_key: &[u8; 32],
+    peer: &[u8; 32],
+) -> Result<[u8; 32], GeneralError> {
+    let shared = x25519(private_key, peer);
+    if shared.iter().all(|b| *b == 0) {
+        return Err(GeneralError::key("peer public key has small order"));
+    }
+    Ok(shared)
+}
+
+pub fn x448_shared_secret(
+    private_key: &[u8; 56],
+    peer: &[u8; 56],
+) -> Result<[u8; 56], GeneralError> {
+    let shared = x448(private_key, peer);
+    if shared.iter().all(|b| *b == 0) {
+        return Err(GeneralError::key("peer public key has small order"));
+    }
+    Ok(shared)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::digital::stream_ciphers::secretbox::SecretBox;
+    use hex_literal::hex;
+
+    #[test]
+    fn x25519_vectors() {
+        assert_eq!(
+            hex!("c3da55379de9c6908e94ea4df28d084f32eccf03491c71f754b4075577a28552"),
+            x25519(
+                &hex!("a546e36bf0527c9d3b16154b82465edd62144c0ac1fc5a18506a2244ba449ac4"),
+                &hex!("e6db6867583030db3594c1a424b15f7c726624ec26b3353b10a903a6d0ab1c4c")
+            )
+        );
+        // The top bit of this u-coordinate is set and must be ignored
+        assert_eq!(
+            hex!("95cbde9476e8907d7aade45cb4b873f88b595a68799fa152e6f8f7647aac7957"),
+            x25519(
+                &hex!("4b66e9d4d1b4673c5ad22691957d6af5c11b6421e0ea01d42ca4169e7918ba0d"),
+                &hex!("e5210f12786811d3f4b7959d0538ae2c31dbe7106fc03c3efc4cd549c715a493")
+            )
+        );
+    }
+
+    #[test]
+    fn x448_vectors() {
+        assert_eq!(
+            hex!("ce3e4ff95a60dc6697da1db1d85e6afbdf79b50a2412d7546d5f239fe14fbaadeb445fc66a01b0779d98223961111e21766282f73dd96b6f"),
+            x448(
+                &hex!("3d262fddf9ec8e88495266fea19a34d28882acef045104d0d1aae121700a779c984c24f8cdd78fbff44943eba368f54b29259a4f1c600ad3"),
+                &hex!("06fce640fa3487bfda5f6cf2d5263f8aad88334cbd07437f020f08f9814dc031ddbdc38c19c6da2583fa5429db94ada18aa7a7fb4ef8a086")
+            )
+        );
+    }
+
+    // Section 5.2, the output of each step becomes the next scalar and the previous scalar the next u-coordinate
+    #[test]
+    fn iterated() {
+        let mut k = X25519_BASE;
+        let mut u = X25519_BASE;
+        for _ in 0..1000 {
+            (k, u) = (x25519(&k, &u), k);
+        }
+        assert_eq!(
+            hex!("684cf59ba83309552800ef566f2f4d3c1c3887c49360e3875f2eb94d99532c51"),
+            k
+        );
+
+        let mut k = X448_BASE;
+        let mut u = X448_BASE;
+        (k, u) = (x448(&k, &u), k);
+        assert_eq!(
+            hex!("3f482c8a9f19b01e6c46ee9711d9dc14fd4bf67af30765c2ae2b846a4d23a8cd0db897086239492caf350b51f833868b9bc2b3bca9cf4113"),
+            k
+        );
+        for _ in 1..1000 {
+            (k, u) = (x448(&k, &u), k);
+        }
+        assert_eq!(
+            hex!("aa3b4749d55b9daf1e5b00288826c467274ce3ebbdd5c17b975e09d4af6c67cf10d087202db88286e2b79fceea3ec353ef54faa26e219f38"),
+            k
+        );
+    }
+
+    // Section 6
+    #[test]
+    fn key_exchange() {
+        let alice = hex!("77076d0a7318a57d3c16c17251b26645df4c2f87ebc0992ab177fba51db92c2a");
+        let bob = hex!("5dab087e624a8a4b79e17f8b83800ee66f3bb1292618b6fd1c2f8b27ff88e0eb");
+        assert_eq!(
+            hex!("8520f0098930a754748b7ddcb43ef75a0dbf3a0d26381af4eba4a98eaa9b4e6a"),
+            x25519_public_key(&alice)
+        );
+        assert_eq!(
+            hex!("de9edb7d7b7dc1b4d35b61c2ece435373f8343c85b78674dadfc7e146f882b4f"),
+            x25519_public_key(&bob)
+        );
+        let shared = x25519_shared_secret(&alice, &x25519_public_key(&bob)).unwrap();
+        assert_eq!(
+            hex!("4a5d9d5ba4ce2de1728e3bf480350f25e07e21c947d19e3376f09b3c1e161742"),
+            shared
+        );
+        assert_eq!(
+            shared,
+            x25519_shared_secret(&bob, &x25519_public_key(&alice)).unwrap()
+        );
+
+        let alice = hex!("9a8f4925d1519f5775cf46b04b5800d4ee9ee8bae8bc5565d498c28dd9c9baf574a9419744897391006382a6f127ab1d9ac2d8c0a598726b");
+        let bob = hex!("1c306a7ac2a0e2e0990b294470cba339e6453772b075811d8fad0d1d6927c120bb5ee8972b0d3e21374c9c921b09d1b0366f10b65173992d");
+        assert_eq!(
+            hex!("07fff4181ac6cc95ec1c16a94a0f74d12da232ce40a77552281d282bb60c0b56fd2464c335543936521c24403085d59a449a5037514a879d"),
+            x448_shared_secret(&alice, &x448_public_key(&bob)).unwrap()
+        );
+    }
+
+    #[test]
+    fn small_order_rejected() {
+        let alice = hex!("77076d0a7318a57d3c16c17251b26645df4c2f87ebc0992ab177fba51db92c2a");
+        assert!(x25519_shared_secret(&alice, &[0; 32]).is_err());
+        let mut one = [0; 32];
+        one[0] = 1;
+        assert!(x25519_shared_secret(&alice, &one).is_err());
+    }
+
+    // The NaCl crypto_box example is X25519 followed by the secretbox precomputation
+    #[test]
+    fn crypto_box() {
+        let alice = hex!("77076d0a7318a57d3c16c17251b26645df4c2f87ebc0992ab177fba51db92c2a");
+        let bob_public = hex!("de9edb7d7b7dc1b4d35b61c2ece435373f8343c85b78674dadfc7e146f882b4f");
+        let shared = x25519_shared_secret(&alice, &bob_public).unwrap();
+        let nonce = hex!("69696ee955b62b73cd62bda875fc73d68219e0036b7a0b37");
+        let a = SecretBox::from_shared_secret(shared, nonce).encrypt_bytes(b"attack at dawn");
+        let b = SecretBox::from_shared_secret(
+            hex!("4a5d9d5ba4ce2de1728e3bf480350f25e07e21c947d19e3376f09b3c1e161742"),
+            nonce,
+        )
+        .encrypt_bytes(b"attack at dawn");
+        assert_eq!(a, b);
+    }
+}
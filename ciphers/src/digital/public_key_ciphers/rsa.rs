@@ -1,8 +1,26 @@
-use num::{bigint::ToBigUint, BigUint, Integer};
-use utils::{byte_formatting::ByteFormat, errors::GeneralError, math_functions::mul_inv};
+use super::rsa_padding::{
+    oaep_decode, oaep_encode, pkcs1_encryption_decode, pkcs1_encryption_encode,
+    pkcs1_signature_encode, pss_encode, pss_verify,
+};
+use hashers::sha::sha2::Sha2Variant;
+use num::{bigint::ToBigUint, BigUint, Integer, One, Zero};
+use rand::{thread_rng, Rng};
+use utils::{
+    byte_formatting::ByteFormat,
+    errors::GeneralError,
+    math_functions::mul_inv,
+    primality::{is_probable_prime, random_prime},
+};
 
 use crate::Cipher;
 
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum RsaPadding {
+    None,
+    Pkcs1,
+    Oaep,
+}
+
 pub struct Rsa {
     pub input_format: ByteFormat,
     pub output_format: ByteFormat,
@@ -11,6 +29,17 @@ pub struct Rsa {
     pub d: BigUint,
     pub e: BigUint,
     pub lambda: BigUint,
+
+    // Values for decryption by the Chinese remainder theorem
+    pub p: BigUint,
+    pub q: BigUint,
+    pub dp: BigUint,
+    pub dq: BigUint,
+    pub qinv: BigUint,
+
+    pub padding: RsaPadding,
+    pub hash: Sha2Variant,
+    pub label: Vec<u8>,
 }
 
 impl Default for Rsa {
@@ -23,24 +52,90 @@ impl Default for Rsa {
             d: BigUint::default(),
             e: BigUint::from(65537_u32), // a small prime constant
             lambda: BigUint::default(),
+
+            p: BigUint::default(),
+            q: BigUint::default(),
+            dp: BigUint::default(),
+            dq: BigUint::default(),
+            qinv: BigUint::default(),
+
+            padding: RsaPadding::None,
+            hash: Sha2Variant::Sha256,
+            label: Vec::new(),
         }
     }
 }
 
+fn inverse(a: &BigUint, m: &BigUint) -> Option<BigUint> {
+    mul_inv(a, m).and_then(|x| BigUint::try_from(x).ok())
+}
+
+// Big endian encoding of x in exactly len bytes
+fn i2osp(x: &BigUint, len: usize) -> Result<Vec<u8>, GeneralError> {
+    let bytes = x.to_bytes_be();
+    if bytes.len() > len {
+        return Err(GeneralError::input("integer too large"));
+    }
+    let mut out = vec![0; len - bytes.len()];
+    out.extend(bytes);
+    Ok(out)
+}
+
 impl Rsa {
-    pub fn set_key<N: ToBigUint>(&mut self, p: &N, q: &N) {
-        let p = p.to_biguint().expect("p could not be converted to BigUint");
-        let q = q.to_biguint().expect("q could not be converted to BigUint");
-        let n = &p * &q;
-        let one = &BigUint::from(1_u32);
+    pub fn set_key<N: ToBigUint>(&mut self, p: &N, q: &N) -> Result<(), GeneralError> {
+        let p = p
+            .to_biguint()
+            .ok_or(GeneralError::key("p could not be converted to BigUint"))?;
+        let q = q
+            .to_biguint()
+            .ok_or(GeneralError::key("q could not be converted to BigUint"))?;
+        if p == q {
+            return Err(GeneralError::key("p and q must be distinct"));
+        }
+        let mut rng = thread_rng();
+        if !is_probable_prime(&p, 20, &mut rng) {
+            return Err(GeneralError::key("p is not prime"));
+        }
+        if !is_probable_prime(&q, 20, &mut rng) {
+            return Err(GeneralError::key("q is not prime"));
+        }
+
+        let one = &BigUint::one();
         let lambda = (&p - one).lcm(&(&q - one));
-        let d = mul_inv(&self.e, &lambda)
-            .expect("modular multiplicative inverse could not be computed");
+        let d = inverse(&self.e, &lambda)
+            .ok_or(GeneralError::key("e must be coprime to both p-1 and q-1"))?;
 
-        self.n = n;
+        self.dp = &d % (&p - one);
+        self.dq = &d % (&q - one);
+        self.qinv = inverse(&q, &p).expect("distinct primes are always coprime");
+        self.n = &p * &q;
         self.lambda = lambda;
-        self.d = BigUint::try_from(d)
-            .expect("modular multiplicative inverse could not convert to BigUint");
+        self.d = d;
+        self.p = p;
+        self.q = q;
+        Ok(())
+    }
+
+    /// Generate a key with a modulus of exactly the given number of bits
+    pub fn generate_key<R: Rng>(&mut self, bits: u64, rng: &mut R) -> Result<(), GeneralError> {
+        if bits < 16 {
+            return Err(GeneralError::key("modulus must be at least 16 bits"));
+        }
+        let one = BigUint::one();
+        let mut prime = |bits| loop {
+            let p = random_prime(bits, rng);
+            if (&p - &one).gcd(&self.e).is_one() {
+                return p;
+            }
+        };
+        let p = prime(bits / 2);
+        let q = loop {
+            let q = prime(bits - bits / 2);
+            if q != p {
+                break q;
+            }
+        };
+        self.set_key(&p, &q)
     }
 
     // Returns n and e
@@ -53,43 +148,182 @@ impl Rsa {
         (&self.n, &self.d)
     }
 
-    pub fn encrypt_bytes(&self, bytes: &[u8]) -> Result<Vec<u8>, GeneralError> {
-        let m = BigUint::from_bytes_be(bytes);
-        if m > self.n {
+    /// Length of the modulus in bytes
+    pub fn modulus_len(&self) -> usize {
+        self.n.bits().div_ceil(8) as usize
+    }
+
+    pub fn public_op(&self, m: &BigUint) -> Result<BigUint, GeneralError> {
+        if self.n.is_zero() {
+            return Err(GeneralError::state("key has not been set"));
+        }
+        if m >= &self.n {
+            return Err(GeneralError::input("message representative out of range"));
+        }
+        Ok(m.modpow(&self.e, &self.n))
+    }
+
+    /// Exponentiation by d, performed modulo p and q separately then combined using Garner's formula. This is about
+    /// four times faster than working modulo n.
+    pub fn private_op(&self, c: &BigUint) -> Result<BigUint, GeneralError> {
+        if self.n.is_zero() {
+            return Err(GeneralError::state("key has not been set"));
+        }
+        if c >= &self.n {
             return Err(GeneralError::input(
-                "message length cannot be greater than group size",
+                "ciphertext representative out of range",
             ));
-        };
-        Ok(m.modpow(&self.e, &self.n).to_bytes_be())
+        }
+        let m1 = c.modpow(&self.dp, &self.p);
+        let m2 = c.modpow(&self.dq, &self.q);
+        let h = (&self.qinv * (&m1 + &self.p - (&m2 % &self.p))) % &self.p;
+        Ok(m2 + h * &self.q)
+    }
+
+    /// Textbook RSA with no padding
+    pub fn encrypt_bytes(&self, bytes: &[u8]) -> Result<Vec<u8>, GeneralError> {
+        let c = self.public_op(&BigUint::from_bytes_be(bytes))?;
+        i2osp(&c, self.modulus_len())
     }
 
+    /// Textbook RSA with no padding
     pub fn decrypt_bytes(&self, bytes: &[u8]) -> Result<Vec<u8>, GeneralError> {
-        let c = BigUint::from_bytes_be(bytes);
-        if c > self.n {
-            return Err(GeneralError::input(
-                "message length cannot be greater than group size",
-            ));
+        Ok(self
+            .private_op(&BigUint::from_bytes_be(bytes))?
+            .to_bytes_be())
+    }
+
+    // Apply the private key and give the result as exactly k bytes, the form the decoders expect
+    fn decrypt_to_block(&self, bytes: &[u8]) -> Result<Vec<u8>, GeneralError> {
+        let k = self.modulus_len();
+        if bytes.len() != k {
+            return Err(GeneralError::input("decryption error"));
+        }
+        let m = self.private_op(&BigUint::from_bytes_be(bytes))?;
+        i2osp(&m, k)
+    }
+
+    pub fn encrypt_pkcs1<R: Rng>(&self, msg: &[u8], rng: &mut R) -> Result<Vec<u8>, GeneralError> {
+        let em = pkcs1_encryption_encode(msg, self.modulus_len(), rng)?;
+        self.encrypt_bytes(&em)
+    }
+
+    pub fn decrypt_pkcs1(&self, bytes: &[u8]) -> Result<Vec<u8>, GeneralError> {
+        pkcs1_encryption_decode(&self.decrypt_to_block(bytes)?)
+    }
+
+    pub fn encrypt_oaep<R: Rng>(
+        &self,
+        msg: &[u8],
+        label: &[u8],
+        hash: Sha2Variant,
+        rng: &mut R,
+    ) -> Result<Vec<u8>, GeneralError> {
+        let em = oaep_encode(msg, label, self.modulus_len(), hash, rng)?;
+        self.encrypt_bytes(&em)
+    }
+
+    pub fn decrypt_oaep(
+        &self,
+        bytes: &[u8],
+        label: &[u8],
+        hash: Sha2Variant,
+    ) -> Result<Vec<u8>, GeneralError> {
+        oaep_decode(
+            &self.decrypt_to_block(bytes)?,
+            label,
+            self.modulus_len(),
+            hash,
+        )
+    }
+
+    pub fn sign_pkcs1(&self, msg: &[u8], hash: Sha2Variant) -> Result<Vec<u8>, GeneralError> {
+        let k = self.modulus_len();
+        let em = pkcs1_signature_encode(msg, k, hash)?;
+        i2osp(&self.private_op(&BigUint::from_bytes_be(&em))?, k)
+    }
+
+    pub fn verify_pkcs1(&self, msg: &[u8], signature: &[u8], hash: Sha2Variant) -> bool {
+        let k = self.modulus_len();
+        if signature.len() != k {
+            return false;
+        }
+        let Ok(m) = self.public_op(&BigUint::from_bytes_be(signature)) else {
+            return false;
         };
-        Ok(c.modpow(&self.d, &self.n).to_bytes_be())
+        // Comparing the whole encoding, rather than parsing it, avoids the classic forgeries against lax parsers
+        match (i2osp(&m, k), pkcs1_signature_encode(msg, k, hash)) {
+            (Ok(em), Ok(expected)) => em == expected,
+            _ => false,
+        }
+    }
+
+    // The encoded message for PSS has one bit fewer than the modulus so that it is always smaller
+    fn em_bits(&self) -> usize {
+        self.n.bits() as usize - 1
+    }
+
+    pub fn sign_pss<R: Rng>(
+        &self,
+        msg: &[u8],
+        hash: Sha2Variant,
+        salt_len: usize,
+        rng: &mut R,
+    ) -> Result<Vec<u8>, GeneralError> {
+        let em = pss_encode(msg, self.em_bits(), hash, salt_len, rng)?;
+        i2osp(
+            &self.private_op(&BigUint::from_bytes_be(&em))?,
+            self.modulus_len(),
+        )
+    }
+
+    pub fn verify_pss(
+        &self,
+        msg: &[u8],
+        signature: &[u8],
+        hash: Sha2Variant,
+        salt_len: usize,
+    ) -> bool {
+        if signature.len() != self.modulus_len() {
+            return false;
+        }
+        let Ok(m) = self.public_op(&BigUint::from_bytes_be(signature)) else {
+            return false;
+        };
+        let em_bits = self.em_bits();
+        match i2osp(&m, em_bits.div_ceil(8)) {
+            Ok(em) => pss_verify(msg, &em, em_bits, hash, salt_len),
+            Err(_) => false,
+        }
     }
 }
 
 impl Cipher for Rsa {
     fn encrypt(&self, text: &str) -> Result<String, GeneralError> {
-        let mut bytes = self
+        let bytes = self
             .input_format
             .text_to_bytes(text)
             .map_err(|_| GeneralError::input("byte format error"))?;
-        let out = self.encrypt_bytes(&mut bytes)?;
+        let out = match self.padding {
+            RsaPadding::None => self.encrypt_bytes(&bytes)?,
+            RsaPadding::Pkcs1 => self.encrypt_pkcs1(&bytes, &mut thread_rng())?,
+            RsaPadding::Oaep => {
+                self.encrypt_oaep(&bytes, &self.label, self.hash, &mut thread_rng())?
+            }
+        };
         Ok(self.output_format.byte_slice_to_text(&out))
     }
 
     fn decrypt(&self, text: &str) -> Result<String, GeneralError> {
-        let mut bytes = self
+        let bytes = self
             .input_format
             .text_to_bytes(text)
             .map_err(|_| GeneralError::input("byte format error"))?;
-        let out = self.decrypt_bytes(&mut bytes)?;
+        let out = match self.padding {
+            RsaPadding::None => self.decrypt_bytes(&bytes)?,
+            RsaPadding::Pkcs1 => self.decrypt_pkcs1(&bytes)?,
+            RsaPadding::Oaep => self.decrypt_oaep(&bytes, &self.label, self.hash)?,
+        };
         Ok(self.output_format.byte_slice_to_text(&out))
     }
 }
@@ -98,19 +332,274 @@ impl Cipher for Rsa {
 mod rsa_tests {
 
     use super::*;
+    use hex_literal::hex;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    const MESSAGE: &[u8] = b"The magic words are squeamish ossifrage";
+
+    // A 1024-bit key and outputs produced with OpenSSL
+    const P: [u8; 64] = hex!(
+        "e89a9c73785bb01cffa2561448b804ce29cd7b50433a822e7c17727013ba5e9d"
+        "6517f8217c7af2d1c4f074fdaaa7b072c010d327e1e9a6e1fbb80cff5b4aa023"
+    );
+    const Q: [u8; 64] = hex!(
+        "dcfb578468c90a29cd145468008736cf30e7adc3c39d6a80ead648fab8d99ab5"
+        "644c7365900d65668986138e658c9bef38953a96cfedf80e9af4a38f5016960d"
+    );
+
+    fn cipher() -> Rsa {
+        let mut cipher = Rsa::default();
+        cipher
+            .set_key(&BigUint::from_bytes_be(&P), &BigUint::from_bytes_be(&Q))
+            .unwrap();
+        cipher
+    }
 
     #[test]
     fn test_ksa() {
-        let mut cipher = Rsa::default();
-        cipher.set_key(&5623, &5869);
+        let mut small = Rsa::default();
+        small.set_key(&5623, &5869).unwrap();
+        assert!(small.set_key(&5623, &5623).is_err());
+        assert!(small.set_key(&5623, &5865).is_err());
+
+        let cipher = cipher();
+        assert_eq!(
+            BigUint::from_bytes_be(&hex!(
+                "c8c93d8f7abf30df950368c0a48cd8b65bc366966d6cf8ab10a063ced948976b"
+                "49841f262d791a014ca6b64536ee2552462b9440edfe1de75de373b5c3058f7c"
+                "0571f6445d0d1d13df17b36671d0191b1d8a988008ad9780d9fef9c8ac5a9aa7"
+                "f075847712a4b097f428ffa588e3b6910672f8dbf9bc73fcbc712f770fa0a3c7"
+            )),
+            cipher.n
+        );
+        assert_eq!(
+            BigUint::from_bytes_be(&hex!(
+                "59f7fb8dba3f14a692c632af53d5419e9092b720bb70350c4fd92ddd65f40f46"
+                "a7e496883542c50b1836bdb9a4398a49483af9c1926332b6740ba3935db746ab"
+            )),
+            cipher.dp
+        );
+        assert_eq!(
+            BigUint::from_bytes_be(&hex!(
+                "cf9796299f3e6d3bc39a3b88d02635c9020a42e4cad9107a372242c0b5f24631"
+                "ed1d1cb2e6568f517a13e83f370a965a6e6f51f0e738887540005dbe7de019e9"
+            )),
+            cipher.dq
+        );
+        assert_eq!(
+            BigUint::from_bytes_be(&hex!(
+                "7508530c056ec929e2d492cbc521a78d70bea59173ed23b327d12337333de4dd"
+                "4bbfba798e0593d74d72f1b950d1b48aceba1f1af3b751ef5770cb08807469a3"
+            )),
+            cipher.qinv
+        );
     }
 
     #[test]
     fn encrypt_decrypt() {
         let mut cipher = Rsa::default();
-        cipher.set_key(&5623, &5869);
+        cipher.set_key(&5623, &5869).unwrap();
         let ptext = "010203";
         let ctext = cipher.encrypt(ptext).unwrap();
         assert_eq!(cipher.decrypt(&ctext).unwrap(), ptext);
     }
+
+    #[test]
+    fn crt_matches_textbook() {
+        let cipher = cipher();
+        let c = BigUint::from_bytes_be(&hex!(
+            "158e5a99c804cf9dd95141f26ccc02f6bcd1eaf4689d83987ef89ba43acde2f8"
+            "6bcbbd86ec4460b0f3bfb29681fcbf6041b0937f30be498eb52a5ed083f4f1e0"
+            "e342d588fbf15e88757603f1318401f1b99fa127bf8fdf0661cb13f0c0cdaa09"
+            "1984a1a61d8f10600296baadbc64ad66e752f291ebcd68043aeaaf054ff4e5f7"
+        ));
+        assert_eq!(
+            c.modpow(&cipher.d, &cipher.n),
+            cipher.private_op(&c).unwrap()
+        );
+    }
+
+    #[test]
+    fn pkcs1_signature() {
+        let cipher = cipher();
+        let sig = cipher.sign_pkcs1(MESSAGE, Sha2Variant::Sha256).unwrap();
+        assert_eq!(
+            hex!(
+                "183086a3ea763a382dfa4270409ae6ee32be94a5906b5045869b8b44b20e4a9a"
+                "4b00c437f70a5a081038a4aa73d582c5e53df0d1ca983c658e6cc557f0277160"
+                "2a6f391a17131c0e6877a73ce5d1e26ebb5a1c78eee06e0cd8b9739acf6bc339"
+                "0680960e630a22e7c069cd4ac85b6b1c67317b62c9dee136e81cfa17af5595ba"
+            )
+            .to_vec(),
+            sig
+        );
+        assert!(cipher.verify_pkcs1(MESSAGE, &sig, Sha2Variant::Sha256));
+        assert!(!cipher.verify_pkcs1(b"The magic words", &sig, Sha2Variant::Sha256));
+        assert!(!cipher.verify_pkcs1(MESSAGE, &sig, Sha2Variant::Sha512));
+
+        let sig = cipher.sign_pkcs1(MESSAGE, Sha2Variant::Sha512).unwrap();
+        assert_eq!(
+            hex!(
+                "674c35475209ff2854a2a944bd20b2b153a1c67d79de8b77b733805d594b7749"
+                "02d6cec3e202303fe8cd1fb2072b9fbfb44d8e97eb4e9d4136435cf819e6a3e7"
+                "fcc20916d87271abc59f83f49af760568c907b0e03afba6b7f72cef167733b87"
+                "e71b5304100e74c9603b9b628ed560f4d79f8ff5eb51427a172e466ac9881bf7"
+            )
+            .to_vec(),
+            sig
+        );
+    }
+
+    #[test]
+    fn pss_signature() {
+        let cipher = cipher();
+        let sig = hex!(
+            "71fdc38eb314fecfc2ed7ece3a7e0f619c10d615085e5a1bde6c1dc4b99221e0"
+            "d443ea4c05677abc444953fae9e6d89912a5f2b645fdee631a01aee7bb0df4d4"
+            "28271494639c0f439c2a516355756c89f9067db29fb567478f3fe114f94bec0f"
+            "a4b3464853035a2ef6b031e95d2d562933ffd439a80226ebbe72017b3c554f00"
+        );
+        assert!(cipher.verify_pss(MESSAGE, &sig, Sha2Variant::Sha256, 32));
+        assert!(!cipher.verify_pss(MESSAGE, &sig, Sha2Variant::Sha256, 20));
+
+        let mut rng = StdRng::seed_from_u64(41);
+        let mut sig = cipher
+            .sign_pss(MESSAGE, Sha2Variant::Sha384, 20, &mut rng)
+            .unwrap();
+        assert!(cipher.verify_pss(MESSAGE, &sig, Sha2Variant::Sha384, 20));
+        sig[5] ^= 1;
+        assert!(!cipher.verify_pss(MESSAGE, &sig, Sha2Variant::Sha384, 20));
+    }
+
+    #[test]
+    fn oaep_encryption() {
+        let cipher = cipher();
+        let ctext = hex!(
+            "4d9e6d9422371cbff0f8719c13122101a72bda6d63e6d0f4e89f41c509b0dd58"
+            "b943fcefd730025d6626a977c2bad5af3325b93e4d6f8dcf54048357b207e5e8"
+            "1b853ac9e1899095c3f470ce48631cdf553ea556926f39d49338a785cf9c463d"
+            "3c5302bb788847fcd3efe458b8c295f05f868e19f73e89fe82752e74bf25e579"
+        );
+        assert_eq!(
+            MESSAGE,
+            cipher
+                .decrypt_oaep(&ctext, b"label", Sha2Variant::Sha256)
+                .unwrap()
+        );
+        assert!(cipher
+            .decrypt_oaep(&ctext, b"other", Sha2Variant::Sha256)
+            .is_err());
+
+        let mut rng = StdRng::seed_from_u64(41);
+        let ctext = cipher
+            .encrypt_oaep(MESSAGE, b"abc", Sha2Variant::Sha256, &mut rng)
+            .unwrap();
+        assert_eq!(
+            MESSAGE,
+            cipher
+                .decrypt_oaep(&ctext, b"abc", Sha2Variant::Sha256)
+                .unwrap()
+        );
+        // A message longer than k - 2*hLen - 2 bytes does not fit
+        assert!(cipher
+            .encrypt_oaep(&[0; 63], b"", Sha2Variant::Sha256, &mut rng)
+            .is_err());
+    }
+
+    #[test]
+    fn pkcs1_encryption() {
+        let cipher = cipher();
+        let ctext = hex!(
+            "158e5a99c804cf9dd95141f26ccc02f6bcd1eaf4689d83987ef89ba43acde2f8"
+            "6bcbbd86ec4460b0f3bfb29681fcbf6041b0937f30be498eb52a5ed083f4f1e0"
+            "e342d588fbf15e88757603f1318401f1b99fa127bf8fdf0661cb13f0c0cdaa09"
+            "1984a1a61d8f10600296baadbc64ad66e752f291ebcd68043aeaaf054ff4e5f7"
+        );
+        assert_eq!(MESSAGE, cipher.decrypt_pkcs1(&ctext).unwrap());
+
+        let mut rng = StdRng::seed_from_u64(41);
+        let ctext = cipher.encrypt_pkcs1(MESSAGE, &mut rng).unwrap();
+        assert_eq!(MESSAGE, cipher.decrypt_pkcs1(&ctext).unwrap());
+        assert!(cipher
+            .decrypt_oaep(&ctext, b"", Sha2Variant::Sha256)
+            .is_err());
+    }
+
+    // Malformed signatures and ciphertexts of the kinds that Wycheproof checks for
+    #[test]
+    fn malformed_inputs() {
+        let mut rng = StdRng::seed_from_u64(41);
+        let cipher = cipher();
+        let k = cipher.modulus_len();
+        let n_bytes = cipher.n.to_bytes_be();
+        let sig = cipher.sign_pkcs1(MESSAGE, Sha2Variant::Sha256).unwrap();
+
+        let mut long = vec![0];
+        long.extend(&sig);
+        assert!(!cipher.verify_pkcs1(MESSAGE, &long, Sha2Variant::Sha256));
+        assert!(!cipher.verify_pkcs1(MESSAGE, &sig[1..], Sha2Variant::Sha256));
+        assert!(!cipher.verify_pkcs1(MESSAGE, &vec![0; k], Sha2Variant::Sha256));
+        assert!(!cipher.verify_pkcs1(MESSAGE, &n_bytes, Sha2Variant::Sha256));
+        let shifted = (BigUint::from_bytes_be(&sig) + &cipher.n).to_bytes_be();
+        assert!(!cipher.verify_pkcs1(MESSAGE, &shifted, Sha2Variant::Sha256));
+
+        // Bleichenbacher's 2006 forgery shape, a short run of 0xff with garbage after the digest
+        let em = pkcs1_signature_encode(MESSAGE, k, Sha2Variant::Sha256).unwrap();
+        let tail = &em[em.len() - 51..];
+        let mut forged = vec![
+            0x00, 0x01, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x00,
+        ];
+        forged.extend(tail);
+        forged.resize(k, 0xab);
+        let forged_sig = i2osp(
+            &cipher.private_op(&BigUint::from_bytes_be(&forged)).unwrap(),
+            k,
+        )
+        .unwrap();
+        assert!(!cipher.verify_pkcs1(MESSAGE, &forged_sig, Sha2Variant::Sha256));
+
+        let pss = cipher
+            .sign_pss(MESSAGE, Sha2Variant::Sha256, 32, &mut rng)
+            .unwrap();
+        assert!(!cipher.verify_pss(MESSAGE, &pss[1..], Sha2Variant::Sha256, 32));
+        assert!(!cipher.verify_pss(MESSAGE, &n_bytes, Sha2Variant::Sha256, 32));
+
+        let ctext = cipher
+            .encrypt_oaep(MESSAGE, b"", Sha2Variant::Sha256, &mut rng)
+            .unwrap();
+        assert!(cipher
+            .decrypt_oaep(&ctext[1..], b"", Sha2Variant::Sha256)
+            .is_err());
+        assert!(cipher
+            .decrypt_oaep(&n_bytes, b"", Sha2Variant::Sha256)
+            .is_err());
+        let mut tampered = ctext.clone();
+        tampered[k / 2] ^= 0x01;
+        assert!(cipher
+            .decrypt_oaep(&tampered, b"", Sha2Variant::Sha256)
+            .is_err());
+        assert!(cipher.decrypt_pkcs1(&ctext[1..]).is_err());
+    }
+
+    #[test]
+    fn generated_key() {
+        let mut rng = StdRng::seed_from_u64(41);
+        let mut cipher = Rsa::default();
+        cipher.generate_key(1024, &mut rng).unwrap();
+        assert_eq!(1024, cipher.n.bits());
+
+        let sig = cipher
+            .sign_pss(MESSAGE, Sha2Variant::Sha256, 32, &mut rng)
+            .unwrap();
+        assert!(cipher.verify_pss(MESSAGE, &sig, Sha2Variant::Sha256, 32));
+        let ctext = cipher
+            .encrypt_oaep(MESSAGE, b"", Sha2Variant::Sha256, &mut rng)
+            .unwrap();
+        assert_eq!(
+            MESSAGE,
+            cipher
+                .decrypt_oaep(&ctext, b"", Sha2Variant::Sha256)
+                .unwrap()
+        );
+    }
 }
@@ -0,0 +1,96 @@
+use super::{rfc6979::int2octets, rsa::Rsa, rsa_padding::pkcs1_signature_encode};
+use hashers::sha::sha2::Sha2Variant;
+use num::{integer::gcd, BigUint, One};
+use rand::Rng;
+use utils::{errors::GeneralError, math_functions::mul_inv, primality::random_biguint_below};
+
+// Chaum's blind signatures. The requester multiplies the encoded message by r^e for a random r, the signer applies
+// the private key without learning anything about the message, and the requester divides the result by r. What is
+// left is an ordinary PKCS#1 v1.5 signature that cannot be linked to the request the signer saw. Because the signer
+// will sign anything the key used should never be used for another purpose.
+// https://doi.org/10.1007/978-1-4757-0602-4_18
+
+/// Requester: blind the PKCS#1 v1.5 encoding of the message. Returns the blinded message to send to the signer and
+/// the secret inverse of r needed to unblind the reply.
+pub fn blind<R: Rng>(
+    rsa: &Rsa,
+    msg: &[u8],
+    hash: Sha2Variant,
+    rng: &mut R,
+) -> Result<(Vec<u8>, BigUint), GeneralError> {
+    let k = rsa.modulus_len();
+    let m = BigUint::from_bytes_be(&pkcs1_signature_encode(msg, k, hash)?);
+    let r = loop {
+        let r = random_biguint_below(&(&rsa.n - 2_u32), rng) + 2_u32;
+        if gcd(r.clone(), rsa.n.clone()).is_one() {
+            break r;
+        }
+    };
+    let blinded = (m * rsa.public_op(&r)?) % &rsa.n;
+    let inverse = mul_inv(&r, &rsa.n)
+        .and_then(|x| x.to_biguint())
+        .expect("r is coprime to n");
+    Ok((int2octets(&blinded, k), inverse))
+}
+
+/// Signer: apply the private key to a blinded message
+pub fn sign_blinded(rsa: &Rsa, blinded: &[u8]) -> Result<Vec<u8>, GeneralError> {
+    let k = rsa.modulus_len();
+    if blinded.len() != k {
+        return Err(GeneralError::input("blinded message has the wrong length"));
+    }
+    Ok(int2octets(
+        &rsa.private_op(&BigUint::from_bytes_be(blinded))?,
+        k,
+    ))
+}
+
+/// Requester: remove the blinding factor to get a signature on the original message
+pub fn unblind(
+    rsa: &Rsa,
+    blind_signature: &[u8],
+    inverse: &BigUint,
+) -> Result<Vec<u8>, GeneralError> {
+    let k = rsa.modulus_len();
+    if blind_signature.len() != k {
+        return Err(GeneralError::input("blind signature has the wrong length"));
+    }
+    let s = (BigUint::from_bytes_be(blind_signature) * inverse) % &rsa.n;
+    Ok(int2octets(&s, k))
+}
+
+#[cfg(test)]
+mod rsa_blind_tests {
+
+    use super::*;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn blind_signature() {
+        let mut rng = StdRng::seed_from_u64(8);
+        let mut signer = Rsa::default();
+        signer.generate_key(1024, &mut rng).unwrap();
+        let msg = b"a coin worth one dollar, serial number 0x5eed";
+
+        let (blinded, inverse) = blind(&signer, msg, Sha2Variant::Sha256, &mut rng).unwrap();
+        let blind_signature = sign_blinded(&signer, &blinded).unwrap();
+        let signature = unblind(&signer, &blind_signature, &inverse).unwrap();
+
+        // The result is exactly the signature the signer would have made had they seen the message
+        assert!(signer.verify_pkcs1(msg, &signature, Sha2Variant::Sha256));
+        assert_eq!(
+            signer.sign_pkcs1(msg, Sha2Variant::Sha256).unwrap(),
+            signature
+        );
+        // But what the signer saw is unrelated to both the message and the signature
+        assert_ne!(
+            pkcs1_signature_encode(msg, signer.modulus_len(), Sha2Variant::Sha256).unwrap(),
+            blinded
+        );
+        assert_ne!(signature, blind_signature);
+
+        // Blinding the same message again gives a different request
+        let (blinded_again, _) = blind(&signer, msg, Sha2Variant::Sha256, &mut rng).unwrap();
+        assert_ne!(blinded, blinded_again);
+    }
+}
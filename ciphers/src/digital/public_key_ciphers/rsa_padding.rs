@@ -0,0 +1,258 @@
+// Encoding methods for RSA encryption and signatures
+// https://datatracker.ietf.org/doc/html/rfc8017
+
+use hashers::{mgf1::Mgf1, sha::sha2::Sha2Variant, traits::StatefulHasher};
+use rand::Rng;
+use utils::errors::GeneralError;
+
+pub fn hash_len(hash: Sha2Variant) -> usize {
+    match hash {
+        Sha2Variant::Sha224 => 28,
+        Sha2Variant::Sha256 => 32,
+        Sha2Variant::Sha384 => 48,
+        Sha2Variant::Sha512 => 64,
+        Sha2Variant::Sha512_224 => 28,
+        Sha2Variant::Sha512_256 => 32,
+    }
+}
+
+// DER encoding of the DigestInfo header that precedes the hash in a PKCS#1 v1.5 signature
+fn digest_info_prefix(hash: Sha2Variant) -> [u8; 19] {
+    let (len, id, hlen) = match hash {
+        Sha2Variant::Sha224 => (0x2d, 0x04, 0x1c),
+        Sha2Variant::Sha256 => (0x31, 0x01, 0x20),
+        Sha2Variant::Sha384 => (0x41, 0x02, 0x30),
+        Sha2Variant::Sha512 => (0x51, 0x03, 0x40),
+        Sha2Variant::Sha512_224 => (0x2d, 0x05, 0x1c),
+        Sha2Variant::Sha512_256 => (0x31, 0x06, 0x20),
+    };
+    [
+        0x30, len, 0x30, 0x0d, 0x06, 0x09, 0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, id,
+        0x05, 0x00, 0x04, hlen,
+    ]
+}
+
+fn mgf1(seed: &[u8], len: usize, hash: Sha2Variant) -> Vec<u8> {
+    Mgf1::init(len as u32, hash).hash(seed)
+}
+
+fn xor_in_place(target: &mut [u8], mask: &[u8]) {
+    for (t, m) in target.iter_mut().zip(mask) {
+        *t ^= m
+    }
+}
+
+// Every failure to decode a ciphertext gives the same error so that the reason does not leak
+fn decryption_error() -> GeneralError {
+    GeneralError::input("decryption error")
+}
+
+/// EME-PKCS1-v1_5 encoding of a message into k bytes, 0x00 0x02 then at least eight random nonzero bytes, then 0x00
+/// and the message
+pub fn pkcs1_encryption_encode<R: Rng>(
+    msg: &[u8],
+    k: usize,
+    rng: &mut R,
+) -> Result<Vec<u8>, GeneralError> {
+    if msg.len() + 11 > k {
+        return Err(GeneralError::input("message too long"));
+    }
+    let mut out = Vec::with_capacity(k);
+    out.extend([0x00, 0x02]);
+    for _ in 0..(k - msg.len() - 3) {
+        out.push(rng.gen_range(1..=255));
+    }
+    out.push(0x00);
+    out.extend_from_slice(msg);
+    Ok(out)
+}
+
+pub fn pkcs1_encryption_decode(em: &[u8]) -> Result<Vec<u8>, GeneralError> {
+    if em.len() < 11 || em[0] != 0x00 || em[1] != 0x02 {
+        return Err(decryption_error());
+    }
+    let sep = em[2..]
+        .iter()
+        .position(|b| *b == 0x00)
+        .ok_or_else(decryption_error)?
+        + 2;
+    if sep < 10 {
+        return Err(decryption_error());
+    }
+    Ok(em[sep + 1..].to_vec())
+}
+
+/// EME-OAEP encoding with a fixed seed, which must be as long as the hash output
+pub fn oaep_encode_with_seed(
+    msg: &[u8],
+    label: &[u8],
+    k: usize,
+    hash: Sha2Variant,
+    seed: &[u8],
+) -> Result<Vec<u8>, GeneralError> {
+    let h_len = hash_len(hash);
+    if k < 2 * h_len + 2 || msg.len() > k - 2 * h_len - 2 {
+        return Err(GeneralError::input("message too long"));
+    }
+    if seed.len() != h_len {
+        return Err(GeneralError::input(
+            "seed must be the same length as the hash",
+        ));
+    }
+
+    // DB = lHash || PS || 0x01 || M
+    let mut db = hash.hash(label);
+    db.resize(k - msg.len() - h_len - 2, 0x00);
+    db.push(0x01);
+    db.extend_from_slice(msg);
+    xor_in_place(&mut db, &mgf1(seed, k - h_len - 1, hash));
+
+    let mut masked_seed = seed.to_vec();
+    xor_in_place(&mut masked_seed, &mgf1(&db, h_len, hash));
+
+    let mut out = vec![0x00];
+    out.extend(masked_seed);
+    out.extend(db);
+    Ok(out)
+}
+
+/// EME-OAEP encoding with a random seed
+pub fn oaep_encode<R: Rng>(
+    msg: &[u8],
+    label: &[u8],
+    k: usize,
+    hash: Sha2Variant,
+    rng: &mut R,
+) -> Result<Vec<u8>, GeneralError> {
+    let mut seed = vec![0; hash_len(hash)];
+    rng.fill(&mut seed[..]);
+    oaep_encode_with_seed(msg, label, k, hash, &seed)
+}
+
+pub fn oaep_decode(
+    em: &[u8],
+    label: &[u8],
+    k: usize,
+    hash: Sha2Variant,
+) -> Result<Vec<u8>, GeneralError> {
+    let h_len = hash_len(hash);
+    if em.len() != k || k < 2 * h_len + 2 {
+        return Err(decryption_error());
+    }
+
+    let (masked_seed, masked_db) = em[1..].split_at(h_len);
+    let mut seed = masked_seed.to_vec();
+    xor_in_place(&mut seed, &mgf1(masked_db, h_len, hash));
+    let mut db = masked_db.to_vec();
+    xor_in_place(&mut db, &mgf1(&seed, k - h_len - 1, hash));
+
+    let sep = db[h_len..]
+        .iter()
+        .position(|b| *b != 0x00)
+        .map(|i| i + h_len);
+    match sep {
+        Some(i) if em[0] == 0x00 && db[..h_len] == hash.hash(label) && db[i] == 0x01 => {
+            Ok(db[i + 1..].to_vec())
+        }
+        _ => Err(decryption_error()),
+    }
+}
+
+/// EMSA-PKCS1-v1_5 encoding of the hash of a message into k bytes, 0x00 0x01 then 0xff bytes, then 0x00 and the
+/// DigestInfo
+pub fn pkcs1_signature_encode(
+    msg: &[u8],
+    k: usize,
+    hash: Sha2Variant,
+) -> Result<Vec<u8>, GeneralError> {
+    let mut t = digest_info_prefix(hash).to_vec();
+    t.extend(hash.hash(msg));
+    if k < t.len() + 11 {
+        return Err(GeneralError::input(
+            "intended encoded message length too short",
+        ));
+    }
+    let mut out = vec![0x00, 0x01];
+    out.resize(k - t.len() - 1, 0xff);
+    out.push(0x00);
+    out.extend(t);
+    Ok(out)
+}
+
+/// EMSA-PSS encoding with a fixed salt. The result has em_bits bits, one fewer than the modulus.
+pub fn pss_encode_with_salt(
+    msg: &[u8],
+    em_bits: usize,
+    hash: Sha2Variant,
+    salt: &[u8],
+) -> Result<Vec<u8>, GeneralError> {
+    let h_len = hash_len(hash);
+    let em_len = em_bits.div_ceil(8);
+    if em_len < h_len + salt.len() + 2 {
+        return Err(GeneralError::input("encoding error"));
+    }
+
+    // H = Hash(0x00 x 8 || mHash || salt)
+    let mut m_prime = vec![0x00; 8];
+    m_prime.extend(hash.hash(msg));
+    m_prime.extend_from_slice(salt);
+    let h = hash.hash(&m_prime);
+
+    // DB = PS || 0x01 || salt
+    let mut db = vec![0x00; em_len - salt.len() - h_len - 2];
+    db.push(0x01);
+    db.extend_from_slice(salt);
+    xor_in_place(&mut db, &mgf1(&h, em_len - h_len - 1, hash));
+    db[0] &= 0xff >> (8 * em_len - em_bits);
+
+    let mut out = db;
+    out.extend(h);
+    out.push(0xbc);
+    Ok(out)
+}
+
+/// EMSA-PSS encoding with a random salt of the given length
+pub fn pss_encode<R: Rng>(
+    msg: &[u8],
+    em_bits: usize,
+    hash: Sha2Variant,
+    salt_len: usize,
+    rng: &mut R,
+) -> Result<Vec<u8>, GeneralError> {
+    let mut salt = vec![0; salt_len];
+    rng.fill(&mut salt[..]);
+    pss_encode_with_salt(msg, em_bits, hash, &salt)
+}
+
+pub fn pss_verify(
+    msg: &[u8],
+    em: &[u8],
+    em_bits: usize,
+    hash: Sha2Variant,
+    salt_len: usize,
+) -> bool {
+    let h_len = hash_len(hash);
+    let em_len = em_bits.div_ceil(8);
+    let top_mask = 0xff_u8 >> (8 * em_len - em_bits);
+    if em.len() != em_len || em_len < h_len + salt_len + 2 || em[em_len - 1] != 0xbc {
+        return false;
+    }
+
+    let (masked_db, h) = em[..em_len - 1].split_at(em_len - h_len - 1);
+    if masked_db[0] & !top_mask != 0 {
+        return false;
+    }
+    let mut db = masked_db.to_vec();
+    xor_in_place(&mut db, &mgf1(h, em_len - h_len - 1, hash));
+    db[0] &= top_mask;
+
+    let ps_len = em_len - h_len - salt_len - 2;
+    if db[..ps_len].iter().any(|b| *b != 0x00) || db[ps_len] != 0x01 {
+        return false;
+    }
+
+    let mut m_prime = vec![0x00; 8];
+    m_prime.extend(hash.hash(msg));
+    m_prime.extend_from_slice(&db[ps_len + 1..]);
+    hash.hash(&m_prime) == h
+}
@@ -0,0 +1,189 @@
+use super::{
+    dsa::DsaParameters,
+    rfc6979::{bits2int, int2octets, Rfc6979},
+};
+use crate::Cipher;
+use hashers::hmac::HmacVariant;
+use num::{BigUint, Zero};
+use rand::Rng;
+use utils::{byte_formatting::ByteFormat, errors::GeneralError, primality::random_biguint_below};
+
+// Schnorr signatures in a subgroup of prime order q of the integers modulo p. The signature is (e, s) where
+// e = H(g^k || m) and s = k + xe mod q. Verification recomputes g^k as g^s * y^-e.
+// https://link.springer.com/article/10.1007/BF00196725
+pub struct Schnorr {
+    pub input_format: ByteFormat,
+    pub output_format: ByteFormat,
+    pub parameters: DsaParameters,
+    pub private_key: BigUint,
+    pub public_key: BigUint,
+    pub hash: HmacVariant,
+}
+
+impl Default for Schnorr {
+    fn default() -> Self {
+        Self {
+            input_format: ByteFormat::Utf8,
+            output_format: ByteFormat::Hex,
+            parameters: DsaParameters::default(),
+            private_key: BigUint::zero(),
+            public_key: BigUint::zero(),
+            hash: HmacVariant::Sha256,
+        }
+    }
+}
+
+impl Schnorr {
+    pub fn with_parameters(mut self, parameters: DsaParameters) -> Self {
+        self.set_parameters(parameters);
+        self
+    }
+
+    /// Changing the parameters discards the keys
+    pub fn set_parameters(&mut self, parameters: DsaParameters) {
+        self.parameters = parameters;
+        self.private_key = BigUint::zero();
+        self.public_key = BigUint::zero();
+    }
+
+    /// Number of bytes used for each half of the signature
+    pub fn scalar_len(&self) -> usize {
+        self.parameters.q.bits().div_ceil(8) as usize
+    }
+
+    pub fn set_private_key(&mut self, x: &BigUint) -> Result<(), GeneralError> {
+        if x.is_zero() || *x >= self.parameters.q {
+            return Err(GeneralError::key("private key must be between 1 and q-1"));
+        }
+        self.private_key = x.clone();
+        self.public_key = self.parameters.g.modpow(x, &self.parameters.p);
+        Ok(())
+    }
+
+    pub fn generate_key<R: Rng>(&mut self, rng: &mut R) {
+        let x = random_biguint_below(&(&self.parameters.q - 1_u32), rng) + 1_u32;
+        self.set_private_key(&x)
+            .expect("generated key is always in range");
+    }
+
+    /// The challenge e = H(r || m) reduced modulo q, with r encoded to the length of p
+    pub fn challenge(&self, r: &BigUint, msg: &[u8]) -> BigUint {
+        let DsaParameters { p, q, .. } = &self.parameters;
+        let mut input = int2octets(r, p.bits().div_ceil(8) as usize);
+        input.extend_from_slice(msg);
+        bits2int(&self.hash.hash(&input), q.bits() as usize) % q
+    }
+
+    /// Sign with a chosen nonce k. As with DSA a reused nonce reveals the private key.
+    pub fn sign_with_nonce(
+        &self,
+        msg: &[u8],
+        k: &BigUint,
+    ) -> Result<(BigUint, BigUint), GeneralError> {
+        let DsaParameters { p, q, g } = &self.parameters;
+        if self.private_key.is_zero() {
+            return Err(GeneralError::state("private key has not been set"));
+        }
+        if k.is_zero() || k >= q {
+            return Err(GeneralError::input("nonce must be between 1 and q-1"));
+        }
+        let e = self.challenge(&g.modpow(k, p), msg);
+        let s = (k + &e * &self.private_key) % q;
+        Ok((e, s))
+    }
+
+    /// Sign with a nonce derived from the private key and message as in RFC 6979
+    pub fn sign(&self, msg: &[u8]) -> Result<(BigUint, BigUint), GeneralError> {
+        if self.private_key.is_zero() {
+            return Err(GeneralError::state("private key has not been set"));
+        }
+        let mut nonces = Rfc6979::new(
+            self.hash,
+            &self.parameters.q,
+            &self.private_key,
+            &self.hash.hash(msg),
+        );
+        self.sign_with_nonce(msg, &nonces.next_nonce())
+    }
+
+    pub fn verify(&self, msg: &[u8], e: &BigUint, s: &BigUint) -> bool {
+        let DsaParameters { p, q, g } = &self.parameters;
+        if e >= q || s >= q || self.public_key.is_zero() {
+            return false;
+        }
+        // y^-e = y^(q-e) since y has order q
+        let r = (g.modpow(s, p) * self.public_key.modpow(&(q - e), p)) % p;
+        self.challenge(&r, msg) == *e
+    }
+
+    /// Fixed width encoding of e followed by s
+    pub fn signature_to_bytes(&self, e: &BigUint, s: &BigUint) -> Vec<u8> {
+        let mut out = int2octets(e, self.scalar_len());
+        out.extend(int2octets(s, self.scalar_len()));
+        out
+    }
+
+    pub fn verify_bytes(&self, msg: &[u8], signature: &[u8]) -> bool {
+        let len = self.scalar_len();
+        if signature.len() != 2 * len {
+            return false;
+        }
+        self.verify(
+            msg,
+            &BigUint::from_bytes_be(&signature[..len]),
+            &BigUint::from_bytes_be(&signature[len..]),
+        )
+    }
+}
+
+impl Cipher for Schnorr {
+    fn encrypt(&self, text: &str) -> Result<String, GeneralError> {
+        let bytes = self
+            .input_format
+            .text_to_bytes(text)
+            .map_err(|_| GeneralError::input("byte format error"))?;
+        let (e, s) = self.sign(&bytes)?;
+        Ok(self
+            .output_format
+            .byte_slice_to_text(self.signature_to_bytes(&e, &s)))
+    }
+
+    fn decrypt(&self, _text: &str) -> Result<String, GeneralError> {
+        Err(GeneralError::general(
+            "Schnorr signatures cannot be decrypted",
+        ))
+    }
+}
+
+#[cfg(test)]
+mod schnorr_tests {
+
+    use super::*;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn sign_verify() {
+        let mut rng = StdRng::seed_from_u64(4);
+        let mut cipher = Schnorr::default();
+        cipher.generate_key(&mut rng);
+        let (e, s) = cipher.sign(b"attack at dawn").unwrap();
+        assert!(cipher.verify(b"attack at dawn", &e, &s));
+        assert!(!cipher.verify(b"attack at dusk", &e, &s));
+        assert!(!cipher.verify(
+            b"attack at dawn",
+            &e,
+            &((&s + 1_u32) % &cipher.parameters.q)
+        ));
+
+        let signature = cipher.signature_to_bytes(&e, &s);
+        assert!(cipher.verify_bytes(b"attack at dawn", &signature));
+        assert!(!cipher.verify_bytes(b"attack at dawn", &signature[1..]));
+
+        // Verification needs only the public key
+        let verifier = Schnorr {
+            public_key: cipher.public_key.clone(),
+            ..Default::default()
+        };
+        assert!(verifier.verify(b"attack at dawn", &e, &s));
+    }
+}
@@ -0,0 +1,415 @@
+use crate::Cipher;
+use hashers::{sha::Sha256, traits::StatefulHasher};
+use rand::Rng;
+use std::cell::Cell;
+use utils::{byte_formatting::ByteFormat, errors::GeneralError};
+
+// WOTS+, the Winternitz one-time signature as specified for XMSS in RFC 8391. Rather than one secret for every bit
+// of the digest there is one for every base w digit and the signature is that secret hashed forward along a chain
+// as many times as the digit says. A checksum of the digits is signed too so that moving any digit forward, which
+// anyone could do, forces some digit of the checksum backward. Each step of a chain is keyed and masked with values
+// derived from a public seed and the address of the step.
+// https://www.rfc-editor.org/rfc/rfc8391
+
+pub const ADDRESS_OTS: u32 = 0;
+pub const ADDRESS_LTREE: u32 = 1;
+pub const ADDRESS_HASH_TREE: u32 = 2;
+
+/// The 32-byte hash address of RFC 8391 section 2.5 that makes every call to the hash functions distinct. The meaning
+/// of the last four words depends on the type.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Address(pub [u8; 32]);
+
+impl Address {
+    fn set_word(&mut self, word: usize, value: u32) {
+        self.0[4 * word..4 * word + 4].copy_from_slice(&value.to_be_bytes());
+    }
+
+    pub fn set_layer(&mut self, layer: u32) {
+        self.set_word(0, layer);
+    }
+
+    pub fn set_tree(&mut self, tree: u64) {
+        self.0[4..12].copy_from_slice(&tree.to_be_bytes());
+    }
+
+    /// Changing the type clears the words that follow it
+    pub fn set_type(&mut self, address_type: u32) {
+        self.set_word(3, address_type);
+        self.0[16..].fill(0);
+    }
+
+    pub fn set_ots(&mut self, ots: u32) {
+        self.set_word(4, ots);
+    }
+
+    pub fn set_ltree(&mut self, ltree: u32) {
+        self.set_word(4, ltree);
+    }
+
+    pub fn set_chain(&mut self, chain: u32) {
+        self.set_word(5, chain);
+    }
+
+    pub fn set_tree_height(&mut self, height: u32) {
+        self.set_word(5, height);
+    }
+
+    pub fn set_hash(&mut self, hash: u32) {
+        self.set_word(6, hash);
+    }
+
+    pub fn set_tree_index(&mut self, index: u32) {
+        self.set_word(6, index);
+    }
+
+    pub fn set_key_and_mask(&mut self, key_and_mask: u32) {
+        self.set_word(7, key_and_mask);
+    }
+}
+
+/// The hash functions and Winternitz parameter shared by WOTS+ and XMSS. Every function is the underlying hash of
+/// a padding block identifying the function, a key and the message.
+pub struct WotsParams<H: StatefulHasher> {
+    pub hasher: fn() -> H,
+    /// Output length of the hash function in bytes
+    pub n: usize,
+    /// Only 4 and 16 are defined by RFC 8391
+    pub w: usize,
+}
+
+impl Default for WotsParams<Sha256> {
+    fn default() -> Self {
+        Self::new(Sha256::init, 16).expect("16 is a valid Winternitz parameter")
+    }
+}
+
+impl<H: StatefulHasher> WotsParams<H> {
+    pub fn new(hasher: fn() -> H, w: usize) -> Result<Self, GeneralError> {
+        if w != 4 && w != 16 {
+            return Err(GeneralError::key("w must be 4 or 16"));
+        }
+        let n = hasher().finalize().len();
+        Ok(Self { hasher, n, w })
+    }
+
+    fn log_w(&self) -> usize {
+        self.w.trailing_zeros() as usize
+    }
+
+    /// Number of digits in the message
+    pub fn len1(&self) -> usize {
+        (8 * self.n).div_ceil(self.log_w())
+    }
+
+    /// Number of digits in the checksum
+    pub fn len2(&self) -> usize {
+        let max_checksum = self.len1() * (self.w - 1);
+        (max_checksum.ilog2() as usize) / self.log_w() + 1
+    }
+
+    /// Number of chains, and so of n byte values in a key or signature
+    pub fn chains(&self) -> usize {
+        self.len1() + self.len2()
+    }
+
+    fn keyed(&self, padding: u8, key: &[u8], msg: &[u8]) -> Vec<u8> {
+        let mut pad = vec![0; self.n];
+        pad[self.n - 1] = padding;
+        let mut h = (self.hasher)();
+        h.update(&pad);
+        h.update(key);
+        h.update(msg);
+        h.finalize()
+    }
+
+    /// Chaining function
+    pub fn f(&self, key: &[u8], msg: &[u8]) -> Vec<u8> {
+        self.keyed(0, key, msg)
+    }
+
+    /// Tree hashing function, the message is two nodes
+    pub fn h(&self, key: &[u8], msg: &[u8]) -> Vec<u8> {
+        self.keyed(1, key, msg)
+    }
+
+    /// Message hash, the key is the randomness, root and index
+    pub fn h_msg(&self, key: &[u8], msg: &[u8]) -> Vec<u8> {
+        self.keyed(2, key, msg)
+    }
+
+    pub fn prf(&self, key: &[u8], msg: &[u8]) -> Vec<u8> {
+        self.keyed(3, key, msg)
+    }
+
+    /// Pseudorandom function used to expand the secret seed, taken from NIST SP 800-208. RFC 8391 leaves the
+    /// generation of secret keys open.
+    pub fn prf_keygen(&self, key: &[u8], msg: &[u8]) -> Vec<u8> {
+        self.keyed(4, key, msg)
+    }
+
+    /// The message split into base w digits followed by the digits of its checksum, most significant first
+    pub fn digits(&self, msg: &[u8]) -> Vec<usize> {
+        let log_w = self.log_w();
+        let mut digits: Vec<usize> = msg
+            .iter()
+            .flat_map(|byte| {
+                (0..8 / log_w)
+                    .rev()
+                    .map(move |i| (*byte as usize >> (i * log_w)) & (self.w - 1))
+            })
+            .take(self.len1())
+            .collect();
+        let checksum: usize = digits.iter().map(|d| self.w - 1 - d).sum();
+        for i in (0..self.len2()).rev() {
+            digits.push((checksum >> (i * log_w)) & (self.w - 1));
+        }
+        digits
+    }
+
+    /// Apply steps iterations of the keyed and masked chaining function starting from position start
+    pub fn chain(
+        &self,
+        x: &[u8],
+        start: usize,
+        steps: usize,
+        seed: &[u8],
+        address: &mut Address,
+    ) -> Vec<u8> {
+        let mut tmp = x.to_vec();
+        for i in start..start + steps {
+            address.set_hash(i as u32);
+            address.set_key_and_mask(0);
+            let key = self.prf(seed, &address.0);
+            address.set_key_and_mask(1);
+            let mask = self.prf(seed, &address.0);
+            for (t, m) in tmp.iter_mut().zip(mask) {
+                *t ^= m;
+            }
+            tmp = self.f(&key, &tmp);
+        }
+        tmp
+    }
+
+    fn secret_value(&self, secret_seed: &[u8], seed: &[u8], address: &mut Address) -> Vec<u8> {
+        address.set_hash(0);
+        address.set_key_and_mask(0);
+        let mut msg = seed.to_vec();
+        msg.extend_from_slice(&address.0);
+        self.prf_keygen(secret_seed, &msg)
+    }
+
+    /// The end of every chain, the address must already have its OTS index set
+    pub fn public_key(
+        &self,
+        secret_seed: &[u8],
+        seed: &[u8],
+        address: &mut Address,
+    ) -> Vec<Vec<u8>> {
+        (0..self.chains())
+            .map(|i| {
+                address.set_chain(i as u32);
+                let sk = self.secret_value(secret_seed, seed, address);
+                self.chain(&sk, 0, self.w - 1, seed, address)
+            })
+            .collect()
+    }
+
+    /// Sign an n byte digest by moving along each chain as far as its digit
+    pub fn sign(
+        &self,
+        digest: &[u8],
+        secret_seed: &[u8],
+        seed: &[u8],
+        address: &mut Address,
+    ) -> Vec<Vec<u8>> {
+        self.digits(digest)
+            .into_iter()
+            .enumerate()
+            .map(|(i, d)| {
+                address.set_chain(i as u32);
+                let sk = self.secret_value(secret_seed, seed, address);
+                self.chain(&sk, 0, d, seed, address)
+            })
+            .collect()
+    }
+
+    /// Finish each chain of a signature, giving the public key if the signature is valid
+    pub fn public_key_from_signature(
+        &self,
+        digest: &[u8],
+        signature: &[Vec<u8>],
+        seed: &[u8],
+        address: &mut Address,
+    ) -> Vec<Vec<u8>> {
+        self.digits(digest)
+            .into_iter()
+            .zip(signature)
+            .enumerate()
+            .map(|(i, (d, s))| {
+                address.set_chain(i as u32);
+                self.chain(s, d, self.w - 1 - d, seed, address)
+            })
+            .collect()
+    }
+}
+
+/// A single WOTS+ key pair that signs the hash of a message
+pub struct Wots<H: StatefulHasher> {
+    pub input_format: ByteFormat,
+    pub output_format: ByteFormat,
+
+    pub params: WotsParams<H>,
+    pub secret_seed: Vec<u8>,
+    pub public_seed: Vec<u8>,
+    pub public_key: Vec<Vec<u8>>,
+    used: Cell<bool>,
+}
+
+impl Default for Wots<Sha256> {
+    fn default() -> Self {
+        Self::new(WotsParams::default())
+    }
+}
+
+impl<H: StatefulHasher> Wots<H> {
+    pub fn new(params: WotsParams<H>) -> Self {
+        Self {
+            input_format: ByteFormat::Utf8,
+            output_format: ByteFormat::Hex,
+            params,
+            secret_seed: Vec::new(),
+            public_seed: Vec::new(),
+            public_key: Vec::new(),
+            used: Cell::new(false),
+        }
+    }
+
+    pub fn set_key(
+        &mut self,
+        secret_seed: Vec<u8>,
+        public_seed: Vec<u8>,
+    ) -> Result<(), GeneralError> {
+        let n = self.params.n;
+        if secret_seed.len() != n || public_seed.len() != n {
+            return Err(GeneralError::key(format!(
+                "seeds must be exactly {n} bytes"
+            )));
+        }
+        self.public_key =
+            self.params
+                .public_key(&secret_seed, &public_seed, &mut Address::default());
+        self.secret_seed = secret_seed;
+        self.public_seed = public_seed;
+        self.used.set(false);
+        Ok(())
+    }
+
+    pub fn generate_key<R: Rng>(&mut self, rng: &mut R) {
+        let n = self.params.n;
+        let secret_seed = (0..n).map(|_| rng.gen()).collect();
+        let public_seed = (0..n).map(|_| rng.gen()).collect();
+        self.set_key(secret_seed, public_seed)
+            .expect("generated seeds always have the right size");
+    }
+
+    pub fn signature_len(&self) -> usize {
+        self.params.chains() * self.params.n
+    }
+
+    fn digest(&self, msg: &[u8]) -> Vec<u8> {
+        (self.params.hasher)().hash(msg)
+    }
+
+    pub fn is_used(&self) -> bool {
+        self.used.get()
+    }
+
+    /// Signing a second message would let anyone advance the chains revealed for both and forge signatures
+    pub fn sign(&self, msg: &[u8]) -> Result<Vec<u8>, GeneralError> {
+        if self.public_key.is_empty() {
+            return Err(GeneralError::state("key has not been set"));
+        }
+        if self.used.get() {
+            return Err(GeneralError::state(
+                "this one-time key has already been used",
+            ));
+        }
+        self.used.set(true);
+        Ok(self
+            .params
+            .sign(
+                &self.digest(msg),
+                &self.secret_seed,
+                &self.public_seed,
+                &mut Address::default(),
+            )
+            .concat())
+    }
+
+    pub fn verify(&self, msg: &[u8], signature: &[u8]) -> bool {
+        if self.public_key.is_empty() || signature.len() != self.signature_len() {
+            return false;
+        }
+        let signature: Vec<Vec<u8>> = signature
+            .chunks_exact(self.params.n)
+            .map(|x| x.to_vec())
+            .collect();
+        self.params.public_key_from_signature(
+            &self.digest(msg),
+            &signature,
+            &self.public_seed,
+            &mut Address::default(),
+        ) == self.public_key
+    }
+}
+
+impl<H: StatefulHasher> Cipher for Wots<H> {
+    fn encrypt(&self, text: &str) -> Result<String, GeneralError> {
+        let bytes = self
+            .input_format
+            .text_to_bytes(text)
+            .map_err(|_| GeneralError::input("byte format error"))?;
+        Ok(self.output_format.byte_slice_to_text(self.sign(&bytes)?))
+    }
+
+    fn decrypt(&self, _text: &str) -> Result<String, GeneralError> {
+        Err(GeneralError::general(
+            "WOTS+ creates signatures, it does not decrypt",
+        ))
+    }
+}
+
+#[cfg(test)]
+mod wots_tests {
+
+    use super::*;
+    use hashers::sha::Keccack;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    // Table 1 of RFC 8391 gives len = 67 for n = 32 and w = 16
+    #[test]
+    fn lengths() {
+        let params = WotsParams::default();
+        assert_eq!((64, 3, 67), (params.len1(), params.len2(), params.chains()));
+        let params = WotsParams::new(Sha256::init, 4).unwrap();
+        assert_eq!((128, 5), (params.len1(), params.len2()));
+        assert!(WotsParams::new(Sha256::init, 8).is_err());
+
+        // An all zero digest has the largest checksum, 64 * 15 = 0x3c0
+        let digits = WotsParams::default().digits(&[0; 32]);
+        assert_eq!(vec![3, 12, 0], digits[64..]);
+    }
+
+    #[test]
+    fn sign_verify() {
+        for w in [4, 16] {
+            let mut signer = Wots::new(WotsParams::new(|| Keccack::shake_128(16), w).unwrap());
+            signer.generate_key(&mut StdRng::seed_from_u64(19));
+            let signature = signer.sign(b"attack at dawn").unwrap();
+            assert!(signer.verify(b"attack at dawn", &signature));
+            assert!(!signer.verify(b"attack at dusk", &signature));
+            assert!(signer.sign(b"attack at dusk").is_err());
+        }
+    }
+}
@@ -0,0 +1,424 @@
+use super::wots::{Address, WotsParams, ADDRESS_HASH_TREE, ADDRESS_LTREE, ADDRESS_OTS};
+use crate::Cipher;
+use hashers::{
+    sha::{Keccack, Sha256, Sha512},
+    traits::StatefulHasher,
+};
+use rand::Rng;
+use std::cell::Cell;
+use utils::{byte_formatting::ByteFormat, errors::GeneralError};
+
+// The eXtended Merkle Signature Scheme from RFC 8391. The leaves of the tree are WOTS+ public keys, each compressed
+// into one value by an L-tree, and every hash in the trees is keyed and masked with values derived from a public
+// seed and the address of the node. That lets the security proof rely only on the hash function being second
+// preimage resistant. Messages are hashed with a randomizer derived from a secret PRF key and the leaf index.
+// The secret WOTS+ keys are expanded from a seed as in NIST SP 800-208. The public key begins with the OID of the
+// parameter set, which is only registered for w = 16 and heights of 10, 16 and 20. Other parameters are allowed
+// for experimenting and use the OID 0, which RFC 8391 reserves. Only single trees are supported, not the multi-tree
+// XMSS^MT. Like HSS for LMS it signs the root of each lower tree with the tree above it, which lets a key sign far
+// more messages, but the layers and their states are not kept here.
+// https://www.rfc-editor.org/rfc/rfc8391
+
+pub struct Xmss<H: StatefulHasher> {
+    pub input_format: ByteFormat,
+    pub output_format: ByteFormat,
+
+    pub params: WotsParams<H>,
+    pub height: usize,
+    /// Identifies the parameter set in the public key, 0 if none is registered
+    pub oid: u32,
+    pub secret_seed: Vec<u8>,
+    pub prf_key: Vec<u8>,
+    pub public_seed: Vec<u8>,
+    /// levels[0] holds the leaves and the last level holds only the root
+    levels: Vec<Vec<Vec<u8>>>,
+    next_index: Cell<u32>,
+}
+
+impl Default for Xmss<Sha256> {
+    fn default() -> Self {
+        Self::sha2_256(10).expect("10 is a registered height")
+    }
+}
+
+impl Xmss<Sha256> {
+    /// XMSS-SHA2_10_256, XMSS-SHA2_16_256 or XMSS-SHA2_20_256
+    pub fn sha2_256(height: usize) -> Result<Self, GeneralError> {
+        Self::registered(WotsParams::default(), height, 0x01)
+    }
+}
+
+impl Xmss<Sha512> {
+    /// XMSS-SHA2_10_512, XMSS-SHA2_16_512 or XMSS-SHA2_20_512
+    pub fn sha2_512(height: usize) -> Result<Self, GeneralError> {
+        Self::registered(WotsParams::new(Sha512::init, 16)?, height, 0x04)
+    }
+}
+
+impl Xmss<Keccack> {
+    /// XMSS-SHAKE_10_256, XMSS-SHAKE_16_256 or XMSS-SHAKE_20_256, which use SHAKE128
+    pub fn shake_256(height: usize) -> Result<Self, GeneralError> {
+        Self::registered(
+            WotsParams::new(|| Keccack::shake_128(32), 16)?,
+            height,
+            0x07,
+        )
+    }
+
+    /// XMSS-SHAKE_10_512, XMSS-SHAKE_16_512 or XMSS-SHAKE_20_512, which use SHAKE256
+    pub fn shake_512(height: usize) -> Result<Self, GeneralError> {
+        Self::registered(
+            WotsParams::new(|| Keccack::shake_256(64), 16)?,
+            height,
+            0x0a,
+        )
+    }
+
+    /// XMSS-SHAKE256_10_256, XMSS-SHAKE256_16_256 or XMSS-SHAKE256_20_256 from SP 800-208
+    pub fn shake256_256(height: usize) -> Result<Self, GeneralError> {
+        Self::registered(
+            WotsParams::new(|| Keccack::shake_256(32), 16)?,
+            height,
+            0x10,
+        )
+    }
+}
+
+impl<H: StatefulHasher> Xmss<H> {
+    pub fn new(params: WotsParams<H>, height: usize) -> Self {
+        Self {
+            input_format: ByteFormat::Utf8,
+            output_format: ByteFormat::Hex,
+            params,
+            height,
+            oid: 0,
+            secret_seed: Vec::new(),
+            prf_key: Vec::new(),
+            public_seed: Vec::new(),
+            levels: Vec::new(),
+            next_index: Cell::new(0),
+        }
+    }
+
+    // Each hash function has three consecutive OIDs, for heights 10, 16 and 20
+    fn registered(
+        params: WotsParams<H>,
+        height: usize,
+        first_oid: u32,
+    ) -> Result<Self, GeneralError> {
+        let i = [10, 16, 20]
+            .iter()
+            .position(|h| *h == height)
+            .ok_or(GeneralError::key("registered heights are 10, 16 and 20"))?;
+        let mut out = Self::new(params, height);
+        out.oid = first_oid + i as u32;
+        Ok(out)
+    }
+
+    // RAND_HASH from section 4.1.4, the key and both masks come from the address
+    fn rand_hash(&self, left: &[u8], right: &[u8], seed: &[u8], address: &mut Address) -> Vec<u8> {
+        address.set_key_and_mask(0);
+        let key = self.params.prf(seed, &address.0);
+        address.set_key_and_mask(1);
+        let mask_left = self.params.prf(seed, &address.0);
+        address.set_key_and_mask(2);
+        let mask_right = self.params.prf(seed, &address.0);
+        let mut msg: Vec<u8> = left.iter().zip(mask_left).map(|(a, b)| a ^ b).collect();
+        msg.extend(right.iter().zip(mask_right).map(|(a, b)| a ^ b));
+        self.params.h(&key, &msg)
+    }
+
+    // Compress a WOTS+ public key into a single value, an unpaired node moves up a level unchanged
+    fn ltree(&self, mut pk: Vec<Vec<u8>>, seed: &[u8], address: &mut Address) -> Vec<u8> {
+        let mut height = 0;
+        address.set_tree_height(0);
+        while pk.len() > 1 {
+            for i in 0..pk.len() / 2 {
+                address.set_tree_index(i as u32);
+                pk[i] = self.rand_hash(&pk[2 * i], &pk[2 * i + 1], seed, address);
+            }
+            if pk.len() % 2 == 1 {
+                let last = pk.len() - 1;
+                pk[last / 2] = pk[last].clone();
+            }
+            pk.truncate(pk.len().div_ceil(2));
+            height += 1;
+            address.set_tree_height(height);
+        }
+        pk.swap_remove(0)
+    }
+
+    fn leaf(&self, index: u32) -> Vec<u8> {
+        let mut address = Address::default();
+        address.set_type(ADDRESS_OTS);
+        address.set_ots(index);
+        let pk = self
+            .params
+            .public_key(&self.secret_seed, &self.public_seed, &mut address);
+        address.set_type(ADDRESS_LTREE);
+        address.set_ltree(index);
+        self.ltree(pk, &self.public_seed, &mut address)
+    }
+
+    /// Set the three secret values and build the whole tree
+    pub fn set_key(
+        &mut self,
+        secret_seed: Vec<u8>,
+        prf_key: Vec<u8>,
+        public_seed: Vec<u8>,
+    ) -> Result<(), GeneralError> {
+        let n = self.params.n;
+        if secret_seed.len() != n || prf_key.len() != n || public_seed.len() != n {
+            return Err(GeneralError::key(format!(
+                "seeds must be exactly {n} bytes"
+            )));
+        }
+        if !(1..=20).contains(&self.height) {
+            return Err(GeneralError::key("height must be between 1 and 20"));
+        }
+        self.secret_seed = secret_seed;
+        self.prf_key = prf_key;
+        self.public_seed = public_seed;
+
+        let mut levels = vec![(0..1_u32 << self.height)
+            .map(|i| self.leaf(i))
+            .collect::<Vec<_>>()];
+        let mut address = Address::default();
+        address.set_type(ADDRESS_HASH_TREE);
+        for level in 0..self.height {
+            address.set_tree_height(level as u32);
+            let next = levels[level]
+                .chunks_exact(2)
+                .enumerate()
+                .map(|(i, pair)| {
+                    address.set_tree_index(i as u32);
+                    self.rand_hash(&pair[0], &pair[1], &self.public_seed, &mut address)
+                })
+                .collect();
+            levels.push(next);
+        }
+        self.levels = levels;
+        self.next_index.set(0);
+        Ok(())
+    }
+
+    pub fn generate_key<R: Rng>(&mut self, rng: &mut R) -> Result<(), GeneralError> {
+        let n = self.params.n;
+        let mut random = || (0..n).map(|_| rng.gen()).collect::<Vec<u8>>();
+        let (secret_seed, prf_key, public_seed) = (random(), random(), random());
+        self.set_key(secret_seed, prf_key, public_seed)
+    }
+
+    pub fn root(&self) -> &[u8] {
+        self.levels.last().map(|l| l[0].as_slice()).unwrap_or(&[])
+    }
+
+    /// The public key is OID || root || SEED
+    pub fn public_key(&self) -> Vec<u8> {
+        let mut out = self.oid.to_be_bytes().to_vec();
+        out.extend_from_slice(self.root());
+        out.extend_from_slice(&self.public_seed);
+        out
+    }
+
+    pub fn next_index(&self) -> u32 {
+        self.next_index.get()
+    }
+
+    pub fn signature_len(&self) -> usize {
+        4 + self.params.n * (1 + self.params.chains() + self.height)
+    }
+
+    fn to_byte(&self, x: u32, len: usize) -> Vec<u8> {
+        let mut out = vec![0; len];
+        out[len - 4..].copy_from_slice(&x.to_be_bytes());
+        out
+    }
+
+    fn message_digest(&self, r: &[u8], root: &[u8], index: u32, msg: &[u8]) -> Vec<u8> {
+        let mut key = r.to_vec();
+        key.extend_from_slice(root);
+        key.extend(self.to_byte(index, self.params.n));
+        self.params.h_msg(&key, msg)
+    }
+
+    /// The signature is idx_sig || r || WOTS+ signature || authentication path
+    pub fn sign_with_index(&self, index: u32, msg: &[u8]) -> Result<Vec<u8>, GeneralError> {
+        if self.levels.is_empty() {
+            return Err(GeneralError::state("key has not been set"));
+        }
+        if index >> self.height != 0 {
+            return Err(GeneralError::input("index is outside of the tree"));
+        }
+        let r = self.params.prf(&self.prf_key, &self.to_byte(index, 32));
+        let digest = self.message_digest(&r, self.root(), index, msg);
+        let mut address = Address::default();
+        address.set_type(ADDRESS_OTS);
+        address.set_ots(index);
+
+        let mut out = index.to_be_bytes().to_vec();
+        out.extend(r);
+        for value in self
+            .params
+            .sign(&digest, &self.secret_seed, &self.public_seed, &mut address)
+        {
+            out.extend(value);
+        }
+        for level in 0..self.height {
+            out.extend_from_slice(&self.levels[level][(index as usize >> level) ^ 1]);
+        }
+        Ok(out)
+    }
+
+    /// Sign with the next unused leaf
+    pub fn sign(&self, msg: &[u8]) -> Result<Vec<u8>, GeneralError> {
+        let index = self.next_index.get();
+        if index >> self.height != 0 {
+            return Err(GeneralError::state("every one-time key has been used"));
+        }
+        let signature = self.sign_with_index(index, msg)?;
+        self.next_index.set(index + 1);
+        Ok(signature)
+    }
+
+    /// Verify a signature against a public key with the same OID, and so the same height and hash function
+    pub fn verify(&self, public_key: &[u8], msg: &[u8], signature: &[u8]) -> bool {
+        let n = self.params.n;
+        if public_key.len() != 4 + 2 * n || signature.len() != self.signature_len() {
+            return false;
+        }
+        if public_key[..4] != self.oid.to_be_bytes() {
+            return false;
+        }
+        let (root, seed) = public_key[4..].split_at(n);
+        let index = u32::from_be_bytes(signature[..4].try_into().unwrap());
+        if index >> self.height != 0 {
+            return false;
+        }
+        let r = &signature[4..4 + n];
+        let chains = self.params.chains();
+        let ots: Vec<Vec<u8>> = signature[4 + n..4 + n * (1 + chains)]
+            .chunks_exact(n)
+            .map(|x| x.to_vec())
+            .collect();
+        let path = signature[4 + n * (1 + chains)..].chunks_exact(n);
+
+        let digest = self.message_digest(r, root, index, msg);
+        let mut address = Address::default();
+        address.set_type(ADDRESS_OTS);
+        address.set_ots(index);
+        let pk = self
+            .params
+            .public_key_from_signature(&digest, &ots, seed, &mut address);
+        address.set_type(ADDRESS_LTREE);
+        address.set_ltree(index);
+        let mut node = self.ltree(pk, seed, &mut address);
+
+        address.set_type(ADDRESS_HASH_TREE);
+        for (level, sibling) in path.enumerate() {
+            address.set_tree_height(level as u32);
+            address.set_tree_index(index >> (level + 1));
+            node = if (index >> level) & 1 == 0 {
+                self.rand_hash(&node, sibling, seed, &mut address)
+            } else {
+                self.rand_hash(sibling, &node, seed, &mut address)
+            };
+        }
+        node == root
+    }
+}
+
+impl<H: StatefulHasher> Cipher for Xmss<H> {
+    fn encrypt(&self, text: &str) -> Result<String, GeneralError> {
+        let bytes = self
+            .input_format
+            .text_to_bytes(text)
+            .map_err(|_| GeneralError::input("byte format error"))?;
+        Ok(self.output_format.byte_slice_to_text(self.sign(&bytes)?))
+    }
+
+    fn decrypt(&self, _text: &str) -> Result<String, GeneralError> {
+        Err(GeneralError::general(
+            "XMSS creates signatures, it does not decrypt",
+        ))
+    }
+}
+
+#[cfg(test)]
+mod xmss_tests {
+
+    use super::*;
+    use hashers::{blake::Blake2b, sha::Keccack};
+    use rand::{rngs::StdRng, SeedableRng};
+
+    fn sign_and_verify<H: StatefulHasher>(mut signer: Xmss<H>) {
+        signer.generate_key(&mut StdRng::seed_from_u64(22)).unwrap();
+        let public_key = signer.public_key();
+        let leaves = 1 << signer.height;
+        for i in 0..leaves {
+            let signature = signer.sign(b"attack at dawn").unwrap();
+            assert_eq!(signer.signature_len(), signature.len());
+            assert_eq!(i, u32::from_be_bytes(signature[..4].try_into().unwrap()));
+            assert!(signer.verify(&public_key, b"attack at dawn", &signature));
+            assert!(!signer.verify(&public_key, b"attack at dusk", &signature));
+        }
+        assert!(signer.sign(b"attack at dawn").is_err());
+    }
+
+    #[test]
+    fn hash_functions() {
+        sign_and_verify(Xmss::new(WotsParams::default(), 2));
+        sign_and_verify(Xmss::new(
+            WotsParams::new(|| Keccack::shake_128(16), 4).unwrap(),
+            3,
+        ));
+        sign_and_verify(Xmss::new(
+            WotsParams::new(|| Blake2b::init_hash(32), 16).unwrap(),
+            2,
+        ));
+    }
+
+    #[test]
+    fn tampering() {
+        let mut signer = Xmss::new(WotsParams::default(), 3);
+        signer.generate_key(&mut StdRng::seed_from_u64(23)).unwrap();
+        let public_key = signer.public_key();
+        let signature = signer.sign_with_index(5, b"attack at dawn").unwrap();
+        assert!(signer.verify(&public_key, b"attack at dawn", &signature));
+        for position in [3, 4, 40, signature.len() - 1] {
+            let mut mauled = signature.clone();
+            mauled[position] ^= 1;
+            assert!(!signer.verify(&public_key, b"attack at dawn", &mauled));
+        }
+        // Signing is deterministic for a given index
+        assert_eq!(
+            signature,
+            signer.sign_with_index(5, b"attack at dawn").unwrap()
+        );
+    }
+
+    #[test]
+    fn oids() {
+        assert!(Xmss::sha2_256(12).is_err());
+        fn oid_and_n<H: StatefulHasher>(x: Xmss<H>) -> (u32, usize) {
+            (x.oid, x.params.n)
+        }
+        assert_eq!((0x02, 32), oid_and_n(Xmss::sha2_256(16).unwrap()));
+        assert_eq!((0x05, 64), oid_and_n(Xmss::sha2_512(16).unwrap()));
+        assert_eq!((0x09, 32), oid_and_n(Xmss::shake_256(20).unwrap()));
+        assert_eq!((0x0c, 64), oid_and_n(Xmss::shake_512(20).unwrap()));
+        assert_eq!((0x10, 32), oid_and_n(Xmss::shake256_256(10).unwrap()));
+
+        // XMSS-SHA2_10_256
+        let mut signer = Xmss::default();
+        signer.generate_key(&mut StdRng::seed_from_u64(25)).unwrap();
+        let mut public_key = signer.public_key();
+        assert_eq!([0, 0, 0, 1], public_key[..4]);
+        assert_eq!(68, public_key.len());
+        let signature = signer.sign(b"attack at dawn").unwrap();
+        assert_eq!(2500, signature.len());
+        assert!(signer.verify(&public_key, b"attack at dawn", &signature));
+        public_key[3] = 2;
+        assert!(!signer.verify(&public_key, b"attack at dawn", &signature));
+    }
+}
@@ -1,3 +1,4 @@
+use super::keystream::KeystreamGenerator;
 use super::lfsr::Lfsr32;
 use utils::byte_formatting::xor_into_bytes;
 
@@ -155,6 +156,18 @@ impl A52Rng {
     }
 }
 
+impl KeystreamGenerator for A52Rng {
+    fn fill_keystream(&mut self, bytes: &mut [u8]) {
+        // Bits are packed most significant first, as in burst_bytes
+        for byte in bytes.iter_mut() {
+            *byte = 0;
+            for _ in 0..8 {
+                *byte = (*byte << 1) | self.next_bit() as u8;
+            }
+        }
+    }
+}
+
 pub struct A52 {
     pub rng: A52Rng,
     pub key: [u8; 8],
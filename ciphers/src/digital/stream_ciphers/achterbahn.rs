@@ -1,3 +1,5 @@
+use super::keystream::KeystreamGenerator;
+
 const LENS: [usize; 13] = [21, 22, 23, 24, 25, 26, 27, 28, 29, 30, 31, 32, 33];
 const MASKS: [u64; 13] = [
     0x001FFFFF,
@@ -360,7 +362,7 @@ fn keystream_bits(x: &[u64; 13]) -> u64 {
         x[9] >> (LENS[9] - 16),
         x[10] >> (LENS[10] - 16),
         x[11] >> (LENS[11] - 16),
-        x[12] & 0xFFFFFFFF >> (LENS[12] - 16),
+        x[12] >> (LENS[12] - 16),
     ])
 }
 
@@ -426,7 +428,7 @@ impl Achterbahn128 {
 
         // 1: Load all NLFSRs with the first key bits
         for i in 0..13 {
-            self.step(i, key33 & MASKS[i]);
+            self.nlfsrs[i] = key33 & MASKS[i];
         }
 
         // 2: For each NLFSRS feed-in the key bits not loaded in step 1
@@ -487,6 +489,12 @@ impl Achterbahn128 {
     }
 }
 
+impl KeystreamGenerator for Achterbahn128 {
+    fn fill_keystream(&mut self, bytes: &mut [u8]) {
+        bytes.copy_from_slice(&self.keystream(bytes.len()));
+    }
+}
+
 #[cfg(test)]
 mod tests {
 
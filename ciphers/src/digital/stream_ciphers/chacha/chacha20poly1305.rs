@@ -111,25 +111,47 @@ impl ChaCha20Poly1305 {
     }
 }
 
+impl ChaCha20Poly1305 {
+    // Encrypt the message and append the tag
+    pub fn encrypt_bytes(&self, bytes: &[u8]) -> Vec<u8> {
+        let mut encrypted_bytes = self.cipher.encrypt_bytes_with_ctr(bytes, self.ctr + 1);
+        let tag = self.create_tag(&encrypted_bytes);
+        encrypted_bytes.extend_from_slice(&tag);
+        encrypted_bytes
+    }
+
+    // Check the tag at the end of the message and decrypt it
+    pub fn decrypt_bytes(&self, message: &[u8]) -> Result<Vec<u8>, GeneralError> {
+        if message.len() < 16 {
+            return Err(GeneralError::input("authentication tag is missing"));
+        }
+
+        // Split the tag and the encrypted message
+        let (encrypted_bytes, message_tag) = message.split_at(message.len() - 16);
+
+        if message_tag != self.create_tag(&encrypted_bytes) {
+            return Err(GeneralError::input("message failed authentication"));
+        }
+
+        // ChaCha is reciprocal
+        Ok(self
+            .cipher
+            .encrypt_bytes_with_ctr(&encrypted_bytes, self.ctr + 1))
+    }
+}
+
 impl Cipher for ChaCha20Poly1305 {
     fn encrypt(&self, text: &str) -> Result<String, GeneralError> {
-        // Create encrypted bytes
         let bytes = self
             .cipher
             .input_format
             .text_to_bytes(text)
             .map_err(|_| GeneralError::input("byte format error"))?;
-        let mut encrypted_bytes = self.cipher.encrypt_bytes_with_ctr(&bytes, self.ctr + 1);
-
-        // The r key is restricted within the hash invocation
-        // Put the tag first for simplicity when decoding
-        let tag = self.create_tag(&encrypted_bytes);
-        encrypted_bytes.extend_from_slice(&tag);
 
         Ok(self
             .cipher
             .output_format
-            .byte_slice_to_text(&encrypted_bytes))
+            .byte_slice_to_text(&self.encrypt_bytes(&bytes)))
     }
 
     fn decrypt(&self, text: &str) -> Result<String, GeneralError> {
@@ -139,25 +161,10 @@ impl Cipher for ChaCha20Poly1305 {
             .text_to_bytes(text)
             .map_err(|_| GeneralError::input("byte format error"))?;
 
-        if message.len() < 16 {
-            return Err(GeneralError::input("authentication tag is missing"));
-        }
-
-        // Split the tag and the encrypted message
-        let (encrypted_bytes, message_tag) = message.split_at(message.len() - 16);
-
-        if message_tag != self.create_tag(&encrypted_bytes) {
-            return Err(GeneralError::input("message failed authentication"));
-        }
-
-        // ChaCha is reciprocal
-        let decrypted_bytes = self
-            .cipher
-            .encrypt_bytes_with_ctr(&encrypted_bytes, self.ctr + 1);
         Ok(self
             .cipher
             .output_format
-            .byte_slice_to_text(&decrypted_bytes))
+            .byte_slice_to_text(&self.decrypt_bytes(&message)?))
     }
 }
 
@@ -1,3 +1,4 @@
+use crate::digital::stream_ciphers::keystream::{KeystreamBlocks, SeekableBlocks};
 use utils::byte_formatting::{fill_u32s_le, ByteFormat};
 
 use super::ChaChaState;
@@ -62,26 +63,7 @@ impl ChaChaIetf {
     }
 
     pub fn block_function(&self, state: &mut ChaChaState, block: &mut [u8; 64]) {
-        // Temporary state
-        let mut t_state = state.clone();
-
-        // Only ChaCha20, ChaCha12, and ChaCha8 are official but any number is usable
-        for _round in 0..self.rounds / 2 {
-            t_state.double_round();
-        }
-        if self.rounds % 2 == 1 {
-            t_state.column_round();
-        }
-
-        // Add the current state into the temporary state
-        for (i, word) in t_state.0.iter_mut().enumerate() {
-            *word = word.wrapping_add(state[i])
-        }
-
-        // Create a byte stream
-        for (i, b) in t_state.0.iter().flat_map(|w| w.to_le_bytes()).enumerate() {
-            block[i] = b
-        }
+        state.block_function(self.rounds, block);
     }
 
     pub fn key_stream_with_ctr(&self, blocks: u64, ctr: u32) -> Vec<u8> {
@@ -132,6 +114,24 @@ impl ChaChaIetf {
     }
 }
 
+impl KeystreamBlocks for ChaChaIetf {
+    const BLOCK_SIZE: usize = 64;
+
+    fn next_keystream_block(&mut self, block: &mut [u8]) {
+        let mut key_stream = [0; 64];
+        let mut state = ChaChaState::new(self.create_state(self.ctr));
+        self.block_function(&mut state, &mut key_stream);
+        block.copy_from_slice(&key_stream);
+        self.ctr = self.ctr.wrapping_add(1);
+    }
+}
+
+impl SeekableBlocks for ChaChaIetf {
+    fn seek_block(&mut self, index: u64) {
+        self.ctr = u32::try_from(index).expect("the IETF block counter is only 32 bits");
+    }
+}
+
 crate::impl_cipher_for_stream_cipher!(ChaChaIetf);
 
 #[cfg(test)]
@@ -1,9 +1,12 @@
 use itertools::Itertools;
+use rngs::chacha::ChaCha as ChaChaCore;
+use utils::byte_formatting::u32s_to_bytes_le;
 
 pub mod chacha;
 pub mod chacha20poly1305;
 pub mod chacha_ietf;
 pub mod xchacha;
+pub mod xchacha20poly1305;
 pub mod xchacha_ietf;
 
 const DEBUG: bool = false;
@@ -53,36 +56,16 @@ impl ChaChaState {
     }
 
     pub fn quarter_round(&mut self, a: usize, b: usize, c: usize, d: usize) {
-        self[a] = self[a].wrapping_add(self[b]);
-        self[d] ^= self[a];
-        self[d] = self[d].rotate_left(16);
-
-        self[c] = self[c].wrapping_add(self[d]);
-        self[b] ^= self[c];
-        self[b] = self[b].rotate_left(12);
-
-        self[a] = self[a].wrapping_add(self[b]);
-        self[d] ^= self[a];
-        self[d] = self[d].rotate_left(8);
-
-        self[c] = self[c].wrapping_add(self[d]);
-        self[b] ^= self[c];
-        self[b] = self[b].rotate_left(7);
+        ChaChaCore::quarter_round(&mut self.0, a, b, c, d);
     }
 
     pub fn column_round(&mut self) {
-        self.quarter_round(0, 4, 8, 12);
-        self.quarter_round(1, 5, 9, 13);
-        self.quarter_round(2, 6, 10, 14);
-        self.quarter_round(3, 7, 11, 15);
+        ChaChaCore::column_round(&mut self.0);
         debug_state!("column", self);
     }
 
     pub fn diag_round(&mut self) {
-        self.quarter_round(0, 5, 10, 15);
-        self.quarter_round(1, 6, 11, 12);
-        self.quarter_round(2, 7, 8, 13);
-        self.quarter_round(3, 4, 9, 14);
+        ChaChaCore::diag_round(&mut self.0);
         debug_state!("diagonal", self);
     }
 
@@ -90,4 +73,9 @@ impl ChaChaState {
         self.column_round();
         self.diag_round();
     }
+
+    // Apply the rounds and add the input back in, the keystream block is the output as little endian bytes
+    pub fn block_function(&self, rounds: u8, block: &mut [u8; 64]) {
+        u32s_to_bytes_le(block, ChaChaCore::block_function(&self.0, rounds));
+    }
 }
@@ -1,3 +1,4 @@
+use crate::digital::stream_ciphers::keystream::{KeystreamBlocks, SeekableBlocks};
 use utils::byte_formatting::{fill_u32s_le, ByteFormat};
 
 use super::ChaChaState;
@@ -91,26 +92,7 @@ impl XChaCha {
         state[12] = ctr as u32; // low bits, "as" cast truncates
         state[13] = (ctr >> 32) as u32; // high bits
 
-        // Temporary state
-        let mut t_state = state.clone();
-
-        // Only XChaCha20, XChaCha12, and XChaCha8 are official but any number is usable
-        for _round in 0..self.rounds / 2 {
-            t_state.double_round();
-        }
-        if self.rounds % 2 == 1 {
-            t_state.column_round();
-        }
-
-        // Add the current state into the temporary state
-        for (i, word) in t_state.0.iter_mut().enumerate() {
-            *word = word.wrapping_add(state[i])
-        }
-
-        // Create a byte stream
-        for (i, b) in t_state.0.iter().flat_map(|w| w.to_le_bytes()).enumerate() {
-            block[i] = b
-        }
+        state.block_function(self.rounds, block);
     }
 
     // Create a key_stream with the specified number of blocks and with the counter started at a particular value
@@ -175,4 +157,22 @@ impl XChaCha {
     }
 }
 
+impl KeystreamBlocks for XChaCha {
+    const BLOCK_SIZE: usize = 64;
+
+    fn next_keystream_block(&mut self, block: &mut [u8]) {
+        let mut key_stream = [0; 64];
+        let mut state = ChaChaState::new(self.create_state(self.ctr));
+        self.block_function(&mut state, &mut key_stream, self.ctr);
+        block.copy_from_slice(&key_stream);
+        self.ctr = self.ctr.wrapping_add(1);
+    }
+}
+
+impl SeekableBlocks for XChaCha {
+    fn seek_block(&mut self, index: u64) {
+        self.ctr = index;
+    }
+}
+
 crate::impl_cipher_for_stream_cipher!(XChaCha);
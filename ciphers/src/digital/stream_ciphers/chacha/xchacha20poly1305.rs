@@ -0,0 +1,117 @@
+use super::{
+    chacha20poly1305::ChaCha20Poly1305, chacha_ietf::ChaChaIetf, xchacha_ietf::XChaChaIetf,
+};
+use crate::Cipher;
+use utils::{byte_formatting::ByteFormat, errors::GeneralError};
+
+// https://datatracker.ietf.org/doc/html/draft-irtf-cfrg-xchacha-03#section-2.3
+#[derive(Default)]
+pub struct XChaCha20Poly1305 {
+    pub cipher: XChaChaIetf,
+    pub associated_data: Vec<u8>,
+}
+
+impl XChaCha20Poly1305 {
+    pub fn with_key_and_nonce(mut self, key: [u8; 32], nonce: [u8; 24]) -> Self {
+        self.cipher.key_and_nonce(key, nonce);
+        self
+    }
+
+    // HChaCha20 compresses the key and the first 128 bits of the nonce into a subkey which is used for
+    // ChaCha20-Poly1305 with the last 64 bits of the nonce
+    pub fn inner_aead(&self) -> ChaCha20Poly1305 {
+        ChaCha20Poly1305 {
+            cipher: ChaChaIetf {
+                input_format: ByteFormat::Hex,
+                output_format: ByteFormat::Hex,
+                key: self.cipher.synthetic_key(),
+                nonce: [0, self.cipher.nonce[4], self.cipher.nonce[5]],
+                rounds: self.cipher.rounds,
+                ctr: 0,
+            },
+            associated_data: self.associated_data.clone(),
+            ctr: 0,
+        }
+    }
+
+    pub fn encrypt_bytes(&self, bytes: &[u8]) -> Vec<u8> {
+        self.inner_aead().encrypt_bytes(bytes)
+    }
+
+    pub fn decrypt_bytes(&self, message: &[u8]) -> Result<Vec<u8>, GeneralError> {
+        self.inner_aead().decrypt_bytes(message)
+    }
+}
+
+impl Cipher for XChaCha20Poly1305 {
+    fn encrypt(&self, text: &str) -> Result<String, GeneralError> {
+        let bytes = self
+            .cipher
+            .input_format
+            .text_to_bytes(text)
+            .map_err(|_| GeneralError::input("byte format error"))?;
+
+        Ok(self
+            .cipher
+            .output_format
+            .byte_slice_to_text(self.encrypt_bytes(&bytes)))
+    }
+
+    fn decrypt(&self, text: &str) -> Result<String, GeneralError> {
+        let message = self
+            .cipher
+            .input_format
+            .text_to_bytes(text)
+            .map_err(|_| GeneralError::input("byte format error"))?;
+
+        Ok(self
+            .cipher
+            .output_format
+            .byte_slice_to_text(&self.decrypt_bytes(&message)?))
+    }
+}
+
+#[cfg(test)]
+mod xchacha20_poly1305_tests {
+
+    use super::*;
+    use hex_literal::hex;
+
+    const PTEXT: &[u8] = b"Ladies and Gentlemen of the class of '99: If I could offer you only one tip for the future, sunscreen would be it.";
+
+    fn cipher() -> XChaCha20Poly1305 {
+        let mut cipher = XChaCha20Poly1305::default().with_key_and_nonce(
+            hex!("808182838485868788898a8b8c8d8e8f909192939495969798999a9b9c9d9e9f"),
+            hex!("404142434445464748494a4b4c4d4e4f5051525354555657"),
+        );
+        cipher.associated_data = hex!("50515253c0c1c2c3c4c5c6c7").to_vec();
+        cipher
+    }
+
+    #[test]
+    fn encrypt_test() {
+        // https://datatracker.ietf.org/doc/html/draft-irtf-cfrg-xchacha-03#appendix-A.3.1
+        let ctext = cipher().encrypt_bytes(PTEXT);
+        let (ctext, tag) = ctext.split_at(PTEXT.len());
+        assert_eq!(
+            hex!(
+                "bd6d179d3e83d43b9576579493c0e939572a1700252bfaccbed2902c21396cbb"
+                "731c7f1b0b4aa6440bf3a82f4eda7e39ae64c6708c54c216cb96b72e1213b452"
+                "2f8c9ba40db5d945b11b69b982c1bb9e3f3fac2bc369488f76b2383565d3fff9"
+                "21f9664c97637da9768812f615c68b13b52e"
+            ),
+            ctext
+        );
+        assert_eq!(hex!("c0875924c1c7987947deafd8780acf49"), tag);
+    }
+
+    #[test]
+    fn decrypt_test() {
+        let cipher = cipher();
+        let mut ctext = cipher.encrypt_bytes(PTEXT);
+        assert_eq!(PTEXT, cipher.decrypt_bytes(&ctext).unwrap());
+
+        ctext[3] ^= 1;
+        assert!(cipher.decrypt_bytes(&ctext).is_err());
+    }
+}
@@ -1,54 +1,173 @@
+use super::keystream::KeystreamGenerator;
 use super::lfsr::Lfsr64;
 
+const LENS: [usize; 4] = [25, 31, 33, 39];
+
+// Bits are taken from positions 24, 24, 32, and 32 counting the input cell as position 1
+const OUTPUT_BITS: [u64; 4] = [23, 23, 31, 31];
+
 fn t2(x: u8) -> u8 {
     let x0 = x & 1;
     let x1 = (x >> 1) & 1;
     (x0 << 1) | (x0 ^ x1)
 }
 
+// Bits of a byte in the order they are shifted into an LFSR, least significant first
+fn byte_bits(byte: u8) -> impl Iterator<Item = u64> {
+    (0..8).map(move |i| ((byte >> i) & 1) as u64)
+}
+
+#[derive(Debug, Clone)]
 pub struct E0 {
     lfsrs: [Lfsr64; 4],
-    delay: [u8; 2],
+    // The blend registers c(t) and c(t-1)
+    blend: [u8; 2],
 }
 
 impl Default for E0 {
     fn default() -> Self {
         Self {
             lfsrs: [
-                Lfsr64::from_taps(0b1000010000000100010000000),
-                Lfsr64::from_taps(0b1000000100000001000100000000000),
-                Lfsr64::from_taps(0b100001000100000000000000000001000),
-                Lfsr64::from_taps(0b1001000000010000000000000000000000000001000),
+                Lfsr64::from_taps(0x1080880),    // t^25 + t^20 + t^12 + t^8 + 1
+                Lfsr64::from_taps(0x40808800),   // t^31 + t^24 + t^16 + t^12 + 1
+                Lfsr64::from_taps(0x108800008),  // t^33 + t^28 + t^24 + t^4 + 1
+                Lfsr64::from_taps(0x4808000008), // t^39 + t^36 + t^28 + t^4 + 1
             ],
-            delay: [0; 2],
+            blend: [0; 2],
         }
     }
 }
 
 impl E0 {
-    fn fsm(&self, y: u8) -> u8 {
-        let s = (y + self.delay[0]) / 2;
-        s ^ self.delay[0] ^ t2(self.delay[1])
+    /// Load the encryption key Kc', the 48-bit Bluetooth device address, and 26 bits of the master clock.
+    pub fn with_key(key: [u8; 16], address: [u8; 6], clock: u32) -> Self {
+        let mut out = Self::default();
+        out.ksa(key, address, clock);
+        out
     }
 
-    fn next_bit(&mut self) -> u8 {
-        let x1 = self.lfsrs[0].next_bit() as u8;
-        let x2 = self.lfsrs[1].next_bit() as u8;
-        let x3 = self.lfsrs[2].next_bit() as u8;
-        let x4 = self.lfsrs[3].next_bit() as u8;
-        let c0 = self.fsm(x1 + x2 + x3 + x4);
+    pub fn ksa(&mut self, key: [u8; 16], address: [u8; 6], clock: u32) {
+        let cl = (clock & 0x03FFFFFF).to_le_bytes();
+        let cl24 = ((clock >> 24) & 1) as u64;
+        let cl25 = ((clock >> 25) & 1) as u64;
 
-        x1 ^ x2 ^ x3 ^ x4 ^ c0
+        // Each LFSR receives the bits of one level of Figure 4.6 of the specification, rightmost bit first
+        let inputs: [Vec<u64>; 4] = [
+            std::iter::once(cl24)
+                .chain(
+                    [key[0], key[4], key[8], key[12], cl[1], address[2]]
+                        .into_iter()
+                        .flat_map(byte_bits),
+                )
+                .collect(),
+            [1, 0, 0]
+                .into_iter()
+                .chain(byte_bits(cl[0]).take(4))
+                .chain(
+                    [key[1], key[5], key[9], key[13], address[0], address[3]]
+                        .into_iter()
+                        .flat_map(byte_bits),
+                )
+                .collect(),
+            std::iter::once(cl25)
+                .chain(
+                    [key[2], key[6], key[10], key[14], cl[2], address[4]]
+                        .into_iter()
+                        .flat_map(byte_bits),
+                )
+                .collect(),
+            [1, 1, 1]
+                .into_iter()
+                .chain(byte_bits(cl[0]).skip(4))
+                .chain(
+                    [key[3], key[7], key[11], key[15], address[1], address[5]]
+                        .into_iter()
+                        .flat_map(byte_bits),
+                )
+                .collect(),
+        ];
+
+        for lfsr in self.lfsrs.iter_mut() {
+            lfsr.register = 0;
+        }
+        self.blend = [0; 2];
+
+        // The combiner is switched on once LFSR4 has been filled and then produces 200 bits, the last 128 of which are
+        // kept as Z[0] to Z[15]
+        let mut z = [0u8; 16];
+        for t in 0..239 {
+            for (i, lfsr) in self.lfsrs.iter_mut().enumerate() {
+                let input = inputs[i].get(t).copied().unwrap_or(0);
+                if t < LENS[i] {
+                    // Until the first bit reaches the end of the LFSR its feedback is off
+                    lfsr.register = ((lfsr.register << 1) | input) & lfsr.mask;
+                } else {
+                    lfsr.next_bit();
+                    lfsr.register ^= input;
+                }
+            }
+            if t >= LENS[3] {
+                let bit = self.next_output();
+                let n = t - LENS[3];
+                if n >= 72 {
+                    z[(n - 72) / 8] |= bit << ((n - 72) % 8);
+                }
+            }
+        }
+
+        // Reload the LFSRs in parallel from Z, keeping the state of the blend registers
+        let word = |bytes: &[u8]| {
+            bytes
+                .iter()
+                .rev()
+                .fold(0u64, |acc, b| (acc << 8) | *b as u64)
+        };
+        self.lfsrs[0].register = word(&[z[0], z[4], z[8], z[12] & 1]);
+        self.lfsrs[1].register = word(&[z[1], z[5], z[9], z[12] >> 1]);
+        self.lfsrs[2].register = word(&[z[2], z[6], z[10], z[13], z[15] & 1]);
+        self.lfsrs[3].register = word(&[z[3], z[7], z[11], z[14], z[15] >> 1]);
+    }
+
+    // Output of the summation combiner for the current state, which also updates the blend registers
+    fn next_output(&mut self) -> u8 {
+        let x: [u8; 4] = std::array::from_fn(|i| self.lfsrs[i].get_bit(OUTPUT_BITS[i]) as u8);
+        let y = x.iter().sum::<u8>();
+        let z = x[0] ^ x[1] ^ x[2] ^ x[3] ^ (self.blend[0] & 1);
+
+        let s = (y + self.blend[0]) / 2;
+        let c = s ^ self.blend[0] ^ t2(self.blend[1]);
+        self.blend = [c, self.blend[0]];
+
+        z
+    }
+
+    pub fn next_bit(&mut self) -> u8 {
+        for lfsr in self.lfsrs.iter_mut() {
+            lfsr.step();
+        }
+        self.next_output()
     }
 
+    // Bits are packed least significant first, the order in which Bluetooth transmits them
     pub fn next_byte(&mut self) -> u8 {
         let mut out = 0;
-        for _ in 0..8 {
-            out <<= 1;
-            out |= self.next_bit() as u8;
+        for i in 0..8 {
+            out |= self.next_bit() << i;
         }
         out
     }
+
+    pub fn encrypt_bytes(&self, bytes: &mut [u8]) {
+        self.clone().apply_keystream(bytes);
+    }
+}
+
+impl KeystreamGenerator for E0 {
+    fn fill_keystream(&mut self, bytes: &mut [u8]) {
+        for byte in bytes.iter_mut() {
+            *byte = self.next_byte();
+        }
+    }
 }
 
 #[cfg(test)]
@@ -63,4 +182,30 @@ mod tests {
         assert_eq!(0b01, t2(0b10));
         assert_eq!(0b10, t2(0b11));
     }
+
+    #[test]
+    fn lfsr_lengths() {
+        let cipher = E0::default();
+        for (lfsr, len) in cipher.lfsrs.iter().zip(LENS) {
+            assert_eq!(lfsr.mask, (1 << len) - 1);
+        }
+    }
+
+    #[test]
+    fn round_trip() {
+        let cipher = E0::with_key([0x21; 16], [0x2c, 0x7f, 0x94, 0x56, 0x0f, 0x1b], 0x1a5f);
+        let ptext: Vec<u8> = (0..30).collect();
+        let mut bytes = ptext.clone();
+        cipher.encrypt_bytes(&mut bytes);
+        assert_ne!(ptext, bytes);
+        cipher.encrypt_bytes(&mut bytes);
+        assert_eq!(ptext, bytes);
+    }
+
+    #[test]
+    fn clock_changes_keystream() {
+        let a = E0::with_key([0; 16], [0; 6], 0).keystream(16);
+        let b = E0::with_key([0; 16], [0; 6], 1).keystream(16);
+        assert_ne!(a, b);
+    }
 }
@@ -1,6 +1,10 @@
+use super::keystream::KeystreamBlocks;
 use num::Integer;
 use std::collections::VecDeque;
-use utils::byte_formatting::{xor_into_bytes, ByteFormat};
+use utils::{
+    byte_formatting::{xor_into_bytes, ByteFormat},
+    errors::GeneralError,
+};
 
 #[derive(Debug, Clone)]
 pub struct FishRng {
@@ -18,13 +22,37 @@ impl Default for FishRng {
 }
 
 impl FishRng {
+    /// The FISH paper does not give a key schedule, the secret is the initial contents of the two generators. At least
+    /// one word of B must be odd or no output would ever be accepted.
+    pub fn with_state(a: [u32; 55], b: [u32; 52]) -> Result<Self, GeneralError> {
+        if b.iter().all(|w| w.is_even()) {
+            return Err(GeneralError::key(
+                "at least one word of generator B must be odd",
+            ));
+        }
+        Ok(Self {
+            lfg_a: VecDeque::from(a),
+            lfg_b: VecDeque::from(b),
+        })
+    }
+
+    /// The current contents of generator A, oldest word first
+    pub fn state_a(&self) -> &VecDeque<u32> {
+        &self.lfg_a
+    }
+
+    /// The current contents of generator B, oldest word first
+    pub fn state_b(&self) -> &VecDeque<u32> {
+        &self.lfg_b
+    }
+
     pub fn step(&mut self) -> Option<(u32, u32)> {
-        // This is roughly correct but the exact taps and lengths are needed
-        let a = self.lfg_a[0].wrapping_add(self.lfg_a[24]);
+        // A[i] = A[i-55] + A[i-24] and B[i] = B[i-52] + B[i-19]
+        let a = self.lfg_a[0].wrapping_add(self.lfg_a[31]);
         self.lfg_a.pop_front();
         self.lfg_a.push_back(a);
 
-        let b = self.lfg_b[0].wrapping_add(self.lfg_b[19]);
+        let b = self.lfg_b[0].wrapping_add(self.lfg_b[33]);
         self.lfg_b.pop_front();
         self.lfg_b.push_back(b);
 
@@ -56,7 +84,7 @@ impl FishRng {
     }
 
     pub fn keystream(&mut self, n_bytes: usize) -> Vec<u8> {
-        let mut bytes = vec![0; n_bytes];
+        let mut bytes = Vec::with_capacity(n_bytes + 8);
         while bytes.len() < n_bytes {
             bytes.extend(self.next_output())
         }
@@ -65,6 +93,14 @@ impl FishRng {
     }
 }
 
+impl KeystreamBlocks for FishRng {
+    const BLOCK_SIZE: usize = 8;
+
+    fn next_keystream_block(&mut self, block: &mut [u8]) {
+        block.copy_from_slice(&self.next_output());
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct FishCipher {
     pub input_format: ByteFormat,
@@ -83,6 +119,13 @@ impl Default for FishCipher {
 }
 
 impl FishCipher {
+    pub fn with_state(a: [u32; 55], b: [u32; 52]) -> Result<Self, GeneralError> {
+        Ok(Self {
+            rng: FishRng::with_state(a, b)?,
+            ..Default::default()
+        })
+    }
+
     pub fn encrypt_bytes(&self, bytes: &mut [u8]) {
         let mut rng = self.rng.clone();
         let keystream = rng.keystream(bytes.len());
@@ -91,3 +134,50 @@ impl FishCipher {
 }
 
 crate::impl_cipher_for_stream_cipher!(FishCipher);
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn lags() {
+        let mut rng = FishRng::default();
+        rng.step();
+        // The newest words are A[55] = A[0] + A[31] and B[52] = B[0] + B[33]
+        assert_eq!(rng.lfg_a[54], 31);
+        assert_eq!(rng.lfg_b[51], 33);
+    }
+
+    #[test]
+    fn shrinking() {
+        let mut rng = FishRng::with_state(
+            std::array::from_fn(|i| i as u32 * 0x9e3779b9),
+            std::array::from_fn(|i| i as u32 * 0x7f4a7c15),
+        )
+        .unwrap();
+        for _ in 0..100 {
+            assert!(rng.next_pair().1.is_odd());
+        }
+    }
+
+    #[test]
+    fn all_even_b_rejected() {
+        assert!(FishRng::with_state([1; 55], [2; 52]).is_err());
+    }
+
+    #[test]
+    fn round_trip() {
+        let cipher = FishCipher::with_state(
+            std::array::from_fn(|i| 0xdeadbeef ^ i as u32),
+            std::array::from_fn(|i| 3 * i as u32),
+        )
+        .unwrap();
+        let ptext: Vec<u8> = (0..20).collect();
+        let mut bytes = ptext.clone();
+        cipher.encrypt_bytes(&mut bytes);
+        assert_ne!(ptext, bytes);
+        cipher.encrypt_bytes(&mut bytes);
+        assert_eq!(ptext, bytes);
+    }
+}
@@ -0,0 +1,401 @@
+use super::keystream::KeystreamGenerator;
+use rngs::grain::Grain as GrainCore;
+use utils::{byte_formatting::ByteFormat, errors::GeneralError};
+
+fn bit(x: u128, i: u32) -> u8 {
+    ((x >> i) & 1) as u8
+}
+
+fn bits(x: u128, idx: &[u32]) -> u8 {
+    idx.iter().fold(0, |acc, i| acc ^ bit(x, *i))
+}
+
+/// Grain v1 as a stream cipher, using the generator from the rngs crate.
+#[derive(Debug, Clone)]
+pub struct Grain {
+    core: GrainCore,
+    pub input_format: ByteFormat,
+    pub output_format: ByteFormat,
+}
+
+impl Default for Grain {
+    fn default() -> Self {
+        Self::with_key_and_iv([0; 10], [0; 8])
+    }
+}
+
+impl Grain {
+    pub fn with_key_and_iv(key: [u8; 10], iv: [u8; 8]) -> Self {
+        Self {
+            core: GrainCore::with_key_and_iv(key, iv),
+            input_format: ByteFormat::Hex,
+            output_format: ByteFormat::Hex,
+        }
+    }
+
+    pub fn ksa(&mut self, key: [u8; 10], iv: [u8; 8]) {
+        self.core.ksa(key, iv);
+    }
+
+    pub fn next_bit(&mut self) -> u8 {
+        self.core.next_bit()
+    }
+
+    // Bits are packed least significant first
+    pub fn next_byte(&mut self) -> u8 {
+        self.core.next_byte()
+    }
+
+    pub fn encrypt_bytes(&self, bytes: &mut [u8]) {
+        self.clone().apply_keystream(bytes);
+    }
+}
+
+impl KeystreamGenerator for Grain {
+    fn fill_keystream(&mut self, bytes: &mut [u8]) {
+        for byte in bytes.iter_mut() {
+            *byte = self.next_byte();
+        }
+    }
+}
+
+crate::impl_cipher_for_stream_cipher!(Grain);
+
+// The 128-bit registers shared by Grain-128 and its descendants
+#[derive(Debug, Clone)]
+struct Grain128State {
+    nfsr: u128,
+    lfsr: u128,
+}
+
+impl Grain128State {
+    // Load the key and nonce then run the rounds of initialization that feed the output back into both registers
+    fn new(key: [u8; 16], nonce: [u8; 12]) -> Self {
+        // The nonce is followed by 31 ones and a single zero
+        let mut state = Self {
+            nfsr: load_msb_first(&key),
+            lfsr: load_msb_first(&nonce) | (0x7fffffff << 96),
+        };
+        for _ in 0..256 {
+            let y = state.y();
+            state.clock(y, y);
+        }
+        state
+    }
+
+    fn y(&self) -> u8 {
+        let b = |i| bit(self.nfsr, i);
+        let s = |i| bit(self.lfsr, i);
+        let h = (b(12) & s(8))
+            ^ (s(13) & s(20))
+            ^ (b(95) & s(42))
+            ^ (s(60) & s(79))
+            ^ (b(12) & b(95) & s(94));
+        h ^ s(93) ^ bits(self.nfsr, &[2, 15, 36, 45, 64, 73, 89])
+    }
+
+    fn clock(&mut self, lfsr_input: u8, nfsr_input: u8) {
+        let b = |i| bit(self.nfsr, i);
+        let f = bits(self.lfsr, &[0, 7, 38, 70, 81, 96]);
+        let g = bit(self.lfsr, 0)
+            ^ bits(self.nfsr, &[0, 26, 56, 91, 96])
+            ^ (b(3) & b(67))
+            ^ (b(11) & b(13))
+            ^ (b(17) & b(18))
+            ^ (b(27) & b(59))
+            ^ (b(40) & b(48))
+            ^ (b(61) & b(65))
+            ^ (b(68) & b(84))
+            ^ (b(22) & b(24) & b(25))
+            ^ (b(70) & b(78) & b(82))
+            ^ (b(88) & b(92) & b(93) & b(95));
+
+        self.lfsr = (self.lfsr >> 1) | (((f ^ lfsr_input) as u128) << 127);
+        self.nfsr = (self.nfsr >> 1) | (((g ^ nfsr_input) as u128) << 127);
+    }
+
+    fn next_y(&mut self) -> u8 {
+        let y = self.y();
+        self.clock(0, 0);
+        y
+    }
+}
+
+// Bit i of the register is the ith bit of the bytes reading each byte from the most significant bit
+fn load_msb_first(bytes: &[u8]) -> u128 {
+    let mut b = [0; 16];
+    for (i, byte) in bytes.iter().enumerate() {
+        b[i] = byte.reverse_bits();
+    }
+    u128::from_le_bytes(b)
+}
+
+fn der_length(n: usize) -> Vec<u8> {
+    if n < 128 {
+        vec![n as u8]
+    } else {
+        let bytes: Vec<u8> = n
+            .to_be_bytes()
+            .into_iter()
+            .skip_while(|b| *b == 0)
+            .collect();
+        let mut out = vec![0x80 | bytes.len() as u8];
+        out.extend(bytes);
+        out
+    }
+}
+
+// The pre-output generator together with the authenticator, the accumulator and shift register each 64 bits
+#[derive(Debug, Clone)]
+struct Grain128AeadState {
+    state: Grain128State,
+    accumulator: u64,
+    register: u64,
+}
+
+impl Grain128AeadState {
+    fn new(key: [u8; 16], nonce: [u8; 12]) -> Self {
+        let k = load_msb_first(&key);
+        let mut state = Grain128State::new(key, nonce);
+
+        // For the last 64 rounds of initialization the key is added in a second time instead of the output
+        for t in 0..64 {
+            state.clock(bit(k, 64 + t), bit(k, t));
+        }
+
+        let mut accumulator = 0;
+        for i in 0..64 {
+            accumulator |= (state.next_y() as u64) << i;
+        }
+        let mut register = 0;
+        for i in 0..64 {
+            register |= (state.next_y() as u64) << i;
+        }
+
+        Self {
+            state,
+            accumulator,
+            register,
+        }
+    }
+
+    fn authenticate(&mut self, m: u8) {
+        if m == 1 {
+            self.accumulator ^= self.register;
+        }
+    }
+
+    // Process one bit, returning the keystream bit. Even pre-output bits are keystream and odd bits are shifted into
+    // the authentication register.
+    fn next(&mut self, m: u8) -> u8 {
+        let z = self.state.next_y();
+        let a = self.state.next_y();
+        self.authenticate(m);
+        self.register = (self.register >> 1) | ((a as u64) << 63);
+        z
+    }
+
+    // Returns the keystream bits used, packed into a byte
+    fn next_byte(&mut self, byte: u8) -> u8 {
+        let mut out = 0;
+        for i in 0..8 {
+            out |= self.next((byte >> (7 - i)) & 1) << (7 - i);
+        }
+        out
+    }
+
+    fn tag(mut self) -> [u8; 8] {
+        // A single 1 is appended for padding
+        self.authenticate(1);
+        let mut out = [0; 8];
+        for i in 0..64 {
+            out[i / 8] |= (bit(self.accumulator as u128, i as u32)) << (7 - (i % 8));
+        }
+        out
+    }
+}
+
+// https://grain-128aead.github.io/
+// The eight byte tag is appended to the ciphertext
+#[derive(Debug, Clone)]
+pub struct Grain128Aead {
+    pub key: [u8; 16],
+    pub nonce: [u8; 12],
+    pub associated_data: Vec<u8>,
+    pub input_format: ByteFormat,
+    pub output_format: ByteFormat,
+}
+
+impl Default for Grain128Aead {
+    fn default() -> Self {
+        Self {
+            key: [0; 16],
+            nonce: [0; 12],
+            associated_data: Vec::new(),
+            input_format: ByteFormat::Hex,
+            output_format: ByteFormat::Hex,
+        }
+    }
+}
+
+impl Grain128Aead {
+    pub const TAG_LEN: usize = 8;
+
+    // The associated data, preceded by its length, is authenticated but the keystream used for it is discarded
+    fn start(&self) -> Grain128AeadState {
+        let mut state = Grain128AeadState::new(self.key, self.nonce);
+        for byte in der_length(self.associated_data.len())
+            .iter()
+            .chain(self.associated_data.iter())
+        {
+            state.next_byte(*byte);
+        }
+        state
+    }
+
+    pub fn encrypt_bytes(&self, bytes: &[u8]) -> Vec<u8> {
+        let mut state = self.start();
+        let mut out: Vec<u8> = bytes.iter().map(|b| b ^ state.next_byte(*b)).collect();
+        out.extend_from_slice(&state.tag());
+        out
+    }
+
+    pub fn decrypt_bytes(&self, bytes: &[u8]) -> Result<Vec<u8>, GeneralError> {
+        if bytes.len() < Self::TAG_LEN {
+            return Err(GeneralError::input("authentication tag is missing"));
+        }
+        let (ctext, tag) = bytes.split_at(bytes.len() - Self::TAG_LEN);
+
+        let mut state = self.start();
+        let mut out = Vec::with_capacity(ctext.len());
+        for c in ctext {
+            // The keystream bits do not depend on the message so the plaintext bit is found before authenticating it
+            let mut p = 0;
+            for i in (0..8).rev() {
+                let z = state.state.y();
+                let m = ((c >> i) & 1) ^ z;
+                state.next(m);
+                p |= m << i;
+            }
+            out.push(p);
+        }
+
+        if state.tag() != tag {
+            return Err(GeneralError::input("message failed authentication"));
+        }
+        Ok(out)
+    }
+}
+
+impl crate::Cipher for Grain128Aead {
+    fn encrypt(&self, text: &str) -> Result<String, GeneralError> {
+        let bytes = self
+            .input_format
+            .text_to_bytes(text)
+            .map_err(GeneralError::input)?;
+        Ok(self
+            .output_format
+            .byte_slice_to_text(self.encrypt_bytes(&bytes)))
+    }
+
+    fn decrypt(&self, text: &str) -> Result<String, GeneralError> {
+        let bytes = self
+            .input_format
+            .text_to_bytes(text)
+            .map_err(GeneralError::input)?;
+        Ok(self
+            .output_format
+            .byte_slice_to_text(self.decrypt_bytes(&bytes)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use hex_literal::hex;
+
+    #[test]
+    fn grain_keystream_zero() {
+        let mut cipher = Grain::with_key_and_iv([0; 10], [0; 8]);
+        assert_eq!(
+            hex!("dee931cf1662a72f77d0"),
+            cipher.keystream(10).as_slice()
+        );
+    }
+
+    #[test]
+    fn grain_keystream() {
+        let mut cipher =
+            Grain::with_key_and_iv(hex!("0123456789abcdef1234"), hex!("0123456789abcdef"));
+        assert_eq!(
+            hex!("7f362bd3f7abae203664"),
+            cipher.keystream(10).as_slice()
+        );
+    }
+
+    #[test]
+    fn aead_pre_output() {
+        // Test vector 1 of the Grain-128AEADv2 specification, the pre-output bits that follow the rounds which feed the
+        // output back. The key is zero so adding it in a second time leaves the registers unchanged.
+        let mut state = Grain128State::new([0; 16], [0; 12]);
+        let mut out = [0; 40];
+        for i in 0..320 {
+            out[i / 8] |= state.next_y() << (7 - (i % 8));
+        }
+        assert_eq!(
+            hex!(
+                "c0207f221660650b6a952ae26586136fa0904140c8621cfe8660c0dec0969e9436f4ace92cf1ebb7"
+            ),
+            out
+        );
+    }
+
+    #[test]
+    fn grain_round_trip() {
+        let cipher = Grain::with_key_and_iv([0x3c; 10], [0x81; 8]);
+        let ptext: Vec<u8> = (0..29).collect();
+        let mut bytes = ptext.clone();
+        cipher.encrypt_bytes(&mut bytes);
+        assert_ne!(ptext, bytes);
+        cipher.encrypt_bytes(&mut bytes);
+        assert_eq!(ptext, bytes);
+    }
+
+    #[test]
+    fn aead_round_trip() {
+        let cipher = Grain128Aead {
+            key: hex!("000102030405060708090a0b0c0d0e0f"),
+            nonce: hex!("000102030405060708090a0b"),
+            associated_data: (0..200).map(|x| x as u8).collect(),
+            ..Default::default()
+        };
+        let ptext: Vec<u8> = (0..45).collect();
+        let ctext = cipher.encrypt_bytes(&ptext);
+        assert_eq!(ptext.len() + Grain128Aead::TAG_LEN, ctext.len());
+        assert_eq!(ptext, cipher.decrypt_bytes(&ctext).unwrap());
+    }
+
+    #[test]
+    fn aead_rejects_forgery() {
+        let mut cipher = Grain128Aead {
+            associated_data: b"header".to_vec(),
+            ..Default::default()
+        };
+        let mut ctext = cipher.encrypt_bytes(b"attack at dawn");
+        ctext[3] ^= 0x10;
+        assert!(cipher.decrypt_bytes(&ctext).is_err());
+
+        let ctext = cipher.encrypt_bytes(b"attack at dawn");
+        cipher.associated_data = b"Header".to_vec();
+        assert!(cipher.decrypt_bytes(&ctext).is_err());
+    }
+
+    #[test]
+    fn der_lengths() {
+        assert_eq!(vec![0x00], der_length(0));
+        assert_eq!(vec![0x7f], der_length(127));
+        assert_eq!(vec![0x81, 0x80], der_length(128));
+        assert_eq!(vec![0x82, 0x01, 0x00], der_length(256));
+    }
+}
@@ -1,116 +1,22 @@
+use super::keystream::KeystreamBlocks;
+use rngs::{hc128::Hc128 as Hc128Core, SimpleRng};
 use utils::byte_formatting::xor_into_bytes;
 
-fn f1(x: u32) -> u32 {
-    x.rotate_right(7) ^ x.rotate_right(18) ^ (x >> 3)
-}
-
-fn f2(x: u32) -> u32 {
-    x.rotate_right(17) ^ x.rotate_right(19) ^ (x >> 10)
-}
-
-fn g1(x: u32, y: u32, z: u32) -> u32 {
-    (x.rotate_right(10) ^ z.rotate_right(23)).wrapping_add(y.rotate_right(8))
-}
-
-fn g2(x: u32, y: u32, z: u32) -> u32 {
-    (x.rotate_left(10) ^ z.rotate_left(23)).wrapping_add(y.rotate_left(8))
-}
-
-// Subtraction modulo 512
-macro_rules! sub {
-    ($n: expr, $s: literal) => {
-        $n.wrapping_sub($s) & 0x1FF
-    };
-}
-
-#[derive(Debug, Clone)]
+/// HC-128 as a stream cipher. The generator is the one from the rngs crate so both share a single implementation.
+#[derive(Debug, Clone, Default)]
 pub struct Hc128 {
-    p: [u32; 512],
-    q: [u32; 512],
-    ctr: u32,
-}
-
-impl Default for Hc128 {
-    fn default() -> Self {
-        Self {
-            p: [0; 512],
-            q: [0; 512],
-            ctr: 0,
-        }
-    }
+    core: Hc128Core,
 }
 
 impl Hc128 {
-    fn h1(&self, x: u32) -> u32 {
-        let x0 = x & 0xff;
-        let x2 = (x >> 16) & 0xff;
-        self.q[x0 as usize].wrapping_add(self.q[(256 + x2) as usize])
-    }
-
-    fn h2(&self, x: u32) -> u32 {
-        let x0 = x & 0xff;
-        let x2 = (x >> 16) & 0xff;
-        self.p[x0 as usize].wrapping_add(self.p[(256 + x2) as usize])
-    }
-
     pub fn with_key_and_iv_u32(key: [u32; 4], iv: [u32; 4]) -> Self {
-        let mut w = [0; 1280];
-        w[0..4].copy_from_slice(&key);
-        w[4..8].copy_from_slice(&key);
-        w[8..12].copy_from_slice(&iv);
-        w[12..16].copy_from_slice(&iv);
-
-        for i in 16..1280 {
-            w[i] = f2(w[i - 2])
-                .wrapping_add(w[i - 7])
-                .wrapping_add(f1(w[i - 15]))
-                .wrapping_add(w[i - 16])
-                .wrapping_add(i as u32)
-        }
-
-        let mut out = Self::default();
-        out.p.copy_from_slice(&w[256..768]);
-        out.q.copy_from_slice(&w[768..1280]);
-
-        // These steps are the same as normal stepping but they XOR the output back into the Sbox
-        for i in 0..512 {
-            out.p[i] = out.p[i].wrapping_add(g1(
-                out.p[sub!(i, 3)],
-                out.p[sub!(i, 10)],
-                out.p[sub!(i, 511)],
-            )) ^ out.h1(out.p[sub!(i, 12)]);
-        }
-        for i in 0..512 {
-            out.q[i] = out.q[i].wrapping_add(g2(
-                out.q[sub!(i, 3)],
-                out.q[sub!(i, 10)],
-                out.q[sub!(i, 511)],
-            )) ^ out.h2(out.q[sub!(i, 12)]);
+        Self {
+            core: Hc128Core::with_key_and_iv_u32(key, iv),
         }
-
-        out
     }
 
     pub fn step(&mut self) -> u32 {
-        let j = (self.ctr % 512) as usize;
-        let out: u32;
-        if self.ctr < 512 {
-            self.p[j] = self.p[j].wrapping_add(g1(
-                self.p[sub!(j, 3)],
-                self.p[sub!(j, 10)],
-                self.p[sub!(j, 511)],
-            ));
-            out = self.h1(self.p[sub!(j, 12)]) ^ self.p[j]
-        } else {
-            self.q[j] = self.q[j].wrapping_add(g2(
-                self.q[sub!(j, 3)],
-                self.q[sub!(j, 10)],
-                self.q[sub!(j, 511)],
-            ));
-            out = self.h2(self.q[sub!(j, 12)]) ^ self.q[j]
-        }
-        self.ctr = (self.ctr + 1) % 1024;
-        out
+        self.core.next_u32()
     }
 
     pub fn next_block(&mut self) -> [u8; 4] {
@@ -133,6 +39,14 @@ impl Hc128 {
     }
 }
 
+impl KeystreamBlocks for Hc128 {
+    const BLOCK_SIZE: usize = 4;
+
+    fn next_keystream_block(&mut self, block: &mut [u8]) {
+        block.copy_from_slice(&self.next_block());
+    }
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -209,4 +123,16 @@ mod tests {
         assert_eq!(0xab03806f, cipher.step());
         assert_eq!(0xc9a6e5a0, cipher.step());
     }
+
+    // eSTREAM test vectors set 1, vector 0. The key and IV bytes are read as little endian words and so are the
+    // keystream bytes.
+    #[test]
+    fn estream_set_1_vector_0() {
+        let mut cipher = Hc128::with_key_and_iv_u32([0x80, 0, 0, 0], [0; 4]);
+        let keystream: Vec<u8> = (0..8).flat_map(|_| cipher.step().to_le_bytes()).collect();
+        assert_eq!(
+            hex_literal::hex!("378602b98f32a74847515654ae0de7ed8f72bc34776a065103e51595521ffe47"),
+            keystream.as_slice()
+        );
+    }
 }
@@ -1,112 +1,22 @@
+use super::keystream::KeystreamBlocks;
+use rngs::{hc256::Hc256 as Hc256Core, SimpleRng};
 use utils::byte_formatting::xor_into_bytes;
 
-fn f1(n: u32) -> u32 {
-    n.rotate_right(7) ^ n.rotate_right(18) ^ (n >> 3)
-}
-
-fn f2(n: u32) -> u32 {
-    n.rotate_right(17) ^ n.rotate_right(19) ^ (n >> 10)
-}
-
-// Subtraction modulo 1024
-macro_rules! sub {
-    ($n: expr, $s: literal) => {
-        $n.wrapping_sub($s) & 0x3FF
-    };
-}
-
-#[derive(Debug, Clone)]
+/// HC-256 as a stream cipher. The generator is the one from the rngs crate so both share a single implementation.
+#[derive(Debug, Clone, Default)]
 pub struct Hc256 {
-    p: [u32; 1024],
-    q: [u32; 1024],
-    ctr: u32,
-}
-
-impl Default for Hc256 {
-    fn default() -> Self {
-        Self {
-            p: [0; 1024],
-            q: [0; 1024],
-            ctr: 0,
-        }
-    }
+    core: Hc256Core,
 }
 
 impl Hc256 {
-    fn g1(&self, x: u32, y: u32) -> u32 {
-        let i = ((x ^ y) as usize) % 1024;
-        (x.rotate_right(10) ^ y.rotate_right(23)).wrapping_add(self.q[i])
-    }
-
-    fn g2(&self, x: u32, y: u32) -> u32 {
-        let i = ((x ^ y) as usize) % 1024;
-        (x.rotate_right(10) ^ y.rotate_right(23)).wrapping_add(self.p[i])
-    }
-
-    fn h1(&self, x: u32) -> u32 {
-        let x0 = x & 0xff;
-        let x1 = (x >> 8) & 0xff;
-        let x2 = (x >> 16) & 0xff;
-        let x3 = (x >> 24) & 0xff;
-        self.q[x0 as usize]
-            .wrapping_add(self.q[x1 as usize + 256])
-            .wrapping_add(self.q[x2 as usize + 512])
-            .wrapping_add(self.q[x3 as usize + 768])
-    }
-
-    fn h2(&self, x: u32) -> u32 {
-        let x0 = x & 0xff;
-        let x1 = (x >> 8) & 0xff;
-        let x2 = (x >> 16) & 0xff;
-        let x3 = (x >> 24) & 0xff;
-        self.p[x0 as usize]
-            .wrapping_add(self.p[x1 as usize + 256])
-            .wrapping_add(self.p[x2 as usize + 512])
-            .wrapping_add(self.p[x3 as usize + 768])
-    }
-
     pub fn with_key_and_iv_u32(key: [u32; 8], iv: [u32; 8]) -> Self {
-        let mut w = [0; 2560];
-        w[0..8].copy_from_slice(&key);
-        w[8..16].copy_from_slice(&iv);
-
-        for i in 16..2560 {
-            w[i] = f2(w[i - 2])
-                .wrapping_add(w[i - 7])
-                .wrapping_add(f1(w[i - 15]))
-                .wrapping_add(w[i - 16])
-                .wrapping_add(i as u32)
-        }
-
-        let mut out = Self::default();
-        out.p.copy_from_slice(&w[512..1536]);
-        out.q.copy_from_slice(&w[1536..2560]);
-
-        for _ in 0..4096 {
-            out.step();
+        Self {
+            core: Hc256Core::with_key_and_iv_u32(key, iv),
         }
-
-        // Because 4096 % 2048 = 0 there is no need to reset the counter to zero
-
-        out
     }
 
     pub fn step(&mut self) -> u32 {
-        let j = (self.ctr % 1024) as usize;
-        let out: u32;
-        if self.ctr < 1024 {
-            self.p[j] = self.p[j]
-                .wrapping_add(self.p[sub!(j, 10)])
-                .wrapping_add(self.g1(self.p[sub!(j, 3)], self.p[sub!(j, 1023)]));
-            out = self.h1(self.p[sub!(j, 12)]) ^ self.p[j]
-        } else {
-            self.q[j] = self.q[j]
-                .wrapping_add(self.q[sub!(j, 10)])
-                .wrapping_add(self.g2(self.q[sub!(j, 3)], self.q[sub!(j, 1023)]));
-            out = self.h2(self.q[sub!(j, 12)]) ^ self.q[j]
-        }
-        self.ctr = (self.ctr + 1) % 2048;
-        out
+        self.core.next_u32()
     }
 
     pub fn next_block(&mut self) -> [u8; 4] {
@@ -129,6 +39,14 @@ impl Hc256 {
     }
 }
 
+impl KeystreamBlocks for Hc256 {
+    const BLOCK_SIZE: usize = 4;
+
+    fn next_keystream_block(&mut self, block: &mut [u8]) {
+        block.copy_from_slice(&self.next_block());
+    }
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -1,3 +1,4 @@
+use super::keystream::KeystreamBlocks;
 use itertools::Itertools;
 use std::num::Wrapping;
 use utils::byte_formatting::{xor_into_bytes, ByteFormat};
@@ -160,6 +161,14 @@ impl Isaac {
 
 crate::impl_cipher_for_stream_cipher!(Isaac);
 
+impl KeystreamBlocks for Isaac {
+    const BLOCK_SIZE: usize = 4;
+
+    fn next_keystream_block(&mut self, block: &mut [u8]) {
+        block.copy_from_slice(&self.next_u32().to_be_bytes());
+    }
+}
+
 #[cfg(test)]
 mod tests {
 
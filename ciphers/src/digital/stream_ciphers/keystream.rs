@@ -0,0 +1,189 @@
+use utils::byte_formatting::xor_into_bytes;
+
+// The ciphers crate depends on the rngs crate and never the other way around. A generator that exists in both, such as
+// HC-128 and HC-256, keeps its core in rngs and the stream cipher here wraps it.
+
+/// A source of keystream bytes. Each call continues from where the previous one stopped so a keystream can be produced
+/// in pieces of any size.
+pub trait KeystreamGenerator {
+    fn fill_keystream(&mut self, bytes: &mut [u8]);
+
+    /// XOR the next bytes of keystream into the input. For an additive stream cipher this both encrypts and decrypts.
+    fn apply_keystream(&mut self, bytes: &mut [u8]) {
+        let mut keystream = vec![0; bytes.len()];
+        self.fill_keystream(&mut keystream);
+        xor_into_bytes(bytes, &keystream);
+    }
+
+    fn keystream(&mut self, n_bytes: usize) -> Vec<u8> {
+        let mut out = vec![0; n_bytes];
+        self.fill_keystream(&mut out);
+        out
+    }
+}
+
+/// A keystream generator that can jump to any position without producing the keystream before it.
+pub trait SeekableKeystream: KeystreamGenerator {
+    /// Move to a byte offset counted from the start of the keystream (block counter zero). Panics if the offset is past
+    /// the end of the keystream the generator can produce.
+    fn seek(&mut self, byte_offset: u64);
+}
+
+/// A generator that naturally produces its keystream in fixed size blocks, such as a word or a hash block. Wrap it in
+/// a BlockKeystream to get a KeystreamGenerator.
+pub trait KeystreamBlocks {
+    const BLOCK_SIZE: usize;
+
+    /// Write the next BLOCK_SIZE bytes of keystream into block
+    fn next_keystream_block(&mut self, block: &mut [u8]);
+}
+
+/// Block generators driven by a counter, allowing random access to the keystream
+pub trait SeekableBlocks: KeystreamBlocks {
+    /// Set the index of the next block to be produced. Panics if the index does not fit in the counter.
+    fn seek_block(&mut self, index: u64);
+}
+
+/// Adapts a KeystreamBlocks generator to the KeystreamGenerator trait, holding on to any part of a block that has not
+/// been used yet.
+#[derive(Debug, Clone)]
+pub struct BlockKeystream<G> {
+    pub generator: G,
+    buffer: Vec<u8>,
+    used: usize,
+}
+
+impl<G: KeystreamBlocks> BlockKeystream<G> {
+    pub fn new(generator: G) -> Self {
+        Self {
+            generator,
+            buffer: vec![0; G::BLOCK_SIZE],
+            used: G::BLOCK_SIZE,
+        }
+    }
+}
+
+impl<G: KeystreamBlocks> KeystreamGenerator for BlockKeystream<G> {
+    fn fill_keystream(&mut self, bytes: &mut [u8]) {
+        for byte in bytes.iter_mut() {
+            if self.used == G::BLOCK_SIZE {
+                self.generator.next_keystream_block(&mut self.buffer);
+                self.used = 0;
+            }
+            *byte = self.buffer[self.used];
+            self.used += 1;
+        }
+    }
+}
+
+impl<G: SeekableBlocks> SeekableKeystream for BlockKeystream<G> {
+    fn seek(&mut self, byte_offset: u64) {
+        let block_size = G::BLOCK_SIZE as u64;
+        self.generator.seek_block(byte_offset / block_size);
+        self.used = G::BLOCK_SIZE;
+        // Partway into a block the rest of that block is buffered
+        let skip = (byte_offset % block_size) as usize;
+        if skip != 0 {
+            self.generator.next_keystream_block(&mut self.buffer);
+            self.used = skip;
+        }
+    }
+}
+
+#[cfg(test)]
+mod keystream_tests {
+
+    use super::*;
+    use crate::digital::stream_ciphers::{
+        chacha::{chacha::ChaCha, chacha_ietf::ChaChaIetf},
+        hc128::Hc128,
+        rabbit::Rabbit,
+        rc4::Rc4,
+        salsa20::Salsa20,
+    };
+
+    #[test]
+    fn pieces_match_whole() {
+        let rng = Hc128::with_key_and_iv_u32([1, 2, 3, 4], [5, 6, 7, 8]);
+        let whole = BlockKeystream::new(rng.clone()).keystream(23);
+        let mut pieces = BlockKeystream::new(rng);
+        let mut a = [0; 3];
+        let mut b = [0; 5];
+        let mut c = [0; 15];
+        pieces.fill_keystream(&mut a);
+        pieces.fill_keystream(&mut b);
+        pieces.fill_keystream(&mut c);
+        assert_eq!(whole, [&a[..], &b[..], &c[..]].concat());
+    }
+
+    #[test]
+    fn chacha_seek() {
+        let mut ks = BlockKeystream::new(ChaCha::default());
+        let whole = ks.keystream(300);
+        for offset in [0, 1, 63, 64, 100, 200] {
+            ks.seek(offset);
+            assert_eq!(
+                whole[offset as usize..],
+                ks.keystream(300 - offset as usize)
+            );
+        }
+    }
+
+    #[test]
+    fn chacha_ietf_seek() {
+        let mut ks = BlockKeystream::new(ChaChaIetf::default());
+        let whole = ks.keystream(200);
+        ks.seek(130);
+        assert_eq!(whole[130..], ks.keystream(70));
+    }
+
+    #[test]
+    #[should_panic(expected = "the IETF block counter is only 32 bits")]
+    fn chacha_ietf_seek_past_counter() {
+        let mut ks = BlockKeystream::new(ChaChaIetf::default());
+        ks.seek(64 << 32);
+    }
+
+    #[test]
+    fn salsa20_seek() {
+        let mut ks = BlockKeystream::new(Salsa20::default());
+        let whole = ks.keystream(200);
+        ks.seek(65);
+        assert_eq!(whole[65..], ks.keystream(135));
+    }
+
+    // Applying the keystream must agree with each cipher's own encryption
+    #[test]
+    fn apply_matches_encrypt() {
+        let ptext: Vec<u8> = (0..100).collect();
+
+        let mut rc4 = Rc4::default();
+        rc4.ksa(b"Key");
+        let mut expected = ptext.clone();
+        rc4.encrypt_bytes(&mut expected);
+        let mut bytes = ptext.clone();
+        rc4.apply_keystream(&mut bytes);
+        assert_eq!(expected, bytes);
+
+        let rabbit = Rabbit::with_key([7; 16]);
+        let mut expected = ptext.clone();
+        expected.extend([0; 12]); // Rabbit::encrypt_bytes requires whole blocks
+        rabbit.encrypt_bytes(&mut expected);
+        let mut bytes = ptext.clone();
+        BlockKeystream::new(rabbit).apply_keystream(&mut bytes);
+        assert_eq!(expected[..100], bytes);
+
+        let chacha = ChaCha::default();
+        let mut expected = ptext.clone();
+        chacha.encrypt_bytes(&mut expected);
+        let mut bytes = ptext.clone();
+        BlockKeystream::new(chacha).apply_keystream(&mut bytes);
+        assert_eq!(expected, bytes);
+
+        let salsa = Salsa20::default();
+        let expected = salsa.encrypt_bytes_with_ctr(&ptext, salsa.ctr);
+        let mut bytes = ptext.clone();
+        BlockKeystream::new(salsa).apply_keystream(&mut bytes);
+        assert_eq!(expected, bytes);
+    }
+}
@@ -0,0 +1,328 @@
+use crate::digital::block_ciphers::{block_cipher::BlockCipher, kasumi::Kasumi};
+use utils::byte_formatting::{xor_into_bytes, ByteFormat};
+
+// https://www.etsi.org/deliver/etsi_ts/155200_155299/155216/
+// https://www.etsi.org/deliver/etsi_ts/135200_135299/135201/
+
+// Key modifiers for the first KGCORE encryption and for the final f9 encryption
+const KM_KGCORE: [u8; 16] = [0x55; 16];
+const KM_F9: [u8; 16] = [0xaa; 16];
+
+// Zero every bit after the first length bits, for messages that are not a whole number of bytes
+fn mask_trailing_bits(bytes: &mut [u8], length: usize) {
+    if !length.is_multiple_of(8) {
+        if let Some(b) = bytes.get_mut(length / 8) {
+            *b &= 0xff << (8 - length % 8);
+        }
+    }
+    for b in bytes.iter_mut().skip(length.div_ceil(8)) {
+        *b = 0;
+    }
+}
+
+fn xor_key(key: [u8; 16], modifier: [u8; 16]) -> [u8; 16] {
+    let mut out = key;
+    xor_into_bytes(&mut out, modifier);
+    out
+}
+
+/// KGCORE, the Kasumi based keystream generator shared by A5/3, GEA3, and UEA1. Returns length bits of keystream with
+/// any unused bits of the last byte set to zero. The parameters are named as in the specification.
+/// ca: 8 bit algorithm constant
+/// cb: 5 bit bearer
+/// cc: 32 bit count
+/// cd: direction bit
+/// ce: 16 bit constant
+pub fn kgcore(ca: u8, cb: u8, cc: u32, cd: bool, ce: u16, ck: [u8; 16], length: usize) -> Vec<u8> {
+    let a = ((cc as u64) << 32)
+        | (((cb & 0x1f) as u64) << 27)
+        | ((cd as u64) << 26)
+        | ((ca as u64) << 16)
+        | ce as u64;
+
+    let mut w = a.to_be_bytes();
+    Kasumi::default()
+        .with_key(xor_key(ck, KM_KGCORE))
+        .encrypt_block(&mut w);
+    let w = u64::from_be_bytes(w);
+
+    let cipher = Kasumi::default().with_key(ck);
+    let mut out = Vec::with_capacity(length.div_ceil(64) * 8);
+    let mut ksb = 0_u64;
+    for blkcnt in 0..length.div_ceil(64) as u64 {
+        let mut block = (w ^ blkcnt ^ ksb).to_be_bytes();
+        cipher.encrypt_block(&mut block);
+        ksb = u64::from_be_bytes(block);
+        out.extend_from_slice(&block);
+    }
+    out.truncate(length.div_ceil(8));
+    mask_trailing_bits(&mut out, length);
+    out
+}
+
+/// A5/3, the GSM encryption algorithm. Each TDMA frame produces a 114 bit block for each direction. The 64-bit key Kc
+/// is repeated to create the 128-bit Kasumi key. In GSM each block encrypts exactly one burst, use `burst_bytes` for
+/// that. The `Cipher` implementation instead chains the downlink blocks of consecutive frames so that text of any
+/// length can be encrypted, which is not part of the standard.
+pub struct A53 {
+    pub input_format: ByteFormat,
+    pub output_format: ByteFormat,
+    pub key: [u8; 8],
+    pub frame_number: u32,
+}
+
+impl Default for A53 {
+    fn default() -> Self {
+        Self {
+            input_format: ByteFormat::Hex,
+            output_format: ByteFormat::Hex,
+            key: [0; 8],
+            frame_number: 0,
+        }
+    }
+}
+
+impl A53 {
+    fn ck(&self) -> [u8; 16] {
+        let mut ck = [0; 16];
+        ck[..8].copy_from_slice(&self.key);
+        ck[8..].copy_from_slice(&self.key);
+        ck
+    }
+
+    /// The downlink and uplink blocks for a single frame, as defined in the standard. Each is 114 bits, stored in 15
+    /// bytes with the lower 6 bits of the last byte always zero.
+    pub fn burst_bytes(&self, frame_number: u32) -> ([u8; 15], [u8; 15]) {
+        // Frame number limited to 22 bits
+        let ks = kgcore(0x0f, 0, frame_number & 0x3fffff, false, 0, self.ck(), 228);
+        let mut block1 = [0; 15];
+        let mut block2 = [0; 15];
+        for i in 0..114 {
+            block1[i / 8] |= ((ks[i / 8] >> (7 - i % 8)) & 1) << (7 - i % 8);
+            let j = i + 114;
+            block2[i / 8] |= ((ks[j / 8] >> (7 - j % 8)) & 1) << (7 - i % 8);
+        }
+        (block1, block2)
+    }
+
+    /// Concatenate the downlink blocks of consecutive frames, starting from frame_number. This is a convenience for
+    /// encrypting longer messages and does not correspond to anything in GSM, where the blocks are never joined.
+    pub fn keystream(&self, n_bytes: usize) -> Vec<u8> {
+        let mut bytes = vec![0; n_bytes];
+        let mut f = self.frame_number;
+        let mut block = [0; 15];
+        for i in 0..(n_bytes * 8) {
+            if i % 114 == 0 {
+                block = self.burst_bytes(f).0;
+                f = (f + 1) % 0x400000;
+            }
+            let b = (block[(i % 114) / 8] >> (7 - (i % 114) % 8)) & 1;
+            bytes[i / 8] |= b << (7 - (i % 8));
+        }
+        bytes
+    }
+
+    pub fn encrypt_bytes(&self, bytes: &mut [u8]) {
+        let ks = self.keystream(bytes.len());
+        xor_into_bytes(bytes, ks);
+    }
+}
+
+crate::impl_cipher_for_stream_cipher!(A53);
+
+/// GEA3, the GPRS encryption algorithm. The 64-bit key Kc is repeated to create the 128-bit Kasumi key.
+pub struct Gea3 {
+    pub input_format: ByteFormat,
+    pub output_format: ByteFormat,
+    pub key: [u8; 8],
+    pub input: u32,
+    pub direction: bool,
+}
+
+impl Default for Gea3 {
+    fn default() -> Self {
+        Self {
+            input_format: ByteFormat::Hex,
+            output_format: ByteFormat::Hex,
+            key: [0; 8],
+            input: 0,
+            direction: false,
+        }
+    }
+}
+
+impl Gea3 {
+    pub fn encrypt_bytes(&self, bytes: &mut [u8]) {
+        let mut ck = [0; 16];
+        ck[..8].copy_from_slice(&self.key);
+        ck[8..].copy_from_slice(&self.key);
+        let ks = kgcore(0xff, 0, self.input, self.direction, 0, ck, bytes.len() * 8);
+        xor_into_bytes(bytes, ks);
+    }
+}
+
+crate::impl_cipher_for_stream_cipher!(Gea3);
+
+/// UEA1, the f8 confidentiality algorithm of UMTS built on Kasumi.
+pub struct Uea1 {
+    pub input_format: ByteFormat,
+    pub output_format: ByteFormat,
+    pub key: [u8; 16],
+    pub count: u32,
+    pub bearer: u8,
+    pub direction: bool,
+}
+
+impl Default for Uea1 {
+    fn default() -> Self {
+        Self {
+            input_format: ByteFormat::Hex,
+            output_format: ByteFormat::Hex,
+            key: [0; 16],
+            count: 0,
+            bearer: 0,
+            direction: false,
+        }
+    }
+}
+
+impl Uea1 {
+    /// Encrypt a message of length bits. Any unused bits of the last byte are set to zero.
+    pub fn encrypt_bits(&self, bytes: &mut [u8], length: usize) {
+        let ks = kgcore(
+            0,
+            self.bearer,
+            self.count,
+            self.direction,
+            0,
+            self.key,
+            length,
+        );
+        xor_into_bytes(&mut *bytes, ks);
+        mask_trailing_bits(bytes, length);
+    }
+
+    pub fn encrypt_bytes(&self, bytes: &mut [u8]) {
+        self.encrypt_bits(bytes, bytes.len() * 8);
+    }
+}
+
+crate::impl_cipher_for_stream_cipher!(Uea1);
+
+/// UIA1, the f9 integrity algorithm of UMTS built on Kasumi. Produces a 32-bit MAC for a message of length bits.
+pub fn uia1(
+    key: [u8; 16],
+    count: u32,
+    fresh: u32,
+    direction: bool,
+    message: &[u8],
+    length: usize,
+) -> u32 {
+    assert!(
+        message.len() * 8 >= length,
+        "message is shorter than the given length"
+    );
+
+    // The padded string is COUNT || FRESH || MESSAGE || DIRECTION || 1 || 0...
+    let total = 64 + length + 2;
+    let mut ps = vec![0_u8; total.div_ceil(64) * 8];
+    ps[..4].copy_from_slice(&count.to_be_bytes());
+    ps[4..8].copy_from_slice(&fresh.to_be_bytes());
+    ps[8..8 + length.div_ceil(8)].copy_from_slice(&message[..length.div_ceil(8)]);
+    mask_trailing_bits(&mut ps[8..], length);
+    let bit = 64 + length;
+    ps[bit / 8] |= (direction as u8) << (7 - bit % 8);
+    let bit = bit + 1;
+    ps[bit / 8] |= 1 << (7 - bit % 8);
+
+    let cipher = Kasumi::default().with_key(key);
+    let mut a = [0_u8; 8];
+    let mut b = [0_u8; 8];
+    for block in ps.chunks_exact(8) {
+        xor_into_bytes(&mut a, block);
+        cipher.encrypt_block(&mut a);
+        xor_into_bytes(&mut b, a);
+    }
+    Kasumi::default()
+        .with_key(xor_key(key, KM_F9))
+        .encrypt_block(&mut b);
+    u32::from_be_bytes(b[..4].try_into().unwrap())
+}
+
+#[cfg(test)]
+mod kgcore_tests {
+
+    use super::*;
+    use hex_literal::hex;
+
+    // 3GPP TS 35.203 confidentiality test set 1
+    #[test]
+    fn uea1_test_set_1() {
+        let cipher = Uea1 {
+            key: hex!("2bd6459f82c5b300952c49104881ff48"),
+            count: 0x72a4f20f,
+            bearer: 0x0c,
+            direction: true,
+            ..Default::default()
+        };
+        let mut bytes = hex!("7ec61272743bf1614726446a6c38ced166f6ca76eb5430044286346cef130f92922b03450d3a9975e5bd2ea0eb55ad8e1b199e3ec4316020e9a1b285e762795359b7bdfd39bef4b2484583d5afe082aee638bf5fd5a606193901a08f4ab41aab9b134880");
+        cipher.encrypt_bits(&mut bytes, 798);
+        assert_eq!(
+            hex!("d1e2de70eef86c6964fb542bc2d460aabfaa10a4a093262b7d199e706fc2d4891553296910f3a973012682e41c4e2b02be2017b7253bbf9309de5819cb42e81956f4c99bc9765caf53b1d0bb8279826adbbc5522e915c120a618a5a7f5e897089339650c"),
+            bytes
+        );
+        // The cipher is an involution
+        cipher.encrypt_bits(&mut bytes, 798);
+        assert_eq!(
+            hex!("7ec61272743bf1614726446a6c38ced166f6ca76eb5430044286346cef130f92922b03450d3a9975e5bd2ea0eb55ad8e1b199e3ec4316020e9a1b285e762795359b7bdfd39bef4b2484583d5afe082aee638bf5fd5a606193901a08f4ab41aab9b134880"),
+            bytes
+        );
+    }
+
+    // The keystream continues with the downlink block of the next frame
+    #[test]
+    fn a53_frames() {
+        let cipher = A53 {
+            key: hex!("2bd6459f82c5b300"),
+            frame_number: 0x3fffff,
+            ..Default::default()
+        };
+        let ks = cipher.keystream(30);
+        let (first, _) = cipher.burst_bytes(0x3fffff);
+        let (second, _) = cipher.burst_bytes(0);
+        assert_eq!(first[..14], ks[..14]);
+        assert_eq!(first[14] & 0xc0, ks[14] & 0xc0);
+        // Bits 114 through 119 of the keystream are the first six bits of the next frame
+        assert_eq!(second[0] >> 2, ks[14] & 0x3f);
+    }
+
+    #[test]
+    fn gea3_round_trip() {
+        let cipher = Gea3 {
+            key: hex!("2bd6459f82c5b300"),
+            input: 0x8e9421a3,
+            direction: true,
+            ..Default::default()
+        };
+        let ptext = hex!("00112233445566778899aabbccddeeff0123");
+        let mut bytes = ptext;
+        cipher.encrypt_bytes(&mut bytes);
+        assert_ne!(ptext, bytes);
+        cipher.encrypt_bytes(&mut bytes);
+        assert_eq!(ptext, bytes);
+    }
+
+    // 3GPP TS 35.203 integrity test set 1
+    #[test]
+    fn uia1_test_set_1() {
+        let mac = uia1(
+            hex!("2bd6459f82c5b300952c49104881ff48"),
+            0x38a6f056,
+            0x05d2ec49,
+            false,
+            &hex!("6b227737296f393c8079353edc87e2e805d2ec49a4f2d8e0"),
+            189,
+        );
+        assert_eq!(0xf63bd72c, mac);
+    }
+}
@@ -0,0 +1,88 @@
+use super::keystream::KeystreamGenerator;
+use rngs::mickey::Mickey2 as Mickey2Core;
+use utils::{byte_formatting::ByteFormat, errors::GeneralError};
+
+/// MICKEY 2.0 as a stream cipher, using the generator from the rngs crate.
+#[derive(Debug, Clone)]
+pub struct Mickey2 {
+    core: Mickey2Core,
+    pub input_format: ByteFormat,
+    pub output_format: ByteFormat,
+}
+
+impl Default for Mickey2 {
+    fn default() -> Self {
+        Self::with_key_and_iv([0; 10], &[]).unwrap()
+    }
+}
+
+impl Mickey2 {
+    /// The IV may be anywhere from zero to eighty bits, given here as up to ten bytes
+    pub fn with_key_and_iv(key: [u8; 10], iv: &[u8]) -> Result<Self, GeneralError> {
+        Ok(Self {
+            core: Mickey2Core::with_key_and_iv(key, iv)?,
+            input_format: ByteFormat::Hex,
+            output_format: ByteFormat::Hex,
+        })
+    }
+
+    pub fn ksa(&mut self, key: [u8; 10], iv: &[u8]) -> Result<(), GeneralError> {
+        self.core.ksa(key, iv)
+    }
+
+    pub fn next_bit(&mut self) -> u8 {
+        self.core.next_bit()
+    }
+
+    // Bits are packed most significant first
+    pub fn next_byte(&mut self) -> u8 {
+        self.core.next_byte()
+    }
+
+    pub fn encrypt_bytes(&self, bytes: &mut [u8]) {
+        self.clone().apply_keystream(bytes);
+    }
+}
+
+impl KeystreamGenerator for Mickey2 {
+    fn fill_keystream(&mut self, bytes: &mut [u8]) {
+        for byte in bytes.iter_mut() {
+            *byte = self.next_byte();
+        }
+    }
+}
+
+crate::impl_cipher_for_stream_cipher!(Mickey2);
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use hex_literal::hex;
+
+    #[test]
+    fn keystream() {
+        let mut cipher =
+            Mickey2::with_key_and_iv(hex!("123456789abcdef01234"), &hex!("21436587")).unwrap();
+        assert_eq!(
+            hex!("9821e10c5ed28d32bbc3d1fb15e93a15"),
+            cipher.keystream(16).as_slice()
+        );
+    }
+
+    #[test]
+    fn iv_too_long() {
+        assert!(Mickey2::with_key_and_iv([0; 10], &[0; 11]).is_err());
+    }
+
+    #[test]
+    fn round_trip() {
+        let cipher = Mickey2::with_key_and_iv([0xa5; 10], &[1, 2, 3]).unwrap();
+        let ptext: Vec<u8> = (0..31).collect();
+        let mut bytes = ptext.clone();
+        cipher.encrypt_bytes(&mut bytes);
+        assert_ne!(ptext, bytes);
+        cipher.encrypt_bytes(&mut bytes);
+        assert_eq!(ptext, bytes);
+    }
+}
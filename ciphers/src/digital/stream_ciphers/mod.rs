@@ -5,16 +5,24 @@ pub mod aes_gcm;
 pub mod chacha;
 pub mod e0;
 pub mod fish;
+pub mod grain;
 pub mod hc128;
 pub mod hc256;
 pub mod isaac;
+pub mod keystream;
+pub mod kgcore;
 pub mod lfsr;
+pub mod mickey;
 pub mod rabbit;
 pub mod rc4;
 pub mod salsa20;
+pub mod secretbox;
 pub mod seal;
 pub mod snow;
+pub mod sosemanuk;
+pub mod trivium;
 pub mod wake;
+pub mod xsalsa20;
 
 #[macro_export]
 macro_rules! impl_cipher_for_stream_cipher {
@@ -1,3 +1,4 @@
+use super::keystream::KeystreamGenerator;
 use utils::byte_formatting::ByteFormat;
 
 pub struct Rc4 {
@@ -7,6 +8,8 @@ pub struct Rc4 {
     pub drop: u32,
     pub input_format: ByteFormat,
     pub output_format: ByteFormat,
+    // Whether the first drop bytes have been discarded by the keystream generator
+    dropped: bool,
 }
 
 impl Default for Rc4 {
@@ -22,6 +25,7 @@ impl Default for Rc4 {
             drop: 0,
             input_format: ByteFormat::Hex,
             output_format: ByteFormat::Hex,
+            dropped: false,
         }
     }
 }
@@ -40,6 +44,7 @@ impl Rc4 {
         }
         self.i = 0;
         self.j = 0;
+        self.dropped = false;
     }
 
     pub fn next_byte(&mut self) -> u8 {
@@ -82,6 +87,20 @@ impl Rc4 {
 
 crate::impl_cipher_for_stream_cipher!(Rc4);
 
+impl KeystreamGenerator for Rc4 {
+    fn fill_keystream(&mut self, bytes: &mut [u8]) {
+        if !self.dropped {
+            for _ in 0..self.drop {
+                self.next_byte();
+            }
+            self.dropped = true;
+        }
+        for byte in bytes.iter_mut() {
+            *byte = self.next_byte();
+        }
+    }
+}
+
 #[cfg(test)]
 mod rc4_tests {
 
@@ -101,6 +120,22 @@ mod rc4_tests {
         assert_eq!(cipher.encrypt(PTEXT).unwrap(), CTEXT)
     }
 
+    #[test]
+    fn keystream_drop() {
+        let mut cipher = Rc4::default();
+        cipher.ksa("Secret".as_bytes());
+        cipher.drop = 768;
+        let mut expected = PTEXT.as_bytes().to_vec();
+        cipher.encrypt_bytes(&mut expected);
+
+        // The drop happens once, however the keystream is split up
+        let mut bytes = PTEXT.as_bytes().to_vec();
+        let (a, b) = bytes.split_at_mut(5);
+        cipher.apply_keystream(a);
+        cipher.apply_keystream(b);
+        assert_eq!(expected, bytes);
+    }
+
     #[test]
     fn decrypt_test() {
         let mut cipher = Rc4::default();
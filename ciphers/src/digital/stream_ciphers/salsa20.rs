@@ -1,4 +1,6 @@
-use utils::byte_formatting::ByteFormat;
+use super::keystream::{KeystreamBlocks, SeekableBlocks};
+use rngs::salsa20::Salsa20 as Salsa20Core;
+use utils::byte_formatting::{u32s_to_bytes_le, ByteFormat};
 
 // https://cr.yp.to/snuffle/salsafamily-20071225.pdf
 pub struct Salsa20 {
@@ -7,6 +9,7 @@ pub struct Salsa20 {
     pub key: [u32; 8],
     pub nonce: [u32; 2],
     pub rounds: u8,
+    pub ctr: u64,
 }
 
 impl Default for Salsa20 {
@@ -20,6 +23,7 @@ impl Default for Salsa20 {
             ],
             nonce: [0x01040103, 0x06020905],
             rounds: 20,
+            ctr: 0,
         }
     }
 }
@@ -77,89 +81,76 @@ impl Salsa20 {
         ]
     }
 
-    pub fn quarter_round(state: &mut [u32; 16], a: usize, b: usize, c: usize, d: usize) {
-        state[b] ^= (state[a].wrapping_add(state[d])).rotate_left(7);
-        state[c] ^= (state[b].wrapping_add(state[a])).rotate_left(9);
-        state[d] ^= (state[c].wrapping_add(state[b])).rotate_left(13);
-        state[a] ^= (state[d].wrapping_add(state[c])).rotate_left(18);
-    }
-
-    // Acts on columns
-    pub fn odd_round(state: &mut [u32; 16]) {
-        Self::quarter_round(state, 0, 4, 8, 12);
-        Self::quarter_round(state, 5, 9, 13, 1);
-        Self::quarter_round(state, 10, 14, 2, 6);
-        Self::quarter_round(state, 15, 3, 7, 11);
-    }
-
-    // Acts on rows
-    pub fn even_round(state: &mut [u32; 16]) {
-        Self::quarter_round(state, 0, 1, 2, 3);
-        Self::quarter_round(state, 5, 6, 7, 4);
-        Self::quarter_round(state, 10, 11, 8, 9);
-        Self::quarter_round(state, 15, 12, 13, 14);
-    }
-
-    pub fn double_round(state: &mut [u32; 16]) {
-        // Odd rounds first as the specification counts from one
-        Self::odd_round(state);
-        Self::even_round(state);
+    // Produce the block of keystream for a particular counter value
+    pub fn key_stream_block(&self, ctr: u64) -> [u8; 64] {
+        let mut block = [0; 64];
+        u32s_to_bytes_le(
+            &mut block,
+            Salsa20Core::block_function(&self.create_state(ctr), self.rounds),
+        );
+        block
     }
 
-    pub fn encrypt_bytes(&self, bytes: &[u8]) -> Vec<u8> {
-        let mut ctr = 0_u64;
-        let mut out = Vec::new();
-        let mut state = [
-            0x61707865,
-            self.key[0],
-            self.key[1],
-            self.key[2],
-            self.key[3],
-            0x3320646e,
-            self.nonce[0],
-            self.nonce[1],
-            0x00000000,
-            0x00000000,
-            0x79622d32,
-            self.key[4],
-            self.key[5],
-            self.key[6],
-            self.key[7],
-            0x6b206574,
-        ];
+    // Encrypt a message with the counter started at the given value
+    pub fn encrypt_bytes_with_ctr(&self, bytes: &[u8], ctr: u64) -> Vec<u8> {
+        let mut ctr = ctr;
+        let mut out = Vec::with_capacity(bytes.len());
 
         for block in bytes.chunks(64) {
-            // Mix the counter into the state
-            state[8] = ctr as u32; // low bits, "as" cast truncates
-            state[9] = (ctr >> 32) as u32; // high bits
-
-            // Temporary state
-            let mut t_state = state.clone();
-
-            // Only Salsa20/20, Salsa20/12, and Salsa20/8 are official but any number is usable
-            for _round in 0..self.rounds / 2 {
-                Self::double_round(&mut t_state);
-            }
-            if self.rounds % 2 == 1 {
-                Self::odd_round(&mut t_state)
+            let key_stream = self.key_stream_block(ctr);
+            for (input_byte, key_byte) in block.iter().zip(key_stream) {
+                out.push(*input_byte ^ key_byte)
             }
+            ctr = ctr.wrapping_add(1);
+        }
 
-            // XOR the current state into the temporary state
-            for (i, word) in t_state.iter_mut().enumerate() {
-                *word = word.wrapping_add(state[i])
-            }
+        out
+    }
 
-            // Create a byte stream
-            let key_steam = t_state.iter().flat_map(|w| w.to_le_bytes());
+    // Encrypt a message in place with the counter started at the stored value
+    pub fn encrypt_bytes(&self, bytes: &mut [u8]) {
+        let mut ctr = self.ctr;
 
-            for (input_byte, key_byte) in block.iter().zip(key_steam) {
-                out.push(*input_byte ^ key_byte)
+        for block in bytes.chunks_mut(64) {
+            let key_stream = self.key_stream_block(ctr);
+            for (input_byte, key_byte) in block.iter_mut().zip(key_stream) {
+                *input_byte ^= key_byte
             }
+            ctr = ctr.wrapping_add(1);
+        }
+    }
 
-            ctr += 1;
+    // HSalsa20 takes the place of the nonce and counter with 128 bits of input and skips the final addition, then
+    // returns the words that are not directly derived from the key. Used to derive subkeys for XSalsa20 and NaCl.
+    pub fn hsalsa20(key: [u32; 8], input: [u32; 4], rounds: u8) -> [u32; 8] {
+        let mut state = [
+            0x61707865, key[0], key[1], key[2], key[3], 0x3320646e, input[0], input[1], input[2],
+            input[3], 0x79622d32, key[4], key[5], key[6], key[7], 0x6b206574,
+        ];
+        for _round in 0..rounds / 2 {
+            Salsa20Core::double_round(&mut state);
         }
+        if rounds % 2 == 1 {
+            Salsa20Core::odd_round(&mut state)
+        }
+        [
+            state[0], state[5], state[10], state[15], state[6], state[7], state[8], state[9],
+        ]
+    }
+}
 
-        out
+impl KeystreamBlocks for Salsa20 {
+    const BLOCK_SIZE: usize = 64;
+
+    fn next_keystream_block(&mut self, block: &mut [u8]) {
+        block.copy_from_slice(&self.key_stream_block(self.ctr));
+        self.ctr = self.ctr.wrapping_add(1);
+    }
+}
+
+impl SeekableBlocks for Salsa20 {
+    fn seek_block(&mut self, index: u64) {
+        self.ctr = index;
     }
 }
 
@@ -171,6 +162,7 @@ mod salsa20_tests {
     use super::*;
 
     use crate::Cipher;
+    use utils::byte_formatting::{make_u32s_le, u32s_to_bytes_le};
 
     #[test]
     fn encrypt_decrypt_test() {
@@ -178,9 +170,26 @@ mod salsa20_tests {
         let cipher = Salsa20::default();
 
         let ctext = cipher.encrypt(ptext).unwrap();
+        assert_ne!(ctext, ptext);
         assert_eq!(cipher.decrypt(&ctext).unwrap(), ptext);
     }
 
+    // https://cr.yp.to/highspeed/naclcrypto-20090310.pdf
+    #[test]
+    fn hsalsa20_test() {
+        // Shared secret from the NaCl crypto_box example
+        let shared: [u32; 8] = make_u32s_le(&hex_literal::hex!(
+            "4a5d9d5ba4ce2de1728e3bf480350f25e07e21c947d19e3376f09b3c1e161742"
+        ));
+        let key = Salsa20::hsalsa20(shared, [0; 4], 20);
+        let mut out = [0; 32];
+        u32s_to_bytes_le(&mut out, key);
+        assert_eq!(
+            hex_literal::hex!("1b27556473e985d462cd51197a9a46c76009549eac6474f206c4ee0844f68389"),
+            out
+        );
+    }
+
     // #[test]
     // fn test_core() {
     //     let input = [
@@ -199,7 +208,7 @@ mod salsa20_tests {
     //     ];
 
     //     for i in 0..4 {
-    //         Salsa20::double_round(state);
+    //         Salsa20Core::double_round(state);
     //     }
 
     // }
@@ -220,7 +229,7 @@ mod salsa20_tests {
         );
 
         for _ in 0..10 {
-            Salsa20::double_round(&mut t_state);
+            Salsa20Core::double_round(&mut t_state);
         }
 
         assert_eq!(
@@ -252,7 +261,7 @@ mod salsa20_tests {
             fn $name() {
                 let cipher = Salsa20::salsa20_20().with_key($key).with_nonce($nonce);
                 let reference: [u8; 256] = $reference;
-                let stream = cipher.encrypt_bytes(&[0; 512]);
+                let stream = cipher.encrypt_bytes_with_ctr(&[0; 512], 0);
                 assert_eq!(&reference[0..64], &stream[0..64]);
                 assert_eq!(&reference[64..128], &stream[192..256]);
                 assert_eq!(&reference[128..192], &stream[256..320]);
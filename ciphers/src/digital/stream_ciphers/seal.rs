@@ -1,5 +1,5 @@
 use num::Integer;
-use utils::byte_formatting::ByteFormat;
+use utils::byte_formatting::{xor_into_bytes, ByteFormat};
 
 // https://www.cs.ucdavis.edu/~rogaway/papers/seal.pdf
 pub struct Seal3 {
@@ -8,9 +8,7 @@ pub struct Seal3 {
 
     pub key: [u32; 5], // 160-bit key
     pub n: u32,
-    pub l: u32, // number of output bits, limited to 524288 (64 * 1024 * 8)
-    // registers: [u32; 4],
-    // ns: [u32; 4],
+    pub l: u32,    // number of output bits, limited to 524288 (64 * 1024 * 8)
     r: [u32; 256], // r is sometimes not completely used depending on the value of l
     t: [u32; 512],
     s: [u32; 256],
@@ -18,23 +16,33 @@ pub struct Seal3 {
 
 impl Default for Seal3 {
     fn default() -> Self {
-        Self {
+        let mut out = Self {
             input_format: ByteFormat::Hex,
             output_format: ByteFormat::Hex,
 
             key: [0; 5],
             n: 0,
             l: 524288,
-            // registers: [0; 4],
-            // ns: [0; 4],
             r: [0; 256],
             t: [0; 512],
             s: [0; 256],
-        }
+        };
+        out.derive_tables();
+        out
     }
 }
 
 impl Seal3 {
+    pub fn with_key_and_nonce(key: [u32; 5], n: u32) -> Self {
+        let mut out = Self {
+            key,
+            n,
+            ..Default::default()
+        };
+        out.derive_tables();
+        out
+    }
+
     /// This is taken from the SHA1 compression function
     pub fn g(&self, i: u32) -> [u32; 5] {
         let mut a = self.key[0];
@@ -44,7 +52,7 @@ impl Seal3 {
         let mut e = self.key[4];
 
         let mut w = [0u32; 80];
-        w[0] = i.to_le();
+        w[0] = i;
 
         // Fill all of w
         for i in 16..80 {
@@ -83,11 +91,11 @@ impl Seal3 {
             b = a;
             a = t;
         }
-        a = a.wrapping_add(a);
-        b = b.wrapping_add(b);
-        c = c.wrapping_add(c);
-        d = d.wrapping_add(d);
-        e = e.wrapping_add(e);
+        a = a.wrapping_add(self.key[0]);
+        b = b.wrapping_add(self.key[1]);
+        c = c.wrapping_add(self.key[2]);
+        d = d.wrapping_add(self.key[3]);
+        e = e.wrapping_add(self.key[4]);
 
         [a, b, c, d, e]
     }
@@ -119,7 +127,7 @@ impl Seal3 {
         let mut a = self.n ^ self.r[4 * ctr];
         let mut b = self.n.rotate_right(8) ^ self.r[4 * ctr + 1];
         let mut c = self.n.rotate_right(16) ^ self.r[4 * ctr + 2];
-        let mut d = self.n.rotate_right(23) ^ self.r[4 * ctr + 3];
+        let mut d = self.n.rotate_right(24) ^ self.r[4 * ctr + 3];
 
         let mut p = 0;
 
@@ -140,27 +148,92 @@ impl Seal3 {
         *registers = [a, b, c, d];
     }
 
-    fn keystream(&self, p: &mut u32, q: &mut u32, registers: &mut [u32; 4], ns: &mut [u32; 4]) {}
+    /// Produce the 16 bytes of keystream for step i (counting from 1) after the registers have been initialized
+    fn keystream_block(&self, i: usize, registers: &mut [u32; 4], ns: &[u32; 4]) -> [u8; 16] {
+        let [mut a, mut b, mut c, mut d] = *registers;
 
-    pub fn encrypt_bytes(&self, bytes: &mut [u8]) {
+        let mut p = a & 0x7fc;
+        b = b.wrapping_add(self.t[p as usize / 4]);
+        a = a.rotate_right(9);
+        b ^= a;
+
+        let mut q = b & 0x7fc;
+        c ^= self.t[q as usize / 4];
+        b = b.rotate_right(9);
+        c = c.wrapping_add(b);
+
+        p = p.wrapping_add(c) & 0x7fc;
+        d = d.wrapping_add(self.t[p as usize / 4]);
+        c = c.rotate_right(9);
+        d ^= c;
+
+        q = q.wrapping_add(d) & 0x7fc;
+        a ^= self.t[q as usize / 4];
+        d = d.rotate_right(9);
+        a = a.wrapping_add(d);
+
+        p = p.wrapping_add(a) & 0x7fc;
+        b ^= self.t[p as usize / 4];
+        a = a.rotate_right(9);
+
+        q = q.wrapping_add(b) & 0x7fc;
+        c = c.wrapping_add(self.t[q as usize / 4]);
+        b = b.rotate_right(9);
+
+        p = p.wrapping_add(c) & 0x7fc;
+        d ^= self.t[p as usize / 4];
+        c = c.rotate_right(9);
+
+        q = q.wrapping_add(d) & 0x7fc;
+        a = a.wrapping_add(self.t[q as usize / 4]);
+        d = d.rotate_right(9);
+
+        let mut out = [0; 16];
+        out[0..4].copy_from_slice(&b.wrapping_add(self.s[4 * i - 4]).to_be_bytes());
+        out[4..8].copy_from_slice(&(c ^ self.s[4 * i - 3]).to_be_bytes());
+        out[8..12].copy_from_slice(&d.wrapping_add(self.s[4 * i - 2]).to_be_bytes());
+        out[12..16].copy_from_slice(&(a ^ self.s[4 * i - 1]).to_be_bytes());
+
+        // Odd and even steps mix in different pairs of the saved words
+        if i.is_odd() {
+            a = a.wrapping_add(ns[0]);
+            c = c.wrapping_add(ns[1]);
+        } else {
+            a = a.wrapping_add(ns[2]);
+            c = c.wrapping_add(ns[3]);
+        }
+
+        *registers = [a, b, c, d];
+        out
+    }
+
+    pub fn keystream(&self, n_bytes: usize) -> Vec<u8> {
+        let mut out = Vec::with_capacity(n_bytes + 16);
         let mut ctr = 0;
         let mut registers = [0; 4];
         let mut ns = [0; 4];
-        let mut chunks = bytes.chunks_mut(16);
-
-        loop {
+        'outer: loop {
             self.initialize(ctr, &mut registers, &mut ns);
             for i in 1..=64 {
-                // Generate keystream bytes
-                if let Some(chunk) = chunks.next() {
-                    // XOR keystream into the chunk
-                } else {
-                    return ();
+                if out.len() >= n_bytes {
+                    break 'outer;
                 }
+                out.extend_from_slice(&self.keystream_block(i, &mut registers, &ns));
             }
-
             ctr += 1;
         }
+        out.truncate(n_bytes);
+        out
+    }
+
+    pub fn encrypt_bytes(&self, bytes: &mut [u8]) {
+        assert!(
+            bytes.len() * 8 <= self.l as usize,
+            "SEAL 3.0 is limited to {} bits of output",
+            self.l
+        );
+        let keystream = self.keystream(bytes.len());
+        xor_into_bytes(bytes, &keystream);
     }
 }
 
@@ -174,21 +247,41 @@ mod seal_tests {
     #[test]
     fn tables() {
         let mut cipher = Seal3::default();
-        cipher.key = [0x67462301, 0xefcdab89, 0x98badcfe, 0x10325476, 0xc3d2e1f0];
+        cipher.key = [0x67452301, 0xefcdab89, 0x98badcfe, 0x10325476, 0xc3d2e1f0];
 
         cipher.derive_tables();
 
-        // assert_eq!(
-        //     &cipher.r[0..16],
-        //     &[
-        //         0x5021758d, 0xce577c11, 0xfa5bd5dd, 0x366d1b93, 0x182cff72, 0xac06d7c6, 0x2683ead8,
-        //         0xfabe3573, 0x82a10c96, 0x48c483bd, 0xca92285c, 0x71fe84c0, 0xbd76b700, 0x6fdcc20c,
-        //         0x8dada151, 0x4506dd64
-        //     ]
-        // );
-
-        println!("R: {:08x?}", &cipher.r[0..4]);
-        println!("T: {:08x?}", &cipher.t[0..4]);
-        println!("S: {:08x?}", &cipher.s[0..4]);
+        assert_eq!(
+            &cipher.r[0..16],
+            &[
+                0x5021758d, 0xce577c11, 0xfa5bd5dd, 0x366d1b93, 0x182cff72, 0xac06d7c6, 0x2683ead8,
+                0xfabe3573, 0x82a10c96, 0x48c483bd, 0xca92285c, 0x71fe84c0, 0xbd76b700, 0x6fdcc20c,
+                0x8dada151, 0x4506dd64
+            ]
+        );
+    }
+
+    #[test]
+    fn keystream() {
+        let cipher = Seal3::with_key_and_nonce(
+            [0x67452301, 0xefcdab89, 0x98badcfe, 0x10325476, 0xc3d2e1f0],
+            0x013577af,
+        );
+        assert_eq!(
+            cipher.keystream(16),
+            [
+                0x37, 0xa0, 0x05, 0x95, 0x9b, 0x84, 0xc4, 0x9c, 0xa4, 0xbe, 0x1e, 0x05, 0x06, 0x73,
+                0x53, 0x0f
+            ]
+        );
+    }
+
+    #[test]
+    fn keystream_crosses_counter() {
+        // The registers are reinitialized from R after every 1024 bytes
+        let cipher = Seal3::with_key_and_nonce([1, 2, 3, 4, 5], 6);
+        let long = cipher.keystream(2100);
+        assert_eq!(&long[..1024], &cipher.keystream(1024)[..]);
+        assert_ne!(&long[..1024], &long[1024..2048]);
     }
 }
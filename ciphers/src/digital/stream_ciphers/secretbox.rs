@@ -0,0 +1,158 @@
+use super::{salsa20::Salsa20, xsalsa20::XSalsa20};
+use crate::Cipher;
+use hashers::{poly1305::Poly1305, traits::StatefulHasher};
+use utils::{
+    byte_formatting::{make_u32s_le, u32s_to_bytes_le},
+    errors::GeneralError,
+};
+
+// NaCl crypto_secretbox, XSalsa20 with a Poly1305 tag
+// https://cr.yp.to/highspeed/naclcrypto-20090310.pdf
+#[derive(Default)]
+pub struct SecretBox {
+    pub cipher: XSalsa20,
+}
+
+impl SecretBox {
+    pub fn with_key_and_nonce(mut self, key: [u8; 32], nonce: [u8; 24]) -> Self {
+        self.cipher.key_and_nonce(key, nonce);
+        self
+    }
+
+    // The precomputation step of crypto_box. The Curve25519 shared secret is passed through HSalsa20 with an input of
+    // zero and the result is the secretbox key.
+    pub fn from_shared_secret(shared_secret: [u8; 32], nonce: [u8; 24]) -> Self {
+        let key = Salsa20::hsalsa20(make_u32s_le(&shared_secret), [0; 4], 20);
+        let mut out = Self::default();
+        out.cipher.key = key;
+        out.cipher.nonce = make_u32s_le(&nonce);
+        out
+    }
+
+    // The first 32 bytes of keystream are the one time Poly1305 key and the message is encrypted with the rest
+    fn keystream(&self, len: usize) -> Vec<u8> {
+        self.cipher.encrypt_bytes_with_ctr(&vec![0; len + 32], 0)
+    }
+
+    fn tag(poly_key: &[u8], encrypted_bytes: &[u8]) -> Vec<u8> {
+        Poly1305::init(&poly_key[0..16], &poly_key[16..32]).hash(encrypted_bytes)
+    }
+
+    // The tag is placed before the encrypted message
+    pub fn encrypt_bytes(&self, bytes: &[u8]) -> Vec<u8> {
+        let keystream = self.keystream(bytes.len());
+        let encrypted_bytes: Vec<u8> = bytes
+            .iter()
+            .zip(&keystream[32..])
+            .map(|(b, k)| b ^ k)
+            .collect();
+        let mut out = Self::tag(&keystream[0..32], &encrypted_bytes);
+        out.extend(encrypted_bytes);
+        out
+    }
+
+    pub fn decrypt_bytes(&self, message: &[u8]) -> Result<Vec<u8>, GeneralError> {
+        if message.len() < 16 {
+            return Err(GeneralError::input("authentication tag is missing"));
+        }
+        let (message_tag, encrypted_bytes) = message.split_at(16);
+
+        let keystream = self.keystream(encrypted_bytes.len());
+        if message_tag != Self::tag(&keystream[0..32], encrypted_bytes) {
+            return Err(GeneralError::input("message failed authentication"));
+        }
+
+        Ok(encrypted_bytes
+            .iter()
+            .zip(&keystream[32..])
+            .map(|(b, k)| b ^ k)
+            .collect())
+    }
+
+    pub fn key_bytes(&self) -> [u8; 32] {
+        let mut out = [0; 32];
+        u32s_to_bytes_le(&mut out, self.cipher.key);
+        out
+    }
+}
+
+impl Cipher for SecretBox {
+    fn encrypt(&self, text: &str) -> Result<String, GeneralError> {
+        let bytes = self
+            .cipher
+            .input_format
+            .text_to_bytes(text)
+            .map_err(|_| GeneralError::input("byte format error"))?;
+
+        Ok(self
+            .cipher
+            .output_format
+            .byte_slice_to_text(self.encrypt_bytes(&bytes)))
+    }
+
+    fn decrypt(&self, text: &str) -> Result<String, GeneralError> {
+        let message = self
+            .cipher
+            .input_format
+            .text_to_bytes(text)
+            .map_err(|_| GeneralError::input("byte format error"))?;
+
+        Ok(self
+            .cipher
+            .output_format
+            .byte_slice_to_text(&self.decrypt_bytes(&message)?))
+    }
+}
+
+#[cfg(test)]
+mod secretbox_tests {
+
+    use super::*;
+    use hex_literal::hex;
+
+    const NONCE: [u8; 24] = hex!("69696ee955b62b73cd62bda875fc73d68219e0036b7a0b37");
+    const PTEXT: [u8; 131] = hex!(
+        "be075fc53c81f2d5cf141316ebeb0c7b5228c52a4c62cbd44b66849b64244ffc"
+        "e5ecbaaf33bd751a1ac728d45e6c61296cdc3c01233561f41db66cce314adb31"
+        "0e3be8250c46f06dceea3a7fa1348057e2f6556ad6b1318a024a838f21af1fde"
+        "048977eb48f59ffd4924ca1c60902e52f0a089bc76897040e082f93776384864"
+        "5e0705"
+    );
+    const CTEXT: [u8; 147] = hex!(
+        "f3ffc7703f9400e52a7dfb4b3d3305d9"
+        "8e993b9f48681273c29650ba32fc76ce48332ea7164d96a4476fb8c531a1186a"
+        "c0dfc17c98dce87b4da7f011ec48c97271d2c20f9b928fe2270d6fb863d51738"
+        "b48eeee314a7cc8ab932164548e526ae90224368517acfeabd6bb3732bc0e9da"
+        "99832b61ca01b6de56244a9e88d5f9b37973f622a43d14a6599b1f654cb45a74"
+        "e355a5"
+    );
+
+    // The secretbox and box tests of NaCl use the same message and produce the same output
+    #[test]
+    fn secretbox_test() {
+        let cipher = SecretBox::default().with_key_and_nonce(
+            hex!("1b27556473e985d462cd51197a9a46c76009549eac6474f206c4ee0844f68389"),
+            NONCE,
+        );
+        assert_eq!(CTEXT.to_vec(), cipher.encrypt_bytes(&PTEXT));
+        assert_eq!(PTEXT.to_vec(), cipher.decrypt_bytes(&CTEXT).unwrap());
+    }
+
+    #[test]
+    fn box_test() {
+        let cipher = SecretBox::from_shared_secret(
+            hex!("4a5d9d5ba4ce2de1728e3bf480350f25e07e21c947d19e3376f09b3c1e161742"),
+            NONCE,
+        );
+        assert_eq!(CTEXT.to_vec(), cipher.encrypt_bytes(&PTEXT));
+    }
+
+    #[test]
+    fn tampered() {
+        let cipher = SecretBox::default();
+        let mut ctext = cipher.encrypt_bytes(b"attack at dawn");
+        ctext[20] ^= 0x80;
+        assert!(cipher.decrypt_bytes(&ctext).is_err());
+        assert!(cipher.decrypt_bytes(&ctext[0..10]).is_err());
+    }
+}
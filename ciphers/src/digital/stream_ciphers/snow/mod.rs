@@ -1,3 +1,5 @@
 pub mod snow1;
 pub mod snow2;
 pub mod snow3g;
+pub mod snowv;
+pub mod uea2;
@@ -1,4 +1,5 @@
-use utils::byte_formatting::ByteFormat;
+use crate::digital::stream_ciphers::keystream::KeystreamBlocks;
+use utils::byte_formatting::{xor_into_bytes, ByteFormat};
 
 const SBOX0: [u32; 256] = [
     0x40408000, 0x00408000, 0x40408004, 0x10010844, 0x00410804, 0x10408804, 0x40000804, 0x00410040,
@@ -147,14 +148,16 @@ fn sbox(n: u32) -> u32 {
         | SBOX3[(n as usize) >> 24 & 0xff]
 }
 
+// Multiplication by a root of x^32 + x^29 + x^20 + x^15 + x^10 + x + 1
 fn alpha(n: u32) -> u32 {
-    if n & 0x80000000 == 1 {
+    if n & 0x80000000 != 0 {
         (n << 1) ^ 0x20108403
     } else {
         n << 1
     }
 }
 
+#[derive(Debug, Clone)]
 pub struct Fsm {
     r1: u32,
     r2: u32,
@@ -170,6 +173,7 @@ impl Fsm {
     }
 }
 
+#[derive(Debug, Clone)]
 pub struct Snow1 {
     pub input_format: ByteFormat,
     pub output_format: ByteFormat,
@@ -188,6 +192,7 @@ impl Default for Snow1 {
     }
 }
 
+// In the specification "k ⊕ 1" means the key word XORed with the all one vector
 impl Snow1 {
     pub fn with_key_128(key: [u32; 4]) -> Self {
         Self::with_key_and_iv_128(key, [0; 2])
@@ -195,68 +200,45 @@ impl Snow1 {
 
     pub fn with_key_and_iv_128(key: [u32; 4], iv: [u32; 2]) -> Self {
         let mut s = Snow1::default();
-        s.words[0] = key[0] ^ iv[0];
-        s.words[1] = key[1];
-        s.words[2] = key[2];
-        s.words[3] = key[3] ^ iv[1];
-
-        s.words[4] = key[0] ^ 1;
-        s.words[5] = key[1] ^ 1;
-        s.words[6] = key[2] ^ 1;
-        s.words[7] = key[3] ^ 1;
-
-        s.words[8] = key[0];
-        s.words[9] = key[1];
-        s.words[10] = key[2];
-        s.words[11] = key[3];
-
-        s.words[12] = key[0] ^ 1;
-        s.words[13] = key[1] ^ 1;
-        s.words[14] = key[2] ^ 1;
-        s.words[15] = key[3] ^ 1;
-
-        s.fsm.r1 = 0;
-        s.fsm.r2 = 0;
-
-        for _ in 0..32 {
-            s.next_u32();
+        for (i, k) in key.into_iter().enumerate() {
+            s.words[i] = k;
+            s.words[i + 4] = !k;
+            s.words[i + 8] = k;
+            s.words[i + 12] = !k;
         }
+        s.words[0] ^= iv[0];
+        s.words[3] ^= iv[1];
 
+        s.initialize();
         s
     }
 
-    pub fn with_key_256(mut self, key: [u32; 8]) -> Self {
+    pub fn with_key_256(key: [u32; 8]) -> Self {
         Self::with_key_and_iv_256(key, [0; 2])
     }
 
     pub fn with_key_and_iv_256(key: [u32; 8], iv: [u32; 2]) -> Self {
         let mut s = Snow1::default();
-        s.words[0] = key[0] ^ iv[0];
-        s.words[1] = key[1];
-        s.words[2] = key[2];
-        s.words[3] = key[3];
-        s.words[4] = key[4] ^ iv[1];
-        s.words[5] = key[5];
-        s.words[6] = key[6];
-        s.words[7] = key[7];
-
-        s.words[8] = key[0] ^ 1;
-        s.words[9] = key[1] ^ 1;
-        s.words[10] = key[2] ^ 1;
-        s.words[11] = key[3] ^ 1;
-        s.words[12] = key[4] ^ 1;
-        s.words[13] = key[5] ^ 1;
-        s.words[14] = key[6] ^ 1;
-        s.words[15] = key[7] ^ 1;
+        for (i, k) in key.into_iter().enumerate() {
+            s.words[i] = k;
+            s.words[i + 8] = !k;
+        }
+        s.words[0] ^= iv[0];
+        s.words[3] ^= iv[1];
 
-        s.fsm.r1 = 0;
-        s.fsm.r2 = 0;
+        s.initialize();
+        s
+    }
 
+    // Clock 32 times without output, mixing the output of the FSM into the feedback
+    fn initialize(&mut self) {
+        self.fsm = Fsm { r1: 0, r2: 0 };
         for _ in 0..32 {
-            s.next_u32();
+            let f = self.fsm.step(self.words[0]);
+            let t = alpha(self.words[6] ^ self.words[12] ^ self.words[15]) ^ f;
+            self.words.rotate_right(1);
+            self.words[0] = t;
         }
-
-        s
     }
 
     pub fn next_u32(&mut self) -> u32 {
@@ -267,12 +249,59 @@ impl Snow1 {
     }
 
     pub fn encrypt_bytes(&self, bytes: &mut [u8]) {
-        todo!()
+        self.clone().encrypt_bytes_mut(bytes);
     }
 
     pub fn encrypt_bytes_mut(&mut self, bytes: &mut [u8]) {
-        todo!()
+        let mut keystream = Vec::new();
+        for _ in 0..bytes.len().div_ceil(4) {
+            keystream.extend(self.next_u32().to_be_bytes());
+        }
+        xor_into_bytes(bytes, &keystream);
     }
 }
 
 crate::impl_cipher_for_stream_cipher!(Snow1);
+
+impl KeystreamBlocks for Snow1 {
+    const BLOCK_SIZE: usize = 4;
+
+    fn next_keystream_block(&mut self, block: &mut [u8]) {
+        block.copy_from_slice(&self.next_u32().to_be_bytes());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn alpha_reduction() {
+        assert_eq!(0x00000002, alpha(0x00000001));
+        assert_eq!(0x20108403, alpha(0x80000000));
+    }
+
+    #[test]
+    fn encrypt_decrypt() {
+        let cipher = Snow1::with_key_and_iv_128([0x80000000, 0, 0, 0], [1, 2]);
+        let ptext: Vec<u8> = (0..37).collect();
+        let mut bytes = ptext.clone();
+        cipher.encrypt_bytes(&mut bytes);
+        assert_ne!(ptext, bytes);
+        cipher.encrypt_bytes(&mut bytes);
+        assert_eq!(ptext, bytes);
+    }
+
+    #[test]
+    fn key_and_iv_change_keystream() {
+        let mut a = Snow1::with_key_128([0x80000000, 0, 0, 0]);
+        let mut b = Snow1::with_key_and_iv_128([0x80000000, 0, 0, 0], [0, 1]);
+        let mut c = Snow1::with_key_256([0x80000000, 0, 0, 0, 0, 0, 0, 0]);
+        let x: Vec<u32> = (0..8).map(|_| a.next_u32()).collect();
+        let y: Vec<u32> = (0..8).map(|_| b.next_u32()).collect();
+        let z: Vec<u32> = (0..8).map(|_| c.next_u32()).collect();
+        assert_ne!(x, y);
+        assert_ne!(x, z);
+    }
+}
@@ -1,4 +1,5 @@
-use utils::byte_formatting::{xor_into_bytes, ByteFormat};
+use crate::digital::stream_ciphers::keystream::KeystreamBlocks;
+use utils::byte_formatting::{make_u32s_be, xor_into_bytes, ByteFormat};
 
 const ALPHA_MUL: [u32; 256] = [
     0x0, 0xE19FCF13, 0x6B973726, 0x8A08F835, 0xD6876E4C, 0x3718A15F, 0xBD10596A, 0x5C8F9679,
@@ -210,11 +211,12 @@ const S3: [u32; 256] = [
     0x82c34141, 0x29b09999, 0x5a772d2d, 0x1e110f0f, 0x7bcbb0b0, 0xa8fc5454, 0x6dd6bbbb, 0x2c3a1616,
 ];
 
+// The AES SubBytes and MixColumns operations on a word, with the least significant byte taken as the first row
 fn sbox(n: u32) -> u32 {
-    S3[n as usize & 0xff]
-        | S2[(n as usize) >> 8 & 0xff]
-        | S1[(n as usize) >> 16 & 0xff]
-        | S0[(n as usize) >> 24 & 0xff]
+    S0[n as usize & 0xff]
+        ^ S1[(n as usize) >> 8 & 0xff]
+        ^ S2[(n as usize) >> 16 & 0xff]
+        ^ S3[(n as usize) >> 24 & 0xff]
 }
 
 fn alpha(n: u32) -> u32 {
@@ -225,6 +227,7 @@ fn alpha_inv(n: u32) -> u32 {
     (n >> 8) ^ ALPHA_INV_MUL[(n & 0xff) as usize]
 }
 
+// The LFSR words are stored with words[i] = s_i so that the oldest word is at the start
 #[derive(Debug, Clone)]
 pub struct Snow2 {
     pub input_format: ByteFormat,
@@ -247,97 +250,73 @@ impl Default for Snow2 {
 }
 
 impl Snow2 {
+    /// The IV is given as (IV3, IV2, IV1, IV0)
     pub fn with_key_and_iv_128(key: [u8; 16], iv: [u32; 4]) -> Self {
         let mut s = Snow2::default();
-        s.words[0] = u32::from_be_bytes([key[0], key[1], key[2], key[3]]);
-        s.words[1] = u32::from_be_bytes([key[4], key[5], key[6], key[7]]);
-        s.words[2] = u32::from_be_bytes([key[8], key[9], key[10], key[11]]);
-        s.words[3] = u32::from_be_bytes([key[12], key[13], key[14], key[15]]);
-
-        s.words[4] = !s.words[0];
-        s.words[5] = !s.words[1];
-        s.words[6] = !s.words[2];
-        s.words[7] = !s.words[3];
-
-        s.words[8] = s.words[0];
-        s.words[9] = s.words[1];
-        s.words[10] = s.words[2];
-        s.words[11] = s.words[3];
-
-        s.words[12] = !s.words[0];
-        s.words[13] = !s.words[1];
-        s.words[14] = !s.words[2];
-        s.words[15] = !s.words[3];
-
-        // XOR the IV in after setting all the words so that the first set can be copied
-        s.words[0] ^= iv[3];
-        s.words[3] ^= iv[2];
-        s.words[5] ^= iv[1];
-        s.words[6] ^= iv[0];
-
-        for _ in 0..32 {
-            s.clock_iv();
+        // The first word of the key is s15
+        for (i, k) in make_u32s_be::<4>(&key).into_iter().enumerate() {
+            s.words[15 - i] = k;
+            s.words[11 - i] = !k;
+            s.words[7 - i] = k;
+            s.words[3 - i] = !k;
         }
-        s.clock_k();
 
+        s.load_iv(iv);
         s
     }
 
+    /// The IV is given as (IV3, IV2, IV1, IV0)
     pub fn with_key_and_iv_256(key: [u8; 32], iv: [u32; 4]) -> Self {
         let mut s = Snow2::default();
-        s.words[0] = u32::from_be_bytes([key[0], key[1], key[2], key[3]]);
-        s.words[1] = u32::from_be_bytes([key[4], key[5], key[6], key[7]]);
-        s.words[2] = u32::from_be_bytes([key[8], key[9], key[10], key[11]]);
-        s.words[3] = u32::from_be_bytes([key[12], key[13], key[14], key[15]]);
-
-        s.words[4] = u32::from_be_bytes([key[16], key[17], key[18], key[19]]);
-        s.words[5] = u32::from_be_bytes([key[20], key[21], key[22], key[23]]);
-        s.words[6] = u32::from_be_bytes([key[24], key[25], key[26], key[27]]);
-        s.words[7] = u32::from_be_bytes([key[28], key[29], key[30], key[31]]);
-
-        s.words[8] = !s.words[0];
-        s.words[9] = !s.words[1];
-        s.words[10] = !s.words[2];
-        s.words[11] = !s.words[3];
-
-        s.words[12] = !s.words[4];
-        s.words[13] = !s.words[5];
-        s.words[14] = !s.words[6];
-        s.words[15] = !s.words[7];
-
-        // XOR the IV in after setting all the words so that the first set can be copied
-        s.words[0] ^= iv[3];
-        s.words[3] ^= iv[2];
-        s.words[5] ^= iv[1];
-        s.words[6] ^= iv[0];
+        // The first word of the key is s15
+        for (i, k) in make_u32s_be::<8>(&key).into_iter().enumerate() {
+            s.words[15 - i] = k;
+            s.words[7 - i] = !k;
+        }
+
+        s.load_iv(iv);
+        s
+    }
+
+    fn load_iv(&mut self, iv: [u32; 4]) {
+        self.words[15] ^= iv[3];
+        self.words[12] ^= iv[2];
+        self.words[10] ^= iv[1];
+        self.words[9] ^= iv[0];
 
         for _ in 0..32 {
-            s.clock_iv();
+            self.clock_iv();
         }
-        s.clock_k();
 
-        s
+        // The output of the first clocking in keystream mode is discarded
+        self.clock_k();
     }
 
+    /// Output of the finite state machine, which also updates it
     pub fn fsm(&mut self) -> u32 {
-        let out = self.words[0].wrapping_add(self.r1) ^ self.r2;
-        let temp = self.r2.wrapping_add(self.words[10]);
+        let out = self.words[15].wrapping_add(self.r1) ^ self.r2;
+        let temp = self.r2.wrapping_add(self.words[5]);
         self.r2 = sbox(self.r1);
         self.r1 = temp;
         out
     }
 
-    /// Clock once in IV mode
+    fn feedback(&self) -> u32 {
+        alpha(self.words[0]) ^ self.words[2] ^ alpha_inv(self.words[11])
+    }
+
+    /// Clock once in IV mode, with the FSM output mixed into the feedback
     pub fn clock_iv(&mut self) {
-        let v = alpha_inv(self.words[4]) ^ self.words[13] ^ alpha(self.words[15]) ^ self.fsm();
+        let v = self.feedback();
+        let f = self.fsm();
         self.words.rotate_left(1);
-        self.words[15] = v;
+        self.words[15] = v ^ f;
     }
 
     /// Clock once in keystream mode, outputting the value
     pub fn clock_k(&mut self) -> u32 {
-        let out = self.fsm() ^ self.words[15];
-        let v = alpha_inv(self.words[4]) ^ self.words[13] ^ alpha(self.words[15]);
+        let out = self.fsm() ^ self.words[0];
+        let v = self.feedback();
         self.words.rotate_left(1);
         self.words[15] = v;
         out
@@ -345,7 +324,7 @@ impl Snow2 {
 
     pub fn encrypt_bytes_mut(&mut self, bytes: &mut [u8]) {
         let mut keystream = Vec::new();
-        for _ in 0..(bytes.len() / 4) {
+        for _ in 0..bytes.len().div_ceil(4) {
             keystream.extend(self.clock_k().to_be_bytes());
         }
         xor_into_bytes(bytes, &keystream);
@@ -358,6 +337,14 @@ impl Snow2 {
 
 crate::impl_cipher_for_stream_cipher!(Snow2);
 
+impl KeystreamBlocks for Snow2 {
+    const BLOCK_SIZE: usize = 4;
+
+    fn next_keystream_block(&mut self, block: &mut [u8]) {
+        block.copy_from_slice(&self.clock_k().to_be_bytes());
+    }
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -365,51 +352,50 @@ mod tests {
 
     use super::*;
 
-    // #[test]
-    // fn test_keystream_80() {
-    //     let mut cipher =
-    //         Snow2::with_key_and_iv_128(hex!("80000000000000000000000000000000"), [0; 4]);
-    //     // let stream = cipher.next_16();
-    //     // println!("{:08x?}", stream);
-
-    //     for _ in 0..5 {
-    //         println!("{:08x?}", cipher.clock_k())
-    //     }
-    // }
-
-    #[test]
-    fn test_keystream_aa() {
-        let mut cipher =
-            Snow2::with_key_and_iv_128(hex!("AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA"), [0; 4]);
-        let correct_stream: [u32; 5] = [0xE00982F5, 0x25F02054, 0x214992D8, 0x706F2B20, 0xDA585E5B];
-        for i in 0..5 {
-            let n = cipher.clock_k();
-            // assert_eq!(correct_stream[i], n);
-            println!("{:08x?} {:08x?}", correct_stream[i], n);
-        }
+    macro_rules! test_keystream {
+        ($name: ident, $cipher: expr, $stream: expr) => {
+            #[test]
+            fn $name() {
+                let mut cipher = $cipher;
+                let correct_stream: [u32; 5] = $stream;
+                for word in correct_stream {
+                    assert_eq!(word, cipher.clock_k());
+                }
+            }
+        };
     }
 
-    // #[test]
-    // fn test_keystream_80_iv() {
-    //     let mut cipher =
-    //         Snow2::with_key_and_iv_128(hex!("80000000000000000000000000000000"), [4, 3, 2, 1]);
-    //     // let stream = cipher.next_16();
-    //     // println!("{:08x?}", stream);
-
-    //     for _ in 0..5 {
-    //         println!("{:08x?}", cipher.clock_k())
-    //     }
-    // }
-
-    // #[test]
-    // fn test_keystream_aa_iv() {
-    //     let mut cipher =
-    //         Snow2::with_key_and_iv_128(hex!("AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA"), [4, 3, 2, 1]);
-    //     // let stream = cipher.next_16();
-    //     // println!("{:08x?}", stream);
-
-    //     for _ in 0..5 {
-    //         println!("{:08x?}", cipher.clock_k())
-    //     }
-    // }
+    // Test vectors from the SNOW 2.0 specification
+    test_keystream!(
+        test_keystream_80,
+        Snow2::with_key_and_iv_128(hex!("80000000000000000000000000000000"), [0; 4]),
+        [0x8D590AE9, 0xA74A7D05, 0x6DC9CA74, 0xB72D1A45, 0x99B0A083]
+    );
+
+    test_keystream!(
+        test_keystream_aa,
+        Snow2::with_key_and_iv_128(hex!("AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA"), [0; 4]),
+        [0xE00982F5, 0x25F02054, 0x214992D8, 0x706F2B20, 0xDA585E5B]
+    );
+
+    test_keystream!(
+        test_keystream_80_iv,
+        Snow2::with_key_and_iv_128(hex!("80000000000000000000000000000000"), [4, 3, 2, 1]),
+        [0xD6403358, 0xE0354A69, 0x57F43FCE, 0x44B4B13F, 0xF78E24C2]
+    );
+
+    test_keystream!(
+        test_keystream_aa_iv,
+        Snow2::with_key_and_iv_128(hex!("AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA"), [4, 3, 2, 1]),
+        [0xC355385D, 0xB31D6CBD, 0xF774AF53, 0x66C2E877, 0x4DEADAC7]
+    );
+
+    test_keystream!(
+        test_keystream_256_aa,
+        Snow2::with_key_and_iv_256(
+            hex!("AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA"),
+            [0; 4]
+        ),
+        [0xD9CC22FD, 0x861492D0, 0xAE6F43FB, 0x0F072012, 0x078C5AEE]
+    );
 }
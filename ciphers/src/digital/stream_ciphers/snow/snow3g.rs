@@ -1,3 +1,4 @@
+use crate::digital::stream_ciphers::keystream::KeystreamBlocks;
 use utils::byte_formatting::xor_into_bytes;
 
 // https://www.gsma.com/about-us/wp-content/uploads/2014/12/snow3gspec.pdf
@@ -162,7 +163,7 @@ impl Snow3G {
         self.words[15] = v;
     }
 
-    fn clock_k(&mut self) -> u32 {
+    pub fn clock_k(&mut self) -> u32 {
         let out = self.fsm() ^ self.words[0];
         let [s0, s1, s2, s3] = self.words[0].to_be_bytes();
         let [t0, t1, t2, t3] = self.words[11].to_be_bytes();
@@ -178,7 +179,7 @@ impl Snow3G {
 
     pub fn encrypt_bytes_mut(&mut self, bytes: &mut [u8]) {
         let mut keystream = Vec::new();
-        for _ in 0..(bytes.len() / 4) {
+        for _ in 0..bytes.len().div_ceil(4) {
             keystream.extend(self.clock_k().to_be_bytes());
         }
         xor_into_bytes(bytes, &keystream);
@@ -189,6 +190,14 @@ impl Snow3G {
     }
 }
 
+impl KeystreamBlocks for Snow3G {
+    const BLOCK_SIZE: usize = 4;
+
+    fn next_keystream_block(&mut self, block: &mut [u8]) {
+        block.copy_from_slice(&self.clock_k().to_be_bytes());
+    }
+}
+
 #[cfg(test)]
 mod tests {
 
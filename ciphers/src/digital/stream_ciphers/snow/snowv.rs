@@ -0,0 +1,228 @@
+use crate::digital::{
+    block_ciphers::aes::aes_functions::{mix_columns, shift_rows, sub_bytes, transpose_state},
+    stream_ciphers::keystream::KeystreamBlocks,
+};
+use utils::byte_formatting::{xor_into_bytes, ByteFormat};
+
+// "A new SNOW stream cipher called SNOW-V" by Ekdahl, Johansson, Maximov, and Yang
+
+// Byte permutation applied to the new value of R1
+const SIGMA: [usize; 16] = [0, 4, 8, 12, 1, 5, 9, 13, 2, 6, 10, 14, 3, 7, 11, 15];
+
+// Multiplication by the generator of GF(2^16) given by the polynomial with low bits c
+fn mul_x(x: u16, c: u16) -> u16 {
+    if x & 0x8000 != 0 {
+        (x << 1) ^ c
+    } else {
+        x << 1
+    }
+}
+
+// Multiplication by the inverse of the generator, d is the inverse of the generator multiplied by the low bits
+fn mul_x_inv(x: u16, d: u16) -> u16 {
+    if x & 0x0001 != 0 {
+        (x >> 1) ^ d
+    } else {
+        x >> 1
+    }
+}
+
+// Registers are 128 bits, treated as four little endian words for addition
+fn to_bytes(r: [u32; 4]) -> [u8; 16] {
+    let mut out = [0; 16];
+    for (chunk, word) in out.chunks_exact_mut(4).zip(r) {
+        chunk.copy_from_slice(&word.to_le_bytes());
+    }
+    out
+}
+
+fn from_bytes(bytes: [u8; 16]) -> [u32; 4] {
+    std::array::from_fn(|i| u32::from_le_bytes(bytes[4 * i..4 * i + 4].try_into().unwrap()))
+}
+
+fn add_32(a: [u32; 4], b: [u32; 4]) -> [u32; 4] {
+    std::array::from_fn(|i| a[i].wrapping_add(b[i]))
+}
+
+// A single AES encryption round with a round key of zero
+fn aes_round(r: [u32; 4]) -> [u32; 4] {
+    let mut state = to_bytes(r);
+    transpose_state(&mut state);
+    sub_bytes(&mut state);
+    shift_rows(&mut state);
+    mix_columns(&mut state);
+    transpose_state(&mut state);
+    from_bytes(state)
+}
+
+fn sigma(r: [u32; 4]) -> [u32; 4] {
+    let bytes = to_bytes(r);
+    from_bytes(std::array::from_fn(|i| bytes[SIGMA[i]]))
+}
+
+// Eight 16-bit cells taken as a 128-bit register
+fn cells_to_words(cells: &[u16]) -> [u32; 4] {
+    std::array::from_fn(|i| cells[2 * i] as u32 | (cells[2 * i + 1] as u32) << 16)
+}
+
+/// SNOW-V, the 256-bit key successor of SNOW 3G proposed for 5G. Two LFSRs of sixteen 16-bit cells feed an FSM of three
+/// 128-bit registers which are updated with AES rounds. Each step produces 16 bytes of keystream.
+#[derive(Debug, Clone)]
+pub struct SnowV {
+    pub input_format: ByteFormat,
+    pub output_format: ByteFormat,
+    a: [u16; 16],
+    b: [u16; 16],
+    r1: [u32; 4],
+    r2: [u32; 4],
+    r3: [u32; 4],
+}
+
+impl Default for SnowV {
+    fn default() -> Self {
+        Self::with_key_and_iv([0; 32], [0; 16])
+    }
+}
+
+impl SnowV {
+    pub fn with_key_and_iv(key: [u8; 32], iv: [u8; 16]) -> Self {
+        let mut a = [0; 16];
+        let mut b = [0; 16];
+        for i in 0..8 {
+            a[i] = u16::from_le_bytes([iv[2 * i], iv[2 * i + 1]]);
+            a[i + 8] = u16::from_le_bytes([key[2 * i], key[2 * i + 1]]);
+            b[i + 8] = u16::from_le_bytes([key[2 * i + 16], key[2 * i + 17]]);
+        }
+
+        let mut s = Self {
+            input_format: ByteFormat::Hex,
+            output_format: ByteFormat::Hex,
+            a,
+            b,
+            r1: [0; 4],
+            r2: [0; 4],
+            r3: [0; 4],
+        };
+
+        // During initialization the keystream is fed back into the upper half of LFSR-A and the key is mixed into R1
+        // after the last two steps
+        let k0 = from_bytes(key[..16].try_into().unwrap());
+        let k1 = from_bytes(key[16..].try_into().unwrap());
+        for t in 1..=16 {
+            let z = s.next_block();
+            for i in 0..8 {
+                s.a[i + 8] ^= u16::from_le_bytes([z[2 * i], z[2 * i + 1]]);
+            }
+            if t == 15 {
+                s.r1 = std::array::from_fn(|i| s.r1[i] ^ k0[i]);
+            }
+            if t == 16 {
+                s.r1 = std::array::from_fn(|i| s.r1[i] ^ k1[i]);
+            }
+        }
+
+        s
+    }
+
+    fn update_fsm(&mut self) {
+        let t2 = cells_to_words(&self.a[0..8]);
+        let tmp = add_32(self.r2, std::array::from_fn(|i| self.r3[i] ^ t2[i]));
+        self.r3 = aes_round(self.r2);
+        self.r2 = aes_round(self.r1);
+        self.r1 = sigma(tmp);
+    }
+
+    // Eight steps of each LFSR. Every tap is within the older half so all eight new cells can be computed at once.
+    fn update_lfsr(&mut self) {
+        let mut new_a = [0; 8];
+        let mut new_b = [0; 8];
+        for i in 0..8 {
+            new_a[i] = self.b[i]
+                ^ mul_x(self.a[i], 0x990f)
+                ^ self.a[i + 1]
+                ^ mul_x_inv(self.a[i + 8], 0xcc87);
+            new_b[i] = self.a[i]
+                ^ mul_x(self.b[i], 0xc963)
+                ^ self.b[i + 3]
+                ^ mul_x_inv(self.b[i + 8], 0xe4b1);
+        }
+        self.a.copy_within(8.., 0);
+        self.b.copy_within(8.., 0);
+        self.a[8..].copy_from_slice(&new_a);
+        self.b[8..].copy_from_slice(&new_b);
+    }
+
+    pub fn next_block(&mut self) -> [u8; 16] {
+        let t1 = cells_to_words(&self.b[8..16]);
+        let z = add_32(self.r1, t1);
+        let z = to_bytes(std::array::from_fn(|i| z[i] ^ self.r2[i]));
+        self.update_fsm();
+        self.update_lfsr();
+        z
+    }
+
+    pub fn encrypt_bytes_mut(&mut self, bytes: &mut [u8]) {
+        for chunk in bytes.chunks_mut(16) {
+            let n = chunk.len();
+            let keystream = self.next_block();
+            xor_into_bytes(chunk, &keystream[..n]);
+        }
+    }
+
+    pub fn encrypt_bytes(&self, bytes: &mut [u8]) {
+        self.clone().encrypt_bytes_mut(bytes);
+    }
+}
+
+crate::impl_cipher_for_stream_cipher!(SnowV);
+
+impl KeystreamBlocks for SnowV {
+    const BLOCK_SIZE: usize = 16;
+
+    fn next_keystream_block(&mut self, block: &mut [u8]) {
+        block.copy_from_slice(&self.next_block());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use hex_literal::hex;
+
+    // Test vector from the SNOW-V paper
+    #[test]
+    fn keystream_zero() {
+        let mut cipher = SnowV::with_key_and_iv([0; 32], [0; 16]);
+        assert_eq!(
+            hex!("69ca6daf9ae3b72db134a85a837e419d"),
+            cipher.next_block()
+        );
+        assert_eq!(
+            hex!("ec08aad39d7b0f009b60b28c534300ed"),
+            cipher.next_block()
+        );
+        assert_eq!(
+            hex!("84abf594fb08a7f1f3a2df18e617683b"),
+            cipher.next_block()
+        );
+        assert_eq!(
+            hex!("481fa378079dcf04db53b5d629a9eb9d"),
+            cipher.next_block()
+        );
+    }
+
+    #[test]
+    fn keystream_key_and_iv() {
+        let cipher = SnowV::with_key_and_iv(
+            hex!("505152535455565758595a5b5c5d5e5f606162636465666768696a6b6c6d6e6f"),
+            hex!("0a1a2a3a4a5a6a7a8a9aaabacadaeafa"),
+        );
+        let mut bytes = [0; 64];
+        cipher.encrypt_bytes(&mut bytes);
+        assert_eq!(
+            hex!("751565ea44952c6121265918fedeea259ff2158d5e949554a21e6a01ea96d05c38ca7e7e78c2fb0d10393cdfe71c3f6423124d0b8b5229a56e17190216af988c"),
+            bytes
+        );
+    }
+}
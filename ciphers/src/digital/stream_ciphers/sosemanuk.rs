@@ -0,0 +1,100 @@
+use super::keystream::{BlockKeystream, KeystreamBlocks, KeystreamGenerator};
+use rngs::sosemanuk::Sosemanuk as SosemanukCore;
+use utils::{
+    byte_formatting::{u32s_to_bytes_le, ByteFormat},
+    errors::GeneralError,
+};
+
+/// Sosemanuk as a stream cipher. The generator is the one from the rngs crate so both share a single implementation.
+#[derive(Debug, Clone)]
+pub struct Sosemanuk {
+    core: SosemanukCore,
+    pub input_format: ByteFormat,
+    pub output_format: ByteFormat,
+}
+
+impl Default for Sosemanuk {
+    fn default() -> Self {
+        Self::with_key_and_iv(&[0; 16], [0; 16]).unwrap()
+    }
+}
+
+impl Sosemanuk {
+    /// The key can be from one to thirty-two bytes, though only 128 bits of security are claimed
+    pub fn with_key_and_iv(key: &[u8], iv: [u8; 16]) -> Result<Self, GeneralError> {
+        let mut out = Self {
+            core: SosemanukCore::default(),
+            input_format: ByteFormat::Hex,
+            output_format: ByteFormat::Hex,
+        };
+        out.ksa(key, iv)?;
+        Ok(out)
+    }
+
+    pub fn ksa(&mut self, key: &[u8], iv: [u8; 16]) -> Result<(), GeneralError> {
+        if key.is_empty() || key.len() > 32 {
+            return Err(GeneralError::key(
+                "Sosemanuk keys must be from 1 to 32 bytes",
+            ));
+        }
+        self.core = SosemanukCore::with_key_and_iv(key, iv);
+        Ok(())
+    }
+
+    pub fn next_block(&mut self) -> [u32; 4] {
+        self.core.next_block()
+    }
+
+    pub fn encrypt_bytes(&self, bytes: &mut [u8]) {
+        BlockKeystream::new(self.clone()).apply_keystream(bytes);
+    }
+}
+
+impl KeystreamBlocks for Sosemanuk {
+    const BLOCK_SIZE: usize = 16;
+
+    fn next_keystream_block(&mut self, block: &mut [u8]) {
+        u32s_to_bytes_le(block, self.next_block());
+    }
+}
+
+crate::impl_cipher_for_stream_cipher!(Sosemanuk);
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use hex_literal::hex;
+
+    #[test]
+    fn keystream_short_key() {
+        let cipher = Sosemanuk::with_key_and_iv(
+            &hex!("A7C083FEB7"),
+            hex!("00112233445566778899AABBCCDDEEFF"),
+        )
+        .unwrap();
+        assert_eq!(
+            hex!("FE81D2162C9A100D04895C454A77515BBE6A431A935CB90E2221EBB7EF502328"),
+            BlockKeystream::new(cipher).keystream(32).as_slice()
+        );
+    }
+
+    #[test]
+    fn keystream_128_bit_key() {
+        let cipher = Sosemanuk::with_key_and_iv(
+            &hex!("00112233445566778899AABBCCDDEEFF"),
+            hex!("8899AABBCCDDEEFF0011223344556677"),
+        )
+        .unwrap();
+        assert_eq!(
+            hex!("FA61DBEB71178131A77C714BD2EABF4E1394207A25698AA1308F2F063A0F7606"),
+            BlockKeystream::new(cipher).keystream(32).as_slice()
+        );
+    }
+
+    #[test]
+    fn key_length() {
+        assert!(Sosemanuk::with_key_and_iv(&[], [0; 16]).is_err());
+        assert!(Sosemanuk::with_key_and_iv(&[0; 33], [0; 16]).is_err());
+    }
+}
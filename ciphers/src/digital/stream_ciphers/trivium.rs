@@ -0,0 +1,83 @@
+use super::keystream::KeystreamGenerator;
+use rngs::trivium::Trivium as TriviumCore;
+use utils::byte_formatting::ByteFormat;
+
+/// Trivium as a stream cipher. The generator is the one from the rngs crate so both share a single implementation.
+#[derive(Debug, Clone)]
+pub struct Trivium {
+    core: TriviumCore,
+    pub input_format: ByteFormat,
+    pub output_format: ByteFormat,
+}
+
+impl Default for Trivium {
+    fn default() -> Self {
+        Self::with_key_and_iv([0; 10], [0; 10])
+    }
+}
+
+impl Trivium {
+    pub fn with_key_and_iv(key: [u8; 10], iv: [u8; 10]) -> Self {
+        Self {
+            core: TriviumCore::with_key_and_iv(key, iv),
+            input_format: ByteFormat::Hex,
+            output_format: ByteFormat::Hex,
+        }
+    }
+
+    pub fn ksa(&mut self, key: [u8; 10], iv: [u8; 10]) {
+        self.core = TriviumCore::with_key_and_iv(key, iv);
+    }
+
+    pub fn next_bit(&mut self) -> u8 {
+        self.core.next_bit()
+    }
+
+    pub fn next_byte(&mut self) -> u8 {
+        self.core.next_byte()
+    }
+
+    pub fn encrypt_bytes(&self, bytes: &mut [u8]) {
+        self.clone().apply_keystream(bytes);
+    }
+}
+
+impl KeystreamGenerator for Trivium {
+    fn fill_keystream(&mut self, bytes: &mut [u8]) {
+        for byte in bytes.iter_mut() {
+            *byte = self.next_byte();
+        }
+    }
+}
+
+crate::impl_cipher_for_stream_cipher!(Trivium);
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use hex_literal::hex;
+
+    #[test]
+    fn keystream_zero() {
+        let mut cipher = Trivium::with_key_and_iv([0; 10], [0; 10]);
+        assert_eq!(hex!("fbe0bf265859051b"), cipher.keystream(8).as_slice());
+    }
+
+    #[test]
+    fn keystream_set_1_vector_0() {
+        let mut cipher = Trivium::with_key_and_iv(hex!("80000000000000000000"), [0; 10]);
+        assert_eq!(hex!("38eb86ff730d7a9c"), cipher.keystream(8).as_slice());
+    }
+
+    #[test]
+    fn round_trip() {
+        let cipher = Trivium::with_key_and_iv(hex!("0123456789abcdef0123"), [0x55; 10]);
+        let ptext: Vec<u8> = (0..37).collect();
+        let mut bytes = ptext.clone();
+        cipher.encrypt_bytes(&mut bytes);
+        assert_ne!(ptext, bytes);
+        cipher.encrypt_bytes(&mut bytes);
+        assert_eq!(ptext, bytes);
+    }
+}
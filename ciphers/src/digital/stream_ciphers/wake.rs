@@ -1,49 +1,149 @@
-use utils::byte_formatting::ByteFormat;
+use utils::{byte_formatting::ByteFormat, errors::GeneralError};
 
+const TT: [u32; 8] = [
+    0x726a8f3b, 0xe69a3b5c, 0xd3c71fe5, 0xab3c73d2, 0x4d3a8eb3, 0x0396d6e8, 0x3d4c2f7a, 0x9ee27cf3,
+];
+
+// https://link.springer.com/content/pdf/10.1007/3-540-58108-1_16.pdf
+#[derive(Debug, Clone)]
 pub struct Wake {
-    pub table: [u32; 256],
-    pub regs: [u32; 6],
+    pub table: [u32; 257],
+    pub key: [u32; 4],
     pub input_format: ByteFormat,
     pub output_format: ByteFormat,
 }
 
 impl Default for Wake {
     fn default() -> Self {
-        Self {
-            table: [0; 256],
-            regs: [0; 6],
+        Self::with_key([0; 4])
+    }
+}
+
+impl Wake {
+    pub fn with_key(key: [u32; 4]) -> Self {
+        let mut out = Self {
+            table: [0; 257],
+            key,
             input_format: ByteFormat::Hex,
             output_format: ByteFormat::Hex,
+        };
+        out.ksa(key);
+        out
+    }
+
+    // Follows Wheeler's genkey(), including the arithmetic right shift of his signed longs
+    pub fn ksa(&mut self, key: [u32; 4]) {
+        self.key = key;
+        let t = &mut self.table;
+
+        t[..4].copy_from_slice(&key);
+        for p in 4..256 {
+            let x = t[p - 4].wrapping_add(t[p - 1]);
+            t[p] = ((x as i32) >> 3) as u32 ^ TT[(x & 7) as usize];
+        }
+
+        for p in 0..23 {
+            t[p] = t[p].wrapping_add(t[p + 89]);
+        }
+
+        let mut x = t[33];
+        let z = (t[59] | 0x01000001) & 0xff7fffff;
+        for word in t[..256].iter_mut() {
+            x = (x & 0xff7fffff).wrapping_add(z);
+            *word = (*word & 0x00ffffff) ^ x;
+        }
+
+        t[256] = t[0];
+        let mut y = x & 0xff;
+        for p in 0..256 {
+            y = (t[p ^ y as usize] ^ y) & 0xff;
+            t[p] = t[y as usize];
+            t[y as usize] = t[p + 1];
         }
     }
-}
 
-impl Wake {
     fn mix(&self, x: u32, y: u32) -> u32 {
         let s = x.wrapping_add(y);
         (s >> 8) ^ self.table[s as u8 as usize]
     }
 
-    pub fn encrypt_bytes(&self, bytes: &[u8]) -> Vec<u8> {
-        let mut out = Vec::with_capacity(bytes.len());
+    // The registers are driven by the ciphertext, so encryption and decryption differ only in which word is fed back
+    fn process(&self, bytes: &mut [u8], encrypt: bool) {
+        let [mut r3, mut r4, mut r5, mut r6] = self.key;
 
-        let mut r3 = 0;
-        let mut r4 = 0;
-        let mut r5 = 0;
-        let mut r6 = 0;
-
-        for block in bytes.chunks(4) {
-            let word = u32::from_be_bytes(block.try_into().unwrap());
-            let r2 = word ^ r6;
+        for block in bytes.chunks_mut(4) {
+            let keystream = r6.to_be_bytes();
+            let mut fed_back = [0; 4];
+            for (i, byte) in block.iter_mut().enumerate() {
+                let input = *byte;
+                *byte ^= keystream[i];
+                fed_back[i] = if encrypt { *byte } else { input };
+            }
+            // A final partial block needs no further keystream
+            let r2 = u32::from_be_bytes(fed_back);
             r3 = self.mix(r3, r2);
             r4 = self.mix(r4, r3);
             r5 = self.mix(r5, r4);
             r6 = self.mix(r6, r5);
-            out.extend_from_slice(&r2.to_be_bytes());
         }
+    }
 
-        out
+    pub fn encrypt_bytes(&self, bytes: &mut [u8]) {
+        self.process(bytes, true);
+    }
+
+    pub fn decrypt_bytes(&self, bytes: &mut [u8]) {
+        self.process(bytes, false);
+    }
+}
+
+impl crate::Cipher for Wake {
+    fn encrypt(&self, text: &str) -> Result<String, GeneralError> {
+        let mut bytes = self
+            .input_format
+            .text_to_bytes(text)
+            .map_err(GeneralError::input)?;
+        self.encrypt_bytes(&mut bytes);
+        Ok(self.output_format.byte_slice_to_text(&bytes))
+    }
+
+    fn decrypt(&self, text: &str) -> Result<String, GeneralError> {
+        let mut bytes = self
+            .input_format
+            .text_to_bytes(text)
+            .map_err(GeneralError::input)?;
+        self.decrypt_bytes(&mut bytes);
+        Ok(self.output_format.byte_slice_to_text(&bytes))
     }
 }
 
-crate::impl_cipher_for_stream_cipher!(Wake);
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let cipher = Wake::with_key([0x01234567, 0x89abcdef, 0xfedcba98, 0x76543210]);
+        let ptext: Vec<u8> = (0..23).collect();
+        let mut bytes = ptext.clone();
+        cipher.encrypt_bytes(&mut bytes);
+        assert_ne!(ptext, bytes);
+        cipher.decrypt_bytes(&mut bytes);
+        assert_eq!(ptext, bytes);
+    }
+
+    #[test]
+    fn ciphertext_feedback() {
+        // Changing one word of plaintext changes every later word of ciphertext
+        let cipher = Wake::with_key([1, 2, 3, 4]);
+        let mut a = [0; 16];
+        let mut b = [0; 16];
+        b[5] = 1;
+        cipher.encrypt_bytes(&mut a);
+        cipher.encrypt_bytes(&mut b);
+        assert_eq!(a[..4], b[..4]);
+        assert_ne!(a[8..12], b[8..12]);
+        assert_ne!(a[12..], b[12..]);
+    }
+}
@@ -0,0 +1,140 @@
+use super::{
+    keystream::{KeystreamBlocks, SeekableBlocks},
+    salsa20::Salsa20,
+};
+use utils::byte_formatting::{fill_u32s_le, ByteFormat};
+
+// https://cr.yp.to/snuffle/xsalsa-20110204.pdf
+pub struct XSalsa20 {
+    pub input_format: ByteFormat,
+    pub output_format: ByteFormat,
+    pub key: [u32; 8],
+    pub nonce: [u32; 6],
+    pub rounds: u8,
+    pub ctr: u64,
+}
+
+impl Default for XSalsa20 {
+    fn default() -> Self {
+        Self {
+            input_format: ByteFormat::Hex,
+            output_format: ByteFormat::Hex,
+            key: [0; 8],
+            nonce: [0; 6],
+            rounds: 20,
+            ctr: 0,
+        }
+    }
+}
+
+impl XSalsa20 {
+    pub fn key_and_nonce(&mut self, key: [u8; 32], nonce: [u8; 24]) {
+        fill_u32s_le(&mut self.key, key);
+        fill_u32s_le(&mut self.nonce, nonce);
+    }
+
+    pub fn with_key_and_nonce(mut self, key: [u8; 32], nonce: [u8; 24]) -> Self {
+        self.key_and_nonce(key, nonce);
+        self
+    }
+
+    pub fn xsalsa20_8() -> Self {
+        Self {
+            rounds: 8,
+            ..Default::default()
+        }
+    }
+
+    pub fn xsalsa20_12() -> Self {
+        Self {
+            rounds: 12,
+            ..Default::default()
+        }
+    }
+
+    pub fn xsalsa20_20() -> Self {
+        Self {
+            rounds: 20,
+            ..Default::default()
+        }
+    }
+
+    // HSalsa20 of the key and the first 128 bits of the nonce
+    pub fn synthetic_key(&self) -> [u32; 8] {
+        Salsa20::hsalsa20(
+            self.key,
+            [self.nonce[0], self.nonce[1], self.nonce[2], self.nonce[3]],
+            self.rounds,
+        )
+    }
+
+    // Salsa20 with the subkey and the last 64 bits of the nonce
+    pub fn salsa20(&self) -> Salsa20 {
+        Salsa20 {
+            input_format: ByteFormat::Hex,
+            output_format: ByteFormat::Hex,
+            key: self.synthetic_key(),
+            nonce: [self.nonce[4], self.nonce[5]],
+            rounds: self.rounds,
+            ctr: self.ctr,
+        }
+    }
+
+    pub fn encrypt_bytes_with_ctr(&self, bytes: &[u8], ctr: u64) -> Vec<u8> {
+        self.salsa20().encrypt_bytes_with_ctr(bytes, ctr)
+    }
+
+    pub fn encrypt_bytes(&self, bytes: &mut [u8]) {
+        self.salsa20().encrypt_bytes(bytes)
+    }
+}
+
+impl KeystreamBlocks for XSalsa20 {
+    const BLOCK_SIZE: usize = 64;
+
+    fn next_keystream_block(&mut self, block: &mut [u8]) {
+        block.copy_from_slice(&self.salsa20().key_stream_block(self.ctr));
+        self.ctr = self.ctr.wrapping_add(1);
+    }
+}
+
+impl SeekableBlocks for XSalsa20 {
+    fn seek_block(&mut self, index: u64) {
+        self.ctr = index;
+    }
+}
+
+crate::impl_cipher_for_stream_cipher!(XSalsa20);
+
+#[cfg(test)]
+mod xsalsa20_tests {
+
+    use super::*;
+    use crate::Cipher;
+    use hex_literal::hex;
+
+    #[test]
+    fn encrypt_decrypt_test() {
+        let ptext = "0102030405060708";
+        let cipher = XSalsa20::default();
+
+        let ctext = cipher.encrypt(ptext).unwrap();
+        assert_ne!(ctext, ptext);
+        assert_eq!(cipher.decrypt(&ctext).unwrap(), ptext);
+    }
+
+    #[test]
+    fn keystream_test() {
+        // The stream3 test of NaCl
+        let cipher = XSalsa20::default().with_key_and_nonce(
+            hex!("1b27556473e985d462cd51197a9a46c76009549eac6474f206c4ee0844f68389"),
+            hex!("69696ee955b62b73cd62bda875fc73d68219e0036b7a0b37"),
+        );
+        let mut stream = [0; 32];
+        cipher.encrypt_bytes(&mut stream);
+        assert_eq!(
+            hex!("eea6a7251c1e72916d11c2cb214d3c252539121d8e234e652d651fa4c8cff880"),
+            stream
+        );
+    }
+}
@@ -34,6 +34,7 @@ cipher_ids_and_names!(
     Affine, "Affine";
     Alberti, "Alberti Cipher Disk";
     Amsco, "AMSCO";
+    Anubis, "Anubis";
     Ascon128, "ASCON-128";
     Ascon80pq, "ASCON-80pq";
     Aria, "ARIA";
@@ -45,28 +46,41 @@ cipher_ids_and_names!(
     Bazeries, "Bazeries";
     Beaufort, "Beaufort";
     Bifid, "Bifid";
+    BlindRsa, "Blind RSA";
+    BlockCipherModes, "Block Cipher Modes";
     Blowfish, "Blowfish";
     Caesar, "Caesar";
     Camellia, "Camellia";
+    Cast, "CAST";
     ChaCha, "ChaCha";
     ChaCha20Poly1305, "ChaCha20-Poly1305";
     Chaocipher, "Chaocipher";
     Checkerboard, "Straddling Checkerboard";
     Columnar, "Columnar Transposition";
+    CramerShoup, "Cramer-Shoup";
     Decoder, "Decoder Ring";
     Des, "DES";
     DesX, "DES-X";
     DiagonalColumnar, "Diagonal Columnar";
     DiffieHellman, "Diffie-Hellman";
     Dryad, "DRYAD";
+    Dsa, "DSA";
+    E0, "E0";
     E2, "E2";
+    Ecdsa, "ECDSA";
+    Ed25519, "Ed25519";
     ElGamal, "ElGamal";
+    ElGamalSignature, "ElGamal Signature";
     Enigma, "Enigma";
     FealNx, "FEAL-NX";
     Fialka, "Fialka";
+    Fish, "FISH";
     FourSquare, "Four-Square";
     Gift, "GIFT";
+    GoldwasserMicali, "Goldwasser-Micali";
     Gost, "GOST 28147-89";
+    Grain, "Grain";
+    Grain128Aead, "Grain-128AEAD";
     Grille, "Grille";
     Hc128, "HC-128";
     Hc256, "HC-256";
@@ -77,15 +91,27 @@ cipher_ids_and_names!(
     Idea, "IDEA";
     Isaac, "ISAAC";
     Kasumi, "KASUMI";
+    KeyWrap, "Key Wrap";
+    Kuznyechik, "Kuznyechik";
     Khufu, "Khufu";
     Khafre, "Khafre";
+    Lamport, "Lamport";
     Lea, "LEA";
+    Lms, "LMS";
     Lorzen, "Lorenz";
     Lucifer, "Lucifer";
+    Lwe, "LWE (Regev)";
     M94, "M-94";
     M209, "M-209";
+    Magma, "Magma";
+    MerkleHellman, "Merkle-Hellman";
+    MerkleSignature, "Merkle Signature";
+    Mickey, "MICKEY 2.0";
     Misty1, "MISTY1";
+    MlKem, "ML-KEM";
     Nihilist, "Nihilist";
+    Ntru, "NTRU";
+    Paillier, "Paillier";
     Playfair, "Playfair";
     Plugboard, "Plugboard";
     Present, "PRESENT";
@@ -95,6 +121,7 @@ cipher_ids_and_names!(
     Purple, "Purple";
     Quagmire, "Quagmire";
     Rabbit, "Rabbit";
+    Rabin, "Rabin";
     RailFence, "Rail Fence";
     Rc2, "RC2";
     Rc4, "RC4";
@@ -102,8 +129,10 @@ cipher_ids_and_names!(
     Rsa, "RSA";
     Rs44, "RS44";
     Salsa20, "Salsa20";
+    Schnorr, "Schnorr (BIP-340)";
     Scytale, "Scytale";
     Seal3, "SEAL 3.0";
+    SecretBox, "Secretbox";
     Seed, "SEED";
     SeriatedPlayfair, "Seriated Playfair";
     Serpent, "Serpent";
@@ -115,21 +144,30 @@ cipher_ids_and_names!(
     Slidefair, "Slidefair";
     Solitaire, "Solitaire";
     Sm4, "SM4";
+    Snow, "SNOW";
     Snow3G, "Snow 3G";
+    Sosemanuk, "Sosemanuk";
     Speck, "Speck";
     Substitution, "Substitution";
     Tea, "TEA";
     Threefish, "Threefish";
     Trifid, "Trifid";
     TripleDes, "Triple DES";
+    Trivium, "Trivium";
     TurningGrille, "Turning Grille";
     Twofish, "Twofish";
     TwoSquare, "Two-Square";
+    Uea, "UEA";
     Vic, "VIC";
     Vigenere, "Vigenère";
+    Wake, "WAKE";
+    Wots, "WOTS+";
     XChaCha, "XChaCha";
+    XChaCha20Poly1305, "XChaCha20-Poly1305";
+    Xmss, "XMSS";
     XorSplitting, "XOR Secret Splitting";
     Xtea, "XTEA";
+    XSalsa20, "XSalsa20";
     Xxtea, "XXTEA";
 );
 
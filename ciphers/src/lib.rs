@@ -1,3 +1,4 @@
+pub mod cryptanalysis;
 pub mod digital;
 pub mod hill;
 pub mod machines;
@@ -51,13 +51,13 @@ impl HmacVariant {
             Self::Md5 => 16,
             Self::Sha224 => 28,
             Self::Sha256 => 32,
-            Self::Sha384 => 58,
+            Self::Sha384 => 48,
             Self::Sha512 => 64,
             Self::Sha512_224 => 28,
             Self::Sha512_256 => 32,
             Self::Sha3_224 => 28,
             Self::Sha3_256 => 32,
-            Self::Sha3_384 => 58,
+            Self::Sha3_384 => 48,
             Self::Sha3_512 => 64,
         }
     }
@@ -1,6 +1,5 @@
 use crate::SimpleRng;
 use itertools::Itertools;
-use std::num::Wrapping;
 
 pub struct ChaCha {
     pub key: [u32; 8],
@@ -48,65 +47,70 @@ impl ChaCha {
         ]
     }
 
-    pub fn quarter_round(state: &mut [Wrapping<u32>; 16], a: usize, b: usize, c: usize, d: usize) {
-        state[a] += state[b];
+    pub fn quarter_round(state: &mut [u32; 16], a: usize, b: usize, c: usize, d: usize) {
+        state[a] = state[a].wrapping_add(state[b]);
         state[d] ^= state[a];
-        state[d] = Wrapping(state[d].0.rotate_left(16));
+        state[d] = state[d].rotate_left(16);
 
-        state[c] += state[d];
+        state[c] = state[c].wrapping_add(state[d]);
         state[b] ^= state[c];
-        state[b] = Wrapping(state[b].0.rotate_left(12));
+        state[b] = state[b].rotate_left(12);
 
-        state[a] += state[b];
+        state[a] = state[a].wrapping_add(state[b]);
         state[d] ^= state[a];
-        state[d] = Wrapping(state[d].0.rotate_left(8));
+        state[d] = state[d].rotate_left(8);
 
-        state[c] += state[d];
+        state[c] = state[c].wrapping_add(state[d]);
         state[b] ^= state[c];
-        state[b] = Wrapping(state[b].0.rotate_left(7));
+        state[b] = state[b].rotate_left(7);
     }
 
-    pub fn column_round(state: &mut [Wrapping<u32>; 16]) {
+    pub fn column_round(state: &mut [u32; 16]) {
         Self::quarter_round(state, 0, 4, 8, 12);
         Self::quarter_round(state, 1, 5, 9, 13);
         Self::quarter_round(state, 2, 6, 10, 14);
         Self::quarter_round(state, 3, 7, 11, 15);
     }
 
-    pub fn diag_round(state: &mut [Wrapping<u32>; 16]) {
+    pub fn diag_round(state: &mut [u32; 16]) {
         Self::quarter_round(state, 0, 5, 10, 15);
         Self::quarter_round(state, 1, 6, 11, 12);
         Self::quarter_round(state, 2, 7, 8, 13);
         Self::quarter_round(state, 3, 4, 9, 14);
     }
 
-    pub fn double_round(state: &mut [Wrapping<u32>; 16]) {
+    pub fn double_round(state: &mut [u32; 16]) {
         Self::column_round(state);
         Self::diag_round(state);
     }
 
-    pub fn next_block(&mut self) -> Vec<u32> {
-        let state = self.create_state().map(|w| Wrapping(w));
-        self.ctr += 1;
-
+    // The block function shared by every ChaCha variant in the ciphers crate, they differ only in how the state is laid out
+    pub fn block_function(state: &[u32; 16], rounds: u8) -> [u32; 16] {
         // Temporary state
-        let mut t_state = state.clone();
+        let mut t_state = *state;
 
         // Only ChaCha20, ChaCha12, and ChaCha8 are official but any number is usable
-        for _round in 0..self.rounds / 2 {
+        for _round in 0..rounds / 2 {
             Self::double_round(&mut t_state);
         }
-        if self.rounds % 2 == 1 {
+        if rounds % 2 == 1 {
             Self::column_round(&mut t_state)
         }
 
-        // XOR the current state into the temporary state
+        // Add the current state into the temporary state
         for (i, word) in t_state.iter_mut().enumerate() {
-            *word += state[i]
+            *word = word.wrapping_add(state[i])
         }
 
-        // Create a byte stream
-        t_state.iter().map(|w| w.0.to_be()).rev().collect_vec()
+        t_state
+    }
+
+    pub fn next_block(&mut self) -> Vec<u32> {
+        let block = Self::block_function(&self.create_state(), self.rounds);
+        self.ctr += 1;
+
+        // Reversed so that popping returns the words in order
+        block.iter().map(|w| w.to_be()).rev().collect_vec()
     }
 }
 
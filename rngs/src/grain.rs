@@ -0,0 +1,137 @@
+use crate::SimpleRng;
+
+fn bit(x: u128, i: u32) -> u8 {
+    ((x >> i) & 1) as u8
+}
+
+fn bits(x: u128, idx: &[u32]) -> u8 {
+    idx.iter().fold(0, |acc, i| acc ^ bit(x, *i))
+}
+
+// https://www.ecrypt.eu.org/stream/p3ciphers/grain/Grain_p3.pdf
+// Bit i of each register is b_i or s_i, so clocking shifts right and the new bit enters at the top
+#[derive(Debug, Clone)]
+pub struct Grain {
+    nfsr: u128,
+    lfsr: u128,
+}
+
+impl Default for Grain {
+    fn default() -> Self {
+        Self::with_key_and_iv([0; 10], [0; 8])
+    }
+}
+
+impl Grain {
+    pub fn with_key_and_iv(key: [u8; 10], iv: [u8; 8]) -> Self {
+        let mut out = Self { nfsr: 0, lfsr: 0 };
+        out.ksa(key, iv);
+        out
+    }
+
+    // Key and IV bits are taken least significant first from each byte
+    pub fn ksa(&mut self, key: [u8; 10], iv: [u8; 8]) {
+        let mut k = [0; 16];
+        k[..10].copy_from_slice(&key);
+        self.nfsr = u128::from_le_bytes(k);
+        self.lfsr = u64::from_le_bytes(iv) as u128 | (0xffff << 64);
+
+        // During initialization the output is fed back into both registers
+        for _ in 0..160 {
+            let z = self.output();
+            self.clock(z);
+        }
+    }
+
+    fn h(&self) -> u8 {
+        let x0 = bit(self.lfsr, 3);
+        let x1 = bit(self.lfsr, 25);
+        let x2 = bit(self.lfsr, 46);
+        let x3 = bit(self.lfsr, 64);
+        let x4 = bit(self.nfsr, 63);
+        x1 ^ x4
+            ^ (x0 & x3)
+            ^ (x2 & x3)
+            ^ (x3 & x4)
+            ^ (x0 & x1 & x2)
+            ^ (x0 & x2 & x3)
+            ^ (x0 & x2 & x4)
+            ^ (x1 & x2 & x4)
+            ^ (x2 & x3 & x4)
+    }
+
+    fn output(&self) -> u8 {
+        bits(self.nfsr, &[1, 2, 4, 10, 31, 43, 56]) ^ self.h()
+    }
+
+    fn clock(&mut self, feedback: u8) {
+        let f = bits(self.lfsr, &[0, 13, 23, 38, 51, 62]);
+        let b = |i| bit(self.nfsr, i);
+        let g = bit(self.lfsr, 0)
+            ^ b(62)
+            ^ b(60)
+            ^ b(52)
+            ^ b(45)
+            ^ b(37)
+            ^ b(33)
+            ^ b(28)
+            ^ b(21)
+            ^ b(14)
+            ^ b(9)
+            ^ b(0)
+            ^ (b(63) & b(60))
+            ^ (b(37) & b(33))
+            ^ (b(15) & b(9))
+            ^ (b(60) & b(52) & b(45))
+            ^ (b(33) & b(28) & b(21))
+            ^ (b(63) & b(45) & b(28) & b(9))
+            ^ (b(60) & b(52) & b(37) & b(33))
+            ^ (b(63) & b(60) & b(21) & b(15))
+            ^ (b(63) & b(60) & b(52) & b(45) & b(37))
+            ^ (b(33) & b(28) & b(21) & b(15) & b(9))
+            ^ (b(52) & b(45) & b(37) & b(33) & b(28) & b(21));
+
+        self.lfsr = (self.lfsr >> 1) | (((f ^ feedback) as u128) << 79);
+        self.nfsr = (self.nfsr >> 1) | (((g ^ feedback) as u128) << 79);
+    }
+
+    pub fn next_bit(&mut self) -> u8 {
+        let z = self.output();
+        self.clock(0);
+        z
+    }
+
+    // Bits are packed least significant first
+    pub fn next_byte(&mut self) -> u8 {
+        let mut out = 0;
+        for i in 0..8 {
+            out |= self.next_bit() << i;
+        }
+        out
+    }
+}
+
+impl SimpleRng for Grain {
+    // The first bit of keystream is the least significant bit, so the words are the keystream bytes read as little
+    // endian integers
+    fn next_u32(&mut self) -> u32 {
+        let mut out = 0;
+        for i in 0..32 {
+            out |= (self.next_bit() as u32) << i;
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn keystream_zero() {
+        let mut rng = Grain::default();
+        assert_eq!(0xcf31e9de, rng.next_u32());
+        assert_eq!(0x2fa76216, rng.next_u32());
+    }
+}
@@ -23,6 +23,7 @@ macro_rules! sub {
     };
 }
 
+#[derive(Debug, Clone)]
 pub struct Hc128 {
     p: [u32; 512],
     q: [u32; 512],
@@ -15,6 +15,7 @@ macro_rules! sub {
     };
 }
 
+#[derive(Debug, Clone)]
 pub struct Hc256 {
     p: [u32; 1024],
     q: [u32; 1024],
@@ -36,6 +36,7 @@ rng_ids_and_names!(
     DualEcDrbg, "Dual_EC_DRBG";
     Geffe, "Geffe";
     Gmwc, "Generalized Multiply-with-Carry";
+    Grain, "Grain";
     Hc128, "HC-128";
     Hc256, "HC-256";
     Halton, "Halton";
@@ -49,6 +50,7 @@ rng_ids_and_names!(
     Lfg, "Lagged Fibonacci Generator";
     Lfsr, "Linear Feedback Shift Register";
     MersenneTwister, "Mersenne Twister";
+    Mickey2, "MICKEY 2.0";
     MiddleSquare, "Middle Square";
     MiddleSquareBinary, "Middle Square (Word Sized)";
     MultipleRecursive, "Multiple Recursive Generator";
@@ -64,9 +66,11 @@ rng_ids_and_names!(
     Salsa20, "Salsa20";
     SelfShrinkingGenerator, "Self Shrinking Generator";
     ShrinkingGenerator, "Shrinking Generator";
+    Sosemanuk, "Sosemanuk";
     Splitmix, "Splitmix64";
     Squares, "Squares";
     Threefry, "Threefry";
+    Trivium, "Trivium";
     Tt800, "TT800";
     Vmpcr, "VMPC-R";
     Well, "WELL";
@@ -8,6 +8,7 @@ pub mod dual_ec_drbg;
 pub mod errors;
 pub mod geffe;
 pub mod gmwc;
+pub mod grain;
 pub mod halton;
 pub mod hc128;
 pub mod hc256;
@@ -22,6 +23,7 @@ pub mod lfg;
 pub mod lfsr;
 pub mod lfsr_small;
 pub mod mersenne_twister;
+pub mod mickey;
 pub mod middle_square;
 pub mod middle_square_binary;
 pub mod multiple_recursive;
@@ -37,10 +39,12 @@ pub mod rule30;
 pub mod salsa20;
 pub mod self_shrinking_generator;
 pub mod shrinking_generator;
+pub mod sosemanuk;
 pub mod splitmix;
 pub mod squares;
 pub mod threefry;
 pub mod traits;
+pub mod trivium;
 pub mod vmpcr;
 pub mod well;
 pub mod weyl;
@@ -0,0 +1,145 @@
+use crate::SimpleRng;
+use utils::errors::GeneralError;
+
+const MASK: u128 = (1 << 100) - 1;
+// Bits 1 to 98, the positions where the S register uses its nonlinear update
+const S_MIDDLE: u128 = MASK ^ 1 ^ (1 << 99);
+
+// Feedback taps of the R register
+const R_TAPS: u128 = 0x3_df87818f_b5546660_1279327b;
+const COMP0: u128 = 0x6_057ebfea_7942a809_6aa97a30;
+const COMP1: u128 = 0x1_91c23dd7_e3a21d63_dd629e9a;
+const FB0: u128 = 0x1_9cec5802_af4a9381_9ffa7faf;
+const FB1: u128 = 0x8_40fbc52b_4911b063_4c8cb877;
+
+fn bit(x: u128, i: u32) -> u8 {
+    ((x >> i) & 1) as u8
+}
+
+// Bits of a byte string most significant first
+fn bits_msb(bytes: &[u8]) -> impl Iterator<Item = u8> + '_ {
+    bytes
+        .iter()
+        .flat_map(|byte| (0..8).rev().map(move |i| (byte >> i) & 1))
+}
+
+// https://www.ecrypt.eu.org/stream/p3ciphers/mickey/mickey_p3.pdf
+// Bit i of each register is r_i or s_i
+#[derive(Debug, Clone)]
+pub struct Mickey2 {
+    r: u128,
+    s: u128,
+}
+
+impl Default for Mickey2 {
+    fn default() -> Self {
+        Self::with_key_and_iv([0; 10], &[]).unwrap()
+    }
+}
+
+impl Mickey2 {
+    /// The IV may be anywhere from zero to eighty bits, given here as up to ten bytes
+    pub fn with_key_and_iv(key: [u8; 10], iv: &[u8]) -> Result<Self, GeneralError> {
+        let mut out = Self { r: 0, s: 0 };
+        out.ksa(key, iv)?;
+        Ok(out)
+    }
+
+    pub fn ksa(&mut self, key: [u8; 10], iv: &[u8]) -> Result<(), GeneralError> {
+        if iv.len() > 10 {
+            return Err(GeneralError::key(
+                "MICKEY 2.0 accepts an IV of at most 80 bits",
+            ));
+        }
+        self.r = 0;
+        self.s = 0;
+        for b in bits_msb(iv) {
+            self.clock_kg(true, b);
+        }
+        for b in bits_msb(&key) {
+            self.clock_kg(true, b);
+        }
+        for _ in 0..100 {
+            self.clock_kg(true, 0);
+        }
+        Ok(())
+    }
+
+    fn clock_r(&mut self, input: u8, control: u8) {
+        let feedback = bit(self.r, 99) ^ input;
+        let mut r = (self.r << 1) & MASK;
+        if control == 1 {
+            r ^= self.r;
+        }
+        if feedback == 1 {
+            r ^= R_TAPS;
+        }
+        self.r = r;
+    }
+
+    fn clock_s(&mut self, input: u8, control: u8) {
+        let feedback = bit(self.s, 99) ^ input;
+        let s = self.s;
+        let mut s_hat = ((s << 1) ^ ((s ^ COMP0) & ((s >> 1) ^ COMP1) & S_MIDDLE)) & MASK;
+        if feedback == 1 {
+            s_hat ^= if control == 1 { FB1 } else { FB0 };
+        }
+        self.s = s_hat;
+    }
+
+    fn clock_kg(&mut self, mixing: bool, input: u8) {
+        let control_r = bit(self.s, 34) ^ bit(self.r, 67);
+        let control_s = bit(self.s, 67) ^ bit(self.r, 33);
+        let input_r = if mixing {
+            input ^ bit(self.s, 50)
+        } else {
+            input
+        };
+        self.clock_r(input_r, control_r);
+        self.clock_s(input, control_s);
+    }
+
+    pub fn next_bit(&mut self) -> u8 {
+        let z = bit(self.r, 0) ^ bit(self.s, 0);
+        self.clock_kg(false, 0);
+        z
+    }
+
+    // Bits are packed most significant first
+    pub fn next_byte(&mut self) -> u8 {
+        let mut out = 0;
+        for _ in 0..8 {
+            out = (out << 1) | self.next_bit();
+        }
+        out
+    }
+}
+
+impl SimpleRng for Mickey2 {
+    // The first bit of keystream is the most significant bit, so the words are the keystream bytes read as big endian
+    // integers
+    fn next_u32(&mut self) -> u32 {
+        let mut out = 0;
+        for _ in 0..32 {
+            out = (out << 1) | self.next_bit() as u32;
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn keystream() {
+        let mut rng = Mickey2::with_key_and_iv(
+            [0x12, 0x34, 0x56, 0x78, 0x9a, 0xbc, 0xde, 0xf0, 0x12, 0x34],
+            &[0x21, 0x43, 0x65, 0x87],
+        )
+        .unwrap();
+        assert_eq!(0x9821e10c, rng.next_u32());
+        assert_eq!(0x5ed28d32, rng.next_u32());
+    }
+}
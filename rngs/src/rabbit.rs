@@ -28,7 +28,7 @@ impl Default for Rabbit {
             ctrs: Default::default(),
             carry: 0,
             cache: [0; 4],
-            ptr: 0,
+            ptr: 4,
         }
     }
 }
@@ -78,7 +78,7 @@ impl Rabbit {
             ctrs,
             carry: 0,
             cache: [0; 4],
-            ptr: 0,
+            ptr: 4,
         };
 
         for _ in 0..4 {
@@ -150,14 +150,15 @@ impl Rabbit {
         self.state[7] = g[7].wrapping_add(g[6].rotate_left(8)).wrapping_add(g[5]);
     }
 
+    // Each word joins two 16-bit halves, matching the little endian words of the cipher's output block
     pub fn extract(&self) -> [u32; 4] {
         let s = self.state;
 
         [
-            ((s[0]) ^ (s[5] >> 16)) | ((s[0] >> 16) ^ (s[3])),
-            ((s[2]) ^ (s[7] >> 16)) | ((s[2] >> 16) ^ (s[5])),
-            ((s[4]) ^ (s[1] >> 16)) | ((s[4] >> 16) ^ (s[7])),
-            ((s[6]) ^ (s[3] >> 16)) | ((s[6] >> 16) ^ (s[1])),
+            ((s[0] ^ (s[5] >> 16)) & 0xffff) | ((s[0] >> 16) ^ s[3]) << 16,
+            ((s[2] ^ (s[7] >> 16)) & 0xffff) | ((s[2] >> 16) ^ s[5]) << 16,
+            ((s[4] ^ (s[1] >> 16)) & 0xffff) | ((s[4] >> 16) ^ s[7]) << 16,
+            ((s[6] ^ (s[3] >> 16)) & 0xffff) | ((s[6] >> 16) ^ s[1]) << 16,
         ]
     }
 
@@ -169,7 +170,7 @@ impl Rabbit {
 
 impl SimpleRng for Rabbit {
     fn next_u32(&mut self) -> u32 {
-        if self.ptr > 4 {
+        if self.ptr >= 4 {
             self.refill_cache();
             self.ptr = 0;
         }
@@ -178,3 +179,40 @@ impl SimpleRng for Rabbit {
         out
     }
 }
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    // The keystream blocks of the reference test vectors read as little endian words
+    #[test]
+    fn keystream_zero_key() {
+        let mut rng = Rabbit::with_key([0; 16]);
+        for word in [
+            0x1C4AF702, 0xF56B4526, 0x36A5D6EC, 0xB15754F0, 0x89C68AA7, 0x7B696C47, 0xC59C0C39,
+            0x88E8D815,
+        ] {
+            assert_eq!(word, rng.next_u32());
+        }
+    }
+
+    #[test]
+    fn keystream_key() {
+        let mut rng = Rabbit::with_key_u32([0xC21FCF38, 0x81CD5EE8, 0x628ACCB0, 0xA9890DF8]);
+        for word in [0xC7E0023D, 0x12915530, 0x90B773B4, 0xDF18E0DE] {
+            assert_eq!(word, rng.next_u32());
+        }
+    }
+
+    #[test]
+    fn keystream_iv() {
+        let mut rng = Rabbit::with_key_and_iv_u32([0, 0, 0, 0], [0x597E26C1, 0x75F573C3]);
+        for word in [
+            0x22017D6D, 0xE0DCCC92, 0x580012E2, 0x1FCD4EB9, 0xED936F2E, 0x7B2499FF, 0xD1212501,
+            0xA75F4E10,
+        ] {
+            assert_eq!(word, rng.next_u32());
+        }
+    }
+}
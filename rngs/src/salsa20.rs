@@ -76,35 +76,40 @@ impl Salsa20 {
         Self::even_round(state);
     }
 
-    pub fn next_block(&mut self) -> Vec<u32> {
-        let state = self.create_state();
-        self.ctr += 1;
-
+    // The block function shared with the Salsa20 and XSalsa20 stream ciphers
+    pub fn block_function(state: &[u32; 16], rounds: u8) -> [u32; 16] {
         // Temporary state
-        let mut t_state = state.clone();
+        let mut t_state = *state;
 
         // Only Salsa20/20, Salsa20/12, and Salsa20/8 are official but any number is usable
-        for _round in 0..self.rounds / 2 {
+        for _round in 0..rounds / 2 {
             Self::double_round(&mut t_state);
         }
-        if self.rounds % 2 == 1 {
+        if rounds % 2 == 1 {
             Self::odd_round(&mut t_state)
         }
 
-        // XOR the current state into the temporary state
+        // Add the current state into the temporary state
         for (i, word) in t_state.iter_mut().enumerate() {
             *word = word.wrapping_add(state[i])
         }
 
-        // Output the block
-        t_state.to_vec()
+        t_state
+    }
+
+    pub fn next_block(&mut self) -> Vec<u32> {
+        let block = Self::block_function(&self.create_state(), self.rounds);
+        self.ctr += 1;
+
+        // Reversed so that popping returns the words in order
+        block.into_iter().rev().collect()
     }
 }
 
 impl SimpleRng for Salsa20 {
     fn next_u32(&mut self) -> u32 {
         if self.saved_keystream.is_empty() {
-            self.next_block();
+            self.saved_keystream = self.next_block();
         }
 
         if self.big_endian {
@@ -120,3 +125,21 @@ impl SimpleRng for Salsa20 {
         }
     }
 }
+
+#[cfg(test)]
+mod salsa20_rng_tests {
+
+    use super::*;
+
+    #[test]
+    fn keystream_zero_key() {
+        // The first keystream block of Salsa20/20 with an all zero key and nonce, read as big endian words
+        let mut rng = Salsa20::default();
+        rng.key = [0; 8];
+        rng.nonce = [0; 2];
+
+        for word in [0x9a97f65b, 0x9b4c721b, 0x960a6721, 0x45fca8d4] {
+            assert_eq!(word, rng.next_u32());
+        }
+    }
+}
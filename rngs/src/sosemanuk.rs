@@ -0,0 +1,251 @@
+use crate::SimpleRng;
+
+// Sosemanuk initializes its state with a reduced version of Serpent. The forward Serpent primitives live here so that
+// the Serpent block cipher in the ciphers crate can share them.
+
+// The Serpent S-boxes, all of them are used by Serpent24 to initialize the state. Only S2 is used when producing outputs.
+pub const SBOX: [[u8; 16]; 8] = [
+    [3, 8, 15, 1, 10, 6, 5, 11, 14, 13, 4, 2, 7, 0, 9, 12],
+    [15, 12, 2, 7, 9, 0, 5, 10, 1, 11, 14, 8, 6, 13, 3, 4],
+    [8, 6, 7, 9, 3, 12, 10, 15, 13, 1, 14, 4, 0, 11, 5, 2],
+    [0, 15, 11, 8, 12, 9, 6, 3, 13, 1, 2, 4, 10, 7, 5, 14],
+    [1, 15, 8, 3, 12, 0, 11, 6, 2, 5, 4, 10, 9, 14, 7, 13],
+    [15, 5, 2, 11, 4, 10, 9, 12, 0, 3, 14, 8, 13, 6, 7, 1],
+    [7, 2, 12, 5, 8, 4, 6, 11, 14, 9, 1, 15, 13, 3, 10, 0],
+    [1, 13, 15, 0, 14, 8, 2, 11, 7, 4, 12, 10, 9, 3, 5, 6],
+];
+
+// Apply an S-box across the bits of four words
+pub fn sbox_bitslice(idx: usize, words: [u32; 4]) -> [u32; 4] {
+    let mut out = [0; 4];
+    for i in 0..32 {
+        let nibble = (0..4).fold(0, |acc, j| acc | (((words[j] >> i) & 1) << j));
+        let s = SBOX[idx % 8][nibble as usize] as u32;
+        for (j, word) in out.iter_mut().enumerate() {
+            *word |= ((s >> j) & 1) << i;
+        }
+    }
+    out
+}
+
+// Serpent's linear transformation
+pub fn lt(mut x: [u32; 4]) -> [u32; 4] {
+    x[0] = x[0].rotate_left(13);
+    x[2] = x[2].rotate_left(3);
+    x[1] ^= x[0] ^ x[2];
+    x[3] ^= x[2] ^ (x[0] << 3);
+    x[1] = x[1].rotate_left(1);
+    x[3] = x[3].rotate_left(7);
+    x[0] ^= x[1] ^ x[3];
+    x[2] ^= x[3] ^ (x[1] << 7);
+    x[0] = x[0].rotate_left(5);
+    x[2] = x[2].rotate_left(22);
+    x
+}
+
+pub fn xor_words(a: [u32; 4], b: [u32; 4]) -> [u32; 4] {
+    std::array::from_fn(|i| a[i] ^ b[i])
+}
+
+// All 33 subkeys of the Serpent key schedule, keys shorter than 256 bits are padded with a single one bit
+pub fn serpent_subkeys(key: &[u8]) -> [[u32; 4]; 33] {
+    let mut bytes = [0; 32];
+    bytes[..key.len()].copy_from_slice(key);
+    if key.len() < 32 {
+        bytes[key.len()] = 0x01;
+    }
+
+    let mut w = [0; 140];
+    for i in 0..8 {
+        w[i] = u32::from_le_bytes(bytes[4 * i..4 * i + 4].try_into().unwrap());
+    }
+    for i in 0..132 {
+        w[i + 8] = (w[i] ^ w[i + 3] ^ w[i + 5] ^ w[i + 7] ^ 0x9e3779b9 ^ i as u32).rotate_left(11);
+    }
+
+    std::array::from_fn(|i| {
+        let pre_key = [w[4 * i + 8], w[4 * i + 9], w[4 * i + 10], w[4 * i + 11]];
+        sbox_bitslice((35 - i) % 8, pre_key)
+    })
+}
+
+// Multiplication in GF(2^8) defined by x^8 + x^7 + x^5 + x^3 + 1, where beta is the element x
+const fn gf_mul(mut a: u8, mut b: u8) -> u8 {
+    let mut out = 0;
+    while b != 0 {
+        if b & 1 == 1 {
+            out ^= a;
+        }
+        let carry = a & 0x80 != 0;
+        a <<= 1;
+        if carry {
+            a ^= 0xa9;
+        }
+        b >>= 1;
+    }
+    out
+}
+
+const fn beta_pow(n: usize) -> u8 {
+    let mut out = 1;
+    let mut i = 0;
+    while i < n {
+        out = gf_mul(out, 2);
+        i += 1;
+    }
+    out
+}
+
+// Multiplying a byte by the four coefficients given as powers of beta produces one entry of a table
+const fn table(exponents: [usize; 4]) -> [u32; 256] {
+    let mut out = [0; 256];
+    let mut b = 0;
+    while b < 256 {
+        let mut i = 0;
+        while i < 4 {
+            out[b] |= (gf_mul(b as u8, beta_pow(exponents[i])) as u32) << (24 - 8 * i);
+            i += 1;
+        }
+        b += 1;
+    }
+    out
+}
+
+// Alpha is a root of X^4 + b^23 X^3 + b^245 X^2 + b^48 X + b^239 over GF(2^8) so each of these tables reduces the byte
+// that is shifted out when multiplying or dividing by alpha
+const MUL_ALPHA: [u32; 256] = table([23, 245, 48, 239]);
+const DIV_ALPHA: [u32; 256] = table([16, 39, 6, 64]);
+
+fn mul_alpha(x: u32) -> u32 {
+    (x << 8) ^ MUL_ALPHA[(x >> 24) as usize]
+}
+
+fn div_alpha(x: u32) -> u32 {
+    (x >> 8) ^ DIV_ALPHA[(x & 0xff) as usize]
+}
+
+// https://www.ecrypt.eu.org/stream/p3ciphers/sosemanuk/sosemanuk_p3.pdf
+// Combines a SNOW 2.0 style LFSR and FSM with the Serpent S-boxes, which are also used to set up the state from
+// the key and IV
+#[derive(Debug, Clone)]
+pub struct Sosemanuk {
+    // The words s_t to s_(t+9)
+    lfsr: [u32; 10],
+    r1: u32,
+    r2: u32,
+    cache: [u32; 4],
+    ptr: usize,
+}
+
+impl Default for Sosemanuk {
+    fn default() -> Self {
+        Self::with_key_and_iv(&[0; 16], [0; 16])
+    }
+}
+
+impl Sosemanuk {
+    /// Keys from 1 to 32 bytes are accepted
+    pub fn with_key_and_iv(key: &[u8], iv: [u8; 16]) -> Self {
+        assert!(
+            !key.is_empty() && key.len() <= 32,
+            "Sosemanuk keys must be from 1 to 32 bytes"
+        );
+        // Serpent24 uses the first 25 subkeys of the ordinary Serpent key schedule
+        let k = serpent_subkeys(key);
+
+        let mut block: [u32; 4] =
+            std::array::from_fn(|i| u32::from_le_bytes(iv[4 * i..4 * i + 4].try_into().unwrap()));
+        let mut y12 = [0; 4];
+        let mut y18 = [0; 4];
+        for (i, subkey) in k.iter().enumerate().take(24) {
+            block = lt(sbox_bitslice(i, xor_words(block, *subkey)));
+            if i == 11 {
+                y12 = block;
+            } else if i == 17 {
+                y18 = block;
+            }
+        }
+        // The last round is a complete round followed by an extra subkey addition
+        let y24 = xor_words(block, k[24]);
+
+        Self {
+            lfsr: [
+                y24[3], y24[2], y24[1], y24[0], y18[1], y18[3], y12[3], y12[2], y12[1], y12[0],
+            ],
+            r1: y18[0],
+            r2: y18[2],
+            cache: [0; 4],
+            ptr: 4,
+        }
+    }
+
+    // Clock the FSM and LFSR once, returning the FSM output and the word dropped from the LFSR
+    fn step(&mut self) -> (u32, u32) {
+        let s = self.lfsr;
+
+        let mux = if self.r1 & 1 == 1 { s[1] ^ s[8] } else { s[1] };
+        let old_r1 = self.r1;
+        self.r1 = self.r2.wrapping_add(mux);
+        self.r2 = old_r1.wrapping_mul(0x54655307).rotate_left(7);
+        let f = s[9].wrapping_add(self.r1) ^ self.r2;
+
+        self.lfsr.rotate_left(1);
+        self.lfsr[9] = mul_alpha(s[0]) ^ div_alpha(s[3]) ^ s[9];
+
+        (f, s[0])
+    }
+
+    // Four steps produce four words which are passed through the third Serpent S-box and combined with the words
+    // dropped from the LFSR
+    pub fn next_block(&mut self) -> [u32; 4] {
+        let mut f = [0; 4];
+        let mut dropped = [0; 4];
+        for i in 0..4 {
+            (f[i], dropped[i]) = self.step();
+        }
+        xor_words(sbox_bitslice(2, f), dropped)
+    }
+
+    pub fn refill_cache(&mut self) {
+        self.cache = self.next_block();
+    }
+}
+
+impl SimpleRng for Sosemanuk {
+    fn next_u32(&mut self) -> u32 {
+        if self.ptr >= 4 {
+            self.refill_cache();
+            self.ptr = 0;
+        }
+        let out = self.cache[self.ptr];
+        self.ptr += 1;
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn keystream() {
+        let mut rng = Sosemanuk::with_key_and_iv(
+            &[0xA7, 0xC0, 0x83, 0xFE, 0xB7],
+            [
+                0x00, 0x11, 0x22, 0x33, 0x44, 0x55, 0x66, 0x77, 0x88, 0x99, 0xAA, 0xBB, 0xCC, 0xDD,
+                0xEE, 0xFF,
+            ],
+        );
+        for word in [0x16D281FE, 0x0D109A2C, 0x455C8904, 0x5B51774A, 0x1A436ABE] {
+            assert_eq!(word, rng.next_u32());
+        }
+    }
+
+    #[test]
+    fn alpha_inverse() {
+        for x in [1, 0x12345678, 0xffffffff, 0x80000001] {
+            assert_eq!(x, div_alpha(mul_alpha(x)));
+        }
+    }
+}
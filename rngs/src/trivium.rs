@@ -0,0 +1,104 @@
+use crate::SimpleRng;
+
+// Lengths of the three registers, which together hold the 288 bit state
+const MASK_A: u128 = (1 << 93) - 1;
+const MASK_B: u128 = (1 << 84) - 1;
+const MASK_C: u128 = (1 << 111) - 1;
+
+fn bit(x: u128, i: usize) -> u8 {
+    ((x >> i) & 1) as u8
+}
+
+// https://www.ecrypt.eu.org/stream/p3ciphers/trivium/trivium_p3.pdf
+// Bit i of each register is the state bit with index i + 1 counting from the start of that register, so a is
+// s1..s93, b is s94..s177, and c is s178..s288
+#[derive(Debug, Clone)]
+pub struct Trivium {
+    a: u128,
+    b: u128,
+    c: u128,
+}
+
+impl Default for Trivium {
+    fn default() -> Self {
+        Self::with_key_and_iv([0; 10], [0; 10])
+    }
+}
+
+impl Trivium {
+    pub fn with_key_and_iv(key: [u8; 10], iv: [u8; 10]) -> Self {
+        // The key bits K1 to K80 are numbered from the least significant bit of the first byte and loaded in reverse,
+        // so s1 = K80 and s80 = K1. The IV is loaded the same way.
+        let reversed = |bytes: [u8; 10]| {
+            let mut b = [0; 16];
+            b[..10].copy_from_slice(&bytes);
+            u128::from_le_bytes(b).reverse_bits() >> 48
+        };
+
+        let mut out = Self {
+            a: reversed(key),
+            b: reversed(iv),
+            c: 0b111 << 108,
+        };
+        for _ in 0..(4 * 288) {
+            out.next_bit();
+        }
+        out
+    }
+
+    pub fn next_bit(&mut self) -> u8 {
+        let (a, b, c) = (self.a, self.b, self.c);
+
+        let mut t1 = bit(a, 65) ^ bit(a, 92);
+        let mut t2 = bit(b, 68) ^ bit(b, 83);
+        let mut t3 = bit(c, 65) ^ bit(c, 110);
+        let z = t1 ^ t2 ^ t3;
+
+        t1 ^= (bit(a, 90) & bit(a, 91)) ^ bit(b, 77);
+        t2 ^= (bit(b, 81) & bit(b, 82)) ^ bit(c, 86);
+        t3 ^= (bit(c, 108) & bit(c, 109)) ^ bit(a, 68);
+
+        self.a = ((a << 1) | t3 as u128) & MASK_A;
+        self.b = ((b << 1) | t1 as u128) & MASK_B;
+        self.c = ((c << 1) | t2 as u128) & MASK_C;
+
+        z
+    }
+
+    // Bits are packed least significant first
+    pub fn next_byte(&mut self) -> u8 {
+        let mut out = 0;
+        for i in 0..8 {
+            out |= self.next_bit() << i;
+        }
+        out
+    }
+}
+
+impl SimpleRng for Trivium {
+    // The first bit of keystream is the least significant bit, so the words are the keystream bytes read as little
+    // endian integers
+    fn next_u32(&mut self) -> u32 {
+        u32::from_le_bytes(std::array::from_fn(|_| self.next_byte()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn keystream_zero() {
+        let mut rng = Trivium::default();
+        assert_eq!(0x26bfe0fb, rng.next_u32());
+        assert_eq!(0x1b055958, rng.next_u32());
+    }
+
+    #[test]
+    fn keystream_set_1_vector_0() {
+        let mut rng = Trivium::with_key_and_iv([0x80, 0, 0, 0, 0, 0, 0, 0, 0, 0], [0; 10]);
+        assert_eq!(0xff86eb38, rng.next_u32());
+        assert_eq!(0x9c7a0d73, rng.next_u32());
+    }
+}
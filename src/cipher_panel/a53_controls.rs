@@ -0,0 +1,117 @@
+use super::CipherFrame;
+use crate::ui_elements::UiElements;
+use ciphers::{
+    digital::stream_ciphers::kgcore::{Gea3, A53},
+    Cipher,
+};
+use egui::Ui;
+use rand::{thread_rng, Rng};
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+enum A53Select {
+    #[default]
+    A53,
+    Gea3,
+}
+
+#[derive(Default)]
+pub struct A53Frame {
+    a53: A53,
+    gea3: Gea3,
+    selector: A53Select,
+    key: u64,
+}
+
+impl A53Frame {
+    fn set_key(&mut self) {
+        self.a53.key = self.key.to_be_bytes();
+        self.gea3.key = self.key.to_be_bytes();
+    }
+}
+
+impl CipherFrame for A53Frame {
+    fn ui(&mut self, ui: &mut Ui, _errors: &mut String) {
+        ui.hyperlink_to(
+            "see the code",
+            "https://github.com/SymmetricChaos/crypto-gui/blob/master/ciphers/src/digital/stream_ciphers/kgcore.rs",
+        );
+        ui.add_space(8.0);
+
+        ui.randomize_reset_cipher(self);
+        ui.add_space(16.0);
+
+        ui.horizontal(|ui| {
+            ui.selectable_value(&mut self.selector, A53Select::A53, "A5/3 (GSM)");
+            ui.selectable_value(&mut self.selector, A53Select::Gea3, "GEA3 (GPRS)");
+        });
+        ui.add_space(8.0);
+
+        match self.selector {
+            A53Select::A53 => {
+                ui.byte_io_mode_cipher(&mut self.a53.input_format, &mut self.a53.output_format)
+            }
+            A53Select::Gea3 => {
+                ui.byte_io_mode_cipher(&mut self.gea3.input_format, &mut self.gea3.output_format)
+            }
+        };
+        ui.add_space(16.0);
+
+        ui.subheading("Key (Kc, 64 bits)");
+        ui.label("The key is repeated to form the 128-bit KASUMI key used by KGCORE.");
+        if ui.u64_hex_edit(&mut self.key).changed() {
+            self.set_key();
+        }
+        ui.add_space(8.0);
+
+        match self.selector {
+            A53Select::A53 => {
+                ui.subheading("Frame Number (Limited to 22 Bits)");
+                if ui.u32_hex_edit(&mut self.a53.frame_number).lost_focus() {
+                    self.a53.frame_number &= 0x3fffff;
+                }
+                ui.add_space(4.0);
+                ui.label("Each frame produces a 114-bit block for each direction. In GSM each block encrypts a single burst. To allow longer messages here the text is encrypted with the downlink blocks of consecutive frames, which is not part of the standard.");
+                ui.add_space(8.0);
+                ui.collapsing("Keystream Blocks", |ui| {
+                    let (down, up) = self.a53.burst_bytes(self.a53.frame_number);
+                    ui.label("Downlink");
+                    ui.mono(utils::byte_formatting::bytes_to_hex(down));
+                    ui.label("Uplink");
+                    ui.mono(utils::byte_formatting::bytes_to_hex(up));
+                });
+            }
+            A53Select::Gea3 => {
+                ui.subheading("Input (32 Bits)");
+                ui.u32_hex_edit(&mut self.gea3.input);
+                ui.add_space(8.0);
+                ui.checkbox(&mut self.gea3.direction, "Direction (Downlink)");
+            }
+        }
+    }
+
+    fn randomize(&mut self) {
+        let mut rng = thread_rng();
+        self.key = rng.gen();
+        self.set_key();
+        self.a53.frame_number = rng.gen::<u32>() & 0x3fffff;
+        self.gea3.input = rng.gen();
+    }
+
+    fn reset(&mut self) {
+        *self = Self::default()
+    }
+
+    fn encrypt_string(&self, text: &str) -> Result<String, utils::errors::GeneralError> {
+        match self.selector {
+            A53Select::A53 => self.a53.encrypt(text),
+            A53Select::Gea3 => self.gea3.encrypt(text),
+        }
+    }
+
+    fn decrypt_string(&self, text: &str) -> Result<String, utils::errors::GeneralError> {
+        match self.selector {
+            A53Select::A53 => self.a53.decrypt(text),
+            A53Select::Gea3 => self.gea3.decrypt(text),
+        }
+    }
+}
@@ -0,0 +1,88 @@
+use super::CipherFrame;
+use crate::ui_elements::UiElements;
+use ciphers::digital::stream_ciphers::achterbahn::Achterbahn128;
+use egui::Ui;
+use rand::{thread_rng, Rng};
+use utils::byte_formatting::ByteFormat;
+
+pub struct AchterbahnFrame {
+    input_format: ByteFormat,
+    output_format: ByteFormat,
+    cipher: Achterbahn128,
+    key: u128,
+    iv: u128,
+}
+
+impl Default for AchterbahnFrame {
+    fn default() -> Self {
+        // These match the key and IV of Achterbahn128::default()
+        Self {
+            input_format: ByteFormat::Utf8,
+            output_format: ByteFormat::Hex,
+            cipher: Default::default(),
+            key: 0x0102030405060708090a0b0c0d0e0f10,
+            iv: 0x0102030405060708090a0b0c0d0e0f10,
+        }
+    }
+}
+
+impl AchterbahnFrame {
+    fn set_cipher(&mut self) {
+        self.cipher
+            .ksa(self.key.to_be_bytes(), self.iv.to_be_bytes());
+    }
+}
+
+impl CipherFrame for AchterbahnFrame {
+    fn ui(&mut self, ui: &mut Ui, _errors: &mut String) {
+        ui.hyperlink_to(
+            "see the code",
+            "https://github.com/SymmetricChaos/crypto-gui/blob/master/ciphers/src/digital/stream_ciphers/achterbahn.rs",
+        );
+        ui.add_space(8.0);
+
+        ui.randomize_reset_cipher(self);
+        ui.add_space(16.0);
+
+        ui.byte_io_mode_cipher(&mut self.input_format, &mut self.output_format);
+        ui.add_space(16.0);
+
+        ui.subheading("Key (128 bits)");
+        if ui.u128_hex_edit(&mut self.key).lost_focus() {
+            self.set_cipher();
+        }
+        ui.add_space(8.0);
+
+        ui.subheading("IV (128 bits)");
+        if ui.u128_hex_edit(&mut self.iv).lost_focus() {
+            self.set_cipher();
+        }
+        ui.add_space(8.0);
+
+        ui.label("Achterbahn-128 steps thirteen nonlinear feedback shift registers of lengths 21 through 33 and combines one bit from each with a Boolean function of degree four.");
+    }
+
+    fn randomize(&mut self) {
+        let mut rng = thread_rng();
+        self.key = rng.gen();
+        self.iv = rng.gen();
+        self.set_cipher();
+    }
+
+    fn reset(&mut self) {
+        *self = Self::default()
+    }
+
+    fn encrypt_string(&self, text: &str) -> Result<String, utils::errors::GeneralError> {
+        let mut bytes = self
+            .input_format
+            .text_to_bytes(text)
+            .map_err(|e| utils::errors::GeneralError::input(e.to_string()))?;
+        self.cipher.encrypt_bytes(&mut bytes);
+        Ok(self.output_format.byte_slice_to_text(&bytes))
+    }
+
+    fn decrypt_string(&self, text: &str) -> Result<String, utils::errors::GeneralError> {
+        self.encrypt_string(text)
+    }
+}
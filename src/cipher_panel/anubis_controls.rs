@@ -0,0 +1,91 @@
+use super::CipherFrame;
+use crate::ui_elements::{block_cipher_iv_128, block_cipher_mode_and_padding, UiElements};
+use ciphers::digital::block_ciphers::anubis::Anubis;
+use egui::Ui;
+use rand::{thread_rng, Rng};
+use utils::byte_formatting::ByteFormat;
+
+pub struct AnubisFrame {
+    cipher: Anubis,
+    key: String,
+    ksa_error: String,
+}
+
+impl Default for AnubisFrame {
+    fn default() -> Self {
+        let mut frame = Self {
+            cipher: Default::default(),
+            key: String::from("00000000000000000000000000000000"),
+            ksa_error: String::new(),
+        };
+        frame.run_ksa();
+        frame
+    }
+}
+
+impl AnubisFrame {
+    fn run_ksa(&mut self) {
+        match ByteFormat::Hex.text_to_bytes(&self.key) {
+            Ok(bytes) => match self.cipher.ksa(&bytes) {
+                Ok(_) => self.ksa_error.clear(),
+                Err(e) => self.ksa_error = e.to_string(),
+            },
+            Err(_) => self.ksa_error = String::from("key must be given as hexadecimal bytes"),
+        }
+    }
+}
+
+impl CipherFrame for AnubisFrame {
+    fn ui(&mut self, ui: &mut Ui, _errors: &mut String) {
+        ui.hyperlink_to(
+            "see the code",
+            "https://github.com/SymmetricChaos/crypto-gui/blob/master/ciphers/src/digital/block_ciphers/anubis.rs",
+        );
+        ui.add_space(8.0);
+
+        ui.randomize_reset_cipher(self);
+        ui.add_space(16.0);
+
+        ui.byte_io_mode_cipher(
+            &mut self.cipher.input_format,
+            &mut self.cipher.output_format,
+        );
+
+        ui.add_space(16.0);
+
+        block_cipher_mode_and_padding(ui, &mut self.cipher.mode, &mut self.cipher.padding);
+        ui.add_space(8.0);
+
+        ui.label("Every component of Anubis is an involution so decryption uses the same round function as encryption, only the key schedule changes. The number of rounds is eight more than the number of 32-bit words in the key.");
+        ui.add_space(8.0);
+
+        ui.subheading("Key");
+        ui.label("Key should be provided as a string of hexadecimal digits representing between 16 and 40 bytes, in steps of 4 bytes.");
+        if ui.control_string(&mut self.key).changed() {
+            self.key = self.key.chars().filter(|c| c.is_ascii_hexdigit()).collect();
+            self.run_ksa();
+        }
+        ui.error_text(&self.ksa_error);
+        ui.label(format!("Rounds: {}", self.cipher.rounds));
+
+        ui.add_space(8.0);
+
+        block_cipher_iv_128(ui, &mut self.cipher.iv, self.cipher.mode);
+
+        ui.add_space(16.0);
+    }
+
+    fn randomize(&mut self) {
+        let mut rng = thread_rng();
+        let mut key = [0_u8; 16];
+        rng.fill(&mut key);
+        self.key = ByteFormat::Hex.byte_slice_to_text(key);
+        self.run_ksa();
+
+        if self.cipher.mode.iv_needed() {
+            self.cipher.iv = rng.gen();
+        }
+    }
+
+    crate::simple_cipher! {}
+}
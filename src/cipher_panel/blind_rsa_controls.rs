@@ -0,0 +1,180 @@
+use super::CipherFrame;
+use crate::ui_elements::UiElements;
+use ciphers::digital::public_key_ciphers::{
+    rsa::Rsa,
+    rsa_blind::{blind, sign_blinded, unblind},
+};
+use egui::Ui;
+use hashers::sha::sha2::Sha2Variant;
+use num::BigUint;
+use rand::thread_rng;
+use utils::{byte_formatting::ByteFormat, errors::GeneralError};
+
+pub struct BlindRsaFrame {
+    input_format: ByteFormat,
+    output_format: ByteFormat,
+    signer: Rsa,
+    hash: Sha2Variant,
+    message: String,
+    // The blinded message and the inverse of the blinding factor, which only the requester knows
+    blinded: Option<(Vec<u8>, BigUint)>,
+    blind_signature: Option<Vec<u8>>,
+    signature: Option<Vec<u8>>,
+}
+
+impl Default for BlindRsaFrame {
+    fn default() -> Self {
+        let mut out = Self {
+            input_format: ByteFormat::Utf8,
+            output_format: ByteFormat::Hex,
+            signer: Rsa::default(),
+            hash: Sha2Variant::Sha256,
+            message: String::from("a coin worth one dollar, serial number 0x5eed"),
+            blinded: None,
+            blind_signature: None,
+            signature: None,
+        };
+        out.randomize();
+        out
+    }
+}
+
+impl BlindRsaFrame {
+    fn clear_steps(&mut self) {
+        self.blinded = None;
+        self.blind_signature = None;
+        self.signature = None;
+    }
+
+    fn message_bytes(&self, text: &str) -> Result<Vec<u8>, GeneralError> {
+        self.input_format
+            .text_to_bytes(text)
+            .map_err(|_| GeneralError::input("byte format error"))
+    }
+}
+
+impl CipherFrame for BlindRsaFrame {
+    fn ui(&mut self, ui: &mut Ui, errors: &mut String) {
+        ui.hyperlink_to(
+            "see the code",
+            "https://github.com/SymmetricChaos/crypto-gui/blob/master/ciphers/src/digital/public_key_ciphers/rsa_blind.rs",
+        );
+        ui.add_space(8.0);
+
+        ui.randomize_reset_cipher(self);
+        ui.add_space(16.0);
+
+        ui.byte_io_mode_cipher(&mut self.input_format, &mut self.output_format);
+        ui.add_space(16.0);
+
+        ui.horizontal(|ui| {
+            ui.subheading("Signer's Key");
+            if ui.button("New Key").clicked() {
+                errors.clear();
+                self.randomize();
+            }
+        });
+        ui.label("n");
+        ui.mono(self.signer.n.to_str_radix(16));
+        ui.label("e");
+        ui.mono(self.signer.e.to_str_radix(16));
+        ui.add_space(8.0);
+
+        ui.subheading("Hash");
+        ui.horizontal(|ui| {
+            for (variant, name) in [
+                (Sha2Variant::Sha256, "SHA-256"),
+                (Sha2Variant::Sha384, "SHA-384"),
+                (Sha2Variant::Sha512, "SHA-512"),
+            ] {
+                if ui.selectable_value(&mut self.hash, variant, name).clicked() {
+                    self.clear_steps();
+                }
+            }
+        });
+        ui.add_space(16.0);
+
+        ui.subheading("Step by Step");
+        ui.label("The requester blinds the PKCS#1 v1.5 encoding of the message by multiplying it by r^e for a random r. The signer applies the private key to whatever it is sent. Dividing the reply by r leaves an ordinary signature on the message that the signer has never seen.");
+        ui.label("Message");
+        if ui.control_string(&mut self.message).changed() {
+            self.clear_steps();
+        }
+
+        if ui.button("1. Blind (requester)").clicked() {
+            errors.clear();
+            self.clear_steps();
+            match self
+                .message_bytes(&self.message)
+                .and_then(|msg| blind(&self.signer, &msg, self.hash, &mut thread_rng()))
+            {
+                Ok(blinded) => self.blinded = Some(blinded),
+                Err(e) => *errors = e.to_string(),
+            }
+        }
+        if let Some((blinded, _)) = &self.blinded {
+            ui.label("Blinded message, all that the signer sees");
+            ui.mono(self.output_format.byte_slice_to_text(blinded));
+
+            if ui.button("2. Sign (signer)").clicked() {
+                errors.clear();
+                match sign_blinded(&self.signer, blinded) {
+                    Ok(s) => self.blind_signature = Some(s),
+                    Err(e) => *errors = e.to_string(),
+                }
+            }
+        }
+        if let (Some((_, inverse)), Some(blind_signature)) = (&self.blinded, &self.blind_signature)
+        {
+            ui.label("Blind signature");
+            ui.mono(self.output_format.byte_slice_to_text(blind_signature));
+
+            if ui.button("3. Unblind (requester)").clicked() {
+                errors.clear();
+                match unblind(&self.signer, blind_signature, inverse) {
+                    Ok(s) => self.signature = Some(s),
+                    Err(e) => *errors = e.to_string(),
+                }
+            }
+        }
+        if let Some(signature) = &self.signature {
+            ui.label("Signature");
+            ui.mono(self.output_format.byte_slice_to_text(signature));
+            let valid = self
+                .message_bytes(&self.message)
+                .is_ok_and(|msg| self.signer.verify_pkcs1(&msg, signature, self.hash));
+            if valid {
+                ui.label("valid PKCS#1 v1.5 signature under the signer's public key");
+            } else {
+                ui.error_text("INVALID SIGNATURE");
+            }
+        }
+        ui.add_space(16.0);
+    }
+
+    fn randomize(&mut self) {
+        self.signer
+            .generate_key(1024, &mut thread_rng())
+            .expect("1024 bits is a valid modulus size");
+        self.clear_steps();
+    }
+
+    fn reset(&mut self) {
+        *self = Self::default()
+    }
+
+    // Runs all three steps and gives the unblinded signature
+    fn encrypt_string(&self, text: &str) -> Result<String, GeneralError> {
+        let msg = self.message_bytes(text)?;
+        let (blinded, inverse) = blind(&self.signer, &msg, self.hash, &mut thread_rng())?;
+        let blind_signature = sign_blinded(&self.signer, &blinded)?;
+        let signature = unblind(&self.signer, &blind_signature, &inverse)?;
+        Ok(self.output_format.byte_slice_to_text(signature))
+    }
+
+    fn decrypt_string(&self, _text: &str) -> Result<String, GeneralError> {
+        Err(GeneralError::general(
+            "blind signatures cannot be decrypted",
+        ))
+    }
+}
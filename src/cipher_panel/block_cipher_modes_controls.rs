@@ -0,0 +1,170 @@
+use super::CipherFrame;
+use crate::ui_elements::{block_cipher_mode_and_padding, UiElements};
+use ciphers::digital::block_ciphers::{
+    block_cipher::{BCMode, BCPadding},
+    dyn_block_cipher::{BlockCipherRegistry, DynBlockCipher, BLOCK_CIPHER_NAMES},
+};
+use egui::{ComboBox, Ui};
+use rand::{thread_rng, Rng};
+use utils::{byte_formatting::ByteFormat, errors::GeneralError};
+
+// Every cipher in the registry is run through the same mode and padding code, selected by name at runtime
+pub struct BlockCipherModesFrame {
+    input_format: ByteFormat,
+    output_format: ByteFormat,
+    registry: BlockCipherRegistry,
+    name: &'static str,
+    mode: BCMode,
+    padding: BCPadding,
+    key: String,
+    iv: String,
+}
+
+impl Default for BlockCipherModesFrame {
+    fn default() -> Self {
+        let mut out = Self {
+            input_format: ByteFormat::Utf8,
+            output_format: ByteFormat::Hex,
+            registry: BlockCipherRegistry::default(),
+            name: BLOCK_CIPHER_NAMES[0],
+            mode: BCMode::default(),
+            padding: BCPadding::default(),
+            key: String::new(),
+            iv: String::new(),
+        };
+        out.randomize();
+        out
+    }
+}
+
+impl BlockCipherModesFrame {
+    fn cipher(&self) -> &dyn DynBlockCipher {
+        self.registry
+            .get(self.name)
+            .expect("only registered names can be selected")
+    }
+
+    fn set_key(&mut self) -> Result<(), GeneralError> {
+        let key = ByteFormat::Hex
+            .text_to_bytes(&self.key)
+            .map_err(|_| GeneralError::key("key must be given as hexadecimal bytes"))?;
+        self.registry.set_key(self.name, &key)
+    }
+
+    fn iv_bytes(&self) -> Result<Vec<u8>, GeneralError> {
+        if !self.mode.iv_needed() {
+            return Ok(vec![0; self.cipher().block_size()]);
+        }
+        ByteFormat::Hex
+            .text_to_bytes(&self.iv)
+            .map_err(|_| GeneralError::input("IV must be given as hexadecimal bytes"))
+    }
+
+    // Random key with the same length as the current one, or the first length the cipher accepts if that fails
+    fn random_key(&mut self) {
+        let mut rng = thread_rng();
+        let current = self.key.len() / 2;
+        for len in [current, 16].into_iter().chain(1..=32) {
+            let key: Vec<u8> = (0..len).map(|_| rng.gen()).collect();
+            if self.registry.set_key(self.name, &key).is_ok() {
+                self.key = ByteFormat::Hex.byte_slice_to_text(&key);
+                return;
+            }
+        }
+    }
+}
+
+impl CipherFrame for BlockCipherModesFrame {
+    fn ui(&mut self, ui: &mut Ui, errors: &mut String) {
+        ui.hyperlink_to(
+            "see the code",
+            "https://github.com/SymmetricChaos/crypto-gui/blob/master/ciphers/src/digital/block_ciphers/dyn_block_cipher.rs",
+        );
+        ui.add_space(8.0);
+
+        ui.randomize_reset_cipher(self);
+        ui.add_space(16.0);
+
+        ui.byte_io_mode_cipher(&mut self.input_format, &mut self.output_format);
+        ui.add_space(16.0);
+
+        ui.subheading("Block Cipher");
+        let previous = self.name;
+        ComboBox::from_id_salt("BlockCipherName")
+            .selected_text(self.name)
+            .show_ui(ui, |ui| {
+                for name in BLOCK_CIPHER_NAMES {
+                    ui.selectable_value(&mut self.name, *name, *name);
+                }
+            });
+        if self.name != previous {
+            errors.clear();
+            self.random_key();
+            self.iv = ByteFormat::Hex.byte_slice_to_text(vec![0; self.cipher().block_size()]);
+        }
+        ui.label(format!(
+            "{} has a block size of {} bytes.",
+            self.name,
+            self.cipher().block_size()
+        ));
+        ui.add_space(8.0);
+
+        block_cipher_mode_and_padding(ui, &mut self.mode, &mut self.padding);
+        ui.add_space(8.0);
+
+        ui.horizontal(|ui| {
+            ui.subheading("Key (hexadecimal)");
+            if ui.button("🎲").on_hover_text("random key").clicked() {
+                errors.clear();
+                self.random_key();
+            }
+        });
+        if ui.control_string(&mut self.key).changed() {
+            errors.clear();
+            if let Err(e) = self.set_key() {
+                *errors = e.to_string();
+            }
+        }
+        ui.add_space(8.0);
+
+        if self.mode.iv_needed() {
+            ui.subheading("IV (hexadecimal)");
+            ui.control_string(&mut self.iv);
+        } else {
+            ui.label(format!("No IV is needed in {} mode", self.mode));
+        }
+        ui.add_space(16.0);
+    }
+
+    fn randomize(&mut self) {
+        self.random_key();
+        let iv: Vec<u8> = (0..self.cipher().block_size())
+            .map(|_| thread_rng().gen())
+            .collect();
+        self.iv = ByteFormat::Hex.byte_slice_to_text(iv);
+    }
+
+    fn reset(&mut self) {
+        *self = Self::default()
+    }
+
+    fn encrypt_string(&self, text: &str) -> Result<String, GeneralError> {
+        let mut bytes = self
+            .input_format
+            .text_to_bytes(text)
+            .map_err(|_| GeneralError::input("byte format error"))?;
+        self.cipher()
+            .encrypt_padded(&mut bytes, self.mode, self.padding, &self.iv_bytes()?)?;
+        Ok(self.output_format.byte_slice_to_text(&bytes))
+    }
+
+    fn decrypt_string(&self, text: &str) -> Result<String, GeneralError> {
+        let mut bytes = self
+            .input_format
+            .text_to_bytes(text)
+            .map_err(|_| GeneralError::input("byte format error"))?;
+        self.cipher()
+            .decrypt_padded(&mut bytes, self.mode, self.padding, &self.iv_bytes()?)?;
+        Ok(self.output_format.byte_slice_to_text(&bytes))
+    }
+}
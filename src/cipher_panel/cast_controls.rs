@@ -0,0 +1,180 @@
+use super::CipherFrame;
+use crate::ui_elements::{
+    block_cipher_iv_128, block_cipher_iv_64, block_cipher_mode_and_padding, UiElements,
+};
+use ciphers::{
+    digital::block_ciphers::cast::{cast128::Cast128, cast256::Cast256},
+    Cipher,
+};
+use egui::Ui;
+use rand::{thread_rng, Rng};
+use utils::byte_formatting::ByteFormat;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+enum CastSelect {
+    #[default]
+    Cast128,
+    Cast256,
+}
+
+pub struct CastFrame {
+    cipher128: Cast128,
+    cipher256: Cast256,
+    selector: CastSelect,
+    key: String,
+    ksa_error: String,
+}
+
+impl Default for CastFrame {
+    fn default() -> Self {
+        let mut frame = Self {
+            cipher128: Default::default(),
+            cipher256: Default::default(),
+            selector: Default::default(),
+            key: String::from("00000000000000000000000000000000"),
+            ksa_error: String::new(),
+        };
+        frame.run_ksa();
+        frame
+    }
+}
+
+impl CastFrame {
+    fn run_ksa(&mut self) {
+        let bytes = match ByteFormat::Hex.text_to_bytes(&self.key) {
+            Ok(bytes) => bytes,
+            Err(_) => {
+                self.ksa_error = String::from("key must be given as hexadecimal bytes");
+                return;
+            }
+        };
+        let result = match self.selector {
+            CastSelect::Cast128 => self.cipher128.ksa(&bytes),
+            CastSelect::Cast256 => self.cipher256.ksa(&bytes),
+        };
+        match result {
+            Ok(_) => self.ksa_error.clear(),
+            Err(e) => self.ksa_error = e.to_string(),
+        }
+    }
+}
+
+impl CipherFrame for CastFrame {
+    fn ui(&mut self, ui: &mut Ui, _errors: &mut String) {
+        ui.hyperlink_to(
+            "see the code",
+            "https://github.com/SymmetricChaos/crypto-gui/tree/master/ciphers/src/digital/block_ciphers/cast",
+        );
+        ui.add_space(8.0);
+
+        ui.randomize_reset_cipher(self);
+        ui.add_space(16.0);
+
+        ui.horizontal(|ui| {
+            if ui
+                .selectable_value(&mut self.selector, CastSelect::Cast128, "CAST-128")
+                .clicked()
+                || ui
+                    .selectable_value(&mut self.selector, CastSelect::Cast256, "CAST-256")
+                    .clicked()
+            {
+                self.run_ksa();
+            }
+        });
+        ui.add_space(8.0);
+
+        match self.selector {
+            CastSelect::Cast128 => {
+                ui.byte_io_mode_cipher(
+                    &mut self.cipher128.input_format,
+                    &mut self.cipher128.output_format,
+                );
+                ui.add_space(16.0);
+                block_cipher_mode_and_padding(
+                    ui,
+                    &mut self.cipher128.mode,
+                    &mut self.cipher128.padding,
+                );
+                ui.add_space(8.0);
+                ui.label("CAST-128 is a Feistel cipher with a 64-bit block. The round function rotates between three types that combine four 8x32 S-boxes using addition, subtraction, and XOR in different orders. Keys of 80 bits or less use 12 rounds rather than 16.");
+            }
+            CastSelect::Cast256 => {
+                ui.byte_io_mode_cipher(
+                    &mut self.cipher256.input_format,
+                    &mut self.cipher256.output_format,
+                );
+                ui.add_space(16.0);
+                block_cipher_mode_and_padding(
+                    ui,
+                    &mut self.cipher256.mode,
+                    &mut self.cipher256.padding,
+                );
+                ui.add_space(8.0);
+                ui.label("CAST-256 extends the CAST-128 round functions to a 128-bit block arranged as a generalized Feistel network of four words. Six forward quad-rounds are followed by six reverse quad-rounds.");
+            }
+        }
+        ui.add_space(8.0);
+
+        ui.subheading("Key");
+        match self.selector {
+            CastSelect::Cast128 => ui.label("Key should be provided as a string of hexadecimal digits representing between 5 and 16 bytes."),
+            CastSelect::Cast256 => ui.label("Key should be provided as a string of hexadecimal digits representing 16, 20, 24, 28, or 32 bytes."),
+        };
+        if ui.control_string(&mut self.key).changed() {
+            self.key = self.key.chars().filter(|c| c.is_ascii_hexdigit()).collect();
+            self.run_ksa();
+        }
+        ui.error_text(&self.ksa_error);
+        ui.add_space(8.0);
+
+        match self.selector {
+            CastSelect::Cast128 => {
+                block_cipher_iv_64(ui, &mut self.cipher128.iv, self.cipher128.mode)
+            }
+            CastSelect::Cast256 => {
+                block_cipher_iv_128(ui, &mut self.cipher256.iv, self.cipher256.mode)
+            }
+        }
+
+        ui.add_space(16.0);
+    }
+
+    fn randomize(&mut self) {
+        let mut rng = thread_rng();
+        let mut key = [0_u8; 16];
+        rng.fill(&mut key);
+        self.key = ByteFormat::Hex.byte_slice_to_text(key);
+        self.run_ksa();
+
+        match self.selector {
+            CastSelect::Cast128 => {
+                if self.cipher128.mode.iv_needed() {
+                    self.cipher128.iv = rng.gen();
+                }
+            }
+            CastSelect::Cast256 => {
+                if self.cipher256.mode.iv_needed() {
+                    self.cipher256.iv = rng.gen();
+                }
+            }
+        }
+    }
+
+    fn reset(&mut self) {
+        *self = Self::default()
+    }
+
+    fn encrypt_string(&self, text: &str) -> Result<String, utils::errors::GeneralError> {
+        match self.selector {
+            CastSelect::Cast128 => self.cipher128.encrypt(text),
+            CastSelect::Cast256 => self.cipher256.encrypt(text),
+        }
+    }
+
+    fn decrypt_string(&self, text: &str) -> Result<String, utils::errors::GeneralError> {
+        match self.selector {
+            CastSelect::Cast128 => self.cipher128.decrypt(text),
+            CastSelect::Cast256 => self.cipher256.decrypt(text),
+        }
+    }
+}
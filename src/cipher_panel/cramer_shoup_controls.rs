@@ -0,0 +1,78 @@
+use super::CipherFrame;
+use crate::ui_elements::UiElements;
+use ciphers::digital::public_key_ciphers::cramer_shoup::CramerShoup;
+use egui::Ui;
+use hashers::hmac::HmacVariant;
+use rand::thread_rng;
+
+pub struct CramerShoupFrame {
+    cipher: CramerShoup,
+}
+
+impl Default for CramerShoupFrame {
+    fn default() -> Self {
+        let mut out = Self {
+            cipher: Default::default(),
+        };
+        out.randomize();
+        out
+    }
+}
+
+impl CipherFrame for CramerShoupFrame {
+    fn ui(&mut self, ui: &mut Ui, _errors: &mut String) {
+        ui.hyperlink_to(
+            "see the code",
+            "https://github.com/SymmetricChaos/crypto-gui/blob/master/ciphers/src/digital/public_key_ciphers/cramer_shoup.rs",
+        );
+        ui.add_space(8.0);
+
+        ui.randomize_reset_cipher(self);
+        ui.add_space(16.0);
+
+        ui.byte_io_mode_cipher(
+            &mut self.cipher.input_format,
+            &mut self.cipher.output_format,
+        );
+        ui.add_space(16.0);
+
+        ui.subheading("Group");
+        ui.label("The subgroup of prime order q = (p-1)/2 in the 2048-bit MODP group from RFC 3526, generated by g1 = 2. The second generator is a random power of the first.");
+        ui.label("g2");
+        ui.mono(self.cipher.g2.to_str_radix(16));
+        ui.add_space(8.0);
+
+        ui.subheading("Hash");
+        ui.horizontal(|ui| {
+            ui.selectable_value(&mut self.cipher.hash, HmacVariant::Sha256, "SHA-256");
+            ui.selectable_value(&mut self.cipher.hash, HmacVariant::Sha384, "SHA-384");
+            ui.selectable_value(&mut self.cipher.hash, HmacVariant::Sha512, "SHA-512");
+        });
+        ui.label("The hash of u1, u2 and e binds the check value v to the rest of the ciphertext.");
+        ui.add_space(16.0);
+
+        ui.horizontal(|ui| {
+            ui.subheading("Public Key");
+            if ui.button("🎲").on_hover_text("random key").clicked() {
+                self.randomize();
+            }
+        });
+        ui.label("c = g1^x1 g2^x2");
+        ui.mono(self.cipher.c.to_str_radix(16));
+        ui.label("d = g1^y1 g2^y2");
+        ui.mono(self.cipher.d.to_str_radix(16));
+        ui.label("h = g1^z");
+        ui.mono(self.cipher.h.to_str_radix(16));
+        ui.add_space(16.0);
+
+        ui.subheading("Ciphertext");
+        ui.label("The ciphertext is the four group elements u1 = g1^k, u2 = g2^k, e = h^k m and v = c^k d^(kα) where α is the hash. Decryption refuses any ciphertext where v does not match.");
+        ui.add_space(16.0);
+    }
+
+    fn randomize(&mut self) {
+        self.cipher.generate_key(&mut thread_rng());
+    }
+
+    crate::simple_cipher! {}
+}
@@ -1,38 +1,55 @@
 use super::CipherFrame;
 use crate::ui_elements::UiElements;
-use ciphers::digital::public_key_ciphers::diffie_hellman::DiffieHellman;
+use ciphers::digital::public_key_ciphers::{dh_groups::DhGroup, diffie_hellman::DiffieHellman};
 use eframe::egui::Ui;
-use rand::{thread_rng, Rng};
-use utils::primality::is_prime32;
+use num::BigUint;
+use rand::thread_rng;
+use utils::{byte_formatting::ByteFormat, primality::is_prime32};
 
 pub struct DiffieHellmanFrame {
     cipher: DiffieHellman,
+    modulus: u32,
+    generator: u32,
+    toy_keys: Vec<u32>,
     g_is_valid: bool,
     m_is_prime: bool,
+    salt: String,
+    info: String,
 }
 
 impl Default for DiffieHellmanFrame {
     fn default() -> Self {
         Self {
             cipher: Default::default(),
+            modulus: 23,
+            generator: 5,
+            toy_keys: vec![4, 3],
             g_is_valid: true,
             m_is_prime: true,
+            salt: String::new(),
+            info: String::new(),
         }
     }
 }
 
-impl DiffieHellmanFrame {}
+impl DiffieHellmanFrame {
+    fn set_toy_group(&mut self) {
+        self.cipher.set_toy_group(self.generator, self.modulus);
+        self.cipher.private_keys = self.toy_keys.iter().map(|k| BigUint::from(*k)).collect();
+    }
 
-impl CipherFrame for DiffieHellmanFrame {
-    fn ui(&mut self, ui: &mut Ui, _errors: &mut String) {
-        ui.hyperlink_to(
-            "see the code",
-            "https://github.com/SymmetricChaos/crypto-gui/blob/master/ciphers/src/digital/public_key_ciphers/diffie_hellman.rs",
-        );
+    fn number_to_text(&self, n: &BigUint) -> String {
+        match self.cipher.group {
+            Some(_) => n.to_str_radix(16),
+            None => n.to_string(),
+        }
+    }
 
+    fn toy_ui(&mut self, ui: &mut Ui) {
         ui.subheading("Modulus");
-        if ui.u32_drag_value_dec(&mut self.cipher.modulus).changed() {
-            self.m_is_prime = is_prime32(self.cipher.modulus)
+        if ui.u32_drag_value_dec(&mut self.modulus).changed() {
+            self.m_is_prime = is_prime32(self.modulus);
+            self.set_toy_group();
         }
         if !self.m_is_prime {
             ui.error_text(
@@ -44,8 +61,9 @@ impl CipherFrame for DiffieHellmanFrame {
 
         ui.add_space(8.0);
         ui.subheading("Base");
-        if ui.u32_drag_value_dec(&mut self.cipher.generator).changed() {
-            self.cipher.generator = self.cipher.generator.clamp(2, self.cipher.modulus - 1);
+        if ui.u32_drag_value_dec(&mut self.generator).changed() {
+            self.generator = self.generator.clamp(2, self.modulus - 1);
+            self.set_toy_group();
             self.g_is_valid = self.cipher.g_is_valid();
         }
         if !self.g_is_valid {
@@ -55,40 +73,134 @@ impl CipherFrame for DiffieHellmanFrame {
         } else {
             ui.error_text("");
         }
+    }
+
+    fn standard_group_ui(&mut self, ui: &mut Ui) {
+        ui.subheading("Modulus (hexadecimal)");
+        ui.label(format!(
+            "A {}-bit safe prime p. The base is 2, which generates the subgroup of prime order q = (p-1)/2.",
+            self.cipher.modulus.bits()
+        ));
+        ui.mono(self.cipher.modulus.to_str_radix(16));
+    }
+}
+
+impl CipherFrame for DiffieHellmanFrame {
+    fn ui(&mut self, ui: &mut Ui, errors: &mut String) {
+        ui.hyperlink_to(
+            "see the code",
+            "https://github.com/SymmetricChaos/crypto-gui/blob/master/ciphers/src/digital/public_key_ciphers/diffie_hellman.rs",
+        );
+
+        ui.subheading("Group");
+        if ui
+            .selectable_label(self.cipher.group.is_none(), "Small Numbers")
+            .clicked()
+        {
+            self.set_toy_group();
+        }
+        for group in DhGroup::ALL {
+            if ui
+                .selectable_label(self.cipher.group == Some(group), group.name())
+                .clicked()
+            {
+                self.cipher.set_group(group);
+                self.cipher.randomize_private_keys(&mut thread_rng());
+            }
+        }
+        ui.add_space(8.0);
+
+        match self.cipher.group {
+            Some(_) => self.standard_group_ui(ui),
+            None => self.toy_ui(ui),
+        }
 
         ui.add_space(8.0);
-        ui.subheading("Private Keys with Corresponding Public Keys");
+        ui.horizontal(|ui| {
+            ui.subheading("Private Keys with Corresponding Public Keys");
+            if ui.button("🎲").on_hover_text("random keys").clicked() {
+                self.randomize();
+            }
+        });
         ui.horizontal(|ui| {
             if ui.button("-").clicked() {
                 if self.cipher.private_keys.len() > 2 {
                     self.cipher.private_keys.pop();
+                    self.toy_keys.pop();
                 }
             };
             ui.label(format!("{}", self.cipher.private_keys.len()));
             if ui.button("+").clicked() {
                 if self.cipher.private_keys.len() < 10 {
-                    self.cipher.private_keys.push(2);
+                    self.toy_keys.push(2);
+                    let key = match self.cipher.group {
+                        Some(_) => self.cipher.generate_private_key(&mut thread_rng()),
+                        None => BigUint::from(2_u32),
+                    };
+                    self.cipher.private_keys.push(key);
                 }
             };
         });
         for (i, p) in self.cipher.public_keys().iter().enumerate() {
-            ui.horizontal(|ui| {
-                ui.u32_drag_value_dec(&mut self.cipher.private_keys[i]);
-                ui.label(p.to_string());
-            });
+            match self.cipher.group {
+                Some(_) => {
+                    ui.label("private");
+                    ui.mono(self.cipher.private_keys[i].to_str_radix(16));
+                    ui.label("public");
+                    ui.mono(p.to_str_radix(16));
+                    ui.add_space(4.0);
+                }
+                None => {
+                    ui.horizontal(|ui| {
+                        if ui.u32_drag_value_dec(&mut self.toy_keys[i]).changed() {
+                            self.cipher.private_keys[i] = BigUint::from(self.toy_keys[i]);
+                        }
+                        ui.label(p.to_string());
+                    });
+                }
+            }
         }
 
         ui.add_space(8.0);
         ui.subheading("Shared Secret Key");
-        ui.label(self.cipher.shared_key().to_string());
+        ui.mono(self.number_to_text(&self.cipher.shared_key()));
+
+        if self.cipher.group.is_some() && self.cipher.private_keys.len() == 2 {
+            ui.add_space(8.0);
+            ui.subheading("Derived Key (HKDF-SHA256)");
+            ui.label("The shared secret is not uniformly random so a key derivation function is used to turn it into a symmetric key.");
+            ui.label("Salt");
+            ui.control_string(&mut self.salt);
+            ui.label("Info");
+            ui.control_string(&mut self.info);
+            let peer = self.cipher.public_key(&self.cipher.private_keys[1]);
+            match self.cipher.derive_key(
+                &self.cipher.private_keys[0],
+                &peer,
+                self.salt.as_bytes(),
+                self.info.as_bytes(),
+                32,
+            ) {
+                Ok(key) => {
+                    ui.mono(ByteFormat::Hex.byte_slice_to_text(key));
+                }
+                Err(e) => *errors = e.to_string(),
+            }
+        }
 
         ui.add_space(8.0);
     }
 
     fn randomize(&mut self) {
         let mut rng = thread_rng();
-        for p in self.cipher.private_keys.iter_mut() {
-            *p = rng.gen_range(2..=self.cipher.modulus - 1)
+        self.cipher.randomize_private_keys(&mut rng);
+        if self.cipher.group.is_none() {
+            self.toy_keys = self
+                .cipher
+                .private_keys
+                .iter()
+                .map(|k| u32::try_from(k).unwrap_or(2))
+                .collect();
         }
     }
 
@@ -0,0 +1,168 @@
+use super::CipherFrame;
+use crate::ui_elements::UiElements;
+use ciphers::digital::public_key_ciphers::dsa::{Dsa, DsaParameters, DsaSeed, DSA_SIZES};
+use egui::Ui;
+use hashers::hmac::HmacVariant;
+use num::BigUint;
+use rand::thread_rng;
+use utils::byte_formatting::ByteFormat;
+
+pub struct DsaFrame {
+    cipher: Dsa,
+    sizes: (u64, u64),
+    seed: Option<DsaSeed>,
+    private_key: String,
+    verify_message: String,
+    verify_signature: String,
+    verified: Option<bool>,
+}
+
+impl Default for DsaFrame {
+    fn default() -> Self {
+        let mut out = Self {
+            cipher: Default::default(),
+            sizes: (2048, 256),
+            seed: None,
+            private_key: String::new(),
+            verify_message: String::new(),
+            verify_signature: String::new(),
+            verified: None,
+        };
+        out.randomize();
+        out
+    }
+}
+
+impl CipherFrame for DsaFrame {
+    fn ui(&mut self, ui: &mut Ui, errors: &mut String) {
+        ui.hyperlink_to(
+            "see the code",
+            "https://github.com/SymmetricChaos/crypto-gui/blob/master/ciphers/src/digital/public_key_ciphers/dsa.rs",
+        );
+        ui.add_space(8.0);
+
+        ui.randomize_reset_cipher(self);
+        ui.add_space(16.0);
+
+        ui.byte_io_mode_cipher(
+            &mut self.cipher.input_format,
+            &mut self.cipher.output_format,
+        );
+        ui.add_space(16.0);
+
+        ui.subheading("Domain Parameters");
+        ui.horizontal(|ui| {
+            for (l, n) in DSA_SIZES {
+                ui.selectable_value(&mut self.sizes, (l, n), format!("L = {l}, N = {n}"));
+            }
+        });
+        if ui
+            .button("Generate")
+            .on_hover_text("larger sizes may take several seconds")
+            .clicked()
+        {
+            errors.clear();
+            match DsaParameters::generate(
+                self.sizes.0,
+                self.sizes.1,
+                HmacVariant::Sha256,
+                &mut thread_rng(),
+            ) {
+                Ok((parameters, seed)) => {
+                    self.cipher.set_parameters(parameters);
+                    self.seed = Some(seed);
+                    self.randomize();
+                }
+                Err(e) => *errors = e.to_string(),
+            }
+        }
+        match &self.seed {
+            Some(seed) => {
+                ui.label(format!(
+                    "Generated with SHA-256 from this seed, p was found after {} attempts",
+                    seed.counter + 1
+                ));
+                ui.mono(ByteFormat::Hex.byte_slice_to_text(&seed.seed));
+            }
+            None => {
+                ui.label("Default parameters with L = 2048 and N = 256");
+            }
+        }
+        ui.label("p");
+        ui.mono(self.cipher.parameters.p.to_str_radix(16));
+        ui.label("q");
+        ui.mono(self.cipher.parameters.q.to_str_radix(16));
+        ui.label("g");
+        ui.mono(self.cipher.parameters.g.to_str_radix(16));
+        ui.add_space(16.0);
+
+        ui.subheading("Hash");
+        ui.horizontal(|ui| {
+            ui.selectable_value(&mut self.cipher.hash, HmacVariant::Sha256, "SHA-256");
+            ui.selectable_value(&mut self.cipher.hash, HmacVariant::Sha384, "SHA-384");
+            ui.selectable_value(&mut self.cipher.hash, HmacVariant::Sha512, "SHA-512");
+        });
+        ui.label(
+            "The hash is truncated to the size of q. Nonces are derived as described in RFC 6979.",
+        );
+        ui.add_space(16.0);
+
+        ui.horizontal(|ui| {
+            ui.subheading("Private Key (hexadecimal)");
+            if ui.button("🎲").on_hover_text("random key").clicked() {
+                self.randomize();
+            }
+        });
+        if ui.control_string(&mut self.private_key).changed() {
+            errors.clear();
+            self.private_key = self
+                .private_key
+                .chars()
+                .filter(|c| c.is_ascii_hexdigit())
+                .collect();
+            if let Err(e) = BigUint::parse_bytes(self.private_key.as_bytes(), 16)
+                .ok_or(utils::errors::GeneralError::key("invalid private key"))
+                .and_then(|x| self.cipher.set_private_key(&x))
+            {
+                *errors = e.to_string();
+            }
+        }
+        ui.add_space(8.0);
+
+        ui.subheading("Public Key (hexadecimal)");
+        ui.mono(self.cipher.public_key.to_str_radix(16));
+        ui.add_space(16.0);
+
+        ui.subheading("Verify a Signature");
+        ui.label("Message");
+        ui.control_string(&mut self.verify_message);
+        ui.label("Signature (hexadecimal)");
+        ui.control_string(&mut self.verify_signature);
+        if ui.button("Verify").clicked() {
+            self.verified = match (
+                self.cipher.input_format.text_to_bytes(&self.verify_message),
+                ByteFormat::Hex.text_to_bytes(&self.verify_signature),
+            ) {
+                (Ok(msg), Ok(sig)) => Some(self.cipher.verify_bytes(&msg, &sig)),
+                _ => Some(false),
+            }
+        }
+        match self.verified {
+            Some(true) => {
+                ui.label("valid signature");
+            }
+            Some(false) => {
+                ui.error_text("INVALID SIGNATURE");
+            }
+            None => (),
+        }
+        ui.add_space(16.0);
+    }
+
+    fn randomize(&mut self) {
+        self.cipher.generate_key(&mut thread_rng());
+        self.private_key = self.cipher.private_key.to_str_radix(16);
+    }
+
+    crate::simple_cipher! {}
+}
@@ -0,0 +1,101 @@
+use super::CipherFrame;
+use crate::ui_elements::UiElements;
+use ciphers::digital::stream_ciphers::e0::E0;
+use egui::Ui;
+use rand::{thread_rng, Rng};
+use utils::byte_formatting::ByteFormat;
+
+pub struct E0Frame {
+    input_format: ByteFormat,
+    output_format: ByteFormat,
+    cipher: E0,
+    key: u128,
+    address: u64,
+    clock: u32,
+}
+
+impl Default for E0Frame {
+    fn default() -> Self {
+        Self {
+            input_format: ByteFormat::Utf8,
+            output_format: ByteFormat::Hex,
+            cipher: E0::with_key([0; 16], [0; 6], 0),
+            key: 0,
+            address: 0,
+            clock: 0,
+        }
+    }
+}
+
+impl E0Frame {
+    fn set_cipher(&mut self) {
+        self.address &= 0xffffffffffff;
+        self.clock &= 0x03ffffff;
+        // Bluetooth numbers the bytes of each value from the least significant
+        let mut address = [0; 6];
+        address.copy_from_slice(&self.address.to_le_bytes()[..6]);
+        self.cipher = E0::with_key(self.key.to_le_bytes(), address, self.clock);
+    }
+}
+
+impl CipherFrame for E0Frame {
+    fn ui(&mut self, ui: &mut Ui, _errors: &mut String) {
+        ui.hyperlink_to(
+            "see the code",
+            "https://github.com/SymmetricChaos/crypto-gui/blob/master/ciphers/src/digital/stream_ciphers/e0.rs",
+        );
+        ui.add_space(8.0);
+
+        ui.randomize_reset_cipher(self);
+        ui.add_space(16.0);
+
+        ui.byte_io_mode_cipher(&mut self.input_format, &mut self.output_format);
+        ui.add_space(16.0);
+
+        ui.subheading("Key (Kc')");
+        ui.label("The 128-bit encryption key after its effective length has been reduced.");
+        if ui.u128_hex_edit(&mut self.key).lost_focus() {
+            self.set_cipher();
+        }
+        ui.add_space(8.0);
+
+        ui.subheading("Device Address (48 bits)");
+        if ui.u64_hex_edit(&mut self.address).lost_focus() {
+            self.set_cipher();
+        }
+        ui.add_space(8.0);
+
+        ui.subheading("Master Clock (26 bits)");
+        if ui.u32_hex_edit(&mut self.clock).lost_focus() {
+            self.set_cipher();
+        }
+        ui.add_space(8.0);
+
+        ui.label("The key, address, and clock are shifted into the four LFSRs. After 200 steps of the summation combiner the last 128 output bits are loaded back into the LFSRs and the keystream begins.");
+    }
+
+    fn randomize(&mut self) {
+        let mut rng = thread_rng();
+        self.key = rng.gen();
+        self.address = rng.gen();
+        self.clock = rng.gen();
+        self.set_cipher();
+    }
+
+    fn reset(&mut self) {
+        *self = Self::default()
+    }
+
+    fn encrypt_string(&self, text: &str) -> Result<String, utils::errors::GeneralError> {
+        let mut bytes = self
+            .input_format
+            .text_to_bytes(text)
+            .map_err(|e| utils::errors::GeneralError::input(e.to_string()))?;
+        self.cipher.encrypt_bytes(&mut bytes);
+        Ok(self.output_format.byte_slice_to_text(&bytes))
+    }
+
+    fn decrypt_string(&self, text: &str) -> Result<String, utils::errors::GeneralError> {
+        self.encrypt_string(text)
+    }
+}
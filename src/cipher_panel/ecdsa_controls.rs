@@ -0,0 +1,158 @@
+use super::CipherFrame;
+use crate::ui_elements::UiElements;
+use ciphers::digital::public_key_ciphers::ecdsa::Ecdsa;
+use egui::Ui;
+use hashers::hmac::HmacVariant;
+use num::BigUint;
+use rand::thread_rng;
+use utils::{byte_formatting::ByteFormat, elliptic_curves::NamedCurve};
+
+const CURVES: [NamedCurve; 6] = [
+    NamedCurve::P256,
+    NamedCurve::P224,
+    NamedCurve::Secp256k1,
+    NamedCurve::BrainpoolP256r1,
+    NamedCurve::Textbook17,
+    NamedCurve::Textbook97,
+];
+
+pub struct EcdsaFrame {
+    cipher: Ecdsa,
+    curve: NamedCurve,
+    private_key: String,
+    public_key: String,
+    verify_message: String,
+    verify_signature: String,
+    verified: Option<bool>,
+}
+
+impl Default for EcdsaFrame {
+    fn default() -> Self {
+        let mut out = Self {
+            cipher: Default::default(),
+            curve: NamedCurve::P256,
+            private_key: String::new(),
+            public_key: String::new(),
+            verify_message: String::new(),
+            verify_signature: String::new(),
+            verified: None,
+        };
+        out.randomize();
+        out
+    }
+}
+
+impl EcdsaFrame {
+    fn key_strings(&mut self) {
+        self.private_key = self.cipher.private_key.to_str_radix(16);
+        self.public_key = ByteFormat::Hex.byte_slice_to_text(self.cipher.public_key_bytes(true));
+    }
+}
+
+impl CipherFrame for EcdsaFrame {
+    fn ui(&mut self, ui: &mut Ui, errors: &mut String) {
+        ui.hyperlink_to(
+            "see the code",
+            "https://github.com/SymmetricChaos/crypto-gui/blob/master/ciphers/src/digital/public_key_ciphers/ecdsa.rs",
+        );
+        ui.add_space(8.0);
+
+        ui.randomize_reset_cipher(self);
+        ui.add_space(16.0);
+
+        ui.byte_io_mode_cipher(
+            &mut self.cipher.input_format,
+            &mut self.cipher.output_format,
+        );
+        ui.add_space(16.0);
+
+        ui.subheading("Curve");
+        for curve in CURVES {
+            if ui
+                .selectable_value(&mut self.curve, curve, curve.name())
+                .clicked()
+            {
+                self.cipher.set_curve(curve);
+                self.cipher.generate_key(&mut thread_rng());
+                self.key_strings();
+            }
+        }
+        ui.label(format!(
+            "Base point of order {} bits",
+            self.cipher.domain.order_bits()
+        ));
+        ui.add_space(16.0);
+
+        ui.subheading("Hash");
+        ui.horizontal(|ui| {
+            ui.selectable_value(&mut self.cipher.hash, HmacVariant::Sha256, "SHA-256");
+            ui.selectable_value(&mut self.cipher.hash, HmacVariant::Sha384, "SHA-384");
+            ui.selectable_value(&mut self.cipher.hash, HmacVariant::Sha512, "SHA-512");
+        });
+        ui.label("The hash is truncated to the size of the order of the base point. The same hash is used with HMAC to derive the nonce from the private key and the message, as described in RFC 6979, so signatures are deterministic and never reuse a nonce.");
+        ui.add_space(16.0);
+
+        ui.horizontal(|ui| {
+            ui.subheading("Private Key (hexadecimal)");
+            if ui.button("🎲").on_hover_text("random key").clicked() {
+                self.cipher.generate_key(&mut thread_rng());
+                self.key_strings();
+            }
+        });
+        if ui.control_string(&mut self.private_key).changed() {
+            errors.clear();
+            self.private_key = self
+                .private_key
+                .chars()
+                .filter(|c| c.is_ascii_hexdigit())
+                .collect();
+            match BigUint::parse_bytes(self.private_key.as_bytes(), 16)
+                .ok_or(utils::errors::GeneralError::key("invalid private key"))
+                .and_then(|d| self.cipher.set_private_key(&d))
+            {
+                Ok(_) => {
+                    self.public_key =
+                        ByteFormat::Hex.byte_slice_to_text(self.cipher.public_key_bytes(true))
+                }
+                Err(e) => *errors = e.to_string(),
+            }
+        }
+        ui.add_space(8.0);
+
+        ui.subheading("Public Key (SEC 1 compressed)");
+        ui.mono(&self.public_key);
+        ui.add_space(16.0);
+
+        ui.subheading("Verify a Signature");
+        ui.label("Message");
+        ui.control_string(&mut self.verify_message);
+        ui.label("Signature (hexadecimal)");
+        ui.control_string(&mut self.verify_signature);
+        if ui.button("Verify").clicked() {
+            self.verified = match (
+                self.cipher.input_format.text_to_bytes(&self.verify_message),
+                ByteFormat::Hex.text_to_bytes(&self.verify_signature),
+            ) {
+                (Ok(msg), Ok(sig)) => Some(self.cipher.verify_bytes(&msg, &sig)),
+                _ => Some(false),
+            }
+        }
+        match self.verified {
+            Some(true) => {
+                ui.label("valid signature");
+            }
+            Some(false) => {
+                ui.error_text("INVALID SIGNATURE");
+            }
+            None => (),
+        }
+        ui.add_space(16.0);
+    }
+
+    fn randomize(&mut self) {
+        self.cipher.generate_key(&mut thread_rng());
+        self.key_strings();
+    }
+
+    crate::simple_cipher! {}
+}
@@ -0,0 +1,132 @@
+use super::CipherFrame;
+use crate::ui_elements::UiElements;
+use ciphers::digital::public_key_ciphers::ed25519::{Ed25519, Ed25519Variant};
+use egui::Ui;
+use rand::thread_rng;
+use utils::byte_formatting::ByteFormat;
+
+pub struct Ed25519Frame {
+    cipher: Ed25519,
+    private_key: String,
+    context: String,
+    verify_message: String,
+    verify_signature: String,
+    verified: Option<bool>,
+}
+
+impl Default for Ed25519Frame {
+    fn default() -> Self {
+        let mut out = Self {
+            cipher: Default::default(),
+            private_key: String::new(),
+            context: String::new(),
+            verify_message: String::new(),
+            verify_signature: String::new(),
+            verified: None,
+        };
+        out.randomize();
+        out
+    }
+}
+
+impl CipherFrame for Ed25519Frame {
+    fn ui(&mut self, ui: &mut Ui, errors: &mut String) {
+        ui.hyperlink_to(
+            "see the code",
+            "https://github.com/SymmetricChaos/crypto-gui/blob/master/ciphers/src/digital/public_key_ciphers/ed25519.rs",
+        );
+        ui.add_space(8.0);
+
+        ui.randomize_reset_cipher(self);
+        ui.add_space(16.0);
+
+        ui.byte_io_mode_cipher(
+            &mut self.cipher.input_format,
+            &mut self.cipher.output_format,
+        );
+        ui.add_space(16.0);
+
+        ui.subheading("Variant");
+        ui.horizontal(|ui| {
+            ui.selectable_value(&mut self.cipher.variant, Ed25519Variant::Pure, "Ed25519");
+            ui.selectable_value(&mut self.cipher.variant, Ed25519Variant::Ctx, "Ed25519ctx");
+            ui.selectable_value(&mut self.cipher.variant, Ed25519Variant::Ph, "Ed25519ph");
+        });
+        match self.cipher.variant {
+            Ed25519Variant::Pure => {
+                ui.label("The message is signed directly and no context is used.");
+                self.cipher.context.clear();
+            }
+            _ => {
+                if self.cipher.variant == Ed25519Variant::Ph {
+                    ui.label("The SHA-512 hash of the message is signed.");
+                }
+                ui.label("Context (UTF-8, at most 255 bytes)");
+                // Switching back from Ed25519 restores the context
+                ui.control_string(&mut self.context);
+                self.cipher.context = self.context.as_bytes().to_vec();
+            }
+        }
+        ui.add_space(16.0);
+
+        ui.horizontal(|ui| {
+            ui.subheading("Private Key (hexadecimal)");
+            if ui.button("🎲").on_hover_text("random key").clicked() {
+                self.randomize();
+            }
+        });
+        if ui.control_string(&mut self.private_key).changed() {
+            errors.clear();
+            self.private_key = self
+                .private_key
+                .chars()
+                .filter(|c| c.is_ascii_hexdigit())
+                .collect();
+            match ByteFormat::Hex
+                .text_to_bytes(&self.private_key)
+                .ok()
+                .and_then(|bytes| <[u8; 32]>::try_from(bytes).ok())
+            {
+                Some(key) => self.cipher.set_private_key(&key),
+                None => *errors = String::from("private key must be exactly 32 bytes"),
+            }
+        }
+        ui.add_space(8.0);
+
+        ui.subheading("Public Key");
+        ui.mono(ByteFormat::Hex.byte_slice_to_text(self.cipher.public_key));
+        ui.add_space(16.0);
+
+        ui.subheading("Verify a Signature");
+        ui.label("Message");
+        ui.control_string(&mut self.verify_message);
+        ui.label("Signature (hexadecimal)");
+        ui.control_string(&mut self.verify_signature);
+        if ui.button("Verify").clicked() {
+            self.verified = match (
+                self.cipher.input_format.text_to_bytes(&self.verify_message),
+                ByteFormat::Hex.text_to_bytes(&self.verify_signature),
+            ) {
+                (Ok(msg), Ok(sig)) => Some(self.cipher.verify(&msg, &sig)),
+                _ => Some(false),
+            }
+        }
+        match self.verified {
+            Some(true) => {
+                ui.label("valid signature");
+            }
+            Some(false) => {
+                ui.error_text("INVALID SIGNATURE");
+            }
+            None => (),
+        }
+        ui.add_space(16.0);
+    }
+
+    fn randomize(&mut self) {
+        self.cipher.generate_key(&mut thread_rng());
+        self.private_key = ByteFormat::Hex.byte_slice_to_text(self.cipher.private_key);
+    }
+
+    crate::simple_cipher! {}
+}
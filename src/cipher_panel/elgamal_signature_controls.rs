@@ -0,0 +1,157 @@
+use super::CipherFrame;
+use crate::ui_elements::UiElements;
+use ciphers::digital::public_key_ciphers::{
+    dh_groups::DhGroup, elgamal_signature::ElGamalSignature,
+};
+use egui::{ComboBox, Ui};
+use hashers::hmac::HmacVariant;
+use num::BigUint;
+use rand::thread_rng;
+use utils::byte_formatting::ByteFormat;
+
+pub struct ElGamalSignatureFrame {
+    cipher: ElGamalSignature,
+    // None is the small group from the Handbook of Applied Cryptography
+    group: Option<DhGroup>,
+    private_key: String,
+    verify_message: String,
+    verify_signature: String,
+    verified: Option<bool>,
+}
+
+impl Default for ElGamalSignatureFrame {
+    fn default() -> Self {
+        let mut out = Self {
+            cipher: Default::default(),
+            group: Some(DhGroup::Modp2048),
+            private_key: String::new(),
+            verify_message: String::new(),
+            verify_signature: String::new(),
+            verified: None,
+        };
+        out.randomize();
+        out
+    }
+}
+
+impl ElGamalSignatureFrame {
+    fn set_group(&mut self) {
+        match self.group {
+            Some(group) => self.cipher.set_group(group.generator(), group.modulus()),
+            None => self
+                .cipher
+                .set_group(BigUint::from(2_u32), BigUint::from(2357_u32)),
+        }
+        self.randomize();
+    }
+}
+
+impl CipherFrame for ElGamalSignatureFrame {
+    fn ui(&mut self, ui: &mut Ui, errors: &mut String) {
+        ui.hyperlink_to(
+            "see the code",
+            "https://github.com/SymmetricChaos/crypto-gui/blob/master/ciphers/src/digital/public_key_ciphers/elgamal_signature.rs",
+        );
+        ui.add_space(8.0);
+
+        ui.randomize_reset_cipher(self);
+        ui.add_space(16.0);
+
+        ui.byte_io_mode_cipher(
+            &mut self.cipher.input_format,
+            &mut self.cipher.output_format,
+        );
+        ui.add_space(16.0);
+
+        ui.subheading("Group");
+        let previous = self.group;
+        ComboBox::from_id_salt("ElGamalSignatureGroup")
+            .selected_text(match self.group {
+                Some(group) => group.name(),
+                None => "p = 2357 (textbook example)",
+            })
+            .show_ui(ui, |ui| {
+                ui.selectable_value(&mut self.group, None, "p = 2357 (textbook example)");
+                for group in DhGroup::ALL {
+                    ui.selectable_value(&mut self.group, Some(group), group.name());
+                }
+            });
+        if self.group != previous {
+            errors.clear();
+            self.set_group();
+        }
+        ui.label("p");
+        ui.mono(self.cipher.modulus.to_str_radix(16));
+        ui.label("g");
+        ui.mono(self.cipher.generator.to_str_radix(16));
+        ui.add_space(16.0);
+
+        ui.subheading("Hash");
+        ui.horizontal(|ui| {
+            ui.selectable_value(&mut self.cipher.hash, HmacVariant::Sha256, "SHA-256");
+            ui.selectable_value(&mut self.cipher.hash, HmacVariant::Sha384, "SHA-384");
+            ui.selectable_value(&mut self.cipher.hash, HmacVariant::Sha512, "SHA-512");
+        });
+        ui.label("The hash is reduced modulo p-1. Each signature uses a fresh random nonce coprime to p-1, a nonce used twice reveals the private key.");
+        ui.add_space(16.0);
+
+        ui.horizontal(|ui| {
+            ui.subheading("Private Key (hexadecimal)");
+            if ui.button("🎲").on_hover_text("random key").clicked() {
+                self.randomize();
+            }
+        });
+        if ui.control_string(&mut self.private_key).changed() {
+            errors.clear();
+            self.private_key = self
+                .private_key
+                .chars()
+                .filter(|c| c.is_ascii_hexdigit())
+                .collect();
+            if let Err(e) = BigUint::parse_bytes(self.private_key.as_bytes(), 16)
+                .ok_or(utils::errors::GeneralError::key("invalid private key"))
+                .and_then(|x| self.cipher.set_private_key(&x))
+            {
+                *errors = e.to_string();
+            }
+        }
+        ui.add_space(8.0);
+
+        ui.subheading("Public Key (hexadecimal)");
+        ui.mono(self.cipher.public_key.to_str_radix(16));
+        ui.add_space(16.0);
+
+        ui.subheading("Verify a Signature");
+        ui.label("Message");
+        ui.control_string(&mut self.verify_message);
+        ui.label("Signature (hexadecimal)");
+        ui.control_string(&mut self.verify_signature);
+        if ui.button("Verify").clicked() {
+            self.verified = match (
+                self.cipher.input_format.text_to_bytes(&self.verify_message),
+                ByteFormat::Hex.text_to_bytes(&self.verify_signature),
+            ) {
+                (Ok(msg), Ok(sig)) => Some(self.cipher.verify_bytes(&msg, &sig)),
+                _ => Some(false),
+            }
+        }
+        match self.verified {
+            Some(true) => {
+                ui.label("valid signature");
+            }
+            Some(false) => {
+                ui.error_text("INVALID SIGNATURE");
+            }
+            None => (),
+        }
+        ui.add_space(16.0);
+    }
+
+    fn randomize(&mut self) {
+        self.cipher.generate_key(&mut thread_rng());
+        self.private_key = self.cipher.private_key.to_str_radix(16);
+        self.verified = None;
+    }
+
+    crate::simple_cipher! {}
+}
@@ -0,0 +1,72 @@
+use super::CipherFrame;
+use crate::ui_elements::UiElements;
+use ciphers::digital::stream_ciphers::fish::{FishCipher, FishRng};
+use egui::Ui;
+use rand::{thread_rng, Rng};
+use std::collections::VecDeque;
+
+// Eight words to a line
+fn state_text(words: &VecDeque<u32>) -> String {
+    let words: Vec<String> = words.iter().map(|w| format!("{w:08x}")).collect();
+    words
+        .chunks(8)
+        .map(|line| line.join(" "))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+pub struct FishFrame {
+    cipher: FishCipher,
+}
+
+impl Default for FishFrame {
+    fn default() -> Self {
+        let mut out = Self {
+            cipher: FishCipher::default(),
+        };
+        out.randomize();
+        out
+    }
+}
+
+impl CipherFrame for FishFrame {
+    fn ui(&mut self, ui: &mut Ui, _errors: &mut String) {
+        ui.hyperlink_to(
+            "see the code",
+            "https://github.com/SymmetricChaos/crypto-gui/blob/master/ciphers/src/digital/stream_ciphers/fish.rs",
+        );
+        ui.add_space(8.0);
+
+        ui.randomize_reset_cipher(self);
+        ui.add_space(16.0);
+
+        ui.byte_io_mode_cipher(
+            &mut self.cipher.input_format,
+            &mut self.cipher.output_format,
+        );
+        ui.add_space(16.0);
+
+        ui.label("FISH combines two lagged Fibonacci generators, A[i] = A[i-55] + A[i-24] and B[i] = B[i-52] + B[i-19]. A pair of words is kept only when the word from B is odd, then two pairs are mixed into 64 bits of keystream. The original description does not give a key schedule, the key is the initial contents of both generators.");
+        ui.add_space(8.0);
+
+        ui.subheading("Key");
+        ui.collapsing("Generator A", |ui| {
+            ui.mono(state_text(self.cipher.rng.state_a()));
+        });
+        ui.collapsing("Generator B", |ui| {
+            ui.mono(state_text(self.cipher.rng.state_b()));
+        });
+    }
+
+    fn randomize(&mut self) {
+        let mut rng = thread_rng();
+        let mut a = [0; 55];
+        let mut b = [0; 52];
+        rng.fill(&mut a[..]);
+        rng.fill(&mut b[..]);
+        b[0] |= 1;
+        self.cipher.rng = FishRng::with_state(a, b).expect("b[0] is odd");
+    }
+
+    crate::simple_cipher! {}
+}
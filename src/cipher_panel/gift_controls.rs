@@ -0,0 +1,78 @@
+use super::CipherFrame;
+use crate::ui_elements::{block_cipher_iv_64, block_cipher_mode_and_padding, UiElements};
+use ciphers::digital::block_ciphers::gift::Gift64;
+use egui::Ui;
+use rand::{thread_rng, Rng};
+use utils::byte_formatting::u32s_to_bytes_be;
+
+#[derive(Default)]
+pub struct GiftFrame {
+    cipher: Gift64,
+    key: [u32; 4],
+}
+
+impl GiftFrame {
+    fn set_key(&mut self) {
+        let mut bytes = [0; 16];
+        u32s_to_bytes_be(&mut bytes, self.key);
+        self.cipher.ksa(bytes);
+    }
+}
+
+impl CipherFrame for GiftFrame {
+    fn ui(&mut self, ui: &mut Ui, _errors: &mut String) {
+        ui.hyperlink_to(
+            "see the code",
+            "https://github.com/SymmetricChaos/crypto-gui/blob/master/ciphers/src/digital/block_ciphers/gift.rs",
+        );
+        ui.add_space(8.0);
+
+        ui.randomize_reset_cipher(self);
+        ui.add_space(16.0);
+
+        ui.byte_io_mode_cipher(
+            &mut self.cipher.input_format,
+            &mut self.cipher.output_format,
+        );
+
+        ui.add_space(16.0);
+
+        block_cipher_mode_and_padding(ui, &mut self.cipher.mode, &mut self.cipher.padding);
+        ui.add_space(8.0);
+
+        ui.label("GIFT-64 has a 64-bit block and uses 28 rounds. Each round key is taken from the lowest 32 bits of the key state, which is then rotated and mixed.");
+        ui.add_space(8.0);
+
+        ui.horizontal(|ui| {
+            ui.subheading("Key (128 bits)");
+            if ui.random_bytes_button(&mut self.key).clicked() {
+                self.set_key();
+            }
+        });
+        ui.horizontal(|ui| {
+            for i in 0..4 {
+                if ui.u32_hex_edit(&mut self.key[i]).changed() {
+                    self.set_key();
+                }
+            }
+        });
+
+        ui.add_space(8.0);
+
+        block_cipher_iv_64(ui, &mut self.cipher.iv, self.cipher.mode);
+
+        ui.add_space(16.0);
+    }
+
+    fn randomize(&mut self) {
+        let mut rng = thread_rng();
+        rng.fill(&mut self.key);
+        self.set_key();
+
+        if self.cipher.mode.iv_needed() {
+            self.cipher.iv = rng.gen();
+        }
+    }
+
+    crate::simple_cipher! {}
+}
@@ -0,0 +1,79 @@
+use super::CipherFrame;
+use crate::ui_elements::UiElements;
+use ciphers::digital::public_key_ciphers::goldwasser_micali::GoldwasserMicali;
+use egui::Ui;
+use rand::thread_rng;
+
+pub struct GoldwasserMicaliFrame {
+    cipher: GoldwasserMicali,
+    key_bits: u64,
+}
+
+impl Default for GoldwasserMicaliFrame {
+    fn default() -> Self {
+        let mut out = Self {
+            cipher: Default::default(),
+            key_bits: 256,
+        };
+        out.randomize();
+        out
+    }
+}
+
+impl CipherFrame for GoldwasserMicaliFrame {
+    fn ui(&mut self, ui: &mut Ui, errors: &mut String) {
+        ui.hyperlink_to(
+            "see the code",
+            "https://github.com/SymmetricChaos/crypto-gui/blob/master/ciphers/src/digital/public_key_ciphers/goldwasser_micali.rs",
+        );
+        ui.add_space(8.0);
+
+        ui.randomize_reset_cipher(self);
+        ui.add_space(16.0);
+
+        ui.byte_io_mode_cipher(
+            &mut self.cipher.input_format,
+            &mut self.cipher.output_format,
+        );
+        ui.add_space(16.0);
+
+        ui.subheading("Generate Key");
+        ui.horizontal(|ui| {
+            for bits in [128, 256, 512, 1024] {
+                ui.selectable_value(&mut self.key_bits, bits, format!("{bits}-bit"));
+            }
+            if ui.button("Generate").clicked() {
+                errors.clear();
+                self.randomize();
+            }
+        });
+        ui.label(format!(
+            "Every bit of the message is encrypted as a separate {}-byte number, so each byte of plaintext becomes {} bytes of ciphertext.",
+            self.cipher.block_len(),
+            8 * self.cipher.block_len()
+        ));
+        ui.add_space(16.0);
+
+        ui.subheading("Public Key");
+        ui.label("n");
+        ui.mono(self.cipher.n.to_str_radix(16));
+        ui.label("x, a non-square modulo both p and q");
+        ui.mono(self.cipher.x.to_str_radix(16));
+        ui.add_space(8.0);
+
+        ui.subheading("Private Key");
+        ui.label("p");
+        ui.mono(self.cipher.p.to_str_radix(16));
+        ui.label("q");
+        ui.mono(self.cipher.q.to_str_radix(16));
+        ui.add_space(16.0);
+    }
+
+    fn randomize(&mut self) {
+        self.cipher
+            .generate_key(self.key_bits, &mut thread_rng())
+            .expect("key sizes offered are always valid");
+    }
+
+    crate::simple_cipher! {}
+}
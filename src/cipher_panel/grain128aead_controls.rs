@@ -0,0 +1,105 @@
+use super::CipherFrame;
+use crate::ui_elements::UiElements;
+use ciphers::digital::stream_ciphers::grain::Grain128Aead;
+use egui::Ui;
+use rand::{thread_rng, Rng};
+use strum::IntoEnumIterator;
+use utils::byte_formatting::ByteFormat;
+
+const MASK_96: u128 = (1 << 96) - 1;
+
+pub struct Grain128AeadFrame {
+    cipher: Grain128Aead,
+    key: u128,
+    nonce: u128,
+    ad: String,
+    ad_mode: ByteFormat,
+}
+
+impl Default for Grain128AeadFrame {
+    fn default() -> Self {
+        Self {
+            cipher: Default::default(),
+            key: 0,
+            nonce: 0,
+            ad: Default::default(),
+            ad_mode: ByteFormat::Hex,
+        }
+    }
+}
+
+impl Grain128AeadFrame {
+    fn set_cipher(&mut self) {
+        self.nonce &= MASK_96;
+        self.cipher.key = self.key.to_be_bytes();
+        self.cipher.nonce = self.nonce.to_be_bytes()[4..].try_into().unwrap();
+    }
+}
+
+impl CipherFrame for Grain128AeadFrame {
+    fn ui(&mut self, ui: &mut Ui, errors: &mut String) {
+        ui.hyperlink_to(
+            "see the code",
+            "https://github.com/SymmetricChaos/crypto-gui/blob/master/ciphers/src/digital/stream_ciphers/grain.rs",
+        );
+        ui.add_space(8.0);
+
+        ui.randomize_reset_cipher(self);
+        ui.add_space(16.0);
+
+        ui.byte_io_mode_cipher(
+            &mut self.cipher.input_format,
+            &mut self.cipher.output_format,
+        );
+        ui.add_space(16.0);
+
+        ui.subheading("Key (128 bits)");
+        if ui.u128_hex_edit(&mut self.key).lost_focus() {
+            self.set_cipher();
+        }
+        ui.add_space(8.0);
+
+        ui.subheading("Nonce (96 bits)");
+        if ui.u128_hex_edit(&mut self.nonce).lost_focus() {
+            self.set_cipher();
+        }
+        ui.add_space(8.0);
+
+        ui.subheading("Associated Data");
+        ui.label("Arbitrary data can be associated with the message. This is usually data that cannot be encrypted such as routing information. The tag authenticates this data as well as the message.");
+        ui.horizontal(|ui| {
+            for variant in ByteFormat::iter() {
+                if ui
+                    .selectable_value(&mut self.ad_mode, variant, variant.to_string())
+                    .clicked()
+                {
+                    match self.ad_mode.text_to_bytes(&self.ad) {
+                        Ok(v) => self.cipher.associated_data = v,
+                        Err(_) => errors.push_str("Error formatting associated data as bytes"),
+                    }
+                }
+            }
+        });
+        if ui.control_string(&mut self.ad).lost_focus() {
+            match self.ad_mode.text_to_bytes(&self.ad) {
+                Ok(v) => self.cipher.associated_data = v,
+                Err(_) => {
+                    errors.push_str("Error formatting associated data as bytes");
+                    self.cipher.associated_data.clear();
+                }
+            }
+        }
+        ui.add_space(8.0);
+
+        ui.label("Grain-128AEAD splits the output of its pre-output generator in two. Even bits are used as keystream while odd bits are shifted into a 64-bit register. For every 1 bit of the message the register is added into an accumulator, which becomes the tag once the associated data and the message have been processed. The 8 byte tag is appended to the ciphertext.");
+    }
+
+    fn randomize(&mut self) {
+        let mut rng = thread_rng();
+        self.key = rng.gen();
+        self.nonce = rng.gen();
+        self.set_cipher();
+    }
+
+    crate::simple_cipher! {}
+}
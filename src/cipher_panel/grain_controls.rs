@@ -0,0 +1,66 @@
+use super::CipherFrame;
+use crate::ui_elements::UiElements;
+use ciphers::digital::stream_ciphers::grain::Grain;
+use egui::Ui;
+use rand::{thread_rng, Rng};
+
+const MASK_80: u128 = (1 << 80) - 1;
+
+#[derive(Default)]
+pub struct GrainFrame {
+    cipher: Grain,
+    key: u128,
+    iv: u64,
+}
+
+impl GrainFrame {
+    fn set_cipher(&mut self) {
+        self.key &= MASK_80;
+        self.cipher.ksa(
+            self.key.to_be_bytes()[6..].try_into().unwrap(),
+            self.iv.to_be_bytes(),
+        );
+    }
+}
+
+impl CipherFrame for GrainFrame {
+    fn ui(&mut self, ui: &mut Ui, _errors: &mut String) {
+        ui.hyperlink_to(
+            "see the code",
+            "https://github.com/SymmetricChaos/crypto-gui/blob/master/ciphers/src/digital/stream_ciphers/grain.rs",
+        );
+        ui.add_space(8.0);
+
+        ui.randomize_reset_cipher(self);
+        ui.add_space(16.0);
+
+        ui.byte_io_mode_cipher(
+            &mut self.cipher.input_format,
+            &mut self.cipher.output_format,
+        );
+        ui.add_space(16.0);
+
+        ui.subheading("Key (80 bits)");
+        if ui.u128_hex_edit(&mut self.key).lost_focus() {
+            self.set_cipher();
+        }
+        ui.add_space(8.0);
+
+        ui.subheading("IV (64 bits)");
+        if ui.u64_hex_edit(&mut self.iv).lost_focus() {
+            self.set_cipher();
+        }
+        ui.add_space(8.0);
+
+        ui.label("Grain combines an 80-bit LFSR with an 80-bit NFSR whose feedback includes the output of the LFSR. Each output bit is a nonlinear function of five bits from the two registers added to seven bits of the NFSR. During the 160 steps of initialization the output is fed back into both registers.");
+    }
+
+    fn randomize(&mut self) {
+        let mut rng = thread_rng();
+        self.key = rng.gen();
+        self.iv = rng.gen();
+        self.set_cipher();
+    }
+
+    crate::simple_cipher! {}
+}
@@ -0,0 +1,111 @@
+use super::CipherFrame;
+use crate::ui_elements::UiElements;
+use ciphers::digital::block_ciphers::key_wrap::{KeyWrap, KeyWrapMode};
+use egui::Ui;
+use rand::{thread_rng, Rng};
+use utils::byte_formatting::ByteFormat;
+
+pub struct KeyWrapFrame {
+    cipher: KeyWrap,
+    kek: String,
+    kek_error: String,
+}
+
+impl Default for KeyWrapFrame {
+    fn default() -> Self {
+        Self {
+            cipher: Default::default(),
+            kek: String::from("00000000000000000000000000000000"),
+            kek_error: String::new(),
+        }
+    }
+}
+
+impl KeyWrapFrame {
+    fn set_kek(&mut self) {
+        match ByteFormat::Hex.text_to_bytes(&self.kek) {
+            Ok(bytes) => {
+                let valid = match self.cipher.mode {
+                    KeyWrapMode::Kw | KeyWrapMode::Kwp => [16, 24, 32].contains(&bytes.len()),
+                    KeyWrapMode::Tkw => bytes.len() == 24,
+                };
+                if valid {
+                    self.kek_error.clear();
+                } else {
+                    self.kek_error = String::from("invalid KEK length for this mode");
+                }
+                self.cipher.kek = bytes;
+            }
+            Err(_) => self.kek_error = String::from("KEK must be given as hexadecimal bytes"),
+        }
+    }
+}
+
+impl CipherFrame for KeyWrapFrame {
+    fn ui(&mut self, ui: &mut Ui, _errors: &mut String) {
+        ui.hyperlink_to(
+            "see the code",
+            "https://github.com/SymmetricChaos/crypto-gui/blob/master/ciphers/src/digital/block_ciphers/key_wrap.rs",
+        );
+        ui.add_space(8.0);
+
+        ui.randomize_reset_cipher(self);
+        ui.add_space(16.0);
+
+        ui.byte_io_mode_cipher(
+            &mut self.cipher.input_format,
+            &mut self.cipher.output_format,
+        );
+        ui.add_space(16.0);
+
+        ui.subheading("Mode");
+        ui.horizontal(|ui| {
+            for mode in [KeyWrapMode::Kw, KeyWrapMode::Kwp, KeyWrapMode::Tkw] {
+                if ui
+                    .selectable_value(&mut self.cipher.mode, mode, mode.to_string())
+                    .clicked()
+                {
+                    self.set_kek();
+                }
+            }
+        });
+        match self.cipher.mode {
+            KeyWrapMode::Kw => ui.label("AES Key Wrap (RFC 3394) wraps keys that are a multiple of 64 bits and at least 128 bits long. Unwrapping checks that the fixed initial value A6A6A6A6A6A6A6A6 is recovered."),
+            KeyWrapMode::Kwp => ui.label("AES Key Wrap with Padding (RFC 5649) wraps keys of any length by storing the length of the key in the initial value and padding it with zeroes. Unwrapping checks the initial value, the length, and the padding."),
+            KeyWrapMode::Tkw => ui.label("TDEA Key Wrap (NIST SP 800-38F) is the same construction as AES Key Wrap but using Triple DES and 32-bit semiblocks. Keys must be a multiple of 32 bits and at least 64 bits long."),
+        };
+        ui.add_space(8.0);
+
+        ui.subheading("Key Encryption Key (KEK)");
+        match self.cipher.mode {
+            KeyWrapMode::Kw | KeyWrapMode::Kwp => {
+                ui.label("Provide 16, 24, or 32 bytes as hexadecimal to select AES-128, AES-192, or AES-256.")
+            }
+            KeyWrapMode::Tkw => ui.label("Provide 24 bytes as hexadecimal, used as the three Triple DES keys."),
+        };
+        if ui.control_string(&mut self.kek).changed() {
+            self.kek = self
+                .kek
+                .chars()
+                .filter(|c| c.is_ascii_hexdigit())
+                .take(64)
+                .collect();
+            self.set_kek();
+        }
+        ui.error_text(&self.kek_error);
+
+        ui.add_space(16.0);
+    }
+
+    fn randomize(&mut self) {
+        let mut rng = thread_rng();
+        let n = match self.cipher.mode {
+            KeyWrapMode::Kw | KeyWrapMode::Kwp => 16,
+            KeyWrapMode::Tkw => 24,
+        };
+        self.kek = (0..n).map(|_| format!("{:02x}", rng.gen::<u8>())).collect();
+        self.set_kek();
+    }
+
+    crate::simple_cipher! {}
+}
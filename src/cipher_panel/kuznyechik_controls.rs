@@ -0,0 +1,84 @@
+use super::CipherFrame;
+use crate::ui_elements::{block_cipher_iv_128, block_cipher_mode_and_padding, UiElements};
+use ciphers::digital::block_ciphers::kuznyechik::Kuznyechik;
+use egui::Ui;
+use rand::{thread_rng, Rng};
+use utils::byte_formatting::u64s_to_bytes_be;
+
+#[derive(Default)]
+pub struct KuznyechikFrame {
+    cipher: Kuznyechik,
+    key: [u64; 4],
+}
+
+impl KuznyechikFrame {
+    fn set_key(&mut self) {
+        let mut bytes = [0; 32];
+        u64s_to_bytes_be(&mut bytes, self.key);
+        self.cipher.ksa(bytes);
+    }
+}
+
+impl CipherFrame for KuznyechikFrame {
+    fn ui(&mut self, ui: &mut Ui, _errors: &mut String) {
+        ui.hyperlink_to(
+            "see the code",
+            "https://github.com/SymmetricChaos/crypto-gui/blob/master/ciphers/src/digital/block_ciphers/kuznyechik.rs",
+        );
+        ui.add_space(8.0);
+
+        ui.randomize_reset_cipher(self);
+        ui.add_space(16.0);
+
+        ui.byte_io_mode_cipher(
+            &mut self.cipher.input_format,
+            &mut self.cipher.output_format,
+        );
+
+        ui.add_space(16.0);
+
+        block_cipher_mode_and_padding(ui, &mut self.cipher.mode, &mut self.cipher.padding);
+        ui.add_space(8.0);
+
+        ui.label("Kuznyechik is a substitution-permutation network with nine full rounds. Each round adds a round key, applies an 8-bit S-box to every byte, and then mixes the block with a linear transformation built from an LFSR over GF(2^8).");
+        ui.add_space(8.0);
+
+        ui.horizontal(|ui| {
+            ui.subheading("Key (256 bits)");
+            if ui.random_bytes_button(&mut self.key).clicked() {
+                self.set_key();
+            }
+        });
+        for i in 0..4 {
+            if ui.u64_hex_edit(&mut self.key[i]).changed() {
+                self.set_key();
+            }
+        }
+
+        ui.add_space(8.0);
+
+        ui.collapsing("Round Keys", |ui| {
+            for key in self.cipher.round_keys.iter() {
+                ui.mono(key.iter().map(|b| format!("{:02x}", b)).collect::<String>());
+            }
+        });
+
+        ui.add_space(8.0);
+
+        block_cipher_iv_128(ui, &mut self.cipher.iv, self.cipher.mode);
+
+        ui.add_space(16.0);
+    }
+
+    fn randomize(&mut self) {
+        let mut rng = thread_rng();
+        rng.fill(&mut self.key);
+        self.set_key();
+
+        if self.cipher.mode.iv_needed() {
+            self.cipher.iv = rng.gen();
+        }
+    }
+
+    crate::simple_cipher! {}
+}
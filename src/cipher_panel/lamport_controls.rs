@@ -0,0 +1,109 @@
+use super::CipherFrame;
+use crate::ui_elements::UiElements;
+use ciphers::digital::public_key_ciphers::lamport::Lamport;
+use egui::Ui;
+use hashers::sha::Sha256;
+use rand::thread_rng;
+use utils::byte_formatting::ByteFormat;
+
+pub struct LamportFrame {
+    cipher: Lamport<Sha256>,
+    verify_message: String,
+    verify_signature: String,
+    verified: Option<bool>,
+}
+
+impl Default for LamportFrame {
+    fn default() -> Self {
+        let mut out = Self {
+            cipher: Default::default(),
+            verify_message: String::new(),
+            verify_signature: String::new(),
+            verified: None,
+        };
+        out.randomize();
+        out
+    }
+}
+
+impl CipherFrame for LamportFrame {
+    fn ui(&mut self, ui: &mut Ui, errors: &mut String) {
+        ui.hyperlink_to(
+            "see the code",
+            "https://github.com/SymmetricChaos/crypto-gui/blob/master/ciphers/src/digital/public_key_ciphers/lamport.rs",
+        );
+        ui.add_space(8.0);
+
+        ui.randomize_reset_cipher(self);
+        ui.add_space(16.0);
+
+        ui.byte_io_mode_cipher(
+            &mut self.cipher.input_format,
+            &mut self.cipher.output_format,
+        );
+        ui.add_space(16.0);
+
+        ui.horizontal(|ui| {
+            ui.subheading("Key");
+            if ui.button("New Key").clicked() {
+                errors.clear();
+                self.randomize();
+            }
+        });
+        ui.label("The message is hashed with SHA-256 and each of the 256 bits of the digest selects one of a pair of secret values. The public key is the hash of all 512 secret values. Only the first few pairs are shown.");
+        if self.cipher.is_used() {
+            ui.error_text("This key has already signed a message and cannot be used again.");
+        }
+        ui.collapsing("Private Key", |ui| {
+            for [a, b] in self.cipher.private_key.iter().take(8) {
+                ui.mono(format!(
+                    "{}  {}",
+                    ByteFormat::Hex.byte_slice_to_text(a),
+                    ByteFormat::Hex.byte_slice_to_text(b)
+                ));
+            }
+        });
+        ui.collapsing("Public Key", |ui| {
+            for [a, b] in self.cipher.public_key.iter().take(8) {
+                ui.mono(format!(
+                    "{}  {}",
+                    ByteFormat::Hex.byte_slice_to_text(a),
+                    ByteFormat::Hex.byte_slice_to_text(b)
+                ));
+            }
+        });
+        ui.add_space(16.0);
+
+        ui.subheading("Verify a Signature");
+        ui.label("Message");
+        ui.control_string(&mut self.verify_message);
+        ui.label("Signature (hexadecimal)");
+        ui.control_string(&mut self.verify_signature);
+        if ui.button("Verify").clicked() {
+            self.verified = match (
+                self.cipher.input_format.text_to_bytes(&self.verify_message),
+                ByteFormat::Hex.text_to_bytes(&self.verify_signature),
+            ) {
+                (Ok(msg), Ok(sig)) => Some(self.cipher.verify(&msg, &sig)),
+                _ => Some(false),
+            }
+        }
+        match self.verified {
+            Some(true) => {
+                ui.label("valid signature");
+            }
+            Some(false) => {
+                ui.error_text("INVALID SIGNATURE");
+            }
+            None => (),
+        }
+        ui.add_space(16.0);
+    }
+
+    fn randomize(&mut self) {
+        self.cipher.generate_key(&mut thread_rng());
+        self.verified = None;
+    }
+
+    crate::simple_cipher! {}
+}
@@ -0,0 +1,147 @@
+use super::CipherFrame;
+use crate::ui_elements::UiElements;
+use ciphers::digital::public_key_ciphers::lms::{Lms, LmsHash};
+use egui::Ui;
+use rand::thread_rng;
+use utils::byte_formatting::ByteFormat;
+
+pub struct LmsFrame {
+    cipher: Lms,
+    verify_message: String,
+    verify_signature: String,
+    verified: Option<bool>,
+}
+
+impl Default for LmsFrame {
+    fn default() -> Self {
+        let mut out = Self {
+            cipher: Default::default(),
+            verify_message: String::new(),
+            verify_signature: String::new(),
+            verified: None,
+        };
+        out.randomize();
+        out
+    }
+}
+
+impl CipherFrame for LmsFrame {
+    fn ui(&mut self, ui: &mut Ui, errors: &mut String) {
+        ui.hyperlink_to(
+            "see the code",
+            "https://github.com/SymmetricChaos/crypto-gui/blob/master/ciphers/src/digital/public_key_ciphers/lms.rs",
+        );
+        ui.add_space(8.0);
+
+        ui.randomize_reset_cipher(self);
+        ui.add_space(16.0);
+
+        ui.byte_io_mode_cipher(
+            &mut self.cipher.input_format,
+            &mut self.cipher.output_format,
+        );
+        ui.add_space(16.0);
+
+        ui.subheading("Hash Function");
+        ui.horizontal(|ui| {
+            for hash in LmsHash::ALL {
+                if ui
+                    .selectable_label(
+                        self.cipher.hash == hash,
+                        format!("{} (n = {})", hash.name(), hash.n()),
+                    )
+                    .clicked()
+                {
+                    errors.clear();
+                    match self.cipher.set_parameters(hash, 5, self.cipher.w) {
+                        Ok(_) => self.randomize(),
+                        Err(e) => *errors = e.to_string(),
+                    }
+                }
+            }
+        });
+        ui.label("RFC 8554 defines only SHA-256 with 32 byte outputs. NIST SP 800-208 adds SHA-256 truncated to 24 bytes and SHAKE256 with 24 or 32 bytes of output, each with its own type codes.");
+        ui.add_space(8.0);
+
+        ui.subheading("Winternitz Parameter");
+        ui.horizontal(|ui| {
+            for w in [1, 2, 4, 8] {
+                if ui
+                    .selectable_label(self.cipher.w == w, format!("w = {w}"))
+                    .clicked()
+                {
+                    errors.clear();
+                    match self.cipher.set_parameters(self.cipher.hash, 5, w) {
+                        Ok(_) => self.randomize(),
+                        Err(e) => *errors = e.to_string(),
+                    }
+                }
+            }
+        });
+        let (lms_name, lmots_name) = self.cipher.parameter_names();
+        ui.label(format!(
+            "{lms_name} with {lmots_name}. Each one-time signature uses {} hash chains of length {}, larger w makes signatures shorter and slower. Only trees of height 5 are offered here since larger trees take a long time to build.",
+            self.cipher.chains(),
+            (1 << self.cipher.w) - 1
+        ));
+        ui.add_space(16.0);
+
+        ui.horizontal(|ui| {
+            ui.subheading("Key");
+            if ui.button("New Key").clicked() {
+                errors.clear();
+                self.randomize();
+            }
+        });
+        ui.label("Secret Seed");
+        ui.mono(ByteFormat::Hex.byte_slice_to_text(&self.cipher.seed));
+        ui.label("Identifier");
+        ui.mono(ByteFormat::Hex.byte_slice_to_text(self.cipher.identifier));
+        ui.label("Public Key (type codes, identifier and root)");
+        ui.mono(ByteFormat::Hex.byte_slice_to_text(self.cipher.public_key()));
+        if self.cipher.next_index() < 1 << self.cipher.height {
+            ui.label(format!(
+                "The next signature will use leaf {} of {}.",
+                self.cipher.next_index(),
+                1 << self.cipher.height
+            ));
+        } else {
+            ui.error_text("Every leaf has been used, a new key is needed.");
+        }
+        ui.add_space(16.0);
+
+        ui.subheading("Verify a Signature");
+        ui.label("Message");
+        ui.control_string(&mut self.verify_message);
+        ui.label("Signature (hexadecimal)");
+        ui.control_string(&mut self.verify_signature);
+        if ui.button("Verify").clicked() {
+            self.verified = match (
+                self.cipher.input_format.text_to_bytes(&self.verify_message),
+                ByteFormat::Hex.text_to_bytes(&self.verify_signature),
+            ) {
+                (Ok(msg), Ok(sig)) => {
+                    Some(self.cipher.verify(&self.cipher.public_key(), &msg, &sig))
+                }
+                _ => Some(false),
+            }
+        }
+        match self.verified {
+            Some(true) => {
+                ui.label("valid signature");
+            }
+            Some(false) => {
+                ui.error_text("INVALID SIGNATURE");
+            }
+            None => (),
+        }
+        ui.add_space(16.0);
+    }
+
+    fn randomize(&mut self) {
+        self.cipher.generate_key(&mut thread_rng());
+        self.verified = None;
+    }
+
+    crate::simple_cipher! {}
+}
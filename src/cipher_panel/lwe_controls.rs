@@ -0,0 +1,138 @@
+use super::CipherFrame;
+use crate::ui_elements::UiElements;
+use ciphers::{
+    cryptanalysis::lattice_attacks::lwe_secret_recovery, digital::public_key_ciphers::lwe::Lwe,
+};
+use egui::{DragValue, Ui};
+use rand::thread_rng;
+
+fn list(ui: &mut Ui, numbers: &[u64]) {
+    ui.mono(
+        numbers
+            .iter()
+            .map(|x| x.to_string())
+            .collect::<Vec<_>>()
+            .join(", "),
+    );
+}
+
+pub struct LweFrame {
+    cipher: Lwe,
+    n: usize,
+    m: usize,
+    q: u64,
+    error_bound: u64,
+    attack_output: Option<String>,
+}
+
+impl Default for LweFrame {
+    fn default() -> Self {
+        let cipher = Lwe::default();
+        let mut out = Self {
+            n: cipher.n,
+            m: cipher.m,
+            q: cipher.q,
+            error_bound: cipher.error_bound,
+            cipher,
+            attack_output: None,
+        };
+        out.randomize();
+        out
+    }
+}
+
+impl CipherFrame for LweFrame {
+    fn ui(&mut self, ui: &mut Ui, errors: &mut String) {
+        ui.hyperlink_to(
+            "see the code",
+            "https://github.com/SymmetricChaos/crypto-gui/blob/master/ciphers/src/digital/public_key_ciphers/lwe.rs",
+        );
+        ui.add_space(8.0);
+
+        ui.randomize_reset_cipher(self);
+        ui.add_space(16.0);
+
+        ui.byte_io_mode_cipher(
+            &mut self.cipher.input_format,
+            &mut self.cipher.output_format,
+        );
+        ui.add_space(16.0);
+
+        ui.subheading("Parameters");
+        ui.horizontal(|ui| {
+            ui.label("n");
+            ui.add(DragValue::new(&mut self.n).range(1..=256));
+            ui.label("m");
+            ui.add(DragValue::new(&mut self.m).range(1..=1024));
+            ui.label("q");
+            ui.add(DragValue::new(&mut self.q).range(4..=(1_u64 << 31) - 1));
+            ui.label("B");
+            ui.add(DragValue::new(&mut self.error_bound).range(0..=64));
+        });
+        ui.horizontal(|ui| {
+            if ui.button("Apply").clicked() {
+                errors.clear();
+                match self
+                    .cipher
+                    .set_parameters(self.n, self.m, self.q, self.error_bound)
+                {
+                    Ok(_) => self.randomize(),
+                    Err(e) => *errors = e.to_string(),
+                }
+            }
+            if ui
+                .button("Small")
+                .on_hover_text("n = 8, m = 24, q = 97, B = 1")
+                .clicked()
+            {
+                (self.n, self.m, self.q, self.error_bound) = (8, 24, 97, 1);
+            }
+        });
+        ui.label("The secret has n entries and the public key has m samples. Errors are chosen uniformly from -B to B. Decryption can never fail when q > 4mB. Each bit of plaintext becomes n+1 integers modulo q.");
+        ui.add_space(16.0);
+
+        ui.subheading("Private Key");
+        ui.label("Secret s");
+        list(ui, &self.cipher.secret);
+        ui.add_space(8.0);
+
+        ui.subheading("Public Key");
+        ui.label("b = As + e");
+        list(ui, &self.cipher.b);
+        ui.collapsing("Matrix A", |ui| {
+            for row in self.cipher.a.iter() {
+                list(ui, row);
+            }
+        });
+        ui.add_space(16.0);
+
+        ui.subheading("Lattice Attack");
+        ui.label("Recover the secret from the public key alone by finding the short error vector with LLL lattice reduction. This only works for small dimensions, try n = 8 and m = 24.");
+        if ui.button("Attack").clicked() {
+            errors.clear();
+            if self.cipher.m > 64 {
+                *errors = String::from("the attack is limited to at most 64 samples");
+            } else {
+                self.attack_output = Some(match lwe_secret_recovery(&self.cipher) {
+                    Some(secret) => secret
+                        .iter()
+                        .map(|x| x.to_string())
+                        .collect::<Vec<_>>()
+                        .join(", "),
+                    None => String::from("attack failed"),
+                });
+            }
+        }
+        if let Some(out) = &self.attack_output {
+            ui.mono(out);
+        }
+        ui.add_space(16.0);
+    }
+
+    fn randomize(&mut self) {
+        self.cipher.generate_key(&mut thread_rng());
+        self.attack_output = None;
+    }
+
+    crate::simple_cipher! {}
+}
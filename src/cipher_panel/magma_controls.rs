@@ -0,0 +1,68 @@
+use super::CipherFrame;
+use crate::ui_elements::{block_cipher_iv_64, block_cipher_mode_and_padding, UiElements};
+use ciphers::digital::block_ciphers::magma::Magma;
+use egui::Ui;
+use rand::{thread_rng, Rng};
+
+#[derive(Default)]
+pub struct MagmaFrame {
+    cipher: Magma,
+}
+
+impl CipherFrame for MagmaFrame {
+    fn ui(&mut self, ui: &mut Ui, _errors: &mut String) {
+        ui.hyperlink_to(
+            "see the code",
+            "https://github.com/SymmetricChaos/crypto-gui/blob/master/ciphers/src/digital/block_ciphers/magma.rs",
+        );
+        ui.add_space(8.0);
+
+        ui.randomize_reset_cipher(self);
+        ui.add_space(16.0);
+
+        ui.byte_io_mode_cipher(
+            &mut self.cipher.input_format,
+            &mut self.cipher.output_format,
+        );
+
+        ui.add_space(16.0);
+
+        block_cipher_mode_and_padding(ui, &mut self.cipher.mode, &mut self.cipher.padding);
+        ui.add_space(8.0);
+
+        ui.label("Magma is the GOST 28147-89 Feistel cipher as standardized in GOST R 34.12-2015. Unlike the original standard the S-boxes are fixed and cannot be changed.");
+        ui.add_space(8.0);
+
+        ui.horizontal(|ui| {
+            ui.subheading("Key (256 bits)");
+            ui.random_bytes_button(&mut self.cipher.subkeys);
+        });
+        ui.horizontal(|ui| {
+            for k in self.cipher.subkeys[..4].iter_mut() {
+                ui.u32_hex_edit(k);
+            }
+        });
+        ui.horizontal(|ui| {
+            for k in self.cipher.subkeys[4..].iter_mut() {
+                ui.u32_hex_edit(k);
+            }
+        });
+
+        ui.add_space(8.0);
+
+        block_cipher_iv_64(ui, &mut self.cipher.iv, self.cipher.mode);
+
+        ui.add_space(16.0);
+    }
+
+    fn randomize(&mut self) {
+        let mut rng = thread_rng();
+        rng.fill(&mut self.cipher.subkeys);
+
+        if self.cipher.mode.iv_needed() {
+            self.cipher.iv = rng.gen();
+        }
+    }
+
+    crate::simple_cipher! {}
+}
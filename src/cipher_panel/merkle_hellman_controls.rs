@@ -0,0 +1,121 @@
+use super::CipherFrame;
+use crate::ui_elements::UiElements;
+use ciphers::{
+    cryptanalysis::knapsack::break_merkle_hellman,
+    digital::public_key_ciphers::merkle_hellman::MerkleHellman,
+};
+use egui::Ui;
+use rand::thread_rng;
+
+pub struct MerkleHellmanFrame {
+    cipher: MerkleHellman,
+    size: usize,
+    attack_input: String,
+    attack_output: Option<String>,
+}
+
+impl Default for MerkleHellmanFrame {
+    fn default() -> Self {
+        let mut out = Self {
+            cipher: Default::default(),
+            size: 32,
+            attack_input: String::new(),
+            attack_output: None,
+        };
+        out.randomize();
+        out
+    }
+}
+
+fn list(ui: &mut Ui, numbers: &[num::BigUint]) {
+    ui.mono(
+        numbers
+            .iter()
+            .map(|x| x.to_str_radix(10))
+            .collect::<Vec<_>>()
+            .join(", "),
+    );
+}
+
+impl CipherFrame for MerkleHellmanFrame {
+    fn ui(&mut self, ui: &mut Ui, errors: &mut String) {
+        ui.hyperlink_to(
+            "see the code",
+            "https://github.com/SymmetricChaos/crypto-gui/blob/master/ciphers/src/digital/public_key_ciphers/merkle_hellman.rs",
+        );
+        ui.add_space(8.0);
+
+        ui.randomize_reset_cipher(self);
+        ui.add_space(16.0);
+
+        ui.byte_io_mode_cipher(
+            &mut self.cipher.input_format,
+            &mut self.cipher.output_format,
+        );
+        ui.add_space(16.0);
+
+        ui.subheading("Generate Key");
+        ui.horizontal(|ui| {
+            for size in [16, 32, 64, 128] {
+                ui.selectable_value(&mut self.size, size, format!("{size} elements"));
+            }
+            if ui.button("Generate").clicked() {
+                errors.clear();
+                self.randomize();
+            }
+        });
+        ui.label("Each block of plaintext has one bit per element of the key. Bit padding is used to fill the last block.");
+        ui.add_space(16.0);
+
+        ui.subheading("Private Key");
+        ui.label("Superincreasing sequence");
+        list(ui, &self.cipher.private_sequence);
+        ui.label("Modulus");
+        ui.mono(self.cipher.modulus.to_str_radix(10));
+        ui.label("Multiplier");
+        ui.mono(self.cipher.multiplier.to_str_radix(10));
+        ui.label("Permutation");
+        ui.mono(format!("{:?}", self.cipher.permutation));
+        ui.add_space(8.0);
+
+        ui.subheading("Public Key");
+        ui.label("Each element of the sequence times the multiplier, reduced by the modulus, then placed according to the permutation.");
+        list(ui, &self.cipher.public_key);
+        ui.add_space(16.0);
+
+        ui.subheading("Lattice Attack");
+        ui.label("Decrypt a ciphertext using only the public key. Each block is solved by finding a short vector with LLL lattice reduction.");
+        ui.control_string(&mut self.attack_input);
+        if ui
+            .button("Attack")
+            .on_hover_text("larger keys may take some time")
+            .clicked()
+        {
+            errors.clear();
+            self.attack_output = match self.cipher.input_format.text_to_bytes(&self.attack_input) {
+                Ok(bytes) => Some(
+                    break_merkle_hellman(&self.cipher, &bytes)
+                        .map(|msg| self.cipher.output_format.byte_slice_to_text(msg))
+                        .unwrap_or_else(|| String::from("attack failed")),
+                ),
+                Err(_) => {
+                    *errors = String::from("byte format error");
+                    None
+                }
+            };
+        }
+        if let Some(out) = &self.attack_output {
+            ui.mono(out);
+        }
+        ui.add_space(16.0);
+    }
+
+    fn randomize(&mut self) {
+        self.cipher
+            .generate_key(self.size, &mut thread_rng())
+            .expect("key sizes offered are always valid");
+        self.attack_output = None;
+    }
+
+    crate::simple_cipher! {}
+}
@@ -0,0 +1,132 @@
+use super::CipherFrame;
+use crate::ui_elements::UiElements;
+use ciphers::digital::public_key_ciphers::merkle_signature::MerkleSignature;
+use egui::{DragValue, Ui};
+use hashers::sha::Sha256;
+use rand::thread_rng;
+use utils::byte_formatting::ByteFormat;
+
+pub struct MerkleSignatureFrame {
+    cipher: MerkleSignature<Sha256>,
+    selected_leaf: usize,
+    verify_message: String,
+    verify_signature: String,
+    verified: Option<bool>,
+}
+
+impl Default for MerkleSignatureFrame {
+    fn default() -> Self {
+        let mut out = Self {
+            cipher: Default::default(),
+            selected_leaf: 0,
+            verify_message: String::new(),
+            verify_signature: String::new(),
+            verified: None,
+        };
+        out.randomize();
+        out
+    }
+}
+
+impl CipherFrame for MerkleSignatureFrame {
+    fn ui(&mut self, ui: &mut Ui, errors: &mut String) {
+        ui.hyperlink_to(
+            "see the code",
+            "https://github.com/SymmetricChaos/crypto-gui/blob/master/ciphers/src/digital/public_key_ciphers/merkle_signature.rs",
+        );
+        ui.add_space(8.0);
+
+        ui.randomize_reset_cipher(self);
+        ui.add_space(16.0);
+
+        ui.byte_io_mode_cipher(
+            &mut self.cipher.input_format,
+            &mut self.cipher.output_format,
+        );
+        ui.add_space(16.0);
+
+        ui.subheading("Tree Height");
+        if ui
+            .add(DragValue::new(&mut self.cipher.height).range(2..=4))
+            .changed()
+        {
+            errors.clear();
+            self.randomize();
+        }
+        ui.label(format!(
+            "The tree has {} leaves, each the hash of a Lamport public key that can sign a single message using SHA-256.",
+            1 << self.cipher.height
+        ));
+        ui.add_space(16.0);
+
+        ui.horizontal(|ui| {
+            ui.subheading("Key");
+            if ui.button("New Key").clicked() {
+                errors.clear();
+                self.randomize();
+            }
+        });
+        ui.label("Secret Seed");
+        ui.mono(ByteFormat::Hex.byte_slice_to_text(&self.cipher.seed));
+        ui.label("Public Key (the root of the tree)");
+        ui.mono(ByteFormat::Hex.byte_slice_to_text(self.cipher.public_key()));
+        let leaves = 1 << self.cipher.height;
+        if self.cipher.next_index() < leaves {
+            ui.label(format!(
+                "The next signature will use leaf {}.",
+                self.cipher.next_index()
+            ));
+        } else {
+            ui.error_text("Every leaf has been used, a new key is needed.");
+        }
+        ui.add_space(16.0);
+
+        ui.subheading("Merkle Tree");
+        ui.horizontal(|ui| {
+            ui.label("Leaf");
+            ui.add(DragValue::new(&mut self.selected_leaf).range(0..=leaves - 1));
+        });
+        ui.label("The nodes that are recomputed when verifying a signature from this leaf are shown in parentheses and the authentication path that the signature includes is shown in brackets. Only the first four hex digits of each hash are shown.");
+        ui.mono(
+            self.cipher
+                .tree
+                .diagram(Some(self.selected_leaf.min(leaves - 1)), 4),
+        );
+        ui.add_space(16.0);
+
+        ui.subheading("Verify a Signature");
+        ui.label("Message");
+        ui.control_string(&mut self.verify_message);
+        ui.label("Signature (hexadecimal)");
+        ui.control_string(&mut self.verify_signature);
+        if ui.button("Verify").clicked() {
+            self.verified = match (
+                self.cipher.input_format.text_to_bytes(&self.verify_message),
+                ByteFormat::Hex.text_to_bytes(&self.verify_signature),
+            ) {
+                (Ok(msg), Ok(sig)) => Some(self.cipher.verify(&msg, &sig)),
+                _ => Some(false),
+            }
+        }
+        match self.verified {
+            Some(true) => {
+                ui.label("valid signature");
+            }
+            Some(false) => {
+                ui.error_text("INVALID SIGNATURE");
+            }
+            None => (),
+        }
+        ui.add_space(16.0);
+    }
+
+    fn randomize(&mut self) {
+        self.cipher
+            .generate_key(&mut thread_rng())
+            .expect("height is limited to 4");
+        self.selected_leaf = 0;
+        self.verified = None;
+    }
+
+    crate::simple_cipher! {}
+}
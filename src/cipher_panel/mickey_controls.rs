@@ -0,0 +1,87 @@
+use super::CipherFrame;
+use crate::ui_elements::UiElements;
+use ciphers::digital::stream_ciphers::mickey::Mickey2;
+use egui::{DragValue, Ui};
+use rand::{thread_rng, Rng};
+
+const MASK_80: u128 = (1 << 80) - 1;
+
+pub struct MickeyFrame {
+    cipher: Mickey2,
+    key: u128,
+    iv: u128,
+    iv_bytes: usize,
+}
+
+impl Default for MickeyFrame {
+    fn default() -> Self {
+        Self {
+            cipher: Default::default(),
+            key: 0,
+            iv: 0,
+            iv_bytes: 0,
+        }
+    }
+}
+
+impl MickeyFrame {
+    fn set_cipher(&mut self) {
+        self.key &= MASK_80;
+        self.iv &= (1 << (8 * self.iv_bytes)) - 1;
+        let iv = &self.iv.to_be_bytes()[16 - self.iv_bytes..];
+        // Cannot fail as the IV is never more than ten bytes
+        self.cipher
+            .ksa(self.key.to_be_bytes()[6..].try_into().unwrap(), iv)
+            .unwrap();
+    }
+}
+
+impl CipherFrame for MickeyFrame {
+    fn ui(&mut self, ui: &mut Ui, _errors: &mut String) {
+        ui.hyperlink_to(
+            "see the code",
+            "https://github.com/SymmetricChaos/crypto-gui/blob/master/ciphers/src/digital/stream_ciphers/mickey.rs",
+        );
+        ui.add_space(8.0);
+
+        ui.randomize_reset_cipher(self);
+        ui.add_space(16.0);
+
+        ui.byte_io_mode_cipher(
+            &mut self.cipher.input_format,
+            &mut self.cipher.output_format,
+        );
+        ui.add_space(16.0);
+
+        ui.subheading("Key (80 bits)");
+        if ui.u128_hex_edit(&mut self.key).lost_focus() {
+            self.set_cipher();
+        }
+        ui.add_space(8.0);
+
+        ui.subheading("IV Length (bytes)");
+        if ui
+            .add(DragValue::new(&mut self.iv_bytes).range(0..=10))
+            .changed()
+        {
+            self.set_cipher();
+        }
+        ui.subheading("IV");
+        if ui.u128_hex_edit(&mut self.iv).lost_focus() {
+            self.set_cipher();
+        }
+        ui.add_space(8.0);
+
+        ui.label("MICKEY 2.0 has two registers of 100 bits. The R register is linear and the S register is nonlinear, and each step a bit from each register decides whether the other is clocked regularly or irregularly. One bit of keystream is taken from the ends of the two registers at each step.");
+    }
+
+    fn randomize(&mut self) {
+        let mut rng = thread_rng();
+        self.key = rng.gen();
+        self.iv = rng.gen();
+        self.iv_bytes = 10;
+        self.set_cipher();
+    }
+
+    crate::simple_cipher! {}
+}
@@ -0,0 +1,117 @@
+use super::CipherFrame;
+use crate::ui_elements::UiElements;
+use ciphers::digital::public_key_ciphers::ml_kem::{MlKem, MlKemParameters};
+use egui::Ui;
+use rand::thread_rng;
+use utils::byte_formatting::ByteFormat;
+
+pub struct MlKemFrame {
+    cipher: MlKem,
+    demo: Option<(String, String, bool)>,
+}
+
+impl Default for MlKemFrame {
+    fn default() -> Self {
+        let mut out = Self {
+            cipher: Default::default(),
+            demo: None,
+        };
+        out.randomize();
+        out
+    }
+}
+
+impl CipherFrame for MlKemFrame {
+    fn ui(&mut self, ui: &mut Ui, errors: &mut String) {
+        ui.hyperlink_to(
+            "see the code",
+            "https://github.com/SymmetricChaos/crypto-gui/blob/master/ciphers/src/digital/public_key_ciphers/ml_kem.rs",
+        );
+        ui.add_space(8.0);
+
+        ui.randomize_reset_cipher(self);
+        ui.add_space(16.0);
+
+        ui.byte_io_mode_cipher(
+            &mut self.cipher.input_format,
+            &mut self.cipher.output_format,
+        );
+        ui.add_space(16.0);
+
+        ui.subheading("Parameter Set");
+        ui.horizontal(|ui| {
+            for parameters in [
+                MlKemParameters::MlKem512,
+                MlKemParameters::MlKem768,
+                MlKemParameters::MlKem1024,
+            ] {
+                if ui
+                    .selectable_value(&mut self.cipher.parameters, parameters, parameters.name())
+                    .clicked()
+                {
+                    self.randomize();
+                }
+            }
+        });
+        ui.label(format!(
+            "Vectors of k = {} polynomials with 256 coefficients modulo 3329. The encapsulation key is {} bytes, the decapsulation key {} bytes and the ciphertext {} bytes.",
+            self.cipher.parameters.k(),
+            self.cipher.parameters.encapsulation_key_len(),
+            self.cipher.parameters.decapsulation_key_len(),
+            self.cipher.parameters.ciphertext_len(),
+        ));
+        ui.add_space(8.0);
+
+        ui.subheading("Encapsulation and Decapsulation");
+        ui.label("ML-KEM does not encrypt messages, it establishes a random 32-byte shared key. Encrypting takes the 32-byte random value m used in encapsulation and gives the ciphertext followed by the shared key on the next line. Decrypting takes a ciphertext and gives the shared key. A ciphertext that has been tampered with gives an unrelated pseudorandom key rather than an error.");
+        ui.add_space(16.0);
+
+        ui.horizontal(|ui| {
+            ui.subheading("Encapsulation Key");
+            if ui.button("🎲").on_hover_text("random key").clicked() {
+                self.randomize();
+            }
+        });
+        ui.mono(ByteFormat::Hex.byte_slice_to_text(&self.cipher.encapsulation_key));
+        ui.add_space(8.0);
+        ui.collapsing("Decapsulation Key", |ui| {
+            ui.mono(ByteFormat::Hex.byte_slice_to_text(&self.cipher.decapsulation_key));
+        });
+        ui.add_space(16.0);
+
+        ui.subheading("Key Exchange");
+        if ui.button("Encapsulate").clicked() {
+            errors.clear();
+            match self.cipher.encapsulate(&mut thread_rng()) {
+                Ok((key, c)) => {
+                    let matches = self.cipher.decapsulate(&c).is_ok_and(|k| k == key);
+                    self.demo = Some((
+                        ByteFormat::Hex.byte_slice_to_text(&c),
+                        ByteFormat::Hex.byte_slice_to_text(key),
+                        matches,
+                    ));
+                }
+                Err(e) => *errors = e.to_string(),
+            }
+        }
+        if let Some((c, key, matches)) = &self.demo {
+            ui.label("Ciphertext");
+            ui.mono(c);
+            ui.label("Shared Key");
+            ui.mono(key);
+            if *matches {
+                ui.label("Decapsulation gives the same key.");
+            } else {
+                ui.error_text("Decapsulation gave a different key.");
+            }
+        }
+        ui.add_space(16.0);
+    }
+
+    fn randomize(&mut self) {
+        self.cipher.generate_key(&mut thread_rng());
+        self.demo = None;
+    }
+
+    crate::simple_cipher! {}
+}
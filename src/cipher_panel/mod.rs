@@ -3,12 +3,15 @@ use egui::Ui;
 
 mod a51_controls;
 mod a52_controls;
+mod a53_controls;
+mod achterbahn_controls;
 mod adfgvx_controls;
 mod aes_controls;
 mod aes_gcm_controls;
 mod affine_controls;
 mod alberti_controls;
 mod amsco_controls;
+mod anubis_controls;
 mod aria_controls;
 mod ascon128_controls;
 mod ascon80pq_controls;
@@ -17,25 +20,39 @@ mod batco_controls;
 mod bazeries_controls;
 mod beaufort_controls;
 mod bifid_controls;
+mod blind_rsa_controls;
+mod block_cipher_modes_controls;
 mod blowfish_controls;
 mod caesar_controls;
 mod camellia_controls;
+mod cast_controls;
 mod chacha20_poly1305_controls;
 mod chacha_controls;
 mod chaocipher_controls;
 mod checkerboard_controls;
 mod columnar_controls;
+mod cramer_shoup_controls;
 mod decoder_ring_controls;
 mod des_controls;
 mod desx_controls;
 mod diagonal_columnar_controls;
 mod diffie_hellman_controls;
 mod dryad_controls;
+mod dsa_controls;
+mod e0_controls;
+mod ecdsa_controls;
+mod ed25519_controls;
 mod elgamal_controls;
+mod elgamal_signature_controls;
 mod enigma_controls;
 mod fealnx_control;
+mod fish_controls;
 mod four_square_controls;
+mod gift_controls;
+mod goldwasser_micali_controls;
 mod gost_controls;
+mod grain128aead_controls;
+mod grain_controls;
 mod grille_controls;
 mod hc128_controls;
 mod hc256_controls;
@@ -45,11 +62,24 @@ mod homophonic_controls;
 mod hutton_controls;
 mod idea_controls;
 mod isaac_controls;
+mod kasumi_controls;
+mod key_wrap_controls;
+mod kuznyechik_controls;
+mod lamport_controls;
 mod lea_controls;
+mod lms_controls;
+mod lwe_controls;
 mod m209_controls;
 mod m94_controls;
+mod magma_controls;
+mod merkle_hellman_controls;
+mod merkle_signature_controls;
+mod mickey_controls;
 mod misty1_controls;
+mod ml_kem_controls;
 mod nihilist_controls;
+mod ntru_controls;
+mod paillier_controls;
 mod playfair_controls;
 mod plugboard_controls;
 mod polybius_cube_controls;
@@ -58,6 +88,7 @@ mod porta_controls;
 mod purple_controls;
 mod quagmire_controls;
 mod rabbit_controls;
+mod rabin_controls;
 mod rail_fence_controls;
 mod rc2_controls;
 mod rc4_controls;
@@ -66,7 +97,10 @@ mod rc6_controls;
 mod rs44_controls;
 mod rsa_controls;
 mod salsa20_controls;
+mod schnorr_controls;
 mod scytale_controls;
+mod seal_controls;
+mod secretbox_controls;
 mod seed_controls;
 mod seriated_playfair_controls;
 mod serpent_controls;
@@ -78,20 +112,30 @@ mod skip_cipher_controls;
 mod slidefair_controls;
 mod sm4_controls;
 mod snow3g_controls;
+mod snow_controls;
 mod solitaire_controls;
+mod sosemanuk_controls;
 mod speck_controls;
 mod tea_controls;
 mod threefish_controls;
 mod trifid_controls;
 mod triple_des_controls;
+mod trivium_controls;
 mod turning_grille_controls;
 mod two_square_controls;
 mod twofish_controls;
+mod uea_controls;
 mod vic_controls;
 mod vigenere_controls;
+mod wake_controls;
+mod wots_controls;
+mod xchacha20_poly1305_controls;
 mod xchacha_controls;
+mod xmss_controls;
 mod xor_splitting_controls;
+mod xsalsa20_controls;
 mod xtea_controls;
+mod xxtea_controls;
 
 #[macro_export]
 /// Implements reset, encrypt_string, and decrypt_string in the simplest way. Reset just calls Self::default() while the encrypt and decrypt just call the encrypt and decrypt methods from Cipher
@@ -111,109 +155,6 @@ macro_rules! simple_cipher {
     };
 }
 
-#[macro_export]
-macro_rules! simple_block_cipher {
-    ($blocksize: literal) => {
-        fn reset(&mut self) {
-            *self = Self::default()
-        }
-
-        fn encrypt_string(&self, text: &str) -> Result<String, utils::errors::GeneralError> {
-            use ciphers::digital::block_ciphers::block_cipher::BlockCipher;
-            // Interpret the input
-            let mut bytes = self
-                .input_format
-                .text_to_bytes(text)
-                .map_err(|e| ciphers::errors::GeneralError::input(e.to_string()))?;
-
-            // Provide the necessary kind and amount of padding
-            if self.cipher.mode.padded() {
-                self.cipher.padding.add_padding(&mut bytes, $blocksize)?;
-            }
-
-            // Select the correct mode. Since block ciphers all implement the BlockCipher
-            // trait these are available for free. The fully qualified names for each of
-            // the encrypt and decrypt functions are too messy and avoiding them is a pain
-            // so when this macro is called the file must have the BlockCipher trait
-            // imported.
-            match self.cipher.mode {
-                ciphers::digital::block_ciphers::block_cipher::BCMode::Ecb => {
-                    self.cipher.encrypt_ecb(&mut bytes)
-                }
-                ciphers::digital::block_ciphers::block_cipher::BCMode::Ctr => self
-                    .cipher
-                    .encrypt_ctr(&mut bytes, self.cipher.iv.to_be_bytes()),
-                ciphers::digital::block_ciphers::block_cipher::BCMode::Cbc => self
-                    .cipher
-                    .encrypt_cbc(&mut bytes, self.cipher.iv.to_be_bytes()),
-                ciphers::digital::block_ciphers::block_cipher::BCMode::Pcbc => self
-                    .cipher
-                    .encrypt_pcbc(&mut bytes, self.cipher.iv.to_be_bytes()),
-                ciphers::digital::block_ciphers::block_cipher::BCMode::Ofb => self
-                    .cipher
-                    .encrypt_ofb(&mut bytes, self.cipher.iv.to_be_bytes()),
-                ciphers::digital::block_ciphers::block_cipher::BCMode::Cfb => self
-                    .cipher
-                    .encrypt_cfb(&mut bytes, self.cipher.iv.to_be_bytes()),
-            };
-
-            Ok(self.output_format.byte_slice_to_text(&bytes))
-        }
-
-        fn decrypt_string(&self, text: &str) -> Result<String, utils::errors::GeneralError> {
-            use crate::digital::block_ciphers::block_cipher::BlockCipher;
-            // Interpret the input
-            let mut bytes = self
-                .input_format
-                .text_to_bytes(text)
-                .map_err(|e| crate::errors::GeneralError::input(e.to_string()))?;
-
-            // If padding is needed return an error if the input for decryption is the wrong size
-            if self.mode.padded() {
-                if bytes.len() % $blocksize != 0 {
-                    return Err(crate::errors::GeneralError::general(format!(
-                        "decryption requires blocks of exactly {} bytes",
-                        $blocksize
-                    )));
-                }
-            }
-
-            // Select the correct mode. Since block ciphers all implement the BlockCipher
-            // trait these are available for free. The fully qualified names for each of
-            // the encrypt and decrypt functions are too messy and avoiding them is a pain
-            // so when this macro is called the file must have the BlockCipher trait
-            // imported.
-            match self.mode {
-                crate::digital::block_ciphers::block_cipher::BCMode::Ecb => {
-                    self.decrypt_ecb(&mut bytes)
-                }
-                crate::digital::block_ciphers::block_cipher::BCMode::Ctr => {
-                    self.decrypt_ctr(&mut bytes, self.iv.to_be_bytes())
-                }
-                crate::digital::block_ciphers::block_cipher::BCMode::Cbc => {
-                    self.decrypt_cbc(&mut bytes, self.iv.to_be_bytes())
-                }
-                crate::digital::block_ciphers::block_cipher::BCMode::Pcbc => {
-                    self.decrypt_pcbc(&mut bytes, self.iv.to_be_bytes())
-                }
-                crate::digital::block_ciphers::block_cipher::BCMode::Ofb => {
-                    self.decrypt_ofb(&mut bytes, self.iv.to_be_bytes())
-                }
-                crate::digital::block_ciphers::block_cipher::BCMode::Cfb => {
-                    self.decrypt_cfb(&mut bytes, self.iv.to_be_bytes())
-                }
-            };
-
-            // Remove the appropriate kind and amount of padding
-            if self.mode.padded() {
-                self.padding.strip_padding(&mut bytes, $blocksize)?;
-            }
-
-            Ok(self.output_format.byte_slice_to_text(&bytes))
-        }
-    };
-}
-
 pub trait CipherFrame {
     fn ui(&mut self, ui: &mut Ui, errors: &mut String);
     fn randomize(&mut self);
@@ -308,17 +249,25 @@ pub struct CipherInterface {
 
     // Block
     aes: aes_controls::AesFrame,
+    anubis: anubis_controls::AnubisFrame,
     aria: aria_controls::AriaFrame,
     ascon128: ascon128_controls::Ascon128Frame,
     ascon80pq: ascon80pq_controls::Ascon80pqFrame,
+    block_cipher_modes: block_cipher_modes_controls::BlockCipherModesFrame,
     blowfish: blowfish_controls::BlowfishFrame,
     camellia: camellia_controls::CamelliaFrame,
+    cast: cast_controls::CastFrame,
     des: des_controls::DesFrame,
     desx: desx_controls::DesXFrame,
     fealnx: fealnx_control::FealNxFrame,
+    gift: gift_controls::GiftFrame,
     gost: gost_controls::GostFrame,
     idea: idea_controls::IdeaFrame,
+    kasumi: kasumi_controls::KasumiFrame,
+    key_wrap: key_wrap_controls::KeyWrapFrame,
+    kuznyechik: kuznyechik_controls::KuznyechikFrame,
     lea: lea_controls::LeaFrame,
+    magma: magma_controls::MagmaFrame,
     misty1: misty1_controls::Misty1Frame,
     rc2: rc2_controls::Rc2Frame,
     rc5: rc5_controls::Rc5Frame,
@@ -332,25 +281,61 @@ pub struct CipherInterface {
     triple_des: triple_des_controls::TripleDesFrame,
     twofish: twofish_controls::TwofishFrame,
     xtea: xtea_controls::XteaFrame,
+    xxtea: xxtea_controls::XxteaFrame,
 
     // Stream
     a51: a51_controls::A51Frame,
     a52: a52_controls::A52Frame,
+    a53: a53_controls::A53Frame,
+    achterbahn: achterbahn_controls::AchterbahnFrame,
     aes_gcm: aes_gcm_controls::AesGcmFrame,
     chacha: chacha_controls::ChaChaFrame,
     chacha20poly1305: chacha20_poly1305_controls::ChaCha20Poly1305Frame,
+    e0: e0_controls::E0Frame,
+    fish: fish_controls::FishFrame,
+    grain: grain_controls::GrainFrame,
+    grain128aead: grain128aead_controls::Grain128AeadFrame,
     hc128: hc128_controls::Hc128Frame,
     hc256: hc256_controls::Hc256Frame,
     isaac: isaac_controls::IsaacFrame,
+    mickey: mickey_controls::MickeyFrame,
     rabbit: rabbit_controls::RabbitFrame,
     rc4: rc4_controls::Rc4Frame,
     salsa20: salsa20_controls::Salsa20Frame,
+    secretbox: secretbox_controls::SecretBoxFrame,
+    seal3: seal_controls::Seal3Frame,
+    snow: snow_controls::SnowFrame,
     snow3g: snow3g_controls::Snow3GFrame,
+    sosemanuk: sosemanuk_controls::SosemanukFrame,
+    trivium: trivium_controls::TriviumFrame,
+    uea: uea_controls::UeaFrame,
+    wake: wake_controls::WakeFrame,
     xchacha: xchacha_controls::XChaChaFrame,
+    xchacha20poly1305: xchacha20_poly1305_controls::XChaCha20Poly1305Frame,
+    xsalsa20: xsalsa20_controls::XSalsa20Frame,
 
     // Public Key
+    blind_rsa: blind_rsa_controls::BlindRsaFrame,
+    cramer_shoup: cramer_shoup_controls::CramerShoupFrame,
     diffie_hellman: diffie_hellman_controls::DiffieHellmanFrame,
+    dsa: dsa_controls::DsaFrame,
+    ecdsa: ecdsa_controls::EcdsaFrame,
+    ed25519: ed25519_controls::Ed25519Frame,
+    elgamal_signature: elgamal_signature_controls::ElGamalSignatureFrame,
+    goldwasser_micali: goldwasser_micali_controls::GoldwasserMicaliFrame,
+    lamport: lamport_controls::LamportFrame,
+    lms: lms_controls::LmsFrame,
+    lwe: lwe_controls::LweFrame,
+    merkle_hellman: merkle_hellman_controls::MerkleHellmanFrame,
+    merkle_signature: merkle_signature_controls::MerkleSignatureFrame,
+    ml_kem: ml_kem_controls::MlKemFrame,
+    ntru: ntru_controls::NtruFrame,
+    paillier: paillier_controls::PaillierFrame,
+    rabin: rabin_controls::RabinFrame,
     rsa: rsa_controls::RsaFrame,
+    schnorr: schnorr_controls::SchnorrFrame,
+    wots: wots_controls::WotsFrame,
+    xmss: xmss_controls::XmssFrame,
 
     // Composite
     vic: vic_controls::VicFrame,
@@ -477,17 +462,25 @@ impl CipherInterface {
         combox_box(
             &[
                 CipherId::Aes,
+                CipherId::Anubis,
                 CipherId::Aria,
                 CipherId::Ascon128,
                 CipherId::Ascon80pq,
+                CipherId::BlockCipherModes,
                 CipherId::Blowfish,
                 CipherId::Camellia,
+                CipherId::Cast,
                 CipherId::Des,
                 CipherId::DesX,
                 CipherId::FealNx,
+                CipherId::Gift,
                 CipherId::Gost,
                 CipherId::Idea,
+                CipherId::Kasumi,
+                CipherId::KeyWrap,
+                CipherId::Kuznyechik,
                 CipherId::Lea,
+                CipherId::Magma,
                 CipherId::Misty1,
                 CipherId::Rc2,
                 CipherId::Rc5,
@@ -501,6 +494,7 @@ impl CipherInterface {
                 CipherId::TripleDes,
                 CipherId::Twofish,
                 CipherId::Xtea,
+                CipherId::Xxtea,
             ],
             active_cipher,
             CipherCategory::DigitalBlock,
@@ -511,17 +505,33 @@ impl CipherInterface {
             &[
                 CipherId::A51,
                 CipherId::A52,
+                CipherId::A53,
+                CipherId::Achterbahn,
                 CipherId::AesGcm,
                 CipherId::ChaCha,
                 CipherId::ChaCha20Poly1305,
+                CipherId::E0,
+                CipherId::Fish,
+                CipherId::Grain,
+                CipherId::Grain128Aead,
                 CipherId::Hc128,
                 CipherId::Hc256,
                 CipherId::Isaac,
+                CipherId::Mickey,
                 CipherId::Rabbit,
                 CipherId::Rc4,
                 CipherId::Salsa20,
+                CipherId::Seal3,
+                CipherId::SecretBox,
+                CipherId::Snow,
                 CipherId::Snow3G,
+                CipherId::Sosemanuk,
+                CipherId::Trivium,
+                CipherId::Uea,
+                CipherId::Wake,
                 CipherId::XChaCha,
+                CipherId::XChaCha20Poly1305,
+                CipherId::XSalsa20,
             ],
             active_cipher,
             CipherCategory::DigitalStream,
@@ -536,7 +546,29 @@ impl CipherInterface {
         );
 
         combox_box(
-            &[CipherId::Rsa, CipherId::DiffieHellman],
+            &[
+                CipherId::Rsa,
+                CipherId::DiffieHellman,
+                CipherId::Dsa,
+                CipherId::Ecdsa,
+                CipherId::Ed25519,
+                CipherId::Schnorr,
+                CipherId::ElGamalSignature,
+                CipherId::BlindRsa,
+                CipherId::Paillier,
+                CipherId::Rabin,
+                CipherId::GoldwasserMicali,
+                CipherId::MerkleHellman,
+                CipherId::CramerShoup,
+                CipherId::MlKem,
+                CipherId::Ntru,
+                CipherId::Lwe,
+                CipherId::Lamport,
+                CipherId::Wots,
+                CipherId::MerkleSignature,
+                CipherId::Lms,
+                CipherId::Xmss,
+            ],
             active_cipher,
             CipherCategory::PublicKey,
             ui,
@@ -547,6 +579,8 @@ impl CipherInterface {
         match active_cipher {
             CipherId::A51 => &mut self.a51,
             CipherId::A52 => &mut self.a52,
+            CipherId::A53 => &mut self.a53,
+            CipherId::Achterbahn => &mut self.achterbahn,
             CipherId::Aria => &mut self.aria,
             CipherId::Aes => &mut self.aes,
             CipherId::AesGcm => &mut self.aes_gcm,
@@ -554,6 +588,7 @@ impl CipherInterface {
             CipherId::Affine => &mut self.affine,
             CipherId::Alberti => &mut self.alberti,
             CipherId::Amsco => &mut self.amsco,
+            CipherId::Anubis => &mut self.anubis,
             CipherId::Ascon128 => &mut self.ascon128,
             CipherId::Ascon80pq => &mut self.ascon80pq,
             CipherId::B64 => &mut self.b64,
@@ -561,24 +596,38 @@ impl CipherInterface {
             CipherId::Bazeries => &mut self.bazeries,
             CipherId::Beaufort => &mut self.beaufort,
             CipherId::Bifid => &mut self.bifid,
+            CipherId::BlindRsa => &mut self.blind_rsa,
+            CipherId::BlockCipherModes => &mut self.block_cipher_modes,
             CipherId::Blowfish => &mut self.blowfish,
             CipherId::Caesar => &mut self.caesar,
             CipherId::Camellia => &mut self.camellia,
+            CipherId::Cast => &mut self.cast,
             CipherId::ChaCha => &mut self.chacha,
             CipherId::ChaCha20Poly1305 => &mut self.chacha20poly1305,
             CipherId::Chaocipher => &mut self.chaocipher,
             CipherId::Checkerboard => &mut self.checkerboard,
             CipherId::Columnar => &mut self.columnar,
+            CipherId::CramerShoup => &mut self.cramer_shoup,
             CipherId::Decoder => &mut self.decoder_ring,
             CipherId::Des => &mut self.des,
             CipherId::DesX => &mut self.desx,
             CipherId::DiagonalColumnar => &mut self.diagonal_columnar,
             CipherId::DiffieHellman => &mut self.diffie_hellman,
+            CipherId::Dsa => &mut self.dsa,
+            CipherId::Ecdsa => &mut self.ecdsa,
+            CipherId::Ed25519 => &mut self.ed25519,
+            CipherId::ElGamalSignature => &mut self.elgamal_signature,
             CipherId::Dryad => &mut self.dryad,
+            CipherId::E0 => &mut self.e0,
             CipherId::Enigma => &mut self.enigma,
             CipherId::FealNx => &mut self.fealnx,
+            CipherId::Fish => &mut self.fish,
             CipherId::FourSquare => &mut self.four_square,
+            CipherId::Gift => &mut self.gift,
+            CipherId::GoldwasserMicali => &mut self.goldwasser_micali,
             CipherId::Gost => &mut self.gost,
+            CipherId::Grain => &mut self.grain,
+            CipherId::Grain128Aead => &mut self.grain128aead,
             CipherId::Grille => &mut self.grille,
             CipherId::Hc128 => &mut self.hc128,
             CipherId::Hc256 => &mut self.hc256,
@@ -587,12 +636,25 @@ impl CipherInterface {
             CipherId::Homophonic => &mut self.homophonic,
             CipherId::Hutton => &mut self.hutton,
             CipherId::Idea => &mut self.idea,
+            CipherId::Kasumi => &mut self.kasumi,
             CipherId::Isaac => &mut self.isaac,
+            CipherId::KeyWrap => &mut self.key_wrap,
+            CipherId::Kuznyechik => &mut self.kuznyechik,
+            CipherId::Lamport => &mut self.lamport,
             CipherId::Lea => &mut self.lea,
+            CipherId::Lms => &mut self.lms,
+            CipherId::Lwe => &mut self.lwe,
             CipherId::M209 => &mut self.m209,
             CipherId::M94 => &mut self.m94,
+            CipherId::Magma => &mut self.magma,
+            CipherId::MerkleHellman => &mut self.merkle_hellman,
+            CipherId::MerkleSignature => &mut self.merkle_signature,
+            CipherId::Mickey => &mut self.mickey,
             CipherId::Misty1 => &mut self.misty1,
+            CipherId::MlKem => &mut self.ml_kem,
             CipherId::Nihilist => &mut self.nihilist,
+            CipherId::Ntru => &mut self.ntru,
+            CipherId::Paillier => &mut self.paillier,
             CipherId::Playfair => &mut self.playfair,
             CipherId::Plugboard => &mut self.plugboard,
             CipherId::Polybius => &mut self.polybius,
@@ -601,6 +663,7 @@ impl CipherInterface {
             CipherId::Purple => &mut self.purple,
             CipherId::Quagmire => &mut self.quagmire,
             CipherId::Rabbit => &mut self.rabbit,
+            CipherId::Rabin => &mut self.rabin,
             CipherId::RailFence => &mut self.rail_fence,
             CipherId::Rc2 => &mut self.rc2,
             CipherId::Rc4 => &mut self.rc4,
@@ -608,7 +671,10 @@ impl CipherInterface {
             CipherId::Rsa => &mut self.rsa,
             CipherId::Rs44 => &mut self.rs44,
             CipherId::Salsa20 => &mut self.salsa20,
+            CipherId::Schnorr => &mut self.schnorr,
+            CipherId::SecretBox => &mut self.secretbox,
             CipherId::Scytale => &mut self.scytale,
+            CipherId::Seal3 => &mut self.seal3,
             CipherId::Seed => &mut self.seed,
             CipherId::SeriatedPlayfair => &mut self.seriated,
             CipherId::Serpent => &mut self.serpent,
@@ -618,22 +684,32 @@ impl CipherInterface {
             CipherId::SkipCipher => &mut self.skip,
             CipherId::Slidefair => &mut self.slidefair,
             CipherId::Sm4 => &mut self.sm4,
+            CipherId::Snow => &mut self.snow,
             CipherId::Snow3G => &mut self.snow3g,
             CipherId::Solitaire => &mut self.solitaire,
+            CipherId::Sosemanuk => &mut self.sosemanuk,
             CipherId::Speck => &mut self.speck,
             CipherId::Substitution => &mut self.gen_sub,
             CipherId::Tea => &mut self.tea,
             CipherId::Threefish => &mut self.threefish,
             CipherId::Trifid => &mut self.trifid,
             CipherId::TripleDes => &mut self.triple_des,
+            CipherId::Trivium => &mut self.trivium,
             CipherId::TurningGrille => &mut self.turning_grille,
             CipherId::Twofish => &mut self.twofish,
             CipherId::TwoSquare => &mut self.two_square,
+            CipherId::Uea => &mut self.uea,
             CipherId::Vic => &mut self.vic,
             CipherId::Vigenere => &mut self.vigenere,
+            CipherId::Wake => &mut self.wake,
+            CipherId::Wots => &mut self.wots,
             CipherId::XorSplitting => &mut self.xor_splitting,
             CipherId::XChaCha => &mut self.xchacha,
+            CipherId::XChaCha20Poly1305 => &mut self.xchacha20poly1305,
+            CipherId::Xmss => &mut self.xmss,
+            CipherId::XSalsa20 => &mut self.xsalsa20,
             CipherId::Xtea => &mut self.xtea,
+            CipherId::Xxtea => &mut self.xxtea,
             _ => todo!("<<<CIPHER NOT FOUND>>>"),
         }
     }
@@ -0,0 +1,137 @@
+use super::CipherFrame;
+use crate::ui_elements::UiElements;
+use ciphers::{
+    cryptanalysis::lattice_attacks::ntru_key_recovery, digital::public_key_ciphers::ntru::Ntru,
+};
+use egui::Ui;
+use rand::thread_rng;
+
+fn list(ui: &mut Ui, numbers: &[i64]) {
+    ui.mono(
+        numbers
+            .iter()
+            .map(|x| x.to_string())
+            .collect::<Vec<_>>()
+            .join(", "),
+    );
+}
+
+// (N, p, q, d), all with q > (6d + 1)p so decryption never fails
+const PRESETS: [(usize, i64, i64, usize); 4] = [
+    (23, 3, 64, 3),
+    (107, 3, 512, 20),
+    (167, 3, 1024, 40),
+    (401, 3, 2048, 113),
+];
+
+pub struct NtruFrame {
+    cipher: Ntru,
+    attack_output: Option<Result<Vec<i64>, String>>,
+}
+
+impl Default for NtruFrame {
+    fn default() -> Self {
+        let mut out = Self {
+            cipher: Default::default(),
+            attack_output: None,
+        };
+        out.randomize();
+        out
+    }
+}
+
+impl CipherFrame for NtruFrame {
+    fn ui(&mut self, ui: &mut Ui, errors: &mut String) {
+        ui.hyperlink_to(
+            "see the code",
+            "https://github.com/SymmetricChaos/crypto-gui/blob/master/ciphers/src/digital/public_key_ciphers/ntru.rs",
+        );
+        ui.add_space(8.0);
+
+        ui.randomize_reset_cipher(self);
+        ui.add_space(16.0);
+
+        ui.byte_io_mode_cipher(
+            &mut self.cipher.input_format,
+            &mut self.cipher.output_format,
+        );
+        ui.add_space(16.0);
+
+        ui.subheading("Parameters");
+        ui.horizontal(|ui| {
+            for (n, p, q, d) in PRESETS {
+                if ui
+                    .selectable_label(self.cipher.n == n, format!("N = {n}"))
+                    .clicked()
+                {
+                    errors.clear();
+                    match self.cipher.set_parameters(n, p, q, d) {
+                        Ok(_) => self.randomize(),
+                        Err(e) => *errors = e.to_string(),
+                    }
+                }
+            }
+        });
+        ui.label(format!(
+            "Polynomials modulo x^{} - 1 with p = {}, q = {} and d = {}. Each block of {} bytes is encrypted as one polynomial with a coefficient for each bit, bit padding fills the last block.",
+            self.cipher.n,
+            self.cipher.p,
+            self.cipher.q,
+            self.cipher.d,
+            self.cipher.block_bytes()
+        ));
+        ui.add_space(16.0);
+
+        ui.subheading("Private Key");
+        ui.label(format!(
+            "f, with {} coefficients equal to 1 and {} equal to -1",
+            self.cipher.d + 1,
+            self.cipher.d
+        ));
+        list(ui, &self.cipher.f);
+        ui.label(format!(
+            "g, with {} coefficients of each sign",
+            self.cipher.d
+        ));
+        list(ui, &self.cipher.g);
+        ui.add_space(8.0);
+
+        ui.subheading("Public Key");
+        ui.label("h = p g / f mod q");
+        list(ui, &self.cipher.h);
+        ui.add_space(16.0);
+
+        ui.subheading("Lattice Attack");
+        ui.label("Find a private key that works as well as f from the public key alone by searching for a short vector with LLL lattice reduction. Only practical with N = 23.");
+        if ui.button("Attack").clicked() {
+            errors.clear();
+            if self.cipher.n > 50 {
+                *errors = String::from("the attack is limited to N of at most 50");
+            } else {
+                self.attack_output = Some(
+                    ntru_key_recovery(&self.cipher)
+                        .map(|key| key.f)
+                        .ok_or(String::from("attack failed")),
+                );
+            }
+        }
+        match &self.attack_output {
+            Some(Ok(f)) => {
+                ui.label("Recovered f");
+                list(ui, f);
+            }
+            Some(Err(e)) => {
+                ui.mono(e);
+            }
+            None => (),
+        }
+        ui.add_space(16.0);
+    }
+
+    fn randomize(&mut self) {
+        self.cipher.generate_key(&mut thread_rng());
+        self.attack_output = None;
+    }
+
+    crate::simple_cipher! {}
+}
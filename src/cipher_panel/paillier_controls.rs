@@ -0,0 +1,129 @@
+use super::CipherFrame;
+use crate::ui_elements::UiElements;
+use ciphers::digital::public_key_ciphers::paillier::Paillier;
+use egui::Ui;
+use num::BigUint;
+use rand::thread_rng;
+use std::str::FromStr;
+
+pub struct PaillierFrame {
+    cipher: Paillier,
+    key_bits: u64,
+    a: String,
+    b: String,
+    sum: Option<(BigUint, BigUint, BigUint, BigUint)>,
+}
+
+impl Default for PaillierFrame {
+    fn default() -> Self {
+        let mut out = Self {
+            cipher: Default::default(),
+            key_bits: 512,
+            a: String::from("1234"),
+            b: String::from("5678"),
+            sum: None,
+        };
+        out.randomize();
+        out
+    }
+}
+
+impl CipherFrame for PaillierFrame {
+    fn ui(&mut self, ui: &mut Ui, errors: &mut String) {
+        ui.hyperlink_to(
+            "see the code",
+            "https://github.com/SymmetricChaos/crypto-gui/blob/master/ciphers/src/digital/public_key_ciphers/paillier.rs",
+        );
+        ui.add_space(8.0);
+
+        ui.randomize_reset_cipher(self);
+        ui.add_space(16.0);
+
+        ui.byte_io_mode_cipher(
+            &mut self.cipher.input_format,
+            &mut self.cipher.output_format,
+        );
+        ui.add_space(16.0);
+
+        ui.subheading("Generate Key");
+        ui.horizontal(|ui| {
+            for bits in [512, 1024, 2048] {
+                ui.selectable_value(&mut self.key_bits, bits, format!("{bits}-bit"));
+            }
+            if ui.button("Generate").clicked() {
+                errors.clear();
+                self.randomize();
+            }
+        });
+        ui.add_space(16.0);
+
+        ui.subheading("Public Key");
+        ui.label(
+            "The generator g is always n+1, which makes encryption faster without losing security.",
+        );
+        ui.label("n");
+        ui.mono(self.cipher.n.to_str_radix(16));
+        ui.add_space(8.0);
+
+        ui.subheading("Private Key");
+        ui.label("λ");
+        ui.mono(self.cipher.lambda.to_str_radix(16));
+        ui.label("μ");
+        ui.mono(self.cipher.mu.to_str_radix(16));
+        ui.add_space(16.0);
+
+        ui.subheading("Homomorphic Addition");
+        ui.label("Multiplying the ciphertexts of two numbers gives a ciphertext of their sum.");
+        ui.horizontal(|ui| {
+            ui.label("a");
+            ui.control_string(&mut self.a);
+            ui.label("b");
+            ui.control_string(&mut self.b);
+        });
+        if ui.button("Add").clicked() {
+            errors.clear();
+            let mut rng = thread_rng();
+            self.sum = match (BigUint::from_str(&self.a), BigUint::from_str(&self.b)) {
+                (Ok(a), Ok(b)) => {
+                    match (
+                        self.cipher.encrypt_int(&a, &mut rng),
+                        self.cipher.encrypt_int(&b, &mut rng),
+                    ) {
+                        (Ok(ca), Ok(cb)) => {
+                            let c = self.cipher.add(&ca, &cb);
+                            let m = self.cipher.decrypt_int(&c).unwrap_or_default();
+                            Some((ca, cb, c, m))
+                        }
+                        (Err(e), _) | (_, Err(e)) => {
+                            *errors = e.to_string();
+                            None
+                        }
+                    }
+                }
+                _ => {
+                    *errors = String::from("a and b must be non-negative integers");
+                    None
+                }
+            };
+        }
+        if let Some((ca, cb, c, m)) = &self.sum {
+            ui.label("E(a)");
+            ui.mono(ca.to_str_radix(16));
+            ui.label("E(b)");
+            ui.mono(cb.to_str_radix(16));
+            ui.label("E(a) × E(b)");
+            ui.mono(c.to_str_radix(16));
+            ui.label(format!("which decrypts to {m}"));
+        }
+        ui.add_space(16.0);
+    }
+
+    fn randomize(&mut self) {
+        self.cipher
+            .generate_key(self.key_bits, &mut thread_rng())
+            .expect("key sizes offered are always valid");
+        self.sum = None;
+    }
+
+    crate::simple_cipher! {}
+}
@@ -0,0 +1,77 @@
+use super::CipherFrame;
+use crate::ui_elements::UiElements;
+use ciphers::digital::public_key_ciphers::rabin::Rabin;
+use egui::Ui;
+use rand::thread_rng;
+
+pub struct RabinFrame {
+    cipher: Rabin,
+    key_bits: u64,
+}
+
+impl Default for RabinFrame {
+    fn default() -> Self {
+        let mut out = Self {
+            cipher: Default::default(),
+            key_bits: 512,
+        };
+        out.randomize();
+        out
+    }
+}
+
+impl CipherFrame for RabinFrame {
+    fn ui(&mut self, ui: &mut Ui, errors: &mut String) {
+        ui.hyperlink_to(
+            "see the code",
+            "https://github.com/SymmetricChaos/crypto-gui/blob/master/ciphers/src/digital/public_key_ciphers/rabin.rs",
+        );
+        ui.add_space(8.0);
+
+        ui.randomize_reset_cipher(self);
+        ui.add_space(16.0);
+
+        ui.byte_io_mode_cipher(
+            &mut self.cipher.input_format,
+            &mut self.cipher.output_format,
+        );
+        ui.add_space(16.0);
+
+        ui.subheading("Generate Key");
+        ui.horizontal(|ui| {
+            for bits in [512, 1024, 2048] {
+                ui.selectable_value(&mut self.key_bits, bits, format!("{bits}-bit"));
+            }
+            if ui.button("Generate").clicked() {
+                errors.clear();
+                self.randomize();
+            }
+        });
+        ui.label("Both primes are 3 mod 4 so square roots modulo each can be found with a single exponentiation.");
+        ui.add_space(16.0);
+
+        ui.subheading("Public Key");
+        ui.label("n");
+        ui.mono(self.cipher.n.to_str_radix(16));
+        ui.add_space(8.0);
+
+        ui.subheading("Private Key");
+        ui.label("p");
+        ui.mono(self.cipher.p.to_str_radix(16));
+        ui.label("q");
+        ui.mono(self.cipher.q.to_str_radix(16));
+        ui.add_space(16.0);
+
+        ui.subheading("Redundancy");
+        ui.label("Before encryption the message is prefixed with the byte 01 and followed by the first eight bytes of its SHA-256 hash. Decryption finds all four square roots of the ciphertext and keeps the one where the hash matches.");
+        ui.add_space(16.0);
+    }
+
+    fn randomize(&mut self) {
+        self.cipher
+            .generate_key(self.key_bits, &mut thread_rng())
+            .expect("key sizes offered are always valid");
+    }
+
+    crate::simple_cipher! {}
+}
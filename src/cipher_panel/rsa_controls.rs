@@ -1,7 +1,8 @@
 use super::CipherFrame;
 use crate::ui_elements::UiElements;
-use ciphers::digital::public_key_ciphers::rsa::Rsa;
+use ciphers::digital::public_key_ciphers::rsa::{Rsa, RsaPadding};
 use egui::Ui;
+use hashers::sha::sha2::Sha2Variant;
 use num::BigUint;
 use num_prime::{nt_funcs::is_prime, PrimalityTestConfig, RandPrime};
 use rand::thread_rng;
@@ -31,23 +32,51 @@ fn prime_string(ui: &mut Ui, s: &mut String, n: &mut BigUint) {
     });
 }
 
-#[derive(Default)]
 pub struct RsaFrame {
     cipher: Rsa,
     p: String,
     p_num: BigUint,
     q: String,
     q_num: BigUint,
+    key_bits: u64,
+    label: String,
+}
+
+impl Default for RsaFrame {
+    fn default() -> Self {
+        Self {
+            cipher: Default::default(),
+            p: Default::default(),
+            p_num: Default::default(),
+            q: Default::default(),
+            q_num: Default::default(),
+            key_bits: 1024,
+            label: Default::default(),
+        }
+    }
 }
 
 impl RsaFrame {
-    fn run_ksa(&mut self) {
-        self.cipher.set_key(&self.p_num, &self.q_num)
+    fn run_ksa(&mut self, errors: &mut String) {
+        errors.clear();
+        if let Err(e) = self.cipher.set_key(&self.p_num, &self.q_num) {
+            *errors = e.to_string()
+        }
+    }
+
+    fn generate_key(&mut self) {
+        self.cipher
+            .generate_key(self.key_bits, &mut thread_rng())
+            .expect("key sizes offered are always valid");
+        self.p_num = self.cipher.p.clone();
+        self.p = self.p_num.to_str_radix(10);
+        self.q_num = self.cipher.q.clone();
+        self.q = self.q_num.to_str_radix(10);
     }
 }
 
 impl CipherFrame for RsaFrame {
-    fn ui(&mut self, ui: &mut Ui, _errors: &mut String) {
+    fn ui(&mut self, ui: &mut Ui, errors: &mut String) {
         ui.hyperlink_to(
             "see the code",
             "https://github.com/SymmetricChaos/crypto-gui/blob/master/ciphers/src/digital/public_key_ciphers/rsa.rs",
@@ -63,6 +92,46 @@ impl CipherFrame for RsaFrame {
         );
         ui.add_space(16.0);
 
+        ui.subheading("Padding");
+        ui.horizontal(|ui| {
+            ui.selectable_value(&mut self.cipher.padding, RsaPadding::None, "None");
+            ui.selectable_value(&mut self.cipher.padding, RsaPadding::Pkcs1, "PKCS#1 v1.5");
+            ui.selectable_value(&mut self.cipher.padding, RsaPadding::Oaep, "OAEP");
+        });
+        match self.cipher.padding {
+            RsaPadding::None => {
+                ui.label("Textbook RSA is deterministic and malleable, the same message always gives the same ciphertext and ciphertexts can be multiplied together to give the encryption of the product of the messages.");
+            }
+            RsaPadding::Pkcs1 => {
+                ui.label("The message is preceded by at least eight random nonzero bytes. This padding is still widely used but a server that reveals whether the padding was valid allows Bleichenbacher's attack.");
+            }
+            RsaPadding::Oaep => {
+                ui.label("Optimal Asymmetric Encryption Padding masks the message and a random seed with the MGF1 function, binding in an optional label.");
+                ui.horizontal(|ui| {
+                    ui.selectable_value(&mut self.cipher.hash, Sha2Variant::Sha256, "SHA-256");
+                    ui.selectable_value(&mut self.cipher.hash, Sha2Variant::Sha384, "SHA-384");
+                    ui.selectable_value(&mut self.cipher.hash, Sha2Variant::Sha512, "SHA-512");
+                });
+                ui.label("Label");
+                if ui.control_string(&mut self.label).changed() {
+                    self.cipher.label = self.label.as_bytes().to_vec();
+                }
+            }
+        }
+        ui.add_space(16.0);
+
+        ui.subheading("Generate Key");
+        ui.horizontal(|ui| {
+            for bits in [512, 1024, 2048] {
+                ui.selectable_value(&mut self.key_bits, bits, format!("{bits}-bit"));
+            }
+            if ui.button("Generate").clicked() {
+                errors.clear();
+                self.generate_key();
+            }
+        });
+        ui.add_space(16.0);
+
         ui.subheading("Prime (p)");
         prime_string(ui, &mut self.p, &mut self.p_num);
 
@@ -74,7 +143,7 @@ impl CipherFrame for RsaFrame {
         ui.add_space(16.0);
 
         if ui.button("Calculate Keys").clicked() {
-            self.run_ksa()
+            self.run_ksa(errors)
         }
         ui.add_space(16.0);
 
@@ -105,7 +174,7 @@ impl CipherFrame for RsaFrame {
         self.q_num = rng.gen_prime(64, None);
         self.q = self.q_num.to_str_radix(10);
 
-        self.run_ksa();
+        self.run_ksa(&mut String::new());
     }
 
     crate::simple_cipher! {}
@@ -0,0 +1,131 @@
+use super::CipherFrame;
+use crate::ui_elements::UiElements;
+use ciphers::digital::public_key_ciphers::bip340::Bip340;
+use egui::Ui;
+use rand::{thread_rng, Rng};
+use utils::byte_formatting::ByteFormat;
+
+pub struct SchnorrFrame {
+    cipher: Bip340,
+    private_key: String,
+    aux_rand: String,
+    verify_message: String,
+    verify_signature: String,
+    verified: Option<bool>,
+}
+
+impl Default for SchnorrFrame {
+    fn default() -> Self {
+        let mut out = Self {
+            cipher: Default::default(),
+            private_key: String::new(),
+            aux_rand: ByteFormat::Hex.byte_slice_to_text([0; 32]),
+            verify_message: String::new(),
+            verify_signature: String::new(),
+            verified: None,
+        };
+        out.randomize();
+        out
+    }
+}
+
+impl SchnorrFrame {
+    fn hex_32(text: &mut String) -> Option<[u8; 32]> {
+        *text = text.chars().filter(|c| c.is_ascii_hexdigit()).collect();
+        ByteFormat::Hex
+            .text_to_bytes(text)
+            .ok()
+            .and_then(|bytes| <[u8; 32]>::try_from(bytes).ok())
+    }
+}
+
+impl CipherFrame for SchnorrFrame {
+    fn ui(&mut self, ui: &mut Ui, errors: &mut String) {
+        ui.hyperlink_to(
+            "see the code",
+            "https://github.com/SymmetricChaos/crypto-gui/blob/master/ciphers/src/digital/public_key_ciphers/bip340.rs",
+        );
+        ui.add_space(8.0);
+
+        ui.randomize_reset_cipher(self);
+        ui.add_space(16.0);
+
+        ui.byte_io_mode_cipher(
+            &mut self.cipher.input_format,
+            &mut self.cipher.output_format,
+        );
+        ui.add_space(16.0);
+
+        ui.horizontal(|ui| {
+            ui.subheading("Private Key (hexadecimal)");
+            if ui.button("🎲").on_hover_text("random key").clicked() {
+                self.randomize();
+            }
+        });
+        if ui.control_string(&mut self.private_key).changed() {
+            errors.clear();
+            match Self::hex_32(&mut self.private_key) {
+                Some(key) => {
+                    if let Err(e) = self.cipher.set_private_key(&key) {
+                        *errors = e.to_string();
+                    }
+                }
+                None => *errors = String::from("private key must be exactly 32 bytes"),
+            }
+        }
+        ui.add_space(8.0);
+
+        ui.subheading("Public Key (x coordinate only)");
+        ui.mono(ByteFormat::Hex.byte_slice_to_text(self.cipher.public_key));
+        ui.add_space(8.0);
+
+        ui.horizontal(|ui| {
+            ui.subheading("Auxiliary Randomness (hexadecimal)");
+            if ui.button("🎲").on_hover_text("random bytes").clicked() {
+                self.cipher.aux_rand = thread_rng().gen();
+                self.aux_rand = ByteFormat::Hex.byte_slice_to_text(self.cipher.aux_rand);
+            }
+        });
+        ui.label("Mixed into the nonce to protect against side channel attacks. The signature is still secure if it is all zeroes.");
+        if ui.control_string(&mut self.aux_rand).changed() {
+            errors.clear();
+            match Self::hex_32(&mut self.aux_rand) {
+                Some(bytes) => self.cipher.aux_rand = bytes,
+                None => *errors = String::from("auxiliary randomness must be exactly 32 bytes"),
+            }
+        }
+        ui.add_space(16.0);
+
+        ui.subheading("Verify a Signature");
+        ui.label("Message");
+        ui.control_string(&mut self.verify_message);
+        ui.label("Signature (hexadecimal)");
+        ui.control_string(&mut self.verify_signature);
+        if ui.button("Verify").clicked() {
+            self.verified = match (
+                self.cipher.input_format.text_to_bytes(&self.verify_message),
+                ByteFormat::Hex.text_to_bytes(&self.verify_signature),
+            ) {
+                (Ok(msg), Ok(sig)) => Some(self.cipher.verify(&msg, &sig)),
+                _ => Some(false),
+            }
+        }
+        match self.verified {
+            Some(true) => {
+                ui.label("valid signature");
+            }
+            Some(false) => {
+                ui.error_text("INVALID SIGNATURE");
+            }
+            None => (),
+        }
+        ui.add_space(16.0);
+    }
+
+    fn randomize(&mut self) {
+        self.cipher.generate_key(&mut thread_rng());
+        self.private_key = ByteFormat::Hex.byte_slice_to_text(self.cipher.private_key);
+    }
+
+    crate::simple_cipher! {}
+}
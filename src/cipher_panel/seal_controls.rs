@@ -0,0 +1,90 @@
+use super::CipherFrame;
+use crate::ui_elements::UiElements;
+use ciphers::digital::stream_ciphers::seal::Seal3;
+use egui::Ui;
+use rand::{thread_rng, Rng};
+use utils::errors::GeneralError;
+
+#[derive(Default)]
+pub struct Seal3Frame {
+    cipher: Seal3,
+    key: [u32; 5],
+    n: u32,
+}
+
+impl Seal3Frame {
+    fn set_cipher(&mut self) {
+        let mut cipher = Seal3::with_key_and_nonce(self.key, self.n);
+        cipher.input_format = self.cipher.input_format;
+        cipher.output_format = self.cipher.output_format;
+        self.cipher = cipher;
+    }
+}
+
+impl CipherFrame for Seal3Frame {
+    fn ui(&mut self, ui: &mut Ui, _errors: &mut String) {
+        ui.hyperlink_to(
+            "see the code",
+            "https://github.com/SymmetricChaos/crypto-gui/blob/master/ciphers/src/digital/stream_ciphers/seal.rs",
+        );
+        ui.add_space(8.0);
+
+        ui.randomize_reset_cipher(self);
+        ui.add_space(16.0);
+
+        ui.byte_io_mode_cipher(
+            &mut self.cipher.input_format,
+            &mut self.cipher.output_format,
+        );
+        ui.add_space(16.0);
+
+        ui.subheading("Key (160 bits)");
+        ui.horizontal(|ui| {
+            for i in 0..5 {
+                if ui.u32_hex_edit(&mut self.key[i]).lost_focus() {
+                    self.set_cipher();
+                }
+            }
+        });
+        ui.add_space(8.0);
+
+        ui.subheading("Position (n)");
+        if ui.u32_hex_edit(&mut self.n).lost_focus() {
+            self.set_cipher();
+        }
+        ui.add_space(8.0);
+
+        ui.label("SEAL is a length increasing pseudorandom function that maps the 32-bit position n to up to 64 KiB of keystream. The key is expanded into three tables using the SHA-1 compression function.");
+    }
+
+    fn randomize(&mut self) {
+        let mut rng = thread_rng();
+        rng.fill(&mut self.key);
+        self.n = rng.gen();
+        self.set_cipher();
+    }
+
+    fn reset(&mut self) {
+        *self = Self::default()
+    }
+
+    fn encrypt_string(&self, text: &str) -> Result<String, GeneralError> {
+        let mut bytes = self
+            .cipher
+            .input_format
+            .text_to_bytes(text)
+            .map_err(|e| GeneralError::input(e.to_string()))?;
+        if bytes.len() * 8 > self.cipher.l as usize {
+            return Err(GeneralError::input(format!(
+                "SEAL 3.0 produces at most {} bytes of keystream for each position",
+                self.cipher.l / 8
+            )));
+        }
+        self.cipher.encrypt_bytes(&mut bytes);
+        Ok(self.cipher.output_format.byte_slice_to_text(&bytes))
+    }
+
+    fn decrypt_string(&self, text: &str) -> Result<String, GeneralError> {
+        self.encrypt_string(text)
+    }
+}
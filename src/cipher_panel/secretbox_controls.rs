@@ -0,0 +1,67 @@
+use super::CipherFrame;
+use crate::ui_elements::UiElements;
+use ciphers::digital::stream_ciphers::secretbox::SecretBox;
+use rand::{thread_rng, Rng};
+
+pub struct SecretBoxFrame {
+    cipher: SecretBox,
+}
+
+impl Default for SecretBoxFrame {
+    fn default() -> Self {
+        Self {
+            cipher: Default::default(),
+        }
+    }
+}
+
+impl CipherFrame for SecretBoxFrame {
+    fn ui(&mut self, ui: &mut egui::Ui, _errors: &mut String) {
+        ui.hyperlink_to(
+            "see the code",
+            "https://github.com/SymmetricChaos/crypto-gui/blob/master/ciphers/src/digital/stream_ciphers/secretbox.rs",
+        );
+        ui.add_space(8.0);
+
+        ui.randomize_reset_cipher(self);
+        ui.add_space(16.0);
+
+        ui.byte_io_mode_cipher(
+            &mut self.cipher.cipher.input_format,
+            &mut self.cipher.cipher.output_format,
+        );
+        ui.add_space(16.0);
+
+        ui.horizontal(|ui| {
+            ui.subheading("Key (256 bits)");
+            ui.random_bytes_button(&mut self.cipher.cipher.key);
+        });
+        ui.horizontal(|ui| {
+            for i in 0..8 {
+                ui.u32_hex_edit(&mut self.cipher.cipher.key[i]);
+            }
+        });
+        ui.add_space(8.0);
+        ui.horizontal(|ui| {
+            ui.subheading("Nonce (192 bits)");
+            ui.random_bytes_button(&mut self.cipher.cipher.nonce);
+        });
+        ui.label("The nonce is long enough that it can be chosen randomly for every message.");
+        ui.horizontal(|ui| {
+            for i in 0..6 {
+                ui.u32_hex_edit(&mut self.cipher.cipher.nonce[i]);
+            }
+        });
+        ui.add_space(8.0);
+
+        ui.label("The first 32 bytes of XSalsa20 keystream become the one-time Poly1305 key and the rest encrypts the message. The 16 byte tag is placed before the ciphertext.");
+    }
+
+    fn randomize(&mut self) {
+        let mut rng = thread_rng();
+        rng.fill(&mut self.cipher.cipher.key);
+        rng.fill(&mut self.cipher.cipher.nonce);
+    }
+
+    crate::simple_cipher! {}
+}
@@ -45,7 +45,7 @@ impl CipherFrame for Snow3GFrame {
             ui.random_bytes_button(&mut self.key);
         });
         ui.horizontal(|ui| {
-            for i in 0..8 {
+            for i in 0..4 {
                 if ui.u32_hex_edit(&mut self.key[i]).lost_focus() {
                     self.cipher = Snow3G::with_key_and_iv(self.key, self.iv);
                 }
@@ -58,7 +58,7 @@ impl CipherFrame for Snow3GFrame {
             ui.random_bytes_button(&mut self.iv);
         });
         ui.horizontal(|ui| {
-            for i in 0..8 {
+            for i in 0..4 {
                 if ui.u32_hex_edit(&mut self.iv[i]).lost_focus() {
                     self.cipher = Snow3G::with_key_and_iv(self.key, self.iv);
                 }
@@ -0,0 +1,197 @@
+use super::CipherFrame;
+use crate::ui_elements::UiElements;
+use ciphers::digital::stream_ciphers::snow::{snow1::Snow1, snow2::Snow2, snowv::SnowV};
+use egui::Ui;
+use rand::{thread_rng, Rng};
+use utils::byte_formatting::{u32s_to_bytes_be, ByteFormat};
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+enum SnowSelect {
+    Snow1,
+    #[default]
+    Snow2,
+    SnowV,
+}
+
+pub struct SnowFrame {
+    input_format: ByteFormat,
+    output_format: ByteFormat,
+    selector: SnowSelect,
+    snow1: Snow1,
+    snow2: Snow2,
+    snowv: SnowV,
+    long_key: bool,
+    key: [u32; 8],
+    iv: [u32; 4],
+}
+
+impl Default for SnowFrame {
+    fn default() -> Self {
+        Self {
+            input_format: ByteFormat::Utf8,
+            output_format: ByteFormat::Hex,
+            selector: Default::default(),
+            snow1: Snow1::with_key_128([0; 4]),
+            snow2: Snow2::with_key_and_iv_128([0; 16], [0; 4]),
+            snowv: Default::default(),
+            long_key: false,
+            key: [0; 8],
+            iv: [0; 4],
+        }
+    }
+}
+
+impl SnowFrame {
+    fn key_words(&self) -> usize {
+        if self.selector == SnowSelect::SnowV || self.long_key {
+            8
+        } else {
+            4
+        }
+    }
+
+    fn iv_words(&self) -> usize {
+        if self.selector == SnowSelect::Snow1 {
+            2
+        } else {
+            4
+        }
+    }
+
+    fn set_cipher(&mut self) {
+        let k = self.key;
+        let iv = self.iv;
+        match self.selector {
+            SnowSelect::Snow1 => {
+                self.snow1 = if self.long_key {
+                    Snow1::with_key_and_iv_256(k, [iv[0], iv[1]])
+                } else {
+                    Snow1::with_key_and_iv_128([k[0], k[1], k[2], k[3]], [iv[0], iv[1]])
+                }
+            }
+            SnowSelect::Snow2 => {
+                self.snow2 = if self.long_key {
+                    let mut key = [0; 32];
+                    u32s_to_bytes_be(&mut key, k);
+                    Snow2::with_key_and_iv_256(key, iv)
+                } else {
+                    let mut key = [0; 16];
+                    u32s_to_bytes_be(&mut key, [k[0], k[1], k[2], k[3]]);
+                    Snow2::with_key_and_iv_128(key, iv)
+                }
+            }
+            SnowSelect::SnowV => {
+                let mut key = [0; 32];
+                let mut iv_bytes = [0; 16];
+                u32s_to_bytes_be(&mut key, k);
+                u32s_to_bytes_be(&mut iv_bytes, iv);
+                self.snowv = SnowV::with_key_and_iv(key, iv_bytes);
+            }
+        }
+    }
+}
+
+impl CipherFrame for SnowFrame {
+    fn ui(&mut self, ui: &mut Ui, _errors: &mut String) {
+        ui.hyperlink_to(
+            "see the code",
+            "https://github.com/SymmetricChaos/crypto-gui/blob/master/ciphers/src/digital/stream_ciphers/snow",
+        );
+        ui.add_space(8.0);
+
+        ui.randomize_reset_cipher(self);
+        ui.add_space(16.0);
+
+        ui.horizontal(|ui| {
+            for (variant, name) in [
+                (SnowSelect::Snow1, "SNOW 1.0"),
+                (SnowSelect::Snow2, "SNOW 2.0"),
+                (SnowSelect::SnowV, "SNOW-V"),
+            ] {
+                if ui
+                    .selectable_value(&mut self.selector, variant, name)
+                    .clicked()
+                {
+                    self.set_cipher();
+                }
+            }
+        });
+        ui.add_space(8.0);
+
+        ui.byte_io_mode_cipher(&mut self.input_format, &mut self.output_format);
+        ui.add_space(16.0);
+
+        if self.selector != SnowSelect::SnowV {
+            if ui.checkbox(&mut self.long_key, "256-bit Key").changed() {
+                self.set_cipher();
+            }
+            ui.add_space(4.0);
+        }
+
+        let key_words = self.key_words();
+        ui.horizontal(|ui| {
+            ui.subheading(format!("Key ({} bits)", key_words * 32));
+            if ui.random_bytes_button(&mut self.key).clicked() {
+                self.set_cipher();
+            }
+        });
+        ui.horizontal(|ui| {
+            for i in 0..key_words {
+                if ui.u32_hex_edit(&mut self.key[i]).lost_focus() {
+                    self.set_cipher();
+                }
+            }
+        });
+        ui.add_space(8.0);
+
+        let iv_words = self.iv_words();
+        ui.horizontal(|ui| {
+            ui.subheading(format!("IV ({} bits)", iv_words * 32));
+            if ui.random_bytes_button(&mut self.iv).clicked() {
+                self.set_cipher();
+            }
+        });
+        ui.horizontal(|ui| {
+            for i in 0..iv_words {
+                if ui.u32_hex_edit(&mut self.iv[i]).lost_focus() {
+                    self.set_cipher();
+                }
+            }
+        });
+        ui.add_space(8.0);
+
+        match self.selector {
+            SnowSelect::Snow1 => ui.label("SNOW 1.0 uses a single 32-bit LFSR over GF(2^32) and a two register FSM. Weaknesses found during the NESSIE project led to SNOW 2.0."),
+            SnowSelect::Snow2 => ui.label("SNOW 2.0 replaces the LFSR feedback and the FSM S-box of SNOW 1.0, the latter with the AES round function applied to a single word."),
+            SnowSelect::SnowV => ui.label("SNOW-V has two LFSRs of 16-bit cells and an FSM with three 128-bit registers updated by AES rounds. Only 256-bit keys are used."),
+        };
+    }
+
+    fn randomize(&mut self) {
+        let mut rng = thread_rng();
+        rng.fill(&mut self.key);
+        rng.fill(&mut self.iv);
+        self.set_cipher();
+    }
+
+    fn reset(&mut self) {
+        *self = Self::default()
+    }
+
+    fn encrypt_string(&self, text: &str) -> Result<String, utils::errors::GeneralError> {
+        let mut bytes = self
+            .input_format
+            .text_to_bytes(text)
+            .map_err(|e| utils::errors::GeneralError::input(e.to_string()))?;
+        match self.selector {
+            SnowSelect::Snow1 => self.snow1.encrypt_bytes(&mut bytes),
+            SnowSelect::Snow2 => self.snow2.encrypt_bytes(&mut bytes),
+            SnowSelect::SnowV => self.snowv.encrypt_bytes(&mut bytes),
+        }
+        Ok(self.output_format.byte_slice_to_text(&bytes))
+    }
+
+    fn decrypt_string(&self, text: &str) -> Result<String, utils::errors::GeneralError> {
+        self.encrypt_string(text)
+    }
+}
@@ -0,0 +1,63 @@
+use super::CipherFrame;
+use crate::ui_elements::UiElements;
+use ciphers::digital::stream_ciphers::sosemanuk::Sosemanuk;
+use egui::Ui;
+use rand::{thread_rng, Rng};
+
+#[derive(Default)]
+pub struct SosemanukFrame {
+    cipher: Sosemanuk,
+    key: u128,
+    iv: u128,
+}
+
+impl SosemanukFrame {
+    fn set_cipher(&mut self) {
+        // Cannot fail as the key is always sixteen bytes
+        self.cipher
+            .ksa(&self.key.to_be_bytes(), self.iv.to_be_bytes())
+            .unwrap();
+    }
+}
+
+impl CipherFrame for SosemanukFrame {
+    fn ui(&mut self, ui: &mut Ui, _errors: &mut String) {
+        ui.hyperlink_to(
+            "see the code",
+            "https://github.com/SymmetricChaos/crypto-gui/blob/master/ciphers/src/digital/stream_ciphers/sosemanuk.rs",
+        );
+        ui.add_space(8.0);
+
+        ui.randomize_reset_cipher(self);
+        ui.add_space(16.0);
+
+        ui.byte_io_mode_cipher(
+            &mut self.cipher.input_format,
+            &mut self.cipher.output_format,
+        );
+        ui.add_space(16.0);
+
+        ui.subheading("Key (128 bits)");
+        if ui.u128_hex_edit(&mut self.key).lost_focus() {
+            self.set_cipher();
+        }
+        ui.add_space(8.0);
+
+        ui.subheading("IV (128 bits)");
+        if ui.u128_hex_edit(&mut self.iv).lost_focus() {
+            self.set_cipher();
+        }
+        ui.add_space(8.0);
+
+        ui.label("Sosemanuk is built from a ten word LFSR and a two word finite state machine like those of SNOW 2.0. Every four steps the outputs of the state machine pass through the third Serpent S-box and are added to the words dropped from the LFSR. The initial state is taken from the middle and end of a 24 round Serpent encryption of the IV.");
+    }
+
+    fn randomize(&mut self) {
+        let mut rng = thread_rng();
+        self.key = rng.gen();
+        self.iv = rng.gen();
+        self.set_cipher();
+    }
+
+    crate::simple_cipher! {}
+}
@@ -0,0 +1,69 @@
+use super::CipherFrame;
+use crate::ui_elements::UiElements;
+use ciphers::digital::stream_ciphers::trivium::Trivium;
+use egui::Ui;
+use rand::{thread_rng, Rng};
+
+const MASK_80: u128 = (1 << 80) - 1;
+
+// The last ten bytes of a u128, matching the way the value is displayed
+fn bytes_80(n: u128) -> [u8; 10] {
+    n.to_be_bytes()[6..].try_into().unwrap()
+}
+
+#[derive(Default)]
+pub struct TriviumFrame {
+    cipher: Trivium,
+    key: u128,
+    iv: u128,
+}
+
+impl TriviumFrame {
+    fn set_cipher(&mut self) {
+        self.key &= MASK_80;
+        self.iv &= MASK_80;
+        self.cipher.ksa(bytes_80(self.key), bytes_80(self.iv));
+    }
+}
+
+impl CipherFrame for TriviumFrame {
+    fn ui(&mut self, ui: &mut Ui, _errors: &mut String) {
+        ui.hyperlink_to(
+            "see the code",
+            "https://github.com/SymmetricChaos/crypto-gui/blob/master/ciphers/src/digital/stream_ciphers/trivium.rs",
+        );
+        ui.add_space(8.0);
+
+        ui.randomize_reset_cipher(self);
+        ui.add_space(16.0);
+
+        ui.byte_io_mode_cipher(
+            &mut self.cipher.input_format,
+            &mut self.cipher.output_format,
+        );
+        ui.add_space(16.0);
+
+        ui.subheading("Key (80 bits)");
+        if ui.u128_hex_edit(&mut self.key).lost_focus() {
+            self.set_cipher();
+        }
+        ui.add_space(8.0);
+
+        ui.subheading("IV (80 bits)");
+        if ui.u128_hex_edit(&mut self.iv).lost_focus() {
+            self.set_cipher();
+        }
+        ui.add_space(8.0);
+
+        ui.label("Trivium holds 288 bits of state in three shift registers of 93, 84, and 111 bits. The output of each register is mixed with a single AND gate and fed into the next. The key and IV are loaded into the first two registers and the state is clocked 1152 times before any keystream is produced.");
+    }
+
+    fn randomize(&mut self) {
+        let mut rng = thread_rng();
+        self.key = rng.gen();
+        self.iv = rng.gen();
+        self.set_cipher();
+    }
+
+    crate::simple_cipher! {}
+}
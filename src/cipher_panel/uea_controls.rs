@@ -0,0 +1,186 @@
+use super::CipherFrame;
+use crate::ui_elements::UiElements;
+use ciphers::{
+    digital::stream_ciphers::{
+        kgcore::{uia1, Uea1},
+        snow::uea2::{uia2, Uea2},
+    },
+    Cipher,
+};
+use egui::Ui;
+use rand::{thread_rng, Rng};
+use utils::byte_formatting::{u32s_to_bytes_be, ByteFormat};
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+enum UeaSelect {
+    #[default]
+    Uea1,
+    Uea2,
+}
+
+#[derive(Default)]
+pub struct UeaFrame {
+    uea1: Uea1,
+    uea2: Uea2,
+    selector: UeaSelect,
+    key: [u32; 4],
+    count: u32,
+    bearer: u8,
+    direction: bool,
+    fresh: u32,
+    message: String,
+}
+
+impl UeaFrame {
+    fn key_bytes(&self) -> [u8; 16] {
+        let mut bytes = [0; 16];
+        u32s_to_bytes_be(&mut bytes, self.key);
+        bytes
+    }
+
+    // Both algorithms take the same parameters so they are always kept in sync
+    fn set_cipher(&mut self) {
+        self.bearer &= 0x1f;
+        let key = self.key_bytes();
+        self.uea1.key = key;
+        self.uea1.count = self.count;
+        self.uea1.bearer = self.bearer;
+        self.uea1.direction = self.direction;
+        self.uea2.key = key;
+        self.uea2.count = self.count;
+        self.uea2.bearer = self.bearer;
+        self.uea2.direction = self.direction;
+    }
+
+    fn mac(&self) -> Result<u32, String> {
+        let bytes = ByteFormat::Hex
+            .text_to_bytes(&self.message)
+            .map_err(|_| String::from("message must be given as hexadecimal bytes"))?;
+        let length = bytes.len() * 8;
+        Ok(match self.selector {
+            UeaSelect::Uea1 => uia1(
+                self.key_bytes(),
+                self.count,
+                self.fresh,
+                self.direction,
+                &bytes,
+                length,
+            ),
+            UeaSelect::Uea2 => uia2(
+                self.key_bytes(),
+                self.count,
+                self.fresh,
+                self.direction,
+                &bytes,
+                length,
+            ),
+        })
+    }
+}
+
+impl CipherFrame for UeaFrame {
+    fn ui(&mut self, ui: &mut Ui, _errors: &mut String) {
+        ui.hyperlink_to(
+            "see the code",
+            "https://github.com/SymmetricChaos/crypto-gui/blob/master/ciphers/src/digital/stream_ciphers/kgcore.rs",
+        );
+        ui.add_space(8.0);
+
+        ui.randomize_reset_cipher(self);
+        ui.add_space(16.0);
+
+        ui.horizontal(|ui| {
+            ui.selectable_value(&mut self.selector, UeaSelect::Uea1, "UEA1 (KASUMI)");
+            ui.selectable_value(&mut self.selector, UeaSelect::Uea2, "UEA2 (SNOW 3G)");
+        });
+        ui.add_space(8.0);
+
+        match self.selector {
+            UeaSelect::Uea1 => {
+                ui.byte_io_mode_cipher(&mut self.uea1.input_format, &mut self.uea1.output_format)
+            }
+            UeaSelect::Uea2 => {
+                ui.byte_io_mode_cipher(&mut self.uea2.input_format, &mut self.uea2.output_format)
+            }
+        };
+        ui.add_space(16.0);
+
+        ui.horizontal(|ui| {
+            ui.subheading("Key (128 bits)");
+            if ui.random_bytes_button(&mut self.key).clicked() {
+                self.set_cipher();
+            }
+        });
+        ui.horizontal(|ui| {
+            for i in 0..4 {
+                if ui.u32_hex_edit(&mut self.key[i]).changed() {
+                    self.set_cipher();
+                }
+            }
+        });
+        ui.add_space(8.0);
+
+        ui.subheading("Count");
+        if ui.u32_hex_edit(&mut self.count).changed() {
+            self.set_cipher();
+        }
+        ui.add_space(8.0);
+
+        ui.subheading("Bearer (Limited to 5 Bits)");
+        if ui.u8_hex_edit(&mut self.bearer).lost_focus() {
+            self.set_cipher();
+        }
+        ui.add_space(8.0);
+
+        if ui
+            .checkbox(&mut self.direction, "Direction (Downlink)")
+            .changed()
+        {
+            self.set_cipher();
+        }
+        ui.add_space(16.0);
+
+        match self.selector {
+            UeaSelect::Uea1 => ui.subheading("Integrity (UIA1)"),
+            UeaSelect::Uea2 => ui.subheading("Integrity (UIA2)"),
+        };
+        ui.label("The integrity algorithm uses the same key, count, and direction along with a 32-bit FRESH value in place of the bearer.");
+        ui.add_space(4.0);
+        ui.subheading("Fresh");
+        ui.u32_hex_edit(&mut self.fresh);
+        ui.add_space(4.0);
+        ui.subheading("Message (Hexadecimal)");
+        ui.control_string(&mut self.message);
+        match self.mac() {
+            Ok(mac) => ui.mono(format!("MAC: {:08x}", mac)),
+            Err(e) => ui.error_text(e),
+        };
+    }
+
+    fn randomize(&mut self) {
+        let mut rng = thread_rng();
+        rng.fill(&mut self.key);
+        self.count = rng.gen();
+        self.bearer = rng.gen();
+        self.fresh = rng.gen();
+        self.set_cipher();
+    }
+
+    fn reset(&mut self) {
+        *self = Self::default()
+    }
+
+    fn encrypt_string(&self, text: &str) -> Result<String, utils::errors::GeneralError> {
+        match self.selector {
+            UeaSelect::Uea1 => self.uea1.encrypt(text),
+            UeaSelect::Uea2 => self.uea2.encrypt(text),
+        }
+    }
+
+    fn decrypt_string(&self, text: &str) -> Result<String, utils::errors::GeneralError> {
+        match self.selector {
+            UeaSelect::Uea1 => self.uea1.decrypt(text),
+            UeaSelect::Uea2 => self.uea2.decrypt(text),
+        }
+    }
+}
@@ -0,0 +1,65 @@
+use super::CipherFrame;
+use crate::ui_elements::UiElements;
+use ciphers::digital::stream_ciphers::wake::Wake;
+use egui::Ui;
+use rand::{thread_rng, Rng};
+
+#[derive(Default)]
+pub struct WakeFrame {
+    cipher: Wake,
+    key: [u32; 4],
+}
+
+impl CipherFrame for WakeFrame {
+    fn ui(&mut self, ui: &mut Ui, _errors: &mut String) {
+        ui.hyperlink_to(
+            "see the code",
+            "https://github.com/SymmetricChaos/crypto-gui/blob/master/ciphers/src/digital/stream_ciphers/wake.rs",
+        );
+        ui.add_space(8.0);
+
+        ui.randomize_reset_cipher(self);
+        ui.add_space(16.0);
+
+        ui.byte_io_mode_cipher(
+            &mut self.cipher.input_format,
+            &mut self.cipher.output_format,
+        );
+        ui.add_space(16.0);
+
+        ui.subheading("Key");
+        ui.horizontal(|ui| {
+            for i in 0..4 {
+                if ui.u32_hex_edit(&mut self.key[i]).lost_focus() {
+                    self.cipher.ksa(self.key);
+                }
+            }
+        });
+        ui.add_space(8.0);
+
+        ui.label("WAKE runs in cipher feedback mode. Each word of ciphertext is fed through four registers that are mixed using a key dependent table of 256 words, so encryption and decryption are different operations.");
+        ui.add_space(8.0);
+
+        ui.collapsing("Table", |ui| {
+            egui::Grid::new("wake_table")
+                .num_columns(8)
+                .striped(true)
+                .show(ui, |ui| {
+                    for (n, word) in self.cipher.table[..256].iter().enumerate() {
+                        if n % 8 == 0 && n != 0 {
+                            ui.end_row()
+                        }
+                        ui.mono(format!("{:08x}", word));
+                    }
+                });
+        });
+    }
+
+    fn randomize(&mut self) {
+        let mut rng = thread_rng();
+        rng.fill(&mut self.key);
+        self.cipher.ksa(self.key);
+    }
+
+    crate::simple_cipher! {}
+}
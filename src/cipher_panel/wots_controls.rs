@@ -0,0 +1,122 @@
+use super::CipherFrame;
+use crate::ui_elements::UiElements;
+use ciphers::digital::public_key_ciphers::wots::{Wots, WotsParams};
+use egui::Ui;
+use hashers::sha::Sha256;
+use rand::thread_rng;
+use utils::byte_formatting::ByteFormat;
+
+pub struct WotsFrame {
+    cipher: Wots<Sha256>,
+    verify_message: String,
+    verify_signature: String,
+    verified: Option<bool>,
+}
+
+impl Default for WotsFrame {
+    fn default() -> Self {
+        let mut out = Self {
+            cipher: Default::default(),
+            verify_message: String::new(),
+            verify_signature: String::new(),
+            verified: None,
+        };
+        out.randomize();
+        out
+    }
+}
+
+impl CipherFrame for WotsFrame {
+    fn ui(&mut self, ui: &mut Ui, errors: &mut String) {
+        ui.hyperlink_to(
+            "see the code",
+            "https://github.com/SymmetricChaos/crypto-gui/blob/master/ciphers/src/digital/public_key_ciphers/wots.rs",
+        );
+        ui.add_space(8.0);
+
+        ui.randomize_reset_cipher(self);
+        ui.add_space(16.0);
+
+        ui.byte_io_mode_cipher(
+            &mut self.cipher.input_format,
+            &mut self.cipher.output_format,
+        );
+        ui.add_space(16.0);
+
+        ui.subheading("Winternitz Parameter");
+        ui.horizontal(|ui| {
+            for w in [4, 16] {
+                if ui
+                    .selectable_label(self.cipher.params.w == w, format!("w = {w}"))
+                    .clicked()
+                {
+                    errors.clear();
+                    self.cipher.params = WotsParams::new(Sha256::init, w)
+                        .expect("only valid values of w are offered");
+                    self.randomize();
+                }
+            }
+        });
+        ui.label(format!(
+            "The SHA-256 digest is split into {} digits of {} bits each, followed by {} digits of checksum. Each digit says how many steps along its own hash chain to reveal, so a signature is {} bytes.",
+            self.cipher.params.len1(),
+            self.cipher.params.w.trailing_zeros(),
+            self.cipher.params.len2(),
+            self.cipher.signature_len()
+        ));
+        ui.add_space(16.0);
+
+        ui.horizontal(|ui| {
+            ui.subheading("Key");
+            if ui.button("New Key").clicked() {
+                errors.clear();
+                self.randomize();
+            }
+        });
+        if self.cipher.is_used() {
+            ui.error_text("This key has already signed a message and cannot be used again.");
+        }
+        ui.label("Secret Seed");
+        ui.mono(ByteFormat::Hex.byte_slice_to_text(&self.cipher.secret_seed));
+        ui.label("Public Seed");
+        ui.mono(ByteFormat::Hex.byte_slice_to_text(&self.cipher.public_seed));
+        ui.collapsing("Public Key (ends of the chains)", |ui| {
+            for value in self.cipher.public_key.iter() {
+                ui.mono(ByteFormat::Hex.byte_slice_to_text(value));
+            }
+        });
+        ui.add_space(16.0);
+
+        ui.subheading("Verify a Signature");
+        ui.label("Message");
+        ui.control_string(&mut self.verify_message);
+        ui.label("Signature (hexadecimal)");
+        ui.control_string(&mut self.verify_signature);
+        if ui.button("Verify").clicked() {
+            self.verified = match (
+                self.cipher.input_format.text_to_bytes(&self.verify_message),
+                ByteFormat::Hex.text_to_bytes(&self.verify_signature),
+            ) {
+                (Ok(msg), Ok(sig)) => Some(self.cipher.verify(&msg, &sig)),
+                _ => Some(false),
+            }
+        }
+        match self.verified {
+            Some(true) => {
+                ui.label("valid signature");
+            }
+            Some(false) => {
+                ui.error_text("INVALID SIGNATURE");
+            }
+            None => (),
+        }
+        ui.add_space(16.0);
+    }
+
+    fn randomize(&mut self) {
+        self.cipher.generate_key(&mut thread_rng());
+        self.verified = None;
+    }
+
+    crate::simple_cipher! {}
+}
@@ -0,0 +1,72 @@
+use ciphers::digital::stream_ciphers::chacha::xchacha20poly1305::XChaCha20Poly1305;
+use rand::{thread_rng, Rng};
+
+use crate::ui_elements::UiElements;
+
+use super::CipherFrame;
+
+pub struct XChaCha20Poly1305Frame {
+    cipher: XChaCha20Poly1305,
+}
+
+impl Default for XChaCha20Poly1305Frame {
+    fn default() -> Self {
+        Self {
+            cipher: Default::default(),
+        }
+    }
+}
+
+impl CipherFrame for XChaCha20Poly1305Frame {
+    fn ui(&mut self, ui: &mut egui::Ui, _errors: &mut String) {
+        ui.hyperlink_to(
+            "see the code",
+            "https://github.com/SymmetricChaos/crypto-gui/blob/master/ciphers/src/digital/stream_ciphers/chacha",
+        );
+        ui.add_space(8.0);
+
+        ui.randomize_reset_cipher(self);
+        ui.add_space(16.0);
+
+        ui.byte_io_mode_cipher(
+            &mut self.cipher.cipher.input_format,
+            &mut self.cipher.cipher.output_format,
+        );
+
+        ui.add_space(16.0);
+
+        ui.horizontal(|ui| {
+            ui.subheading("Key (256 bits)");
+            ui.random_bytes_button(&mut self.cipher.cipher.key);
+        });
+        ui.horizontal(|ui| {
+            for i in 0..8 {
+                ui.u32_hex_edit(&mut self.cipher.cipher.key[i]);
+            }
+        });
+        ui.add_space(8.0);
+
+        ui.horizontal(|ui| {
+            ui.subheading("Nonce (192 bits)");
+            ui.random_bytes_button(&mut self.cipher.cipher.nonce);
+        });
+        ui.label("HChaCha20 combines the key with the first 128 bits of the nonce to make a subkey. ChaCha20-Poly1305 is then used with that subkey and a nonce of four zero bytes followed by the last 64 bits.");
+        ui.horizontal(|ui| {
+            for i in 0..6 {
+                ui.u32_hex_edit(&mut self.cipher.cipher.nonce[i]);
+            }
+        });
+
+        ui.add_space(8.0);
+        ui.subheading("Number of Rounds");
+        ui.label("The XChaCha20-Poly1305 standard does not accept a variant number of rounds.");
+    }
+
+    fn randomize(&mut self) {
+        let mut rng = thread_rng();
+        rng.fill(&mut self.cipher.cipher.key);
+        rng.fill(&mut self.cipher.cipher.nonce);
+    }
+
+    crate::simple_cipher! {}
+}
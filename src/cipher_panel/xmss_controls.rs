@@ -0,0 +1,124 @@
+use super::CipherFrame;
+use crate::ui_elements::UiElements;
+use ciphers::digital::public_key_ciphers::{wots::WotsParams, xmss::Xmss};
+use egui::{DragValue, Ui};
+use hashers::sha::Sha256;
+use rand::thread_rng;
+use utils::byte_formatting::ByteFormat;
+
+pub struct XmssFrame {
+    cipher: Xmss<Sha256>,
+    verify_message: String,
+    verify_signature: String,
+    verified: Option<bool>,
+}
+
+impl Default for XmssFrame {
+    fn default() -> Self {
+        // The full height of 10 takes too long to build on every reset
+        let mut out = Self {
+            cipher: Xmss::new(WotsParams::default(), 4),
+            verify_message: String::new(),
+            verify_signature: String::new(),
+            verified: None,
+        };
+        out.randomize();
+        out
+    }
+}
+
+impl CipherFrame for XmssFrame {
+    fn ui(&mut self, ui: &mut Ui, errors: &mut String) {
+        ui.hyperlink_to(
+            "see the code",
+            "https://github.com/SymmetricChaos/crypto-gui/blob/master/ciphers/src/digital/public_key_ciphers/xmss.rs",
+        );
+        ui.add_space(8.0);
+
+        ui.randomize_reset_cipher(self);
+        ui.add_space(16.0);
+
+        ui.byte_io_mode_cipher(
+            &mut self.cipher.input_format,
+            &mut self.cipher.output_format,
+        );
+        ui.add_space(16.0);
+
+        ui.subheading("Tree Height");
+        if ui
+            .add(DragValue::new(&mut self.cipher.height).range(2..=6))
+            .changed()
+        {
+            errors.clear();
+            self.randomize();
+        }
+        ui.label(format!(
+            "The tree has {} leaves, each a WOTS+ key with w = {} and SHA-256 whose {} chain ends are compressed into one value by an L-tree. A signature is {} bytes. OIDs are only registered for heights 10, 16 and 20, these smaller trees use the reserved OID 0.",
+            1 << self.cipher.height,
+            self.cipher.params.w,
+            self.cipher.params.chains(),
+            self.cipher.signature_len()
+        ));
+        ui.add_space(16.0);
+
+        ui.horizontal(|ui| {
+            ui.subheading("Key");
+            if ui.button("New Key").clicked() {
+                errors.clear();
+                self.randomize();
+            }
+        });
+        ui.label("Secret Seed");
+        ui.mono(ByteFormat::Hex.byte_slice_to_text(&self.cipher.secret_seed));
+        ui.label("PRF Key");
+        ui.mono(ByteFormat::Hex.byte_slice_to_text(&self.cipher.prf_key));
+        ui.label("Public Key (OID, root and public seed)");
+        ui.mono(ByteFormat::Hex.byte_slice_to_text(self.cipher.public_key()));
+        if (self.cipher.next_index() as usize) < 1 << self.cipher.height {
+            ui.label(format!(
+                "The next signature will use leaf {} of {}.",
+                self.cipher.next_index(),
+                1 << self.cipher.height
+            ));
+        } else {
+            ui.error_text("Every leaf has been used, a new key is needed.");
+        }
+        ui.add_space(16.0);
+
+        ui.subheading("Verify a Signature");
+        ui.label("Message");
+        ui.control_string(&mut self.verify_message);
+        ui.label("Signature (hexadecimal)");
+        ui.control_string(&mut self.verify_signature);
+        if ui.button("Verify").clicked() {
+            self.verified = match (
+                self.cipher.input_format.text_to_bytes(&self.verify_message),
+                ByteFormat::Hex.text_to_bytes(&self.verify_signature),
+            ) {
+                (Ok(msg), Ok(sig)) => {
+                    Some(self.cipher.verify(&self.cipher.public_key(), &msg, &sig))
+                }
+                _ => Some(false),
+            }
+        }
+        match self.verified {
+            Some(true) => {
+                ui.label("valid signature");
+            }
+            Some(false) => {
+                ui.error_text("INVALID SIGNATURE");
+            }
+            None => (),
+        }
+        ui.add_space(16.0);
+    }
+
+    fn randomize(&mut self) {
+        self.cipher
+            .generate_key(&mut thread_rng())
+            .expect("height is limited to 6");
+        self.verified = None;
+    }
+
+    crate::simple_cipher! {}
+}
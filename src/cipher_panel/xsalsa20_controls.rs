@@ -0,0 +1,99 @@
+use super::CipherFrame;
+use crate::ui_elements::UiElements;
+use ciphers::digital::stream_ciphers::xsalsa20::XSalsa20;
+use egui::Slider;
+use rand::{thread_rng, Rng};
+
+pub struct XSalsa20Frame {
+    cipher: XSalsa20,
+}
+
+impl Default for XSalsa20Frame {
+    fn default() -> Self {
+        Self {
+            cipher: Default::default(),
+        }
+    }
+}
+
+impl XSalsa20Frame {
+    fn start_state(&self) -> String {
+        let mut out = String::new();
+
+        let state = self.cipher.salsa20().create_state(0);
+
+        for line in state.chunks_exact(4) {
+            for word in line {
+                out.push_str(&format!("{:08x?}  ", word))
+            }
+            out.push('\n')
+        }
+
+        out
+    }
+}
+
+impl CipherFrame for XSalsa20Frame {
+    fn ui(&mut self, ui: &mut egui::Ui, _errors: &mut String) {
+        ui.hyperlink_to(
+            "see the code",
+            "https://github.com/SymmetricChaos/crypto-gui/blob/master/ciphers/src/digital/stream_ciphers/xsalsa20.rs",
+        );
+        ui.add_space(8.0);
+
+        ui.randomize_reset_cipher(self);
+        ui.add_space(16.0);
+
+        ui.byte_io_mode_cipher(
+            &mut self.cipher.input_format,
+            &mut self.cipher.output_format,
+        );
+        ui.add_space(16.0);
+
+        ui.horizontal(|ui| {
+            ui.subheading("Key (256 bits)");
+            ui.random_bytes_button(&mut self.cipher.key);
+        });
+        ui.horizontal(|ui| {
+            for i in 0..8 {
+                ui.u32_hex_edit(&mut self.cipher.key[i]);
+            }
+        });
+        ui.add_space(8.0);
+        ui.horizontal(|ui| {
+            ui.subheading("Nonce (192 bits)");
+            ui.random_bytes_button(&mut self.cipher.nonce);
+        });
+        ui.label("The first 128 bits of the nonce are combined with the key by HSalsa20 to produce a subkey. The remaining 64 bits are used as the nonce for Salsa20 with that subkey.");
+        ui.horizontal(|ui| {
+            for i in 0..6 {
+                ui.u32_hex_edit(&mut self.cipher.nonce[i]);
+            }
+        });
+        ui.add_space(8.0);
+        ui.horizontal(|ui| {
+            if ui.small_button("XSalsa20/8").clicked() {
+                self.cipher.rounds = 8;
+            }
+            if ui.small_button("XSalsa20/12").clicked() {
+                self.cipher.rounds = 12;
+            }
+            if ui.small_button("XSalsa20/20").clicked() {
+                self.cipher.rounds = 20;
+            }
+        });
+        ui.add(Slider::new(&mut self.cipher.rounds, 2..=20));
+        ui.add_space(8.0);
+
+        ui.subheading("Initial Salsa20 State");
+        ui.mono(self.start_state());
+    }
+
+    fn randomize(&mut self) {
+        let mut rng = thread_rng();
+        rng.fill(&mut self.cipher.key);
+        rng.fill(&mut self.cipher.nonce);
+    }
+
+    crate::simple_cipher! {}
+}
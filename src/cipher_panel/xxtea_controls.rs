@@ -0,0 +1,52 @@
+use super::CipherFrame;
+use crate::ui_elements::UiElements;
+use ciphers::digital::block_ciphers::tea::xxtea::Xxtea;
+use egui::Ui;
+use rand::{thread_rng, Rng};
+
+#[derive(Default)]
+pub struct XxteaFrame {
+    cipher: Xxtea,
+}
+
+impl CipherFrame for XxteaFrame {
+    fn ui(&mut self, ui: &mut Ui, _errors: &mut String) {
+        ui.hyperlink_to(
+            "see the code",
+            "https://github.com/SymmetricChaos/crypto-gui/blob/master/ciphers/src/digital/block_ciphers/tea/xxtea.rs",
+        );
+        ui.add_space(8.0);
+
+        ui.randomize_reset_cipher(self);
+        ui.add_space(16.0);
+
+        ui.byte_io_mode_cipher(
+            &mut self.cipher.input_format,
+            &mut self.cipher.output_format,
+        );
+
+        ui.add_space(16.0);
+
+        ui.label("XXTEA treats the whole message as a single block so no mode of operation or padding is used. The message must be a multiple of 32 bits and at least 64 bits long.");
+        ui.add_space(8.0);
+
+        ui.horizontal(|ui| {
+            ui.subheading("Key (128 bits)");
+            ui.random_bytes_button(&mut self.cipher.key)
+        });
+        ui.horizontal(|ui| {
+            for i in 0..4 {
+                ui.u32_hex_edit(&mut self.cipher.key[i]);
+            }
+        });
+
+        ui.add_space(16.0);
+    }
+
+    fn randomize(&mut self) {
+        let mut rng = thread_rng();
+        rng.fill(&mut self.cipher.key);
+    }
+
+    crate::simple_cipher! {}
+}
@@ -3,6 +3,7 @@ mod blum_blum_shub_controls;
 mod chacha_controls;
 mod dual_ec_drbg;
 mod geffe_controls;
+mod grain_controls;
 mod halton_controls;
 mod hc128_controls;
 mod hc256_controls;
@@ -15,6 +16,7 @@ mod lehmer_controls;
 mod lfg_controls;
 mod lfsr_controls;
 mod mersenne_twister_controls;
+mod mickey_controls;
 mod middle_square_binary_controls;
 mod middle_square_controls;
 mod naor_reingold_controls;
@@ -28,9 +30,11 @@ mod rule30_controls;
 mod salsa20_controls;
 mod self_shrinking_generator_controls;
 mod shrinking_generator_controls;
+mod sosemanuk_controls;
 mod splitmix_controls;
 mod squares_controls;
 mod threefry_controls;
+mod trivium_controls;
 mod tt800_controls;
 mod vmpcr_controls;
 mod well_controls;
@@ -83,6 +87,7 @@ pub struct RngInterface {
     chacha: chacha_controls::ChaChaFrame,
     dual_ec_drbg: dual_ec_drbg::DualEcFrame,
     geffe: geffe_controls::GeffeFrame,
+    grain: grain_controls::GrainFrame,
     halton: halton_controls::HaltonFrame,
     hc128: hc128_controls::Hc128Frame,
     hc256: hc256_controls::Hc256Frame,
@@ -95,6 +100,7 @@ pub struct RngInterface {
     lfg: lfg_controls::LfgFrame,
     lfsr: lfsr_controls::LfsrFrame,
     mersenne_twister: mersenne_twister_controls::MTFrame,
+    mickey: mickey_controls::MickeyFrame,
     middle_square: middle_square_controls::MiddleSquareFrame,
     middle_square_binary: middle_square_binary_controls::MiddleSquareBinaryFrame,
     naor_reingold: naor_reingold_controls::NaorReingoldFrame,
@@ -108,9 +114,11 @@ pub struct RngInterface {
     salsa20: salsa20_controls::Salsa20Frame,
     self_shrinking_generator: self_shrinking_generator_controls::SelfShrinkingGeneratorFrame,
     shrinking_generator: shrinking_generator_controls::ShrinkingGeneratorFrame,
+    sosemanuk: sosemanuk_controls::SosemanukFrame,
     splitmix: splitmix_controls::SplitmixFrame,
     squares: squares_controls::SquaresFrame,
     threefry: threefry_controls::ThreefryFrame,
+    trivium: trivium_controls::TriviumFrame,
     tt800: tt800_controls::Tt800Frame,
     vmpcr: vmpcr_controls::VmpcrFrame,
     well: well_controls::WellFrame,
@@ -176,11 +184,15 @@ impl RngInterface {
                 RngId::ChaCha,
                 RngId::DualEcDrbg,
                 RngId::Geffe,
+                RngId::Grain,
                 RngId::Hc128,
                 RngId::Hc256,
+                RngId::Mickey2,
                 RngId::NaorReingold,
                 RngId::Rabbit,
                 RngId::Salsa20,
+                RngId::Sosemanuk,
+                RngId::Trivium,
             ],
             active_rng,
             RngCategory::CSPRNG,
@@ -197,6 +209,7 @@ impl RngInterface {
             RngId::ChaCha => &mut self.chacha,
             RngId::DualEcDrbg => &mut self.dual_ec_drbg,
             RngId::Geffe => &mut self.geffe,
+            RngId::Grain => &mut self.grain,
             RngId::Halton => &mut self.halton,
             RngId::Hc128 => &mut self.hc128,
             RngId::Hc256 => &mut self.hc256,
@@ -209,6 +222,7 @@ impl RngInterface {
             RngId::Lfg => &mut self.lfg,
             RngId::Lfsr => &mut self.lfsr,
             RngId::MersenneTwister => &mut self.mersenne_twister,
+            RngId::Mickey2 => &mut self.mickey,
             RngId::MiddleSquare => &mut self.middle_square,
             RngId::MiddleSquareBinary => &mut self.middle_square_binary,
             RngId::NaorReingold => &mut self.naor_reingold,
@@ -222,9 +236,11 @@ impl RngInterface {
             RngId::Salsa20 => &mut self.salsa20,
             RngId::SelfShrinkingGenerator => &mut self.self_shrinking_generator,
             RngId::ShrinkingGenerator => &mut self.shrinking_generator,
+            RngId::Sosemanuk => &mut self.sosemanuk,
             RngId::Splitmix => &mut self.splitmix,
             RngId::Squares => &mut self.squares,
             RngId::Threefry => &mut self.threefry,
+            RngId::Trivium => &mut self.trivium,
             RngId::Tt800 => &mut self.tt800,
             RngId::Vmpcr => &mut self.vmpcr,
             RngId::Well => &mut self.well,
@@ -0,0 +1,72 @@
+use super::ClassicRngFrame;
+use crate::ui_elements::{generate_randoms_box, UiElements};
+use rand::{thread_rng, Rng};
+use rngs::sosemanuk::Sosemanuk;
+
+pub struct SosemanukFrame {
+    rng: Sosemanuk,
+    key: u128,
+    iv: u128,
+    n_random: usize,
+    randoms: String,
+}
+
+impl Default for SosemanukFrame {
+    fn default() -> Self {
+        Self {
+            rng: Default::default(),
+            key: 0,
+            iv: 0,
+            n_random: 1,
+            randoms: String::new(),
+        }
+    }
+}
+
+impl SosemanukFrame {
+    fn set_rng(&mut self) {
+        self.rng = Sosemanuk::with_key_and_iv(&self.key.to_be_bytes(), self.iv.to_be_bytes());
+    }
+}
+
+impl ClassicRngFrame for SosemanukFrame {
+    fn ui(&mut self, ui: &mut egui::Ui, _errors: &mut String) {
+        ui.hyperlink_to(
+            "see the code",
+            "https://github.com/SymmetricChaos/crypto-gui/blob/master/rngs/src/sosemanuk.rs",
+        );
+
+        ui.add_space(8.0);
+        ui.randomize_reset_rng(self);
+
+        ui.add_space(8.0);
+        ui.subheading("Key");
+        if ui.u128_hex_edit(&mut self.key).lost_focus() {
+            self.set_rng();
+        }
+
+        ui.add_space(8.0);
+        ui.subheading("Nonce");
+        if ui.u128_hex_edit(&mut self.iv).lost_focus() {
+            self.set_rng();
+        }
+
+        ui.add_space(8.0);
+        generate_randoms_box(ui, &mut self.rng, &mut self.n_random, &mut self.randoms);
+    }
+
+    fn rng(&mut self) -> &mut dyn rngs::SimpleRng {
+        &mut self.rng
+    }
+
+    fn randomize(&mut self) {
+        let mut rng = thread_rng();
+        self.key = rng.gen();
+        self.iv = rng.gen();
+        self.set_rng();
+    }
+
+    fn reset(&mut self) {
+        *self = Self::default()
+    }
+}
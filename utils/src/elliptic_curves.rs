@@ -1,8 +1,66 @@
+use crate::errors::GeneralError;
 use crypto_bigint::{NonZero, Zero, U256};
-use num::One;
+use num::{BigUint, One};
 use std::fmt::Display;
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+pub fn u256_to_biguint(x: &U256) -> BigUint {
+    BigUint::from_bytes_be(&x.to_be_bytes())
+}
+
+/// Panics if the value does not fit in 256 bits
+pub fn biguint_to_u256(x: &BigUint) -> U256 {
+    let bytes = x.to_bytes_be();
+    assert!(bytes.len() <= 32, "value does not fit in 256 bits");
+    let mut buf = [0; 32];
+    buf[32 - bytes.len()..].copy_from_slice(&bytes);
+    U256::from_be_slice(&buf)
+}
+
+/// Square root modulo an odd prime by the Tonelli-Shanks algorithm, or None if a is not a square
+pub fn sqrt_mod_prime(a: &BigUint, p: &BigUint) -> Option<BigUint> {
+    let one = BigUint::one();
+    let a = a % p;
+    if num::Zero::is_zero(&a) {
+        return Some(a);
+    }
+    let pm1 = p - 1_u32;
+    if a.modpow(&(&pm1 >> 1), p) != one {
+        return None;
+    }
+    // A single exponentiation suffices for the common case of p = 3 (mod 4)
+    if p % 4_u32 == BigUint::from(3_u32) {
+        return Some(a.modpow(&((p + 1_u32) >> 2), p));
+    }
+
+    // Write p - 1 = q * 2^s and find a nonresidue z
+    let s = pm1.trailing_zeros().unwrap();
+    let q = &pm1 >> s;
+    let mut z = BigUint::from(2_u32);
+    while z.modpow(&(&pm1 >> 1), p) != pm1 {
+        z += 1_u32;
+    }
+
+    let mut m = s;
+    let mut c = z.modpow(&q, p);
+    let mut t = a.modpow(&q, p);
+    let mut r = a.modpow(&((&q + 1_u32) >> 1), p);
+    while t != one {
+        let mut i = 0;
+        let mut t2 = t.clone();
+        while t2 != one {
+            t2 = &t2 * &t2 % p;
+            i += 1;
+        }
+        let b = c.modpow(&(&one << (m - i - 1)), p);
+        m = i;
+        c = &b * &b % p;
+        t = t * &c % p;
+        r = r * b % p;
+    }
+    Some(r)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct EcPoint {
     pub x: Option<U256>,
     pub y: Option<U256>,
@@ -248,11 +306,224 @@ impl FiniteEllipticCurve {
     }
 }
 
+impl FiniteEllipticCurve {
+    /// Number of bytes in an encoded coordinate
+    pub fn byte_len(&self) -> usize {
+        (self.m.bits() as usize).div_ceil(8)
+    }
+
+    /// Find a point with the given x coordinate and the given parity of y
+    pub fn lift_x(&self, x: &U256, odd: bool) -> Option<EcPoint> {
+        let p = u256_to_biguint(&self.m);
+        let x_big = u256_to_biguint(x);
+        let rhs = (&x_big * &x_big * &x_big
+            + u256_to_biguint(&self.a) * &x_big
+            + u256_to_biguint(&self.b))
+            % &p;
+        let mut y = sqrt_mod_prime(&rhs, &p)?;
+        if y.bit(0) != odd {
+            if num::Zero::is_zero(&y) {
+                return None;
+            }
+            y = &p - y;
+        }
+        Some(EcPoint::from_u256(*x, biguint_to_u256(&y)))
+    }
+
+    /// SEC 1 encoding of a point. Uncompressed points are 0x04 followed by both coordinates, compressed points give
+    /// only x, preceded by 0x02 or 0x03 for the parity of y. The point at infinity is a single zero byte.
+    pub fn encode_point(&self, p: &EcPoint, compressed: bool) -> Vec<u8> {
+        if p.is_inf() {
+            return vec![0x00];
+        }
+        let len = self.byte_len();
+        let x = p.x.unwrap().to_be_bytes();
+        let y = p.y.unwrap().to_be_bytes();
+        let mut out = Vec::with_capacity(2 * len + 1);
+        if compressed {
+            out.push(0x02 | (y[31] & 1));
+            out.extend_from_slice(&x[32 - len..]);
+        } else {
+            out.push(0x04);
+            out.extend_from_slice(&x[32 - len..]);
+            out.extend_from_slice(&y[32 - len..]);
+        }
+        out
+    }
+
+    /// Decode a SEC 1 point, rejecting anything that is not on the curve
+    pub fn decode_point(&self, bytes: &[u8]) -> Result<EcPoint, GeneralError> {
+        let len = self.byte_len();
+        let coordinate = |b: &[u8]| {
+            let mut buf = [0; 32];
+            buf[32 - len..].copy_from_slice(b);
+            let c = U256::from_be_slice(&buf);
+            if c >= *self.m {
+                Err(GeneralError::input("coordinate is not reduced modulo p"))
+            } else {
+                Ok(c)
+            }
+        };
+        let point = match bytes {
+            [0x00] => EcPoint::point_at_inf(),
+            [0x02 | 0x03, x @ ..] if x.len() == len => self
+                .lift_x(&coordinate(x)?, bytes[0] == 0x03)
+                .ok_or(GeneralError::input("no point has this x coordinate"))?,
+            [0x04, xy @ ..] if xy.len() == 2 * len => {
+                EcPoint::from_u256(coordinate(&xy[..len])?, coordinate(&xy[len..])?)
+            }
+            _ => return Err(GeneralError::input("invalid SEC 1 point encoding")),
+        };
+        if !self.on_curve(&point) {
+            return Err(GeneralError::input("point is not on the curve"));
+        }
+        Ok(point)
+    }
+}
+
+/// A curve together with a base point of prime order, everything needed for elliptic curve cryptography
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EcDomain {
+    pub curve: FiniteEllipticCurve,
+    pub generator: EcPoint,
+    pub order: U256,
+    pub cofactor: u64,
+}
+
+impl EcDomain {
+    pub fn order_bits(&self) -> usize {
+        self.order.bits() as usize
+    }
+
+    /// Multiply the base point
+    pub fn mul_generator(&self, s: &U256) -> EcPoint {
+        self.curve.scalar_mul(&self.generator, s)
+    }
+
+    /// A public key must be a point on the curve other than infinity and, when the cofactor is not 1, lie in the
+    /// subgroup generated by the base point
+    pub fn is_valid_public_key(&self, q: &EcPoint) -> bool {
+        if !q.is_valid() || q.is_inf() || !self.curve.on_curve(q) {
+            return false;
+        }
+        self.cofactor == 1 || self.curve.scalar_mul(q, &self.order).is_inf()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NamedCurve {
+    P224,
+    P256,
+    Secp256k1,
+    BrainpoolP256r1,
+    Textbook17,
+    Textbook97,
+}
+
+impl NamedCurve {
+    pub fn name(&self) -> &'static str {
+        match self {
+            NamedCurve::P224 => "P-224",
+            NamedCurve::P256 => "P-256",
+            NamedCurve::Secp256k1 => "secp256k1",
+            NamedCurve::BrainpoolP256r1 => "brainpoolP256r1",
+            NamedCurve::Textbook17 => "y² = x³ + 2x + 2 (mod 17)",
+            NamedCurve::Textbook97 => "y² = x³ + 2x + 3 (mod 97)",
+        }
+    }
+
+    // https://nvlpubs.nist.gov/nistpubs/SpecialPublications/NIST.SP.800-186.pdf
+    // https://www.secg.org/sec2-v2.pdf
+    // https://datatracker.ietf.org/doc/html/rfc5639
+    pub fn domain(&self) -> EcDomain {
+        match self {
+            NamedCurve::P224 => EcDomain {
+                curve: FiniteEllipticCurve::from_be_hex(
+                    "00000000fffffffffffffffffffffffffffffffefffffffffffffffffffffffe",
+                    "00000000b4050a850c04b3abf54132565044b0b7d7bfd8ba270b39432355ffb4",
+                    "00000000ffffffffffffffffffffffffffffffff000000000000000000000001",
+                ),
+                generator: EcPoint::from_be_hex(
+                    "00000000b70e0cbd6bb4bf7f321390b94a03c1d356c21122343280d6115c1d21",
+                    "00000000bd376388b5f723fb4c22dfe6cd4375a05a07476444d5819985007e34",
+                ),
+                order: U256::from_be_hex(
+                    "00000000ffffffffffffffffffffffffffff16a2e0b8f03e13dd29455c5c2a3d",
+                ),
+                cofactor: 1,
+            },
+            NamedCurve::P256 => EcDomain {
+                curve: FiniteEllipticCurve::p256(),
+                generator: EcPoint::from_be_hex(
+                    "6b17d1f2e12c4247f8bce6e563a440f277037d812deb33a0f4a13945d898c296",
+                    "4fe342e2fe1a7f9b8ee7eb4a7c0f9e162bce33576b315ececbb6406837bf51f5",
+                ),
+                order: U256::from_be_hex(
+                    "ffffffff00000000ffffffffffffffffbce6faada7179e84f3b9cac2fc632551",
+                ),
+                cofactor: 1,
+            },
+            NamedCurve::Secp256k1 => EcDomain {
+                curve: FiniteEllipticCurve::from_be_hex(
+                    "0000000000000000000000000000000000000000000000000000000000000000",
+                    "0000000000000000000000000000000000000000000000000000000000000007",
+                    "fffffffffffffffffffffffffffffffffffffffffffffffffffffffefffffc2f",
+                ),
+                generator: EcPoint::from_be_hex(
+                    "79be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798",
+                    "483ada7726a3c4655da4fbfc0e1108a8fd17b448a68554199c47d08ffb10d4b8",
+                ),
+                order: U256::from_be_hex(
+                    "fffffffffffffffffffffffffffffffebaaedce6af48a03bbfd25e8cd0364141",
+                ),
+                cofactor: 1,
+            },
+            NamedCurve::BrainpoolP256r1 => EcDomain {
+                curve: FiniteEllipticCurve::from_be_hex(
+                    "7d5a0975fc2c3057eef67530417affe7fb8055c126dc5c6ce94a4b44f330b5d9",
+                    "26dc5c6ce94a4b44f330b5d9bbd77cbf958416295cf7e1ce6bccdc18ff8c07b6",
+                    "a9fb57dba1eea9bc3e660a909d838d726e3bf623d52620282013481d1f6e5377",
+                ),
+                generator: EcPoint::from_be_hex(
+                    "8bd2aeb9cb7e57cb2c4b482ffc81b7afb9de27e1e3bd23c23a4453bd9ace3262",
+                    "547ef835c3dac4fd97f8461a14611dc9c27745132ded8e545c1d54c72f046997",
+                ),
+                order: U256::from_be_hex(
+                    "a9fb57dba1eea9bc3e660a909d838d718c397aa3b561a6f7901e0e82974856a7",
+                ),
+                cofactor: 1,
+            },
+            // Small curves where every step can be followed by hand
+            NamedCurve::Textbook17 => EcDomain {
+                curve: FiniteEllipticCurve::from_u64(2, 2, 17),
+                generator: EcPoint::from_u64(5, 1),
+                order: U256::from_u64(19),
+                cofactor: 1,
+            },
+            NamedCurve::Textbook97 => EcDomain {
+                curve: FiniteEllipticCurve::from_u64(2, 3, 97),
+                generator: EcPoint::from_u64(3, 6),
+                order: U256::from_u64(5),
+                cofactor: 20,
+            },
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
 
     use super::*;
 
+    const NAMED: [NamedCurve; 6] = [
+        NamedCurve::P224,
+        NamedCurve::P256,
+        NamedCurve::Secp256k1,
+        NamedCurve::BrainpoolP256r1,
+        NamedCurve::Textbook17,
+        NamedCurve::Textbook97,
+    ];
+
     #[test]
     fn generating_point() {
         let curve = FiniteEllipticCurve::from_u64(0, 3, 11);
@@ -286,4 +557,71 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn named_curves() {
+        for named in NAMED {
+            let domain = named.domain();
+            assert!(domain.curve.on_curve(&domain.generator), "{}", named.name());
+            assert!(
+                domain.mul_generator(&domain.order).is_inf(),
+                "{}",
+                named.name()
+            );
+            assert!(domain.is_valid_public_key(&domain.generator));
+        }
+    }
+
+    #[test]
+    fn textbook_point_counts() {
+        // Brute force count of the points, including infinity
+        for (named, count) in [(NamedCurve::Textbook17, 19), (NamedCurve::Textbook97, 100)] {
+            let domain = named.domain();
+            let mut n = 1;
+            for x in 0..domain.curve.m.as_words()[0] {
+                for y in 0..domain.curve.m.as_words()[0] {
+                    if domain.curve.on_curve(&EcPoint::from_u64(x, y)) {
+                        n += 1
+                    }
+                }
+            }
+            assert_eq!(count, n);
+            assert_eq!(count, domain.cofactor * domain.order.as_words()[0]);
+        }
+    }
+
+    #[test]
+    fn sec1_encoding() {
+        for named in NAMED {
+            let domain = named.domain();
+            let mut p = domain.generator.clone();
+            for _ in 0..4 {
+                for compressed in [true, false] {
+                    let bytes = domain.curve.encode_point(&p, compressed);
+                    assert_eq!(p, domain.curve.decode_point(&bytes).unwrap());
+                }
+                p = domain.curve.double(&p);
+            }
+        }
+        let curve = NamedCurve::P256.domain().curve;
+        assert!(curve.decode_point(&[0x04; 65]).is_err());
+        assert!(curve.decode_point(&[0x02; 32]).is_err());
+        assert_eq!(
+            EcPoint::point_at_inf(),
+            curve.decode_point(&[0x00]).unwrap()
+        );
+    }
+
+    #[test]
+    fn tonelli_shanks() {
+        // 17 = 1 (mod 16) which needs the full algorithm
+        let p = BigUint::from(17_u32);
+        for a in 0..17_u32 {
+            let a = BigUint::from(a);
+            if let Some(r) = sqrt_mod_prime(&a, &p) {
+                assert_eq!(a, &r * &r % &p);
+            }
+        }
+        assert_eq!(None, sqrt_mod_prime(&BigUint::from(3_u32), &p));
+    }
 }
@@ -0,0 +1,151 @@
+use num::{BigInt, BigRational, One, Signed, Zero};
+
+// Lattice basis reduction with exact rational arithmetic. Floating point LLL is much faster but can go wrong on
+// badly conditioned bases, exact arithmetic is slow but always gives the right answer, which is what matters for
+// the small lattices used to demonstrate attacks.
+
+fn dot(a: &[BigInt], b: &[BigInt]) -> BigInt {
+    a.iter().zip(b).map(|(x, y)| x * y).sum()
+}
+
+/// Gram-Schmidt orthogonalization of the rows of a basis. Returns the coefficients mu[i][j] = <b_i, b*_j>/<b*_j, b*_j>
+/// for j < i and the squared lengths of the orthogonalized vectors b*_i.
+pub fn gram_schmidt(basis: &[Vec<BigInt>]) -> (Vec<Vec<BigRational>>, Vec<BigRational>) {
+    let n = basis.len();
+    let mut mu = vec![vec![BigRational::zero(); n]; n];
+    let mut b_star: Vec<Vec<BigRational>> = Vec::with_capacity(n);
+    let mut norms = Vec::with_capacity(n);
+    for i in 0..n {
+        let mut v: Vec<BigRational> = basis[i]
+            .iter()
+            .map(|x| BigRational::from_integer(x.clone()))
+            .collect();
+        for j in 0..i {
+            let m = basis[i]
+                .iter()
+                .zip(&b_star[j])
+                .map(|(x, y)| y * BigRational::from_integer(x.clone()))
+                .fold(BigRational::zero(), |acc, t| acc + t)
+                / &norms[j];
+            for (a, b) in v.iter_mut().zip(&b_star[j]) {
+                *a -= &m * b;
+            }
+            mu[i][j] = m;
+        }
+        norms.push(v.iter().fold(BigRational::zero(), |acc, x| acc + x * x));
+        b_star.push(v);
+    }
+    (mu, norms)
+}
+
+/// LLL reduction of a basis given as rows, which must be linearly independent. The parameter delta should be between
+/// 1/4 and 1, larger values give a better basis more slowly. The first vector of the result is at most
+/// (4/(4 delta - 1))^((n-1)/2) times longer than the shortest vector in the lattice, and in practice is often the
+/// shortest.
+pub fn lll_with_delta(basis: &[Vec<BigInt>], delta: &BigRational) -> Vec<Vec<BigInt>> {
+    let n = basis.len();
+    let mut b = basis.to_vec();
+    if n < 2 {
+        return b;
+    }
+    let (mut mu, mut norms) = gram_schmidt(&b);
+    let half = BigRational::new(BigInt::one(), BigInt::from(2));
+
+    // Make |mu[k][j]| <= 1/2 by subtracting a multiple of b_j from b_k
+    let reduce = |b: &mut Vec<Vec<BigInt>>, mu: &mut Vec<Vec<BigRational>>, k: usize, j: usize| {
+        if mu[k][j].abs() > half {
+            let q = mu[k][j].round();
+            let q_int = q.to_integer();
+            let bj = b[j].clone();
+            for (x, y) in b[k].iter_mut().zip(&bj) {
+                *x -= &q_int * y;
+            }
+            let (lower, upper) = mu.split_at_mut(k);
+            for (a, b) in upper[0][..j].iter_mut().zip(&lower[j][..j]) {
+                *a -= &q * b;
+            }
+            mu[k][j] -= q;
+        }
+    };
+
+    let mut k = 1;
+    while k < n {
+        reduce(&mut b, &mut mu, k, k - 1);
+        let m = mu[k][k - 1].clone();
+        // Lovasz condition
+        if norms[k] >= (delta - &m * &m) * &norms[k - 1] {
+            for j in (0..k - 1).rev() {
+                reduce(&mut b, &mut mu, k, j);
+            }
+            k += 1;
+        } else {
+            // Swap b_k and b_(k-1) and update the orthogonalization rather than recomputing it
+            b.swap(k, k - 1);
+            let new_norm = &norms[k] + &m * &m * &norms[k - 1];
+            mu[k][k - 1] = &m * &norms[k - 1] / &new_norm;
+            norms[k] = &norms[k - 1] * &norms[k] / &new_norm;
+            norms[k - 1] = new_norm;
+            let (lower, upper) = mu.split_at_mut(k);
+            lower[k - 1][..k - 1].swap_with_slice(&mut upper[0][..k - 1]);
+            for i in k + 1..n {
+                let t = mu[i][k].clone();
+                mu[i][k] = &mu[i][k - 1] - &m * &t;
+                mu[i][k - 1] = t + &mu[k][k - 1] * &mu[i][k];
+            }
+            k = (k - 1).max(1);
+        }
+    }
+    b
+}
+
+/// LLL reduction with the usual delta = 3/4
+pub fn lll(basis: &[Vec<BigInt>]) -> Vec<Vec<BigInt>> {
+    lll_with_delta(basis, &BigRational::new(BigInt::from(3), BigInt::from(4)))
+}
+
+/// Squared Euclidean length of a vector
+pub fn norm_squared(v: &[BigInt]) -> BigInt {
+    dot(v, v)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    fn basis(rows: &[&[i64]]) -> Vec<Vec<BigInt>> {
+        rows.iter()
+            .map(|r| r.iter().map(|x| BigInt::from(*x)).collect())
+            .collect()
+    }
+
+    #[test]
+    fn small_example() {
+        let reduced = lll(&basis(&[&[1, 1, 1], &[-1, 0, 2], &[3, 5, 6]]));
+        assert_eq!(basis(&[&[0, 1, 0], &[1, 0, 1], &[-1, 0, 2]]), reduced);
+    }
+
+    #[test]
+    fn reduced_basis_properties() {
+        let b = basis(&[
+            &[201, 37, 0, 0],
+            &[1648, 297, 1, 0],
+            &[-913, 454, 7, 2],
+            &[51, -817, 88, 1001],
+        ]);
+        let reduced = lll(&b);
+        let (mu, norms) = gram_schmidt(&reduced);
+        let half = BigRational::new(BigInt::one(), BigInt::from(2));
+        let delta = BigRational::new(BigInt::from(3), BigInt::from(4));
+        for i in 1..reduced.len() {
+            for m in &mu[i][..i] {
+                assert!(m.abs() <= half);
+            }
+            assert!(norms[i] >= (&delta - &mu[i][i - 1] * &mu[i][i - 1]) * &norms[i - 1]);
+        }
+        // The lattice is unchanged, so the volume is the same
+        let volume = |n: &[BigRational]| n.iter().fold(BigRational::one(), |acc, x| acc * x);
+        assert_eq!(volume(&gram_schmidt(&b).1), volume(&norms));
+        assert!(norm_squared(&reduced[0]) <= norm_squared(&b[0]));
+    }
+}
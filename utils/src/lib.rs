@@ -7,6 +7,7 @@ pub mod elliptic_curves;
 pub mod errors;
 pub mod finite_int;
 pub mod grid;
+pub mod lattice;
 pub mod math_functions;
 pub mod other_word_sizes;
 pub mod padding;
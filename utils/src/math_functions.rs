@@ -1,4 +1,4 @@
-use num::{bigint::ToBigInt, integer::Roots, BigInt, Integer, One, ToPrimitive, Zero};
+use num::{bigint::ToBigInt, integer::Roots, BigInt, BigUint, Integer, One, ToPrimitive, Zero};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Parity {
@@ -44,6 +44,19 @@ pub fn modular_division<N: ToBigInt>(n: &N, d: &N, m: &N) -> Option<BigInt> {
     )
 }
 
+/// Find the unique x less than the product of the moduli with x = r_i mod m_i for each residue. Returns None if the
+/// moduli are not pairwise coprime.
+pub fn crt(residues: &[BigUint], moduli: &[BigUint]) -> Option<BigUint> {
+    let product: BigUint = moduli.iter().product();
+    let mut x = BigUint::zero();
+    for (r, m) in residues.iter().zip(moduli) {
+        let partial = &product / m;
+        let inv = BigUint::try_from(mul_inv(&(&partial % m), m)?).ok()?;
+        x += r * partial * inv;
+    }
+    Some(x % product)
+}
+
 // We're not going to deal with big numbers so this thse crude factorizations are plenty
 pub fn factors<N: Integer + Copy + ToPrimitive>(n: N) -> Vec<N> {
     let mut out = Vec::new();
@@ -132,6 +145,15 @@ mod math_tests {
         assert_eq!(10, x);
     }
 
+    #[test]
+    fn test_crt() {
+        let moduli = [3_u32, 5, 7].map(BigUint::from);
+        let residues = [2_u32, 3, 2].map(BigUint::from);
+        assert_eq!(Some(BigUint::from(23_u32)), crt(&residues, &moduli));
+        let moduli = [4_u32, 6].map(BigUint::from);
+        assert_eq!(None, crt(&residues[..2], &moduli));
+    }
+
     #[test]
     fn test_incr_array_ctr() {
         let mut ctr = [0x00, 0x00, 0xfe, 0xff, 0xff];
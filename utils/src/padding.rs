@@ -76,6 +76,11 @@ pub fn strip_pkcs5_padding(bytes: &mut Vec<u8>) -> Result<(), PaddingError> {
     let n_padding = *bytes.iter().last().ok_or(PaddingError(String::from(
         "PKCS padded ciphertext cannot have zero length",
     )))?;
+    if n_padding == 0 {
+        return Err(PaddingError(String::from(
+            "invalid PKCS padding, final byte cannot be zero",
+        )));
+    }
     for _ in 0..n_padding {
         let p = bytes.pop();
         if p == Some(n_padding) {
@@ -111,6 +116,11 @@ pub fn strip_ansi923_padding(bytes: &mut Vec<u8>) -> Result<(), PaddingError> {
     let n_padding = bytes.pop().ok_or(PaddingError(String::from(
         "ANSI X9.23 padded ciphertext cannot have zero length",
     )))?;
+    if n_padding == 0 {
+        return Err(PaddingError(String::from(
+            "invalid ANSI X9.23 padding, final byte cannot be zero",
+        )));
+    }
 
     for _ in 0..(n_padding - 1) {
         let p = bytes.pop();
@@ -225,6 +235,12 @@ mod padding_tests {
         assert_eq!(vec![0x01, 0x02, 0xff, 0xff, 0xff, 0xff, 0xff, 0x80], bytes);
     }
 
+    #[test]
+    fn test_zero_length_padding_rejected() {
+        assert!(strip_pkcs5_padding(&mut vec![0x01, 0x02, 0x00]).is_err());
+        assert!(strip_ansi923_padding(&mut vec![0x01, 0x02, 0x00]).is_err());
+    }
+
     #[test]
     fn test_ansi_padding() {
         let mut bytes = vec![0x01, 0x02, 0xff, 0x80];
@@ -1,5 +1,6 @@
 use mod_exp::mod_exp;
-use num::{Integer, Unsigned};
+use num::{BigUint, Integer, One, Unsigned, Zero};
+use rand::Rng;
 use std::collections::{BTreeMap, HashMap};
 
 pub fn prime_factorization<N: Integer + Copy + Unsigned>(n: N) -> Vec<N> {
@@ -26,17 +27,21 @@ pub fn prime_factorization_map<N: Integer + Copy + Unsigned>(n: N) -> BTreeMap<N
     }
     let mut out = BTreeMap::new();
     let mut n = n;
-    let mut f = N::one();
-    while !n.is_one() {
-        f = f + N::one();
+    let mut f = N::one() + N::one();
+    // Once f*f exceeds what remains of n it must be prime. Dividing rather than squaring avoids overflow.
+    while f <= n / f {
         let mut ctr = 0;
         while n.is_multiple_of(&f) {
             ctr += 1;
             n = n / f;
         }
         if ctr != 0 {
-            out.insert(n, ctr);
+            out.insert(f, ctr);
         }
+        f = f + N::one();
+    }
+    if !n.is_one() {
+        *out.entry(n).or_insert(0) += 1;
     }
     out
 }
@@ -182,3 +187,152 @@ pub fn is_prime64<N: Into<u64>>(n: N) -> bool {
     }
     true
 }
+
+// Odd primes below 256, used to quickly discard most composites before Miller-Rabin
+const SMALL_PRIMES: [u32; 53] = [
+    3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47, 53, 59, 61, 67, 71, 73, 79, 83, 89, 97,
+    101, 103, 107, 109, 113, 127, 131, 137, 139, 149, 151, 157, 163, 167, 173, 179, 181, 191, 193,
+    197, 199, 211, 223, 227, 229, 233, 239, 241, 251,
+];
+
+/// Uniformly random integer with at most the given number of bits
+pub fn random_biguint_bits<R: Rng>(bits: u64, rng: &mut R) -> BigUint {
+    let mut bytes = vec![0_u8; bits.div_ceil(8) as usize];
+    rng.fill(&mut bytes[..]);
+    if !bits.is_multiple_of(8) {
+        bytes[0] &= (1 << (bits % 8)) - 1;
+    }
+    BigUint::from_bytes_be(&bytes)
+}
+
+/// Uniformly random integer in the range [0, bound), found by rejection sampling
+pub fn random_biguint_below<R: Rng>(bound: &BigUint, rng: &mut R) -> BigUint {
+    assert!(!bound.is_zero(), "bound must be positive");
+    loop {
+        let n = random_biguint_bits(bound.bits(), rng);
+        if &n < bound {
+            return n;
+        }
+    }
+}
+
+// Probabilistic primality test for arbitrarily large integers
+// First checks small prime factors then applies Miller-Rabin with random witnesses. A composite passes each round
+// with probability at most 1/4.
+pub fn is_probable_prime<R: Rng>(n: &BigUint, rounds: usize, rng: &mut R) -> bool {
+    let one = BigUint::one();
+    let two = BigUint::from(2_u32);
+    if n < &two {
+        return false;
+    }
+    if n.is_even() {
+        return n == &two;
+    }
+    for p in SMALL_PRIMES {
+        if n == &BigUint::from(p) {
+            return true;
+        }
+        if (n % p).is_zero() {
+            return false;
+        }
+    }
+
+    let n_minus_one = n - &one;
+    let r = n_minus_one.trailing_zeros().unwrap_or(0);
+    let d = &n_minus_one >> r;
+
+    'outer: for _ in 0..rounds {
+        // Witness in the range [2, n-2]
+        let w = random_biguint_below(&(n - 3_u32), rng) + &two;
+        let mut x = w.modpow(&d, n);
+
+        if x == one || x == n_minus_one {
+            continue 'outer;
+        }
+        for _ in 0..r - 1 {
+            x = x.modpow(&two, n);
+            if x == n_minus_one {
+                continue 'outer;
+            }
+        }
+        return false;
+    }
+    true
+}
+
+/// Random probable prime with exactly the given number of bits. The top two bits are set so that the product of two
+/// such primes has exactly twice as many bits.
+pub fn random_prime<R: Rng>(bits: u64, rng: &mut R) -> BigUint {
+    assert!(bits >= 8, "primes must have at least 8 bits");
+    loop {
+        let mut n = random_biguint_bits(bits, rng);
+        n.set_bit(bits - 1, true);
+        n.set_bit(bits - 2, true);
+        n.set_bit(0, true);
+        if is_probable_prime(&n, 40, rng) {
+            return n;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn factorization_map() {
+        assert_eq!(
+            BTreeMap::from([(2, 3), (3, 2), (7, 1), (101, 1)]),
+            prime_factorization_map(2_u64 * 2 * 2 * 3 * 3 * 7 * 101)
+        );
+        assert_eq!(
+            BTreeMap::from([(4294967291, 1)]),
+            prime_factorization_map(4294967291_u64)
+        );
+        assert!(prime_factorization_map(1_u32).is_empty());
+        // The largest primes that fit the type, where f*f would overflow before the loop ends
+        assert_eq!(
+            BTreeMap::from([(4294967291, 1)]),
+            prime_factorization_map(4294967291_u32)
+        );
+        assert_eq!(
+            BTreeMap::from([(65521, 1), (65537, 1)]),
+            prime_factorization_map(65521_u32 * 65537)
+        );
+    }
+
+    #[test]
+    #[ignore = "long test, trial division up to 2^32"]
+    fn factorization_map_large_u64() {
+        assert_eq!(
+            BTreeMap::from([(18446744073709551557, 1)]),
+            prime_factorization_map(18446744073709551557_u64)
+        );
+    }
+
+    #[test]
+    fn probable_primes() {
+        let mut rng = StdRng::seed_from_u64(1);
+        // Mersenne prime 2^127 - 1 and the Carmichael number 561
+        let m127 = (BigUint::one() << 127) - 1_u32;
+        assert!(is_probable_prime(&m127, 20, &mut rng));
+        assert!(!is_probable_prime(&(&m127 + 2_u32), 20, &mut rng));
+        assert!(!is_probable_prime(&BigUint::from(561_u32), 20, &mut rng));
+        for n in 0..2000_u32 {
+            assert_eq!(
+                is_prime32(n),
+                is_probable_prime(&BigUint::from(n), 20, &mut rng)
+            );
+        }
+    }
+
+    #[test]
+    fn prime_size() {
+        let mut rng = StdRng::seed_from_u64(2);
+        let p = random_prime(256, &mut rng);
+        assert_eq!(256, p.bits());
+        assert!(is_probable_prime(&p, 20, &mut rng));
+    }
+}